@@ -1,4 +1,5 @@
 use crate::buffer::Buffer;
+use crate::conv::{Conv2dGeometry, Conv2dOptions, Pool2dOptions};
 use crate::error::Error;
 use crate::hardware::Hardware;
 use crate::result::Result;
@@ -65,6 +66,25 @@ impl<'hw> Array<'hw> {
         }
     }
 
+    /// Creates a new `Array` using an existing buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `shape` - `Shape` of the new array.
+    /// * `buffer` - `Buffer` holding the values.
+    ///
+    /// # Returns
+    ///
+    /// A new `Array` object.
+    ///
+    /// # Safety
+    ///
+    /// `buffer` must hold initialized `f32` values with the size of `shape`.
+    pub(crate) unsafe fn from_buffer(shape: Shape, buffer: Buffer<'hw>) -> Self {
+        debug_assert_eq!(buffer.size(), shape.memory_size::<f32>());
+        Self { shape, buffer }
+    }
+
     /// Returns the shape of the array.
     ///
     /// # Returns
@@ -242,6 +262,154 @@ impl<'hw> Array<'hw> {
         Self::fill_f32(other.hardware(), shape, value)
     }
 
+    /// Creates a new `Array` of uniform random numbers in [`low`, `high`).
+    ///
+    /// Elements are taken from the stream specified by `seed`, starting at `offset`. See
+    /// `random::Rng` for a stateful interface.
+    ///
+    /// # Arguments
+    ///
+    /// * `hardware` - `Hardware` object to host the value.
+    /// * `shape` - `Shape` of the resulting `Array`.
+    /// * `seed` - Seed of the stream.
+    /// * `offset` - Index of the first element in the stream.
+    /// * `low` - Lower bound of the values.
+    /// * `high` - Upper bound of the values.
+    ///
+    /// # Returns
+    ///
+    /// A new `Array` object.
+    pub fn random_uniform_f32(
+        hardware: &'hw RefCell<dyn Hardware>,
+        shape: Shape,
+        seed: u64,
+        offset: u64,
+        low: f32,
+        high: f32,
+    ) -> Self {
+        unsafe {
+            let mut array = Self::raw(hardware, shape);
+            hardware.borrow_mut().random_uniform_f32(
+                array.buffer.as_mut_handle(),
+                seed,
+                offset,
+                low,
+                high,
+                array.shape.num_elements(),
+            );
+            array
+        }
+    }
+
+    /// Creates a new `Array` of normal random numbers.
+    ///
+    /// # Arguments
+    ///
+    /// * `hardware` - `Hardware` object to host the value.
+    /// * `shape` - `Shape` of the resulting `Array`.
+    /// * `seed` - Seed of the stream.
+    /// * `offset` - Index of the first element in the stream.
+    /// * `mean` - Mean of the distribution.
+    /// * `stddev` - Standard deviation of the distribution.
+    ///
+    /// # Returns
+    ///
+    /// A new `Array` object.
+    pub fn random_normal_f32(
+        hardware: &'hw RefCell<dyn Hardware>,
+        shape: Shape,
+        seed: u64,
+        offset: u64,
+        mean: f32,
+        stddev: f32,
+    ) -> Self {
+        unsafe {
+            let mut array = Self::raw(hardware, shape);
+            hardware.borrow_mut().random_normal_f32(
+                array.buffer.as_mut_handle(),
+                seed,
+                offset,
+                mean,
+                stddev,
+                array.shape.num_elements(),
+            );
+            array
+        }
+    }
+
+    /// Creates a new `Array` of normal random numbers truncated into
+    /// [`mean` - `bound` * `stddev`, `mean` + `bound` * `stddev`].
+    ///
+    /// # Arguments
+    ///
+    /// * `hardware` - `Hardware` object to host the value.
+    /// * `shape` - `Shape` of the resulting `Array`.
+    /// * `seed` - Seed of the stream.
+    /// * `offset` - Index of the first element in the stream.
+    /// * `mean` - Mean of the distribution before truncation.
+    /// * `stddev` - Standard deviation of the distribution before truncation.
+    /// * `bound` - Bound of the values in the unit of `stddev`.
+    ///
+    /// # Returns
+    ///
+    /// A new `Array` object.
+    pub fn random_truncated_normal_f32(
+        hardware: &'hw RefCell<dyn Hardware>,
+        shape: Shape,
+        seed: u64,
+        offset: u64,
+        mean: f32,
+        stddev: f32,
+        bound: f32,
+    ) -> Self {
+        unsafe {
+            let mut array = Self::raw(hardware, shape);
+            hardware.borrow_mut().random_truncated_normal_f32(
+                array.buffer.as_mut_handle(),
+                seed,
+                offset,
+                mean,
+                stddev,
+                bound,
+                array.shape.num_elements(),
+            );
+            array
+        }
+    }
+
+    /// Creates a new `Array` of Bernoulli random numbers: 1 with probability `p`, 0 otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `hardware` - `Hardware` object to host the value.
+    /// * `shape` - `Shape` of the resulting `Array`.
+    /// * `seed` - Seed of the stream.
+    /// * `offset` - Index of the first element in the stream.
+    /// * `p` - Probability to obtain 1.
+    ///
+    /// # Returns
+    ///
+    /// A new `Array` object.
+    pub fn random_bernoulli_f32(
+        hardware: &'hw RefCell<dyn Hardware>,
+        shape: Shape,
+        seed: u64,
+        offset: u64,
+        p: f32,
+    ) -> Self {
+        unsafe {
+            let mut array = Self::raw(hardware, shape);
+            hardware.borrow_mut().random_bernoulli_f32(
+                array.buffer.as_mut_handle(),
+                seed,
+                offset,
+                p,
+                array.shape.num_elements(),
+            );
+            array
+        }
+    }
+
     /// Performs elementwise negation operation and returns a new `Array` of resulting
     /// values.
     ///
@@ -263,6 +431,31 @@ impl<'hw> Array<'hw> {
         }
     }
 
+    /// Performs elementwise clamp operation and returns a new `Array` of resulting values.
+    /// NaN values are kept as is.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - Lower bound of the resulting values.
+    /// * `max` - Upper bound of the resulting values.
+    ///
+    /// # Returns
+    ///
+    /// A new `Array` holding the results.
+    pub fn elementwise_clamp_f32(&self, min: f32, max: f32) -> Self {
+        unsafe {
+            let mut output = Self::raw_colocated(self, self.shape.clone());
+            output.hardware().borrow_mut().elementwise_clamp_f32(
+                self.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                min,
+                max,
+                self.shape.num_elements(),
+            );
+            output
+        }
+    }
+
     /// Performs elementwise add operation and returns a new `Array` of resulting values.
     ///
     /// This function does not perform broadcasting.
@@ -374,49 +567,1477 @@ impl<'hw> Array<'hw> {
             Ok(output)
         }
     }
-}
 
-impl<'hw> Clone for Array<'hw> {
-    fn clone(&self) -> Self {
+    /// Performs elementwise square root operation and returns a new `Array` of resulting values.
+    ///
+    /// # Returns
+    ///
+    /// A new `Array` holding the results.
+    pub fn elementwise_sqrt_f32(&self) -> Self {
         unsafe {
-            let mut cloned = Self::raw_colocated(self, self.shape().clone());
-            cloned
-                .buffer
-                .hardware()
-                .borrow_mut()
-                .copy_hardware_to_hardware(
-                    self.buffer.as_handle(),
-                    cloned.buffer.as_mut_handle(),
-                    self.shape().memory_size::<f32>(),
-                );
-            cloned
+            let mut output = Self::raw_colocated(self, self.shape.clone());
+            output.hardware().borrow_mut().elementwise_sqrt_f32(
+                self.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                self.shape.num_elements(),
+            );
+            output
         }
     }
-}
 
-/// Trait to convert something into Array.
-pub trait IntoArray {
-    /// Generates an `Array` representing the same values of `self`.
+    /// Performs elementwise hyperbolic tangent operation and returns a new `Array` of resulting values.
+    ///
+    /// # Returns
+    ///
+    /// A new `Array` holding the results.
+    pub fn elementwise_tanh_f32(&self) -> Self {
+        unsafe {
+            let mut output = Self::raw_colocated(self, self.shape.clone());
+            output.hardware().borrow_mut().elementwise_tanh_f32(
+                self.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                self.shape.num_elements(),
+            );
+            output
+        }
+    }
+
+    /// Performs elementwise logistic sigmoid operation and returns a new `Array` of resulting values.
+    ///
+    /// # Returns
+    ///
+    /// A new `Array` holding the results.
+    pub fn elementwise_sigmoid_f32(&self) -> Self {
+        unsafe {
+            let mut output = Self::raw_colocated(self, self.shape.clone());
+            output.hardware().borrow_mut().elementwise_sigmoid_f32(
+                self.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                self.shape.num_elements(),
+            );
+            output
+        }
+    }
+
+    /// Performs elementwise ReLU operation and returns a new `Array` of resulting values.
+    ///
+    /// # Returns
+    ///
+    /// A new `Array` holding the results.
+    pub fn elementwise_relu_f32(&self) -> Self {
+        unsafe {
+            let mut output = Self::raw_colocated(self, self.shape.clone());
+            output.hardware().borrow_mut().elementwise_relu_f32(
+                self.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                self.shape.num_elements(),
+            );
+            output
+        }
+    }
+
+    /// Performs elementwise step function (1 for positive values, 0 otherwise) operation and returns a new `Array` of resulting values.
+    ///
+    /// # Returns
+    ///
+    /// A new `Array` holding the results.
+    pub fn elementwise_step_f32(&self) -> Self {
+        unsafe {
+            let mut output = Self::raw_colocated(self, self.shape.clone());
+            output.hardware().borrow_mut().elementwise_step_f32(
+                self.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                self.shape.num_elements(),
+            );
+            output
+        }
+    }
+
+    /// Performs elementwise equal operation and returns a new `Array` of resulting values:
+    /// 1 if `self` == `other`, 0 otherwise.
+    ///
+    /// This function does not perform broadcasting.
     ///
     /// # Arguments
     ///
-    /// * `hardware` - A reference to the `Hardware` to own the buffer.
+    /// * `other` - `Array` of right-hand side argument.
     ///
     /// # Returns
     ///
-    /// A new `Array` object.
-    fn into_array(self, hardware: &RefCell<dyn Hardware>) -> Array;
-}
+    /// * `Ok(Array)` - A new `Array` holding the results.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments.
+    pub fn elementwise_eq_f32(&self, other: &Self) -> Result<Self> {
+        self.buffer.check_colocated(&other.buffer)?;
+        let output_shape = self.shape.elementwise(&other.shape)?;
+        let num_elements = output_shape.num_elements();
+        unsafe {
+            let mut output = Self::raw_colocated(self, output_shape);
+            output.hardware().borrow_mut().elementwise_eq_f32(
+                self.buffer.as_handle(),
+                other.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                num_elements,
+            );
+            Ok(output)
+        }
+    }
 
-impl IntoArray for f32 {
-    fn into_array(self, hardware: &RefCell<dyn Hardware>) -> Array {
+    /// Performs elementwise less-than operation and returns a new `Array` of resulting values:
+    /// 1 if `self` < `other`, 0 otherwise.
+    ///
+    /// This function does not perform broadcasting.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - `Array` of right-hand side argument.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` holding the results.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments.
+    pub fn elementwise_lt_f32(&self, other: &Self) -> Result<Self> {
+        self.buffer.check_colocated(&other.buffer)?;
+        let output_shape = self.shape.elementwise(&other.shape)?;
+        let num_elements = output_shape.num_elements();
         unsafe {
-            let mut array = Array::raw(hardware, Shape::new([]));
-            array.set_scalar_f32(self);
-            array
+            let mut output = Self::raw_colocated(self, output_shape);
+            output.hardware().borrow_mut().elementwise_lt_f32(
+                self.buffer.as_handle(),
+                other.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                num_elements,
+            );
+            Ok(output)
         }
     }
-}
+
+    /// Performs elementwise less-than-or-equal operation and returns a new `Array` of resulting values:
+    /// 1 if `self` <= `other`, 0 otherwise.
+    ///
+    /// This function does not perform broadcasting.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - `Array` of right-hand side argument.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` holding the results.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments.
+    pub fn elementwise_le_f32(&self, other: &Self) -> Result<Self> {
+        self.buffer.check_colocated(&other.buffer)?;
+        let output_shape = self.shape.elementwise(&other.shape)?;
+        let num_elements = output_shape.num_elements();
+        unsafe {
+            let mut output = Self::raw_colocated(self, output_shape);
+            output.hardware().borrow_mut().elementwise_le_f32(
+                self.buffer.as_handle(),
+                other.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                num_elements,
+            );
+            Ok(output)
+        }
+    }
+
+    /// Performs elementwise minimum operation and returns a new `Array` of resulting values:
+    /// min(`self`, `other`). The result is NaN if either argument is NaN.
+    ///
+    /// This function does not perform broadcasting.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - `Array` of right-hand side argument.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` holding the results.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments.
+    pub fn elementwise_minimum_f32(&self, other: &Self) -> Result<Self> {
+        self.buffer.check_colocated(&other.buffer)?;
+        let output_shape = self.shape.elementwise(&other.shape)?;
+        let num_elements = output_shape.num_elements();
+        unsafe {
+            let mut output = Self::raw_colocated(self, output_shape);
+            output.hardware().borrow_mut().elementwise_minimum_f32(
+                self.buffer.as_handle(),
+                other.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                num_elements,
+            );
+            Ok(output)
+        }
+    }
+
+    /// Performs elementwise maximum operation and returns a new `Array` of resulting values:
+    /// max(`self`, `other`). The result is NaN if either argument is NaN.
+    ///
+    /// This function does not perform broadcasting.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - `Array` of right-hand side argument.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` holding the results.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments.
+    pub fn elementwise_maximum_f32(&self, other: &Self) -> Result<Self> {
+        self.buffer.check_colocated(&other.buffer)?;
+        let output_shape = self.shape.elementwise(&other.shape)?;
+        let num_elements = output_shape.num_elements();
+        unsafe {
+            let mut output = Self::raw_colocated(self, output_shape);
+            output.hardware().borrow_mut().elementwise_maximum_f32(
+                self.buffer.as_handle(),
+                other.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                num_elements,
+            );
+            Ok(output)
+        }
+    }
+
+    /// Performs elementwise logical AND operation and returns a new `Array` of resulting values:
+    /// 1 if both `self` and `other` are nonzero, 0 otherwise.
+    ///
+    /// This function does not perform broadcasting.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - `Array` of right-hand side argument.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` holding the results.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments.
+    pub fn elementwise_logical_and_f32(&self, other: &Self) -> Result<Self> {
+        self.buffer.check_colocated(&other.buffer)?;
+        let output_shape = self.shape.elementwise(&other.shape)?;
+        let num_elements = output_shape.num_elements();
+        unsafe {
+            let mut output = Self::raw_colocated(self, output_shape);
+            output.hardware().borrow_mut().elementwise_logical_and_f32(
+                self.buffer.as_handle(),
+                other.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                num_elements,
+            );
+            Ok(output)
+        }
+    }
+
+    /// Performs elementwise logical OR operation and returns a new `Array` of resulting values:
+    /// 1 if either `self` or `other` is nonzero, 0 otherwise.
+    ///
+    /// This function does not perform broadcasting.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - `Array` of right-hand side argument.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` holding the results.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments.
+    pub fn elementwise_logical_or_f32(&self, other: &Self) -> Result<Self> {
+        self.buffer.check_colocated(&other.buffer)?;
+        let output_shape = self.shape.elementwise(&other.shape)?;
+        let num_elements = output_shape.num_elements();
+        unsafe {
+            let mut output = Self::raw_colocated(self, output_shape);
+            output.hardware().borrow_mut().elementwise_logical_or_f32(
+                self.buffer.as_handle(),
+                other.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                num_elements,
+            );
+            Ok(output)
+        }
+    }
+
+    /// Performs elementwise logical NOT operation and returns a new `Array` of resulting values:
+    /// 1 if the value is 0, 0 otherwise.
+    ///
+    /// # Returns
+    ///
+    /// A new `Array` holding the results.
+    pub fn elementwise_logical_not_f32(&self) -> Self {
+        unsafe {
+            let mut output = Self::raw_colocated(self, self.shape.clone());
+            output.hardware().borrow_mut().elementwise_logical_not_f32(
+                self.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                self.shape.num_elements(),
+            );
+            output
+        }
+    }
+
+    /// Performs elementwise NaN check operation and returns a new `Array` of resulting values:
+    /// 1 if the value is NaN, 0 otherwise.
+    ///
+    /// # Returns
+    ///
+    /// A new `Array` holding the results.
+    pub fn elementwise_isnan_f32(&self) -> Self {
+        unsafe {
+            let mut output = Self::raw_colocated(self, self.shape.clone());
+            output.hardware().borrow_mut().elementwise_isnan_f32(
+                self.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                self.shape.num_elements(),
+            );
+            output
+        }
+    }
+
+    /// Performs elementwise infinity check operation and returns a new `Array` of resulting values:
+    /// 1 if the value is positive or negative infinity, 0 otherwise.
+    ///
+    /// # Returns
+    ///
+    /// A new `Array` holding the results.
+    pub fn elementwise_isinf_f32(&self) -> Self {
+        unsafe {
+            let mut output = Self::raw_colocated(self, self.shape.clone());
+            output.hardware().borrow_mut().elementwise_isinf_f32(
+                self.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                self.shape.num_elements(),
+            );
+            output
+        }
+    }
+
+    /// Performs elementwise selection and returns a new `Array` of resulting values: the value of
+    /// `lhs` if the condition `self` is nonzero, the value of `rhs` otherwise.
+    ///
+    /// This function does not perform broadcasting.
+    ///
+    /// # Arguments
+    ///
+    /// * `lhs` - `Array` of the values selected by nonzero conditions.
+    /// * `rhs` - `Array` of the values selected by zero conditions.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` holding the results.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments.
+    pub fn elementwise_where_f32(&self, lhs: &Self, rhs: &Self) -> Result<Self> {
+        self.buffer.check_colocated(&lhs.buffer)?;
+        self.buffer.check_colocated(&rhs.buffer)?;
+        let output_shape = self
+            .shape
+            .elementwise(&lhs.shape)?
+            .elementwise(&rhs.shape)?;
+        let num_elements = output_shape.num_elements();
+        unsafe {
+            let mut output = Self::raw_colocated(self, output_shape);
+            output.hardware().borrow_mut().elementwise_where_f32(
+                self.buffer.as_handle(),
+                lhs.buffer.as_handle(),
+                rhs.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                num_elements,
+            );
+            Ok(output)
+        }
+    }
+
+    /// Performs matrix multiplication and returns a new `Array` of resulting values.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - `Array` of right-hand side matrix.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` holding `self @ other`.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments.
+    pub fn matmul_f32(&self, other: &Self) -> Result<Self> {
+        self.buffer.check_colocated(&other.buffer)?;
+        let output_shape = self.shape.matmul(&other.shape)?;
+        let [m, k] = self.shape.as_array2()?;
+        let [_, n] = other.shape.as_array2()?;
+        unsafe {
+            let mut output = Self::raw_colocated(self, output_shape);
+            output.hardware().borrow_mut().matmul_f32(
+                self.buffer.as_handle(),
+                other.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                m,
+                k,
+                n,
+            );
+            Ok(output)
+        }
+    }
+
+    /// Transposes a matrix and returns a new `Array` of resulting values.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` holding the transposed matrix.
+    /// * `Err(Error)` - `self` is not a matrix.
+    pub fn transpose_f32(&self) -> Result<Self> {
+        let output_shape = self.shape.transpose()?;
+        let [rows, cols] = self.shape.as_array2()?;
+        unsafe {
+            let mut output = Self::raw_colocated(self, output_shape);
+            output.hardware().borrow_mut().transpose_f32(
+                self.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                rows,
+                cols,
+            );
+            Ok(output)
+        }
+    }
+
+    /// Sums up values along an axis and returns a new `Array` of resulting values.
+    ///
+    /// # Arguments
+    ///
+    /// * `axis` - Index of the axis to be reduced. This axis is removed from the result.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` holding the results.
+    /// * `Err(Error)` - `axis` is out-of-range.
+    pub fn sum_axis_f32(&self, axis: usize) -> Result<Self> {
+        let output_shape = self.shape.remove_axis(axis)?;
+        let dims = self.shape.dimensions();
+        let outer = dims[..axis].iter().product();
+        let inner = dims[axis + 1..].iter().product();
+        unsafe {
+            let mut output = Self::raw_colocated(self, output_shape);
+            output.hardware().borrow_mut().sum_axis_f32(
+                self.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                outer,
+                dims[axis],
+                inner,
+            );
+            Ok(output)
+        }
+    }
+
+    /// Repeats values along a new axis and returns a new `Array` of resulting values.
+    ///
+    /// # Arguments
+    ///
+    /// * `axis` - Index of the new axis in the result.
+    /// * `size` - Number of repetitions.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` holding the results.
+    /// * `Err(Error)` - `axis` is out-of-range.
+    pub fn expand_axis_f32(&self, axis: usize, size: usize) -> Result<Self> {
+        let output_shape = self.shape.insert_axis(axis, size)?;
+        let dims = self.shape.dimensions();
+        let outer = dims[..axis].iter().product();
+        let inner = dims[axis..].iter().product();
+        unsafe {
+            let mut output = Self::raw_colocated(self, output_shape);
+            output.hardware().borrow_mut().expand_axis_f32(
+                self.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                outer,
+                size,
+                inner,
+            );
+            Ok(output)
+        }
+    }
+
+    /// Gathers rows, i.e., subarrays along the first axis, and returns a new `Array` of resulting
+    /// values.
+    ///
+    /// # Arguments
+    ///
+    /// * `indices` - `Array` of row indices. Each value must be a non-negative integer less than
+    ///   the first dimension of `self`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` with the shape of `indices` followed by the remaining
+    ///   dimensions of `self`.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments.
+    pub fn gather_rows_f32(&self, indices: &Self) -> Result<Self> {
+        self.buffer.check_colocated(&indices.buffer)?;
+        let output_shape = self.shape.gather_rows(&indices.shape)?;
+        let num_rows = self.shape.dimension(0)?;
+        let row_size = self.shape.dimensions()[1..].iter().product();
+        let indices = indices.get_row_indices(num_rows)?;
+        unsafe {
+            let mut output = Self::raw_colocated(self, output_shape);
+            output.hardware().borrow_mut().gather_rows_f32(
+                self.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                &indices,
+                row_size,
+            );
+            Ok(output)
+        }
+    }
+
+    /// Scatters rows with accumulation and returns a new `Array` of resulting values.
+    ///
+    /// This is the inverse operation of `gather_rows_f32()`: rows of `self` are added to the rows
+    /// of a zero-initialized result specified by `indices`.
+    ///
+    /// # Arguments
+    ///
+    /// * `indices` - `Array` of row indices. Each value must be a non-negative integer less than
+    ///   `num_rows`.
+    /// * `num_rows` - Number of rows of the result.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` with `num_rows` rows.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments.
+    pub fn scatter_add_rows_f32(&self, indices: &Self, num_rows: usize) -> Result<Self> {
+        self.buffer.check_colocated(&indices.buffer)?;
+        let output_shape = self.shape.scatter_rows(&indices.shape, num_rows)?;
+        let row_size = output_shape.dimensions()[1..].iter().product();
+        let indices = indices.get_row_indices(num_rows)?;
+        unsafe {
+            let mut output = Self::raw_colocated(self, output_shape);
+            output.hardware().borrow_mut().scatter_add_rows_f32(
+                self.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                &indices,
+                num_rows,
+                row_size,
+            );
+            Ok(output)
+        }
+    }
+
+    /// Performs 2-dimensional convolution and returns a new `Array` of resulting values.
+    ///
+    /// # Arguments
+    ///
+    /// * `weight` - Kernel of the shape `[out_channels, in_channels / groups, kernel_height,
+    ///   kernel_width]`.
+    /// * `options` - Convolution options.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` of the shape `[batch, out_channels, out_height, out_width]`.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments.
+    pub fn conv2d_f32(&self, weight: &Self, options: &Conv2dOptions) -> Result<Self> {
+        self.buffer.check_colocated(&weight.buffer)?;
+        let geometry = Conv2dGeometry::conv2d(&self.shape, &weight.shape, options)?;
+        unsafe {
+            let mut output = Self::raw_colocated(self, geometry.output_shape());
+            output.hardware().borrow_mut().conv2d_f32(
+                self.buffer.as_handle(),
+                weight.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                &geometry,
+            );
+            Ok(output)
+        }
+    }
+
+    /// Calculates the gradient of 2-dimensional convolution with respect to the input, regarding
+    /// `self` as the output gradient.
+    ///
+    /// # Arguments
+    ///
+    /// * `weight` - Kernel of the convolution.
+    /// * `in_size` - Height and width of the input.
+    /// * `options` - Convolution options.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` of the shape `[batch, in_channels, height, width]`.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments.
+    pub fn conv2d_backward_input_f32(
+        &self,
+        weight: &Self,
+        in_size: [usize; 2],
+        options: &Conv2dOptions,
+    ) -> Result<Self> {
+        self.buffer.check_colocated(&weight.buffer)?;
+        let geometry =
+            Conv2dGeometry::conv2d_backward_input(&self.shape, &weight.shape, in_size, options)?;
+        unsafe {
+            let mut output = Self::raw_colocated(self, geometry.input_shape());
+            output.hardware().borrow_mut().conv2d_backward_input_f32(
+                self.buffer.as_handle(),
+                weight.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                &geometry,
+            );
+            Ok(output)
+        }
+    }
+
+    /// Calculates the gradient of 2-dimensional convolution with respect to the kernel, regarding
+    /// `self` as the input.
+    ///
+    /// # Arguments
+    ///
+    /// * `output_grad` - Gradient of the output of the convolution.
+    /// * `kernel_size` - Height and width of the kernel.
+    /// * `options` - Convolution options.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` of the shape `[out_channels, in_channels / groups,
+    ///   kernel_height, kernel_width]`.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments.
+    pub fn conv2d_backward_weight_f32(
+        &self,
+        output_grad: &Self,
+        kernel_size: [usize; 2],
+        options: &Conv2dOptions,
+    ) -> Result<Self> {
+        self.buffer.check_colocated(&output_grad.buffer)?;
+        let geometry = Conv2dGeometry::conv2d_backward_weight(
+            &self.shape,
+            &output_grad.shape,
+            kernel_size,
+            options,
+        )?;
+        unsafe {
+            let mut output = Self::raw_colocated(self, geometry.weight_shape());
+            output.hardware().borrow_mut().conv2d_backward_weight_f32(
+                self.buffer.as_handle(),
+                output_grad.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                &geometry,
+            );
+            Ok(output)
+        }
+    }
+
+    /// Performs 2-dimensional max pooling and returns a new `Array` of resulting values.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Pooling options.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` of the shape `[batch, channels, out_height, out_width]`.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments.
+    pub fn max_pool2d_f32(&self, options: &Pool2dOptions) -> Result<Self> {
+        let geometry = Conv2dGeometry::pool2d(&self.shape, options)?;
+        unsafe {
+            let mut output = Self::raw_colocated(self, geometry.output_shape());
+            output.hardware().borrow_mut().max_pool2d_f32(
+                self.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                &geometry,
+            );
+            Ok(output)
+        }
+    }
+
+    /// Gathers values of `src` at the positions of maxima of each max pooling window of `self`.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - `Array` of the same shape as `self`.
+    /// * `options` - Pooling options.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` of the shape `[batch, channels, out_height, out_width]`.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments.
+    pub fn max_pool2d_gather_f32(&self, src: &Self, options: &Pool2dOptions) -> Result<Self> {
+        self.check_same_layout(src)?;
+        let geometry = Conv2dGeometry::pool2d(&self.shape, options)?;
+        unsafe {
+            let mut output = Self::raw_colocated(self, geometry.output_shape());
+            output.hardware().borrow_mut().max_pool2d_gather_f32(
+                self.buffer.as_handle(),
+                src.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                &geometry,
+            );
+            Ok(output)
+        }
+    }
+
+    /// Scatters values of `src` to the positions of maxima of each max pooling window of `self`
+    /// with accumulation. This is the gradient of max pooling with respect to the input, regarding
+    /// `src` as the output gradient.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - `Array` of the shape of the pooling result.
+    /// * `options` - Pooling options.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` of the same shape as `self`.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments.
+    pub fn max_pool2d_scatter_f32(&self, src: &Self, options: &Pool2dOptions) -> Result<Self> {
+        self.buffer.check_colocated(&src.buffer)?;
+        let geometry = Conv2dGeometry::pool2d(&self.shape, options)?.check_output(&src.shape)?;
+        unsafe {
+            let mut output = Self::raw_colocated(self, self.shape.clone());
+            output.hardware().borrow_mut().max_pool2d_scatter_f32(
+                self.buffer.as_handle(),
+                src.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                &geometry,
+            );
+            Ok(output)
+        }
+    }
+
+    /// Performs 2-dimensional average pooling and returns a new `Array` of resulting values.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Pooling options.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` of the shape `[batch, channels, out_height, out_width]`.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments.
+    pub fn avg_pool2d_f32(&self, options: &Pool2dOptions) -> Result<Self> {
+        let geometry = Conv2dGeometry::pool2d(&self.shape, options)?;
+        unsafe {
+            let mut output = Self::raw_colocated(self, geometry.output_shape());
+            output.hardware().borrow_mut().avg_pool2d_f32(
+                self.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                &geometry,
+            );
+            Ok(output)
+        }
+    }
+
+    /// Calculates the gradient of 2-dimensional average pooling with respect to the input,
+    /// regarding `self` as the output gradient.
+    ///
+    /// # Arguments
+    ///
+    /// * `in_size` - Height and width of the input.
+    /// * `options` - Pooling options.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` of the shape `[batch, channels, height, width]`.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments.
+    pub fn avg_pool2d_backward_f32(
+        &self,
+        in_size: [usize; 2],
+        options: &Pool2dOptions,
+    ) -> Result<Self> {
+        let geometry = Conv2dGeometry::pool2d_backward(&self.shape, in_size, options)?;
+        unsafe {
+            let mut output = Self::raw_colocated(self, geometry.input_shape());
+            output.hardware().borrow_mut().avg_pool2d_backward_f32(
+                self.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                &geometry,
+            );
+            Ok(output)
+        }
+    }
+
+    /// Performs layer normalization over the last axis and returns a new `Array` of resulting
+    /// values.
+    ///
+    /// # Arguments
+    ///
+    /// * `gamma` - Scaling factors of the shape `[size]`, where `size` is the last dimension of
+    ///   `self`.
+    /// * `beta` - Shifts of the shape `[size]`.
+    /// * `epsilon` - Small value added to the variance.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` of the same shape as `self`.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments.
+    pub fn layer_norm_f32(&self, gamma: &Self, beta: &Self, epsilon: f32) -> Result<Self> {
+        gamma.check_same_layout(beta)?;
+        self.buffer.check_colocated(&gamma.buffer)?;
+        let [rows, size] = self.shape.feature_layout(&gamma.shape)?;
+        unsafe {
+            let mut output = Self::raw_colocated(self, self.shape.clone());
+            output.hardware().borrow_mut().layer_norm_f32(
+                self.buffer.as_handle(),
+                gamma.buffer.as_handle(),
+                beta.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                rows,
+                size,
+                epsilon,
+            );
+            Ok(output)
+        }
+    }
+
+    /// Calculates the gradient of layer normalization with respect to `self`.
+    ///
+    /// # Arguments
+    ///
+    /// * `gamma` - Scaling factors of the shape `[size]`, where `size` is the last dimension of
+    ///   `self`.
+    /// * `gy` - Output gradient of the same shape as `self`.
+    /// * `epsilon` - Small value added to the variance.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` of the same shape as `self`.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments.
+    pub fn layer_norm_backward_f32(&self, gamma: &Self, gy: &Self, epsilon: f32) -> Result<Self> {
+        self.check_same_layout(gy)?;
+        self.buffer.check_colocated(&gamma.buffer)?;
+        let [rows, size] = self.shape.feature_layout(&gamma.shape)?;
+        unsafe {
+            let mut output = Self::raw_colocated(self, self.shape.clone());
+            output.hardware().borrow_mut().layer_norm_backward_f32(
+                self.buffer.as_handle(),
+                gamma.buffer.as_handle(),
+                gy.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                rows,
+                size,
+                epsilon,
+            );
+            Ok(output)
+        }
+    }
+
+    /// Performs RMS normalization over the last axis and returns a new `Array` of resulting
+    /// values.
+    ///
+    /// # Arguments
+    ///
+    /// * `gamma` - Scaling factors of the shape `[size]`, where `size` is the last dimension of
+    ///   `self`.
+    /// * `epsilon` - Small value added to the mean square.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` of the same shape as `self`.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments.
+    pub fn rms_norm_f32(&self, gamma: &Self, epsilon: f32) -> Result<Self> {
+        self.buffer.check_colocated(&gamma.buffer)?;
+        let [rows, size] = self.shape.feature_layout(&gamma.shape)?;
+        unsafe {
+            let mut output = Self::raw_colocated(self, self.shape.clone());
+            output.hardware().borrow_mut().rms_norm_f32(
+                self.buffer.as_handle(),
+                gamma.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                rows,
+                size,
+                epsilon,
+            );
+            Ok(output)
+        }
+    }
+
+    /// Calculates the gradient of RMS normalization with respect to `self`.
+    ///
+    /// # Arguments
+    ///
+    /// * `gamma` - Scaling factors of the shape `[size]`, where `size` is the last dimension of
+    ///   `self`.
+    /// * `gy` - Output gradient of the same shape as `self`.
+    /// * `epsilon` - Small value added to the mean square.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` of the same shape as `self`.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments.
+    pub fn rms_norm_backward_f32(&self, gamma: &Self, gy: &Self, epsilon: f32) -> Result<Self> {
+        self.check_same_layout(gy)?;
+        self.buffer.check_colocated(&gamma.buffer)?;
+        let [rows, size] = self.shape.feature_layout(&gamma.shape)?;
+        unsafe {
+            let mut output = Self::raw_colocated(self, self.shape.clone());
+            output.hardware().borrow_mut().rms_norm_backward_f32(
+                self.buffer.as_handle(),
+                gamma.buffer.as_handle(),
+                gy.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                rows,
+                size,
+                epsilon,
+            );
+            Ok(output)
+        }
+    }
+
+    /// Performs batch normalization with the statistics of `self` over every axis except the
+    /// second one, and returns a new `Array` of resulting values.
+    ///
+    /// # Arguments
+    ///
+    /// * `gamma` - Scaling factors of the shape `[channels]`, where `channels` is the second
+    ///   dimension of `self`.
+    /// * `beta` - Shifts of the shape `[channels]`.
+    /// * `epsilon` - Small value added to the variance.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` of the same shape as `self`.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments.
+    pub fn batch_norm_f32(&self, gamma: &Self, beta: &Self, epsilon: f32) -> Result<Self> {
+        gamma.check_same_layout(beta)?;
+        self.buffer.check_colocated(&gamma.buffer)?;
+        let [outer, channels, inner] = self.shape.channel_layout(&gamma.shape)?;
+        unsafe {
+            let mut output = Self::raw_colocated(self, self.shape.clone());
+            output.hardware().borrow_mut().batch_norm_f32(
+                self.buffer.as_handle(),
+                gamma.buffer.as_handle(),
+                beta.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                outer,
+                channels,
+                inner,
+                epsilon,
+            );
+            Ok(output)
+        }
+    }
+
+    /// Calculates the gradient of batch normalization with the statistics of `self` with respect
+    /// to `self`.
+    ///
+    /// # Arguments
+    ///
+    /// * `gamma` - Scaling factors of the shape `[channels]`, where `channels` is the second
+    ///   dimension of `self`.
+    /// * `gy` - Output gradient of the same shape as `self`.
+    /// * `epsilon` - Small value added to the variance.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` of the same shape as `self`.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments.
+    pub fn batch_norm_backward_f32(&self, gamma: &Self, gy: &Self, epsilon: f32) -> Result<Self> {
+        self.check_same_layout(gy)?;
+        self.buffer.check_colocated(&gamma.buffer)?;
+        let [outer, channels, inner] = self.shape.channel_layout(&gamma.shape)?;
+        unsafe {
+            let mut output = Self::raw_colocated(self, self.shape.clone());
+            output.hardware().borrow_mut().batch_norm_backward_f32(
+                self.buffer.as_handle(),
+                gamma.buffer.as_handle(),
+                gy.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                outer,
+                channels,
+                inner,
+                epsilon,
+            );
+            Ok(output)
+        }
+    }
+
+    /// Calculates the mean and the biased variance of each channel, i.e., over every axis except
+    /// the second one.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((Array, Array))` - New `Array`s of the means and the variances, both of the shape
+    ///   `[channels]`.
+    /// * `Err(Error)` - `self` has less than 2 dimensions.
+    pub fn batch_norm_statistics_f32(&self) -> Result<(Self, Self)> {
+        let channels = match self.shape.dimensions() {
+            [_, channels, ..] => Shape::new([*channels]),
+            _ => {
+                return Err(Error::InvalidShape(format!(
+                    "Batch statistics require at least 2 dimensions, but got {}.",
+                    self.shape
+                )))
+            }
+        };
+        let [outer, channels_size, inner] = self.shape.channel_layout(&channels)?;
+        unsafe {
+            let mut mean = Self::raw_colocated(self, channels.clone());
+            let mut variance = Self::raw_colocated(self, channels);
+            self.hardware().borrow_mut().batch_norm_statistics_f32(
+                self.buffer.as_handle(),
+                mean.buffer.as_mut_handle(),
+                variance.buffer.as_mut_handle(),
+                outer,
+                channels_size,
+                inner,
+            );
+            Ok((mean, variance))
+        }
+    }
+
+    /// Performs batch normalization with given statistics and returns a new `Array` of resulting
+    /// values.
+    ///
+    /// # Arguments
+    ///
+    /// * `mean` - Means of the shape `[channels]`, where `channels` is the second dimension of
+    ///   `self`.
+    /// * `variance` - Variances of the shape `[channels]`.
+    /// * `gamma` - Scaling factors of the shape `[channels]`.
+    /// * `beta` - Shifts of the shape `[channels]`.
+    /// * `epsilon` - Small value added to the variance.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` of the same shape as `self`.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments.
+    pub fn batch_norm_inference_f32(
+        &self,
+        mean: &Self,
+        variance: &Self,
+        gamma: &Self,
+        beta: &Self,
+        epsilon: f32,
+    ) -> Result<Self> {
+        for other in [variance, gamma, beta] {
+            mean.check_same_layout(other)?;
+        }
+        self.buffer.check_colocated(&mean.buffer)?;
+        let [outer, channels, inner] = self.shape.channel_layout(&mean.shape)?;
+        unsafe {
+            let mut output = Self::raw_colocated(self, self.shape.clone());
+            output.hardware().borrow_mut().batch_norm_inference_f32(
+                self.buffer.as_handle(),
+                mean.buffer.as_handle(),
+                variance.buffer.as_handle(),
+                gamma.buffer.as_handle(),
+                beta.buffer.as_handle(),
+                output.buffer.as_mut_handle(),
+                outer,
+                channels,
+                inner,
+                epsilon,
+            );
+            Ok(output)
+        }
+    }
+
+    /// Obtains values of `self` as row indices.
+    ///
+    /// # Arguments
+    ///
+    /// * `num_rows` - Number of available rows.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<usize>)` - Row indices.
+    /// * `Err(Error)` - Some value is not an integer in [0, `num_rows`).
+    fn get_row_indices(&self, num_rows: usize) -> Result<Vec<usize>> {
+        self.get_values_f32()
+            .into_iter()
+            .map(|x| {
+                if x >= 0. && x.fract() == 0. && (x as usize) < num_rows {
+                    Ok(x as usize)
+                } else {
+                    Err(Error::OutOfRange(format!(
+                        "Invalid row index {} for {} rows.",
+                        x, num_rows
+                    )))
+                }
+            })
+            .collect()
+    }
+
+    /// Fills all elements of `self` by a single value in place.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Value of the elements.
+    pub fn fill_assign_f32(&mut self, value: f32) {
+        unsafe {
+            self.hardware().borrow_mut().fill_f32(
+                self.buffer.as_mut_handle(),
+                value,
+                self.shape.num_elements(),
+            );
+        }
+    }
+
+    /// Performs elementwise add operation in place: self += other.
+    ///
+    /// This function does not perform broadcasting. Since `self` is borrowed mutably, `other`
+    /// never refers to the same `Array`.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - `Array` of right-hand side argument. It must have the same shape and hardware
+    ///   with `self`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - `self` is updated.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments. `self` is not
+    ///   modified.
+    pub fn add_assign_f32(&mut self, other: &Self) -> Result<()> {
+        self.check_same_layout(other)?;
+        unsafe {
+            self.hardware().borrow_mut().add_assign_f32(
+                self.buffer.as_mut_handle(),
+                other.buffer.as_handle(),
+                self.shape.num_elements(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Performs elementwise multiply operation in place: self *= other.
+    ///
+    /// This function does not perform broadcasting. Since `self` is borrowed mutably, `other`
+    /// never refers to the same `Array`.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - `Array` of right-hand side argument. It must have the same shape and hardware
+    ///   with `self`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - `self` is updated.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments. `self` is not
+    ///   modified.
+    pub fn mul_assign_f32(&mut self, other: &Self) -> Result<()> {
+        self.check_same_layout(other)?;
+        unsafe {
+            self.hardware().borrow_mut().mul_assign_f32(
+                self.buffer.as_mut_handle(),
+                other.buffer.as_handle(),
+                self.shape.num_elements(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Performs scaled add operation in place: self += alpha * other.
+    ///
+    /// This function does not perform broadcasting. Since `self` is borrowed mutably, `other`
+    /// never refers to the same `Array`.
+    ///
+    /// # Arguments
+    ///
+    /// * `alpha` - Scaling factor of `other`.
+    /// * `other` - `Array` to be added. It must have the same shape and hardware with `self`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - `self` is updated.
+    /// * `Err(Error)` - The operation can not be evaluated for given arguments. `self` is not
+    ///   modified.
+    pub fn axpy_f32(&mut self, alpha: f32, other: &Self) -> Result<()> {
+        self.check_same_layout(other)?;
+        unsafe {
+            self.hardware().borrow_mut().axpy_f32(
+                self.buffer.as_mut_handle(),
+                alpha,
+                other.buffer.as_handle(),
+                self.shape.num_elements(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Checks if `other` has the same shape and hardware with `self`.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - `Array` to be checked.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - `other` has the same shape and hardware.
+    /// * `Err(Error)` - `other` has a different shape or hardware.
+    fn check_same_layout(&self, other: &Self) -> Result<()> {
+        self.buffer.check_colocated(&other.buffer)?;
+        if self.shape != other.shape {
+            return Err(Error::InvalidShape(format!(
+                "Shapes must be the same, but got {} and {}.",
+                self.shape, other.shape
+            )));
+        }
+        Ok(())
+    }
+
+    /// Performs an SGD update of `self` in place.
+    ///
+    /// See `Hardware::sgd_update_f32` for the details of the calculation.
+    ///
+    /// # Arguments
+    ///
+    /// * `gradient` - Gradient of `self`.
+    /// * `velocity` - Momentum buffer, updated in place.
+    /// * `learning_rate` - Learning rate.
+    /// * `momentum` - Momentum factor.
+    /// * `weight_decay` - Coefficient of the L2 penalty.
+    /// * `nesterov` - Whether to use the Nesterov momentum or not.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The update is performed.
+    /// * `Err(Error)` - Some arguments have different shapes or hardwares.
+    pub fn sgd_update_f32(
+        &mut self,
+        gradient: &Self,
+        velocity: &mut Self,
+        learning_rate: f32,
+        momentum: f32,
+        weight_decay: f32,
+        nesterov: bool,
+    ) -> Result<()> {
+        self.check_same_layout(gradient)?;
+        self.check_same_layout(velocity)?;
+        unsafe {
+            self.hardware().borrow_mut().sgd_update_f32(
+                self.buffer.as_mut_handle(),
+                gradient.buffer.as_handle(),
+                velocity.buffer.as_mut_handle(),
+                learning_rate,
+                momentum,
+                weight_decay,
+                nesterov,
+                self.shape.num_elements(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Performs an Adam update of `self` in place.
+    ///
+    /// See `Hardware::adam_update_f32` for the details of the calculation.
+    ///
+    /// # Arguments
+    ///
+    /// * `gradient` - Gradient of `self`.
+    /// * `m` - First moment, updated in place.
+    /// * `v` - Second moment, updated in place.
+    /// * `learning_rate` - Learning rate.
+    /// * `beta1` - Decay rate of the first moment.
+    /// * `beta2` - Decay rate of the second moment.
+    /// * `epsilon` - Small value to avoid zero division.
+    /// * `weight_decay` - Coefficient of the weight decay.
+    /// * `decoupled` - Whether to use the decoupled weight decay (AdamW) or not.
+    /// * `step` - Number of updates including this one, used for the bias correction. This must
+    ///   be greater than 0.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The update is performed.
+    /// * `Err(Error)` - Some arguments have different shapes or hardwares.
+    #[allow(clippy::too_many_arguments)]
+    pub fn adam_update_f32(
+        &mut self,
+        gradient: &Self,
+        m: &mut Self,
+        v: &mut Self,
+        learning_rate: f32,
+        beta1: f32,
+        beta2: f32,
+        epsilon: f32,
+        weight_decay: f32,
+        decoupled: bool,
+        step: u64,
+    ) -> Result<()> {
+        self.check_same_layout(gradient)?;
+        self.check_same_layout(m)?;
+        self.check_same_layout(v)?;
+        let step = step.min(i32::MAX as u64) as i32;
+        unsafe {
+            self.hardware().borrow_mut().adam_update_f32(
+                self.buffer.as_mut_handle(),
+                gradient.buffer.as_handle(),
+                m.buffer.as_mut_handle(),
+                v.buffer.as_mut_handle(),
+                learning_rate,
+                beta1,
+                beta2,
+                epsilon,
+                weight_decay,
+                decoupled,
+                1. - beta1.powi(step),
+                1. - beta2.powi(step),
+                self.shape.num_elements(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Performs an RMSProp update of `self` in place.
+    ///
+    /// See `Hardware::rmsprop_update_f32` for the details of the calculation.
+    ///
+    /// # Arguments
+    ///
+    /// * `gradient` - Gradient of `self`.
+    /// * `square_avg` - Moving average of squared gradients, updated in place.
+    /// * `learning_rate` - Learning rate.
+    /// * `alpha` - Decay rate of the moving average.
+    /// * `epsilon` - Small value to avoid zero division.
+    /// * `weight_decay` - Coefficient of the L2 penalty.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The update is performed.
+    /// * `Err(Error)` - Some arguments have different shapes or hardwares.
+    pub fn rmsprop_update_f32(
+        &mut self,
+        gradient: &Self,
+        square_avg: &mut Self,
+        learning_rate: f32,
+        alpha: f32,
+        epsilon: f32,
+        weight_decay: f32,
+    ) -> Result<()> {
+        self.check_same_layout(gradient)?;
+        self.check_same_layout(square_avg)?;
+        unsafe {
+            self.hardware().borrow_mut().rmsprop_update_f32(
+                self.buffer.as_mut_handle(),
+                gradient.buffer.as_handle(),
+                square_avg.buffer.as_mut_handle(),
+                learning_rate,
+                alpha,
+                epsilon,
+                weight_decay,
+                self.shape.num_elements(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Performs an Adagrad update of `self` in place.
+    ///
+    /// See `Hardware::adagrad_update_f32` for the details of the calculation.
+    ///
+    /// # Arguments
+    ///
+    /// * `gradient` - Gradient of `self`.
+    /// * `square_sum` - Sum of squared gradients, updated in place.
+    /// * `learning_rate` - Learning rate.
+    /// * `epsilon` - Small value to avoid zero division.
+    /// * `weight_decay` - Coefficient of the L2 penalty.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The update is performed.
+    /// * `Err(Error)` - Some arguments have different shapes or hardwares.
+    pub fn adagrad_update_f32(
+        &mut self,
+        gradient: &Self,
+        square_sum: &mut Self,
+        learning_rate: f32,
+        epsilon: f32,
+        weight_decay: f32,
+    ) -> Result<()> {
+        self.check_same_layout(gradient)?;
+        self.check_same_layout(square_sum)?;
+        unsafe {
+            self.hardware().borrow_mut().adagrad_update_f32(
+                self.buffer.as_mut_handle(),
+                gradient.buffer.as_handle(),
+                square_sum.buffer.as_mut_handle(),
+                learning_rate,
+                epsilon,
+                weight_decay,
+                self.shape.num_elements(),
+            );
+        }
+        Ok(())
+    }
+}
+
+impl<'hw> Clone for Array<'hw> {
+    fn clone(&self) -> Self {
+        unsafe {
+            let mut cloned = Self::raw_colocated(self, self.shape().clone());
+            cloned
+                .buffer
+                .hardware()
+                .borrow_mut()
+                .copy_hardware_to_hardware(
+                    self.buffer.as_handle(),
+                    cloned.buffer.as_mut_handle(),
+                    self.shape().memory_size::<f32>(),
+                );
+            cloned
+        }
+    }
+}
+
+/// Trait to convert something into Array.
+pub trait IntoArray {
+    /// Generates an `Array` representing the same values of `self`.
+    ///
+    /// # Arguments
+    ///
+    /// * `hardware` - A reference to the `Hardware` to own the buffer.
+    ///
+    /// # Returns
+    ///
+    /// A new `Array` object.
+    fn into_array(self, hardware: &RefCell<dyn Hardware>) -> Array<'_>;
+}
+
+impl IntoArray for f32 {
+    fn into_array(self, hardware: &RefCell<dyn Hardware>) -> Array<'_> {
+        unsafe {
+            let mut array = Array::raw(hardware, Shape::new([]));
+            array.set_scalar_f32(self);
+            array
+        }
+    }
+}
+
+/// Copying an `Array` onto the specified hardware.
+impl IntoArray for &Array<'_> {
+    fn into_array(self, hardware: &RefCell<dyn Hardware>) -> Array<'_> {
+        Array::constant_f32(hardware, self.shape.clone(), &self.get_values_f32()).unwrap()
+    }
+}
+
+/// Obtaining a 1-dimensional Array from a slice.
+impl IntoArray for &[f32] {
+    fn into_array(self, hardware: &RefCell<dyn Hardware>) -> Array<'_> {
+        Array::constant_f32(hardware, Shape::new([self.len()]), self).unwrap()
+    }
+}
+
+/// Obtaining a 1-dimensional Array from a `Vec`.
+impl IntoArray for Vec<f32> {
+    fn into_array(self, hardware: &RefCell<dyn Hardware>) -> Array<'_> {
+        self.as_slice().into_array(hardware)
+    }
+}
+
+/// Obtaining an Array with arbitrary shape from a slice.
+///
+/// # Panics
+///
+/// The number of values is different from the number of elements of the shape.
+impl IntoArray for (Shape, &[f32]) {
+    fn into_array(self, hardware: &RefCell<dyn Hardware>) -> Array<'_> {
+        Array::constant_f32(hardware, self.0, self.1).unwrap()
+    }
+}
+
+/// Obtaining an Array with arbitrary shape from a `Vec`.
+///
+/// # Panics
+///
+/// The number of values is different from the number of elements of the shape.
+impl IntoArray for (Shape, Vec<f32>) {
+    fn into_array(self, hardware: &RefCell<dyn Hardware>) -> Array<'_> {
+        (self.0, self.1.as_slice()).into_array(hardware)
+    }
+}
+
+mod npy;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,456 @@
+use crate::array::Array;
+use crate::error::Error;
+use crate::hardware::Hardware;
+use crate::result::Result;
+use crate::shape::{Shape, MAX_NUM_DIMENSIONS};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::io::{self, Read};
+
+/// Magic string at the beginning of .npy files.
+const MAGIC: &[u8] = b"\x93NUMPY";
+
+/// Total length of the preamble and the header is aligned to this value.
+const HEADER_ALIGNMENT: usize = 64;
+
+/// File extension of arrays in .npz archives.
+const NPY_EXTENSION: &str = ".npy";
+
+/// Element types supported in .npy files.
+#[derive(Clone, Copy)]
+enum DataType {
+    F32,
+    F64,
+    I32,
+    I64,
+}
+
+impl DataType {
+    /// Obtains the data type from the `descr` field of the header.
+    fn from_descr(descr: &str) -> Result<Self> {
+        // `descr` is an arbitrary string in the header, which may start with a multibyte
+        // character.
+        let kind = descr.strip_prefix(['<', '=', '|', '>']);
+        let little_endian =
+            descr.starts_with('<') || (descr.starts_with('=') && cfg!(target_endian = "little"));
+        match (little_endian, kind) {
+            (true, Some("f4")) => Ok(Self::F32),
+            (true, Some("f8")) => Ok(Self::F64),
+            (true, Some("i4")) => Ok(Self::I32),
+            (true, Some("i8")) => Ok(Self::I64),
+            _ => Err(Error::NotSupported(format!(
+                "Unsupported data type: {}",
+                descr
+            ))),
+        }
+    }
+
+    /// Number of bytes of each element.
+    fn size(self) -> usize {
+        match self {
+            Self::F32 | Self::I32 => 4,
+            Self::F64 | Self::I64 => 8,
+        }
+    }
+
+    /// Converts a little-endian element into `f32`.
+    fn to_f32(self, bytes: &[u8]) -> f32 {
+        match self {
+            Self::F32 => f32::from_le_bytes(bytes.try_into().unwrap()),
+            Self::F64 => f64::from_le_bytes(bytes.try_into().unwrap()) as f32,
+            Self::I32 => i32::from_le_bytes(bytes.try_into().unwrap()) as f32,
+            Self::I64 => i64::from_le_bytes(bytes.try_into().unwrap()) as f32,
+        }
+    }
+}
+
+/// Values in the header of .npy files, which is a Python literal of `dict`.
+enum HeaderValue {
+    String(String),
+    Bool(bool),
+    Tuple(Vec<usize>),
+}
+
+/// Parser of the header of .npy files.
+struct HeaderParser<'a> {
+    text: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> HeaderParser<'a> {
+    fn error(&self) -> Error {
+        Error::InvalidData(format!(
+            "Broken .npy header at {}: {}",
+            self.pos,
+            String::from_utf8_lossy(self.text)
+        ))
+    }
+
+    /// Skips whitespaces and returns the next character without consuming it.
+    fn peek(&mut self) -> Option<u8> {
+        while self.pos < self.text.len() && self.text[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+        self.text.get(self.pos).copied()
+    }
+
+    /// Consumes the next character if it is `c`.
+    fn consume(&mut self, c: u8) -> bool {
+        let found = self.peek() == Some(c);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, c: u8) -> Result<()> {
+        if self.consume(c) {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    /// Consumes a sequence of characters satisfying the predicate.
+    fn take_while(&mut self, f: impl Fn(u8) -> bool) -> &'a [u8] {
+        let begin = self.pos;
+        while self.pos < self.text.len() && f(self.text[self.pos]) {
+            self.pos += 1;
+        }
+        &self.text[begin..self.pos]
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let quote = self.peek().ok_or_else(|| self.error())?;
+        if quote != b'\'' && quote != b'"' {
+            return Err(self.error());
+        }
+        self.pos += 1;
+        let value = self.take_while(|c| c != quote);
+        self.expect(quote)?;
+        String::from_utf8(value.to_vec()).map_err(|_| self.error())
+    }
+
+    fn value(&mut self) -> Result<HeaderValue> {
+        match self.peek().ok_or_else(|| self.error())? {
+            b'\'' | b'"' => Ok(HeaderValue::String(self.string()?)),
+            b'(' => {
+                self.pos += 1;
+                let mut values = vec![];
+                while !self.consume(b')') {
+                    self.peek();
+                    let digits = self.take_while(|c| c.is_ascii_digit());
+                    let value = std::str::from_utf8(digits)
+                        .ok()
+                        .and_then(|digits| digits.parse().ok())
+                        .ok_or_else(|| self.error())?;
+                    values.push(value);
+                    // Python 2 appends "L" to long integers.
+                    self.consume(b'L');
+                    if !self.consume(b',') && self.peek() != Some(b')') {
+                        return Err(self.error());
+                    }
+                }
+                Ok(HeaderValue::Tuple(values))
+            }
+            _ => match self.take_while(|c| c.is_ascii_alphabetic()) {
+                b"True" => Ok(HeaderValue::Bool(true)),
+                b"False" => Ok(HeaderValue::Bool(false)),
+                _ => Err(self.error()),
+            },
+        }
+    }
+}
+
+/// Parsed header of .npy files.
+struct Header {
+    data_type: DataType,
+    fortran_order: bool,
+    shape: Shape,
+}
+
+impl Header {
+    fn parse(text: &[u8]) -> Result<Self> {
+        let mut parser = HeaderParser { text, pos: 0 };
+        let (mut descr, mut fortran_order, mut dims) = (None, None, None);
+        parser.expect(b'{')?;
+        while !parser.consume(b'}') {
+            let key = parser.string()?;
+            parser.expect(b':')?;
+            match (key.as_str(), parser.value()?) {
+                ("descr", HeaderValue::String(value)) => descr = Some(value),
+                ("fortran_order", HeaderValue::Bool(value)) => fortran_order = Some(value),
+                ("shape", HeaderValue::Tuple(value)) => dims = Some(value),
+                _ => return Err(parser.error()),
+            }
+            if !parser.consume(b',') && parser.peek() != Some(b'}') {
+                return Err(parser.error());
+            }
+        }
+        let (Some(descr), Some(fortran_order), Some(dims)) = (descr, fortran_order, dims) else {
+            return Err(parser.error());
+        };
+
+        if dims.len() > MAX_NUM_DIMENSIONS
+            || dims
+                .iter()
+                .try_fold(1usize, |acc, &d| acc.checked_mul(d))
+                .is_none()
+        {
+            return Err(Error::InvalidShape(format!(
+                "Unsupported shape: {:?}",
+                dims
+            )));
+        }
+        Ok(Self {
+            data_type: DataType::from_descr(&descr)?,
+            fortran_order,
+            shape: Shape::from_slice(&dims),
+        })
+    }
+}
+
+/// Reorders values in the column-major order into the row-major order.
+fn fortran_to_c(values: &[f32], dims: &[usize]) -> Vec<f32> {
+    let mut strides = vec![1; dims.len()];
+    for axis in 1..dims.len() {
+        strides[axis] = strides[axis - 1] * dims[axis - 1];
+    }
+    let mut index = vec![0; dims.len()];
+    let mut reordered = Vec::with_capacity(values.len());
+    for _ in 0..values.len() {
+        reordered.push(
+            values[index
+                .iter()
+                .zip(&strides)
+                .map(|(i, s)| i * s)
+                .sum::<usize>()],
+        );
+        for axis in (0..dims.len()).rev() {
+            index[axis] += 1;
+            if index[axis] < dims[axis] {
+                break;
+            }
+            index[axis] = 0;
+        }
+    }
+    reordered
+}
+
+/// Reads exactly `len` bytes.
+fn read_exact<R: io::Read>(reader: &mut R, len: usize) -> Result<Vec<u8>> {
+    let mut bytes = vec![];
+    reader
+        .by_ref()
+        .take(len as u64)
+        .read_to_end(&mut bytes)
+        .map_err(|e| Error::Io(e.to_string()))?;
+    if bytes.len() != len {
+        return Err(Error::InvalidData(
+            "Unexpected end of .npy data.".to_string(),
+        ));
+    }
+    Ok(bytes)
+}
+
+impl<'hw> Array<'hw> {
+    /// Encodes the array in the .npy format.
+    fn to_npy(&self) -> Vec<u8> {
+        let dims = self.shape.dimensions();
+        let shape = match dims {
+            [dim] => format!("({},)", dim),
+            _ => format!(
+                "({})",
+                dims.iter()
+                    .map(|d| d.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+        let mut header = format!(
+            "{{'descr': '<f4', 'fortran_order': False, 'shape': {}, }}",
+            shape
+        );
+        // Pads the header by spaces and terminates it by '\n'.
+        let preamble_len = MAGIC.len() + 4;
+        let padded_len = (preamble_len + header.len() + 1).next_multiple_of(HEADER_ALIGNMENT);
+        header.extend(std::iter::repeat_n(
+            ' ',
+            padded_len - preamble_len - header.len() - 1,
+        ));
+        header.push('\n');
+
+        let mut bytes = Vec::with_capacity(padded_len + 4 * self.shape.num_elements());
+        bytes.extend(MAGIC);
+        bytes.extend([1, 0]);
+        bytes.extend((header.len() as u16).to_le_bytes());
+        bytes.extend(header.as_bytes());
+        for value in self.get_values_f32() {
+            bytes.extend(value.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Saves the array in the NumPy .npy format.
+    ///
+    /// The array is stored as little-endian `float32` in the C order (format version 1.0).
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Destination of the .npy data.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The array is saved.
+    /// * `Err(Error)` - Writing failed.
+    pub fn save_npy<W: io::Write>(&self, writer: &mut W) -> Result<()> {
+        writer
+            .write_all(&self.to_npy())
+            .map_err(|e| Error::Io(e.to_string()))
+    }
+
+    /// Loads an array from the NumPy .npy format.
+    ///
+    /// Little-endian `float32`, `float64`, `int32` and `int64` arrays in either C or Fortran
+    /// order are supported. Values are converted into `f32`, which may lose precision.
+    /// Only the bytes of a single array are consumed from `reader`.
+    ///
+    /// # Arguments
+    ///
+    /// * `hardware` - `Hardware` object to host the value.
+    /// * `reader` - Source of the .npy data.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Array)` - A new `Array` object.
+    /// * `Err(Error)` - The data is broken, or it uses an unsupported format.
+    pub fn load_npy<R: io::Read>(
+        hardware: &'hw RefCell<dyn Hardware>,
+        reader: &mut R,
+    ) -> Result<Self> {
+        let preamble = read_exact(reader, MAGIC.len() + 2)?;
+        if &preamble[..MAGIC.len()] != MAGIC {
+            return Err(Error::InvalidData("Not a .npy file.".to_string()));
+        }
+        let header_len = match preamble[MAGIC.len()] {
+            1 => {
+                let len = read_exact(reader, 2)?;
+                u16::from_le_bytes([len[0], len[1]]) as usize
+            }
+            2 | 3 => {
+                let len = read_exact(reader, 4)?;
+                u32::from_le_bytes([len[0], len[1], len[2], len[3]]) as usize
+            }
+            major => {
+                return Err(Error::NotSupported(format!(
+                    "Unsupported .npy version: {}",
+                    major
+                )))
+            }
+        };
+        let header = Header::parse(&read_exact(reader, header_len)?)?;
+
+        let size = header.data_type.size();
+        let len = header
+            .shape
+            .num_elements()
+            .checked_mul(size)
+            .ok_or_else(|| Error::InvalidShape(format!("Too large shape: {}", header.shape)))?;
+        let data = read_exact(reader, len)?;
+        let values = data
+            .chunks_exact(size)
+            .map(|chunk| header.data_type.to_f32(chunk))
+            .collect::<Vec<_>>();
+        let values = if header.fortran_order {
+            fortran_to_c(&values, header.shape.dimensions())
+        } else {
+            values
+        };
+        Self::constant_f32(hardware, header.shape, &values)
+    }
+
+    /// Saves named arrays in the NumPy .npz format, which is a zip archive of .npy files.
+    ///
+    /// Each array is stored as `{name}.npy` in the same format as `save_npy()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `arrays` - Names and arrays to be saved.
+    /// * `writer` - Destination of the .npz data.
+    /// * `compressed` - Whether to compress the files by deflate, like `numpy.savez_compressed()`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The arrays are saved.
+    /// * `Err(Error)` - Names are duplicated, the archive is too large, or writing failed.
+    pub fn save_npz<W: io::Write>(
+        arrays: &[(&str, &Self)],
+        writer: &mut W,
+        compressed: bool,
+    ) -> Result<()> {
+        let mut names = HashSet::new();
+        let mut files = vec![];
+        for (name, array) in arrays {
+            if !names.insert(name) {
+                return Err(Error::InvalidName(format!("Duplicated name: {}", name)));
+            }
+            files.push((format!("{}{}", name, NPY_EXTENSION), array.to_npy()));
+        }
+        let method = if compressed {
+            zip::METHOD_DEFLATED
+        } else {
+            zip::METHOD_STORED
+        };
+        writer
+            .write_all(&zip::write(&files, method)?)
+            .map_err(|e| Error::Io(e.to_string()))
+    }
+
+    /// Loads named arrays from the NumPy .npz format.
+    ///
+    /// Both stored and deflated archives are supported, and each file is decoded in the same
+    /// manner as `load_npy()`. The `.npy` extension is removed from the names.
+    ///
+    /// # Arguments
+    ///
+    /// * `hardware` - `Hardware` object to host the values.
+    /// * `reader` - Source of the .npz data.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<(String, Array)>)` - Names and arrays in the order of the archive.
+    /// * `Err(Error)` - The data is broken, or it uses an unsupported format.
+    pub fn load_npz<R: io::Read>(
+        hardware: &'hw RefCell<dyn Hardware>,
+        reader: &mut R,
+    ) -> Result<Vec<(String, Self)>> {
+        let mut bytes = vec![];
+        reader
+            .read_to_end(&mut bytes)
+            .map_err(|e| Error::Io(e.to_string()))?;
+        zip::read(&bytes)?
+            .into_iter()
+            .map(|(name, data)| {
+                let mut data = data.as_slice();
+                let array = Self::load_npy(hardware, &mut data)?;
+                if !data.is_empty() {
+                    return Err(Error::InvalidData(format!(
+                        "Extra data after the array: {}",
+                        name
+                    )));
+                }
+                let name = match name.strip_suffix(NPY_EXTENSION) {
+                    Some(stem) => stem.to_string(),
+                    None => name,
+                };
+                Ok((name, array))
+            })
+            .collect()
+    }
+}
+
+mod deflate;
+mod zip;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,469 @@
+use crate::error::Error;
+use crate::result::Result;
+
+/// Base lengths of the length symbols 257..=285.
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+
+/// Number of extra bits of the length symbols 257..=285.
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+
+/// Base distances of the distance symbols 0..=29.
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+
+/// Number of extra bits of the distance symbols 0..=29.
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// Order of the code length codes in dynamic blocks.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// Maximum length of Huffman codes.
+const MAX_CODE_LENGTH: usize = 15;
+
+/// Size of the sliding window.
+const WINDOW_SIZE: usize = 1 << 15;
+
+/// Minimum length of back references.
+const MIN_MATCH: usize = 3;
+
+/// Maximum length of back references.
+const MAX_MATCH: usize = 258;
+
+/// Maximum number of candidates examined for each back reference.
+const MAX_CHAIN: usize = 64;
+
+/// Number of bits of the hash of 3-byte sequences.
+const HASH_BITS: usize = 15;
+
+/// Reader of the LSB-first bit stream.
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    buffer: u32,
+    num_bits: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            pos: 0,
+            buffer: 0,
+            num_bits: 0,
+        }
+    }
+
+    /// Reads `n` (<= 16) bits.
+    fn bits(&mut self, n: usize) -> Result<u32> {
+        while self.num_bits < n {
+            let byte = *self.bytes.get(self.pos).ok_or_else(|| {
+                Error::InvalidData("Unexpected end of deflate stream.".to_string())
+            })?;
+            self.buffer |= (byte as u32) << self.num_bits;
+            self.pos += 1;
+            self.num_bits += 8;
+        }
+        let value = self.buffer & ((1 << n) - 1);
+        self.buffer >>= n;
+        self.num_bits -= n;
+        Ok(value)
+    }
+
+    /// Discards the remaining bits of the current byte.
+    fn align(&mut self) {
+        self.buffer = 0;
+        self.num_bits = 0;
+    }
+}
+
+/// Canonical Huffman code used for decoding.
+struct Huffman {
+    /// Number of codes of each length.
+    counts: [u16; MAX_CODE_LENGTH + 1],
+
+    /// Symbols sorted by their codes.
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    /// Creates a new `Huffman` from the code lengths of symbols.
+    ///
+    /// Incomplete codes are accepted, but over-subscribed codes are rejected.
+    fn new(lengths: &[u8]) -> Result<Self> {
+        let mut counts = [0u16; MAX_CODE_LENGTH + 1];
+        for &length in lengths {
+            counts[length as usize] += 1;
+        }
+        let mut left = 1i32;
+        for &count in &counts[1..] {
+            left = (left << 1) - count as i32;
+            if left < 0 {
+                return Err(Error::InvalidData(
+                    "Over-subscribed Huffman code.".to_string(),
+                ));
+            }
+        }
+        let mut offsets = [0u16; MAX_CODE_LENGTH + 2];
+        for length in 1..=MAX_CODE_LENGTH {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+        let mut symbols = vec![0; offsets[MAX_CODE_LENGTH + 1] as usize];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                symbols[offsets[length as usize] as usize] = symbol as u16;
+                offsets[length as usize] += 1;
+            }
+        }
+        counts[0] = 0;
+        Ok(Self { counts, symbols })
+    }
+
+    /// Decodes a single symbol.
+    fn decode(&self, reader: &mut BitReader) -> Result<u16> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for &count in &self.counts[1..] {
+            code |= reader.bits(1)? as i32;
+            let count = count as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(Error::InvalidData("Invalid Huffman code.".to_string()))
+    }
+}
+
+/// Returns the fixed literal/length and distance codes.
+fn fixed_codes() -> Result<(Huffman, Huffman)> {
+    let mut lengths = [0u8; 288];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+    Ok((Huffman::new(&lengths)?, Huffman::new(&[5; 30])?))
+}
+
+/// Reads the literal/length and distance codes of a dynamic block.
+fn dynamic_codes(reader: &mut BitReader) -> Result<(Huffman, Huffman)> {
+    let num_literals = reader.bits(5)? as usize + 257;
+    let num_distances = reader.bits(5)? as usize + 1;
+    let num_code_lengths = reader.bits(4)? as usize + 4;
+    if num_literals > 286 || num_distances > 30 {
+        return Err(Error::InvalidData("Too many Huffman codes.".to_string()));
+    }
+
+    let mut code_lengths = [0u8; 19];
+    for &index in &CODE_LENGTH_ORDER[..num_code_lengths] {
+        code_lengths[index] = reader.bits(3)? as u8;
+    }
+    let code_length_code = Huffman::new(&code_lengths)?;
+
+    let mut lengths = Vec::with_capacity(num_literals + num_distances);
+    while lengths.len() < num_literals + num_distances {
+        let (value, repeat) = match code_length_code.decode(reader)? {
+            symbol @ 0..=15 => (symbol as u8, 1),
+            16 => {
+                let previous = *lengths
+                    .last()
+                    .ok_or_else(|| Error::InvalidData("No length to repeat.".to_string()))?;
+                (previous, 3 + reader.bits(2)? as usize)
+            }
+            17 => (0, 3 + reader.bits(3)? as usize),
+            _ => (0, 11 + reader.bits(7)? as usize),
+        };
+        if lengths.len() + repeat > num_literals + num_distances {
+            return Err(Error::InvalidData("Too many code lengths.".to_string()));
+        }
+        lengths.extend(std::iter::repeat_n(value, repeat));
+    }
+    if lengths[256] == 0 {
+        return Err(Error::InvalidData("Missing end-of-block code.".to_string()));
+    }
+
+    Ok((
+        Huffman::new(&lengths[..num_literals])?,
+        Huffman::new(&lengths[num_literals..])?,
+    ))
+}
+
+/// Decompresses a raw deflate stream (RFC 1951).
+///
+/// # Arguments
+///
+/// * `bytes` - Compressed data.
+/// * `size_hint` - Expected size of the decompressed data, used to reserve the memory.
+///
+/// # Returns
+///
+/// * `Ok(Vec<u8>)` - Decompressed data.
+/// * `Err(Error)` - The stream is broken.
+pub(crate) fn inflate(bytes: &[u8], size_hint: usize) -> Result<Vec<u8>> {
+    let mut reader = BitReader::new(bytes);
+    // The compression ratio of deflate never exceeds 1032:1.
+    let mut output = Vec::with_capacity(size_hint.min(bytes.len().saturating_mul(1032)));
+    loop {
+        let last = reader.bits(1)? == 1;
+        match reader.bits(2)? {
+            0 => {
+                reader.align();
+                let header = reader
+                    .bytes
+                    .get(reader.pos..reader.pos + 4)
+                    .ok_or_else(|| {
+                        Error::InvalidData("Unexpected end of deflate stream.".to_string())
+                    })?;
+                let len = u16::from_le_bytes([header[0], header[1]]);
+                let nlen = u16::from_le_bytes([header[2], header[3]]);
+                if len != !nlen {
+                    return Err(Error::InvalidData("Broken stored block.".to_string()));
+                }
+                let begin = reader.pos + 4;
+                let data = reader
+                    .bytes
+                    .get(begin..begin + len as usize)
+                    .ok_or_else(|| {
+                        Error::InvalidData("Unexpected end of deflate stream.".to_string())
+                    })?;
+                output.extend_from_slice(data);
+                reader.pos = begin + len as usize;
+            }
+            block_type @ (1 | 2) => {
+                let (literals, distances) = if block_type == 1 {
+                    fixed_codes()?
+                } else {
+                    dynamic_codes(&mut reader)?
+                };
+                inflate_block(&mut reader, &literals, &distances, &mut output)?;
+            }
+            _ => return Err(Error::InvalidData("Invalid block type.".to_string())),
+        }
+        if last {
+            return Ok(output);
+        }
+    }
+}
+
+/// Decompresses a single Huffman-coded block.
+fn inflate_block(
+    reader: &mut BitReader,
+    literals: &Huffman,
+    distances: &Huffman,
+    output: &mut Vec<u8>,
+) -> Result<()> {
+    loop {
+        let symbol = literals.decode(reader)? as usize;
+        match symbol {
+            0..=255 => output.push(symbol as u8),
+            256 => return Ok(()),
+            257..=285 => {
+                let index = symbol - 257;
+                let length = LENGTH_BASE[index] as usize
+                    + reader.bits(LENGTH_EXTRA[index] as usize)? as usize;
+                let index = distances.decode(reader)? as usize;
+                if index >= DISTANCE_BASE.len() {
+                    return Err(Error::InvalidData("Invalid distance code.".to_string()));
+                }
+                let distance = DISTANCE_BASE[index] as usize
+                    + reader.bits(DISTANCE_EXTRA[index] as usize)? as usize;
+                if distance > output.len() {
+                    return Err(Error::InvalidData(
+                        "Distance exceeds the decompressed data.".to_string(),
+                    ));
+                }
+                // The source may overlap with the destination.
+                let begin = output.len() - distance;
+                for i in 0..length {
+                    output.push(output[begin + i]);
+                }
+            }
+            _ => {
+                return Err(Error::InvalidData(
+                    "Invalid literal/length code.".to_string(),
+                ))
+            }
+        }
+    }
+}
+
+/// Writer of the LSB-first bit stream.
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u64,
+    num_bits: usize,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        Self {
+            bytes: vec![],
+            buffer: 0,
+            num_bits: 0,
+        }
+    }
+
+    /// Writes `n` bits of `value` from the LSB.
+    fn bits(&mut self, value: u32, n: usize) {
+        self.buffer |= (value as u64) << self.num_bits;
+        self.num_bits += n;
+        while self.num_bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.num_bits -= 8;
+        }
+    }
+
+    /// Writes a Huffman code, which is stored from the MSB.
+    fn code(&mut self, code: u32, n: usize) {
+        self.bits(code.reverse_bits() >> (32 - n), n);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.num_bits > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+
+    /// Writes a literal/length symbol with the fixed code.
+    fn literal(&mut self, symbol: usize) {
+        let symbol = symbol as u32;
+        match symbol {
+            0..=143 => self.code(0x30 + symbol, 8),
+            144..=255 => self.code(0x190 + symbol - 144, 9),
+            256..=279 => self.code(symbol - 256, 7),
+            _ => self.code(0xc0 + symbol - 280, 8),
+        }
+    }
+
+    /// Writes a back reference with the fixed code.
+    fn reference(&mut self, length: usize, distance: usize) {
+        let index = LENGTH_BASE.partition_point(|&base| base as usize <= length) - 1;
+        self.literal(257 + index);
+        self.bits(
+            (length - LENGTH_BASE[index] as usize) as u32,
+            LENGTH_EXTRA[index] as usize,
+        );
+        let index = DISTANCE_BASE.partition_point(|&base| base as usize <= distance) - 1;
+        self.code(index as u32, 5);
+        self.bits(
+            (distance - DISTANCE_BASE[index] as usize) as u32,
+            DISTANCE_EXTRA[index] as usize,
+        );
+    }
+}
+
+/// Calculates the hash of the 3-byte sequence at `pos`.
+fn hash(bytes: &[u8], pos: usize) -> usize {
+    let value =
+        (bytes[pos] as usize) << 16 | (bytes[pos + 1] as usize) << 8 | bytes[pos + 2] as usize;
+    (value.wrapping_mul(0x9e3779b1) >> 8) & ((1 << HASH_BITS) - 1)
+}
+
+/// Hash chains of the positions already processed.
+struct HashChains {
+    /// Most recent position of each hash.
+    head: Vec<usize>,
+
+    /// Previous position with the same hash of each position.
+    prev: Vec<usize>,
+}
+
+impl HashChains {
+    fn new(len: usize) -> Self {
+        Self {
+            head: vec![usize::MAX; 1 << HASH_BITS],
+            prev: vec![usize::MAX; len],
+        }
+    }
+
+    /// Registers the sequence at `pos`.
+    fn insert(&mut self, bytes: &[u8], pos: usize) {
+        if pos + MIN_MATCH <= bytes.len() {
+            let h = hash(bytes, pos);
+            self.prev[pos] = self.head[h];
+            self.head[h] = pos;
+        }
+    }
+
+    /// Finds the longest match with the sequence at `pos`.
+    ///
+    /// # Returns
+    ///
+    /// A tuple of the length and the distance of the match.
+    fn find(&self, bytes: &[u8], pos: usize) -> (usize, usize) {
+        if pos + MIN_MATCH > bytes.len() {
+            return (0, 0);
+        }
+        let max_length = MAX_MATCH.min(bytes.len() - pos);
+        let (mut best_length, mut best_distance) = (0, 0);
+        let mut candidate = self.head[hash(bytes, pos)];
+        for _ in 0..MAX_CHAIN {
+            if candidate == usize::MAX || pos - candidate > WINDOW_SIZE {
+                break;
+            }
+            let length = bytes[candidate..]
+                .iter()
+                .zip(&bytes[pos..pos + max_length])
+                .take_while(|(a, b)| a == b)
+                .count();
+            if length > best_length {
+                (best_length, best_distance) = (length, pos - candidate);
+                if length == max_length {
+                    break;
+                }
+            }
+            candidate = self.prev[candidate];
+        }
+        (best_length, best_distance)
+    }
+}
+
+/// Compresses data into a raw deflate stream (RFC 1951).
+///
+/// This function uses greedy LZ77 matching and a single block with the fixed Huffman codes.
+///
+/// # Arguments
+///
+/// * `bytes` - Data to be compressed.
+///
+/// # Returns
+///
+/// Compressed data.
+pub(crate) fn deflate(bytes: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter::new();
+    // BFINAL = 1, BTYPE = 01 (fixed Huffman codes)
+    writer.bits(0b011, 3);
+
+    let mut chains = HashChains::new(bytes.len());
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let (length, distance) = chains.find(bytes, pos);
+        if length >= MIN_MATCH {
+            writer.reference(length, distance);
+        } else {
+            writer.literal(bytes[pos] as usize);
+        }
+        let next = pos + length.max(1);
+        for p in pos..next {
+            chains.insert(bytes, p);
+        }
+        pos = next;
+    }
+
+    writer.literal(256);
+    writer.finish()
+}
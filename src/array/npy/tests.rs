@@ -0,0 +1,385 @@
+use crate::array::npy::*;
+use crate::hardware::cpu::CpuHardware;
+
+/// .npz archive written by Python's zipfile in the same manner as `numpy.savez_compressed()`:
+/// "a" is int64 [1, -2, 3], and "c" is float64 [[1, 2, 3], [4, 5, 6]] in the Fortran order.
+const NUMPY_NPZ: &[u8] = &[
+    0x50, 0x4b, 0x03, 0x04, 0x2d, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x21, 0x00, 0x74, 0xa8,
+    0xf3, 0x30, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x05, 0x00, 0x14, 0x00, 0x61, 0x2e,
+    0x6e, 0x70, 0x79, 0x01, 0x00, 0x10, 0x00, 0x98, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x4e,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x9b, 0xec, 0x17, 0xea, 0x1b, 0x10, 0xc9, 0xc8, 0x50,
+    0xc6, 0x50, 0xad, 0x9e, 0x92, 0x5a, 0x9c, 0x5c, 0xa4, 0x6e, 0xa5, 0xa0, 0x6e, 0x93, 0x69, 0xa1,
+    0xae, 0xa3, 0xa0, 0x9e, 0x96, 0x5f, 0x54, 0x52, 0x94, 0x98, 0x17, 0x9f, 0x5f, 0x94, 0x92, 0x0a,
+    0x12, 0x77, 0x4b, 0xcc, 0x29, 0x4e, 0x05, 0x8a, 0x17, 0x67, 0x24, 0x16, 0xa4, 0x02, 0xf9, 0x1a,
+    0xc6, 0x3a, 0x9a, 0x3a, 0x0a, 0xb5, 0x0a, 0x14, 0x00, 0x2e, 0x46, 0x06, 0x08, 0xf8, 0xf7, 0x1f,
+    0x02, 0x98, 0xa1, 0x7c, 0x00, 0x50, 0x4b, 0x03, 0x04, 0x2d, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00,
+    0x00, 0x21, 0x00, 0x0b, 0x27, 0x60, 0xdb, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x05,
+    0x00, 0x14, 0x00, 0x63, 0x2e, 0x6e, 0x70, 0x79, 0x01, 0x00, 0x10, 0x00, 0xb0, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x59, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x9b, 0xec, 0x17, 0xea,
+    0x1b, 0x10, 0xc9, 0xc8, 0x50, 0xc6, 0x50, 0xad, 0x9e, 0x92, 0x5a, 0x9c, 0x5c, 0xa4, 0x6e, 0xa5,
+    0xa0, 0x6e, 0x93, 0x66, 0xa1, 0xae, 0xa3, 0xa0, 0x9e, 0x96, 0x5f, 0x54, 0x52, 0x94, 0x98, 0x17,
+    0x9f, 0x5f, 0x94, 0x92, 0x0a, 0x12, 0x0f, 0x29, 0x2a, 0x4d, 0x05, 0x0a, 0x17, 0x67, 0x24, 0x16,
+    0xa4, 0x02, 0xb9, 0x1a, 0x46, 0x3a, 0x0a, 0xc6, 0x9a, 0x3a, 0x0a, 0xb5, 0x0a, 0xe4, 0x03, 0x2e,
+    0x06, 0x30, 0xf8, 0x60, 0x0f, 0xa1, 0x05, 0x1c, 0x20, 0x34, 0x03, 0x94, 0x16, 0x81, 0xd2, 0x1c,
+    0x50, 0x5a, 0xc2, 0x01, 0x00, 0x50, 0x4b, 0x01, 0x02, 0x2d, 0x03, 0x2d, 0x00, 0x00, 0x00, 0x08,
+    0x00, 0x00, 0x00, 0x21, 0x00, 0x74, 0xa8, 0xf3, 0x30, 0x4e, 0x00, 0x00, 0x00, 0x98, 0x00, 0x00,
+    0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x01, 0x00,
+    0x00, 0x00, 0x00, 0x61, 0x2e, 0x6e, 0x70, 0x79, 0x50, 0x4b, 0x01, 0x02, 0x2d, 0x03, 0x2d, 0x00,
+    0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x21, 0x00, 0x0b, 0x27, 0x60, 0xdb, 0x59, 0x00, 0x00, 0x00,
+    0xb0, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x80, 0x01, 0x85, 0x00, 0x00, 0x00, 0x63, 0x2e, 0x6e, 0x70, 0x79, 0x50, 0x4b, 0x05, 0x06, 0x00,
+    0x00, 0x00, 0x00, 0x02, 0x00, 0x02, 0x00, 0x66, 0x00, 0x00, 0x00, 0x15, 0x01, 0x00, 0x00, 0x00,
+    0x00,
+];
+
+/// Raw deflate stream with a dynamic Huffman block, written by zlib.
+const ZLIB_DEFLATE: &[u8] = &[
+    0xe5, 0xcc, 0x81, 0x0d, 0xc0, 0x20, 0x08, 0x00, 0xb0, 0x5b, 0xd9, 0x44, 0x20, 0x0a, 0x62, 0x50,
+    0x78, 0x7f, 0x87, 0xac, 0x07, 0x14, 0xb8, 0x47, 0xfa, 0x03, 0x16, 0x56, 0xb0, 0xce, 0x6e, 0x78,
+    0x1b, 0xe8, 0x62, 0xb7, 0x97, 0x40, 0x24, 0xdf, 0x64, 0x2e, 0x84, 0xa9, 0x38, 0xe5, 0x94, 0x57,
+    0x0e, 0x93, 0xed, 0x34, 0xba, 0x3b, 0xa9, 0xc4, 0xd5, 0xd8, 0x48, 0xf0, 0x8f, 0xe4, 0x03,
+];
+
+/// Creates a .npy file with the given header.
+fn make_npy(version: u8, header: &str, data: &[u8]) -> Vec<u8> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend([version, 0]);
+    if version == 1 {
+        bytes.extend((header.len() as u16).to_le_bytes());
+    } else {
+        bytes.extend((header.len() as u32).to_le_bytes());
+    }
+    bytes.extend(header.as_bytes());
+    bytes.extend(data);
+    bytes
+}
+
+#[test]
+fn test_save_npy() {
+    let hw = RefCell::new(CpuHardware::new());
+    let array = Array::constant_f32(&hw, Shape::new([2, 3]), &[1., 2., 3., 4., 5., 6.]).unwrap();
+    let mut bytes = vec![];
+    array.save_npy(&mut bytes).unwrap();
+
+    assert_eq!(&bytes[..8], b"\x93NUMPY\x01\x00");
+    let header_len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+    assert_eq!((10 + header_len) % 64, 0);
+    let header = std::str::from_utf8(&bytes[10..10 + header_len]).unwrap();
+    assert!(header.starts_with("{'descr': '<f4', 'fortran_order': False, 'shape': (2, 3), }"));
+    assert!(header.ends_with(" \n"));
+    assert_eq!(bytes.len(), 10 + header_len + 24);
+    assert_eq!(
+        &bytes[10 + header_len..10 + header_len + 4],
+        &1f32.to_le_bytes()
+    );
+
+    let loaded = Array::load_npy(&hw, &mut bytes.as_slice()).unwrap();
+    assert_eq!(*loaded.shape(), Shape::new([2, 3]));
+    assert_eq!(loaded.get_values_f32(), array.get_values_f32());
+}
+
+#[test]
+fn test_save_npy_shapes() {
+    let hw = RefCell::new(CpuHardware::new());
+    for (shape, expected) in [
+        (Shape::new([]), "()"),
+        (Shape::new([0]), "(0,)"),
+        (Shape::new([3]), "(3,)"),
+        (
+            Shape::new([1, 2, 1, 1, 1, 1, 1, 1]),
+            "(1, 2, 1, 1, 1, 1, 1, 1)",
+        ),
+    ] {
+        let array = Array::fill_f32(&hw, shape, 1.);
+        let mut bytes = vec![];
+        array.save_npy(&mut bytes).unwrap();
+        let header = String::from_utf8_lossy(&bytes[10..]);
+        assert!(header.contains(&format!("'shape': {}, ", expected)));
+
+        let loaded = Array::load_npy(&hw, &mut bytes.as_slice()).unwrap();
+        assert_eq!(loaded.shape(), array.shape());
+        assert_eq!(loaded.get_values_f32(), array.get_values_f32());
+    }
+}
+
+#[test]
+fn test_load_npy_data_types() {
+    let hw = RefCell::new(CpuHardware::new());
+    let f64s = [1.5f64, -2., 3.]
+        .iter()
+        .flat_map(|x| x.to_le_bytes())
+        .collect::<Vec<_>>();
+    let i32s = [1i32, -2, 3]
+        .iter()
+        .flat_map(|x| x.to_le_bytes())
+        .collect::<Vec<_>>();
+    let i64s = [1i64, -2, 3]
+        .iter()
+        .flat_map(|x| x.to_le_bytes())
+        .collect::<Vec<_>>();
+    for (descr, data, expected) in [
+        ("<f8", &f64s, [1.5, -2., 3.]),
+        ("<i4", &i32s, [1., -2., 3.]),
+        ("<i8", &i64s, [1., -2., 3.]),
+    ] {
+        let header = format!(
+            "{{'descr': '{}', 'fortran_order': False, 'shape': (3,), }}\n",
+            descr
+        );
+        let bytes = make_npy(1, &header, data);
+        let loaded = Array::load_npy(&hw, &mut bytes.as_slice()).unwrap();
+        assert_eq!(loaded.get_values_f32(), expected);
+    }
+}
+
+#[test]
+fn test_load_npy_fortran_order() {
+    let hw = RefCell::new(CpuHardware::new());
+    // [[[0, 1], [2, 3], [4, 5]], [[6, 7], [8, 9], [10, 11]]] in the column-major order.
+    let values = [0f32, 6., 2., 8., 4., 10., 1., 7., 3., 9., 5., 11.];
+    let data = values
+        .iter()
+        .flat_map(|x| x.to_le_bytes())
+        .collect::<Vec<_>>();
+    let bytes = make_npy(
+        2,
+        "{\"shape\": (2L, 3L, 2L), \"fortran_order\": True, \"descr\": \"<f4\"}\n",
+        &data,
+    );
+    let loaded = Array::load_npy(&hw, &mut bytes.as_slice()).unwrap();
+    assert_eq!(*loaded.shape(), Shape::new([2, 3, 2]));
+    assert_eq!(
+        loaded.get_values_f32(),
+        (0..12).map(|x| x as f32).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_load_npy_consumes_single_array() {
+    let hw = RefCell::new(CpuHardware::new());
+    let a = Array::constant_f32(&hw, Shape::new([2]), &[1., 2.]).unwrap();
+    let b = Array::constant_f32(&hw, Shape::new([]), &[3.]).unwrap();
+    let mut bytes = vec![];
+    a.save_npy(&mut bytes).unwrap();
+    b.save_npy(&mut bytes).unwrap();
+
+    let mut reader = bytes.as_slice();
+    assert_eq!(
+        Array::load_npy(&hw, &mut reader).unwrap().get_values_f32(),
+        vec![1., 2.]
+    );
+    assert_eq!(
+        Array::load_npy(&hw, &mut reader).unwrap().get_values_f32(),
+        vec![3.]
+    );
+    assert!(reader.is_empty());
+}
+
+#[test]
+fn test_load_npy_errors() {
+    let hw = RefCell::new(CpuHardware::new());
+    let load = |bytes: &[u8]| Array::load_npy(&hw, &mut &bytes[..]).map(|_| ());
+    let data = [0u8; 8];
+
+    assert!(matches!(
+        load(b"\x93NUMPZ\x01\x00"),
+        Err(Error::InvalidData(_))
+    ));
+    assert!(matches!(load(b"\x93NUM"), Err(Error::InvalidData(_))));
+    let header = "{'descr': '<f4', 'fortran_order': False, 'shape': (2,), }\n";
+    assert!(matches!(
+        load(&make_npy(4, header, &data)),
+        Err(Error::NotSupported(_))
+    ));
+    assert_eq!(load(&make_npy(1, header, &data)), Ok(()));
+    assert!(matches!(
+        load(&make_npy(1, header, &data[..7])),
+        Err(Error::InvalidData(_))
+    ));
+
+    for (header, expected) in [
+        (
+            "{'descr': '>f4', 'fortran_order': False, 'shape': (2,), }",
+            "NotSupported",
+        ),
+        (
+            "{'descr': '<f2', 'fortran_order': False, 'shape': (2,), }",
+            "NotSupported",
+        ),
+        (
+            "{'descr': 'éf4', 'fortran_order': False, 'shape': (2,), }",
+            "NotSupported",
+        ),
+        (
+            "{'descr': 'f4', 'fortran_order': False, 'shape': (2,), }",
+            "NotSupported",
+        ),
+        (
+            "{'descr': '', 'fortran_order': False, 'shape': (2,), }",
+            "NotSupported",
+        ),
+        (
+            "{'descr': '<f4', 'fortran_order': False, 'shape': (1,1,1,1,1,1,1,1,2), }",
+            "InvalidShape",
+        ),
+        (
+            "{'descr': '<f4' 'fortran_order': False, 'shape': (2,), }",
+            "InvalidData",
+        ),
+        (
+            "{'descr': '<f4', 'fortran_order': false, 'shape': (2,), }",
+            "InvalidData",
+        ),
+        ("{'descr': '<f4', 'shape': (2,), }", "InvalidData"),
+        (
+            "{'descr': '<f4', 'fortran_order': False, 'shape': (-2,), }",
+            "InvalidData",
+        ),
+        (
+            "{'descr': '<f4', 'fortran_order': False, 'shape': (2, 3 }",
+            "InvalidData",
+        ),
+        (
+            "{'descr': '<f4, 'fortran_order': False, 'shape': (2,), }",
+            "InvalidData",
+        ),
+    ] {
+        let result = load(&make_npy(1, header, &data));
+        let observed = match result {
+            Err(Error::NotSupported(_)) => "NotSupported",
+            Err(Error::InvalidShape(_)) => "InvalidShape",
+            Err(Error::InvalidData(_)) => "InvalidData",
+            _ => "other",
+        };
+        assert_eq!(observed, expected, "{}", header);
+    }
+}
+
+#[test]
+fn test_save_npz() {
+    let hw = RefCell::new(CpuHardware::new());
+    let a = Array::constant_f32(&hw, Shape::new([2, 2]), &[1., 2., 3., 4.]).unwrap();
+    let b = Array::fill_f32(&hw, Shape::new([100]), 0.25);
+
+    for compressed in [false, true] {
+        let mut bytes = vec![];
+        Array::save_npz(&[("a", &a), ("b", &b)], &mut bytes, compressed).unwrap();
+        assert_eq!(&bytes[..4], b"PK\x03\x04");
+
+        let files = zip::read(&bytes).unwrap();
+        assert_eq!(files[0].0, "a.npy");
+        assert_eq!(files[1].0, "b.npy");
+
+        let loaded = Array::load_npz(&hw, &mut bytes.as_slice()).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].0, "a");
+        assert_eq!(*loaded[0].1.shape(), Shape::new([2, 2]));
+        assert_eq!(loaded[0].1.get_values_f32(), a.get_values_f32());
+        assert_eq!(loaded[1].0, "b");
+        assert_eq!(loaded[1].1.get_values_f32(), b.get_values_f32());
+    }
+
+    // Compression reduces the size of repeated values.
+    let mut stored = vec![];
+    Array::save_npz(&[("b", &b)], &mut stored, false).unwrap();
+    let mut deflated = vec![];
+    Array::save_npz(&[("b", &b)], &mut deflated, true).unwrap();
+    assert!(deflated.len() < stored.len() / 2);
+
+    let mut bytes = vec![];
+    assert!(matches!(
+        Array::save_npz(&[("a", &a), ("a", &b)], &mut bytes, false),
+        Err(Error::InvalidName(_))
+    ));
+    assert!(bytes.is_empty());
+}
+
+#[test]
+fn test_load_npz_numpy() {
+    let hw = RefCell::new(CpuHardware::new());
+    let mut reader = NUMPY_NPZ;
+    let loaded = Array::load_npz(&hw, &mut reader).unwrap();
+    assert_eq!(loaded.len(), 2);
+    assert_eq!(loaded[0].0, "a");
+    assert_eq!(*loaded[0].1.shape(), Shape::new([3]));
+    assert_eq!(loaded[0].1.get_values_f32(), vec![1., -2., 3.]);
+    assert_eq!(loaded[1].0, "c");
+    assert_eq!(*loaded[1].1.shape(), Shape::new([2, 3]));
+    assert_eq!(loaded[1].1.get_values_f32(), vec![1., 2., 3., 4., 5., 6.]);
+}
+
+#[test]
+fn test_load_npz_errors() {
+    let hw = RefCell::new(CpuHardware::new());
+    let a = Array::constant_f32(&hw, Shape::new([2]), &[1., 2.]).unwrap();
+    let mut bytes = vec![];
+    Array::save_npz(&[("a", &a)], &mut bytes, true).unwrap();
+    assert!(Array::load_npz(&hw, &mut bytes.as_slice()).is_ok());
+
+    // Not a zip archive.
+    assert!(matches!(
+        Array::load_npz(&hw, &mut [0u8; 30].as_slice()),
+        Err(Error::InvalidData(_))
+    ));
+
+    // Truncated archive.
+    assert!(Array::load_npz(&hw, &mut &bytes[..bytes.len() - 1]).is_err());
+
+    // Corrupted contents are detected by the checksum or the decoder.
+    let mut corrupted = bytes.clone();
+    corrupted[40] ^= 0x55;
+    assert!(Array::load_npz(&hw, &mut corrupted.as_slice()).is_err());
+
+    // Extra data after the array.
+    let mut npy = vec![];
+    a.save_npy(&mut npy).unwrap();
+    npy.push(0);
+    let archive = zip::write(&[("a.npy".to_string(), npy)], zip::METHOD_STORED).unwrap();
+    assert!(matches!(
+        Array::load_npz(&hw, &mut archive.as_slice()),
+        Err(Error::InvalidData(_))
+    ));
+}
+
+#[test]
+fn test_crc32() {
+    assert_eq!(zip::crc32(b""), 0);
+    assert_eq!(zip::crc32(b"123456789"), 0xcbf43926);
+}
+
+#[test]
+fn test_inflate_zlib() {
+    let expected = (0..300)
+        .map(|i: usize| ((i * i * 7 + i / 3) % 23 + 97) as u8)
+        .collect::<Vec<_>>();
+    assert_eq!(deflate::inflate(ZLIB_DEFLATE, 300), Ok(expected));
+    assert!(deflate::inflate(&ZLIB_DEFLATE[..ZLIB_DEFLATE.len() - 1], 300).is_err());
+
+    // Stored block
+    assert_eq!(
+        deflate::inflate(&[0x01, 0x03, 0x00, 0xfc, 0xff, b'a', b'b', b'c'], 3),
+        Ok(b"abc".to_vec())
+    );
+    assert!(deflate::inflate(&[0x01, 0x03, 0x00, 0xfc, 0xfe, b'a', b'b', b'c'], 3).is_err());
+
+    // Reserved block type
+    assert!(deflate::inflate(&[0x07], 0).is_err());
+}
+
+#[test]
+fn test_deflate_round_trip() {
+    let inputs = [
+        vec![],
+        b"a".to_vec(),
+        b"abcabcabcabcabcabcabc".to_vec(),
+        vec![0; 100000],
+        (0..70000)
+            .map(|i: usize| (i.wrapping_mul(2654435761) >> 13) as u8)
+            .collect(),
+        (0..50000).map(|i: usize| (i % 251) as u8).collect(),
+    ];
+    for input in inputs {
+        let compressed = deflate::deflate(&input);
+        assert_eq!(deflate::inflate(&compressed, input.len()), Ok(input));
+    }
+}
@@ -0,0 +1,261 @@
+use crate::array::npy::deflate;
+use crate::error::Error;
+use crate::result::Result;
+
+/// Signature of local file headers.
+const LOCAL_HEADER_SIGNATURE: u32 = 0x04034b50;
+
+/// Signature of central directory headers.
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x02014b50;
+
+/// Signature of the end of central directory record.
+const END_SIGNATURE: u32 = 0x06054b50;
+
+/// Size of the end of central directory record without the comment.
+const END_SIZE: usize = 22;
+
+/// Header ID of the Zip64 extended information extra field.
+const ZIP64_EXTRA_ID: u16 = 0x0001;
+
+/// Version needed to extract the files written by this module (2.0: deflate).
+const VERSION: u16 = 20;
+
+/// MS-DOS date of 1980-01-01, which is the earliest date representable in zip files.
+const DOS_DATE: u16 = 0x21;
+
+/// Compression method of stored files.
+pub(crate) const METHOD_STORED: u16 = 0;
+
+/// Compression method of deflated files.
+pub(crate) const METHOD_DEFLATED: u16 = 8;
+
+/// Calculates the CRC-32 checksum (ISO-HDLC) of the data.
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xedb88320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+/// Obtains a little-endian `u16` at `pos`.
+fn u16_at(bytes: &[u8], pos: usize) -> Result<u16> {
+    bytes
+        .get(pos..pos + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or_else(|| Error::InvalidData("Unexpected end of zip archive.".to_string()))
+}
+
+/// Obtains a little-endian `u32` at `pos`.
+fn u32_at(bytes: &[u8], pos: usize) -> Result<u32> {
+    bytes
+        .get(pos..pos + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| Error::InvalidData("Unexpected end of zip archive.".to_string()))
+}
+
+/// Obtains a little-endian `u64` at `pos`.
+fn u64_at(bytes: &[u8], pos: usize) -> Result<u64> {
+    Ok(u32_at(bytes, pos)? as u64 | (u32_at(bytes, pos + 4)? as u64) << 32)
+}
+
+/// Obtains `len` bytes at `pos`.
+fn slice_at(bytes: &[u8], pos: usize, len: usize) -> Result<&[u8]> {
+    pos.checked_add(len)
+        .and_then(|end| bytes.get(pos..end))
+        .ok_or_else(|| Error::InvalidData("Unexpected end of zip archive.".to_string()))
+}
+
+/// Converts a size into a 32-bit field, rejecting files requiring Zip64.
+fn to_u32(value: usize) -> Result<u32> {
+    u32::try_from(value)
+        .ok()
+        .filter(|&value| value != u32::MAX)
+        .ok_or_else(|| {
+            Error::NotSupported("Zip archives larger than 4 GiB are not supported.".to_string())
+        })
+}
+
+/// Writes files into a zip archive.
+///
+/// # Arguments
+///
+/// * `files` - Names and contents of files.
+/// * `method` - Compression method: `METHOD_STORED` or `METHOD_DEFLATED`.
+///
+/// # Returns
+///
+/// * `Ok(Vec<u8>)` - Contents of the zip archive.
+/// * `Err(Error)` - The archive requires Zip64.
+pub(crate) fn write(files: &[(String, Vec<u8>)], method: u16) -> Result<Vec<u8>> {
+    let num_files = u16::try_from(files.len())
+        .ok()
+        .filter(|&n| n != u16::MAX)
+        .ok_or_else(|| Error::NotSupported("Too many files in a zip archive.".to_string()))?;
+    let mut archive = vec![];
+    let mut central = vec![];
+    for (name, data) in files {
+        let compressed = match method {
+            METHOD_DEFLATED => deflate::deflate(data),
+            _ => data.clone(),
+        };
+        let name_len = u16::try_from(name.len())
+            .map_err(|_| Error::InvalidName(format!("Too long file name: {}", name)))?;
+        let offset = to_u32(archive.len())?;
+
+        // Fields common between the local header and the central directory.
+        let mut common = vec![];
+        common.extend(VERSION.to_le_bytes()); // version needed to extract
+        common.extend(0u16.to_le_bytes()); // general purpose bit flag
+        common.extend(method.to_le_bytes());
+        common.extend(0u16.to_le_bytes()); // last mod file time
+        common.extend(DOS_DATE.to_le_bytes()); // last mod file date
+        common.extend(crc32(data).to_le_bytes());
+        common.extend(to_u32(compressed.len())?.to_le_bytes());
+        common.extend(to_u32(data.len())?.to_le_bytes());
+        common.extend(name_len.to_le_bytes());
+        common.extend(0u16.to_le_bytes()); // extra field length
+
+        archive.extend(LOCAL_HEADER_SIGNATURE.to_le_bytes());
+        archive.extend(&common);
+        archive.extend(name.as_bytes());
+        archive.extend(compressed);
+
+        central.extend(CENTRAL_HEADER_SIGNATURE.to_le_bytes());
+        central.extend(VERSION.to_le_bytes()); // version made by
+        central.extend(&common);
+        central.extend(0u16.to_le_bytes()); // file comment length
+        central.extend(0u16.to_le_bytes()); // disk number start
+        central.extend(0u16.to_le_bytes()); // internal file attributes
+        central.extend(0u32.to_le_bytes()); // external file attributes
+        central.extend(offset.to_le_bytes());
+        central.extend(name.as_bytes());
+    }
+
+    let central_offset = to_u32(archive.len())?;
+    let central_size = to_u32(central.len())?;
+    archive.extend(central);
+    archive.extend(END_SIGNATURE.to_le_bytes());
+    archive.extend(0u16.to_le_bytes()); // number of this disk
+    archive.extend(0u16.to_le_bytes()); // disk where the central directory starts
+    archive.extend(num_files.to_le_bytes()); // number of entries on this disk
+    archive.extend(num_files.to_le_bytes()); // total number of entries
+    archive.extend(central_size.to_le_bytes());
+    archive.extend(central_offset.to_le_bytes());
+    archive.extend(0u16.to_le_bytes()); // comment length
+    to_u32(archive.len())?;
+    Ok(archive)
+}
+
+/// Reads all files in a zip archive.
+///
+/// Files are located by the central directory. Zip64 extra fields of individual files are
+/// supported, but the Zip64 end of central directory record is not.
+///
+/// # Arguments
+///
+/// * `bytes` - Contents of the zip archive.
+///
+/// # Returns
+///
+/// * `Ok(Vec<(String, Vec<u8>)>)` - Names and contents of files in the order of the central
+///   directory.
+/// * `Err(Error)` - The archive is broken, or it uses unsupported features.
+pub(crate) fn read(bytes: &[u8]) -> Result<Vec<(String, Vec<u8>)>> {
+    // The end of central directory record is followed by a comment of at most 65535 bytes.
+    let end = (END_SIZE..=bytes.len().min(END_SIZE + 0xffff))
+        .map(|offset| bytes.len() - offset)
+        .find(|&pos| u32_at(bytes, pos) == Ok(END_SIGNATURE))
+        .ok_or_else(|| Error::InvalidData("Not a zip archive.".to_string()))?;
+    let num_files = u16_at(bytes, end + 10)? as usize;
+    let central_offset = u32_at(bytes, end + 16)?;
+    if num_files == 0xffff || central_offset == u32::MAX {
+        return Err(Error::NotSupported(
+            "Zip64 archives are not supported.".to_string(),
+        ));
+    }
+
+    let mut files = Vec::with_capacity(num_files);
+    let mut pos = central_offset as usize;
+    for _ in 0..num_files {
+        if u32_at(bytes, pos)? != CENTRAL_HEADER_SIGNATURE {
+            return Err(Error::InvalidData("Broken central directory.".to_string()));
+        }
+        let flags = u16_at(bytes, pos + 8)?;
+        let method = u16_at(bytes, pos + 10)?;
+        let crc = u32_at(bytes, pos + 16)?;
+        let mut compressed_size = u32_at(bytes, pos + 20)? as u64;
+        let mut size = u32_at(bytes, pos + 24)? as u64;
+        let name_len = u16_at(bytes, pos + 28)? as usize;
+        let extra_len = u16_at(bytes, pos + 30)? as usize;
+        let comment_len = u16_at(bytes, pos + 32)? as usize;
+        let mut offset = u32_at(bytes, pos + 42)? as u64;
+        let name = std::str::from_utf8(slice_at(bytes, pos + 46, name_len)?)
+            .map_err(|_| Error::InvalidName("File name is not UTF-8.".to_string()))?
+            .to_string();
+
+        // Fields of the maximum values are stored in the Zip64 extra field in this order.
+        let mut extra = slice_at(bytes, pos + 46 + name_len, extra_len)?;
+        while extra.len() >= 4 {
+            let id = u16_at(extra, 0)?;
+            let len = u16_at(extra, 2)? as usize;
+            let data = slice_at(extra, 4, len)?;
+            if id == ZIP64_EXTRA_ID {
+                let mut field = 0;
+                for value in [&mut size, &mut compressed_size, &mut offset] {
+                    if *value == u32::MAX as u64 {
+                        *value = u64_at(data, field)?;
+                        field += 8;
+                    }
+                }
+            }
+            extra = &extra[4 + len..];
+        }
+        pos += 46 + name_len + extra_len + comment_len;
+
+        if flags & 1 != 0 {
+            return Err(Error::NotSupported(
+                "Encrypted zip archives are not supported.".to_string(),
+            ));
+        }
+        let to_usize = |value: u64| {
+            usize::try_from(value)
+                .map_err(|_| Error::InvalidData("Too large file in zip archive.".to_string()))
+        };
+        let (compressed_size, size, offset) = (
+            to_usize(compressed_size)?,
+            to_usize(size)?,
+            to_usize(offset)?,
+        );
+
+        if u32_at(bytes, offset)? != LOCAL_HEADER_SIGNATURE {
+            return Err(Error::InvalidData("Broken local file header.".to_string()));
+        }
+        let data_offset = offset
+            + 30
+            + u16_at(bytes, offset + 26)? as usize
+            + u16_at(bytes, offset + 28)? as usize;
+        let compressed = slice_at(bytes, data_offset, compressed_size)?;
+        let data = match method {
+            METHOD_STORED => compressed.to_vec(),
+            METHOD_DEFLATED => deflate::inflate(compressed, size)?,
+            _ => {
+                return Err(Error::NotSupported(format!(
+                    "Unsupported compression method: {}",
+                    method
+                )))
+            }
+        };
+        if data.len() != size || crc32(&data) != crc {
+            return Err(Error::InvalidData(format!(
+                "Broken file in zip archive: {}",
+                name
+            )));
+        }
+        files.push((name, data));
+    }
+    Ok(files)
+}
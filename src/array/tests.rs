@@ -282,6 +282,39 @@ fn test_elementwise_unary_f32_n() {
     assert_eq!(y.get_values_f32(), vec![-123., -456., -789.]);
 }
 
+#[test]
+fn test_elementwise_clamp_f32_scalar() {
+    let hw = RefCell::new(CpuHardware::new());
+    let x = 123f32.into_array(&hw);
+
+    let y = x.elementwise_clamp_f32(-1., 1.);
+    assert_eq!(y.shape, Shape::new([]));
+    assert!(ptr::eq(y.hardware(), &hw));
+    assert_eq!(y.get_values_f32(), vec![1.]);
+}
+
+#[test]
+fn test_elementwise_clamp_f32_0() {
+    let hw = RefCell::new(CpuHardware::new());
+    let x = Array::constant_f32(&hw, Shape::new([0]), &[]).unwrap();
+
+    let y = x.elementwise_clamp_f32(-1., 1.);
+    assert_eq!(y.shape, Shape::new([0]));
+    assert!(ptr::eq(y.hardware(), &hw));
+    assert_eq!(y.get_values_f32(), vec![]);
+}
+
+#[test]
+fn test_elementwise_clamp_f32_n() {
+    let hw = RefCell::new(CpuHardware::new());
+    let x = Array::constant_f32(&hw, Shape::new([3]), &[-123., 0.5, 789.]).unwrap();
+
+    let y = x.elementwise_clamp_f32(-1., 1.);
+    assert_eq!(y.shape, Shape::new([3]));
+    assert!(ptr::eq(y.hardware(), &hw));
+    assert_eq!(y.get_values_f32(), vec![-1., 0.5, 1.]);
+}
+
 #[test]
 fn test_elementwise_binary_f32_scalar_scalar() {
     let hw = RefCell::new(CpuHardware::new());
@@ -591,3 +624,704 @@ fn test_clone_n() {
     assert!(ptr::eq(y.hardware(), &hw));
     assert_eq!(y.get_values_f32(), vec![123., 456., 789.]);
 }
+
+#[test]
+fn test_into_array_array() {
+    let hw1 = RefCell::new(CpuHardware::new());
+    let hw2 = RefCell::new(CpuHardware::new());
+    let x = Array::constant_f32(&hw1, Shape::new([3]), &[1., 2., 3.]).unwrap();
+    let y = (&x).into_array(&hw2);
+    assert_eq!(y.shape, Shape::new([3]));
+    assert!(ptr::eq(y.hardware(), &hw2));
+    assert_eq!(y.get_values_f32(), vec![1., 2., 3.]);
+}
+
+#[test]
+fn test_into_array_slice() {
+    let hw = RefCell::new(CpuHardware::new());
+    let x = [1f32, 2., 3.][..].into_array(&hw);
+    assert_eq!(x.shape, Shape::new([3]));
+    assert!(ptr::eq(x.hardware(), &hw));
+    assert_eq!(x.get_values_f32(), vec![1., 2., 3.]);
+}
+
+#[test]
+fn test_into_array_vec() {
+    let hw = RefCell::new(CpuHardware::new());
+    let x = Vec::<f32>::new().into_array(&hw);
+    assert_eq!(x.shape, Shape::new([0]));
+    assert_eq!(x.get_values_f32(), vec![]);
+    let y = vec![1f32, 2., 3.].into_array(&hw);
+    assert_eq!(y.shape, Shape::new([3]));
+    assert_eq!(y.get_values_f32(), vec![1., 2., 3.]);
+}
+
+#[test]
+fn test_into_array_shape_and_slice() {
+    let hw = RefCell::new(CpuHardware::new());
+    let x = (Shape::new([]), &[42f32][..]).into_array(&hw);
+    assert_eq!(x.shape, Shape::new([]));
+    assert_eq!(x.get_scalar_f32(), Ok(42.));
+    let y = (Shape::new([2, 3]), &[1f32, 2., 3., 4., 5., 6.][..]).into_array(&hw);
+    assert_eq!(y.shape, Shape::new([2, 3]));
+    assert_eq!(y.get_values_f32(), vec![1., 2., 3., 4., 5., 6.]);
+}
+
+#[test]
+fn test_into_array_shape_and_vec() {
+    let hw = RefCell::new(CpuHardware::new());
+    let x = (Shape::new([3, 1]), vec![1f32, 2., 3.]).into_array(&hw);
+    assert_eq!(x.shape, Shape::new([3, 1]));
+    assert_eq!(x.get_values_f32(), vec![1., 2., 3.]);
+}
+
+#[test]
+#[should_panic]
+fn test_into_array_shape_and_vec_invalid() {
+    let hw = RefCell::new(CpuHardware::new());
+    let _x = (Shape::new([2, 3]), vec![1f32, 2., 3.]).into_array(&hw);
+}
+
+#[test]
+fn test_sgd_update_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let mut value = Array::constant_f32(&hw, Shape::new([2]), &[1., 2.]).unwrap();
+    let gradient = Array::constant_f32(&hw, Shape::new([2]), &[0.5, -1.]).unwrap();
+    let mut velocity = Array::fill_f32(&hw, Shape::new([2]), 0.);
+    value
+        .sgd_update_f32(&gradient, &mut velocity, 0.5, 0.5, 0., false)
+        .unwrap();
+    assert_eq!(velocity.get_values_f32(), vec![0.5, -1.]);
+    assert_eq!(value.get_values_f32(), vec![0.75, 2.5]);
+}
+
+#[test]
+fn test_sgd_update_f32_invalid() {
+    let hw1 = RefCell::new(CpuHardware::new());
+    let hw2 = RefCell::new(CpuHardware::new());
+    let mut value = Array::fill_f32(&hw1, Shape::new([2]), 1.);
+    let mut velocity = Array::fill_f32(&hw1, Shape::new([2]), 0.);
+    let mut velocity_3 = Array::fill_f32(&hw1, Shape::new([3]), 0.);
+    let gradient_1x2 = Array::fill_f32(&hw1, Shape::new([1, 2]), 1.);
+    let gradient_hw2 = Array::fill_f32(&hw2, Shape::new([2]), 1.);
+    let gradient = Array::fill_f32(&hw1, Shape::new([2]), 1.);
+    assert!(matches!(
+        value.sgd_update_f32(&gradient_1x2, &mut velocity, 1., 0., 0., false),
+        Err(Error::InvalidShape(_))
+    ));
+    assert!(matches!(
+        value.sgd_update_f32(&gradient_hw2, &mut velocity, 1., 0., 0., false),
+        Err(Error::InvalidHardware(_))
+    ));
+    assert!(matches!(
+        value.sgd_update_f32(&gradient, &mut velocity_3, 1., 0., 0., false),
+        Err(Error::InvalidShape(_))
+    ));
+    // Nothing is updated.
+    assert_eq!(value.get_values_f32(), vec![1., 1.]);
+}
+
+#[test]
+fn test_adam_update_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let mut value = Array::constant_f32(&hw, Shape::new([2]), &[1., 2.]).unwrap();
+    let gradient = Array::constant_f32(&hw, Shape::new([2]), &[2., -4.]).unwrap();
+    let mut m = Array::fill_f32(&hw, Shape::new([2]), 0.);
+    let mut v = Array::fill_f32(&hw, Shape::new([2]), 0.);
+    value
+        .adam_update_f32(&gradient, &mut m, &mut v, 0.5, 0.5, 0.75, 0., 0., false, 1)
+        .unwrap();
+    assert_eq!(m.get_values_f32(), vec![1., -2.]);
+    assert_eq!(v.get_values_f32(), vec![1., 4.]);
+    // Bias-corrected first step moves each value by the learning rate.
+    assert_eq!(value.get_values_f32(), vec![0.5, 2.5]);
+}
+
+#[test]
+fn test_rmsprop_update_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let mut value = Array::constant_f32(&hw, Shape::new([2]), &[1., 2.]).unwrap();
+    let gradient = Array::constant_f32(&hw, Shape::new([2]), &[2., -4.]).unwrap();
+    let mut square_avg = Array::constant_f32(&hw, Shape::new([2]), &[0., 16.]).unwrap();
+    value
+        .rmsprop_update_f32(&gradient, &mut square_avg, 0.5, 0.75, 0., 0.)
+        .unwrap();
+    assert_eq!(square_avg.get_values_f32(), vec![1., 16.]);
+    assert_eq!(value.get_values_f32(), vec![0., 2.5]);
+}
+
+#[test]
+fn test_adagrad_update_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let mut value = Array::constant_f32(&hw, Shape::new([2]), &[1., 2.]).unwrap();
+    let gradient = Array::constant_f32(&hw, Shape::new([2]), &[3., -4.]).unwrap();
+    let mut square_sum = Array::constant_f32(&hw, Shape::new([2]), &[16., 0.]).unwrap();
+    value
+        .adagrad_update_f32(&gradient, &mut square_sum, 0.5, 0., 0.)
+        .unwrap();
+    assert_eq!(square_sum.get_values_f32(), vec![25., 16.]);
+    assert_eq!(value.get_values_f32(), vec![0.7, 2.5]);
+}
+
+#[test]
+fn test_fill_assign_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let mut x = Array::constant_f32(&hw, Shape::new([3]), &[1., 2., 3.]).unwrap();
+    x.fill_assign_f32(42.);
+    assert_eq!(x.shape, Shape::new([3]));
+    assert_eq!(x.get_values_f32(), vec![42., 42., 42.]);
+}
+
+#[test]
+fn test_add_assign_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let mut x = Array::constant_f32(&hw, Shape::new([3]), &[1., 2., 3.]).unwrap();
+    let y = Array::constant_f32(&hw, Shape::new([3]), &[4., 5., 6.]).unwrap();
+    x.add_assign_f32(&y).unwrap();
+    assert_eq!(x.get_values_f32(), vec![5., 7., 9.]);
+    assert_eq!(y.get_values_f32(), vec![4., 5., 6.]);
+}
+
+#[test]
+fn test_mul_assign_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let mut x = Array::constant_f32(&hw, Shape::new([3]), &[1., 2., 3.]).unwrap();
+    let y = Array::constant_f32(&hw, Shape::new([3]), &[4., 5., 6.]).unwrap();
+    x.mul_assign_f32(&y).unwrap();
+    assert_eq!(x.get_values_f32(), vec![4., 10., 18.]);
+}
+
+#[test]
+fn test_axpy_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let mut x = Array::constant_f32(&hw, Shape::new([3]), &[1., 2., 3.]).unwrap();
+    let y = Array::constant_f32(&hw, Shape::new([3]), &[4., 5., 6.]).unwrap();
+    x.axpy_f32(2., &y).unwrap();
+    assert_eq!(x.get_values_f32(), vec![9., 12., 15.]);
+}
+
+#[test]
+fn test_assign_f32_invalid() {
+    let hw1 = RefCell::new(CpuHardware::new());
+    let hw2 = RefCell::new(CpuHardware::new());
+    let mut x = Array::fill_f32(&hw1, Shape::new([3]), 1.);
+    let y_shape = Array::fill_f32(&hw1, Shape::new([]), 1.);
+    let y_hw = Array::fill_f32(&hw2, Shape::new([3]), 1.);
+
+    // In-place operations never broadcast.
+    assert!(matches!(
+        x.add_assign_f32(&y_shape),
+        Err(Error::InvalidShape(_))
+    ));
+    assert!(matches!(
+        x.mul_assign_f32(&y_shape),
+        Err(Error::InvalidShape(_))
+    ));
+    assert!(matches!(
+        x.axpy_f32(1., &y_shape),
+        Err(Error::InvalidShape(_))
+    ));
+    assert!(matches!(
+        x.add_assign_f32(&y_hw),
+        Err(Error::InvalidHardware(_))
+    ));
+    assert!(matches!(
+        x.mul_assign_f32(&y_hw),
+        Err(Error::InvalidHardware(_))
+    ));
+    assert!(matches!(
+        x.axpy_f32(1., &y_hw),
+        Err(Error::InvalidHardware(_))
+    ));
+    assert_eq!(x.get_values_f32(), vec![1., 1., 1.]);
+}
+
+#[test]
+fn test_random_uniform_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let array = Array::random_uniform_f32(&hw, Shape::new([2, 3]), 1, 0, -1., 1.);
+    assert_eq!(array.shape, Shape::new([2, 3]));
+    let values = array.get_values_f32();
+    assert!(values.iter().all(|x| (-1. ..1.).contains(x)));
+
+    // Same seed and offset reproduce the same values.
+    let same = Array::random_uniform_f32(&hw, Shape::new([6]), 1, 0, -1., 1.);
+    assert_eq!(same.get_values_f32(), values);
+
+    // Offset shifts the stream.
+    let shifted = Array::random_uniform_f32(&hw, Shape::new([4]), 1, 2, -1., 1.);
+    assert_eq!(shifted.get_values_f32(), values[2..]);
+
+    // Different seed produces different values.
+    let other = Array::random_uniform_f32(&hw, Shape::new([6]), 2, 0, -1., 1.);
+    assert_ne!(other.get_values_f32(), values);
+}
+
+#[test]
+fn test_random_normal_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let array = Array::random_normal_f32(&hw, Shape::new([3]), 1, 0, 0., 1.);
+    let scaled = Array::random_normal_f32(&hw, Shape::new([3]), 1, 0, 2., 3.);
+    let expected = array
+        .get_values_f32()
+        .iter()
+        .map(|x| 2. + 3. * x)
+        .collect::<Vec<_>>();
+    assert_eq!(scaled.shape, Shape::new([3]));
+    assert_eq!(scaled.get_values_f32(), expected);
+}
+
+#[test]
+fn test_random_truncated_normal_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let array = Array::random_truncated_normal_f32(&hw, Shape::new([100]), 1, 0, 1., 2., 1.5);
+    assert_eq!(array.shape, Shape::new([100]));
+    assert!(array
+        .get_values_f32()
+        .iter()
+        .all(|x| (-2. ..=4.).contains(x)));
+}
+
+#[test]
+fn test_random_bernoulli_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let zeros = Array::random_bernoulli_f32(&hw, Shape::new([10]), 1, 0, 0.);
+    let ones = Array::random_bernoulli_f32(&hw, Shape::new([10]), 1, 0, 1.);
+    let half = Array::random_bernoulli_f32(&hw, Shape::new([10]), 1, 0, 0.5);
+    assert_eq!(zeros.get_values_f32(), vec![0.; 10]);
+    assert_eq!(ones.get_values_f32(), vec![1.; 10]);
+    assert!(half.get_values_f32().iter().all(|&x| x == 0. || x == 1.));
+}
+
+#[test]
+fn test_elementwise_unary_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let x = Array::constant_f32(&hw, Shape::new([3]), &[-1., 0., 4.]).unwrap();
+    assert_eq!(
+        Array::constant_f32(&hw, Shape::new([2]), &[0., 4.])
+            .unwrap()
+            .elementwise_sqrt_f32()
+            .get_values_f32(),
+        vec![0., 2.]
+    );
+    assert_eq!(
+        x.elementwise_tanh_f32().get_values_f32(),
+        vec![(-1f32).tanh(), 0., 4f32.tanh()]
+    );
+    assert_eq!(
+        x.elementwise_sigmoid_f32().get_values_f32(),
+        vec![1. / (1. + 1f32.exp()), 0.5, 1. / (1. + (-4f32).exp())]
+    );
+    assert_eq!(x.elementwise_relu_f32().get_values_f32(), vec![0., 0., 4.]);
+    assert_eq!(x.elementwise_step_f32().get_values_f32(), vec![0., 0., 1.]);
+    assert_eq!(x.elementwise_relu_f32().shape, Shape::new([3]));
+}
+
+#[test]
+fn test_matmul_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let a = Array::constant_f32(&hw, Shape::new([2, 3]), &[1., 2., 3., 4., 5., 6.]).unwrap();
+    let b = Array::constant_f32(&hw, Shape::new([3, 2]), &[1., 0., 0., 1., 1., 1.]).unwrap();
+    let c = a.matmul_f32(&b).unwrap();
+    assert_eq!(c.shape, Shape::new([2, 2]));
+    assert_eq!(c.get_values_f32(), vec![4., 5., 10., 11.]);
+    assert!(matches!(a.matmul_f32(&a), Err(Error::InvalidShape(_))));
+}
+
+#[test]
+fn test_matmul_f32_empty() {
+    let hw = RefCell::new(CpuHardware::new());
+    let a = Array::fill_f32(&hw, Shape::new([2, 0]), 1.);
+    let b = Array::fill_f32(&hw, Shape::new([0, 3]), 1.);
+    let c = a.matmul_f32(&b).unwrap();
+    assert_eq!(c.shape, Shape::new([2, 3]));
+    assert_eq!(c.get_values_f32(), vec![0.; 6]);
+}
+
+#[test]
+fn test_transpose_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let a = Array::constant_f32(&hw, Shape::new([2, 3]), &[1., 2., 3., 4., 5., 6.]).unwrap();
+    let t = a.transpose_f32().unwrap();
+    assert_eq!(t.shape, Shape::new([3, 2]));
+    assert_eq!(t.get_values_f32(), vec![1., 4., 2., 5., 3., 6.]);
+    assert!(Array::fill_f32(&hw, Shape::new([3]), 1.)
+        .transpose_f32()
+        .is_err());
+}
+
+#[test]
+fn test_sum_axis_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let values = (0..24).map(|x| x as f32).collect::<Vec<_>>();
+    let a = Array::constant_f32(&hw, Shape::new([2, 3, 4]), &values).unwrap();
+
+    let s0 = a.sum_axis_f32(0).unwrap();
+    assert_eq!(s0.shape, Shape::new([3, 4]));
+    assert_eq!(s0.get_values_f32()[..3], [12., 14., 16.]);
+
+    let s1 = a.sum_axis_f32(1).unwrap();
+    assert_eq!(s1.shape, Shape::new([2, 4]));
+    assert_eq!(
+        s1.get_values_f32(),
+        vec![12., 15., 18., 21., 48., 51., 54., 57.]
+    );
+
+    let s2 = a.sum_axis_f32(2).unwrap();
+    assert_eq!(s2.shape, Shape::new([2, 3]));
+    assert_eq!(s2.get_values_f32(), vec![6., 22., 38., 54., 70., 86.]);
+
+    assert!(a.sum_axis_f32(3).is_err());
+}
+
+#[test]
+fn test_expand_axis_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let a = Array::constant_f32(&hw, Shape::new([2]), &[1., 2.]).unwrap();
+
+    let e0 = a.expand_axis_f32(0, 3).unwrap();
+    assert_eq!(e0.shape, Shape::new([3, 2]));
+    assert_eq!(e0.get_values_f32(), vec![1., 2., 1., 2., 1., 2.]);
+
+    let e1 = a.expand_axis_f32(1, 3).unwrap();
+    assert_eq!(e1.shape, Shape::new([2, 3]));
+    assert_eq!(e1.get_values_f32(), vec![1., 1., 1., 2., 2., 2.]);
+
+    let scalar = Array::fill_f32(&hw, Shape::new([]), 5.);
+    assert_eq!(
+        scalar.expand_axis_f32(0, 2).unwrap().get_values_f32(),
+        vec![5., 5.]
+    );
+
+    assert!(a.expand_axis_f32(2, 3).is_err());
+}
+
+#[test]
+fn test_gather_rows_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let table = Array::constant_f32(&hw, Shape::new([3, 2]), &[1., 2., 3., 4., 5., 6.]).unwrap();
+    let indices = Array::constant_f32(&hw, Shape::new([2, 2]), &[2., 0., 0., 0.]).unwrap();
+    let rows = table.gather_rows_f32(&indices).unwrap();
+    assert_eq!(rows.shape, Shape::new([2, 2, 2]));
+    assert_eq!(rows.get_values_f32(), vec![5., 6., 1., 2., 1., 2., 1., 2.]);
+
+    for invalid in [-1., 0.5, 3.] {
+        let indices = Array::fill_f32(&hw, Shape::new([1]), invalid);
+        assert!(matches!(
+            table.gather_rows_f32(&indices),
+            Err(Error::OutOfRange(_))
+        ));
+    }
+}
+
+#[test]
+fn test_scatter_add_rows_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let src = Array::constant_f32(&hw, Shape::new([3, 2]), &[1., 2., 3., 4., 5., 6.]).unwrap();
+    let indices = Array::constant_f32(&hw, Shape::new([3]), &[2., 0., 2.]).unwrap();
+    let rows = src.scatter_add_rows_f32(&indices, 4).unwrap();
+    assert_eq!(rows.shape, Shape::new([4, 2]));
+    assert_eq!(rows.get_values_f32(), vec![3., 4., 0., 0., 6., 8., 0., 0.]);
+
+    assert!(matches!(
+        src.scatter_add_rows_f32(&indices, 2),
+        Err(Error::OutOfRange(_))
+    ));
+    let mismatched = Array::fill_f32(&hw, Shape::new([2]), 0.);
+    assert!(matches!(
+        src.scatter_add_rows_f32(&mismatched, 4),
+        Err(Error::InvalidShape(_))
+    ));
+}
+
+#[test]
+fn test_conv2d_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let values = (1..=9).map(|x| x as f32).collect::<Vec<_>>();
+    let x = Array::constant_f32(&hw, Shape::new([1, 1, 3, 3]), &values).unwrap();
+    let w = Array::constant_f32(&hw, Shape::new([1, 1, 2, 2]), &[1., 0., 0., 1.]).unwrap();
+    let y = x.conv2d_f32(&w, &Conv2dOptions::default()).unwrap();
+    assert_eq!(y.shape, Shape::new([1, 1, 2, 2]));
+    assert_eq!(y.get_values_f32(), vec![6., 8., 12., 14.]);
+
+    let options = Conv2dOptions {
+        stride: [2, 2],
+        padding: [1, 1],
+        ..Default::default()
+    };
+    let y = x.conv2d_f32(&w, &options).unwrap();
+    assert_eq!(y.shape, Shape::new([1, 1, 2, 2]));
+    assert_eq!(y.get_values_f32(), vec![1., 3., 7., 14.]);
+
+    assert!(matches!(
+        x.conv2d_f32(
+            &Array::fill_f32(&hw, Shape::new([1, 2, 1, 1]), 1.),
+            &Conv2dOptions::default()
+        ),
+        Err(Error::InvalidShape(_))
+    ));
+}
+
+#[test]
+fn test_conv2d_f32_adjoint() {
+    // <conv(x, w), gy> = <x, backward_input(gy, w)> = <w, backward_weight(x, gy)>
+    let hw = RefCell::new(CpuHardware::new());
+    let options = Conv2dOptions {
+        stride: [2, 1],
+        padding: [1, 2],
+        dilation: [1, 2],
+        groups: 2,
+    };
+    let x = Array::random_uniform_f32(&hw, Shape::new([2, 4, 5, 6]), 1, 0, -1., 1.);
+    let w = Array::random_uniform_f32(&hw, Shape::new([6, 2, 3, 2]), 2, 0, -1., 1.);
+    let y = x.conv2d_f32(&w, &options).unwrap();
+    assert_eq!(y.shape, Shape::new([2, 6, 3, 8]));
+    let gy = Array::random_uniform_f32(&hw, y.shape.clone(), 3, 0, -1., 1.);
+    let gx = gy.conv2d_backward_input_f32(&w, [5, 6], &options).unwrap();
+    let gw = x.conv2d_backward_weight_f32(&gy, [3, 2], &options).unwrap();
+    assert_eq!(gx.shape, x.shape);
+    assert_eq!(gw.shape, w.shape);
+
+    let dot = |a: &Array, b: &Array| {
+        a.get_values_f32()
+            .iter()
+            .zip(b.get_values_f32())
+            .map(|(a, b)| a * b)
+            .sum::<f32>()
+    };
+    let expected = dot(&y, &gy);
+    assert!((dot(&x, &gx) - expected).abs() < 1e-3);
+    assert!((dot(&w, &gw) - expected).abs() < 1e-3);
+
+    assert!(gy.conv2d_backward_input_f32(&w, [7, 6], &options).is_err());
+    assert!(x.conv2d_backward_weight_f32(&gy, [3, 3], &options).is_err());
+}
+
+#[test]
+fn test_max_pool2d_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let x = Array::constant_f32(
+        &hw,
+        Shape::new([1, 1, 2, 4]),
+        &[1., 5., 2., 0., 4., 3., 6., 7.],
+    )
+    .unwrap();
+    let options = Pool2dOptions::new([2, 2]);
+    let y = x.max_pool2d_f32(&options).unwrap();
+    assert_eq!(y.shape, Shape::new([1, 1, 1, 2]));
+    assert_eq!(y.get_values_f32(), vec![5., 7.]);
+
+    let values = (0..8).map(|x| x as f32).collect::<Vec<_>>();
+    let src = Array::constant_f32(&hw, Shape::new([1, 1, 2, 4]), &values).unwrap();
+    let gathered = x.max_pool2d_gather_f32(&src, &options).unwrap();
+    assert_eq!(gathered.get_values_f32(), vec![1., 7.]);
+
+    let gy = Array::constant_f32(&hw, Shape::new([1, 1, 1, 2]), &[10., 20.]).unwrap();
+    let gx = x.max_pool2d_scatter_f32(&gy, &options).unwrap();
+    assert_eq!(gx.shape, x.shape);
+    assert_eq!(gx.get_values_f32(), vec![0., 10., 0., 0., 0., 0., 0., 20.]);
+
+    assert!(x.max_pool2d_gather_f32(&gy, &options).is_err());
+    assert!(x.max_pool2d_scatter_f32(&src, &options).is_err());
+    assert!(x.max_pool2d_f32(&Pool2dOptions::new([3, 3])).is_err());
+}
+
+#[test]
+fn test_avg_pool2d_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let x = Array::constant_f32(
+        &hw,
+        Shape::new([1, 1, 2, 4]),
+        &[1., 5., 2., 0., 4., 3., 6., 7.],
+    )
+    .unwrap();
+    let options = Pool2dOptions::new([2, 2]);
+    let y = x.avg_pool2d_f32(&options).unwrap();
+    assert_eq!(y.shape, Shape::new([1, 1, 1, 2]));
+    assert_eq!(y.get_values_f32(), vec![3.25, 3.75]);
+
+    let gy = Array::constant_f32(&hw, Shape::new([1, 1, 1, 2]), &[4., 8.]).unwrap();
+    let gx = gy.avg_pool2d_backward_f32([2, 4], &options).unwrap();
+    assert_eq!(gx.shape, x.shape);
+    assert_eq!(gx.get_values_f32(), vec![1., 1., 2., 2., 1., 1., 2., 2.]);
+
+    // Trailing values that do not fill a window are ignored.
+    let gx = gy.avg_pool2d_backward_f32([3, 5], &options).unwrap();
+    assert_eq!(gx.shape, Shape::new([1, 1, 3, 5]));
+    assert!(gy.avg_pool2d_backward_f32([4, 4], &options).is_err());
+}
+
+#[test]
+fn test_layer_norm_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let x = Array::constant_f32(&hw, Shape::new([2, 2]), &[1., 3., 0., 4.]).unwrap();
+    let gamma = Array::constant_f32(&hw, Shape::new([2]), &[2., 1.]).unwrap();
+    let beta = Array::constant_f32(&hw, Shape::new([2]), &[0., 1.]).unwrap();
+    let y = x.layer_norm_f32(&gamma, &beta, 0.).unwrap();
+    assert_eq!(y.shape, x.shape);
+    assert_eq!(y.get_values_f32(), vec![-2., 2., -2., 2.]);
+
+    let gx = x.layer_norm_backward_f32(&gamma, &x, 0.).unwrap();
+    assert_eq!(gx.shape, x.shape);
+
+    let wrong = Array::fill_f32(&hw, Shape::new([3]), 1.);
+    assert!(x.layer_norm_f32(&wrong, &wrong, 0.).is_err());
+    assert!(x.layer_norm_f32(&gamma, &wrong, 0.).is_err());
+    assert!(x.layer_norm_backward_f32(&gamma, &gamma, 0.).is_err());
+    assert!(Array::fill_f32(&hw, Shape::new([]), 1.)
+        .layer_norm_f32(&gamma, &beta, 0.)
+        .is_err());
+}
+
+#[test]
+fn test_rms_norm_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let x = Array::constant_f32(&hw, Shape::new([1, 4]), &[2., -2., 2., -2.]).unwrap();
+    let gamma = Array::constant_f32(&hw, Shape::new([4]), &[2., 1., 1., 1.]).unwrap();
+    let gy = Array::constant_f32(&hw, Shape::new([1, 4]), &[1., 0., 0., 0.]).unwrap();
+    assert_eq!(
+        x.rms_norm_f32(&gamma, 0.).unwrap().get_values_f32(),
+        vec![2., -1., 1., -1.]
+    );
+    assert_eq!(
+        x.rms_norm_backward_f32(&gamma, &gy, 0.)
+            .unwrap()
+            .get_values_f32(),
+        vec![0.75, 0.25, -0.25, 0.25]
+    );
+    assert!(x.rms_norm_f32(&x, 0.).is_err());
+    assert!(x.rms_norm_backward_f32(&gamma, &gamma, 0.).is_err());
+}
+
+#[test]
+fn test_batch_norm_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let x = Array::constant_f32(&hw, Shape::new([2, 2, 1, 1]), &[-1., 3., 1., 7.]).unwrap();
+    let gamma = Array::constant_f32(&hw, Shape::new([2]), &[2., 3.]).unwrap();
+    let beta = Array::constant_f32(&hw, Shape::new([2]), &[0., 1.]).unwrap();
+    let y = x.batch_norm_f32(&gamma, &beta, 0.).unwrap();
+    assert_eq!(y.shape, x.shape);
+    assert_eq!(y.get_values_f32(), vec![-2., -2., 2., 4.]);
+
+    let (mean, variance) = x.batch_norm_statistics_f32().unwrap();
+    assert_eq!(mean.shape, Shape::new([2]));
+    assert_eq!(mean.get_values_f32(), vec![0., 5.]);
+    assert_eq!(variance.get_values_f32(), vec![1., 4.]);
+    assert_eq!(
+        x.batch_norm_inference_f32(&mean, &variance, &gamma, &beta, 0.)
+            .unwrap()
+            .get_values_f32(),
+        vec![-2., -2., 2., 4.]
+    );
+
+    let gx = x.batch_norm_backward_f32(&gamma, &x, 0.).unwrap();
+    assert_eq!(gx.shape, x.shape);
+    assert_eq!(gx.get_values_f32(), vec![0.; 4]);
+
+    let wrong = Array::fill_f32(&hw, Shape::new([1]), 1.);
+    assert!(x.batch_norm_f32(&wrong, &wrong, 0.).is_err());
+    assert!(x.batch_norm_backward_f32(&gamma, &gamma, 0.).is_err());
+    assert!(x
+        .batch_norm_inference_f32(&mean, &wrong, &gamma, &beta, 0.)
+        .is_err());
+    assert!(Array::fill_f32(&hw, Shape::new([2]), 1.)
+        .batch_norm_statistics_f32()
+        .is_err());
+}
+
+#[test]
+fn test_elementwise_compare_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let a = Array::constant_f32(&hw, Shape::new([2, 2]), &[1., 2., 3., 4.]).unwrap();
+    let b = Array::constant_f32(&hw, Shape::new([2, 2]), &[2., 2., 2., 2.]).unwrap();
+    assert_eq!(
+        a.elementwise_eq_f32(&b).unwrap().get_values_f32(),
+        vec![0., 1., 0., 0.]
+    );
+    assert_eq!(
+        a.elementwise_lt_f32(&b).unwrap().get_values_f32(),
+        vec![1., 0., 0., 0.]
+    );
+    assert_eq!(
+        a.elementwise_le_f32(&b).unwrap().get_values_f32(),
+        vec![1., 1., 0., 0.]
+    );
+    assert_eq!(
+        a.elementwise_minimum_f32(&b).unwrap().get_values_f32(),
+        vec![1., 2., 2., 2.]
+    );
+    assert_eq!(
+        a.elementwise_maximum_f32(&b).unwrap().get_values_f32(),
+        vec![2., 2., 3., 4.]
+    );
+
+    let c = Array::fill_f32(&hw, Shape::new([4]), 0.);
+    assert!(a.elementwise_eq_f32(&c).is_err());
+    assert!(a.elementwise_lt_f32(&c).is_err());
+    assert!(a.elementwise_le_f32(&c).is_err());
+    assert!(a.elementwise_minimum_f32(&c).is_err());
+    assert!(a.elementwise_maximum_f32(&c).is_err());
+
+    let hw2 = RefCell::new(CpuHardware::new());
+    let d = Array::fill_f32(&hw2, Shape::new([2, 2]), 0.);
+    assert!(a.elementwise_eq_f32(&d).is_err());
+}
+
+#[test]
+fn test_elementwise_logical_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let a = Array::constant_f32(&hw, Shape::new([4]), &[0., 0., 2., -1.]).unwrap();
+    let b = Array::constant_f32(&hw, Shape::new([4]), &[0., 1., 0., 0.5]).unwrap();
+    assert_eq!(
+        a.elementwise_logical_and_f32(&b).unwrap().get_values_f32(),
+        vec![0., 0., 0., 1.]
+    );
+    assert_eq!(
+        a.elementwise_logical_or_f32(&b).unwrap().get_values_f32(),
+        vec![0., 1., 1., 1.]
+    );
+    assert_eq!(
+        a.elementwise_logical_not_f32().get_values_f32(),
+        vec![1., 1., 0., 0.]
+    );
+
+    let c = Array::fill_f32(&hw, Shape::new([]), 0.);
+    assert!(a.elementwise_logical_and_f32(&c).is_err());
+    assert!(a.elementwise_logical_or_f32(&c).is_err());
+}
+
+#[test]
+fn test_elementwise_isnan_isinf_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let a = Array::constant_f32(
+        &hw,
+        Shape::new([4]),
+        &[1., f32::NAN, f32::INFINITY, f32::NEG_INFINITY],
+    )
+    .unwrap();
+    assert_eq!(
+        a.elementwise_isnan_f32().get_values_f32(),
+        vec![0., 1., 0., 0.]
+    );
+    assert_eq!(
+        a.elementwise_isinf_f32().get_values_f32(),
+        vec![0., 0., 1., 1.]
+    );
+    assert_eq!(a.elementwise_isnan_f32().shape, Shape::new([4]));
+}
+
+#[test]
+fn test_elementwise_where_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let cond = Array::constant_f32(&hw, Shape::new([2, 2]), &[1., 0., -2., 0.]).unwrap();
+    let a = Array::constant_f32(&hw, Shape::new([2, 2]), &[1., 2., 3., 4.]).unwrap();
+    let b = Array::constant_f32(&hw, Shape::new([2, 2]), &[5., 6., 7., 8.]).unwrap();
+    let y = cond.elementwise_where_f32(&a, &b).unwrap();
+    assert_eq!(y.shape, Shape::new([2, 2]));
+    assert_eq!(y.get_values_f32(), vec![1., 6., 3., 8.]);
+
+    let c = Array::fill_f32(&hw, Shape::new([4]), 0.);
+    assert!(cond.elementwise_where_f32(&a, &c).is_err());
+    assert!(cond.elementwise_where_f32(&c, &b).is_err());
+    assert!(c.elementwise_where_f32(&a, &b).is_err());
+}
@@ -46,6 +46,34 @@ impl<'hw> Buffer<'hw> {
         }
     }
 
+    /// Creates a new `Buffer` object owning a handle supplied by the hardware.
+    ///
+    /// # Arguments
+    ///
+    /// * `hardware` - `HardwareMutex` that supplied the handle.
+    /// * `handle` - Handle to be owned. It is released by `hardware` when the buffer is dropped.
+    /// * `size` - Size in bytes of the memory.
+    ///
+    /// # Returns
+    ///
+    /// A new `Buffer` object.
+    ///
+    /// # Safety
+    ///
+    /// `handle` must be supplied by `hardware` with `size` and must not be owned by any other
+    /// object.
+    pub(crate) unsafe fn from_handle(
+        hardware: &'hw RefCell<dyn Hardware>,
+        handle: *mut u8,
+        size: usize,
+    ) -> Self {
+        Self {
+            hardware,
+            size,
+            handle,
+        }
+    }
+
     /// Creates a new `Buffer` object on the same hardware of `other` without initialization.
     ///
     /// # Arguments
@@ -125,7 +153,7 @@ impl<'hw> Buffer<'hw> {
     /// * `true` - The both buffers are colocated on the same hardware.
     /// * `false` - Otherwise.
     pub fn is_colocated(&self, other: &Self) -> bool {
-        ptr::eq(self.hardware, other.hardware)
+        ptr::addr_eq(self.hardware, other.hardware)
     }
 
     /// Checks if the both buffers are colocated on the same hardware.
@@ -139,7 +167,7 @@ impl<'hw> Buffer<'hw> {
     /// * `Ok(())` - The both buffers are colocated on the same hardware.
     /// * `Err(Error)` - Otherwise.
     pub fn check_colocated(&self, other: &Self) -> Result<()> {
-        self.is_colocated(other).then(|| ()).ok_or_else(|| {
+        self.is_colocated(other).then_some(()).ok_or_else(|| {
             Error::InvalidHardware(format!(
                 "Buffers are not colocated on the same hardware. self: {:p}, other: {:p}",
                 self.hardware, other.hardware,
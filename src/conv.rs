@@ -0,0 +1,419 @@
+use crate::error::Error;
+use crate::result::Result;
+use crate::shape::Shape;
+
+/// Options of 2-dimensional convolutions.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Conv2dOptions {
+    /// Strides of the sliding window along the height and width.
+    pub stride: [usize; 2],
+
+    /// Number of zeros implicitly added to both sides of the height and width.
+    pub padding: [usize; 2],
+
+    /// Spacing between kernel elements along the height and width.
+    pub dilation: [usize; 2],
+
+    /// Number of groups splitting the input and output channels. Each group of output channels is
+    /// connected only to the corresponding group of input channels.
+    pub groups: usize,
+}
+
+impl Default for Conv2dOptions {
+    /// Creates options of the plain convolution: stride 1, no padding, no dilation and 1 group.
+    fn default() -> Self {
+        Self {
+            stride: [1, 1],
+            padding: [0, 0],
+            dilation: [1, 1],
+            groups: 1,
+        }
+    }
+}
+
+/// Options of 1-dimensional convolutions.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Conv1dOptions {
+    /// Stride of the sliding window.
+    pub stride: usize,
+
+    /// Number of zeros implicitly added to both sides of the input.
+    pub padding: usize,
+
+    /// Spacing between kernel elements.
+    pub dilation: usize,
+
+    /// Number of groups splitting the input and output channels.
+    pub groups: usize,
+}
+
+impl Conv1dOptions {
+    /// Obtains the equivalent 2-dimensional options which treat the input as an image of height 1.
+    ///
+    /// # Returns
+    ///
+    /// A new `Conv2dOptions` object.
+    pub fn to_2d(&self) -> Conv2dOptions {
+        Conv2dOptions {
+            stride: [1, self.stride],
+            padding: [0, self.padding],
+            dilation: [1, self.dilation],
+            groups: self.groups,
+        }
+    }
+}
+
+impl Default for Conv1dOptions {
+    /// Creates options of the plain convolution: stride 1, no padding, no dilation and 1 group.
+    fn default() -> Self {
+        Self {
+            stride: 1,
+            padding: 0,
+            dilation: 1,
+            groups: 1,
+        }
+    }
+}
+
+/// Options of 2-dimensional pooling.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Pool2dOptions {
+    /// Height and width of the window.
+    pub kernel_size: [usize; 2],
+
+    /// Strides of the window along the height and width.
+    pub stride: [usize; 2],
+
+    /// Number of values implicitly added to both sides of the height and width. Padded values are
+    /// ignored by max pooling and treated as zeros by average pooling.
+    pub padding: [usize; 2],
+}
+
+impl Pool2dOptions {
+    /// Creates options of non-overlapping windows without padding.
+    ///
+    /// # Arguments
+    ///
+    /// * `kernel_size` - Height and width of the window, which are also used as the stride.
+    ///
+    /// # Returns
+    ///
+    /// A new `Pool2dOptions` object.
+    pub fn new(kernel_size: [usize; 2]) -> Self {
+        Self {
+            kernel_size,
+            stride: kernel_size,
+            padding: [0, 0],
+        }
+    }
+}
+
+/// Complete description of a 2-dimensional sliding window operation over a batch of images with
+/// the layout `[batch, channels, height, width]`.
+///
+/// This structure is passed to the convolution and pooling kernels of `Hardware`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Conv2dGeometry {
+    /// Number of images.
+    pub batch_size: usize,
+
+    /// Number of channels of the input images.
+    pub in_channels: usize,
+
+    /// Height and width of the input images.
+    pub in_size: [usize; 2],
+
+    /// Number of channels of the output images.
+    pub out_channels: usize,
+
+    /// Height and width of the output images.
+    pub out_size: [usize; 2],
+
+    /// Height and width of the kernel.
+    pub kernel_size: [usize; 2],
+
+    /// Strides of the window.
+    pub stride: [usize; 2],
+
+    /// Number of implicit values added to both sides of the input.
+    pub padding: [usize; 2],
+
+    /// Spacing between kernel elements.
+    pub dilation: [usize; 2],
+
+    /// Number of channel groups.
+    pub groups: usize,
+}
+
+impl Conv2dGeometry {
+    /// Obtains the geometry of a convolution.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Shape of the input: `[batch, in_channels, height, width]`.
+    /// * `weight` - Shape of the kernel: `[out_channels, in_channels / groups, kernel_height,
+    ///   kernel_width]`.
+    /// * `options` - Convolution options.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Conv2dGeometry)` - The geometry of the convolution.
+    /// * `Err(Error)` - Shapes or options are not consistent.
+    pub fn conv2d(input: &Shape, weight: &Shape, options: &Conv2dOptions) -> Result<Self> {
+        let [batch_size, in_channels, in_height, in_width] = input.as_array4()?;
+        let [out_channels, group_channels, kernel_height, kernel_width] = weight.as_array4()?;
+        let groups = options.groups;
+        if groups == 0 || out_channels % groups != 0 || group_channels * groups != in_channels {
+            return Err(Error::InvalidShape(format!(
+                "Convolution with {} groups can not be applied to input {} and weight {}.",
+                groups, input, weight
+            )));
+        }
+        let in_size = [in_height, in_width];
+        let kernel_size = [kernel_height, kernel_width];
+        Ok(Self {
+            batch_size,
+            in_channels,
+            in_size,
+            out_channels,
+            out_size: Self::output_size(
+                in_size,
+                kernel_size,
+                options.stride,
+                options.padding,
+                options.dilation,
+            )?,
+            kernel_size,
+            stride: options.stride,
+            padding: options.padding,
+            dilation: options.dilation,
+            groups,
+        })
+    }
+
+    /// Obtains the geometry of a pooling, which processes each channel independently.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Shape of the input: `[batch, channels, height, width]`.
+    /// * `options` - Pooling options.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Conv2dGeometry)` - The geometry of the pooling.
+    /// * `Err(Error)` - The shape or options are not consistent.
+    pub fn pool2d(input: &Shape, options: &Pool2dOptions) -> Result<Self> {
+        let [batch_size, channels, in_height, in_width] = input.as_array4()?;
+        if (0..2).any(|i| options.padding[i] >= options.kernel_size[i]) {
+            return Err(Error::InvalidData(format!(
+                "Padding {:?} must be smaller than the kernel size {:?}.",
+                options.padding, options.kernel_size
+            )));
+        }
+        let in_size = [in_height, in_width];
+        Ok(Self {
+            batch_size,
+            in_channels: channels,
+            in_size,
+            out_channels: channels,
+            out_size: Self::output_size(
+                in_size,
+                options.kernel_size,
+                options.stride,
+                options.padding,
+                [1, 1],
+            )?,
+            kernel_size: options.kernel_size,
+            stride: options.stride,
+            padding: options.padding,
+            dilation: [1, 1],
+            groups: channels,
+        })
+    }
+
+    /// Obtains the geometry of a convolution from the output and kernel, which is used to
+    /// calculate the gradient with respect to the input.
+    ///
+    /// # Arguments
+    ///
+    /// * `output` - Shape of the output: `[batch, out_channels, out_height, out_width]`.
+    /// * `weight` - Shape of the kernel: `[out_channels, in_channels / groups, kernel_height,
+    ///   kernel_width]`.
+    /// * `in_size` - Height and width of the input, which can not be determined from the output
+    ///   if the stride is greater than 1.
+    /// * `options` - Convolution options.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Conv2dGeometry)` - The geometry of the convolution.
+    /// * `Err(Error)` - Shapes or options are not consistent.
+    pub fn conv2d_backward_input(
+        output: &Shape,
+        weight: &Shape,
+        in_size: [usize; 2],
+        options: &Conv2dOptions,
+    ) -> Result<Self> {
+        let [batch_size, ..] = output.as_array4()?;
+        let [_, group_channels, ..] = weight.as_array4()?;
+        let input = Shape::new([
+            batch_size,
+            group_channels * options.groups,
+            in_size[0],
+            in_size[1],
+        ]);
+        Self::conv2d(&input, weight, options)?.check_output(output)
+    }
+
+    /// Obtains the geometry of a convolution from the input and output, which is used to
+    /// calculate the gradient with respect to the kernel.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Shape of the input: `[batch, in_channels, height, width]`.
+    /// * `output` - Shape of the output: `[batch, out_channels, out_height, out_width]`.
+    /// * `kernel_size` - Height and width of the kernel.
+    /// * `options` - Convolution options.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Conv2dGeometry)` - The geometry of the convolution.
+    /// * `Err(Error)` - Shapes or options are not consistent.
+    pub fn conv2d_backward_weight(
+        input: &Shape,
+        output: &Shape,
+        kernel_size: [usize; 2],
+        options: &Conv2dOptions,
+    ) -> Result<Self> {
+        let [_, in_channels, ..] = input.as_array4()?;
+        let [_, out_channels, ..] = output.as_array4()?;
+        if options.groups == 0 || in_channels % options.groups != 0 {
+            return Err(Error::InvalidShape(format!(
+                "{} channels can not be split into {} groups.",
+                in_channels, options.groups
+            )));
+        }
+        let weight = Shape::new([
+            out_channels,
+            in_channels / options.groups,
+            kernel_size[0],
+            kernel_size[1],
+        ]);
+        Self::conv2d(input, &weight, options)?.check_output(output)
+    }
+
+    /// Obtains the geometry of a pooling from the output, which is used to calculate the
+    /// gradient with respect to the input.
+    ///
+    /// # Arguments
+    ///
+    /// * `output` - Shape of the output: `[batch, channels, out_height, out_width]`.
+    /// * `in_size` - Height and width of the input.
+    /// * `options` - Pooling options.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Conv2dGeometry)` - The geometry of the pooling.
+    /// * `Err(Error)` - The shape or options are not consistent.
+    pub fn pool2d_backward(
+        output: &Shape,
+        in_size: [usize; 2],
+        options: &Pool2dOptions,
+    ) -> Result<Self> {
+        let [batch_size, channels, ..] = output.as_array4()?;
+        let input = Shape::new([batch_size, channels, in_size[0], in_size[1]]);
+        Self::pool2d(&input, options)?.check_output(output)
+    }
+
+    /// Checks if the output shape matches the geometry.
+    pub(crate) fn check_output(self, output: &Shape) -> Result<Self> {
+        if self.output_shape() == *output {
+            Ok(self)
+        } else {
+            Err(Error::InvalidShape(format!(
+                "Expected output {}, but got {}.",
+                self.output_shape(),
+                output
+            )))
+        }
+    }
+
+    /// Calculates the size of the output images.
+    fn output_size(
+        in_size: [usize; 2],
+        kernel_size: [usize; 2],
+        stride: [usize; 2],
+        padding: [usize; 2],
+        dilation: [usize; 2],
+    ) -> Result<[usize; 2]> {
+        let mut out_size = [0; 2];
+        for i in 0..2 {
+            if kernel_size[i] == 0 || stride[i] == 0 || dilation[i] == 0 {
+                return Err(Error::InvalidData(format!(
+                    "Kernel size {:?}, stride {:?} and dilation {:?} must be positive.",
+                    kernel_size, stride, dilation
+                )));
+            }
+            let window = dilation[i] * (kernel_size[i] - 1) + 1;
+            let padded = in_size[i] + 2 * padding[i];
+            if padded < window {
+                return Err(Error::InvalidShape(format!(
+                    "Window of size {} does not fit in the padded input of size {}.",
+                    window, padded
+                )));
+            }
+            out_size[i] = (padded - window) / stride[i] + 1;
+        }
+        Ok(out_size)
+    }
+
+    /// Returns the shape of the input images.
+    pub fn input_shape(&self) -> Shape {
+        Shape::new([
+            self.batch_size,
+            self.in_channels,
+            self.in_size[0],
+            self.in_size[1],
+        ])
+    }
+
+    /// Returns the shape of the convolution kernel.
+    pub fn weight_shape(&self) -> Shape {
+        Shape::new([
+            self.out_channels,
+            self.in_channels / self.groups,
+            self.kernel_size[0],
+            self.kernel_size[1],
+        ])
+    }
+
+    /// Returns the shape of the output images.
+    pub fn output_shape(&self) -> Shape {
+        Shape::new([
+            self.batch_size,
+            self.out_channels,
+            self.out_size[0],
+            self.out_size[1],
+        ])
+    }
+
+    /// Obtains the input position covered by a kernel element at an output position.
+    ///
+    /// # Arguments
+    ///
+    /// * `axis` - 0 for the height, 1 for the width.
+    /// * `out_index` - Output position along `axis`.
+    /// * `kernel_index` - Kernel position along `axis`.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(usize)` - The input position.
+    /// * `None` - The kernel element is on the padding.
+    pub fn input_index(&self, axis: usize, out_index: usize, kernel_index: usize) -> Option<usize> {
+        (out_index * self.stride[axis] + kernel_index * self.dilation[axis])
+            .checked_sub(self.padding[axis])
+            .filter(|&i| i < self.in_size[axis])
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,174 @@
+use crate::conv::*;
+
+#[test]
+fn test_default_options() {
+    assert_eq!(
+        Conv2dOptions::default(),
+        Conv2dOptions {
+            stride: [1, 1],
+            padding: [0, 0],
+            dilation: [1, 1],
+            groups: 1
+        }
+    );
+    assert_eq!(Conv1dOptions::default().to_2d(), Conv2dOptions::default());
+}
+
+#[test]
+fn test_conv1d_to_2d() {
+    let options = Conv1dOptions {
+        stride: 2,
+        padding: 3,
+        dilation: 4,
+        groups: 5,
+    };
+    assert_eq!(
+        options.to_2d(),
+        Conv2dOptions {
+            stride: [1, 2],
+            padding: [0, 3],
+            dilation: [1, 4],
+            groups: 5
+        }
+    );
+}
+
+#[test]
+fn test_pool2d_options_new() {
+    let options = Pool2dOptions::new([2, 3]);
+    assert_eq!(options.kernel_size, [2, 3]);
+    assert_eq!(options.stride, [2, 3]);
+    assert_eq!(options.padding, [0, 0]);
+}
+
+#[test]
+fn test_conv2d_geometry() {
+    let geometry = Conv2dGeometry::conv2d(
+        &Shape::new([2, 4, 7, 8]),
+        &Shape::new([6, 2, 3, 2]),
+        &Conv2dOptions {
+            stride: [2, 1],
+            padding: [1, 0],
+            dilation: [1, 3],
+            groups: 2,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        geometry,
+        Conv2dGeometry {
+            batch_size: 2,
+            in_channels: 4,
+            in_size: [7, 8],
+            out_channels: 6,
+            // (7 + 2 - 3) / 2 + 1 = 4, (8 - 4) / 1 + 1 = 5
+            out_size: [4, 5],
+            kernel_size: [3, 2],
+            stride: [2, 1],
+            padding: [1, 0],
+            dilation: [1, 3],
+            groups: 2,
+        }
+    );
+    assert_eq!(geometry.input_shape(), Shape::new([2, 4, 7, 8]));
+    assert_eq!(geometry.weight_shape(), Shape::new([6, 2, 3, 2]));
+    assert_eq!(geometry.output_shape(), Shape::new([2, 6, 4, 5]));
+}
+
+#[rustfmt::skip]
+#[test]
+fn test_conv2d_geometry_invalid() {
+    let options = Conv2dOptions::default();
+    assert!(Conv2dGeometry::conv2d(&Shape::new([1, 2, 3]), &Shape::new([1, 2, 1, 1]), &options).is_err());
+    assert!(Conv2dGeometry::conv2d(&Shape::new([1, 2, 3, 3]), &Shape::new([1, 2, 1]), &options).is_err());
+    assert!(Conv2dGeometry::conv2d(&Shape::new([1, 2, 3, 3]), &Shape::new([1, 3, 1, 1]), &options).is_err());
+    assert!(Conv2dGeometry::conv2d(&Shape::new([1, 2, 3, 3]), &Shape::new([1, 2, 4, 1]), &options).is_err());
+    assert!(Conv2dGeometry::conv2d(&Shape::new([1, 2, 3, 3]), &Shape::new([1, 2, 0, 1]), &options).is_err());
+    assert!(Conv2dGeometry::conv2d(&Shape::new([1, 2, 3, 3]), &Shape::new([1, 2, 1, 1]), &Conv2dOptions { groups: 0, ..options }).is_err());
+    assert!(Conv2dGeometry::conv2d(&Shape::new([1, 2, 3, 3]), &Shape::new([3, 1, 1, 1]), &Conv2dOptions { groups: 2, ..options }).is_err());
+    assert!(Conv2dGeometry::conv2d(&Shape::new([1, 2, 3, 3]), &Shape::new([1, 2, 1, 1]), &Conv2dOptions { stride: [0, 1], ..options }).is_err());
+    assert!(Conv2dGeometry::conv2d(&Shape::new([1, 2, 3, 3]), &Shape::new([1, 2, 1, 1]), &Conv2dOptions { dilation: [1, 0], ..options }).is_err());
+    assert!(Conv2dGeometry::conv2d(&Shape::new([1, 2, 3, 3]), &Shape::new([1, 2, 2, 2]), &Conv2dOptions { dilation: [3, 1], ..options }).is_err());
+    assert!(Conv2dGeometry::conv2d(&Shape::new([1, 2, 3, 3]), &Shape::new([1, 2, 2, 2]), &Conv2dOptions { dilation: [3, 1], padding: [1, 0], ..options }).is_ok());
+}
+
+#[rustfmt::skip]
+#[test]
+fn test_conv2d_backward_geometry() {
+    let options = Conv2dOptions { stride: [2, 2], groups: 2, ..Default::default() };
+    let expected = Conv2dGeometry::conv2d(&Shape::new([3, 4, 6, 5]), &Shape::new([6, 2, 2, 1]), &options).unwrap();
+    assert_eq!(expected.output_shape(), Shape::new([3, 6, 3, 3]));
+
+    assert_eq!(Conv2dGeometry::conv2d_backward_input(&Shape::new([3, 6, 3, 3]), &Shape::new([6, 2, 2, 1]), [6, 5], &options), Ok(expected));
+    assert_eq!(Conv2dGeometry::conv2d_backward_input(&Shape::new([3, 6, 3, 3]), &Shape::new([6, 2, 2, 1]), [7, 6], &options).map(|g| g.in_size), Ok([7, 6]));
+    assert!(Conv2dGeometry::conv2d_backward_input(&Shape::new([3, 6, 3, 3]), &Shape::new([6, 2, 2, 1]), [8, 5], &options).is_err());
+    assert!(Conv2dGeometry::conv2d_backward_input(&Shape::new([3, 4, 3, 3]), &Shape::new([6, 2, 2, 1]), [6, 5], &options).is_err());
+
+    assert_eq!(Conv2dGeometry::conv2d_backward_weight(&Shape::new([3, 4, 6, 5]), &Shape::new([3, 6, 3, 3]), [2, 1], &options), Ok(expected));
+    assert!(Conv2dGeometry::conv2d_backward_weight(&Shape::new([3, 4, 6, 5]), &Shape::new([3, 6, 3, 3]), [2, 2], &options).is_err());
+    assert!(Conv2dGeometry::conv2d_backward_weight(&Shape::new([3, 5, 6, 5]), &Shape::new([3, 6, 3, 3]), [2, 1], &options).is_err());
+    assert!(Conv2dGeometry::conv2d_backward_weight(&Shape::new([2, 4, 6, 5]), &Shape::new([3, 6, 3, 3]), [2, 1], &options).is_err());
+}
+
+#[rustfmt::skip]
+#[test]
+fn test_pool2d_backward_geometry() {
+    let options = Pool2dOptions::new([2, 2]);
+    let expected = Conv2dGeometry::pool2d(&Shape::new([2, 3, 5, 4]), &options).unwrap();
+    assert_eq!(Conv2dGeometry::pool2d_backward(&Shape::new([2, 3, 2, 2]), [5, 4], &options), Ok(expected));
+    assert!(Conv2dGeometry::pool2d_backward(&Shape::new([2, 3, 2, 2]), [6, 6], &options).is_err());
+    assert!(Conv2dGeometry::pool2d_backward(&Shape::new([2, 3, 2]), [5, 4], &options).is_err());
+}
+
+#[test]
+fn test_pool2d_geometry() {
+    let geometry = Conv2dGeometry::pool2d(
+        &Shape::new([2, 3, 5, 4]),
+        &Pool2dOptions {
+            kernel_size: [3, 2],
+            stride: [2, 2],
+            padding: [1, 0],
+        },
+    )
+    .unwrap();
+    assert_eq!(geometry.in_channels, 3);
+    assert_eq!(geometry.out_channels, 3);
+    assert_eq!(geometry.groups, 3);
+    assert_eq!(geometry.dilation, [1, 1]);
+    assert_eq!(geometry.output_shape(), Shape::new([2, 3, 3, 2]));
+
+    assert!(Conv2dGeometry::pool2d(&Shape::new([2, 3, 5]), &Pool2dOptions::new([2, 2])).is_err());
+    assert!(
+        Conv2dGeometry::pool2d(&Shape::new([2, 3, 1, 5]), &Pool2dOptions::new([2, 2])).is_err()
+    );
+    assert!(Conv2dGeometry::pool2d(
+        &Shape::new([2, 3, 5, 5]),
+        &Pool2dOptions {
+            kernel_size: [2, 2],
+            stride: [1, 1],
+            padding: [2, 0],
+        }
+    )
+    .is_err());
+}
+
+#[test]
+fn test_input_index() {
+    let geometry = Conv2dGeometry::conv2d(
+        &Shape::new([1, 1, 5, 5]),
+        &Shape::new([1, 1, 3, 3]),
+        &Conv2dOptions {
+            stride: [2, 1],
+            padding: [1, 1],
+            dilation: [1, 2],
+            groups: 1,
+        },
+    )
+    .unwrap();
+    assert_eq!(geometry.input_index(0, 0, 0), None);
+    assert_eq!(geometry.input_index(0, 0, 1), Some(0));
+    assert_eq!(geometry.input_index(0, 2, 2), None);
+    assert_eq!(geometry.input_index(0, 1, 2), Some(3));
+    assert_eq!(geometry.input_index(1, 0, 2), Some(3));
+    assert_eq!(geometry.input_index(1, 2, 2), None);
+}
@@ -6,8 +6,11 @@ pub enum Error {
     InvalidGraph(String),
     InvalidHardware(String),
     InvalidNode(String),
+    InvalidData(String),
     InvalidLength(String),
+    InvalidName(String),
     InvalidShape(String),
+    Io(String),
     OutOfRange(String),
     NotSupported(String),
 }
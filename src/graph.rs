@@ -3,12 +3,18 @@ use crate::error::Error;
 use crate::hardware::Hardware;
 use crate::operator::Operator;
 use crate::result::Result;
+use crate::serialize::{self, Decoder, Encoder};
 use crate::shape::Shape;
 use std::cell::RefCell;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+use std::io;
+use std::ptr;
 
 /// Placeholder of `Array`s.
 /// Unlike `Option`, the object always holds its `Shape` and `Hardware` informatin.
-pub(crate) enum ArrayPlaceholder<'hw> {
+pub enum ArrayPlaceholder<'hw> {
     /// `Array` is not assigned, while its `Shape` is known.
     Unassigned(Shape, &'hw RefCell<dyn Hardware>),
 
@@ -22,7 +28,7 @@ impl<'hw> ArrayPlaceholder<'hw> {
     /// # Returns
     ///
     /// A reference to the inner `Shape` object.
-    pub(crate) fn shape(&self) -> &Shape {
+    pub fn shape(&self) -> &Shape {
         match self {
             Self::Unassigned(shape, _) => shape,
             Self::Assigned(array) => array.shape(),
@@ -34,7 +40,7 @@ impl<'hw> ArrayPlaceholder<'hw> {
     /// # Returns
     ///
     /// A reference to the `Hardware` object.
-    pub(crate) fn hardware(&self) -> &'hw RefCell<dyn Hardware> {
+    pub fn hardware(&self) -> &'hw RefCell<dyn Hardware> {
         match self {
             Self::Unassigned(_, hardware) => hardware,
             Self::Assigned(array) => array.hardware(),
@@ -47,17 +53,104 @@ impl<'hw> ArrayPlaceholder<'hw> {
     ///
     /// * `Some(&Array)` - A reference to the inner `Array` object.
     /// * `None` - The placeholder does not hold the `Array` object.
-    pub(crate) fn array(&self) -> Option<&Array<'hw>> {
+    pub fn array(&self) -> Option<&Array<'hw>> {
         match self {
             Self::Unassigned(_, _) => None,
             Self::Assigned(array) => Some(array),
         }
     }
+
+    /// Checks whether the placeholder holds the `Array` or not.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the `Array` is already calculated, `false` otherwise.
+    pub fn is_assigned(&self) -> bool {
+        matches!(self, Self::Assigned(_))
+    }
+
+    /// Summarizes the values of the `Array` if the placeholder holds it.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(ValueSummary)` - Statistics of the inner `Array` object.
+    /// * `None` - The placeholder does not hold the `Array` object.
+    pub fn summary(&self) -> Option<ValueSummary> {
+        self.array().map(ValueSummary::new)
+    }
+}
+
+/// Statistics of values in an `Array`, mainly for debugging.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ValueSummary {
+    /// The minimum value except NaNs.
+    pub min: f32,
+
+    /// The maximum value except NaNs.
+    pub max: f32,
+
+    /// The mean value except NaNs.
+    pub mean: f32,
+
+    /// The number of NaNs.
+    pub num_nans: usize,
+}
+
+impl ValueSummary {
+    /// Calculates the statistics of the given `Array`.
+    ///
+    /// Values are transferred to the host memory for calculation.
+    ///
+    /// # Arguments
+    ///
+    /// * `array` - `Array` to be summarized.
+    ///
+    /// # Returns
+    ///
+    /// A new `ValueSummary` object. If the array has no values except NaNs, `min`, `max` and
+    /// `mean` become NaN.
+    pub fn new(array: &Array) -> Self {
+        let values = array.get_values_f32();
+        let num_nans = values.iter().filter(|x| x.is_nan()).count();
+        let mut min = f32::INFINITY;
+        let mut max = f32::NEG_INFINITY;
+        let mut sum = 0f64;
+        for &x in values.iter().filter(|x| !x.is_nan()) {
+            min = min.min(x);
+            max = max.max(x);
+            sum += x as f64;
+        }
+        let count = values.len() - num_nans;
+        if count == 0 {
+            return Self {
+                min: f32::NAN,
+                max: f32::NAN,
+                mean: f32::NAN,
+                num_nans,
+            };
+        }
+        Self {
+            min,
+            max,
+            mean: (sum / count as f64) as f32,
+            num_nans,
+        }
+    }
+}
+
+impl fmt::Display for ValueSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "min={}, max={}, mean={}, nans={}",
+            self.min, self.max, self.mean, self.num_nans
+        )
+    }
 }
 
 /// Individual step in computation graphs.
 /// Step owns an Operator which consumes several values produced by preceding steps.
-pub(crate) struct Step<'hw: 'op, 'op> {
+pub struct Step<'hw: 'op, 'op> {
     /// Operator owned by this step.
     pub(crate) operator: Box<dyn Operator<'hw> + 'op>,
 
@@ -81,12 +174,89 @@ impl<'hw: 'op, 'op> Step<'hw, 'op> {
             output,
         }
     }
+
+    /// Returns the name of the operator owned by this step.
+    ///
+    /// # Returns
+    ///
+    /// The name of the operator.
+    pub fn operator_name(&self) -> String {
+        self.operator.name()
+    }
+
+    /// Returns the input step IDs.
+    ///
+    /// # Returns
+    ///
+    /// A reference to the list of step IDs consumed by this step.
+    pub fn inputs(&self) -> &[usize] {
+        &self.inputs
+    }
+
+    /// Returns the output value.
+    ///
+    /// # Returns
+    ///
+    /// A reference to the `ArrayPlaceholder` holding the output of this step.
+    pub fn output(&self) -> &ArrayPlaceholder<'hw> {
+        &self.output
+    }
+}
+
+/// Identity of steps with pure operators, used to find equivalent steps in the hash-consing
+/// mode.
+#[derive(PartialEq, Eq, Hash)]
+struct StepKey {
+    /// Name of the operator.
+    operator_name: String,
+
+    /// Attributes encoded by `Operator::encode_attributes()`.
+    attributes: Vec<u8>,
+
+    /// Input step IDs.
+    inputs: Vec<usize>,
+
+    /// Address of the output hardware.
+    hardware: *const (),
+}
+
+impl StepKey {
+    /// Creates the key of a step.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(StepKey)` - The key of the step.
+    /// * `None` - The operator is not pure or not serializable, and the step must not be shared.
+    fn new(
+        operator: &dyn Operator,
+        inputs: &[usize],
+        hardware: &RefCell<dyn Hardware>,
+    ) -> Option<Self> {
+        if !operator.is_pure() {
+            return None;
+        }
+        let mut encoder = Encoder::new();
+        operator.encode_attributes(&mut encoder).ok()?;
+        Some(Self {
+            operator_name: operator.name(),
+            attributes: encoder.into_bytes(),
+            inputs: inputs.to_vec(),
+            hardware: hardware as *const RefCell<dyn Hardware> as *const (),
+        })
+    }
 }
 
-// Computation graph.
+/// Computation graph.
 pub struct Graph<'hw: 'op, 'op> {
     /// All steps registered to this graph.
     steps: Vec<Step<'hw, 'op>>,
+
+    /// Mapping from parameter IDs to step IDs holding their values.
+    parameters: HashMap<usize, usize>,
+
+    /// Mapping from identities of pure steps to their step IDs.
+    /// `None` if the hash-consing mode is disabled.
+    pure_steps: Option<HashMap<StepKey, usize>>,
 }
 
 impl<'hw: 'op, 'op> Graph<'hw, 'op> {
@@ -96,7 +266,54 @@ impl<'hw: 'op, 'op> Graph<'hw, 'op> {
     ///
     /// A new `Graph` object containing zero steps.
     pub fn new() -> Self {
-        Self { steps: vec![] }
+        Self {
+            steps: vec![],
+            parameters: HashMap::new(),
+            pure_steps: None,
+        }
+    }
+
+    /// Enables or disables the hash-consing mode.
+    ///
+    /// In the hash-consing mode, registering a step equivalent to an existing one returns the
+    /// existing step ID instead of adding a new step, so that common subexpressions such as
+    /// repeated `Node::fill()` of the same shape are calculated only once. Steps are equivalent if
+    /// their operators are pure (see `Operator::is_pure()`) and have the same name and attributes,
+    /// and the steps have the same inputs and output hardware. Constants are never shared.
+    ///
+    /// Since shared steps are the same node, they also share their gradients. For example,
+    /// `grad(a * b, &[a, b])` with `a` and `b` created by `Node::fill()` with the same arguments
+    /// yields the derivative with respect to the single merged node twice. Use
+    /// `Node::variable()` or `Node::constant()` to create independent inputs.
+    ///
+    /// Steps registered before enabling the mode are also taken into account.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - `true` to enable the mode, `false` to disable it.
+    pub fn set_hash_consing(&mut self, enabled: bool) {
+        if enabled == self.pure_steps.is_some() {
+            return;
+        }
+        self.pure_steps = enabled.then(|| {
+            let mut pure_steps = HashMap::new();
+            for (step_id, step) in self.steps.iter().enumerate() {
+                let key = StepKey::new(&*step.operator, &step.inputs, step.output.hardware());
+                if let Some(key) = key {
+                    pure_steps.entry(key).or_insert(step_id);
+                }
+            }
+            pure_steps
+        });
+    }
+
+    /// Returns whether the hash-consing mode is enabled.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the mode is enabled, `false` otherwise.
+    pub fn is_hash_consing(&self) -> bool {
+        self.pure_steps.is_some()
     }
 
     /// Returns the number of registered steps.
@@ -108,6 +325,15 @@ impl<'hw: 'op, 'op> Graph<'hw, 'op> {
         self.steps.len()
     }
 
+    /// Returns all registered steps.
+    ///
+    /// # Returns
+    ///
+    /// A reference to the list of `Step`s. The index of each element is its step ID.
+    pub fn steps(&self) -> &[Step<'hw, 'op>] {
+        &self.steps
+    }
+
     /// Returns a reference to the specified `Step`.
     ///
     /// # Arguments
@@ -116,9 +342,9 @@ impl<'hw: 'op, 'op> Graph<'hw, 'op> {
     ///
     /// # Returns
     ///
-    /// * `Some(&Step)` - A reference to the specified `Step` in this graph.
-    /// * `None` - `step_id` is invalid.
-    pub(crate) fn get_step(&self, step_id: usize) -> Result<&Step<'hw, 'op>> {
+    /// * `Ok(&Step)` - A reference to the specified `Step` in this graph.
+    /// * `Err(Error)` - `step_id` is invalid.
+    pub fn get_step(&self, step_id: usize) -> Result<&Step<'hw, 'op>> {
         self.steps
             .get(step_id)
             .ok_or_else(|| Error::InvalidNode(format!("Invalid step ID: {}", step_id)))
@@ -133,7 +359,8 @@ impl<'hw: 'op, 'op> Graph<'hw, 'op> {
     ///
     /// # Returns
     ///
-    /// * `Ok(usize)` - The new step ID inserted by this function.
+    /// * `Ok(usize)` - The new step ID inserted by this function, or the ID of an equivalent step
+    ///   if the hash-consing mode is enabled.
     /// * `Err(Error)` - Some error occurred during the process.
     pub(crate) fn add_step<'g>(
         &'g mut self,
@@ -163,6 +390,17 @@ impl<'hw: 'op, 'op> Graph<'hw, 'op> {
         let output_shape = operator.perform_shape(&input_shapes)?;
         let output_hardware = operator.perform_hardware(&input_hardwares)?;
 
+        if let Some(pure_steps) = &mut self.pure_steps {
+            if let Some(key) = StepKey::new(&*operator, &inputs, output_hardware) {
+                match pure_steps.entry(key) {
+                    Entry::Occupied(entry) => return Ok(*entry.get()),
+                    Entry::Vacant(entry) => {
+                        entry.insert(new_step_id);
+                    }
+                }
+            }
+        }
+
         self.steps.push(Step::new(
             operator,
             inputs,
@@ -172,6 +410,30 @@ impl<'hw: 'op, 'op> Graph<'hw, 'op> {
         Ok(new_step_id)
     }
 
+    /// Returns the step holding the value of the specified parameter.
+    ///
+    /// # Arguments
+    ///
+    /// * `parameter_id` - ID of the `Parameter`.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(usize)` - The step ID associated to the parameter.
+    /// * `None` - The parameter is not registered to this graph.
+    pub(crate) fn get_parameter_step(&self, parameter_id: usize) -> Option<usize> {
+        self.parameters.get(&parameter_id).copied()
+    }
+
+    /// Associates a parameter with a step in this graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `parameter_id` - ID of the `Parameter`.
+    /// * `step_id` - Step ID holding the value of the parameter.
+    pub(crate) fn set_parameter_step(&mut self, parameter_id: usize, step_id: usize) {
+        self.parameters.insert(parameter_id, step_id);
+    }
+
     /// Performs calculation to obtain the value of specified node.
     ///
     /// This function internally performs a push-down automaton to recursively obtain the values
@@ -186,6 +448,11 @@ impl<'hw: 'op, 'op> Graph<'hw, 'op> {
     /// # Returns
     ///
     /// * Calculated/cached value associated to `target`.
+    ///
+    /// # Panics
+    ///
+    /// * `target` is not a valid step ID.
+    /// * `Operator::perform()` of some step returns an error.
     pub(crate) fn calculate(&mut self, target: usize) -> &Array<'hw> {
         // Avoiding an edge case: inner step_ids should be correct, but `target` is not constrained.
         assert!(target < self.steps.len(), "Invalid step ID: {}", target);
@@ -206,8 +473,7 @@ impl<'hw: 'op, 'op> Graph<'hw, 'op> {
         // action_stack represents the state of the push-down automaton.
         let mut action_stack = vec![(target, Action::Fetch)];
 
-        while !action_stack.is_empty() {
-            let (step_id, action) = action_stack.pop().unwrap();
+        while let Some((step_id, action)) = action_stack.pop() {
             match action {
                 Action::Fetch => {
                     let step = unsafe { self.steps.get_unchecked(step_id) };
@@ -242,8 +508,13 @@ impl<'hw: 'op, 'op> Graph<'hw, 'op> {
                         .collect::<Vec<_>>();
 
                     // Perform the operator.
+                    // Shapes and hardwares are already checked when the step was added, so errors
+                    // here indicate a broken operator or a hardware failure.
+                    let output = step.operator.perform(&inputs).unwrap_or_else(|e| {
+                        panic!("Failed to perform {}: {}", step.operator.name(), e)
+                    });
                     unsafe { self.steps.get_unchecked_mut(step_id) }.output =
-                        ArrayPlaceholder::Assigned(step.operator.perform(&inputs).unwrap());
+                        ArrayPlaceholder::Assigned(output);
                 }
             }
         }
@@ -254,6 +525,256 @@ impl<'hw: 'op, 'op> Graph<'hw, 'op> {
             .array()
             .unwrap()
     }
+
+    /// Serializes this graph into a versioned binary format.
+    ///
+    /// Each step is stored with the name and attributes of its operator, input step IDs and output
+    /// `Shape`. Arrays owned by operators, e.g., values of `Node::constant()` and
+    /// `Node::parameter()`, are always stored, so that the graph can be calculated without the
+    /// original code after `Graph::load()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Destination of the serialized data.
+    /// * `with_values` - If `true`, values of already calculated steps are also stored.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The graph is serialized.
+    /// * `Err(Error)` - Some operator is not a built-in operator or does not support
+    ///   serialization, or writing failed.
+    pub fn save<W: io::Write>(&self, writer: &mut W, with_values: bool) -> Result<()> {
+        let mut encoder = Encoder::new();
+        encoder.write_bytes(serialize::MAGIC);
+        encoder.write_u32(serialize::VERSION);
+        encoder.write_usize(self.steps.len());
+
+        for step in &self.steps {
+            let name = step.operator.name();
+            if !serialize::BUILTIN_OPERATORS.contains(&name.as_str()) {
+                return Err(Error::NotSupported(format!(
+                    "{} is not a built-in operator and cannot be restored by Graph::load().",
+                    name
+                )));
+            }
+            encoder.write_string(&name);
+            encoder.write_usize(step.inputs.len());
+            for &input in &step.inputs {
+                encoder.write_usize(input);
+            }
+            encoder.write_shape(step.output.shape());
+
+            let mut attributes = Encoder::new();
+            step.operator.encode_attributes(&mut attributes)?;
+            let attributes = attributes.into_bytes();
+            encoder.write_usize(attributes.len());
+            encoder.write_bytes(&attributes);
+
+            match step.output.array() {
+                Some(array) if with_values => {
+                    encoder.write_u8(1);
+                    encoder.write_f32s(&array.get_values_f32());
+                }
+                _ => encoder.write_u8(0),
+            }
+        }
+
+        writer
+            .write_all(&encoder.into_bytes())
+            .map_err(|e| Error::Io(e.to_string()))
+    }
+
+    /// Deserializes a graph stored by `Graph::save()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - Source of the serialized data.
+    /// * `hardware` - `Hardware` object to host all values in the resulting graph, regardless of
+    ///   the hardwares used in the original graph.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Graph)` - A new `Graph` object with the same steps as the original graph. Parameters
+    ///   of the original graph are loaded as constants. Step IDs are also preserved, and
+    ///   `Node::from_step()` obtains the nodes to calculate or to extend the loaded graph.
+    /// * `Err(Error)` - Reading failed, or the data is broken or has an unsupported version.
+    pub fn load<R: io::Read>(reader: &mut R, hardware: &'hw RefCell<dyn Hardware>) -> Result<Self> {
+        let mut bytes = vec![];
+        reader
+            .read_to_end(&mut bytes)
+            .map_err(|e| Error::Io(e.to_string()))?;
+        let mut decoder = Decoder::new(&bytes);
+
+        if decoder.read_bytes(serialize::MAGIC.len())? != serialize::MAGIC {
+            return Err(Error::InvalidData("Not a serialized graph.".to_string()));
+        }
+        let version = decoder.read_u32()?;
+        if version != serialize::VERSION {
+            return Err(Error::NotSupported(format!(
+                "Unsupported version: {}",
+                version
+            )));
+        }
+
+        let mut graph = Self::new();
+        let num_steps = decoder.read_usize()?;
+
+        for step_id in 0..num_steps {
+            let name = decoder.read_string()?;
+            let num_inputs = decoder.read_usize()?;
+            let inputs = (0..num_inputs)
+                .map(|_| decoder.read_usize())
+                .collect::<Result<Vec<_>>>()?;
+            let shape = decoder.read_shape()?;
+            let num_attributes = decoder.read_usize()?;
+            let mut attributes = Decoder::new(decoder.read_bytes(num_attributes)?);
+            let operator = serialize::read_operator(&name, &mut attributes, hardware)?;
+
+            // Steps are stored in the topological order.
+            if let Some(&input) = inputs.iter().find(|&&input| input >= step_id) {
+                return Err(Error::InvalidData(format!(
+                    "Step {} refers to a succeeding step {}.",
+                    step_id, input
+                )));
+            }
+            graph.add_step(operator, inputs)?;
+
+            let output = &mut graph.steps[step_id].output;
+            if *output.shape() != shape {
+                return Err(Error::InvalidData(format!(
+                    "Step {} has a shape {}, but {} is stored.",
+                    step_id,
+                    output.shape(),
+                    shape
+                )));
+            }
+            if decoder.read_u8()? != 0 {
+                let values = decoder.read_f32s(shape.num_elements())?;
+                *output =
+                    ArrayPlaceholder::Assigned(Array::constant_f32(hardware, shape, &values)?);
+            }
+        }
+
+        decoder.finish()?;
+        Ok(graph)
+    }
+
+    /// Renders this graph in the Graphviz DOT language.
+    ///
+    /// Each step is rendered as a vertex labeled by its step ID, operator name, output `Shape`,
+    /// hardware, and whether its value is already calculated. Hardwares are numbered in order of
+    /// first appearance, e.g., `hw0`. Edges are directed from inputs to consumers.
+    ///
+    /// Use `Node::to_dot()` to additionally highlight the subgraph feeding a specific node.
+    ///
+    /// # Returns
+    ///
+    /// A DOT representation of this graph, which can be rendered by e.g. `dot -Tsvg`.
+    pub fn to_dot(&self) -> String {
+        self.render_dot(None)
+    }
+
+    /// Renders this graph in the Graphviz DOT language.
+    ///
+    /// # Arguments
+    ///
+    /// * `highlight` - If `Some(step_id)`, the step and all steps it depends on are highlighted.
+    ///
+    /// # Returns
+    ///
+    /// A DOT representation of this graph.
+    ///
+    /// # Panics
+    ///
+    /// `highlight` is not a valid step ID.
+    pub(crate) fn render_dot(&self, highlight: Option<usize>) -> String {
+        let highlighted = match highlight {
+            Some(target) => self.ancestors(target),
+            None => vec![false; self.steps.len()],
+        };
+
+        // Hardwares have no names, so that they are identified by the order of appearance.
+        let mut hardwares: Vec<&'hw RefCell<dyn Hardware>> = vec![];
+
+        let mut dot = String::from("digraph {\n    node [shape=box];\n");
+
+        for (step_id, step) in self.steps.iter().enumerate() {
+            let hardware = step.output.hardware();
+            let hardware_id = match hardwares.iter().position(|&h| ptr::addr_eq(h, hardware)) {
+                Some(hardware_id) => hardware_id,
+                None => {
+                    hardwares.push(hardware);
+                    hardwares.len() - 1
+                }
+            };
+            let state = match step.output {
+                ArrayPlaceholder::Unassigned(_, _) => "unassigned",
+                ArrayPlaceholder::Assigned(_) => "assigned",
+            };
+            let label = format!(
+                "#{} {}\\n{}\\nhw{}\\n{}",
+                step_id,
+                escape_dot(&step.operator.name()),
+                step.output.shape(),
+                hardware_id,
+                state
+            );
+            let style = if highlight == Some(step_id) {
+                ", style=filled, fillcolor=salmon, penwidth=2"
+            } else if highlighted[step_id] {
+                ", style=filled, fillcolor=lightblue"
+            } else {
+                ""
+            };
+            writeln!(dot, "    s{} [label=\"{}\"{}];", step_id, label, style).unwrap();
+        }
+
+        for (step_id, step) in self.steps.iter().enumerate() {
+            let style = if highlighted[step_id] {
+                " [penwidth=2]"
+            } else {
+                ""
+            };
+            for &input in &step.inputs {
+                writeln!(dot, "    s{} -> s{}{};", input, step_id, style).unwrap();
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Collects the steps necessary to calculate the target step.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - Target step ID.
+    ///
+    /// # Returns
+    ///
+    /// A mask over all steps: `true` if the step is `target` itself or `target` depends on it.
+    ///
+    /// # Panics
+    ///
+    /// `target` is not a valid step ID.
+    fn ancestors(&self, target: usize) -> Vec<bool> {
+        assert!(target < self.steps.len(), "Invalid step ID: {}", target);
+
+        let mut mask = vec![false; self.steps.len()];
+        let mut stack = vec![target];
+        mask[target] = true;
+
+        while let Some(step_id) = stack.pop() {
+            for &input in &self.steps[step_id].inputs {
+                if !mask[input] {
+                    mask[input] = true;
+                    stack.push(input);
+                }
+            }
+        }
+
+        mask
+    }
 }
 
 impl<'hw: 'op, 'op> Default for Graph<'hw, 'op> {
@@ -262,4 +783,37 @@ impl<'hw: 'op, 'op> Default for Graph<'hw, 'op> {
     }
 }
 
-// TODO(odashi): add tests.
+impl<'hw: 'op, 'op> fmt::Display for Graph<'hw, 'op> {
+    /// Prints the textual listing of all steps, one step per line:
+    /// `%5 = Add(%3, %4) : (2, 3)`.
+    /// Summary of the value is appended to the line if the step is already calculated.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (step_id, step) in self.steps.iter().enumerate() {
+            write!(
+                f,
+                "%{} = {}({}) : {}",
+                step_id,
+                step.operator.name(),
+                step.inputs
+                    .iter()
+                    .map(|input| format!("%{}", input))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                step.output.shape()
+            )?;
+            if let Some(summary) = step.output.summary() {
+                write!(f, " ; {}", summary)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Escapes a string to be embedded in a quoted DOT identifier.
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests;
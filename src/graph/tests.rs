@@ -0,0 +1,440 @@
+use crate::graph::*;
+use crate::hardware::cpu::CpuHardware;
+use crate::node::{IntoNode, Node};
+use crate::shape::Shape;
+
+#[test]
+fn test_to_dot_empty() {
+    let g = Graph::new();
+    assert_eq!(g.to_dot(), "digraph {\n    node [shape=box];\n}\n");
+}
+
+#[test]
+fn test_to_dot() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let a = vec![1f32, 2.].into_node(&g, &hw);
+    let b = 3f32.into_node(&g, &hw);
+    let c = -a;
+    let _ = c.calculate();
+    let _ = -b;
+
+    assert_eq!(
+        g.borrow().to_dot(),
+        "digraph {\n    node [shape=box];\n    \
+         s0 [label=\"#0 Constant\\n(2)\\nhw0\\nassigned\"];\n    \
+         s1 [label=\"#1 Fill\\n()\\nhw0\\nunassigned\"];\n    \
+         s2 [label=\"#2 Neg\\n(2)\\nhw0\\nassigned\"];\n    \
+         s3 [label=\"#3 Neg\\n()\\nhw0\\nunassigned\"];\n    \
+         s0 -> s2;\n    \
+         s1 -> s3;\n}\n"
+    );
+}
+
+#[test]
+fn test_to_dot_hardwares() {
+    let hw1 = RefCell::new(CpuHardware::new());
+    let hw2 = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let _ = 1f32.into_node(&g, &hw1);
+    let _ = 2f32.into_node(&g, &hw2);
+    let _ = 3f32.into_node(&g, &hw1);
+
+    let dot = g.borrow().to_dot();
+    assert!(dot.contains("s0 [label=\"#0 Fill\\n()\\nhw0\\nunassigned\"];"));
+    assert!(dot.contains("s1 [label=\"#1 Fill\\n()\\nhw1\\nunassigned\"];"));
+    assert!(dot.contains("s2 [label=\"#2 Fill\\n()\\nhw0\\nunassigned\"];"));
+}
+
+#[test]
+fn test_to_dot_highlight() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let a = 1f32.into_node(&g, &hw);
+    let b = 2f32.into_node(&g, &hw);
+    let c = a + b;
+    let d = -b;
+    let e = c * d;
+
+    let dot = c.to_dot();
+    assert!(dot.contains(
+        "s0 [label=\"#0 Fill\\n()\\nhw0\\nunassigned\", style=filled, fillcolor=lightblue];"
+    ));
+    assert!(dot.contains(
+        "s1 [label=\"#1 Fill\\n()\\nhw0\\nunassigned\", style=filled, fillcolor=lightblue];"
+    ));
+    assert!(dot.contains("s2 [label=\"#2 Add\\n()\\nhw0\\nunassigned\", style=filled, fillcolor=salmon, penwidth=2];"));
+    assert!(dot.contains("s3 [label=\"#3 Neg\\n()\\nhw0\\nunassigned\"];"));
+    assert!(dot.contains("s4 [label=\"#4 Mul\\n()\\nhw0\\nunassigned\"];"));
+    assert!(dot.contains("s0 -> s2 [penwidth=2];"));
+    assert!(dot.contains("s1 -> s2 [penwidth=2];"));
+    assert!(dot.contains("s1 -> s3;"));
+    assert!(dot.contains("s2 -> s4;"));
+    assert!(dot.contains("s3 -> s4;"));
+
+    // The whole graph is highlighted from the last node.
+    let dot = e.to_dot();
+    assert!(dot.contains(
+        "s3 [label=\"#3 Neg\\n()\\nhw0\\nunassigned\", style=filled, fillcolor=lightblue];"
+    ));
+    assert!(dot.contains("s3 -> s4 [penwidth=2];"));
+
+    // Node::to_dot() differs from Graph::to_dot() only in the highlights.
+    assert_eq!(
+        g.borrow().to_dot(),
+        Node::new(&g, 4)
+            .to_dot()
+            .replace(", style=filled, fillcolor=lightblue", "")
+            .replace(", style=filled, fillcolor=salmon, penwidth=2", "")
+            .replace(" [penwidth=2]", "")
+    );
+}
+
+#[test]
+fn test_escape_dot() {
+    assert_eq!(escape_dot("Foo"), "Foo");
+    assert_eq!(escape_dot("a\"b\\c"), "a\\\"b\\\\c");
+}
+
+#[test]
+fn test_steps() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let a = vec![1f32, 2.].into_node(&g, &hw);
+    let b = -a;
+    let c = a + b;
+    let _ = b.calculate();
+
+    let g = g.borrow();
+    let steps = g.steps();
+    assert_eq!(steps.len(), 3);
+    assert_eq!(
+        steps.iter().map(|s| s.operator_name()).collect::<Vec<_>>(),
+        ["Constant", "Neg", "Add"]
+    );
+    assert_eq!(steps[0].inputs(), &[] as &[usize]);
+    assert_eq!(steps[1].inputs(), &[0]);
+    assert_eq!(steps[2].inputs(), &[0, 1]);
+    assert!(steps.iter().all(|s| *s.output().shape() == Shape::new([2])));
+    assert!(steps
+        .iter()
+        .all(|s| ptr::addr_eq(s.output().hardware(), &hw)));
+    assert!(steps[0].output().is_assigned());
+    assert!(steps[1].output().is_assigned());
+    assert!(!steps[2].output().is_assigned());
+    assert_eq!(
+        steps[1].output().array().unwrap().get_values_f32(),
+        vec![-1., -2.]
+    );
+    assert!(steps[2].output().array().is_none());
+    assert!(steps[2].output().summary().is_none());
+
+    assert_eq!(g.get_step(c.step_id()).unwrap().operator_name(), "Add");
+    assert!(g.get_step(3).is_err());
+}
+
+#[test]
+fn test_value_summary() {
+    let hw = RefCell::new(CpuHardware::new());
+    let array = Array::constant_f32(&hw, Shape::new([2, 2]), &[1., -3., f32::NAN, 8.]).unwrap();
+    assert_eq!(
+        ValueSummary::new(&array),
+        ValueSummary {
+            min: -3.,
+            max: 8.,
+            mean: 2.,
+            num_nans: 1,
+        }
+    );
+    assert_eq!(
+        ValueSummary::new(&array).to_string(),
+        "min=-3, max=8, mean=2, nans=1"
+    );
+
+    let array = Array::constant_f32(&hw, Shape::new([2]), &[f32::NAN, f32::NAN]).unwrap();
+    let summary = ValueSummary::new(&array);
+    assert!(summary.min.is_nan());
+    assert!(summary.max.is_nan());
+    assert!(summary.mean.is_nan());
+    assert_eq!(summary.num_nans, 2);
+
+    let array = Array::constant_f32(&hw, Shape::new([0]), &[]).unwrap();
+    let summary = ValueSummary::new(&array);
+    assert!(summary.mean.is_nan());
+    assert_eq!(summary.num_nans, 0);
+}
+
+#[test]
+fn test_display() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    assert_eq!(g.borrow().to_string(), "");
+
+    let a = (Shape::new([2, 3]), vec![1f32, 2., 3., 4., 5., 6.]).into_node(&g, &hw);
+    let b = 2f32.into_node(&g, &hw);
+    let c = a.matmul(a.transpose());
+    let _ = a * b.expand_axis(0, 2).expand_axis(1, 3);
+    let _ = c.calculate();
+
+    assert_eq!(
+        g.borrow().to_string(),
+        "%0 = Constant() : (2, 3) ; min=1, max=6, mean=3.5, nans=0\n\
+         %1 = Fill() : ()\n\
+         %2 = Transpose(%0) : (3, 2) ; min=1, max=6, mean=3.5, nans=0\n\
+         %3 = MatMul(%0, %2) : (2, 2) ; min=14, max=77, mean=38.75, nans=0\n\
+         %4 = ExpandAxis(%1) : (2)\n\
+         %5 = ExpandAxis(%4) : (2, 3)\n\
+         %6 = Mul(%0, %5) : (2, 3)\n"
+    );
+}
+
+#[test]
+fn test_save_load() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let x = (Shape::new([2, 2]), vec![1f32, -2., 3., -4.]).into_node(&g, &hw);
+    let w = (Shape::new([2, 2]), vec![0.5f32, 1., -1., 2.]).into_node(&g, &hw);
+    let y = x.matmul(w).relu().sum_axis(1).clamp(0., 4.);
+    let expected = y.calculate().get_values_f32();
+
+    let mut bytes = vec![];
+    g.borrow().save(&mut bytes, false).unwrap();
+
+    let hw2 = RefCell::new(CpuHardware::new());
+    let loaded = RefCell::new(Graph::load(&mut bytes.as_slice(), &hw2).unwrap());
+    assert_eq!(
+        loaded.borrow().to_string().lines().count(),
+        g.borrow().num_steps()
+    );
+    assert_eq!(
+        loaded
+            .borrow()
+            .steps()
+            .iter()
+            .map(|s| (
+                s.operator_name(),
+                s.inputs().to_vec(),
+                s.output().shape().clone()
+            ))
+            .collect::<Vec<_>>(),
+        g.borrow()
+            .steps()
+            .iter()
+            .map(|s| (
+                s.operator_name(),
+                s.inputs().to_vec(),
+                s.output().shape().clone()
+            ))
+            .collect::<Vec<_>>()
+    );
+    assert!(loaded
+        .borrow()
+        .steps()
+        .iter()
+        .all(|s| ptr::addr_eq(s.output().hardware(), &hw2)));
+
+    // Calculated values are not stored.
+    assert!(loaded
+        .borrow()
+        .steps()
+        .iter()
+        .all(|s| !s.output().is_assigned()));
+    let target = loaded.borrow().num_steps() - 1;
+    let y2 = Node::from_step(&loaded, target).unwrap();
+    assert_eq!(y2.calculate().get_values_f32(), expected);
+
+    // The loaded graph can be extended.
+    assert_eq!(
+        (-y2).calculate().get_values_f32(),
+        expected.iter().map(|v| -v).collect::<Vec<_>>()
+    );
+    assert!(Node::from_step(&loaded, target + 2).is_err());
+
+    // The loaded graph can be saved again into the same bytes.
+    let mut bytes2 = vec![];
+    Graph::load(&mut bytes.as_slice(), &hw2)
+        .unwrap()
+        .save(&mut bytes2, false)
+        .unwrap();
+    assert_eq!(bytes2, bytes);
+}
+
+#[test]
+fn test_save_load_with_values() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let x = vec![1f32, 2.].into_node(&g, &hw);
+    let y = -x;
+    let _ = y * 3f32.into_node(&g, &hw).expand_axis(0, 2);
+    let _ = y.calculate();
+
+    let mut bytes = vec![];
+    g.borrow().save(&mut bytes, true).unwrap();
+    let loaded = Graph::load(&mut bytes.as_slice(), &hw).unwrap();
+    assert_eq!(loaded.to_string(), g.borrow().to_string());
+    assert_eq!(
+        loaded.steps()[1].output().array().unwrap().get_values_f32(),
+        vec![-1., -2.]
+    );
+    assert!(!loaded.steps()[4].output().is_assigned());
+}
+
+#[test]
+fn test_save_not_supported() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let x = 1f32.into_node(&g, &hw);
+    let _ = Node::custom_gradient(&[x], |args| args[0] * args[0], |_, _, gy| vec![gy]);
+
+    let mut bytes = vec![];
+    assert!(matches!(
+        g.borrow().save(&mut bytes, false),
+        Err(Error::NotSupported(_))
+    ));
+}
+
+#[test]
+fn test_load_errors() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let x = vec![1f32, 2.].into_node(&g, &hw);
+    let _ = -x;
+    let mut bytes = vec![];
+    g.borrow().save(&mut bytes, false).unwrap();
+    assert!(Graph::load(&mut bytes.as_slice(), &hw).is_ok());
+
+    // Empty data.
+    assert!(Graph::load(&mut [].as_slice(), &hw).is_err());
+
+    // Wrong magic.
+    let mut broken = bytes.clone();
+    broken[0] = b'X';
+    assert!(matches!(
+        Graph::load(&mut broken.as_slice(), &hw),
+        Err(Error::InvalidData(_))
+    ));
+
+    // Unsupported version.
+    let mut broken = bytes.clone();
+    broken[4] = 99;
+    assert!(matches!(
+        Graph::load(&mut broken.as_slice(), &hw),
+        Err(Error::NotSupported(_))
+    ));
+
+    // Truncated and extended data.
+    assert!(Graph::load(&mut &bytes[..bytes.len() - 1], &hw).is_err());
+    let extended = [bytes.as_slice(), &[0]].concat();
+    assert!(Graph::load(&mut extended.as_slice(), &hw).is_err());
+
+    // The last step refers to itself.
+    let mut broken = bytes.clone();
+    let pos = bytes.len() - 1 - 8 - 8 - 8 - 8;
+    assert_eq!(broken[pos], 0);
+    broken[pos] = 1;
+    assert!(matches!(
+        Graph::load(&mut broken.as_slice(), &hw),
+        Err(Error::InvalidData(_))
+    ));
+}
+
+#[test]
+fn test_hash_consing() {
+    let hw1 = RefCell::new(CpuHardware::new());
+    let hw2 = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    assert!(!g.borrow().is_hash_consing());
+
+    // Disabled by default.
+    let a = Node::fill(&g, &hw1, Shape::new([2]), 1.);
+    let b = Node::fill(&g, &hw1, Shape::new([2]), 1.);
+    assert_ne!(a, b);
+
+    g.borrow_mut().set_hash_consing(true);
+    assert!(g.borrow().is_hash_consing());
+    assert_eq!(Node::fill(&g, &hw1, Shape::new([2]), 1.), a);
+    assert_ne!(Node::fill(&g, &hw1, Shape::new([2]), 2.), a);
+    assert_ne!(Node::fill(&g, &hw1, Shape::new([3]), 1.), a);
+    assert_ne!(Node::fill(&g, &hw2, Shape::new([2]), 1.), a);
+
+    // Inputs are compared by their step IDs and orders.
+    assert_eq!(-a, -a);
+    assert_ne!(-a, -b);
+    assert_eq!(a + b, a + b);
+    assert_ne!(a + b, b + a);
+    assert_ne!(a + b, a - b);
+    assert_eq!(a.sum_axis(0), a.sum_axis(0));
+    assert_ne!(a.sum_axis(0), a.expand_axis(0, 1));
+
+    // Non-pure operators are never shared.
+    let c = vec![1f32, 2.].into_node(&g, &hw1);
+    assert_ne!(vec![1f32, 2.].into_node(&g, &hw1), c);
+    let custom = || Node::custom_gradient(&[a], |args| -args[0], |_, _, gy| vec![-gy]);
+    assert_ne!(custom(), custom());
+
+    // Disabling the mode.
+    let num_steps = g.borrow().num_steps();
+    g.borrow_mut().set_hash_consing(false);
+    assert!(!g.borrow().is_hash_consing());
+    assert_ne!(Node::fill(&g, &hw1, Shape::new([2]), 1.), a);
+    assert_eq!(g.borrow().num_steps(), num_steps + 1);
+}
+
+#[test]
+fn test_hash_consing_existing_steps() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let a = 1f32.into_node(&g, &hw);
+    let b = 1f32.into_node(&g, &hw);
+    let c = -b;
+
+    // The earliest equivalent step is used.
+    g.borrow_mut().set_hash_consing(true);
+    assert_eq!(1f32.into_node(&g, &hw), a);
+    assert_eq!(-b, c);
+    assert_ne!(-a, c);
+    assert_eq!(g.borrow().num_steps(), 4);
+}
+
+#[test]
+fn test_hash_consing_gradients() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    g.borrow_mut().set_hash_consing(true);
+
+    // Fills with the same arguments are merged into a single node.
+    let a = Node::fill(&g, &hw, Shape::new([]), 2.);
+    let b = Node::fill(&g, &hw, Shape::new([]), 2.);
+    assert_eq!(a, b);
+    let gx = crate::node::grad(a * b + a, &[a, b]);
+    assert_eq!(f32::try_from(gx[0]), Ok(5.));
+    assert_eq!(f32::try_from(gx[1]), Ok(5.));
+
+    // Variables are independent as in the normal mode.
+    let a = Node::variable(&g, &hw, Shape::new([]), 2.);
+    let b = Node::variable(&g, &hw, Shape::new([]), 2.);
+    assert_ne!(a, b);
+    let gx = crate::node::grad(a * b + a, &[a, b]);
+    assert_eq!(f32::try_from(gx[0]), Ok(3.));
+    assert_eq!(f32::try_from(gx[1]), Ok(2.));
+}
+
+#[test]
+fn test_hash_consing_calculation() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    g.borrow_mut().set_hash_consing(true);
+    let x = vec![1f32, 2.].into_node(&g, &hw);
+
+    let mut y = Node::fill(&g, &hw, Shape::new([2]), 0.);
+    for _ in 0..3 {
+        y = y + x * Node::fill(&g, &hw, Shape::new([2]), 2.);
+    }
+    // x, 0, 2, x * 2, and three additions.
+    assert_eq!(g.borrow().num_steps(), 7);
+    assert_eq!(y.calculate().get_values_f32(), vec![6., 12.]);
+
+    // Gradients through the shared step are accumulated.
+    let gx = crate::node::grad(y, &[x]);
+    assert_eq!(gx[0].calculate().get_values_f32(), vec![6., 6.]);
+}
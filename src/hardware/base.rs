@@ -1,3 +1,5 @@
+use crate::conv::Conv2dGeometry;
+
 /// Trait for computing backends.
 ///
 /// This trait provides the set of the lowest instructions that each computation backend are
@@ -116,6 +118,30 @@ pub unsafe trait Hardware {
     /// of the value type.
     unsafe fn elementwise_neg_f32(&mut self, src: *const u8, dest: *mut u8, num_elements: usize);
 
+    /// Performs elementwise clamp operation: dest = min(max(src, min), max).
+    /// NaN in `src` is propagated to `dest` as is.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - Hardware memory for the source.
+    /// * `dest` - Hardware memory for the destination.
+    /// * `min` - Lower bound of the resulting values.
+    /// * `max` - Upper bound of the resulting values.
+    /// * `num_elements` - Number of elements on each memory.
+    ///
+    /// # Safety
+    ///
+    /// `src` and `dest` own enough amount of memory to store data with `num_elements` elements
+    /// of the value type.
+    unsafe fn elementwise_clamp_f32(
+        &mut self,
+        src: *const u8,
+        dest: *mut u8,
+        min: f32,
+        max: f32,
+        num_elements: usize,
+    );
+
     /// Performs elementwise add operation.
     ///
     /// # Arguments
@@ -199,4 +225,1150 @@ pub unsafe trait Hardware {
         dest: *mut u8,
         num_elements: usize,
     );
+
+    /// Performs elementwise in-place add operation: dest += src.
+    ///
+    /// # Arguments
+    ///
+    /// * `dest` - Hardware memory for the destination, updated in place.
+    /// * `src` - Hardware memory for the source.
+    /// * `num_elements` - Number of elements on each memory.
+    ///
+    /// # Safety
+    ///
+    /// `dest` and `src` own enough amount of memory to store data with `num_elements` elements of
+    /// the value type. `src` may point to the same memory as `dest`, but must not partially
+    /// overlap with it.
+    unsafe fn add_assign_f32(&mut self, dest: *mut u8, src: *const u8, num_elements: usize);
+
+    /// Performs elementwise in-place multiply operation: dest *= src.
+    ///
+    /// # Arguments
+    ///
+    /// * `dest` - Hardware memory for the destination, updated in place.
+    /// * `src` - Hardware memory for the source.
+    /// * `num_elements` - Number of elements on each memory.
+    ///
+    /// # Safety
+    ///
+    /// `dest` and `src` own enough amount of memory to store data with `num_elements` elements of
+    /// the value type. `src` may point to the same memory as `dest`, but must not partially
+    /// overlap with it.
+    unsafe fn mul_assign_f32(&mut self, dest: *mut u8, src: *const u8, num_elements: usize);
+
+    /// Performs in-place scaled add operation: dest += alpha * src.
+    ///
+    /// # Arguments
+    ///
+    /// * `dest` - Hardware memory for the destination, updated in place.
+    /// * `alpha` - Scaling factor of `src`.
+    /// * `src` - Hardware memory for the source.
+    /// * `num_elements` - Number of elements on each memory.
+    ///
+    /// # Safety
+    ///
+    /// `dest` and `src` own enough amount of memory to store data with `num_elements` elements of
+    /// the value type. `src` may point to the same memory as `dest`, but must not partially
+    /// overlap with it.
+    unsafe fn axpy_f32(&mut self, dest: *mut u8, alpha: f32, src: *const u8, num_elements: usize);
+
+    /// Performs an SGD update in place.
+    ///
+    /// For each element, the following calculation is performed:
+    ///
+    /// ```text
+    /// g = gradient + weight_decay * value
+    /// velocity = momentum * velocity + g
+    /// value -= learning_rate * (nesterov ? g + momentum * velocity : velocity)
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Hardware memory for the parameter value, updated in place.
+    /// * `gradient` - Hardware memory for the gradient.
+    /// * `velocity` - Hardware memory for the momentum buffer, updated in place.
+    /// * `learning_rate` - Learning rate.
+    /// * `momentum` - Momentum factor. 0 represents the plain SGD.
+    /// * `weight_decay` - Coefficient of the L2 penalty.
+    /// * `nesterov` - Whether to use the Nesterov momentum or not.
+    /// * `num_elements` - Number of elements on each memory.
+    ///
+    /// # Safety
+    ///
+    /// `value`, `gradient` and `velocity` own enough amount of memory to store data with
+    /// `num_elements` elements of the value type, and they do not overlap each other.
+    #[allow(clippy::too_many_arguments)]
+    unsafe fn sgd_update_f32(
+        &mut self,
+        value: *mut u8,
+        gradient: *const u8,
+        velocity: *mut u8,
+        learning_rate: f32,
+        momentum: f32,
+        weight_decay: f32,
+        nesterov: bool,
+        num_elements: usize,
+    );
+
+    /// Performs an Adam update in place.
+    ///
+    /// For each element, the following calculation is performed:
+    ///
+    /// ```text
+    /// g = gradient + (decoupled ? 0 : weight_decay * value)
+    /// m = beta1 * m + (1 - beta1) * g
+    /// v = beta2 * v + (1 - beta2) * g^2
+    /// value -= learning_rate * ((m / bias_correction1) / (sqrt(v / bias_correction2) + epsilon)
+    ///                           + (decoupled ? weight_decay * value : 0))
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Hardware memory for the parameter value, updated in place.
+    /// * `gradient` - Hardware memory for the gradient.
+    /// * `m` - Hardware memory for the first moment, updated in place.
+    /// * `v` - Hardware memory for the second moment, updated in place.
+    /// * `learning_rate` - Learning rate.
+    /// * `beta1` - Decay rate of the first moment.
+    /// * `beta2` - Decay rate of the second moment.
+    /// * `epsilon` - Small value to avoid zero division.
+    /// * `weight_decay` - Coefficient of the weight decay.
+    /// * `decoupled` - Whether to apply the weight decay directly to the value (AdamW) or to the
+    ///   gradient (Adam with L2 penalty).
+    /// * `bias_correction1` - Bias correction of the first moment: 1 - beta1^t.
+    /// * `bias_correction2` - Bias correction of the second moment: 1 - beta2^t.
+    /// * `num_elements` - Number of elements on each memory.
+    ///
+    /// # Safety
+    ///
+    /// `value`, `gradient`, `m` and `v` own enough amount of memory to store data with
+    /// `num_elements` elements of the value type, and they do not overlap each other.
+    #[allow(clippy::too_many_arguments)]
+    unsafe fn adam_update_f32(
+        &mut self,
+        value: *mut u8,
+        gradient: *const u8,
+        m: *mut u8,
+        v: *mut u8,
+        learning_rate: f32,
+        beta1: f32,
+        beta2: f32,
+        epsilon: f32,
+        weight_decay: f32,
+        decoupled: bool,
+        bias_correction1: f32,
+        bias_correction2: f32,
+        num_elements: usize,
+    );
+
+    /// Performs an RMSProp update in place.
+    ///
+    /// For each element, the following calculation is performed:
+    ///
+    /// ```text
+    /// g = gradient + weight_decay * value
+    /// square_avg = alpha * square_avg + (1 - alpha) * g^2
+    /// value -= learning_rate * g / (sqrt(square_avg) + epsilon)
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Hardware memory for the parameter value, updated in place.
+    /// * `gradient` - Hardware memory for the gradient.
+    /// * `square_avg` - Hardware memory for the moving average of squared gradients, updated in
+    ///   place.
+    /// * `learning_rate` - Learning rate.
+    /// * `alpha` - Decay rate of the moving average.
+    /// * `epsilon` - Small value to avoid zero division.
+    /// * `weight_decay` - Coefficient of the L2 penalty.
+    /// * `num_elements` - Number of elements on each memory.
+    ///
+    /// # Safety
+    ///
+    /// `value`, `gradient` and `square_avg` own enough amount of memory to store data with
+    /// `num_elements` elements of the value type, and they do not overlap each other.
+    #[allow(clippy::too_many_arguments)]
+    unsafe fn rmsprop_update_f32(
+        &mut self,
+        value: *mut u8,
+        gradient: *const u8,
+        square_avg: *mut u8,
+        learning_rate: f32,
+        alpha: f32,
+        epsilon: f32,
+        weight_decay: f32,
+        num_elements: usize,
+    );
+
+    /// Performs an Adagrad update in place.
+    ///
+    /// For each element, the following calculation is performed:
+    ///
+    /// ```text
+    /// g = gradient + weight_decay * value
+    /// square_sum += g^2
+    /// value -= learning_rate * g / (sqrt(square_sum) + epsilon)
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Hardware memory for the parameter value, updated in place.
+    /// * `gradient` - Hardware memory for the gradient.
+    /// * `square_sum` - Hardware memory for the sum of squared gradients, updated in place.
+    /// * `learning_rate` - Learning rate.
+    /// * `epsilon` - Small value to avoid zero division.
+    /// * `weight_decay` - Coefficient of the L2 penalty.
+    /// * `num_elements` - Number of elements on each memory.
+    ///
+    /// # Safety
+    ///
+    /// `value`, `gradient` and `square_sum` own enough amount of memory to store data with
+    /// `num_elements` elements of the value type, and they do not overlap each other.
+    #[allow(clippy::too_many_arguments)]
+    unsafe fn adagrad_update_f32(
+        &mut self,
+        value: *mut u8,
+        gradient: *const u8,
+        square_sum: *mut u8,
+        learning_rate: f32,
+        epsilon: f32,
+        weight_decay: f32,
+        num_elements: usize,
+    );
+
+    /// Fills uniform random numbers in [`low`, `high`).
+    ///
+    /// The i-th element must be calculated as `low + (high - low) * u` where `u` is
+    /// `random::sample_uniform(seed, offset + i)`, so that every hardware produces the same
+    /// stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `dest` - Hardware memory for the destination.
+    /// * `seed` - Seed of the stream.
+    /// * `offset` - Index of the first element in the stream.
+    /// * `low` - Lower bound of the values.
+    /// * `high` - Upper bound of the values.
+    /// * `num_elements` - Number of elements on the memory.
+    ///
+    /// # Safety
+    ///
+    /// `dest` owns enough amount of memory to store data with `num_elements` elements of the
+    /// value type.
+    unsafe fn random_uniform_f32(
+        &mut self,
+        dest: *mut u8,
+        seed: u64,
+        offset: u64,
+        low: f32,
+        high: f32,
+        num_elements: usize,
+    );
+
+    /// Fills normal random numbers.
+    ///
+    /// The i-th element must be calculated as `mean + stddev * z` where `z` is
+    /// `random::sample_normal(seed, offset + i, 0)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `dest` - Hardware memory for the destination.
+    /// * `seed` - Seed of the stream.
+    /// * `offset` - Index of the first element in the stream.
+    /// * `mean` - Mean of the distribution.
+    /// * `stddev` - Standard deviation of the distribution.
+    /// * `num_elements` - Number of elements on the memory.
+    ///
+    /// # Safety
+    ///
+    /// `dest` owns enough amount of memory to store data with `num_elements` elements of the
+    /// value type.
+    unsafe fn random_normal_f32(
+        &mut self,
+        dest: *mut u8,
+        seed: u64,
+        offset: u64,
+        mean: f32,
+        stddev: f32,
+        num_elements: usize,
+    );
+
+    /// Fills truncated normal random numbers.
+    ///
+    /// The i-th element must be calculated as `mean + stddev * z` where `z` is
+    /// `random::sample_truncated_normal(seed, offset + i, bound)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `dest` - Hardware memory for the destination.
+    /// * `seed` - Seed of the stream.
+    /// * `offset` - Index of the first element in the stream.
+    /// * `mean` - Mean of the distribution before truncation.
+    /// * `stddev` - Standard deviation of the distribution before truncation.
+    /// * `bound` - Bound of the values in the unit of `stddev`.
+    /// * `num_elements` - Number of elements on the memory.
+    ///
+    /// # Safety
+    ///
+    /// `dest` owns enough amount of memory to store data with `num_elements` elements of the
+    /// value type.
+    #[allow(clippy::too_many_arguments)]
+    unsafe fn random_truncated_normal_f32(
+        &mut self,
+        dest: *mut u8,
+        seed: u64,
+        offset: u64,
+        mean: f32,
+        stddev: f32,
+        bound: f32,
+        num_elements: usize,
+    );
+
+    /// Fills Bernoulli random numbers.
+    ///
+    /// The i-th element must be 1 if `random::sample_uniform(seed, offset + i)` is less than `p`,
+    /// or 0 otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `dest` - Hardware memory for the destination.
+    /// * `seed` - Seed of the stream.
+    /// * `offset` - Index of the first element in the stream.
+    /// * `p` - Probability to obtain 1.
+    /// * `num_elements` - Number of elements on the memory.
+    ///
+    /// # Safety
+    ///
+    /// `dest` owns enough amount of memory to store data with `num_elements` elements of the
+    /// value type.
+    unsafe fn random_bernoulli_f32(
+        &mut self,
+        dest: *mut u8,
+        seed: u64,
+        offset: u64,
+        p: f32,
+        num_elements: usize,
+    );
+
+    /// Performs elementwise square root operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - Hardware memory for the source.
+    /// * `dest` - Hardware memory for the destination.
+    /// * `num_elements` - Number of elements on each memory.
+    ///
+    /// # Safety
+    ///
+    /// `src` and `dest` own enough amount of memory to store data with `num_elements` elements of
+    /// the value type.
+    unsafe fn elementwise_sqrt_f32(&mut self, src: *const u8, dest: *mut u8, num_elements: usize);
+
+    /// Performs elementwise hyperbolic tangent operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - Hardware memory for the source.
+    /// * `dest` - Hardware memory for the destination.
+    /// * `num_elements` - Number of elements on each memory.
+    ///
+    /// # Safety
+    ///
+    /// `src` and `dest` own enough amount of memory to store data with `num_elements` elements of
+    /// the value type.
+    unsafe fn elementwise_tanh_f32(&mut self, src: *const u8, dest: *mut u8, num_elements: usize);
+
+    /// Performs elementwise logistic sigmoid: 1 / (1 + exp(-x)) operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - Hardware memory for the source.
+    /// * `dest` - Hardware memory for the destination.
+    /// * `num_elements` - Number of elements on each memory.
+    ///
+    /// # Safety
+    ///
+    /// `src` and `dest` own enough amount of memory to store data with `num_elements` elements of
+    /// the value type.
+    unsafe fn elementwise_sigmoid_f32(
+        &mut self,
+        src: *const u8,
+        dest: *mut u8,
+        num_elements: usize,
+    );
+
+    /// Performs elementwise ReLU: max(x, 0) operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - Hardware memory for the source.
+    /// * `dest` - Hardware memory for the destination.
+    /// * `num_elements` - Number of elements on each memory.
+    ///
+    /// # Safety
+    ///
+    /// `src` and `dest` own enough amount of memory to store data with `num_elements` elements of
+    /// the value type.
+    unsafe fn elementwise_relu_f32(&mut self, src: *const u8, dest: *mut u8, num_elements: usize);
+
+    /// Performs elementwise step function: 1 if x > 0, 0 otherwise operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - Hardware memory for the source.
+    /// * `dest` - Hardware memory for the destination.
+    /// * `num_elements` - Number of elements on each memory.
+    ///
+    /// # Safety
+    ///
+    /// `src` and `dest` own enough amount of memory to store data with `num_elements` elements of
+    /// the value type.
+    unsafe fn elementwise_step_f32(&mut self, src: *const u8, dest: *mut u8, num_elements: usize);
+
+    /// Performs matrix multiplication: dest = a @ b.
+    ///
+    /// All matrices are stored in the row-major order.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - Hardware memory for the left-hand side matrix with `m` rows and `k` columns.
+    /// * `b` - Hardware memory for the right-hand side matrix with `k` rows and `n` columns.
+    /// * `dest` - Hardware memory for the resulting matrix with `m` rows and `n` columns.
+    /// * `m` - Number of rows of `a` and `dest`.
+    /// * `k` - Number of columns of `a` and rows of `b`.
+    /// * `n` - Number of columns of `b` and `dest`.
+    ///
+    /// # Safety
+    ///
+    /// Each memory owns enough amount of memory to store the corresponding matrix, and `dest`
+    /// does not overlap with `a` or `b`.
+    unsafe fn matmul_f32(
+        &mut self,
+        a: *const u8,
+        b: *const u8,
+        dest: *mut u8,
+        m: usize,
+        k: usize,
+        n: usize,
+    );
+
+    /// Transposes a matrix.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - Hardware memory for the source matrix with `rows` rows and `cols` columns.
+    /// * `dest` - Hardware memory for the resulting matrix with `cols` rows and `rows` columns.
+    /// * `rows` - Number of rows of `src`.
+    /// * `cols` - Number of columns of `src`.
+    ///
+    /// # Safety
+    ///
+    /// `src` and `dest` own enough amount of memory to store `rows * cols` elements of the value
+    /// type, and they do not overlap each other.
+    unsafe fn transpose_f32(&mut self, src: *const u8, dest: *mut u8, rows: usize, cols: usize);
+
+    /// Sums up values along the middle axis: dest[i, j] = sum_k src[i, k, j].
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - Hardware memory for the source with the layout [`outer`, `size`, `inner`].
+    /// * `dest` - Hardware memory for the destination with the layout [`outer`, `inner`].
+    /// * `outer` - Product of the dimensions before the reduced axis.
+    /// * `size` - Dimension of the reduced axis.
+    /// * `inner` - Product of the dimensions after the reduced axis.
+    ///
+    /// # Safety
+    ///
+    /// `src` and `dest` own enough amount of memory to store data with the layouts above, and they
+    /// do not overlap each other.
+    unsafe fn sum_axis_f32(
+        &mut self,
+        src: *const u8,
+        dest: *mut u8,
+        outer: usize,
+        size: usize,
+        inner: usize,
+    );
+
+    /// Repeats values along a new middle axis: dest[i, k, j] = src[i, j].
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - Hardware memory for the source with the layout [`outer`, `inner`].
+    /// * `dest` - Hardware memory for the destination with the layout [`outer`, `size`, `inner`].
+    /// * `outer` - Product of the dimensions before the new axis.
+    /// * `size` - Dimension of the new axis.
+    /// * `inner` - Product of the dimensions after the new axis.
+    ///
+    /// # Safety
+    ///
+    /// `src` and `dest` own enough amount of memory to store data with the layouts above, and they
+    /// do not overlap each other.
+    unsafe fn expand_axis_f32(
+        &mut self,
+        src: *const u8,
+        dest: *mut u8,
+        outer: usize,
+        size: usize,
+        inner: usize,
+    );
+
+    /// Gathers rows: dest[i, j] = src[indices[i], j].
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - Hardware memory for the source rows.
+    /// * `dest` - Hardware memory for the destination with `indices.len()` rows.
+    /// * `indices` - Host memory of the row indices.
+    /// * `row_size` - Number of elements in each row.
+    ///
+    /// # Safety
+    ///
+    /// `src` owns enough amount of memory to store all rows referred by `indices`, `dest` owns
+    /// enough amount of memory to store `indices.len() * row_size` elements of the value type, and
+    /// they do not overlap each other.
+    unsafe fn gather_rows_f32(
+        &mut self,
+        src: *const u8,
+        dest: *mut u8,
+        indices: &[usize],
+        row_size: usize,
+    );
+
+    /// Scatters rows with accumulation: dest = 0, then dest[indices[i], j] += src[i, j].
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - Hardware memory for the source with `indices.len()` rows.
+    /// * `dest` - Hardware memory for the destination with `num_rows` rows.
+    /// * `indices` - Host memory of the row indices. Every index must be less than `num_rows`.
+    /// * `num_rows` - Number of rows in `dest`.
+    /// * `row_size` - Number of elements in each row.
+    ///
+    /// # Safety
+    ///
+    /// `src` and `dest` own enough amount of memory to store data with the layouts above, and they
+    /// do not overlap each other.
+    unsafe fn scatter_add_rows_f32(
+        &mut self,
+        src: *const u8,
+        dest: *mut u8,
+        indices: &[usize],
+        num_rows: usize,
+        row_size: usize,
+    );
+
+    /// Performs 2-dimensional convolution (cross-correlation):
+    /// y[n, o, i, j] = sum_{c, p, q} x[n, g * C + c, i * s + p * d - pad, j * s + q * d - pad] *
+    /// w[o, c, p, q], where `g` is the group of `o` and `C` is the number of channels per group.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Hardware memory for the input with the shape `geometry.input_shape()`.
+    /// * `w` - Hardware memory for the kernel with the shape `geometry.weight_shape()`.
+    /// * `y` - Hardware memory for the output with the shape `geometry.output_shape()`.
+    /// * `geometry` - Geometry of the convolution.
+    ///
+    /// # Safety
+    ///
+    /// Each memory owns enough amount of memory to store the corresponding value, and `y` does not
+    /// overlap with `x` or `w`.
+    unsafe fn conv2d_f32(
+        &mut self,
+        x: *const u8,
+        w: *const u8,
+        y: *mut u8,
+        geometry: &Conv2dGeometry,
+    );
+
+    /// Calculates the gradient of 2-dimensional convolution with respect to the input, i.e., the
+    /// transposed convolution of `gy` by `w`.
+    ///
+    /// # Arguments
+    ///
+    /// * `gy` - Hardware memory for the output gradient with the shape `geometry.output_shape()`.
+    /// * `w` - Hardware memory for the kernel with the shape `geometry.weight_shape()`.
+    /// * `gx` - Hardware memory for the input gradient with the shape `geometry.input_shape()`.
+    /// * `geometry` - Geometry of the convolution.
+    ///
+    /// # Safety
+    ///
+    /// Each memory owns enough amount of memory to store the corresponding value, and `gx` does
+    /// not overlap with `gy` or `w`.
+    unsafe fn conv2d_backward_input_f32(
+        &mut self,
+        gy: *const u8,
+        w: *const u8,
+        gx: *mut u8,
+        geometry: &Conv2dGeometry,
+    );
+
+    /// Calculates the gradient of 2-dimensional convolution with respect to the kernel.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Hardware memory for the input with the shape `geometry.input_shape()`.
+    /// * `gy` - Hardware memory for the output gradient with the shape `geometry.output_shape()`.
+    /// * `gw` - Hardware memory for the kernel gradient with the shape `geometry.weight_shape()`.
+    /// * `geometry` - Geometry of the convolution.
+    ///
+    /// # Safety
+    ///
+    /// Each memory owns enough amount of memory to store the corresponding value, and `gw` does
+    /// not overlap with `x` or `gy`.
+    unsafe fn conv2d_backward_weight_f32(
+        &mut self,
+        x: *const u8,
+        gy: *const u8,
+        gw: *mut u8,
+        geometry: &Conv2dGeometry,
+    );
+
+    /// Performs 2-dimensional max pooling. Padded values are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - Hardware memory for the input with the shape `geometry.input_shape()`.
+    /// * `dest` - Hardware memory for the output with the shape `geometry.output_shape()`.
+    /// * `geometry` - Geometry of the pooling. Every window must contain at least one input value.
+    ///
+    /// # Safety
+    ///
+    /// `src` and `dest` own enough amount of memory to store the corresponding values, and they do
+    /// not overlap each other.
+    unsafe fn max_pool2d_f32(&mut self, src: *const u8, dest: *mut u8, geometry: &Conv2dGeometry);
+
+    /// Gathers values at the positions of maxima of each max pooling window:
+    /// dest[window] = src[argmax_{window} x]. The first position is selected for ties.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Hardware memory for the pooled input with the shape `geometry.input_shape()`.
+    /// * `src` - Hardware memory for the gathered values with the shape `geometry.input_shape()`.
+    /// * `dest` - Hardware memory for the output with the shape `geometry.output_shape()`.
+    /// * `geometry` - Geometry of the pooling. Every window must contain at least one input value.
+    ///
+    /// # Safety
+    ///
+    /// Each memory owns enough amount of memory to store the corresponding value, and `dest` does
+    /// not overlap with `x` or `src`.
+    unsafe fn max_pool2d_gather_f32(
+        &mut self,
+        x: *const u8,
+        src: *const u8,
+        dest: *mut u8,
+        geometry: &Conv2dGeometry,
+    );
+
+    /// Scatters values to the positions of maxima of each max pooling window with accumulation:
+    /// dest = 0, then dest[argmax_{window} x] += src[window]. This is the adjoint of
+    /// `max_pool2d_gather_f32()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Hardware memory for the pooled input with the shape `geometry.input_shape()`.
+    /// * `src` - Hardware memory for the scattered values with the shape
+    ///   `geometry.output_shape()`.
+    /// * `dest` - Hardware memory for the output with the shape `geometry.input_shape()`.
+    /// * `geometry` - Geometry of the pooling. Every window must contain at least one input value.
+    ///
+    /// # Safety
+    ///
+    /// Each memory owns enough amount of memory to store the corresponding value, and `dest` does
+    /// not overlap with `x` or `src`.
+    unsafe fn max_pool2d_scatter_f32(
+        &mut self,
+        x: *const u8,
+        src: *const u8,
+        dest: *mut u8,
+        geometry: &Conv2dGeometry,
+    );
+
+    /// Performs 2-dimensional average pooling. Padded values are treated as zeros, i.e., every
+    /// window is divided by the kernel size.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - Hardware memory for the input with the shape `geometry.input_shape()`.
+    /// * `dest` - Hardware memory for the output with the shape `geometry.output_shape()`.
+    /// * `geometry` - Geometry of the pooling.
+    ///
+    /// # Safety
+    ///
+    /// `src` and `dest` own enough amount of memory to store the corresponding values, and they do
+    /// not overlap each other.
+    unsafe fn avg_pool2d_f32(&mut self, src: *const u8, dest: *mut u8, geometry: &Conv2dGeometry);
+
+    /// Calculates the gradient of 2-dimensional average pooling, which is also its adjoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - Hardware memory for the output gradient with the shape `geometry.output_shape()`.
+    /// * `dest` - Hardware memory for the input gradient with the shape `geometry.input_shape()`.
+    /// * `geometry` - Geometry of the pooling.
+    ///
+    /// # Safety
+    ///
+    /// `src` and `dest` own enough amount of memory to store the corresponding values, and they do
+    /// not overlap each other.
+    unsafe fn avg_pool2d_backward_f32(
+        &mut self,
+        src: *const u8,
+        dest: *mut u8,
+        geometry: &Conv2dGeometry,
+    );
+
+    /// Performs layer normalization over each row:
+    /// y[i, j] = (x[i, j] - mean_i) / sqrt(var_i + epsilon) * gamma[j] + beta[j], where mean_i
+    /// and var_i are the mean and the biased variance of the row i.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Hardware memory for the input with the layout [`rows`, `size`].
+    /// * `gamma` - Hardware memory for the scaling factors with `size` elements.
+    /// * `beta` - Hardware memory for the shifts with `size` elements.
+    /// * `y` - Hardware memory for the output with the layout [`rows`, `size`].
+    /// * `rows` - Number of rows.
+    /// * `size` - Number of elements in each row.
+    /// * `epsilon` - Small value added to the variance.
+    ///
+    /// # Safety
+    ///
+    /// Each memory owns enough amount of memory to store the corresponding value, and `y` does not
+    /// overlap with other memories.
+    #[allow(clippy::too_many_arguments)]
+    unsafe fn layer_norm_f32(
+        &mut self,
+        x: *const u8,
+        gamma: *const u8,
+        beta: *const u8,
+        y: *mut u8,
+        rows: usize,
+        size: usize,
+        epsilon: f32,
+    );
+
+    /// Calculates the gradient of layer normalization with respect to the input.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Hardware memory for the input with the layout [`rows`, `size`].
+    /// * `gamma` - Hardware memory for the scaling factors with `size` elements.
+    /// * `gy` - Hardware memory for the output gradient with the layout [`rows`, `size`].
+    /// * `gx` - Hardware memory for the input gradient with the layout [`rows`, `size`].
+    /// * `rows` - Number of rows.
+    /// * `size` - Number of elements in each row.
+    /// * `epsilon` - Small value added to the variance.
+    ///
+    /// # Safety
+    ///
+    /// Each memory owns enough amount of memory to store the corresponding value, and `gx` does
+    /// not overlap with other memories.
+    #[allow(clippy::too_many_arguments)]
+    unsafe fn layer_norm_backward_f32(
+        &mut self,
+        x: *const u8,
+        gamma: *const u8,
+        gy: *const u8,
+        gx: *mut u8,
+        rows: usize,
+        size: usize,
+        epsilon: f32,
+    );
+
+    /// Performs RMS normalization over each row:
+    /// y[i, j] = x[i, j] / sqrt(mean_j(x[i, j]^2) + epsilon) * gamma[j].
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Hardware memory for the input with the layout [`rows`, `size`].
+    /// * `gamma` - Hardware memory for the scaling factors with `size` elements.
+    /// * `y` - Hardware memory for the output with the layout [`rows`, `size`].
+    /// * `rows` - Number of rows.
+    /// * `size` - Number of elements in each row.
+    /// * `epsilon` - Small value added to the mean square.
+    ///
+    /// # Safety
+    ///
+    /// Each memory owns enough amount of memory to store the corresponding value, and `y` does not
+    /// overlap with other memories.
+    unsafe fn rms_norm_f32(
+        &mut self,
+        x: *const u8,
+        gamma: *const u8,
+        y: *mut u8,
+        rows: usize,
+        size: usize,
+        epsilon: f32,
+    );
+
+    /// Calculates the gradient of RMS normalization with respect to the input.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Hardware memory for the input with the layout [`rows`, `size`].
+    /// * `gamma` - Hardware memory for the scaling factors with `size` elements.
+    /// * `gy` - Hardware memory for the output gradient with the layout [`rows`, `size`].
+    /// * `gx` - Hardware memory for the input gradient with the layout [`rows`, `size`].
+    /// * `rows` - Number of rows.
+    /// * `size` - Number of elements in each row.
+    /// * `epsilon` - Small value added to the mean square.
+    ///
+    /// # Safety
+    ///
+    /// Each memory owns enough amount of memory to store the corresponding value, and `gx` does
+    /// not overlap with other memories.
+    #[allow(clippy::too_many_arguments)]
+    unsafe fn rms_norm_backward_f32(
+        &mut self,
+        x: *const u8,
+        gamma: *const u8,
+        gy: *const u8,
+        gx: *mut u8,
+        rows: usize,
+        size: usize,
+        epsilon: f32,
+    );
+
+    /// Performs batch normalization with the statistics of the input:
+    /// y[n, c, i] = (x[n, c, i] - mean_c) / sqrt(var_c + epsilon) * gamma[c] + beta[c], where
+    /// mean_c and var_c are the mean and the biased variance over all `n` and `i`.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Hardware memory for the input with the layout [`outer`, `channels`, `inner`].
+    /// * `gamma` - Hardware memory for the scaling factors with `channels` elements.
+    /// * `beta` - Hardware memory for the shifts with `channels` elements.
+    /// * `y` - Hardware memory for the output with the layout [`outer`, `channels`, `inner`].
+    /// * `outer` - Batch size.
+    /// * `channels` - Number of channels.
+    /// * `inner` - Number of elements of each channel in each sample.
+    /// * `epsilon` - Small value added to the variance.
+    ///
+    /// # Safety
+    ///
+    /// Each memory owns enough amount of memory to store the corresponding value, and `y` does not
+    /// overlap with other memories.
+    #[allow(clippy::too_many_arguments)]
+    unsafe fn batch_norm_f32(
+        &mut self,
+        x: *const u8,
+        gamma: *const u8,
+        beta: *const u8,
+        y: *mut u8,
+        outer: usize,
+        channels: usize,
+        inner: usize,
+        epsilon: f32,
+    );
+
+    /// Calculates the gradient of batch normalization with the statistics of the input with
+    /// respect to the input.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Hardware memory for the input with the layout [`outer`, `channels`, `inner`].
+    /// * `gamma` - Hardware memory for the scaling factors with `channels` elements.
+    /// * `gy` - Hardware memory for the output gradient with the layout [`outer`, `channels`,
+    ///   `inner`].
+    /// * `gx` - Hardware memory for the input gradient with the layout [`outer`, `channels`,
+    ///   `inner`].
+    /// * `outer` - Batch size.
+    /// * `channels` - Number of channels.
+    /// * `inner` - Number of elements of each channel in each sample.
+    /// * `epsilon` - Small value added to the variance.
+    ///
+    /// # Safety
+    ///
+    /// Each memory owns enough amount of memory to store the corresponding value, and `gx` does
+    /// not overlap with other memories.
+    #[allow(clippy::too_many_arguments)]
+    unsafe fn batch_norm_backward_f32(
+        &mut self,
+        x: *const u8,
+        gamma: *const u8,
+        gy: *const u8,
+        gx: *mut u8,
+        outer: usize,
+        channels: usize,
+        inner: usize,
+        epsilon: f32,
+    );
+
+    /// Calculates the mean and the biased variance of each channel.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Hardware memory for the input with the layout [`outer`, `channels`, `inner`].
+    /// * `mean` - Hardware memory for the means with `channels` elements.
+    /// * `variance` - Hardware memory for the variances with `channels` elements.
+    /// * `outer` - Batch size.
+    /// * `channels` - Number of channels.
+    /// * `inner` - Number of elements of each channel in each sample.
+    ///
+    /// # Safety
+    ///
+    /// Each memory owns enough amount of memory to store the corresponding value, and `mean` and
+    /// `variance` do not overlap with other memories.
+    unsafe fn batch_norm_statistics_f32(
+        &mut self,
+        x: *const u8,
+        mean: *mut u8,
+        variance: *mut u8,
+        outer: usize,
+        channels: usize,
+        inner: usize,
+    );
+
+    /// Performs batch normalization with given statistics:
+    /// y[n, c, i] = (x[n, c, i] - mean[c]) / sqrt(variance[c] + epsilon) * gamma[c] + beta[c].
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Hardware memory for the input with the layout [`outer`, `channels`, `inner`].
+    /// * `mean` - Hardware memory for the means with `channels` elements.
+    /// * `variance` - Hardware memory for the variances with `channels` elements.
+    /// * `gamma` - Hardware memory for the scaling factors with `channels` elements.
+    /// * `beta` - Hardware memory for the shifts with `channels` elements.
+    /// * `y` - Hardware memory for the output with the layout [`outer`, `channels`, `inner`].
+    /// * `outer` - Batch size.
+    /// * `channels` - Number of channels.
+    /// * `inner` - Number of elements of each channel in each sample.
+    /// * `epsilon` - Small value added to the variance.
+    ///
+    /// # Safety
+    ///
+    /// Each memory owns enough amount of memory to store the corresponding value, and `y` does not
+    /// overlap with other memories.
+    #[allow(clippy::too_many_arguments)]
+    unsafe fn batch_norm_inference_f32(
+        &mut self,
+        x: *const u8,
+        mean: *const u8,
+        variance: *const u8,
+        gamma: *const u8,
+        beta: *const u8,
+        y: *mut u8,
+        outer: usize,
+        channels: usize,
+        inner: usize,
+        epsilon: f32,
+    );
+
+    /// Performs elementwise equal operation: dest = 1 if lhs == rhs, 0 otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `lhs` - Hardware memory for left-hand side argument.
+    /// * `rhs` - Hardware memory for right-hand side argument.
+    /// * `dest` - Hardware memory for destination.
+    /// * `num_elements` - Number of elements on each memory.
+    ///
+    /// # Safety
+    ///
+    /// `lhs`, `rhs`, and `dest` own enough amount of memory to store data with `num_elements`
+    /// elements of the value type.
+    unsafe fn elementwise_eq_f32(
+        &mut self,
+        lhs: *const u8,
+        rhs: *const u8,
+        dest: *mut u8,
+        num_elements: usize,
+    );
+
+    /// Performs elementwise less-than operation: dest = 1 if lhs < rhs, 0 otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `lhs` - Hardware memory for left-hand side argument.
+    /// * `rhs` - Hardware memory for right-hand side argument.
+    /// * `dest` - Hardware memory for destination.
+    /// * `num_elements` - Number of elements on each memory.
+    ///
+    /// # Safety
+    ///
+    /// `lhs`, `rhs`, and `dest` own enough amount of memory to store data with `num_elements`
+    /// elements of the value type.
+    unsafe fn elementwise_lt_f32(
+        &mut self,
+        lhs: *const u8,
+        rhs: *const u8,
+        dest: *mut u8,
+        num_elements: usize,
+    );
+
+    /// Performs elementwise less-than-or-equal operation: dest = 1 if lhs <= rhs, 0 otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `lhs` - Hardware memory for left-hand side argument.
+    /// * `rhs` - Hardware memory for right-hand side argument.
+    /// * `dest` - Hardware memory for destination.
+    /// * `num_elements` - Number of elements on each memory.
+    ///
+    /// # Safety
+    ///
+    /// `lhs`, `rhs`, and `dest` own enough amount of memory to store data with `num_elements`
+    /// elements of the value type.
+    unsafe fn elementwise_le_f32(
+        &mut self,
+        lhs: *const u8,
+        rhs: *const u8,
+        dest: *mut u8,
+        num_elements: usize,
+    );
+
+    /// Performs elementwise minimum operation: dest = min(lhs, rhs). The result is NaN if either argument is NaN.
+    ///
+    /// # Arguments
+    ///
+    /// * `lhs` - Hardware memory for left-hand side argument.
+    /// * `rhs` - Hardware memory for right-hand side argument.
+    /// * `dest` - Hardware memory for destination.
+    /// * `num_elements` - Number of elements on each memory.
+    ///
+    /// # Safety
+    ///
+    /// `lhs`, `rhs`, and `dest` own enough amount of memory to store data with `num_elements`
+    /// elements of the value type.
+    unsafe fn elementwise_minimum_f32(
+        &mut self,
+        lhs: *const u8,
+        rhs: *const u8,
+        dest: *mut u8,
+        num_elements: usize,
+    );
+
+    /// Performs elementwise maximum operation: dest = max(lhs, rhs). The result is NaN if either argument is NaN.
+    ///
+    /// # Arguments
+    ///
+    /// * `lhs` - Hardware memory for left-hand side argument.
+    /// * `rhs` - Hardware memory for right-hand side argument.
+    /// * `dest` - Hardware memory for destination.
+    /// * `num_elements` - Number of elements on each memory.
+    ///
+    /// # Safety
+    ///
+    /// `lhs`, `rhs`, and `dest` own enough amount of memory to store data with `num_elements`
+    /// elements of the value type.
+    unsafe fn elementwise_maximum_f32(
+        &mut self,
+        lhs: *const u8,
+        rhs: *const u8,
+        dest: *mut u8,
+        num_elements: usize,
+    );
+
+    /// Performs elementwise logical AND operation: dest = 1 if both lhs and rhs are nonzero, 0 otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `lhs` - Hardware memory for left-hand side argument.
+    /// * `rhs` - Hardware memory for right-hand side argument.
+    /// * `dest` - Hardware memory for destination.
+    /// * `num_elements` - Number of elements on each memory.
+    ///
+    /// # Safety
+    ///
+    /// `lhs`, `rhs`, and `dest` own enough amount of memory to store data with `num_elements`
+    /// elements of the value type.
+    unsafe fn elementwise_logical_and_f32(
+        &mut self,
+        lhs: *const u8,
+        rhs: *const u8,
+        dest: *mut u8,
+        num_elements: usize,
+    );
+
+    /// Performs elementwise logical OR operation: dest = 1 if either lhs or rhs is nonzero, 0 otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `lhs` - Hardware memory for left-hand side argument.
+    /// * `rhs` - Hardware memory for right-hand side argument.
+    /// * `dest` - Hardware memory for destination.
+    /// * `num_elements` - Number of elements on each memory.
+    ///
+    /// # Safety
+    ///
+    /// `lhs`, `rhs`, and `dest` own enough amount of memory to store data with `num_elements`
+    /// elements of the value type.
+    unsafe fn elementwise_logical_or_f32(
+        &mut self,
+        lhs: *const u8,
+        rhs: *const u8,
+        dest: *mut u8,
+        num_elements: usize,
+    );
+
+    /// Performs elementwise logical NOT operation: dest = 1 if src is 0, 0 otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - Hardware memory for the source.
+    /// * `dest` - Hardware memory for the destination.
+    /// * `num_elements` - Number of elements on each memory.
+    ///
+    /// # Safety
+    ///
+    /// `src` and `dest` own enough amount of memory to store data with `num_elements` elements
+    /// of the value type.
+    unsafe fn elementwise_logical_not_f32(
+        &mut self,
+        src: *const u8,
+        dest: *mut u8,
+        num_elements: usize,
+    );
+
+    /// Performs elementwise NaN check operation: dest = 1 if src is NaN, 0 otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - Hardware memory for the source.
+    /// * `dest` - Hardware memory for the destination.
+    /// * `num_elements` - Number of elements on each memory.
+    ///
+    /// # Safety
+    ///
+    /// `src` and `dest` own enough amount of memory to store data with `num_elements` elements
+    /// of the value type.
+    unsafe fn elementwise_isnan_f32(&mut self, src: *const u8, dest: *mut u8, num_elements: usize);
+
+    /// Performs elementwise infinity check operation: dest = 1 if src is positive or negative infinity, 0 otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - Hardware memory for the source.
+    /// * `dest` - Hardware memory for the destination.
+    /// * `num_elements` - Number of elements on each memory.
+    ///
+    /// # Safety
+    ///
+    /// `src` and `dest` own enough amount of memory to store data with `num_elements` elements
+    /// of the value type.
+    unsafe fn elementwise_isinf_f32(&mut self, src: *const u8, dest: *mut u8, num_elements: usize);
+
+    /// Performs elementwise selection: dest = lhs if cond is nonzero, rhs otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `cond` - Hardware memory for the condition.
+    /// * `lhs` - Hardware memory for the values selected by nonzero conditions.
+    /// * `rhs` - Hardware memory for the values selected by zero conditions.
+    /// * `dest` - Hardware memory for destination.
+    /// * `num_elements` - Number of elements on each memory.
+    ///
+    /// # Safety
+    ///
+    /// `cond`, `lhs`, `rhs`, and `dest` own enough amount of memory to store data with
+    /// `num_elements` elements of the value type.
+    unsafe fn elementwise_where_f32(
+        &mut self,
+        cond: *const u8,
+        lhs: *const u8,
+        rhs: *const u8,
+        dest: *mut u8,
+        num_elements: usize,
+    );
 }
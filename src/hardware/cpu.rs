@@ -1,7 +1,11 @@
 use std::alloc;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
 
+use crate::conv::Conv2dGeometry;
+use crate::hardware::cpu::mapped::MappedFile;
 use crate::hardware::Hardware;
+use crate::random;
 
 /// Default memory alignment for allocating buffers.
 const DEFAULT_MEMORY_ALIGNMENT: usize = 8;
@@ -13,6 +17,10 @@ const DEFAULT_MEMORY_ALIGNMENT: usize = 8;
 pub struct CpuHardware {
     /// Registry of supplied pointer and associated memory size.
     supplied: HashSet<(usize, usize)>,
+
+    /// Registry of supplied pointers on mapped files, associated with the memory size and the
+    /// file. The file is released when all memories on it are deallocated.
+    mapped: HashMap<usize, (usize, Rc<MappedFile>)>,
 }
 
 impl CpuHardware {
@@ -24,7 +32,54 @@ impl CpuHardware {
     pub fn new() -> Self {
         Self {
             supplied: HashSet::new(),
+            mapped: HashMap::new(),
+        }
+    }
+
+    /// Supplies a memory on a mapped file without copying the data.
+    ///
+    /// The memory is released by `deallocate_memory()` in the same manner as other memories.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - `MappedFile` holding the data.
+    /// * `offset` - Offset in bytes of the memory in the file.
+    /// * `size` - Size in bytes of the memory.
+    ///
+    /// # Returns
+    ///
+    /// Handle of the memory.
+    ///
+    /// # Panics
+    ///
+    /// The range is out of the file, empty, or already supplied.
+    ///
+    /// # Safety
+    ///
+    /// The memory is used as values of the element type directly. `offset` must be aligned to
+    /// the element type.
+    pub(crate) unsafe fn supply_mapped_memory(
+        &mut self,
+        file: &Rc<MappedFile>,
+        offset: usize,
+        size: usize,
+    ) -> *mut u8 {
+        assert!(size > 0 && offset + size <= file.as_slice().len());
+        let handle = file.as_mut_ptr().add(offset);
+        if self
+            .mapped
+            .insert(handle as usize, (size, file.clone()))
+            .is_some()
+        {
+            panic!("Handle {:016p} is supplied twice.", handle);
         }
+        handle
+    }
+
+    /// Returns the number of supplied memories on mapped files.
+    #[cfg(test)]
+    pub(crate) fn num_mapped_memories(&self) -> usize {
+        self.mapped.len()
     }
 }
 
@@ -36,9 +91,10 @@ impl Default for CpuHardware {
 
 impl Drop for CpuHardware {
     fn drop(&mut self) {
-        if !self.supplied.is_empty() {
+        if !self.supplied.is_empty() || !self.mapped.is_empty() {
             // Leak detected. Removes all pointers anyway.
-            let num_leaked = self.supplied.len();
+            let num_leaked = self.supplied.len() + self.mapped.len();
+            self.mapped.clear();
 
             while !self.supplied.is_empty() {
                 unsafe {
@@ -74,6 +130,15 @@ unsafe impl Hardware for CpuHardware {
     }
 
     unsafe fn deallocate_memory(&mut self, handle: *mut u8, size: usize) {
+        // Memories on mapped files are released with the file.
+        if let Some(&(mapped_size, _)) = self.mapped.get(&(handle as usize)) {
+            if mapped_size != size {
+                panic!("Handle {:016p} was supplied with a different size.", handle);
+            }
+            self.mapped.remove(&(handle as usize));
+            return;
+        }
+
         // Removes only memory with nonzero length.
         if size > 0 && !self.supplied.remove(&((handle as usize), size)) {
             panic!("Handle {:016p} was not supplied.", handle);
@@ -112,6 +177,24 @@ unsafe impl Hardware for CpuHardware {
         }
     }
 
+    unsafe fn elementwise_clamp_f32(
+        &mut self,
+        src: *const u8,
+        dest: *mut u8,
+        min: f32,
+        max: f32,
+        num_elements: usize,
+    ) {
+        let src = src as *const f32;
+        let dest = dest as *mut f32;
+        for i in 0..num_elements {
+            // `f32::max()` and `f32::min()` discard NaN, which must be propagated instead.
+            // `f32::clamp()` is not used since it panics if `min > max`.
+            let x = *src.add(i);
+            *dest.add(i) = if x.is_nan() { x } else { x.max(min).min(max) };
+        }
+    }
+
     unsafe fn elementwise_add_f32(
         &mut self,
         lhs: *const u8,
@@ -169,195 +252,2258 @@ unsafe impl Hardware for CpuHardware {
             *dest.add(i) = *lhs.add(i) / *rhs.add(i);
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::buffer::Buffer;
-    use crate::hardware::cpu::CpuHardware;
-    use crate::hardware::Hardware;
-    use std::cell::RefCell;
-    use std::mem::size_of;
+    unsafe fn add_assign_f32(&mut self, dest: *mut u8, src: *const u8, num_elements: usize) {
+        let dest = dest as *mut f32;
+        let src = src as *const f32;
+        for i in 0..num_elements {
+            *dest.add(i) += *src.add(i);
+        }
+    }
 
-    #[test]
-    fn test_allocate_memory() {
-        let hw = RefCell::new(CpuHardware::new());
+    unsafe fn mul_assign_f32(&mut self, dest: *mut u8, src: *const u8, num_elements: usize) {
+        let dest = dest as *mut f32;
+        let src = src as *const f32;
+        for i in 0..num_elements {
+            *dest.add(i) *= *src.add(i);
+        }
+    }
 
-        unsafe {
-            let mut buf = Buffer::raw(&hw, 4);
+    unsafe fn axpy_f32(&mut self, dest: *mut u8, alpha: f32, src: *const u8, num_elements: usize) {
+        let dest = dest as *mut f32;
+        let src = src as *const f32;
+        for i in 0..num_elements {
+            *dest.add(i) += alpha * *src.add(i);
+        }
+    }
 
-            // Owned memory in `buf` should be a usual host memory.
-            *(buf.as_mut_handle() as *mut [u8; 4]) = [0x01, 0x02, 0x03, 0x04];
-            *buf.as_mut_handle().add(0) += 0x40;
-            *buf.as_mut_handle().add(1) += 0x30;
-            *buf.as_mut_handle().add(2) += 0x20;
-            *buf.as_mut_handle().add(3) += 0x10;
-            assert_eq!(
-                *(buf.as_handle() as *const [u8; 4]),
-                [0x41, 0x32, 0x23, 0x14]
-            );
+    unsafe fn sgd_update_f32(
+        &mut self,
+        value: *mut u8,
+        gradient: *const u8,
+        velocity: *mut u8,
+        learning_rate: f32,
+        momentum: f32,
+        weight_decay: f32,
+        nesterov: bool,
+        num_elements: usize,
+    ) {
+        let value = value as *mut f32;
+        let gradient = gradient as *const f32;
+        let velocity = velocity as *mut f32;
+        for i in 0..num_elements {
+            let x = *value.add(i);
+            let g = *gradient.add(i) + weight_decay * x;
+            let vel = momentum * *velocity.add(i) + g;
+            *velocity.add(i) = vel;
+            let step = if nesterov { g + momentum * vel } else { vel };
+            *value.add(i) = x - learning_rate * step;
         }
     }
 
-    #[test]
-    #[should_panic(expected = "Detected memory leak: 1 memory blocks have not been released.")]
-    fn test_memory_leak() {
-        let mut hw = CpuHardware::new();
-        unsafe {
-            hw.allocate_memory(1);
+    unsafe fn adam_update_f32(
+        &mut self,
+        value: *mut u8,
+        gradient: *const u8,
+        m: *mut u8,
+        v: *mut u8,
+        learning_rate: f32,
+        beta1: f32,
+        beta2: f32,
+        epsilon: f32,
+        weight_decay: f32,
+        decoupled: bool,
+        bias_correction1: f32,
+        bias_correction2: f32,
+        num_elements: usize,
+    ) {
+        let value = value as *mut f32;
+        let gradient = gradient as *const f32;
+        let m = m as *mut f32;
+        let v = v as *mut f32;
+        for i in 0..num_elements {
+            let x = *value.add(i);
+            let g = if decoupled {
+                *gradient.add(i)
+            } else {
+                *gradient.add(i) + weight_decay * x
+            };
+            let mi = beta1 * *m.add(i) + (1. - beta1) * g;
+            let vi = beta2 * *v.add(i) + (1. - beta2) * g * g;
+            *m.add(i) = mi;
+            *v.add(i) = vi;
+            let mut step = (mi / bias_correction1) / ((vi / bias_correction2).sqrt() + epsilon);
+            if decoupled {
+                step += weight_decay * x;
+            }
+            *value.add(i) = x - learning_rate * step;
         }
     }
 
-    #[test]
-    fn test_zero_memory_leak() {
-        let mut hw = CpuHardware::new();
-        unsafe {
-            // The hardware don't care about zero-length memories.
-            hw.allocate_memory(0);
-            hw.allocate_memory(0);
-            hw.allocate_memory(0);
+    unsafe fn rmsprop_update_f32(
+        &mut self,
+        value: *mut u8,
+        gradient: *const u8,
+        square_avg: *mut u8,
+        learning_rate: f32,
+        alpha: f32,
+        epsilon: f32,
+        weight_decay: f32,
+        num_elements: usize,
+    ) {
+        let value = value as *mut f32;
+        let gradient = gradient as *const f32;
+        let square_avg = square_avg as *mut f32;
+        for i in 0..num_elements {
+            let x = *value.add(i);
+            let g = *gradient.add(i) + weight_decay * x;
+            let avg = alpha * *square_avg.add(i) + (1. - alpha) * g * g;
+            *square_avg.add(i) = avg;
+            *value.add(i) = x - learning_rate * g / (avg.sqrt() + epsilon);
         }
     }
 
-    #[test]
-    #[should_panic]
-    fn test_deallocate_memory_twice() {
-        let mut hw = CpuHardware::new();
-        unsafe {
-            let ptr = hw.allocate_memory(1);
-            hw.deallocate_memory(ptr, 1);
-            hw.deallocate_memory(ptr, 1);
+    unsafe fn adagrad_update_f32(
+        &mut self,
+        value: *mut u8,
+        gradient: *const u8,
+        square_sum: *mut u8,
+        learning_rate: f32,
+        epsilon: f32,
+        weight_decay: f32,
+        num_elements: usize,
+    ) {
+        let value = value as *mut f32;
+        let gradient = gradient as *const f32;
+        let square_sum = square_sum as *mut f32;
+        for i in 0..num_elements {
+            let x = *value.add(i);
+            let g = *gradient.add(i) + weight_decay * x;
+            let sum = *square_sum.add(i) + g * g;
+            *square_sum.add(i) = sum;
+            *value.add(i) = x - learning_rate * g / (sum.sqrt() + epsilon);
+        }
+    }
+    unsafe fn random_uniform_f32(
+        &mut self,
+        dest: *mut u8,
+        seed: u64,
+        offset: u64,
+        low: f32,
+        high: f32,
+        num_elements: usize,
+    ) {
+        let dest = dest as *mut f32;
+        for i in 0..num_elements {
+            let u = random::sample_uniform(seed, offset.wrapping_add(i as u64));
+            *dest.add(i) = low + (high - low) * u;
         }
     }
 
-    #[test]
-    fn test_copy_host_to_hardware() {
-        let hw = RefCell::new(CpuHardware::new());
-        unsafe {
-            let mut dest = Buffer::raw(&hw, 4);
-            let src: Vec<u8> = vec![1, 2, 3, 4];
-            hw.borrow_mut()
-                .copy_host_to_hardware(src.as_ptr(), dest.as_mut_handle(), 4);
-            assert_eq!(*(dest.as_handle() as *const [u8; 4]), [1, 2, 3, 4]);
+    unsafe fn random_normal_f32(
+        &mut self,
+        dest: *mut u8,
+        seed: u64,
+        offset: u64,
+        mean: f32,
+        stddev: f32,
+        num_elements: usize,
+    ) {
+        let dest = dest as *mut f32;
+        for i in 0..num_elements {
+            let z = random::sample_normal(seed, offset.wrapping_add(i as u64), 0);
+            *dest.add(i) = mean + stddev * z;
         }
     }
 
-    #[test]
-    fn test_copy_hardware_to_host() {
-        let hw = RefCell::new(CpuHardware::new());
-        unsafe {
-            let src = Buffer::raw(&hw, 4);
-            let mut dest: Vec<u8> = vec![0; 4];
-            *(src.as_handle() as *mut [u8; 4]) = [1, 2, 3, 4];
-            hw.borrow_mut()
-                .copy_hardware_to_host(src.as_handle(), dest.as_mut_ptr(), 4);
-            assert_eq!(dest, vec![1, 2, 3, 4]);
+    unsafe fn random_truncated_normal_f32(
+        &mut self,
+        dest: *mut u8,
+        seed: u64,
+        offset: u64,
+        mean: f32,
+        stddev: f32,
+        bound: f32,
+        num_elements: usize,
+    ) {
+        let dest = dest as *mut f32;
+        for i in 0..num_elements {
+            let z = random::sample_truncated_normal(seed, offset.wrapping_add(i as u64), bound);
+            *dest.add(i) = mean + stddev * z;
         }
     }
 
-    #[test]
-    fn test_copy_hardware_to_hardware() {
-        let hw = RefCell::new(CpuHardware::new());
-        unsafe {
-            let src = Buffer::raw(&hw, 4);
-            let mut dest = Buffer::raw(&hw, 4);
-            *(src.as_handle() as *mut [u8; 4]) = [1, 2, 3, 4];
-            hw.borrow_mut()
-                .copy_hardware_to_host(src.as_handle(), dest.as_mut_handle(), 4);
-            assert_eq!(*(dest.as_handle() as *const [u8; 4]), [1, 2, 3, 4]);
+    unsafe fn random_bernoulli_f32(
+        &mut self,
+        dest: *mut u8,
+        seed: u64,
+        offset: u64,
+        p: f32,
+        num_elements: usize,
+    ) {
+        let dest = dest as *mut f32;
+        for i in 0..num_elements {
+            let u = random::sample_uniform(seed, offset.wrapping_add(i as u64));
+            *dest.add(i) = if u < p { 1. } else { 0. };
+        }
+    }
+    unsafe fn elementwise_sqrt_f32(&mut self, src: *const u8, dest: *mut u8, num_elements: usize) {
+        let src = src as *const f32;
+        let dest = dest as *mut f32;
+        for i in 0..num_elements {
+            let x = *src.add(i);
+            *dest.add(i) = x.sqrt();
         }
     }
 
-    #[test]
-    fn test_fill_f32() {
-        let hw = RefCell::new(CpuHardware::new());
-        unsafe {
-            let mut dest = Buffer::raw(&hw, 4 * size_of::<f32>());
-            hw.borrow_mut().fill_f32(dest.as_mut_handle(), 42., 4);
-            assert_eq!(*(dest.as_handle() as *const [f32; 4]), [42.; 4]);
+    unsafe fn elementwise_tanh_f32(&mut self, src: *const u8, dest: *mut u8, num_elements: usize) {
+        let src = src as *const f32;
+        let dest = dest as *mut f32;
+        for i in 0..num_elements {
+            let x = *src.add(i);
+            *dest.add(i) = x.tanh();
         }
     }
 
-    #[test]
-    fn test_elementwise_add_f32() {
-        let hw = RefCell::new(CpuHardware::new());
-        let size = 4 * size_of::<f32>();
-        unsafe {
-            let mut lhs = Buffer::raw(&hw, size);
-            let mut rhs = Buffer::raw(&hw, size);
-            let mut dest = Buffer::raw(&hw, size);
-            *(lhs.as_mut_handle() as *mut [f32; 4]) = [1., 2., 3., 4.];
-            *(rhs.as_mut_handle() as *mut [f32; 4]) = [5., 6., 7., 8.];
-            hw.borrow_mut().elementwise_add_f32(
-                lhs.as_handle(),
-                rhs.as_handle(),
-                dest.as_mut_handle(),
-                4,
-            );
-            assert_eq!(
-                *(dest.as_mut_handle() as *const [f32; 4]),
-                [6., 8., 10., 12.]
-            );
+    unsafe fn elementwise_sigmoid_f32(
+        &mut self,
+        src: *const u8,
+        dest: *mut u8,
+        num_elements: usize,
+    ) {
+        let src = src as *const f32;
+        let dest = dest as *mut f32;
+        for i in 0..num_elements {
+            let x = *src.add(i);
+            *dest.add(i) = 1. / (1. + (-x).exp());
         }
     }
 
-    #[test]
-    fn test_elementwise_sub_f32() {
-        let hw = RefCell::new(CpuHardware::new());
-        let size = 4 * size_of::<f32>();
-        unsafe {
-            let mut lhs = Buffer::raw(&hw, size);
-            let mut rhs = Buffer::raw(&hw, size);
-            let mut dest = Buffer::raw(&hw, size);
-            *(lhs.as_mut_handle() as *mut [f32; 4]) = [9., 8., 7., 6.];
-            *(rhs.as_mut_handle() as *mut [f32; 4]) = [1., 2., 3., 4.];
-            hw.borrow_mut().elementwise_sub_f32(
-                lhs.as_handle(),
-                rhs.as_handle(),
-                dest.as_mut_handle(),
-                4,
-            );
-            assert_eq!(*(dest.as_handle() as *const [f32; 4]), [8., 6., 4., 2.]);
+    unsafe fn elementwise_relu_f32(&mut self, src: *const u8, dest: *mut u8, num_elements: usize) {
+        let src = src as *const f32;
+        let dest = dest as *mut f32;
+        for i in 0..num_elements {
+            let x = *src.add(i);
+            *dest.add(i) = x.max(0.);
         }
     }
 
-    #[test]
-    fn test_elementwise_mul_f32() {
-        let hw = RefCell::new(CpuHardware::new());
-        let size = 4 * size_of::<f32>();
-        unsafe {
-            let mut lhs = Buffer::raw(&hw, size);
-            let mut rhs = Buffer::raw(&hw, size);
-            let mut dest = Buffer::raw(&hw, size);
-            *(lhs.as_mut_handle() as *mut [f32; 4]) = [1., 2., 3., 4.];
-            *(rhs.as_mut_handle() as *mut [f32; 4]) = [5., 6., 7., 8.];
-            hw.borrow_mut().elementwise_mul_f32(
-                lhs.as_handle(),
-                rhs.as_handle(),
-                dest.as_mut_handle(),
-                4,
-            );
-            assert_eq!(*(dest.as_handle() as *const [f32; 4]), [5., 12., 21., 32.]);
+    unsafe fn elementwise_step_f32(&mut self, src: *const u8, dest: *mut u8, num_elements: usize) {
+        let src = src as *const f32;
+        let dest = dest as *mut f32;
+        for i in 0..num_elements {
+            let x = *src.add(i);
+            *dest.add(i) = if x > 0. { 1. } else { 0. };
         }
     }
 
-    #[test]
-    fn test_elementwise_div_f32() {
-        let hw = RefCell::new(CpuHardware::new());
-        let size = 4 * size_of::<f32>();
-        unsafe {
-            let mut lhs = Buffer::raw(&hw, size);
-            let mut rhs = Buffer::raw(&hw, size);
-            let mut dest = Buffer::raw(&hw, size);
-            *(lhs.as_mut_handle() as *mut [f32; 4]) = [1., 2., 3., 4.];
-            *(rhs.as_mut_handle() as *mut [f32; 4]) = [4., 2., 1., 0.5];
+    unsafe fn matmul_f32(
+        &mut self,
+        a: *const u8,
+        b: *const u8,
+        dest: *mut u8,
+        m: usize,
+        k: usize,
+        n: usize,
+    ) {
+        let a = a as *const f32;
+        let b = b as *const f32;
+        let dest = dest as *mut f32;
+        for i in 0..m {
+            for j in 0..n {
+                *dest.add(i * n + j) = 0.;
+            }
+            // i-k-j order to access b and dest contiguously.
+            for p in 0..k {
+                let x = *a.add(i * k + p);
+                for j in 0..n {
+                    *dest.add(i * n + j) += x * *b.add(p * n + j);
+                }
+            }
+        }
+    }
+
+    unsafe fn transpose_f32(&mut self, src: *const u8, dest: *mut u8, rows: usize, cols: usize) {
+        let src = src as *const f32;
+        let dest = dest as *mut f32;
+        for i in 0..rows {
+            for j in 0..cols {
+                *dest.add(j * rows + i) = *src.add(i * cols + j);
+            }
+        }
+    }
+
+    unsafe fn sum_axis_f32(
+        &mut self,
+        src: *const u8,
+        dest: *mut u8,
+        outer: usize,
+        size: usize,
+        inner: usize,
+    ) {
+        let src = src as *const f32;
+        let dest = dest as *mut f32;
+        for i in 0..outer {
+            for j in 0..inner {
+                *dest.add(i * inner + j) = 0.;
+            }
+            for k in 0..size {
+                for j in 0..inner {
+                    *dest.add(i * inner + j) += *src.add((i * size + k) * inner + j);
+                }
+            }
+        }
+    }
+
+    unsafe fn expand_axis_f32(
+        &mut self,
+        src: *const u8,
+        dest: *mut u8,
+        outer: usize,
+        size: usize,
+        inner: usize,
+    ) {
+        let src = src as *const f32;
+        let dest = dest as *mut f32;
+        for i in 0..outer {
+            for k in 0..size {
+                for j in 0..inner {
+                    *dest.add((i * size + k) * inner + j) = *src.add(i * inner + j);
+                }
+            }
+        }
+    }
+
+    unsafe fn gather_rows_f32(
+        &mut self,
+        src: *const u8,
+        dest: *mut u8,
+        indices: &[usize],
+        row_size: usize,
+    ) {
+        let src = src as *const f32;
+        let dest = dest as *mut f32;
+        for (i, &index) in indices.iter().enumerate() {
+            for j in 0..row_size {
+                *dest.add(i * row_size + j) = *src.add(index * row_size + j);
+            }
+        }
+    }
+
+    unsafe fn scatter_add_rows_f32(
+        &mut self,
+        src: *const u8,
+        dest: *mut u8,
+        indices: &[usize],
+        num_rows: usize,
+        row_size: usize,
+    ) {
+        let src = src as *const f32;
+        let dest = dest as *mut f32;
+        for i in 0..num_rows * row_size {
+            *dest.add(i) = 0.;
+        }
+        for (i, &index) in indices.iter().enumerate() {
+            for j in 0..row_size {
+                *dest.add(index * row_size + j) += *src.add(i * row_size + j);
+            }
+        }
+    }
+
+    unsafe fn conv2d_f32(
+        &mut self,
+        x: *const u8,
+        w: *const u8,
+        y: *mut u8,
+        geometry: &Conv2dGeometry,
+    ) {
+        let x = x as *const f32;
+        let w = w as *const f32;
+        let y = y as *mut f32;
+        for k in 0..geometry.output_shape().num_elements() {
+            *y.add(k) = 0.;
+        }
+        for_each_conv2d_tap(geometry, |xi, wi, yi| {
+            *y.add(yi) += *x.add(xi) * *w.add(wi);
+        });
+    }
+
+    unsafe fn conv2d_backward_input_f32(
+        &mut self,
+        gy: *const u8,
+        w: *const u8,
+        gx: *mut u8,
+        geometry: &Conv2dGeometry,
+    ) {
+        let gy = gy as *const f32;
+        let w = w as *const f32;
+        let gx = gx as *mut f32;
+        for k in 0..geometry.input_shape().num_elements() {
+            *gx.add(k) = 0.;
+        }
+        for_each_conv2d_tap(geometry, |xi, wi, yi| {
+            *gx.add(xi) += *gy.add(yi) * *w.add(wi);
+        });
+    }
+
+    unsafe fn conv2d_backward_weight_f32(
+        &mut self,
+        x: *const u8,
+        gy: *const u8,
+        gw: *mut u8,
+        geometry: &Conv2dGeometry,
+    ) {
+        let x = x as *const f32;
+        let gy = gy as *const f32;
+        let gw = gw as *mut f32;
+        for k in 0..geometry.weight_shape().num_elements() {
+            *gw.add(k) = 0.;
+        }
+        for_each_conv2d_tap(geometry, |xi, wi, yi| {
+            *gw.add(wi) += *x.add(xi) * *gy.add(yi);
+        });
+    }
+
+    unsafe fn max_pool2d_f32(&mut self, src: *const u8, dest: *mut u8, geometry: &Conv2dGeometry) {
+        let src = src as *const f32;
+        let dest = dest as *mut f32;
+        for_each_pool2d_window(geometry, |yi, window| {
+            *dest.add(yi) = *src.add(argmax(src, window));
+        });
+    }
+
+    unsafe fn max_pool2d_gather_f32(
+        &mut self,
+        x: *const u8,
+        src: *const u8,
+        dest: *mut u8,
+        geometry: &Conv2dGeometry,
+    ) {
+        let x = x as *const f32;
+        let src = src as *const f32;
+        let dest = dest as *mut f32;
+        for_each_pool2d_window(geometry, |yi, window| {
+            *dest.add(yi) = *src.add(argmax(x, window));
+        });
+    }
+
+    unsafe fn max_pool2d_scatter_f32(
+        &mut self,
+        x: *const u8,
+        src: *const u8,
+        dest: *mut u8,
+        geometry: &Conv2dGeometry,
+    ) {
+        let x = x as *const f32;
+        let src = src as *const f32;
+        let dest = dest as *mut f32;
+        for k in 0..geometry.input_shape().num_elements() {
+            *dest.add(k) = 0.;
+        }
+        for_each_pool2d_window(geometry, |yi, window| {
+            *dest.add(argmax(x, window)) += *src.add(yi);
+        });
+    }
+
+    unsafe fn avg_pool2d_f32(&mut self, src: *const u8, dest: *mut u8, geometry: &Conv2dGeometry) {
+        let src = src as *const f32;
+        let dest = dest as *mut f32;
+        let scale = 1. / (geometry.kernel_size[0] * geometry.kernel_size[1]) as f32;
+        for_each_pool2d_window(geometry, |yi, window| {
+            *dest.add(yi) = window.iter().map(|&xi| *src.add(xi)).sum::<f32>() * scale;
+        });
+    }
+
+    unsafe fn avg_pool2d_backward_f32(
+        &mut self,
+        src: *const u8,
+        dest: *mut u8,
+        geometry: &Conv2dGeometry,
+    ) {
+        let src = src as *const f32;
+        let dest = dest as *mut f32;
+        let scale = 1. / (geometry.kernel_size[0] * geometry.kernel_size[1]) as f32;
+        for k in 0..geometry.input_shape().num_elements() {
+            *dest.add(k) = 0.;
+        }
+        for_each_pool2d_window(geometry, |yi, window| {
+            let value = *src.add(yi) * scale;
+            for &xi in window {
+                *dest.add(xi) += value;
+            }
+        });
+    }
+
+    unsafe fn layer_norm_f32(
+        &mut self,
+        x: *const u8,
+        gamma: *const u8,
+        beta: *const u8,
+        y: *mut u8,
+        rows: usize,
+        size: usize,
+        epsilon: f32,
+    ) {
+        let x = x as *const f32;
+        let gamma = gamma as *const f32;
+        let beta = beta as *const f32;
+        let y = y as *mut f32;
+        for row in 0..rows {
+            let index = |j| row * size + j;
+            let (mean, inv_std) = standardization(x, size, index, epsilon);
+            for j in 0..size {
+                let xhat = ((*x.add(index(j)) as f64 - mean) * inv_std) as f32;
+                *y.add(index(j)) = xhat * *gamma.add(j) + *beta.add(j);
+            }
+        }
+    }
+
+    unsafe fn layer_norm_backward_f32(
+        &mut self,
+        x: *const u8,
+        gamma: *const u8,
+        gy: *const u8,
+        gx: *mut u8,
+        rows: usize,
+        size: usize,
+        epsilon: f32,
+    ) {
+        let x = x as *const f32;
+        let gamma = gamma as *const f32;
+        let gy = gy as *const f32;
+        let gx = gx as *mut f32;
+        for row in 0..rows {
+            let index = |j| row * size + j;
+            let (mean, inv_std) = standardization(x, size, index, epsilon);
+            standardization_backward(x, gy, gx, size, index, |j| *gamma.add(j), mean, inv_std);
+        }
+    }
+
+    unsafe fn rms_norm_f32(
+        &mut self,
+        x: *const u8,
+        gamma: *const u8,
+        y: *mut u8,
+        rows: usize,
+        size: usize,
+        epsilon: f32,
+    ) {
+        let x = x as *const f32;
+        let gamma = gamma as *const f32;
+        let y = y as *mut f32;
+        for row in 0..rows {
+            let index = |j| row * size + j;
+            let inv_rms = inverse_rms(x, size, index, epsilon);
+            for j in 0..size {
+                let xhat = (*x.add(index(j)) as f64 * inv_rms) as f32;
+                *y.add(index(j)) = xhat * *gamma.add(j);
+            }
+        }
+    }
+
+    unsafe fn rms_norm_backward_f32(
+        &mut self,
+        x: *const u8,
+        gamma: *const u8,
+        gy: *const u8,
+        gx: *mut u8,
+        rows: usize,
+        size: usize,
+        epsilon: f32,
+    ) {
+        let x = x as *const f32;
+        let gamma = gamma as *const f32;
+        let gy = gy as *const f32;
+        let gx = gx as *mut f32;
+        for row in 0..rows {
+            let index = |j| row * size + j;
+            let inv_rms = inverse_rms(x, size, index, epsilon);
+
+            // gx = inv_rms * (v - xhat * mean(v * xhat)), where v = gamma * gy.
+            let mut dot = 0f64;
+            for j in 0..size {
+                let v = *gamma.add(j) as f64 * *gy.add(index(j)) as f64;
+                dot += v * *x.add(index(j)) as f64 * inv_rms;
+            }
+            let dot_mean = dot / size as f64;
+            for j in 0..size {
+                let v = *gamma.add(j) as f64 * *gy.add(index(j)) as f64;
+                let xhat = *x.add(index(j)) as f64 * inv_rms;
+                *gx.add(index(j)) = (inv_rms * (v - xhat * dot_mean)) as f32;
+            }
+        }
+    }
+
+    unsafe fn batch_norm_f32(
+        &mut self,
+        x: *const u8,
+        gamma: *const u8,
+        beta: *const u8,
+        y: *mut u8,
+        outer: usize,
+        channels: usize,
+        inner: usize,
+        epsilon: f32,
+    ) {
+        let x = x as *const f32;
+        let gamma = gamma as *const f32;
+        let beta = beta as *const f32;
+        let y = y as *mut f32;
+        for c in 0..channels {
+            let index = |k| channel_index(k, c, channels, inner);
+            let (mean, inv_std) = standardization(x, outer * inner, index, epsilon);
+            let (scale, shift) = (*gamma.add(c), *beta.add(c));
+            for k in 0..outer * inner {
+                let xhat = ((*x.add(index(k)) as f64 - mean) * inv_std) as f32;
+                *y.add(index(k)) = xhat * scale + shift;
+            }
+        }
+    }
+
+    unsafe fn batch_norm_backward_f32(
+        &mut self,
+        x: *const u8,
+        gamma: *const u8,
+        gy: *const u8,
+        gx: *mut u8,
+        outer: usize,
+        channels: usize,
+        inner: usize,
+        epsilon: f32,
+    ) {
+        let x = x as *const f32;
+        let gamma = gamma as *const f32;
+        let gy = gy as *const f32;
+        let gx = gx as *mut f32;
+        for c in 0..channels {
+            let index = |k| channel_index(k, c, channels, inner);
+            let (mean, inv_std) = standardization(x, outer * inner, index, epsilon);
+            let scale = *gamma.add(c);
+            standardization_backward(x, gy, gx, outer * inner, index, |_| scale, mean, inv_std);
+        }
+    }
+
+    unsafe fn batch_norm_statistics_f32(
+        &mut self,
+        x: *const u8,
+        mean: *mut u8,
+        variance: *mut u8,
+        outer: usize,
+        channels: usize,
+        inner: usize,
+    ) {
+        let x = x as *const f32;
+        let mean = mean as *mut f32;
+        let variance = variance as *mut f32;
+        for c in 0..channels {
+            let (m, v) = moments(x, outer * inner, |k| channel_index(k, c, channels, inner));
+            *mean.add(c) = m as f32;
+            *variance.add(c) = v as f32;
+        }
+    }
+
+    unsafe fn batch_norm_inference_f32(
+        &mut self,
+        x: *const u8,
+        mean: *const u8,
+        variance: *const u8,
+        gamma: *const u8,
+        beta: *const u8,
+        y: *mut u8,
+        outer: usize,
+        channels: usize,
+        inner: usize,
+        epsilon: f32,
+    ) {
+        let x = x as *const f32;
+        let mean = mean as *const f32;
+        let variance = variance as *const f32;
+        let gamma = gamma as *const f32;
+        let beta = beta as *const f32;
+        let y = y as *mut f32;
+        for c in 0..channels {
+            let inv_std = 1. / (*variance.add(c) as f64 + epsilon as f64).sqrt();
+            let scale = *gamma.add(c) as f64 * inv_std;
+            let (m, shift) = (*mean.add(c) as f64, *beta.add(c) as f64);
+            for k in 0..outer * inner {
+                let index = channel_index(k, c, channels, inner);
+                *y.add(index) = ((*x.add(index) as f64 - m) * scale + shift) as f32;
+            }
+        }
+    }
+
+    unsafe fn elementwise_eq_f32(
+        &mut self,
+        lhs: *const u8,
+        rhs: *const u8,
+        dest: *mut u8,
+        num_elements: usize,
+    ) {
+        let lhs = lhs as *const f32;
+        let rhs = rhs as *const f32;
+        let dest = dest as *mut f32;
+        for i in 0..num_elements {
+            *dest.add(i) = if *lhs.add(i) == *rhs.add(i) { 1. } else { 0. };
+        }
+    }
+
+    unsafe fn elementwise_lt_f32(
+        &mut self,
+        lhs: *const u8,
+        rhs: *const u8,
+        dest: *mut u8,
+        num_elements: usize,
+    ) {
+        let lhs = lhs as *const f32;
+        let rhs = rhs as *const f32;
+        let dest = dest as *mut f32;
+        for i in 0..num_elements {
+            *dest.add(i) = if *lhs.add(i) < *rhs.add(i) { 1. } else { 0. };
+        }
+    }
+
+    unsafe fn elementwise_le_f32(
+        &mut self,
+        lhs: *const u8,
+        rhs: *const u8,
+        dest: *mut u8,
+        num_elements: usize,
+    ) {
+        let lhs = lhs as *const f32;
+        let rhs = rhs as *const f32;
+        let dest = dest as *mut f32;
+        for i in 0..num_elements {
+            *dest.add(i) = if *lhs.add(i) <= *rhs.add(i) { 1. } else { 0. };
+        }
+    }
+
+    unsafe fn elementwise_minimum_f32(
+        &mut self,
+        lhs: *const u8,
+        rhs: *const u8,
+        dest: *mut u8,
+        num_elements: usize,
+    ) {
+        let lhs = lhs as *const f32;
+        let rhs = rhs as *const f32;
+        let dest = dest as *mut f32;
+        for i in 0..num_elements {
+            *dest.add(i) = minimum(*lhs.add(i), *rhs.add(i));
+        }
+    }
+
+    unsafe fn elementwise_maximum_f32(
+        &mut self,
+        lhs: *const u8,
+        rhs: *const u8,
+        dest: *mut u8,
+        num_elements: usize,
+    ) {
+        let lhs = lhs as *const f32;
+        let rhs = rhs as *const f32;
+        let dest = dest as *mut f32;
+        for i in 0..num_elements {
+            *dest.add(i) = maximum(*lhs.add(i), *rhs.add(i));
+        }
+    }
+
+    unsafe fn elementwise_logical_and_f32(
+        &mut self,
+        lhs: *const u8,
+        rhs: *const u8,
+        dest: *mut u8,
+        num_elements: usize,
+    ) {
+        let lhs = lhs as *const f32;
+        let rhs = rhs as *const f32;
+        let dest = dest as *mut f32;
+        for i in 0..num_elements {
+            *dest.add(i) = if *lhs.add(i) != 0. && *rhs.add(i) != 0. {
+                1.
+            } else {
+                0.
+            };
+        }
+    }
+
+    unsafe fn elementwise_logical_or_f32(
+        &mut self,
+        lhs: *const u8,
+        rhs: *const u8,
+        dest: *mut u8,
+        num_elements: usize,
+    ) {
+        let lhs = lhs as *const f32;
+        let rhs = rhs as *const f32;
+        let dest = dest as *mut f32;
+        for i in 0..num_elements {
+            *dest.add(i) = if *lhs.add(i) != 0. || *rhs.add(i) != 0. {
+                1.
+            } else {
+                0.
+            };
+        }
+    }
+
+    unsafe fn elementwise_logical_not_f32(
+        &mut self,
+        src: *const u8,
+        dest: *mut u8,
+        num_elements: usize,
+    ) {
+        let src = src as *const f32;
+        let dest = dest as *mut f32;
+        for i in 0..num_elements {
+            let x = *src.add(i);
+            *dest.add(i) = if x == 0. { 1. } else { 0. };
+        }
+    }
+
+    unsafe fn elementwise_isnan_f32(&mut self, src: *const u8, dest: *mut u8, num_elements: usize) {
+        let src = src as *const f32;
+        let dest = dest as *mut f32;
+        for i in 0..num_elements {
+            let x = *src.add(i);
+            *dest.add(i) = if x.is_nan() { 1. } else { 0. };
+        }
+    }
+
+    unsafe fn elementwise_isinf_f32(&mut self, src: *const u8, dest: *mut u8, num_elements: usize) {
+        let src = src as *const f32;
+        let dest = dest as *mut f32;
+        for i in 0..num_elements {
+            let x = *src.add(i);
+            *dest.add(i) = if x.is_infinite() { 1. } else { 0. };
+        }
+    }
+
+    unsafe fn elementwise_where_f32(
+        &mut self,
+        cond: *const u8,
+        lhs: *const u8,
+        rhs: *const u8,
+        dest: *mut u8,
+        num_elements: usize,
+    ) {
+        let cond = cond as *const f32;
+        let lhs = lhs as *const f32;
+        let rhs = rhs as *const f32;
+        let dest = dest as *mut f32;
+        for i in 0..num_elements {
+            *dest.add(i) = if *cond.add(i) != 0. {
+                *lhs.add(i)
+            } else {
+                *rhs.add(i)
+            };
+        }
+    }
+}
+
+/// Obtains the minimum of two values, propagating NaN.
+fn minimum(a: f32, b: f32) -> f32 {
+    if a.is_nan() || b.is_nan() {
+        f32::NAN
+    } else {
+        a.min(b)
+    }
+}
+
+/// Obtains the maximum of two values, propagating NaN.
+fn maximum(a: f32, b: f32) -> f32 {
+    if a.is_nan() || b.is_nan() {
+        f32::NAN
+    } else {
+        a.max(b)
+    }
+}
+
+/// Obtains the flat index of the `k`-th element of the channel `c` in the layout
+/// [outer, `channels`, `inner`].
+fn channel_index(k: usize, c: usize, channels: usize, inner: usize) -> usize {
+    ((k / inner) * channels + c) * inner + k % inner
+}
+
+/// Calculates the mean and the biased variance of `size` elements with the two-pass algorithm.
+///
+/// # Safety
+///
+/// `x` owns enough amount of memory to be accessed by `index(k)` for all `k < size`.
+unsafe fn moments(x: *const f32, size: usize, index: impl Fn(usize) -> usize) -> (f64, f64) {
+    if size == 0 {
+        return (0., 0.);
+    }
+    let mean = (0..size).map(|k| *x.add(index(k)) as f64).sum::<f64>() / size as f64;
+    let variance = (0..size)
+        .map(|k| {
+            let d = *x.add(index(k)) as f64 - mean;
+            d * d
+        })
+        .sum::<f64>()
+        / size as f64;
+    (mean, variance)
+}
+
+/// Calculates the mean and the inverse standard deviation used for standardization.
+///
+/// # Safety
+///
+/// `x` owns enough amount of memory to be accessed by `index(k)` for all `k < size`.
+unsafe fn standardization(
+    x: *const f32,
+    size: usize,
+    index: impl Fn(usize) -> usize,
+    epsilon: f32,
+) -> (f64, f64) {
+    let (mean, variance) = moments(x, size, index);
+    (mean, 1. / (variance + epsilon as f64).sqrt())
+}
+
+/// Calculates the inverse root mean square used for RMS normalization.
+///
+/// # Safety
+///
+/// `x` owns enough amount of memory to be accessed by `index(k)` for all `k < size`.
+unsafe fn inverse_rms(
+    x: *const f32,
+    size: usize,
+    index: impl Fn(usize) -> usize,
+    epsilon: f32,
+) -> f64 {
+    let square = (0..size)
+        .map(|k| {
+            let v = *x.add(index(k)) as f64;
+            v * v
+        })
+        .sum::<f64>();
+    1. / (square / size.max(1) as f64 + epsilon as f64).sqrt()
+}
+
+/// Calculates the gradient of standardization followed by scaling:
+/// gx = inv_std * (v - mean(v) - xhat * mean(v * xhat)), where v = scale * gy.
+///
+/// # Safety
+///
+/// Each memory owns enough amount of memory to be accessed by `index(k)` for all `k < size`, and
+/// `gx` does not overlap with other memories.
+#[allow(clippy::too_many_arguments)]
+unsafe fn standardization_backward(
+    x: *const f32,
+    gy: *const f32,
+    gx: *mut f32,
+    size: usize,
+    index: impl Fn(usize) -> usize,
+    scale: impl Fn(usize) -> f32,
+    mean: f64,
+    inv_std: f64,
+) {
+    let mut v_sum = 0f64;
+    let mut vx_sum = 0f64;
+    for k in 0..size {
+        let v = scale(k) as f64 * *gy.add(index(k)) as f64;
+        v_sum += v;
+        vx_sum += v * (*x.add(index(k)) as f64 - mean) * inv_std;
+    }
+    let v_mean = v_sum / size as f64;
+    let vx_mean = vx_sum / size as f64;
+    for k in 0..size {
+        let v = scale(k) as f64 * *gy.add(index(k)) as f64;
+        let xhat = (*x.add(index(k)) as f64 - mean) * inv_std;
+        *gx.add(index(k)) = (inv_std * (v - v_mean - xhat * vx_mean)) as f32;
+    }
+}
+
+/// Enumerates all multiplications in a 2-dimensional convolution.
+///
+/// `f` is called with the flat indices of the input, kernel and output elements for every pair of
+/// an output element and a kernel element which does not lie on the padding.
+fn for_each_conv2d_tap(geometry: &Conv2dGeometry, mut f: impl FnMut(usize, usize, usize)) {
+    let [in_height, in_width] = geometry.in_size;
+    let [out_height, out_width] = geometry.out_size;
+    let [kernel_height, kernel_width] = geometry.kernel_size;
+    let group_in_channels = geometry.in_channels / geometry.groups;
+    let group_out_channels = geometry.out_channels / geometry.groups;
+    for n in 0..geometry.batch_size {
+        for o in 0..geometry.out_channels {
+            let group = o / group_out_channels;
+            for oi in 0..out_height {
+                for oj in 0..out_width {
+                    let yi = ((n * geometry.out_channels + o) * out_height + oi) * out_width + oj;
+                    for c in 0..group_in_channels {
+                        let channel = n * geometry.in_channels + group * group_in_channels + c;
+                        for p in 0..kernel_height {
+                            let Some(ii) = geometry.input_index(0, oi, p) else {
+                                continue;
+                            };
+                            for q in 0..kernel_width {
+                                let Some(ij) = geometry.input_index(1, oj, q) else {
+                                    continue;
+                                };
+                                let xi = (channel * in_height + ii) * in_width + ij;
+                                let wi = ((o * group_in_channels + c) * kernel_height + p)
+                                    * kernel_width
+                                    + q;
+                                f(xi, wi, yi);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Enumerates all windows in a 2-dimensional pooling.
+///
+/// `f` is called with the flat index of each output element and the flat indices of the input
+/// elements in the corresponding window, excluding the padding.
+fn for_each_pool2d_window(geometry: &Conv2dGeometry, mut f: impl FnMut(usize, &[usize])) {
+    let [in_height, in_width] = geometry.in_size;
+    let [out_height, out_width] = geometry.out_size;
+    let [kernel_height, kernel_width] = geometry.kernel_size;
+    let mut window = Vec::with_capacity(kernel_height * kernel_width);
+    for channel in 0..geometry.batch_size * geometry.in_channels {
+        for oi in 0..out_height {
+            for oj in 0..out_width {
+                window.clear();
+                for p in 0..kernel_height {
+                    let Some(ii) = geometry.input_index(0, oi, p) else {
+                        continue;
+                    };
+                    for q in 0..kernel_width {
+                        if let Some(ij) = geometry.input_index(1, oj, q) {
+                            window.push((channel * in_height + ii) * in_width + ij);
+                        }
+                    }
+                }
+                f((channel * out_height + oi) * out_width + oj, &window);
+            }
+        }
+    }
+}
+
+/// Obtains the index of the first maximum value among `indices`.
+///
+/// # Safety
+///
+/// `src` owns enough amount of memory to be accessed by all `indices`, and `indices` is not empty.
+unsafe fn argmax(src: *const f32, indices: &[usize]) -> usize {
+    indices
+        .iter()
+        .copied()
+        .reduce(|best, i| {
+            if *src.add(i) > *src.add(best) {
+                i
+            } else {
+                best
+            }
+        })
+        .unwrap()
+}
+
+pub(crate) mod mapped;
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::Buffer;
+    use crate::conv::{Conv2dGeometry, Conv2dOptions, Pool2dOptions};
+    use crate::hardware::cpu::CpuHardware;
+    use crate::hardware::Hardware;
+    use crate::random;
+    use crate::shape::Shape;
+    use std::cell::RefCell;
+    use std::mem::size_of;
+
+    #[test]
+    fn test_allocate_memory() {
+        let hw = RefCell::new(CpuHardware::new());
+
+        unsafe {
+            let mut buf = Buffer::raw(&hw, 4);
+
+            // Owned memory in `buf` should be a usual host memory.
+            *(buf.as_mut_handle() as *mut [u8; 4]) = [0x01, 0x02, 0x03, 0x04];
+            *buf.as_mut_handle().add(0) += 0x40;
+            *buf.as_mut_handle().add(1) += 0x30;
+            *buf.as_mut_handle().add(2) += 0x20;
+            *buf.as_mut_handle().add(3) += 0x10;
+            assert_eq!(
+                *(buf.as_handle() as *const [u8; 4]),
+                [0x41, 0x32, 0x23, 0x14]
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Detected memory leak: 1 memory blocks have not been released.")]
+    fn test_memory_leak() {
+        let mut hw = CpuHardware::new();
+        unsafe {
+            hw.allocate_memory(1);
+        }
+    }
+
+    #[test]
+    fn test_zero_memory_leak() {
+        let mut hw = CpuHardware::new();
+        unsafe {
+            // The hardware don't care about zero-length memories.
+            hw.allocate_memory(0);
+            hw.allocate_memory(0);
+            hw.allocate_memory(0);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_deallocate_memory_twice() {
+        let mut hw = CpuHardware::new();
+        unsafe {
+            let ptr = hw.allocate_memory(1);
+            hw.deallocate_memory(ptr, 1);
+            hw.deallocate_memory(ptr, 1);
+        }
+    }
+
+    #[test]
+    fn test_copy_host_to_hardware() {
+        let hw = RefCell::new(CpuHardware::new());
+        unsafe {
+            let mut dest = Buffer::raw(&hw, 4);
+            let src: Vec<u8> = vec![1, 2, 3, 4];
+            hw.borrow_mut()
+                .copy_host_to_hardware(src.as_ptr(), dest.as_mut_handle(), 4);
+            assert_eq!(*(dest.as_handle() as *const [u8; 4]), [1, 2, 3, 4]);
+        }
+    }
+
+    #[test]
+    fn test_copy_hardware_to_host() {
+        let hw = RefCell::new(CpuHardware::new());
+        unsafe {
+            let src = Buffer::raw(&hw, 4);
+            let mut dest: Vec<u8> = vec![0; 4];
+            *(src.as_handle() as *mut [u8; 4]) = [1, 2, 3, 4];
+            hw.borrow_mut()
+                .copy_hardware_to_host(src.as_handle(), dest.as_mut_ptr(), 4);
+            assert_eq!(dest, vec![1, 2, 3, 4]);
+        }
+    }
+
+    #[test]
+    fn test_copy_hardware_to_hardware() {
+        let hw = RefCell::new(CpuHardware::new());
+        unsafe {
+            let src = Buffer::raw(&hw, 4);
+            let mut dest = Buffer::raw(&hw, 4);
+            *(src.as_handle() as *mut [u8; 4]) = [1, 2, 3, 4];
+            hw.borrow_mut()
+                .copy_hardware_to_host(src.as_handle(), dest.as_mut_handle(), 4);
+            assert_eq!(*(dest.as_handle() as *const [u8; 4]), [1, 2, 3, 4]);
+        }
+    }
+
+    #[test]
+    fn test_fill_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        unsafe {
+            let mut dest = Buffer::raw(&hw, 4 * size_of::<f32>());
+            hw.borrow_mut().fill_f32(dest.as_mut_handle(), 42., 4);
+            assert_eq!(*(dest.as_handle() as *const [f32; 4]), [42.; 4]);
+        }
+    }
+
+    #[test]
+    fn test_elementwise_clamp_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        let size = 4 * size_of::<f32>();
+        unsafe {
+            let mut src = Buffer::raw(&hw, size);
+            let mut dest = Buffer::raw(&hw, size);
+            *(src.as_mut_handle() as *mut [f32; 4]) = [-2., -0.5, 0.5, 2.];
+            hw.borrow_mut().elementwise_clamp_f32(
+                src.as_handle(),
+                dest.as_mut_handle(),
+                -1.,
+                1.,
+                4,
+            );
+            assert_eq!(*(dest.as_handle() as *const [f32; 4]), [-1., -0.5, 0.5, 1.]);
+
+            *(src.as_mut_handle() as *mut [f32; 4]) = [f32::NAN, -f32::INFINITY, f32::INFINITY, 0.];
+            hw.borrow_mut().elementwise_clamp_f32(
+                src.as_handle(),
+                dest.as_mut_handle(),
+                -1.,
+                1.,
+                4,
+            );
+            let observed = *(dest.as_handle() as *const [f32; 4]);
+            assert!(observed[0].is_nan());
+            assert_eq!(observed[1..], [-1., 1., 0.]);
+        }
+    }
+
+    #[test]
+    fn test_elementwise_add_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        let size = 4 * size_of::<f32>();
+        unsafe {
+            let mut lhs = Buffer::raw(&hw, size);
+            let mut rhs = Buffer::raw(&hw, size);
+            let mut dest = Buffer::raw(&hw, size);
+            *(lhs.as_mut_handle() as *mut [f32; 4]) = [1., 2., 3., 4.];
+            *(rhs.as_mut_handle() as *mut [f32; 4]) = [5., 6., 7., 8.];
+            hw.borrow_mut().elementwise_add_f32(
+                lhs.as_handle(),
+                rhs.as_handle(),
+                dest.as_mut_handle(),
+                4,
+            );
+            assert_eq!(
+                *(dest.as_mut_handle() as *const [f32; 4]),
+                [6., 8., 10., 12.]
+            );
+        }
+    }
+
+    #[test]
+    fn test_elementwise_sub_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        let size = 4 * size_of::<f32>();
+        unsafe {
+            let mut lhs = Buffer::raw(&hw, size);
+            let mut rhs = Buffer::raw(&hw, size);
+            let mut dest = Buffer::raw(&hw, size);
+            *(lhs.as_mut_handle() as *mut [f32; 4]) = [9., 8., 7., 6.];
+            *(rhs.as_mut_handle() as *mut [f32; 4]) = [1., 2., 3., 4.];
+            hw.borrow_mut().elementwise_sub_f32(
+                lhs.as_handle(),
+                rhs.as_handle(),
+                dest.as_mut_handle(),
+                4,
+            );
+            assert_eq!(*(dest.as_handle() as *const [f32; 4]), [8., 6., 4., 2.]);
+        }
+    }
+
+    #[test]
+    fn test_elementwise_mul_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        let size = 4 * size_of::<f32>();
+        unsafe {
+            let mut lhs = Buffer::raw(&hw, size);
+            let mut rhs = Buffer::raw(&hw, size);
+            let mut dest = Buffer::raw(&hw, size);
+            *(lhs.as_mut_handle() as *mut [f32; 4]) = [1., 2., 3., 4.];
+            *(rhs.as_mut_handle() as *mut [f32; 4]) = [5., 6., 7., 8.];
+            hw.borrow_mut().elementwise_mul_f32(
+                lhs.as_handle(),
+                rhs.as_handle(),
+                dest.as_mut_handle(),
+                4,
+            );
+            assert_eq!(*(dest.as_handle() as *const [f32; 4]), [5., 12., 21., 32.]);
+        }
+    }
+
+    #[test]
+    fn test_elementwise_div_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        let size = 4 * size_of::<f32>();
+        unsafe {
+            let mut lhs = Buffer::raw(&hw, size);
+            let mut rhs = Buffer::raw(&hw, size);
+            let mut dest = Buffer::raw(&hw, size);
+            *(lhs.as_mut_handle() as *mut [f32; 4]) = [1., 2., 3., 4.];
+            *(rhs.as_mut_handle() as *mut [f32; 4]) = [4., 2., 1., 0.5];
             hw.borrow_mut().elementwise_div_f32(
                 lhs.as_handle(),
                 rhs.as_handle(),
                 dest.as_mut_handle(),
                 4,
             );
-            assert_eq!(*(dest.as_handle() as *const [f32; 4]), [0.25, 1., 3., 8.]);
+            assert_eq!(*(dest.as_handle() as *const [f32; 4]), [0.25, 1., 3., 8.]);
+        }
+    }
+
+    #[test]
+    fn test_add_assign_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        let size = 4 * size_of::<f32>();
+        unsafe {
+            let mut dest = Buffer::raw(&hw, size);
+            let mut src = Buffer::raw(&hw, size);
+            *(dest.as_mut_handle() as *mut [f32; 4]) = [1., 2., 3., 4.];
+            *(src.as_mut_handle() as *mut [f32; 4]) = [5., 6., 7., 8.];
+            hw.borrow_mut()
+                .add_assign_f32(dest.as_mut_handle(), src.as_handle(), 4);
+            assert_eq!(*(dest.as_handle() as *const [f32; 4]), [6., 8., 10., 12.]);
+
+            // The same memory is allowed.
+            hw.borrow_mut()
+                .add_assign_f32(dest.as_mut_handle(), dest.as_handle(), 4);
+            assert_eq!(*(dest.as_handle() as *const [f32; 4]), [12., 16., 20., 24.]);
+        }
+    }
+
+    #[test]
+    fn test_mul_assign_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        let size = 4 * size_of::<f32>();
+        unsafe {
+            let mut dest = Buffer::raw(&hw, size);
+            let mut src = Buffer::raw(&hw, size);
+            *(dest.as_mut_handle() as *mut [f32; 4]) = [1., 2., 3., 4.];
+            *(src.as_mut_handle() as *mut [f32; 4]) = [5., 6., 7., 8.];
+            hw.borrow_mut()
+                .mul_assign_f32(dest.as_mut_handle(), src.as_handle(), 4);
+            assert_eq!(*(dest.as_handle() as *const [f32; 4]), [5., 12., 21., 32.]);
+
+            // The same memory is allowed.
+            hw.borrow_mut()
+                .mul_assign_f32(dest.as_mut_handle(), dest.as_handle(), 4);
+            assert_eq!(
+                *(dest.as_handle() as *const [f32; 4]),
+                [25., 144., 441., 1024.]
+            );
+        }
+    }
+
+    #[test]
+    fn test_axpy_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        let size = 4 * size_of::<f32>();
+        unsafe {
+            let mut dest = Buffer::raw(&hw, size);
+            let mut src = Buffer::raw(&hw, size);
+            *(dest.as_mut_handle() as *mut [f32; 4]) = [1., 2., 3., 4.];
+            *(src.as_mut_handle() as *mut [f32; 4]) = [5., 6., 7., 8.];
+            hw.borrow_mut()
+                .axpy_f32(dest.as_mut_handle(), -0.5, src.as_handle(), 4);
+            assert_eq!(
+                *(dest.as_handle() as *const [f32; 4]),
+                [-1.5, -1., -0.5, 0.]
+            );
+        }
+    }
+
+    #[test]
+    fn test_sgd_update_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        let size = 2 * size_of::<f32>();
+        unsafe {
+            let mut value = Buffer::raw(&hw, size);
+            let mut gradient = Buffer::raw(&hw, size);
+            let mut velocity = Buffer::raw(&hw, size);
+            *(value.as_mut_handle() as *mut [f32; 2]) = [1., 2.];
+            *(gradient.as_mut_handle() as *mut [f32; 2]) = [0.5, -1.];
+            *(velocity.as_mut_handle() as *mut [f32; 2]) = [1., 1.];
+            hw.borrow_mut().sgd_update_f32(
+                value.as_mut_handle(),
+                gradient.as_handle(),
+                velocity.as_mut_handle(),
+                0.5,
+                0.5,
+                0.,
+                false,
+                2,
+            );
+            // velocity = 0.5 * velocity + gradient
+            assert_eq!(*(velocity.as_handle() as *const [f32; 2]), [1., -0.5]);
+            // value -= 0.5 * velocity
+            assert_eq!(*(value.as_handle() as *const [f32; 2]), [0.5, 2.25]);
+        }
+    }
+
+    #[test]
+    fn test_sgd_update_f32_nesterov_weight_decay() {
+        let hw = RefCell::new(CpuHardware::new());
+        let size = 2 * size_of::<f32>();
+        unsafe {
+            let mut value = Buffer::raw(&hw, size);
+            let mut gradient = Buffer::raw(&hw, size);
+            let mut velocity = Buffer::raw(&hw, size);
+            *(value.as_mut_handle() as *mut [f32; 2]) = [1., 2.];
+            *(gradient.as_mut_handle() as *mut [f32; 2]) = [0.5, -1.];
+            *(velocity.as_mut_handle() as *mut [f32; 2]) = [1., 1.];
+            hw.borrow_mut().sgd_update_f32(
+                value.as_mut_handle(),
+                gradient.as_handle(),
+                velocity.as_mut_handle(),
+                0.5,
+                0.5,
+                0.5,
+                true,
+                2,
+            );
+            // g = gradient + 0.5 * value == [1, 0]
+            // velocity = 0.5 * velocity + g == [1.5, 0.5]
+            assert_eq!(*(velocity.as_handle() as *const [f32; 2]), [1.5, 0.5]);
+            // value -= 0.5 * (g + 0.5 * velocity)
+            assert_eq!(*(value.as_handle() as *const [f32; 2]), [0.125, 1.875]);
+        }
+    }
+
+    #[test]
+    fn test_adam_update_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        let size = 2 * size_of::<f32>();
+        unsafe {
+            let mut value = Buffer::raw(&hw, size);
+            let mut gradient = Buffer::raw(&hw, size);
+            let mut m = Buffer::raw(&hw, size);
+            let mut v = Buffer::raw(&hw, size);
+            *(value.as_mut_handle() as *mut [f32; 2]) = [1., 2.];
+            *(gradient.as_mut_handle() as *mut [f32; 2]) = [2., -4.];
+            *(m.as_mut_handle() as *mut [f32; 2]) = [0., 0.];
+            *(v.as_mut_handle() as *mut [f32; 2]) = [0., 0.];
+            hw.borrow_mut().adam_update_f32(
+                value.as_mut_handle(),
+                gradient.as_handle(),
+                m.as_mut_handle(),
+                v.as_mut_handle(),
+                0.5,
+                0.5,
+                0.75,
+                0.,
+                0.,
+                false,
+                0.5,
+                0.25,
+                2,
+            );
+            assert_eq!(*(m.as_handle() as *const [f32; 2]), [1., -2.]);
+            assert_eq!(*(v.as_handle() as *const [f32; 2]), [1., 4.]);
+            // The first update moves each value by the learning rate.
+            assert_eq!(*(value.as_handle() as *const [f32; 2]), [0.5, 2.5]);
+        }
+    }
+
+    #[test]
+    fn test_adam_update_f32_weight_decay() {
+        let hw = RefCell::new(CpuHardware::new());
+        let size = size_of::<f32>();
+        unsafe {
+            let mut value = Buffer::raw(&hw, size);
+            let mut gradient = Buffer::raw(&hw, size);
+            let mut m = Buffer::raw(&hw, size);
+            let mut v = Buffer::raw(&hw, size);
+
+            // L2 penalty: g = 2 + 0.5 * 4 == 4, and the normalized step is 1.
+            *(value.as_mut_handle() as *mut f32) = 4.;
+            *(gradient.as_mut_handle() as *mut f32) = 2.;
+            *(m.as_mut_handle() as *mut f32) = 0.;
+            *(v.as_mut_handle() as *mut f32) = 0.;
+            hw.borrow_mut().adam_update_f32(
+                value.as_mut_handle(),
+                gradient.as_handle(),
+                m.as_mut_handle(),
+                v.as_mut_handle(),
+                0.5,
+                0.5,
+                0.5,
+                0.,
+                0.5,
+                false,
+                0.5,
+                0.5,
+                1,
+            );
+            assert_eq!(*(m.as_handle() as *const f32), 2.);
+            assert_eq!(*(value.as_handle() as *const f32), 3.5);
+
+            // Decoupled: the normalized step is 1, and the decay 0.5 * 4 is added.
+            *(value.as_mut_handle() as *mut f32) = 4.;
+            *(m.as_mut_handle() as *mut f32) = 0.;
+            *(v.as_mut_handle() as *mut f32) = 0.;
+            hw.borrow_mut().adam_update_f32(
+                value.as_mut_handle(),
+                gradient.as_handle(),
+                m.as_mut_handle(),
+                v.as_mut_handle(),
+                0.5,
+                0.5,
+                0.5,
+                0.,
+                0.5,
+                true,
+                0.5,
+                0.5,
+                1,
+            );
+            assert_eq!(*(m.as_handle() as *const f32), 1.);
+            assert_eq!(*(value.as_handle() as *const f32), 2.5);
+        }
+    }
+
+    #[test]
+    fn test_rmsprop_update_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        let size = 2 * size_of::<f32>();
+        unsafe {
+            let mut value = Buffer::raw(&hw, size);
+            let mut gradient = Buffer::raw(&hw, size);
+            let mut square_avg = Buffer::raw(&hw, size);
+            *(value.as_mut_handle() as *mut [f32; 2]) = [1., 2.];
+            *(gradient.as_mut_handle() as *mut [f32; 2]) = [2., -4.];
+            *(square_avg.as_mut_handle() as *mut [f32; 2]) = [0., 16.];
+            hw.borrow_mut().rmsprop_update_f32(
+                value.as_mut_handle(),
+                gradient.as_handle(),
+                square_avg.as_mut_handle(),
+                0.5,
+                0.75,
+                0.,
+                0.,
+                2,
+            );
+            assert_eq!(*(square_avg.as_handle() as *const [f32; 2]), [1., 16.]);
+            assert_eq!(*(value.as_handle() as *const [f32; 2]), [0., 2.5]);
+        }
+    }
+
+    #[test]
+    fn test_adagrad_update_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        let size = 2 * size_of::<f32>();
+        unsafe {
+            let mut value = Buffer::raw(&hw, size);
+            let mut gradient = Buffer::raw(&hw, size);
+            let mut square_sum = Buffer::raw(&hw, size);
+            *(value.as_mut_handle() as *mut [f32; 2]) = [1., 2.];
+            *(gradient.as_mut_handle() as *mut [f32; 2]) = [3., -4.];
+            *(square_sum.as_mut_handle() as *mut [f32; 2]) = [16., 0.];
+            hw.borrow_mut().adagrad_update_f32(
+                value.as_mut_handle(),
+                gradient.as_handle(),
+                square_sum.as_mut_handle(),
+                0.5,
+                0.,
+                0.,
+                2,
+            );
+            assert_eq!(*(square_sum.as_handle() as *const [f32; 2]), [25., 16.]);
+            assert_eq!(*(value.as_handle() as *const [f32; 2]), [0.7, 2.5]);
+        }
+    }
+
+    #[test]
+    fn test_random_uniform_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        let size = 3 * size_of::<f32>();
+        unsafe {
+            let mut dest = Buffer::raw(&hw, size);
+            hw.borrow_mut()
+                .random_uniform_f32(dest.as_mut_handle(), 42, 5, 2., 4., 3);
+            let values = *(dest.as_handle() as *const [f32; 3]);
+            for (i, &x) in values.iter().enumerate() {
+                let u = random::sample_uniform(42, 5 + i as u64);
+                assert_eq!(x, 2. + 2. * u);
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_normal_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        let size = 3 * size_of::<f32>();
+        unsafe {
+            let mut dest = Buffer::raw(&hw, size);
+            hw.borrow_mut()
+                .random_normal_f32(dest.as_mut_handle(), 42, 5, 1., 0.5, 3);
+            let values = *(dest.as_handle() as *const [f32; 3]);
+            for (i, &x) in values.iter().enumerate() {
+                let z = random::sample_normal(42, 5 + i as u64, 0);
+                assert_eq!(x, 1. + 0.5 * z);
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_truncated_normal_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        let size = 3 * size_of::<f32>();
+        unsafe {
+            let mut dest = Buffer::raw(&hw, size);
+            hw.borrow_mut().random_truncated_normal_f32(
+                dest.as_mut_handle(),
+                42,
+                5,
+                1.,
+                0.5,
+                0.1,
+                3,
+            );
+            let values = *(dest.as_handle() as *const [f32; 3]);
+            for (i, &x) in values.iter().enumerate() {
+                let z = random::sample_truncated_normal(42, 5 + i as u64, 0.1);
+                assert_eq!(x, 1. + 0.5 * z);
+                assert!((0.95..=1.05).contains(&x));
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_bernoulli_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        let size = 3 * size_of::<f32>();
+        unsafe {
+            let mut dest = Buffer::raw(&hw, size);
+            hw.borrow_mut()
+                .random_bernoulli_f32(dest.as_mut_handle(), 42, 5, 0.5, 3);
+            let values = *(dest.as_handle() as *const [f32; 3]);
+            for (i, &x) in values.iter().enumerate() {
+                let u = random::sample_uniform(42, 5 + i as u64);
+                assert_eq!(x, if u < 0.5 { 1. } else { 0. });
+            }
+        }
+    }
+
+    #[test]
+    fn test_elementwise_unary_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        let size = 3 * size_of::<f32>();
+        unsafe {
+            let mut src = Buffer::raw(&hw, size);
+            let mut dest = Buffer::raw(&hw, size);
+            *(src.as_mut_handle() as *mut [f32; 3]) = [-1., 0., 4.];
+            let mut hw = hw.borrow_mut();
+
+            hw.elementwise_relu_f32(src.as_handle(), dest.as_mut_handle(), 3);
+            assert_eq!(*(dest.as_handle() as *const [f32; 3]), [0., 0., 4.]);
+
+            hw.elementwise_step_f32(src.as_handle(), dest.as_mut_handle(), 3);
+            assert_eq!(*(dest.as_handle() as *const [f32; 3]), [0., 0., 1.]);
+
+            hw.elementwise_sigmoid_f32(src.as_handle(), dest.as_mut_handle(), 3);
+            assert_eq!(*(dest.as_handle() as *const f32).add(1), 0.5);
+
+            hw.elementwise_tanh_f32(src.as_handle(), dest.as_mut_handle(), 3);
+            assert_eq!(*(dest.as_handle() as *const f32).add(1), 0.);
+
+            hw.elementwise_sqrt_f32(src.as_handle(), dest.as_mut_handle(), 3);
+            assert_eq!(*(dest.as_handle() as *const f32).add(2), 2.);
+        }
+    }
+
+    #[test]
+    fn test_matmul_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        unsafe {
+            let mut a = Buffer::raw(&hw, 4 * size_of::<f32>());
+            let mut b = Buffer::raw(&hw, 2 * size_of::<f32>());
+            let mut dest = Buffer::raw(&hw, 2 * size_of::<f32>());
+            *(a.as_mut_handle() as *mut [f32; 4]) = [1., 2., 3., 4.];
+            *(b.as_mut_handle() as *mut [f32; 2]) = [5., 6.];
+            hw.borrow_mut()
+                .matmul_f32(a.as_handle(), b.as_handle(), dest.as_mut_handle(), 2, 2, 1);
+            assert_eq!(*(dest.as_handle() as *const [f32; 2]), [17., 39.]);
+        }
+    }
+
+    #[test]
+    fn test_transpose_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        let size = 6 * size_of::<f32>();
+        unsafe {
+            let mut src = Buffer::raw(&hw, size);
+            let mut dest = Buffer::raw(&hw, size);
+            *(src.as_mut_handle() as *mut [f32; 6]) = [1., 2., 3., 4., 5., 6.];
+            hw.borrow_mut()
+                .transpose_f32(src.as_handle(), dest.as_mut_handle(), 2, 3);
+            assert_eq!(
+                *(dest.as_handle() as *const [f32; 6]),
+                [1., 4., 2., 5., 3., 6.]
+            );
+        }
+    }
+
+    #[test]
+    fn test_sum_axis_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        unsafe {
+            let mut src = Buffer::raw(&hw, 8 * size_of::<f32>());
+            let mut dest = Buffer::raw(&hw, 4 * size_of::<f32>());
+            *(src.as_mut_handle() as *mut [f32; 8]) = [1., 2., 3., 4., 5., 6., 7., 8.];
+            hw.borrow_mut()
+                .sum_axis_f32(src.as_handle(), dest.as_mut_handle(), 2, 2, 2);
+            assert_eq!(*(dest.as_handle() as *const [f32; 4]), [4., 6., 12., 14.]);
+        }
+    }
+
+    #[test]
+    fn test_expand_axis_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        unsafe {
+            let mut src = Buffer::raw(&hw, 4 * size_of::<f32>());
+            let mut dest = Buffer::raw(&hw, 8 * size_of::<f32>());
+            *(src.as_mut_handle() as *mut [f32; 4]) = [1., 2., 3., 4.];
+            hw.borrow_mut()
+                .expand_axis_f32(src.as_handle(), dest.as_mut_handle(), 2, 2, 2);
+            assert_eq!(
+                *(dest.as_handle() as *const [f32; 8]),
+                [1., 2., 1., 2., 3., 4., 3., 4.]
+            );
+        }
+    }
+
+    #[test]
+    fn test_gather_and_scatter_add_rows_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        let size = 4 * size_of::<f32>();
+        unsafe {
+            let mut src = Buffer::raw(&hw, size);
+            let mut gathered = Buffer::raw(&hw, size);
+            let mut scattered = Buffer::raw(&hw, size);
+            *(src.as_mut_handle() as *mut [f32; 4]) = [1., 2., 3., 4.];
+            hw.borrow_mut()
+                .gather_rows_f32(src.as_handle(), gathered.as_mut_handle(), &[1, 1], 2);
+            assert_eq!(*(gathered.as_handle() as *const [f32; 4]), [3., 4., 3., 4.]);
+            hw.borrow_mut().scatter_add_rows_f32(
+                gathered.as_handle(),
+                scattered.as_mut_handle(),
+                &[1, 1],
+                2,
+                2,
+            );
+            assert_eq!(
+                *(scattered.as_handle() as *const [f32; 4]),
+                [0., 0., 6., 8.]
+            );
+        }
+    }
+
+    /// Geometry of the convolution of a [1, 1, 3, 3] image by a [1, 1, 2, 2] kernel.
+    fn conv2d_geometry() -> Conv2dGeometry {
+        Conv2dGeometry::conv2d(
+            &Shape::new([1, 1, 3, 3]),
+            &Shape::new([1, 1, 2, 2]),
+            &Conv2dOptions::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_conv2d_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        unsafe {
+            let mut x = Buffer::raw(&hw, 9 * size_of::<f32>());
+            let mut w = Buffer::raw(&hw, 4 * size_of::<f32>());
+            let mut y = Buffer::raw(&hw, 4 * size_of::<f32>());
+            *(x.as_mut_handle() as *mut [f32; 9]) = [1., 2., 3., 4., 5., 6., 7., 8., 9.];
+            *(w.as_mut_handle() as *mut [f32; 4]) = [1., 0., 0., 1.];
+            hw.borrow_mut().conv2d_f32(
+                x.as_handle(),
+                w.as_handle(),
+                y.as_mut_handle(),
+                &conv2d_geometry(),
+            );
+            assert_eq!(*(y.as_handle() as *const [f32; 4]), [6., 8., 12., 14.]);
+        }
+    }
+
+    #[test]
+    fn test_conv2d_f32_groups_and_padding() {
+        let hw = RefCell::new(CpuHardware::new());
+        let geometry = Conv2dGeometry::conv2d(
+            &Shape::new([1, 2, 1, 1]),
+            &Shape::new([2, 1, 1, 3]),
+            &Conv2dOptions {
+                padding: [0, 1],
+                groups: 2,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        unsafe {
+            let mut x = Buffer::raw(&hw, 2 * size_of::<f32>());
+            let mut w = Buffer::raw(&hw, 6 * size_of::<f32>());
+            let mut y = Buffer::raw(&hw, 2 * size_of::<f32>());
+            *(x.as_mut_handle() as *mut [f32; 2]) = [2., 3.];
+            *(w.as_mut_handle() as *mut [f32; 6]) = [1., 10., 1., 1., 100., 1.];
+            hw.borrow_mut()
+                .conv2d_f32(x.as_handle(), w.as_handle(), y.as_mut_handle(), &geometry);
+            assert_eq!(*(y.as_handle() as *const [f32; 2]), [20., 300.]);
+        }
+    }
+
+    #[test]
+    fn test_conv2d_backward_input_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        unsafe {
+            let mut gy = Buffer::raw(&hw, 4 * size_of::<f32>());
+            let mut w = Buffer::raw(&hw, 4 * size_of::<f32>());
+            let mut gx = Buffer::raw(&hw, 9 * size_of::<f32>());
+            *(gy.as_mut_handle() as *mut [f32; 4]) = [1., 1., 1., 1.];
+            *(w.as_mut_handle() as *mut [f32; 4]) = [1., 0., 0., 1.];
+            hw.borrow_mut().conv2d_backward_input_f32(
+                gy.as_handle(),
+                w.as_handle(),
+                gx.as_mut_handle(),
+                &conv2d_geometry(),
+            );
+            assert_eq!(
+                *(gx.as_handle() as *const [f32; 9]),
+                [1., 1., 0., 1., 2., 1., 0., 1., 1.]
+            );
+        }
+    }
+
+    #[test]
+    fn test_conv2d_backward_weight_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        unsafe {
+            let mut x = Buffer::raw(&hw, 9 * size_of::<f32>());
+            let mut gy = Buffer::raw(&hw, 4 * size_of::<f32>());
+            let mut gw = Buffer::raw(&hw, 4 * size_of::<f32>());
+            *(x.as_mut_handle() as *mut [f32; 9]) = [1., 2., 3., 4., 5., 6., 7., 8., 9.];
+            *(gy.as_mut_handle() as *mut [f32; 4]) = [1., 0., 0., 1.];
+            hw.borrow_mut().conv2d_backward_weight_f32(
+                x.as_handle(),
+                gy.as_handle(),
+                gw.as_mut_handle(),
+                &conv2d_geometry(),
+            );
+            assert_eq!(*(gw.as_handle() as *const [f32; 4]), [6., 8., 12., 14.]);
+        }
+    }
+
+    /// Geometry of the pooling of a [1, 1, 2, 3] image by overlapping [2, 2] windows.
+    fn pool2d_geometry() -> Conv2dGeometry {
+        Conv2dGeometry::pool2d(
+            &Shape::new([1, 1, 2, 3]),
+            &Pool2dOptions {
+                kernel_size: [2, 2],
+                stride: [1, 1],
+                padding: [0, 0],
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_max_pool2d_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        unsafe {
+            let mut x = Buffer::raw(&hw, 6 * size_of::<f32>());
+            let mut src = Buffer::raw(&hw, 6 * size_of::<f32>());
+            let mut g = Buffer::raw(&hw, 2 * size_of::<f32>());
+            let mut y = Buffer::raw(&hw, 2 * size_of::<f32>());
+            let mut gx = Buffer::raw(&hw, 6 * size_of::<f32>());
+            *(x.as_mut_handle() as *mut [f32; 6]) = [1., 5., 2., 4., 3., 6.];
+            *(src.as_mut_handle() as *mut [f32; 6]) = [10., 11., 12., 13., 14., 15.];
+            *(g.as_mut_handle() as *mut [f32; 2]) = [1., 2.];
+
+            hw.borrow_mut()
+                .max_pool2d_f32(x.as_handle(), y.as_mut_handle(), &pool2d_geometry());
+            assert_eq!(*(y.as_handle() as *const [f32; 2]), [5., 6.]);
+
+            hw.borrow_mut().max_pool2d_gather_f32(
+                x.as_handle(),
+                src.as_handle(),
+                y.as_mut_handle(),
+                &pool2d_geometry(),
+            );
+            assert_eq!(*(y.as_handle() as *const [f32; 2]), [11., 15.]);
+
+            hw.borrow_mut().max_pool2d_scatter_f32(
+                x.as_handle(),
+                g.as_handle(),
+                gx.as_mut_handle(),
+                &pool2d_geometry(),
+            );
+            assert_eq!(
+                *(gx.as_handle() as *const [f32; 6]),
+                [0., 1., 0., 0., 0., 2.]
+            );
+        }
+    }
+
+    #[test]
+    fn test_max_pool2d_f32_shared_maximum() {
+        let hw = RefCell::new(CpuHardware::new());
+        unsafe {
+            let mut x = Buffer::raw(&hw, 6 * size_of::<f32>());
+            let mut g = Buffer::raw(&hw, 2 * size_of::<f32>());
+            let mut gx = Buffer::raw(&hw, 6 * size_of::<f32>());
+            *(x.as_mut_handle() as *mut [f32; 6]) = [1., 9., 2., 4., 3., 6.];
+            *(g.as_mut_handle() as *mut [f32; 2]) = [1., 2.];
+            hw.borrow_mut().max_pool2d_scatter_f32(
+                x.as_handle(),
+                g.as_handle(),
+                gx.as_mut_handle(),
+                &pool2d_geometry(),
+            );
+            assert_eq!(
+                *(gx.as_handle() as *const [f32; 6]),
+                [0., 3., 0., 0., 0., 0.]
+            );
+        }
+    }
+
+    #[test]
+    fn test_avg_pool2d_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        unsafe {
+            let mut x = Buffer::raw(&hw, 6 * size_of::<f32>());
+            let mut y = Buffer::raw(&hw, 2 * size_of::<f32>());
+            let mut gy = Buffer::raw(&hw, 2 * size_of::<f32>());
+            let mut gx = Buffer::raw(&hw, 6 * size_of::<f32>());
+            *(x.as_mut_handle() as *mut [f32; 6]) = [1., 5., 2., 4., 3., 6.];
+            *(gy.as_mut_handle() as *mut [f32; 2]) = [4., 8.];
+
+            hw.borrow_mut()
+                .avg_pool2d_f32(x.as_handle(), y.as_mut_handle(), &pool2d_geometry());
+            assert_eq!(*(y.as_handle() as *const [f32; 2]), [3.25, 4.]);
+
+            hw.borrow_mut().avg_pool2d_backward_f32(
+                gy.as_handle(),
+                gx.as_mut_handle(),
+                &pool2d_geometry(),
+            );
+            assert_eq!(
+                *(gx.as_handle() as *const [f32; 6]),
+                [1., 3., 2., 1., 3., 2.]
+            );
+        }
+    }
+
+    #[test]
+    fn test_pool2d_f32_padding() {
+        let hw = RefCell::new(CpuHardware::new());
+        let geometry = Conv2dGeometry::pool2d(
+            &Shape::new([1, 1, 1, 1]),
+            &Pool2dOptions {
+                kernel_size: [2, 2],
+                stride: [2, 2],
+                padding: [1, 1],
+            },
+        )
+        .unwrap();
+        unsafe {
+            let mut x = Buffer::raw(&hw, size_of::<f32>());
+            let mut y = Buffer::raw(&hw, size_of::<f32>());
+            *(x.as_mut_handle() as *mut f32) = -4.;
+            hw.borrow_mut()
+                .max_pool2d_f32(x.as_handle(), y.as_mut_handle(), &geometry);
+            assert_eq!(*(y.as_handle() as *const f32), -4.);
+            hw.borrow_mut()
+                .avg_pool2d_f32(x.as_handle(), y.as_mut_handle(), &geometry);
+            assert_eq!(*(y.as_handle() as *const f32), -1.);
+        }
+    }
+
+    #[test]
+    fn test_layer_norm_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        unsafe {
+            let mut x = Buffer::raw(&hw, 4 * size_of::<f32>());
+            let mut gamma = Buffer::raw(&hw, 2 * size_of::<f32>());
+            let mut beta = Buffer::raw(&hw, 2 * size_of::<f32>());
+            let mut y = Buffer::raw(&hw, 4 * size_of::<f32>());
+            *(x.as_mut_handle() as *mut [f32; 4]) = [1., 3., 0., 4.];
+            *(gamma.as_mut_handle() as *mut [f32; 2]) = [2., 1.];
+            *(beta.as_mut_handle() as *mut [f32; 2]) = [0., 1.];
+            hw.borrow_mut().layer_norm_f32(
+                x.as_handle(),
+                gamma.as_handle(),
+                beta.as_handle(),
+                y.as_mut_handle(),
+                2,
+                2,
+                0.,
+            );
+            assert_eq!(*(y.as_handle() as *const [f32; 4]), [-2., 2., -2., 2.]);
+        }
+    }
+
+    #[test]
+    fn test_layer_norm_backward_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        unsafe {
+            let mut x = Buffer::raw(&hw, 4 * size_of::<f32>());
+            let mut gamma = Buffer::raw(&hw, 4 * size_of::<f32>());
+            let mut gy = Buffer::raw(&hw, 4 * size_of::<f32>());
+            let mut gx = Buffer::raw(&hw, 4 * size_of::<f32>());
+            *(x.as_mut_handle() as *mut [f32; 4]) = [-1., -1., 1., 1.];
+            *(gamma.as_mut_handle() as *mut [f32; 4]) = [2., 1., 1., 1.];
+            *(gy.as_mut_handle() as *mut [f32; 4]) = [1., 0., 0., 0.];
+            hw.borrow_mut().layer_norm_backward_f32(
+                x.as_handle(),
+                gamma.as_handle(),
+                gy.as_handle(),
+                gx.as_mut_handle(),
+                1,
+                4,
+                0.,
+            );
+            assert_eq!(*(gx.as_handle() as *const [f32; 4]), [1., -1., 0., 0.]);
+        }
+    }
+
+    #[test]
+    fn test_rms_norm_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        unsafe {
+            let mut x = Buffer::raw(&hw, 4 * size_of::<f32>());
+            let mut gamma = Buffer::raw(&hw, 4 * size_of::<f32>());
+            let mut gy = Buffer::raw(&hw, 4 * size_of::<f32>());
+            let mut y = Buffer::raw(&hw, 4 * size_of::<f32>());
+            let mut gx = Buffer::raw(&hw, 4 * size_of::<f32>());
+            *(x.as_mut_handle() as *mut [f32; 4]) = [2., -2., 2., -2.];
+            *(gamma.as_mut_handle() as *mut [f32; 4]) = [2., 1., 1., 1.];
+            *(gy.as_mut_handle() as *mut [f32; 4]) = [1., 0., 0., 0.];
+
+            hw.borrow_mut().rms_norm_f32(
+                x.as_handle(),
+                gamma.as_handle(),
+                y.as_mut_handle(),
+                1,
+                4,
+                0.,
+            );
+            assert_eq!(*(y.as_handle() as *const [f32; 4]), [2., -1., 1., -1.]);
+
+            hw.borrow_mut().rms_norm_backward_f32(
+                x.as_handle(),
+                gamma.as_handle(),
+                gy.as_handle(),
+                gx.as_mut_handle(),
+                1,
+                4,
+                0.,
+            );
+            assert_eq!(
+                *(gx.as_handle() as *const [f32; 4]),
+                [0.75, 0.25, -0.25, 0.25]
+            );
+        }
+    }
+
+    #[test]
+    fn test_batch_norm_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        unsafe {
+            // Layout: [outer = 2, channels = 2, inner = 1]
+            let mut x = Buffer::raw(&hw, 4 * size_of::<f32>());
+            let mut gamma = Buffer::raw(&hw, 2 * size_of::<f32>());
+            let mut beta = Buffer::raw(&hw, 2 * size_of::<f32>());
+            let mut mean = Buffer::raw(&hw, 2 * size_of::<f32>());
+            let mut variance = Buffer::raw(&hw, 2 * size_of::<f32>());
+            let mut y = Buffer::raw(&hw, 4 * size_of::<f32>());
+            *(x.as_mut_handle() as *mut [f32; 4]) = [-1., 3., 1., 7.];
+            *(gamma.as_mut_handle() as *mut [f32; 2]) = [2., 3.];
+            *(beta.as_mut_handle() as *mut [f32; 2]) = [0., 1.];
+
+            hw.borrow_mut().batch_norm_f32(
+                x.as_handle(),
+                gamma.as_handle(),
+                beta.as_handle(),
+                y.as_mut_handle(),
+                2,
+                2,
+                1,
+                0.,
+            );
+            assert_eq!(*(y.as_handle() as *const [f32; 4]), [-2., -2., 2., 4.]);
+
+            hw.borrow_mut().batch_norm_statistics_f32(
+                x.as_handle(),
+                mean.as_mut_handle(),
+                variance.as_mut_handle(),
+                2,
+                2,
+                1,
+            );
+            assert_eq!(*(mean.as_handle() as *const [f32; 2]), [0., 5.]);
+            assert_eq!(*(variance.as_handle() as *const [f32; 2]), [1., 4.]);
+
+            hw.borrow_mut().fill_f32(y.as_mut_handle(), 0., 4);
+            hw.borrow_mut().batch_norm_inference_f32(
+                x.as_handle(),
+                mean.as_handle(),
+                variance.as_handle(),
+                gamma.as_handle(),
+                beta.as_handle(),
+                y.as_mut_handle(),
+                2,
+                2,
+                1,
+                0.,
+            );
+            assert_eq!(*(y.as_handle() as *const [f32; 4]), [-2., -2., 2., 4.]);
+        }
+    }
+
+    #[test]
+    fn test_batch_norm_backward_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        unsafe {
+            // Layout: [outer = 2, channels = 1, inner = 2]
+            let mut x = Buffer::raw(&hw, 4 * size_of::<f32>());
+            let mut gamma = Buffer::raw(&hw, size_of::<f32>());
+            let mut gy = Buffer::raw(&hw, 4 * size_of::<f32>());
+            let mut gx = Buffer::raw(&hw, 4 * size_of::<f32>());
+            *(x.as_mut_handle() as *mut [f32; 4]) = [-1., -1., 1., 1.];
+            *(gamma.as_mut_handle() as *mut f32) = 2.;
+            *(gy.as_mut_handle() as *mut [f32; 4]) = [1., 0., 0., 0.];
+            hw.borrow_mut().batch_norm_backward_f32(
+                x.as_handle(),
+                gamma.as_handle(),
+                gy.as_handle(),
+                gx.as_mut_handle(),
+                2,
+                1,
+                2,
+                0.,
+            );
+            assert_eq!(*(gx.as_handle() as *const [f32; 4]), [1., -1., 0., 0.]);
+        }
+    }
+
+    #[test]
+    fn test_elementwise_compare_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        let size = 4 * size_of::<f32>();
+        unsafe {
+            let mut lhs = Buffer::raw(&hw, size);
+            let mut rhs = Buffer::raw(&hw, size);
+            let mut dest = Buffer::raw(&hw, size);
+            *(lhs.as_mut_handle() as *mut [f32; 4]) = [1., 2., 3., f32::NAN];
+            *(rhs.as_mut_handle() as *mut [f32; 4]) = [2., 2., 2., f32::NAN];
+
+            hw.borrow_mut().elementwise_eq_f32(
+                lhs.as_handle(),
+                rhs.as_handle(),
+                dest.as_mut_handle(),
+                4,
+            );
+            assert_eq!(*(dest.as_handle() as *const [f32; 4]), [0., 1., 0., 0.]);
+
+            hw.borrow_mut().elementwise_lt_f32(
+                lhs.as_handle(),
+                rhs.as_handle(),
+                dest.as_mut_handle(),
+                4,
+            );
+            assert_eq!(*(dest.as_handle() as *const [f32; 4]), [1., 0., 0., 0.]);
+
+            hw.borrow_mut().elementwise_le_f32(
+                lhs.as_handle(),
+                rhs.as_handle(),
+                dest.as_mut_handle(),
+                4,
+            );
+            assert_eq!(*(dest.as_handle() as *const [f32; 4]), [1., 1., 0., 0.]);
+        }
+    }
+
+    #[test]
+    fn test_elementwise_minimum_maximum_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        let size = 4 * size_of::<f32>();
+        unsafe {
+            let mut lhs = Buffer::raw(&hw, size);
+            let mut rhs = Buffer::raw(&hw, size);
+            let mut dest = Buffer::raw(&hw, size);
+            *(lhs.as_mut_handle() as *mut [f32; 4]) = [1., -2., 3., f32::NAN];
+            *(rhs.as_mut_handle() as *mut [f32; 4]) = [2., -3., 3., 0.];
+
+            hw.borrow_mut().elementwise_minimum_f32(
+                lhs.as_handle(),
+                rhs.as_handle(),
+                dest.as_mut_handle(),
+                4,
+            );
+            let observed = *(dest.as_handle() as *const [f32; 4]);
+            assert_eq!(observed[..3], [1., -3., 3.]);
+            assert!(observed[3].is_nan());
+
+            hw.borrow_mut().elementwise_maximum_f32(
+                lhs.as_handle(),
+                rhs.as_handle(),
+                dest.as_mut_handle(),
+                4,
+            );
+            let observed = *(dest.as_handle() as *const [f32; 4]);
+            assert_eq!(observed[..3], [2., -2., 3.]);
+            assert!(observed[3].is_nan());
+        }
+    }
+
+    #[test]
+    fn test_elementwise_logical_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        let size = 4 * size_of::<f32>();
+        unsafe {
+            let mut lhs = Buffer::raw(&hw, size);
+            let mut rhs = Buffer::raw(&hw, size);
+            let mut dest = Buffer::raw(&hw, size);
+            *(lhs.as_mut_handle() as *mut [f32; 4]) = [0., 0., 2., -1.];
+            *(rhs.as_mut_handle() as *mut [f32; 4]) = [0., 1., 0., 0.5];
+
+            hw.borrow_mut().elementwise_logical_and_f32(
+                lhs.as_handle(),
+                rhs.as_handle(),
+                dest.as_mut_handle(),
+                4,
+            );
+            assert_eq!(*(dest.as_handle() as *const [f32; 4]), [0., 0., 0., 1.]);
+
+            hw.borrow_mut().elementwise_logical_or_f32(
+                lhs.as_handle(),
+                rhs.as_handle(),
+                dest.as_mut_handle(),
+                4,
+            );
+            assert_eq!(*(dest.as_handle() as *const [f32; 4]), [0., 1., 1., 1.]);
+
+            hw.borrow_mut()
+                .elementwise_logical_not_f32(lhs.as_handle(), dest.as_mut_handle(), 4);
+            assert_eq!(*(dest.as_handle() as *const [f32; 4]), [1., 1., 0., 0.]);
+        }
+    }
+
+    #[test]
+    fn test_elementwise_isnan_isinf_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        let size = 4 * size_of::<f32>();
+        unsafe {
+            let mut src = Buffer::raw(&hw, size);
+            let mut dest = Buffer::raw(&hw, size);
+            *(src.as_mut_handle() as *mut [f32; 4]) =
+                [1., f32::NAN, f32::INFINITY, f32::NEG_INFINITY];
+
+            hw.borrow_mut()
+                .elementwise_isnan_f32(src.as_handle(), dest.as_mut_handle(), 4);
+            assert_eq!(*(dest.as_handle() as *const [f32; 4]), [0., 1., 0., 0.]);
+
+            hw.borrow_mut()
+                .elementwise_isinf_f32(src.as_handle(), dest.as_mut_handle(), 4);
+            assert_eq!(*(dest.as_handle() as *const [f32; 4]), [0., 0., 1., 1.]);
+        }
+    }
+
+    #[test]
+    fn test_elementwise_where_f32() {
+        let hw = RefCell::new(CpuHardware::new());
+        let size = 4 * size_of::<f32>();
+        unsafe {
+            let mut cond = Buffer::raw(&hw, size);
+            let mut lhs = Buffer::raw(&hw, size);
+            let mut rhs = Buffer::raw(&hw, size);
+            let mut dest = Buffer::raw(&hw, size);
+            *(cond.as_mut_handle() as *mut [f32; 4]) = [1., 0., -2., 0.];
+            *(lhs.as_mut_handle() as *mut [f32; 4]) = [1., 2., 3., 4.];
+            *(rhs.as_mut_handle() as *mut [f32; 4]) = [5., 6., 7., 8.];
+            hw.borrow_mut().elementwise_where_f32(
+                cond.as_handle(),
+                lhs.as_handle(),
+                rhs.as_handle(),
+                dest.as_mut_handle(),
+                4,
+            );
+            assert_eq!(*(dest.as_handle() as *const [f32; 4]), [1., 6., 3., 8.]);
         }
     }
 }
@@ -0,0 +1,163 @@
+use crate::error::Error;
+use crate::result::Result;
+use std::cell::Cell;
+use std::fs::File;
+
+/// Contents of a file placed on the host memory.
+///
+/// On 64-bit Linux and macOS, the file is mapped by `mmap()` with private (copy-on-write) pages:
+/// the contents are loaded lazily by the OS, and writes to the memory never reach the file.
+/// On other platforms, the whole file is read into an allocated memory.
+///
+/// The memory is aligned to at least 8 bytes in both cases.
+pub(crate) enum MappedFile {
+    /// Pages mapped by `mmap()`.
+    #[cfg(all(
+        any(target_os = "linux", target_os = "macos"),
+        target_pointer_width = "64"
+    ))]
+    Mapped {
+        /// Pointer to the beginning of the pages.
+        ptr: *mut u8,
+
+        /// Size in bytes of the contents.
+        len: usize,
+    },
+
+    /// Allocated memory holding the whole contents. `Cell` allows writes through `as_mut_ptr()`.
+    Owned {
+        /// Memory holding the contents, padded to a multiple of 8 bytes.
+        data: Vec<Cell<u64>>,
+
+        /// Size in bytes of the contents.
+        len: usize,
+    },
+}
+
+#[cfg(all(
+    any(target_os = "linux", target_os = "macos"),
+    target_pointer_width = "64"
+))]
+mod sys {
+    use std::os::raw::{c_int, c_void};
+
+    // Both Linux and macOS use the same values.
+    pub(super) const PROT_READ: c_int = 1;
+    pub(super) const PROT_WRITE: c_int = 2;
+    pub(super) const MAP_PRIVATE: c_int = 2;
+    pub(super) const MAP_FAILED: *mut c_void = !0usize as *mut c_void;
+
+    extern "C" {
+        pub(super) fn mmap(
+            addr: *mut c_void,
+            len: usize,
+            prot: c_int,
+            flags: c_int,
+            fd: c_int,
+            offset: i64,
+        ) -> *mut c_void;
+        pub(super) fn munmap(addr: *mut c_void, len: usize) -> c_int;
+    }
+}
+
+impl MappedFile {
+    /// Places the contents of a file on the memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - File to be mapped. The file is no longer required after this call.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(MappedFile)` - A new `MappedFile` object.
+    /// * `Err(Error)` - Mapping or reading the file failed.
+    ///
+    /// # Safety
+    ///
+    /// Modifying the file while the mapping is alive results in undefined contents of the
+    /// mapped memory.
+    pub(crate) unsafe fn new(file: &File) -> Result<Self> {
+        let len = usize::try_from(file.metadata().map_err(|e| Error::Io(e.to_string()))?.len())
+            .map_err(|_| Error::NotSupported("File is too large to be mapped.".to_string()))?;
+
+        #[cfg(all(
+            any(target_os = "linux", target_os = "macos"),
+            target_pointer_width = "64"
+        ))]
+        if len > 0 {
+            use std::os::unix::io::AsRawFd;
+            let ptr = sys::mmap(
+                std::ptr::null_mut(),
+                len,
+                sys::PROT_READ | sys::PROT_WRITE,
+                sys::MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            );
+            if ptr == sys::MAP_FAILED {
+                return Err(Error::Io(std::io::Error::last_os_error().to_string()));
+            }
+            return Ok(Self::Mapped {
+                ptr: ptr as *mut u8,
+                len,
+            });
+        }
+
+        use std::io::Read;
+        let data = vec![Cell::new(0u64); len.div_ceil(8)];
+        let bytes = std::slice::from_raw_parts_mut(data.as_ptr() as *mut u8, len);
+        let mut reader = file;
+        reader
+            .read_exact(bytes)
+            .map_err(|e| Error::Io(e.to_string()))?;
+        Ok(Self::Owned { data, len })
+    }
+
+    /// Returns the contents as a slice.
+    ///
+    /// # Returns
+    ///
+    /// A slice of the whole contents.
+    pub(crate) fn as_slice(&self) -> &[u8] {
+        match self {
+            #[cfg(all(
+                any(target_os = "linux", target_os = "macos"),
+                target_pointer_width = "64"
+            ))]
+            Self::Mapped { ptr, len } => unsafe { std::slice::from_raw_parts(*ptr, *len) },
+            Self::Owned { data, len } => unsafe {
+                std::slice::from_raw_parts(data.as_ptr() as *const u8, *len)
+            },
+        }
+    }
+
+    /// Returns the pointer to the contents.
+    ///
+    /// # Returns
+    ///
+    /// Pointer to the beginning of the contents.
+    pub(crate) fn as_mut_ptr(&self) -> *mut u8 {
+        match self {
+            #[cfg(all(
+                any(target_os = "linux", target_os = "macos"),
+                target_pointer_width = "64"
+            ))]
+            Self::Mapped { ptr, .. } => *ptr,
+            Self::Owned { data, .. } => data.as_ptr() as *mut u8,
+        }
+    }
+}
+
+#[cfg(all(
+    any(target_os = "linux", target_os = "macos"),
+    target_pointer_width = "64"
+))]
+impl Drop for MappedFile {
+    fn drop(&mut self) {
+        if let Self::Mapped { ptr, len } = *self {
+            unsafe {
+                sys::munmap(ptr as *mut std::os::raw::c_void, len);
+            }
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use crate::array::Array;
+use crate::error::Error;
+use crate::hardware::Hardware;
+use crate::random::Rng;
+use crate::result::Result;
+use crate::shape::Shape;
+use std::cell::RefCell;
+
+/// Calculates fan-in and fan-out of a weight.
+///
+/// Weights are assumed to have the shape `[fan_out, fan_in, receptive...]`: the first dimension
+/// is the number of outputs, the second is the number of inputs, and the rest are the size of the
+/// receptive field (e.g., the kernel size of convolutions). 1-dimensional weights use their
+/// only dimension for both.
+///
+/// # Arguments
+///
+/// * `shape` - `Shape` of the weight.
+///
+/// # Returns
+///
+/// * `Ok((usize, usize))` - Fan-in and fan-out.
+/// * `Err(Error)` - `shape` is a scalar.
+pub fn fans(shape: &Shape) -> Result<(usize, usize)> {
+    match shape.num_dimensions() {
+        0 => Err(Error::InvalidShape(format!(
+            "Fans can not be calculated for the scalar shape {}.",
+            shape
+        ))),
+        1 => {
+            let size = shape.dimension(0)?;
+            Ok((size, size))
+        }
+        n => {
+            let receptive = (2..n)
+                .map(|i| shape.dimension(i))
+                .product::<Result<usize>>()?;
+            Ok((
+                shape.dimension(1)? * receptive,
+                shape.dimension(0)? * receptive,
+            ))
+        }
+    }
+}
+
+/// Generates a weight by the Xavier (Glorot) uniform initialization.
+///
+/// Values are drawn from U(-a, a) where a = gain * sqrt(6 / (fan_in + fan_out)).
+///
+/// # Arguments
+///
+/// * `rng` - Random number generator.
+/// * `hardware` - `Hardware` object to host the value.
+/// * `shape` - `Shape` of the weight. See `fans()` for the layout.
+/// * `gain` - Scaling factor, typically 1.
+///
+/// # Returns
+///
+/// * `Ok(Array)` - A new `Array` object.
+/// * `Err(Error)` - `shape` is a scalar.
+pub fn xavier_uniform<'hw>(
+    rng: &mut Rng,
+    hardware: &'hw RefCell<dyn Hardware>,
+    shape: Shape,
+    gain: f32,
+) -> Result<Array<'hw>> {
+    let (fan_in, fan_out) = fans(&shape)?;
+    let a = gain * (6. / (fan_in + fan_out) as f32).sqrt();
+    Ok(rng.uniform_f32(hardware, shape, -a, a))
+}
+
+/// Generates a weight by the Xavier (Glorot) normal initialization.
+///
+/// Values are drawn from N(0, std^2) where std = gain * sqrt(2 / (fan_in + fan_out)).
+///
+/// # Arguments
+///
+/// * `rng` - Random number generator.
+/// * `hardware` - `Hardware` object to host the value.
+/// * `shape` - `Shape` of the weight. See `fans()` for the layout.
+/// * `gain` - Scaling factor, typically 1.
+///
+/// # Returns
+///
+/// * `Ok(Array)` - A new `Array` object.
+/// * `Err(Error)` - `shape` is a scalar.
+pub fn xavier_normal<'hw>(
+    rng: &mut Rng,
+    hardware: &'hw RefCell<dyn Hardware>,
+    shape: Shape,
+    gain: f32,
+) -> Result<Array<'hw>> {
+    let (fan_in, fan_out) = fans(&shape)?;
+    let stddev = gain * (2. / (fan_in + fan_out) as f32).sqrt();
+    Ok(rng.normal_f32(hardware, shape, 0., stddev))
+}
+
+/// Generates a weight by the He (Kaiming) uniform initialization.
+///
+/// Values are drawn from U(-a, a) where a = gain * sqrt(3 / fan_in).
+///
+/// # Arguments
+///
+/// * `rng` - Random number generator.
+/// * `hardware` - `Hardware` object to host the value.
+/// * `shape` - `Shape` of the weight. See `fans()` for the layout.
+/// * `gain` - Scaling factor, typically sqrt(2) for ReLU.
+///
+/// # Returns
+///
+/// * `Ok(Array)` - A new `Array` object.
+/// * `Err(Error)` - `shape` is a scalar.
+pub fn he_uniform<'hw>(
+    rng: &mut Rng,
+    hardware: &'hw RefCell<dyn Hardware>,
+    shape: Shape,
+    gain: f32,
+) -> Result<Array<'hw>> {
+    let (fan_in, _) = fans(&shape)?;
+    let a = gain * (3. / fan_in as f32).sqrt();
+    Ok(rng.uniform_f32(hardware, shape, -a, a))
+}
+
+/// Generates a weight by the He (Kaiming) normal initialization.
+///
+/// Values are drawn from N(0, std^2) where std = gain / sqrt(fan_in).
+///
+/// # Arguments
+///
+/// * `rng` - Random number generator.
+/// * `hardware` - `Hardware` object to host the value.
+/// * `shape` - `Shape` of the weight. See `fans()` for the layout.
+/// * `gain` - Scaling factor, typically sqrt(2) for ReLU.
+///
+/// # Returns
+///
+/// * `Ok(Array)` - A new `Array` object.
+/// * `Err(Error)` - `shape` is a scalar.
+pub fn he_normal<'hw>(
+    rng: &mut Rng,
+    hardware: &'hw RefCell<dyn Hardware>,
+    shape: Shape,
+    gain: f32,
+) -> Result<Array<'hw>> {
+    let (fan_in, _) = fans(&shape)?;
+    let stddev = gain / (fan_in as f32).sqrt();
+    Ok(rng.normal_f32(hardware, shape, 0., stddev))
+}
+
+/// Generates a weight by the orthogonal initialization.
+///
+/// The weight is treated as a matrix with `shape[0]` rows and the product of the remaining
+/// dimensions as columns. Rows (if there are no more rows than columns) or columns (otherwise)
+/// of the resulting matrix are orthonormal, scaled by `gain`.
+///
+/// # Arguments
+///
+/// * `rng` - Random number generator.
+/// * `hardware` - `Hardware` object to host the value.
+/// * `shape` - `Shape` of the weight.
+/// * `gain` - Scaling factor, typically 1.
+///
+/// # Returns
+///
+/// * `Ok(Array)` - A new `Array` object.
+/// * `Err(Error)` - `shape` has less than 2 dimensions.
+pub fn orthogonal<'hw>(
+    rng: &mut Rng,
+    hardware: &'hw RefCell<dyn Hardware>,
+    shape: Shape,
+    gain: f32,
+) -> Result<Array<'hw>> {
+    if shape.num_dimensions() < 2 {
+        return Err(Error::InvalidShape(format!(
+            "Orthogonal initialization requires at least 2 dimensions, but got {}.",
+            shape
+        )));
+    }
+    let rows = shape.dimension(0)?;
+    let cols = shape.num_elements() / rows.max(1);
+    let (num_vectors, length) = (rows.min(cols), rows.max(cols));
+
+    // Orthonormalizes random vectors by the modified Gram-Schmidt process.
+    let mut vectors = rng
+        .normal_f32(hardware, Shape::new([num_vectors, length]), 0., 1.)
+        .get_values_f32()
+        .into_iter()
+        .map(|x| x as f64)
+        .collect::<Vec<_>>();
+    for i in 0..num_vectors {
+        let (done, rest) = vectors.split_at_mut(i * length);
+        let v = &mut rest[..length];
+        for j in 0..i {
+            let u = &done[j * length..(j + 1) * length];
+            let dot = u.iter().zip(v.iter()).map(|(a, b)| a * b).sum::<f64>();
+            v.iter_mut().zip(u).for_each(|(b, a)| *b -= dot * a);
+        }
+        let norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+        v.iter_mut().for_each(|x| *x /= norm);
+    }
+
+    // Vectors are laid out as rows if rows <= cols, or as columns otherwise.
+    let values = (0..rows * cols)
+        .map(|k| {
+            let (r, c) = (k / cols, k % cols);
+            let x = if rows <= cols {
+                vectors[r * length + c]
+            } else {
+                vectors[c * length + r]
+            };
+            gain * x as f32
+        })
+        .collect::<Vec<_>>();
+    Array::constant_f32(hardware, shape, &values)
+}
+
+#[cfg(test)]
+mod tests;
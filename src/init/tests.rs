@@ -0,0 +1,146 @@
+use crate::hardware::cpu::CpuHardware;
+use crate::init::*;
+
+fn mean_and_variance(values: &[f32]) -> (f32, f32) {
+    let n = values.len() as f32;
+    let mean = values.iter().sum::<f32>() / n;
+    let variance = values.iter().map(|x| (x - mean) * (x - mean)).sum::<f32>() / n;
+    (mean, variance)
+}
+
+#[test]
+fn test_fans() {
+    assert_eq!(fans(&Shape::new([5])), Ok((5, 5)));
+    assert_eq!(fans(&Shape::new([3, 4])), Ok((4, 3)));
+    assert_eq!(fans(&Shape::new([8, 3, 5, 5])), Ok((75, 200)));
+    assert!(matches!(fans(&Shape::new([])), Err(Error::InvalidShape(_))));
+}
+
+#[test]
+fn test_xavier_uniform() {
+    let hw = RefCell::new(CpuHardware::new());
+    let mut rng = Rng::new(1);
+    let array = xavier_uniform(&mut rng, &hw, Shape::new([100, 200]), 2.).unwrap();
+    assert_eq!(array.shape(), &Shape::new([100, 200]));
+    // Values are in (-a, a) with variance a^2 / 3.
+    let a = 2. * (6f32 / 300.).sqrt();
+    let values = array.get_values_f32();
+    assert!(values.iter().all(|x| (-a..a).contains(x)));
+    let (mean, variance) = mean_and_variance(&values);
+    assert!(mean.abs() < 0.01);
+    assert!((variance - a * a / 3.).abs() < 0.002);
+}
+
+#[test]
+fn test_xavier_normal() {
+    let hw = RefCell::new(CpuHardware::new());
+    let mut rng = Rng::new(1);
+    let array = xavier_normal(&mut rng, &hw, Shape::new([100, 200]), 1.).unwrap();
+    let (mean, variance) = mean_and_variance(&array.get_values_f32());
+    assert!(mean.abs() < 0.01);
+    assert!((variance - 2. / 300.).abs() < 0.0005);
+}
+
+#[test]
+fn test_he_uniform() {
+    let hw = RefCell::new(CpuHardware::new());
+    let mut rng = Rng::new(1);
+    let array = he_uniform(&mut rng, &hw, Shape::new([100, 50, 2]), 2f32.sqrt()).unwrap();
+    // a = sqrt(2) * sqrt(3 / 100), variance = 2 / 100
+    let a = (6f32 / 100.).sqrt();
+    let values = array.get_values_f32();
+    assert!(values.iter().all(|x| (-a..a).contains(x)));
+    let (mean, variance) = mean_and_variance(&values);
+    assert!(mean.abs() < 0.01);
+    assert!((variance - 0.02).abs() < 0.002);
+}
+
+#[test]
+fn test_he_normal() {
+    let hw = RefCell::new(CpuHardware::new());
+    let mut rng = Rng::new(1);
+    let array = he_normal(&mut rng, &hw, Shape::new([100, 200]), 2f32.sqrt()).unwrap();
+    let (mean, variance) = mean_and_variance(&array.get_values_f32());
+    assert!(mean.abs() < 0.01);
+    assert!((variance - 0.01).abs() < 0.001);
+}
+
+#[test]
+fn test_initializers_scalar() {
+    let hw = RefCell::new(CpuHardware::new());
+    let mut rng = Rng::new(1);
+    assert!(xavier_uniform(&mut rng, &hw, Shape::new([]), 1.).is_err());
+    assert!(xavier_normal(&mut rng, &hw, Shape::new([]), 1.).is_err());
+    assert!(he_uniform(&mut rng, &hw, Shape::new([]), 1.).is_err());
+    assert!(he_normal(&mut rng, &hw, Shape::new([]), 1.).is_err());
+    // Failed initializations do not consume the stream.
+    assert_eq!(rng.offset(), 0);
+}
+
+#[test]
+fn test_initializers_reproducible() {
+    let hw = RefCell::new(CpuHardware::new());
+    let a = xavier_uniform(&mut Rng::new(7), &hw, Shape::new([3, 4]), 1.).unwrap();
+    let b = xavier_uniform(&mut Rng::new(7), &hw, Shape::new([3, 4]), 1.).unwrap();
+    assert_eq!(a.get_values_f32(), b.get_values_f32());
+}
+
+fn check_orthonormal(
+    values: &[f32],
+    num_vectors: usize,
+    get: impl Fn(usize, usize) -> f32,
+    length: usize,
+    gain: f32,
+) {
+    assert_eq!(values.len(), num_vectors * length);
+    for i in 0..num_vectors {
+        for j in 0..num_vectors {
+            let dot = (0..length).map(|k| get(i, k) * get(j, k)).sum::<f32>();
+            let expected = if i == j { gain * gain } else { 0. };
+            assert!((dot - expected).abs() < 1e-4, "({}, {}): {}", i, j, dot);
+        }
+    }
+}
+
+#[test]
+fn test_orthogonal_wide() {
+    let hw = RefCell::new(CpuHardware::new());
+    let array = orthogonal(&mut Rng::new(1), &hw, Shape::new([3, 5]), 2.).unwrap();
+    assert_eq!(array.shape(), &Shape::new([3, 5]));
+    let values = array.get_values_f32();
+    // Rows are orthogonal.
+    check_orthonormal(&values, 3, |i, k| values[i * 5 + k], 5, 2.);
+}
+
+#[test]
+fn test_orthogonal_tall() {
+    let hw = RefCell::new(CpuHardware::new());
+    let array = orthogonal(&mut Rng::new(1), &hw, Shape::new([6, 2, 2]), 1.).unwrap();
+    assert_eq!(array.shape(), &Shape::new([6, 2, 2]));
+    let values = array.get_values_f32();
+    // Columns of the 6x4 matrix are orthogonal.
+    check_orthonormal(&values, 4, |j, k| values[k * 4 + j], 6, 1.);
+}
+
+#[test]
+fn test_orthogonal_square() {
+    let hw = RefCell::new(CpuHardware::new());
+    let array = orthogonal(&mut Rng::new(1), &hw, Shape::new([4, 4]), 1.).unwrap();
+    let values = array.get_values_f32();
+    check_orthonormal(&values, 4, |i, k| values[i * 4 + k], 4, 1.);
+    check_orthonormal(&values, 4, |j, k| values[k * 4 + j], 4, 1.);
+}
+
+#[test]
+fn test_orthogonal_invalid_shape() {
+    let hw = RefCell::new(CpuHardware::new());
+    let mut rng = Rng::new(1);
+    assert!(matches!(
+        orthogonal(&mut rng, &hw, Shape::new([]), 1.),
+        Err(Error::InvalidShape(_))
+    ));
+    assert!(matches!(
+        orthogonal(&mut rng, &hw, Shape::new([3]), 1.),
+        Err(Error::InvalidShape(_))
+    ));
+}
@@ -1,9 +1,18 @@
 pub mod array;
 pub mod buffer;
+pub mod conv;
 pub mod error;
-mod graph;
+pub mod graph;
 pub mod hardware;
+pub mod init;
+pub mod nn;
 pub mod node;
-mod operator;
+pub mod onnx;
+pub mod operator;
+pub mod optim;
+pub mod parameter;
+pub mod random;
 pub mod result;
+pub mod safetensors;
+pub mod serialize;
 pub mod shape;
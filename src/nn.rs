@@ -0,0 +1,179 @@
+use crate::array::Array;
+use crate::graph::Graph;
+use crate::hardware::Hardware;
+use crate::node::Node;
+use crate::parameter::{Parameter, ParameterStore};
+use crate::random::Rng;
+use crate::result::Result;
+use crate::shape::Shape;
+use std::cell::RefCell;
+
+/// Interface of neural network layers, which own `Parameter`s and construct the forward
+/// computation on a `Graph`.
+///
+/// Since parameters persist across graphs, the same module can be applied to any number of
+/// graphs, e.g., one graph per training step or per input sequence.
+pub trait Module<'hw> {
+    /// Constructs the forward computation.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - `Graph` object to register the operations. Parameters are injected into this
+    ///   graph by `Node::parameter()`.
+    /// * `inputs` - Input `Node`s belonging to `graph`. The number and shapes of the inputs
+    ///   depend on each module.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the output of the module.
+    ///
+    /// # Panics
+    ///
+    /// `inputs` do not satisfy the requirements of the module.
+    fn forward<'op, 'g>(
+        &self,
+        graph: &'g RefCell<Graph<'hw, 'op>>,
+        inputs: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g>
+    where
+        'hw: 'op;
+
+    /// Returns all parameters owned by the module.
+    ///
+    /// # Returns
+    ///
+    /// List of `(name, parameter)` pairs. Parameters of submodules are named as
+    /// `"submodule.name"`.
+    fn parameters(&self) -> Vec<(String, Parameter<'hw>)>;
+
+    /// Registers all parameters owned by the module to a `ParameterStore`.
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - `ParameterStore` to register the parameters.
+    /// * `prefix` - Prefix of the names. If not empty, parameters are registered as
+    ///   `"prefix.name"`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - All parameters are registered.
+    /// * `Err(Error)` - Some name is already used in `store`.
+    fn register_parameters(&self, store: &mut ParameterStore<'hw>, prefix: &str) -> Result<()> {
+        for (name, parameter) in self.parameters() {
+            store.add(&join_name(prefix, &name), parameter)?;
+        }
+        Ok(())
+    }
+}
+
+/// Joins a prefix and a name of a parameter.
+fn join_name(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", prefix, name)
+    }
+}
+
+/// Adds a prefix to the names of parameters of a submodule.
+fn prefixed<'a, 'hw: 'a>(
+    prefix: &'a str,
+    parameters: Vec<(String, Parameter<'hw>)>,
+) -> impl Iterator<Item = (String, Parameter<'hw>)> + 'a {
+    parameters
+        .into_iter()
+        .map(move |(name, parameter)| (join_name(prefix, &name), parameter))
+}
+
+/// Creates a `Node` filled by a single value with the same shape and hardware as `like`.
+fn fill_like<'hw: 'op, 'op: 'g, 'g>(like: Node<'hw, 'op, 'g>, value: f32) -> Node<'hw, 'op, 'g> {
+    Node::fill(like.graph(), like.hardware(), like.shape(), value)
+}
+
+/// Checks the number of inputs passed to `Module::forward()`.
+fn check_num_inputs(name: &str, inputs: &[Node], expected: usize) {
+    assert_eq!(
+        inputs.len(),
+        expected,
+        "{} requires {} inputs, but got {}.",
+        name,
+        expected,
+        inputs.len()
+    );
+}
+
+/// Creates `num_states` zero-initialized states of the same shape.
+fn fill_zero_states<'hw: 'op, 'op: 'g, 'g>(
+    graph: &'g RefCell<Graph<'hw, 'op>>,
+    hardware: &'hw RefCell<dyn Hardware>,
+    shape: Shape,
+    num_states: usize,
+) -> Vec<Node<'hw, 'op, 'g>> {
+    (0..num_states)
+        .map(|_| Node::fill(graph, hardware, shape.clone(), 0.))
+        .collect()
+}
+
+/// Checks the number of states passed to `RecurrentCell::step()`.
+fn check_num_states(name: &str, state: &[Node], expected: usize) {
+    assert_eq!(
+        state.len(),
+        expected,
+        "{} requires {} states, but got {}.",
+        name,
+        expected,
+        state.len()
+    );
+}
+
+/// Affine transformations of a single gate: W_i x + b + W_h h.
+struct Gate<'hw> {
+    input: linear::Linear<'hw>,
+    hidden: linear::Linear<'hw>,
+}
+
+impl<'hw> Gate<'hw> {
+    fn new(
+        rng: &mut Rng,
+        hardware: &'hw RefCell<dyn Hardware>,
+        input_size: usize,
+        hidden_size: usize,
+    ) -> Self {
+        Self {
+            input: linear::Linear::new(rng, hardware, input_size, hidden_size, true),
+            hidden: linear::Linear::new(rng, hardware, hidden_size, hidden_size, false),
+        }
+    }
+
+    fn forward<'op, 'g>(
+        &self,
+        graph: &'g RefCell<Graph<'hw, 'op>>,
+        x: Node<'hw, 'op, 'g>,
+        h: Node<'hw, 'op, 'g>,
+    ) -> Node<'hw, 'op, 'g>
+    where
+        'hw: 'op,
+    {
+        self.input.forward(graph, &[x]) + self.hidden.forward(graph, &[h])
+    }
+
+    fn parameters(&self) -> Vec<(String, Parameter<'hw>)> {
+        prefixed("input", self.input.parameters())
+            .chain(prefixed("hidden", self.hidden.parameters()))
+            .collect()
+    }
+}
+
+pub mod batch_norm;
+pub mod embedding;
+pub mod gru_cell;
+pub mod layer_norm;
+pub mod linear;
+pub mod lstm_cell;
+pub mod mlp;
+pub mod recurrent;
+pub mod rms_norm;
+pub mod rnn_cell;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,260 @@
+use crate::hardware::Hardware;
+use crate::nn::*;
+use std::cell::Cell;
+
+/// Batch normalization over every dimension except the channel dimension (the second one):
+/// y = (x - mean) / sqrt(var + epsilon) * gamma + beta.
+///
+/// In the training mode, `mean` and `var` are the statistics of the current batch, and the running
+/// statistics are updated by the exponential moving average:
+///
+/// ```text
+/// running_mean = (1 - momentum) * running_mean + momentum * mean
+/// running_variance = (1 - momentum) * running_variance + momentum * unbiased_var
+/// ```
+///
+/// In the evaluation mode, the running statistics are used instead and not updated.
+///
+/// All of `gamma`, `beta` and the running statistics have the shape `[num_channels]`.
+pub struct BatchNorm<'hw> {
+    gamma: Parameter<'hw>,
+    beta: Parameter<'hw>,
+    running_mean: RefCell<Array<'hw>>,
+    running_variance: RefCell<Array<'hw>>,
+    momentum: f32,
+    epsilon: f32,
+    training: Cell<bool>,
+}
+
+impl<'hw> BatchNorm<'hw> {
+    /// Creates a new `BatchNorm` object in the training mode.
+    ///
+    /// `gamma` and the running variance are initialized by 1, and `beta` and the running mean are
+    /// initialized by 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `hardware` - `Hardware` object to host the parameters.
+    /// * `num_channels` - Size of the second dimension of inputs.
+    /// * `momentum` - Weight of the current batch in the running statistics, typically 0.1.
+    /// * `epsilon` - Small value to avoid zero division, typically 1e-5.
+    ///
+    /// # Returns
+    ///
+    /// A new `BatchNorm` object.
+    pub fn new(
+        hardware: &'hw RefCell<dyn Hardware>,
+        num_channels: usize,
+        momentum: f32,
+        epsilon: f32,
+    ) -> Self {
+        let shape = Shape::new([num_channels]);
+        Self {
+            gamma: Parameter::new(Array::fill_f32(hardware, shape.clone(), 1.)),
+            beta: Parameter::new(Array::fill_f32(hardware, shape.clone(), 0.)),
+            running_mean: RefCell::new(Array::fill_f32(hardware, shape.clone(), 0.)),
+            running_variance: RefCell::new(Array::fill_f32(hardware, shape, 1.)),
+            momentum,
+            epsilon,
+            training: Cell::new(true),
+        }
+    }
+
+    /// Returns the scaling parameter.
+    pub fn gamma(&self) -> &Parameter<'hw> {
+        &self.gamma
+    }
+
+    /// Returns the shifting parameter.
+    pub fn beta(&self) -> &Parameter<'hw> {
+        &self.beta
+    }
+
+    /// Returns a copy of the running mean.
+    pub fn running_mean(&self) -> Array<'hw> {
+        self.running_mean.borrow().clone()
+    }
+
+    /// Returns a copy of the running variance.
+    pub fn running_variance(&self) -> Array<'hw> {
+        self.running_variance.borrow().clone()
+    }
+
+    /// Switches between the training mode and the evaluation mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `training` - `true` for the training mode, `false` for the evaluation mode.
+    pub fn set_training(&self, training: bool) {
+        self.training.set(training);
+    }
+
+    /// Returns whether the module is in the training mode.
+    pub fn is_training(&self) -> bool {
+        self.training.get()
+    }
+
+    /// Updates the running statistics by the statistics of a batch.
+    fn update_running_statistics(&self, x: &Array<'hw>) {
+        let (mean, variance) = x.batch_norm_statistics_f32().unwrap();
+        let num_samples = x.shape().num_elements() / mean.shape().num_elements().max(1);
+
+        // The running variance tracks the unbiased estimate.
+        let correction = if num_samples > 1 {
+            num_samples as f32 / (num_samples - 1) as f32
+        } else {
+            1.
+        };
+        let mut unbiased = variance;
+        unbiased
+            .mul_assign_f32(&Array::fill_f32(
+                unbiased.hardware(),
+                unbiased.shape().clone(),
+                correction,
+            ))
+            .unwrap();
+
+        for (running, batch) in [
+            (&self.running_mean, mean),
+            (&self.running_variance, unbiased),
+        ] {
+            let mut running = running.borrow_mut();
+            let delta = batch.elementwise_sub_f32(&running).unwrap();
+            running.axpy_f32(self.momentum, &delta).unwrap();
+        }
+    }
+}
+
+impl<'hw> Module<'hw> for BatchNorm<'hw> {
+    /// Constructs the forward computation.
+    ///
+    /// `inputs` must be a single `Node` of the shape `[batch_size, num_channels, ...]`, and the
+    /// output has the same shape.
+    ///
+    /// In the training mode, this function calculates the value of the input immediately to
+    /// update the running statistics.
+    fn forward<'op, 'g>(
+        &self,
+        graph: &'g RefCell<Graph<'hw, 'op>>,
+        inputs: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g>
+    where
+        'hw: 'op,
+    {
+        check_num_inputs("BatchNorm", inputs, 1);
+        let x = inputs[0];
+        let gamma = Node::parameter(graph, &self.gamma);
+        let beta = Node::parameter(graph, &self.beta);
+        if self.is_training() {
+            let y = x.batch_norm(gamma, beta, self.epsilon);
+            self.update_running_statistics(&x.calculate());
+            y
+        } else {
+            let mean = Node::constant(graph, self.running_mean());
+            let variance = Node::constant(graph, self.running_variance());
+            x.batch_norm_inference(mean, variance, gamma, beta, self.epsilon)
+        }
+    }
+
+    fn parameters(&self) -> Vec<(String, Parameter<'hw>)> {
+        vec![
+            ("gamma".to_string(), self.gamma.clone()),
+            ("beta".to_string(), self.beta.clone()),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hardware::cpu::CpuHardware;
+    use crate::nn::batch_norm::*;
+    use crate::node::IntoNode;
+
+    fn assert_close(observed: Vec<f32>, expected: &[f32]) {
+        assert_eq!(observed.len(), expected.len());
+        for (o, e) in observed.iter().zip(expected) {
+            assert!((o - e).abs() < 1e-4, "{:?} != {:?}", observed, expected);
+        }
+    }
+
+    #[test]
+    fn test_new() {
+        let hw = RefCell::new(CpuHardware::new());
+        let norm = BatchNorm::new(&hw, 3, 0.1, 1e-5);
+        assert!(norm.is_training());
+        assert_eq!(norm.gamma().value().get_values_f32(), vec![1., 1., 1.]);
+        assert_eq!(norm.beta().value().get_values_f32(), vec![0., 0., 0.]);
+        assert_eq!(norm.running_mean().get_values_f32(), vec![0., 0., 0.]);
+        assert_eq!(norm.running_variance().get_values_f32(), vec![1., 1., 1.]);
+        assert_eq!(norm.parameters().len(), 2);
+    }
+
+    #[test]
+    fn test_forward_training() {
+        let hw = RefCell::new(CpuHardware::new());
+        let g = RefCell::new(Graph::new());
+        let norm = BatchNorm::new(&hw, 2, 0.5, 0.);
+
+        // Channel 0: [1, 3] (mean 2, var 1), channel 1: [0, 8] (mean 4, var 16)
+        let x = (Shape::new([2, 2]), vec![1f32, 0., 3., 8.]).into_node(&g, &hw);
+        let y = norm.forward(&g, &[x]);
+        assert_eq!(y.shape(), Shape::new([2, 2]));
+        assert_close(y.calculate().get_values_f32(), &[-1., -1., 1., 1.]);
+
+        // Unbiased variances are [2, 32].
+        assert_close(norm.running_mean().get_values_f32(), &[1., 2.]);
+        assert_close(norm.running_variance().get_values_f32(), &[1.5, 16.5]);
+
+        let _ = norm.forward(&g, &[x]);
+        assert_close(norm.running_mean().get_values_f32(), &[1.5, 3.]);
+        assert_close(norm.running_variance().get_values_f32(), &[1.75, 24.25]);
+    }
+
+    #[test]
+    fn test_forward_evaluation() {
+        let hw = RefCell::new(CpuHardware::new());
+        let g = RefCell::new(Graph::new());
+        let norm = BatchNorm::new(&hw, 2, 0.5, 0.);
+        let x = (Shape::new([2, 2, 1]), vec![1f32, 0., 3., 8.]).into_node(&g, &hw);
+        let _ = norm.forward(&g, &[x]);
+
+        norm.set_training(false);
+        assert!(!norm.is_training());
+        let y = norm.forward(&g, &[x]);
+
+        // running_mean = [1, 2], running_variance = [1.5, 16.5]
+        let expected = [
+            0.,
+            -2. / 16.5f32.sqrt(),
+            2. / 1.5f32.sqrt(),
+            6. / 16.5f32.sqrt(),
+        ];
+        assert_close(y.calculate().get_values_f32(), &expected);
+
+        // The running statistics are not updated in the evaluation mode.
+        assert_close(norm.running_mean().get_values_f32(), &[1., 2.]);
+    }
+
+    #[test]
+    fn test_gradient() {
+        let hw = RefCell::new(CpuHardware::new());
+        let g = RefCell::new(Graph::new());
+        let norm = BatchNorm::new(&hw, 2, 0.1, 0.);
+        let mut store = ParameterStore::new();
+        norm.register_parameters(&mut store, "norm").unwrap();
+
+        let x = (Shape::new([2, 2]), vec![1f32, 0., 3., 8.]).into_node(&g, &hw);
+        let w = (Shape::new([2, 2]), vec![1f32, 1., 0., 2.]).into_node(&g, &hw);
+        store.accumulate_gradients(norm.forward(&g, &[x]) * w);
+
+        // Normalized values are [[-1, -1], [1, 1]].
+        assert_close(
+            store.get("norm.gamma").unwrap().gradient().get_values_f32(),
+            &[-1., 1.],
+        );
+        assert_close(
+            store.get("norm.beta").unwrap().gradient().get_values_f32(),
+            &[1., 3.],
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use crate::hardware::Hardware;
+use crate::nn::*;
+use crate::random::Rng;
+
+/// Lookup table of embedding vectors.
+///
+/// The weight has the shape `[num_embeddings, embedding_size]`.
+pub struct Embedding<'hw> {
+    weight: Parameter<'hw>,
+}
+
+impl<'hw> Embedding<'hw> {
+    /// Creates a new `Embedding` object.
+    ///
+    /// The weight is initialized by N(0, 1).
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - Random number generator for the initialization.
+    /// * `hardware` - `Hardware` object to host the parameters.
+    /// * `num_embeddings` - Number of entries in the table.
+    /// * `embedding_size` - Size of each embedding vector.
+    ///
+    /// # Returns
+    ///
+    /// A new `Embedding` object.
+    pub fn new(
+        rng: &mut Rng,
+        hardware: &'hw RefCell<dyn Hardware>,
+        num_embeddings: usize,
+        embedding_size: usize,
+    ) -> Self {
+        let shape = Shape::new([num_embeddings, embedding_size]);
+        Self {
+            weight: Parameter::new(rng.normal_f32(hardware, shape, 0., 1.)),
+        }
+    }
+
+    /// Returns the weight parameter.
+    pub fn weight(&self) -> &Parameter<'hw> {
+        &self.weight
+    }
+}
+
+impl<'hw> Module<'hw> for Embedding<'hw> {
+    /// Constructs the forward computation.
+    ///
+    /// `inputs` must be a single `Node` of integer indices with an arbitrary shape, and the output
+    /// has the same shape followed by `embedding_size`.
+    fn forward<'op, 'g>(
+        &self,
+        graph: &'g RefCell<Graph<'hw, 'op>>,
+        inputs: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g>
+    where
+        'hw: 'op,
+    {
+        check_num_inputs("Embedding", inputs, 1);
+        Node::parameter(graph, &self.weight).gather_rows(inputs[0])
+    }
+
+    fn parameters(&self) -> Vec<(String, Parameter<'hw>)> {
+        vec![("weight".to_string(), self.weight.clone())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array::IntoArray;
+    use crate::hardware::cpu::CpuHardware;
+    use crate::nn::embedding::*;
+    use crate::node::IntoNode;
+
+    #[test]
+    fn test_new() {
+        let hw = RefCell::new(CpuHardware::new());
+        let embedding = Embedding::new(&mut Rng::new(1), &hw, 5, 3);
+        assert_eq!(embedding.weight().shape(), Shape::new([5, 3]));
+        assert_eq!(embedding.parameters().len(), 1);
+    }
+
+    #[test]
+    fn test_forward_and_gradient() {
+        let hw = RefCell::new(CpuHardware::new());
+        let g = RefCell::new(Graph::new());
+        let embedding = Embedding::new(&mut Rng::new(1), &hw, 3, 2);
+        embedding
+            .weight()
+            .set_value((Shape::new([3, 2]), vec![1f32, 2., 3., 4., 5., 6.]).into_array(&hw))
+            .unwrap();
+        let mut store = ParameterStore::new();
+        embedding.register_parameters(&mut store, "").unwrap();
+
+        let indices = (Shape::new([2, 2]), vec![2f32, 0., 2., 2.]).into_node(&g, &hw);
+        let y = embedding.forward(&g, &[indices]);
+        assert_eq!(y.shape(), Shape::new([2, 2, 2]));
+        assert_eq!(
+            y.calculate().get_values_f32(),
+            vec![5., 6., 1., 2., 5., 6., 5., 6.]
+        );
+
+        // Each row receives the number of its occurrences.
+        store.accumulate_gradients(y);
+        assert_eq!(
+            store.get("weight").unwrap().gradient().get_values_f32(),
+            vec![1., 1., 0., 0., 3., 3.]
+        );
+    }
+}
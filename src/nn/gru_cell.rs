@@ -0,0 +1,191 @@
+use crate::hardware::Hardware;
+use crate::nn::recurrent::RecurrentCell;
+use crate::nn::*;
+use crate::random::Rng;
+
+/// Gated recurrent unit.
+///
+/// ```text
+/// r = sigmoid(W_ir x + b_r + W_hr h)
+/// z = sigmoid(W_iz x + b_z + W_hz h)
+/// n = tanh(W_in x + b_n + r * (W_hn h))
+/// h' = (1 - z) * n + z * h
+/// ```
+pub struct GruCell<'hw> {
+    reset_gate: Gate<'hw>,
+    update_gate: Gate<'hw>,
+    new_gate: Gate<'hw>,
+    hidden_size: usize,
+}
+
+impl<'hw> GruCell<'hw> {
+    /// Creates a new `GruCell` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - Random number generator for the initialization.
+    /// * `hardware` - `Hardware` object to host the parameters.
+    /// * `input_size` - Number of input features.
+    /// * `hidden_size` - Number of features of the hidden state.
+    ///
+    /// # Returns
+    ///
+    /// A new `GruCell` object.
+    pub fn new(
+        rng: &mut Rng,
+        hardware: &'hw RefCell<dyn Hardware>,
+        input_size: usize,
+        hidden_size: usize,
+    ) -> Self {
+        Self {
+            reset_gate: Gate::new(rng, hardware, input_size, hidden_size),
+            update_gate: Gate::new(rng, hardware, input_size, hidden_size),
+            new_gate: Gate::new(rng, hardware, input_size, hidden_size),
+            hidden_size,
+        }
+    }
+}
+
+impl<'hw> RecurrentCell<'hw> for GruCell<'hw> {
+    fn hidden_size(&self) -> usize {
+        self.hidden_size
+    }
+
+    /// Creates a zero-initialized state: `[h]`.
+    fn zero_states<'op, 'g>(
+        &self,
+        graph: &'g RefCell<Graph<'hw, 'op>>,
+        batch_size: usize,
+    ) -> Vec<Node<'hw, 'op, 'g>>
+    where
+        'hw: 'op,
+    {
+        let hardware = self.reset_gate.input.weight().hardware();
+        fill_zero_states(
+            graph,
+            hardware,
+            Shape::new([batch_size, self.hidden_size]),
+            1,
+        )
+    }
+
+    /// Constructs the computation of a single time step. `state` must be `[h]`.
+    fn step<'op, 'g>(
+        &self,
+        graph: &'g RefCell<Graph<'hw, 'op>>,
+        x: Node<'hw, 'op, 'g>,
+        state: &[Node<'hw, 'op, 'g>],
+    ) -> Vec<Node<'hw, 'op, 'g>>
+    where
+        'hw: 'op,
+    {
+        check_num_states("GruCell", state, 1);
+        vec![self.forward(graph, &[x, state[0]])]
+    }
+}
+
+impl<'hw> Module<'hw> for GruCell<'hw> {
+    /// Constructs the computation of a single time step.
+    ///
+    /// `inputs` must be `[x, h]`, where `x` is of the shape `[batch_size, input_size]` and `h` is
+    /// of the shape `[batch_size, hidden_size]`. The output is the next hidden state.
+    fn forward<'op, 'g>(
+        &self,
+        graph: &'g RefCell<Graph<'hw, 'op>>,
+        inputs: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g>
+    where
+        'hw: 'op,
+    {
+        check_num_inputs("GruCell", inputs, 2);
+        let (x, h) = (inputs[0], inputs[1]);
+        let r = self.reset_gate.forward(graph, x, h).sigmoid();
+        let z = self.update_gate.forward(graph, x, h).sigmoid();
+        let n = (self.new_gate.input.forward(graph, &[x])
+            + r * self.new_gate.hidden.forward(graph, &[h]))
+        .tanh();
+        (fill_like(z, 1.) - z) * n + z * h
+    }
+
+    fn parameters(&self) -> Vec<(String, Parameter<'hw>)> {
+        prefixed("reset_gate", self.reset_gate.parameters())
+            .chain(prefixed("update_gate", self.update_gate.parameters()))
+            .chain(prefixed("new_gate", self.new_gate.parameters()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array::Array;
+    use crate::hardware::cpu::CpuHardware;
+    use crate::nn::gru_cell::*;
+    use crate::node::IntoNode;
+
+    fn sigmoid(x: f32) -> f32 {
+        1. / (1. + (-x).exp())
+    }
+
+    #[test]
+    fn test_new() {
+        let hw = RefCell::new(CpuHardware::new());
+        let cell = GruCell::new(&mut Rng::new(1), &hw, 3, 2);
+        assert_eq!(cell.hidden_size(), 2);
+        let parameters = cell.parameters();
+        assert_eq!(parameters.len(), 9);
+        assert_eq!(parameters[0].0, "reset_gate.input.weight");
+        assert_eq!(parameters[0].1.shape(), Shape::new([2, 3]));
+        assert_eq!(parameters[1].0, "reset_gate.input.bias");
+        assert_eq!(parameters[2].0, "reset_gate.hidden.weight");
+        assert_eq!(parameters[2].1.shape(), Shape::new([2, 2]));
+        assert_eq!(parameters[8].0, "new_gate.hidden.weight");
+    }
+
+    #[test]
+    fn test_zero_states() {
+        let hw = RefCell::new(CpuHardware::new());
+        let g = RefCell::new(Graph::new());
+        let cell = GruCell::new(&mut Rng::new(1), &hw, 3, 2);
+        let state = cell.zero_states(&g, 4);
+        assert_eq!(state.len(), 1);
+        assert_eq!(state[0].shape(), Shape::new([4, 2]));
+        assert_eq!(state[0].calculate().get_values_f32(), vec![0.; 8]);
+    }
+
+    #[test]
+    fn test_step() {
+        let hw = RefCell::new(CpuHardware::new());
+        let g = RefCell::new(Graph::new());
+
+        // W_i* = 1, b_* = 0, W_h* = 0.5
+        let cell = GruCell::new(&mut Rng::new(1), &hw, 1, 1);
+        for (name, parameter) in cell.parameters() {
+            let value = if name.ends_with("input.weight") {
+                1.
+            } else if name.ends_with("bias") {
+                0.
+            } else {
+                0.5
+            };
+            parameter
+                .set_value(Array::fill_f32(&hw, parameter.shape(), value))
+                .unwrap();
+        }
+
+        let x = (Shape::new([1, 1]), vec![1f32]).into_node(&g, &hw);
+        let h = (Shape::new([1, 1]), vec![2f32]).into_node(&g, &hw);
+        let state = cell.step(&g, x, &[h]);
+        assert_eq!(state.len(), 1);
+
+        // r and z receive 1 * 1 + 0.5 * 2 = 2.
+        let s = sigmoid(2.);
+        let n = (1. + s * 1.).tanh();
+        let expected = (1. - s) * n + s * 2.;
+        let observed = state[0].calculate().get_values_f32()[0];
+        assert!((observed - expected).abs() < 1e-5);
+        assert_eq!(
+            cell.forward(&g, &[x, h]).calculate().get_values_f32(),
+            vec![observed]
+        );
+    }
+}
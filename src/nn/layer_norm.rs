@@ -0,0 +1,166 @@
+use crate::hardware::Hardware;
+use crate::nn::*;
+
+/// Layer normalization over the last dimension:
+/// y = (x - mean(x)) / sqrt(var(x) + epsilon) * gamma + beta.
+///
+/// Both `gamma` and `beta` have the shape `[normalized_size]`.
+pub struct LayerNorm<'hw> {
+    gamma: Parameter<'hw>,
+    beta: Parameter<'hw>,
+    epsilon: f32,
+}
+
+impl<'hw> LayerNorm<'hw> {
+    /// Creates a new `LayerNorm` object.
+    ///
+    /// `gamma` is initialized by 1, and `beta` is initialized by 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `hardware` - `Hardware` object to host the parameters.
+    /// * `normalized_size` - Size of the last dimension of inputs.
+    /// * `epsilon` - Small value to avoid zero division, typically 1e-5.
+    ///
+    /// # Returns
+    ///
+    /// A new `LayerNorm` object.
+    pub fn new(hardware: &'hw RefCell<dyn Hardware>, normalized_size: usize, epsilon: f32) -> Self {
+        let shape = Shape::new([normalized_size]);
+        Self {
+            gamma: Parameter::new(Array::fill_f32(hardware, shape.clone(), 1.)),
+            beta: Parameter::new(Array::fill_f32(hardware, shape, 0.)),
+            epsilon,
+        }
+    }
+
+    /// Returns the scaling parameter.
+    pub fn gamma(&self) -> &Parameter<'hw> {
+        &self.gamma
+    }
+
+    /// Returns the shifting parameter.
+    pub fn beta(&self) -> &Parameter<'hw> {
+        &self.beta
+    }
+}
+
+impl<'hw> Module<'hw> for LayerNorm<'hw> {
+    /// Constructs the forward computation.
+    ///
+    /// `inputs` must be a single `Node` whose last dimension is `normalized_size`, and the output
+    /// has the same shape.
+    fn forward<'op, 'g>(
+        &self,
+        graph: &'g RefCell<Graph<'hw, 'op>>,
+        inputs: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g>
+    where
+        'hw: 'op,
+    {
+        check_num_inputs("LayerNorm", inputs, 1);
+        inputs[0].layer_norm(
+            Node::parameter(graph, &self.gamma),
+            Node::parameter(graph, &self.beta),
+            self.epsilon,
+        )
+    }
+
+    fn parameters(&self) -> Vec<(String, Parameter<'hw>)> {
+        vec![
+            ("gamma".to_string(), self.gamma.clone()),
+            ("beta".to_string(), self.beta.clone()),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array::IntoArray;
+    use crate::hardware::cpu::CpuHardware;
+    use crate::nn::layer_norm::*;
+    use crate::node::IntoNode;
+
+    fn assert_close(observed: Vec<f32>, expected: &[f32]) {
+        assert_eq!(observed.len(), expected.len());
+        for (o, e) in observed.iter().zip(expected) {
+            assert!((o - e).abs() < 1e-4, "{:?} != {:?}", observed, expected);
+        }
+    }
+
+    #[test]
+    fn test_new() {
+        let hw = RefCell::new(CpuHardware::new());
+        let norm = LayerNorm::new(&hw, 3, 1e-5);
+        assert_eq!(norm.gamma().value().get_values_f32(), vec![1., 1., 1.]);
+        assert_eq!(norm.beta().value().get_values_f32(), vec![0., 0., 0.]);
+        assert_eq!(norm.parameters().len(), 2);
+    }
+
+    #[test]
+    fn test_forward() {
+        let hw = RefCell::new(CpuHardware::new());
+        let g = RefCell::new(Graph::new());
+        let norm = LayerNorm::new(&hw, 2, 0.);
+        norm.gamma()
+            .set_value(vec![1f32, 2.].into_array(&hw))
+            .unwrap();
+        norm.beta()
+            .set_value(vec![0f32, 10.].into_array(&hw))
+            .unwrap();
+
+        // Each row is normalized into [-1, 1] independently.
+        let x = (Shape::new([2, 2]), vec![1f32, 3., 10., 0.]).into_node(&g, &hw);
+        let y = norm.forward(&g, &[x]);
+        assert_eq!(y.shape(), Shape::new([2, 2]));
+        assert_close(y.calculate().get_values_f32(), &[-1., 12., 1., 8.]);
+    }
+
+    #[test]
+    fn test_forward_1d() {
+        let hw = RefCell::new(CpuHardware::new());
+        let g = RefCell::new(Graph::new());
+        let norm = LayerNorm::new(&hw, 4, 0.);
+
+        // mean = 2.5, var = 1.25
+        let x = vec![1f32, 2., 3., 4.].into_node(&g, &hw);
+        let y = norm.forward(&g, &[x]);
+        let s = 1.25f32.sqrt();
+        assert_close(
+            y.calculate().get_values_f32(),
+            &[-1.5 / s, -0.5 / s, 0.5 / s, 1.5 / s],
+        );
+    }
+
+    #[test]
+    fn test_gradient() {
+        let hw = RefCell::new(CpuHardware::new());
+        let g = RefCell::new(Graph::new());
+        let norm = LayerNorm::new(&hw, 2, 0.);
+        let mut store = ParameterStore::new();
+        norm.register_parameters(&mut store, "norm").unwrap();
+
+        let x = (Shape::new([2, 2]), vec![1f32, 3., 10., 0.]).into_node(&g, &hw);
+        let y = norm.forward(&g, &[x]);
+        let w = (Shape::new([2, 2]), vec![1f32, 0., 0., 1.]).into_node(&g, &hw);
+        store.accumulate_gradients(y * w);
+
+        // Normalized values are [[-1, 1], [1, -1]].
+        assert_close(
+            store.get("norm.gamma").unwrap().gradient().get_values_f32(),
+            &[-1., -1.],
+        );
+        assert_close(
+            store.get("norm.beta").unwrap().gradient().get_values_f32(),
+            &[1., 1.],
+        );
+
+        // The output is invariant to shifting the input, so that the gradient of each row sums up
+        // to 0.
+        let gx = crate::node::grad(y * w, &[x])[0]
+            .calculate()
+            .get_values_f32();
+        assert!((gx[0] + gx[1]).abs() < 1e-5);
+        assert!((gx[2] + gx[3]).abs() < 1e-5);
+    }
+}
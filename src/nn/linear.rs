@@ -0,0 +1,196 @@
+use crate::hardware::Hardware;
+use crate::nn::*;
+use crate::random::Rng;
+
+/// Fully-connected layer: y = x @ weight^T + bias.
+///
+/// The weight has the shape `[out_features, in_features]`, and the bias has the shape
+/// `[out_features]`.
+pub struct Linear<'hw> {
+    weight: Parameter<'hw>,
+    bias: Option<Parameter<'hw>>,
+}
+
+impl<'hw> Linear<'hw> {
+    /// Creates a new `Linear` object.
+    ///
+    /// Both the weight and the bias are initialized by U(-k, k) where k = 1 / sqrt(in_features).
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - Random number generator for the initialization.
+    /// * `hardware` - `Hardware` object to host the parameters.
+    /// * `in_features` - Number of input features.
+    /// * `out_features` - Number of output features.
+    /// * `bias` - Whether to use the bias or not.
+    ///
+    /// # Returns
+    ///
+    /// A new `Linear` object.
+    pub fn new(
+        rng: &mut Rng,
+        hardware: &'hw RefCell<dyn Hardware>,
+        in_features: usize,
+        out_features: usize,
+        bias: bool,
+    ) -> Self {
+        let k = 1. / (in_features.max(1) as f32).sqrt();
+        let weight = rng.uniform_f32(hardware, Shape::new([out_features, in_features]), -k, k);
+        let bias = bias.then(|| rng.uniform_f32(hardware, Shape::new([out_features]), -k, k));
+        Self {
+            weight: Parameter::new(weight),
+            bias: bias.map(Parameter::new),
+        }
+    }
+
+    /// Returns the weight parameter.
+    pub fn weight(&self) -> &Parameter<'hw> {
+        &self.weight
+    }
+
+    /// Returns the bias parameter if exists.
+    pub fn bias(&self) -> Option<&Parameter<'hw>> {
+        self.bias.as_ref()
+    }
+}
+
+impl<'hw> Module<'hw> for Linear<'hw> {
+    /// Constructs the forward computation.
+    ///
+    /// `inputs` must be a single `Node` of the shape `[batch_size, in_features]`, and the output
+    /// has the shape `[batch_size, out_features]`.
+    fn forward<'op, 'g>(
+        &self,
+        graph: &'g RefCell<Graph<'hw, 'op>>,
+        inputs: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g>
+    where
+        'hw: 'op,
+    {
+        check_num_inputs("Linear", inputs, 1);
+        let x = inputs[0];
+        let y = x.matmul(Node::parameter(graph, &self.weight).transpose());
+        match &self.bias {
+            Some(bias) => {
+                let batch_size = x.shape().dimension(0).unwrap();
+                y + Node::parameter(graph, bias).expand_axis(0, batch_size)
+            }
+            None => y,
+        }
+    }
+
+    fn parameters(&self) -> Vec<(String, Parameter<'hw>)> {
+        let mut parameters = vec![("weight".to_string(), self.weight.clone())];
+        if let Some(bias) = &self.bias {
+            parameters.push(("bias".to_string(), bias.clone()));
+        }
+        parameters
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array::IntoArray;
+    use crate::hardware::cpu::CpuHardware;
+    use crate::nn::linear::*;
+    use crate::node::IntoNode;
+
+    fn linear<'hw>(hw: &'hw RefCell<CpuHardware>, bias: bool) -> Linear<'hw> {
+        let linear = Linear::new(&mut Rng::new(1), hw, 2, 3, bias);
+        linear
+            .weight()
+            .set_value((Shape::new([3, 2]), vec![1f32, 2., 3., 4., 5., 6.]).into_array(hw))
+            .unwrap();
+        if let Some(b) = linear.bias() {
+            b.set_value(vec![1f32, -1., 0.].into_array(hw)).unwrap();
+        }
+        linear
+    }
+
+    #[test]
+    fn test_new() {
+        let hw = RefCell::new(CpuHardware::new());
+        let linear = Linear::new(&mut Rng::new(1), &hw, 4, 3, true);
+        assert_eq!(linear.weight().shape(), Shape::new([3, 4]));
+        assert_eq!(linear.bias().unwrap().shape(), Shape::new([3]));
+        assert!(linear
+            .weight()
+            .value()
+            .get_values_f32()
+            .iter()
+            .all(|x| (-0.5..0.5).contains(x)));
+
+        let names = linear
+            .parameters()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["weight", "bias"]);
+    }
+
+    #[test]
+    fn test_new_without_bias() {
+        let hw = RefCell::new(CpuHardware::new());
+        let linear = Linear::new(&mut Rng::new(1), &hw, 4, 3, false);
+        assert!(linear.bias().is_none());
+        assert_eq!(linear.parameters().len(), 1);
+    }
+
+    #[test]
+    fn test_forward() {
+        let hw = RefCell::new(CpuHardware::new());
+        let g = RefCell::new(Graph::new());
+        let linear = linear(&hw, true);
+
+        let x = (Shape::new([2, 2]), vec![1f32, 1., 1., -1.]).into_node(&g, &hw);
+        let y = linear.forward(&g, &[x]);
+        assert_eq!(y.shape(), Shape::new([2, 3]));
+        assert_eq!(
+            y.calculate().get_values_f32(),
+            vec![4., 6., 11., 0., -2., -1.]
+        );
+    }
+
+    #[test]
+    fn test_forward_without_bias() {
+        let hw = RefCell::new(CpuHardware::new());
+        let g = RefCell::new(Graph::new());
+        let linear = linear(&hw, false);
+
+        let x = (Shape::new([1, 2]), vec![1f32, 1.]).into_node(&g, &hw);
+        let y = linear.forward(&g, &[x]);
+        assert_eq!(y.calculate().get_values_f32(), vec![3., 7., 11.]);
+    }
+
+    #[test]
+    fn test_gradient() {
+        let hw = RefCell::new(CpuHardware::new());
+        let g = RefCell::new(Graph::new());
+        let linear = linear(&hw, true);
+        let mut store = ParameterStore::new();
+        linear.register_parameters(&mut store, "fc").unwrap();
+
+        let x = (Shape::new([2, 2]), vec![1f32, 2., 3., 4.]).into_node(&g, &hw);
+        let y = linear.forward(&g, &[x]);
+        store.accumulate_gradients(y);
+
+        // d(sum(y))/dW[i, j] = sum_b x[b, j], d(sum(y))/db[i] = batch_size
+        assert_eq!(
+            store.get("fc.weight").unwrap().gradient().get_values_f32(),
+            vec![4., 6., 4., 6., 4., 6.]
+        );
+        assert_eq!(
+            store.get("fc.bias").unwrap().gradient().get_values_f32(),
+            vec![2., 2., 2.]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_forward_invalid_num_inputs() {
+        let hw = RefCell::new(CpuHardware::new());
+        let g = RefCell::new(Graph::new());
+        let linear = linear(&hw, true);
+        let _y = linear.forward(&g, &[]);
+    }
+}
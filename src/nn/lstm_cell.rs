@@ -0,0 +1,257 @@
+use crate::hardware::Hardware;
+use crate::nn::recurrent::RecurrentCell;
+use crate::nn::*;
+use crate::random::Rng;
+
+/// Long short-term memory cell.
+///
+/// ```text
+/// i = sigmoid(W_ii x + b_i + W_hi h)
+/// f = sigmoid(W_if x + b_f + W_hf h)
+/// g = tanh(W_ig x + b_g + W_hg h)
+/// o = sigmoid(W_io x + b_o + W_ho h)
+/// c' = f * c + i * g
+/// h' = o * tanh(c')
+/// ```
+pub struct LstmCell<'hw> {
+    input_gate: Gate<'hw>,
+    forget_gate: Gate<'hw>,
+    cell_gate: Gate<'hw>,
+    output_gate: Gate<'hw>,
+    hidden_size: usize,
+}
+
+impl<'hw> LstmCell<'hw> {
+    /// Creates a new `LstmCell` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - Random number generator for the initialization.
+    /// * `hardware` - `Hardware` object to host the parameters.
+    /// * `input_size` - Number of input features.
+    /// * `hidden_size` - Number of features of the hidden and cell states.
+    ///
+    /// # Returns
+    ///
+    /// A new `LstmCell` object.
+    pub fn new(
+        rng: &mut Rng,
+        hardware: &'hw RefCell<dyn Hardware>,
+        input_size: usize,
+        hidden_size: usize,
+    ) -> Self {
+        Self {
+            input_gate: Gate::new(rng, hardware, input_size, hidden_size),
+            forget_gate: Gate::new(rng, hardware, input_size, hidden_size),
+            cell_gate: Gate::new(rng, hardware, input_size, hidden_size),
+            output_gate: Gate::new(rng, hardware, input_size, hidden_size),
+            hidden_size,
+        }
+    }
+
+    /// Creates zero-initialized hidden and cell states.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - `Graph` object to register the states.
+    /// * `batch_size` - Number of sequences processed at once.
+    ///
+    /// # Returns
+    ///
+    /// `Node`s of the hidden and cell states of the shape `[batch_size, hidden_size]`.
+    pub fn zero_state<'op, 'g>(
+        &self,
+        graph: &'g RefCell<Graph<'hw, 'op>>,
+        batch_size: usize,
+    ) -> (Node<'hw, 'op, 'g>, Node<'hw, 'op, 'g>)
+    where
+        'hw: 'op,
+    {
+        let hardware = self.input_gate.input.weight().hardware();
+        let shape = Shape::new([batch_size, self.hidden_size]);
+        (
+            Node::fill(graph, hardware, shape.clone(), 0.),
+            Node::fill(graph, hardware, shape, 0.),
+        )
+    }
+
+    /// Constructs the computation of a single time step.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - `Graph` object to register the operations.
+    /// * `x` - Input of the shape `[batch_size, input_size]`.
+    /// * `h` - Hidden state of the shape `[batch_size, hidden_size]`.
+    /// * `c` - Cell state of the shape `[batch_size, hidden_size]`.
+    ///
+    /// # Returns
+    ///
+    /// `Node`s of the next hidden and cell states.
+    pub fn forward_state<'op, 'g>(
+        &self,
+        graph: &'g RefCell<Graph<'hw, 'op>>,
+        x: Node<'hw, 'op, 'g>,
+        h: Node<'hw, 'op, 'g>,
+        c: Node<'hw, 'op, 'g>,
+    ) -> (Node<'hw, 'op, 'g>, Node<'hw, 'op, 'g>)
+    where
+        'hw: 'op,
+    {
+        let i = self.input_gate.forward(graph, x, h).sigmoid();
+        let f = self.forget_gate.forward(graph, x, h).sigmoid();
+        let g = self.cell_gate.forward(graph, x, h).tanh();
+        let o = self.output_gate.forward(graph, x, h).sigmoid();
+        let c = f * c + i * g;
+        (o * c.tanh(), c)
+    }
+}
+
+impl<'hw> RecurrentCell<'hw> for LstmCell<'hw> {
+    fn hidden_size(&self) -> usize {
+        self.hidden_size
+    }
+
+    /// Creates zero-initialized states: `[h, c]`. See also `zero_state()`.
+    fn zero_states<'op, 'g>(
+        &self,
+        graph: &'g RefCell<Graph<'hw, 'op>>,
+        batch_size: usize,
+    ) -> Vec<Node<'hw, 'op, 'g>>
+    where
+        'hw: 'op,
+    {
+        let (h, c) = self.zero_state(graph, batch_size);
+        vec![h, c]
+    }
+
+    /// Constructs the computation of a single time step. `state` must be `[h, c]`.
+    fn step<'op, 'g>(
+        &self,
+        graph: &'g RefCell<Graph<'hw, 'op>>,
+        x: Node<'hw, 'op, 'g>,
+        state: &[Node<'hw, 'op, 'g>],
+    ) -> Vec<Node<'hw, 'op, 'g>>
+    where
+        'hw: 'op,
+    {
+        check_num_states("LstmCell", state, 2);
+        let (h, c) = self.forward_state(graph, x, state[0], state[1]);
+        vec![h, c]
+    }
+}
+
+impl<'hw> Module<'hw> for LstmCell<'hw> {
+    /// Constructs the computation of a single time step.
+    ///
+    /// `inputs` must be `[x, h, c]` as described in `forward_state()`, and the output is the next
+    /// hidden state. Use `forward_state()` to obtain the next cell state as well.
+    fn forward<'op, 'g>(
+        &self,
+        graph: &'g RefCell<Graph<'hw, 'op>>,
+        inputs: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g>
+    where
+        'hw: 'op,
+    {
+        check_num_inputs("LstmCell", inputs, 3);
+        self.forward_state(graph, inputs[0], inputs[1], inputs[2]).0
+    }
+
+    fn parameters(&self) -> Vec<(String, Parameter<'hw>)> {
+        prefixed("input_gate", self.input_gate.parameters())
+            .chain(prefixed("forget_gate", self.forget_gate.parameters()))
+            .chain(prefixed("cell_gate", self.cell_gate.parameters()))
+            .chain(prefixed("output_gate", self.output_gate.parameters()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array::Array;
+    use crate::hardware::cpu::CpuHardware;
+    use crate::nn::lstm_cell::*;
+    use crate::node::IntoNode;
+
+    fn sigmoid(x: f32) -> f32 {
+        1. / (1. + (-x).exp())
+    }
+
+    /// Creates a cell with 1 input and 1 hidden unit whose parameters are:
+    /// W_i* = 1, b_* = 0, W_h* = 0.5
+    fn cell(hw: &RefCell<CpuHardware>) -> LstmCell<'_> {
+        let cell = LstmCell::new(&mut Rng::new(1), hw, 1, 1);
+        for (name, parameter) in cell.parameters() {
+            let value = if name.ends_with("input.weight") {
+                1.
+            } else if name.ends_with("bias") {
+                0.
+            } else {
+                0.5
+            };
+            parameter
+                .set_value(Array::fill_f32(hw, parameter.shape(), value))
+                .unwrap();
+        }
+        cell
+    }
+
+    #[test]
+    fn test_new() {
+        let hw = RefCell::new(CpuHardware::new());
+        let cell = LstmCell::new(&mut Rng::new(1), &hw, 3, 2);
+        assert_eq!(cell.hidden_size(), 2);
+        let parameters = cell.parameters();
+        assert_eq!(parameters.len(), 12);
+        assert_eq!(parameters[0].0, "input_gate.input.weight");
+        assert_eq!(parameters[0].1.shape(), Shape::new([2, 3]));
+        assert_eq!(parameters[1].0, "input_gate.input.bias");
+        assert_eq!(parameters[2].0, "input_gate.hidden.weight");
+        assert_eq!(parameters[2].1.shape(), Shape::new([2, 2]));
+        assert_eq!(parameters[11].0, "output_gate.hidden.weight");
+    }
+
+    #[test]
+    fn test_zero_state() {
+        let hw = RefCell::new(CpuHardware::new());
+        let g = RefCell::new(Graph::new());
+        let cell = LstmCell::new(&mut Rng::new(1), &hw, 3, 2);
+        let (h, c) = cell.zero_state(&g, 4);
+        assert_eq!(h.shape(), Shape::new([4, 2]));
+        assert_eq!(c.calculate().get_values_f32(), vec![0.; 8]);
+
+        let state = cell.zero_states(&g, 4);
+        assert_eq!(state.len(), 2);
+        assert_eq!(state[0].shape(), Shape::new([4, 2]));
+        assert_eq!(state[1].calculate().get_values_f32(), vec![0.; 8]);
+    }
+
+    #[test]
+    fn test_forward_state() {
+        let hw = RefCell::new(CpuHardware::new());
+        let g = RefCell::new(Graph::new());
+        let cell = cell(&hw);
+
+        let x = (Shape::new([1, 1]), vec![1f32]).into_node(&g, &hw);
+        let h = (Shape::new([1, 1]), vec![2f32]).into_node(&g, &hw);
+        let c = (Shape::new([1, 1]), vec![3f32]).into_node(&g, &hw);
+        let (h2, c2) = cell.forward_state(&g, x, h, c);
+
+        // Every gate receives 1 * 1 + 0.5 * 2 = 2.
+        let (s, t) = (sigmoid(2.), 2f32.tanh());
+        let expected_c = s * 3. + s * t;
+        let expected_h = s * expected_c.tanh();
+        let observed_c = c2.calculate().get_values_f32()[0];
+        let observed_h = h2.calculate().get_values_f32()[0];
+        assert!((observed_c - expected_c).abs() < 1e-5);
+        assert!((observed_h - expected_h).abs() < 1e-5);
+        assert_eq!(
+            cell.forward(&g, &[x, h, c]).calculate().get_values_f32(),
+            vec![observed_h]
+        );
+
+        // dc'/dc = f
+        let gc = crate::node::grad(c2, &[c])[0].calculate().get_values_f32()[0];
+        assert!((gc - s).abs() < 1e-5);
+    }
+}
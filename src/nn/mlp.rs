@@ -0,0 +1,206 @@
+use crate::hardware::Hardware;
+use crate::nn::linear::Linear;
+use crate::nn::*;
+use crate::random::Rng;
+
+/// Activation functions used between layers.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Activation {
+    /// max(x, 0)
+    Relu,
+
+    /// tanh(x)
+    Tanh,
+
+    /// 1 / (1 + exp(-x))
+    Sigmoid,
+}
+
+impl Activation {
+    /// Applies the activation function.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Input `Node`.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the result.
+    pub fn apply<'hw: 'op, 'op: 'g, 'g>(&self, x: Node<'hw, 'op, 'g>) -> Node<'hw, 'op, 'g> {
+        match self {
+            Self::Relu => x.relu(),
+            Self::Tanh => x.tanh(),
+            Self::Sigmoid => x.sigmoid(),
+        }
+    }
+}
+
+/// Multi-layer perceptron: a stack of `Linear` layers with activations between them.
+///
+/// No activation is applied after the last layer.
+pub struct Mlp<'hw> {
+    layers: Vec<Linear<'hw>>,
+    activation: Activation,
+}
+
+impl<'hw> Mlp<'hw> {
+    /// Creates a new `Mlp` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - Random number generator for the initialization.
+    /// * `hardware` - `Hardware` object to host the parameters.
+    /// * `sizes` - Number of features of the input, each hidden layer, and the output.
+    /// * `activation` - Activation function between layers.
+    ///
+    /// # Returns
+    ///
+    /// A new `Mlp` object with `sizes.len() - 1` layers.
+    ///
+    /// # Panics
+    ///
+    /// `sizes` has less than 2 elements.
+    pub fn new(
+        rng: &mut Rng,
+        hardware: &'hw RefCell<dyn Hardware>,
+        sizes: &[usize],
+        activation: Activation,
+    ) -> Self {
+        assert!(sizes.len() >= 2, "Mlp requires at least 2 sizes.");
+        Self {
+            layers: sizes
+                .windows(2)
+                .map(|w| Linear::new(rng, hardware, w[0], w[1], true))
+                .collect(),
+            activation,
+        }
+    }
+
+    /// Returns the layers.
+    pub fn layers(&self) -> &[Linear<'hw>] {
+        &self.layers
+    }
+}
+
+impl<'hw> Module<'hw> for Mlp<'hw> {
+    /// Constructs the forward computation.
+    ///
+    /// `inputs` must be a single `Node` of the shape `[batch_size, sizes[0]]`, and the output has
+    /// the shape `[batch_size, sizes[sizes.len() - 1]]`.
+    fn forward<'op, 'g>(
+        &self,
+        graph: &'g RefCell<Graph<'hw, 'op>>,
+        inputs: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g>
+    where
+        'hw: 'op,
+    {
+        check_num_inputs("Mlp", inputs, 1);
+        let (last, hidden) = self.layers.split_last().unwrap();
+        let h = hidden.iter().fold(inputs[0], |h, layer| {
+            self.activation.apply(layer.forward(graph, &[h]))
+        });
+        last.forward(graph, &[h])
+    }
+
+    fn parameters(&self) -> Vec<(String, Parameter<'hw>)> {
+        self.layers
+            .iter()
+            .enumerate()
+            .flat_map(|(i, layer)| {
+                prefixed(&format!("layers.{}", i), layer.parameters()).collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array::IntoArray;
+    use crate::hardware::cpu::CpuHardware;
+    use crate::nn::mlp::*;
+    use crate::node::IntoNode;
+
+    #[test]
+    fn test_activation() {
+        let hw = RefCell::new(CpuHardware::new());
+        let g = RefCell::new(Graph::new());
+        let x = vec![-1f32, 0.].into_node(&g, &hw);
+        assert_eq!(
+            Activation::Relu.apply(x).calculate().get_values_f32(),
+            vec![0., 0.]
+        );
+        assert_eq!(
+            Activation::Tanh.apply(x).calculate().get_values_f32(),
+            vec![(-1f32).tanh(), 0.]
+        );
+        assert_eq!(
+            Activation::Sigmoid.apply(x).calculate().get_values_f32()[1],
+            0.5
+        );
+    }
+
+    #[test]
+    fn test_new() {
+        let hw = RefCell::new(CpuHardware::new());
+        let mlp = Mlp::new(&mut Rng::new(1), &hw, &[4, 8, 2], Activation::Relu);
+        assert_eq!(mlp.layers().len(), 2);
+        assert_eq!(mlp.layers()[0].weight().shape(), Shape::new([8, 4]));
+        assert_eq!(mlp.layers()[1].weight().shape(), Shape::new([2, 8]));
+
+        let names = mlp
+            .parameters()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "layers.0.weight",
+                "layers.0.bias",
+                "layers.1.weight",
+                "layers.1.bias"
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_new_invalid_sizes() {
+        let hw = RefCell::new(CpuHardware::new());
+        let _mlp = Mlp::new(&mut Rng::new(1), &hw, &[4], Activation::Relu);
+    }
+
+    #[test]
+    fn test_forward_and_gradient() {
+        let hw = RefCell::new(CpuHardware::new());
+        let g = RefCell::new(Graph::new());
+        let mlp = Mlp::new(&mut Rng::new(1), &hw, &[2, 2, 1], Activation::Relu);
+        let [l0, l1] = mlp.layers() else { panic!() };
+        let values: [(&Parameter, Shape, Vec<f32>); 4] = [
+            (l0.weight(), Shape::new([2, 2]), vec![1., 0., 0., -1.]),
+            (l0.bias().unwrap(), Shape::new([2]), vec![0., 0.]),
+            (l1.weight(), Shape::new([1, 2]), vec![2., 3.]),
+            (l1.bias().unwrap(), Shape::new([1]), vec![1.]),
+        ];
+        for (parameter, shape, value) in values {
+            parameter.set_value((shape, value).into_array(&hw)).unwrap();
+        }
+        let mut store = ParameterStore::new();
+        mlp.register_parameters(&mut store, "mlp").unwrap();
+
+        // h = relu([1, -2]) = [1, 0], y = 2 * 1 + 3 * 0 + 1 = 3
+        let x = (Shape::new([1, 2]), vec![1f32, 2.]).into_node(&g, &hw);
+        let y = mlp.forward(&g, &[x]);
+        assert_eq!(y.shape(), Shape::new([1, 1]));
+        assert_eq!(y.calculate().get_values_f32(), vec![3.]);
+
+        store.accumulate_gradients(y);
+        let gradient = |name| store.get(name).unwrap().gradient().get_values_f32();
+        assert_eq!(gradient("mlp.layers.1.weight"), vec![1., 0.]);
+        assert_eq!(gradient("mlp.layers.1.bias"), vec![1.]);
+        // Only the first hidden unit is active.
+        assert_eq!(gradient("mlp.layers.0.weight"), vec![2., 4., 0., 0.]);
+        assert_eq!(gradient("mlp.layers.0.bias"), vec![2., 0.]);
+    }
+}
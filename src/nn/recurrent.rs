@@ -0,0 +1,96 @@
+use crate::nn::*;
+
+/// Interface of recurrent cells, which update their states by one time step.
+///
+/// Since `Graph`s are constructed dynamically, a sequence of any length can be processed by
+/// applying the cell to each element in order. See `unroll()`.
+pub trait RecurrentCell<'hw> {
+    /// Returns the number of features of the hidden state.
+    fn hidden_size(&self) -> usize;
+
+    /// Creates zero-initialized states.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - `Graph` object to register the states.
+    /// * `batch_size` - Number of sequences processed at once.
+    ///
+    /// # Returns
+    ///
+    /// `Node`s of the states, each of the shape `[batch_size, hidden_size]`. The first element
+    /// is the hidden state.
+    fn zero_states<'op, 'g>(
+        &self,
+        graph: &'g RefCell<Graph<'hw, 'op>>,
+        batch_size: usize,
+    ) -> Vec<Node<'hw, 'op, 'g>>
+    where
+        'hw: 'op;
+
+    /// Constructs the computation of a single time step.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - `Graph` object to register the operations.
+    /// * `x` - Input of the shape `[batch_size, input_size]`.
+    /// * `state` - Current states, as returned by `zero_states()` or the previous `step()`.
+    ///
+    /// # Returns
+    ///
+    /// `Node`s of the next states. The first element is the hidden state, which is also the
+    /// output of this time step.
+    ///
+    /// # Panics
+    ///
+    /// `state` does not have the expected number of elements.
+    fn step<'op, 'g>(
+        &self,
+        graph: &'g RefCell<Graph<'hw, 'op>>,
+        x: Node<'hw, 'op, 'g>,
+        state: &[Node<'hw, 'op, 'g>],
+    ) -> Vec<Node<'hw, 'op, 'g>>
+    where
+        'hw: 'op;
+}
+
+/// Applies a recurrent cell to each element of a sequence.
+///
+/// # Arguments
+///
+/// * `cell` - Recurrent cell to be applied.
+/// * `graph` - `Graph` object to register the operations.
+/// * `inputs` - Elements of the sequence in order, each of the shape `[batch_size, input_size]`.
+/// * `initial_state` - Initial states of the cell. If `None`, zero states are used.
+///
+/// # Returns
+///
+/// A tuple of the hidden states after each element, and the final states.
+///
+/// # Panics
+///
+/// `inputs` is empty and `initial_state` is `None`.
+#[allow(clippy::type_complexity)]
+pub fn unroll<'hw: 'op, 'op: 'g, 'g, C: RecurrentCell<'hw> + ?Sized>(
+    cell: &C,
+    graph: &'g RefCell<Graph<'hw, 'op>>,
+    inputs: &[Node<'hw, 'op, 'g>],
+    initial_state: Option<&[Node<'hw, 'op, 'g>]>,
+) -> (Vec<Node<'hw, 'op, 'g>>, Vec<Node<'hw, 'op, 'g>>) {
+    let mut state = match initial_state {
+        Some(state) => state.to_vec(),
+        None => {
+            let first = inputs
+                .first()
+                .expect("Either inputs or initial_state is required.");
+            cell.zero_states(graph, first.shape().dimension(0).unwrap())
+        }
+    };
+    let outputs = inputs
+        .iter()
+        .map(|&x| {
+            state = cell.step(graph, x, &state);
+            state[0]
+        })
+        .collect();
+    (outputs, state)
+}
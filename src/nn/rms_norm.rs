@@ -0,0 +1,108 @@
+use crate::hardware::Hardware;
+use crate::nn::*;
+
+/// RMS normalization over the last dimension: y = x / sqrt(mean(x^2) + epsilon) * gamma.
+///
+/// `gamma` has the shape `[normalized_size]`.
+pub struct RmsNorm<'hw> {
+    gamma: Parameter<'hw>,
+    epsilon: f32,
+}
+
+impl<'hw> RmsNorm<'hw> {
+    /// Creates a new `RmsNorm` object.
+    ///
+    /// `gamma` is initialized by 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `hardware` - `Hardware` object to host the parameters.
+    /// * `normalized_size` - Size of the last dimension of inputs.
+    /// * `epsilon` - Small value to avoid zero division, typically 1e-6.
+    ///
+    /// # Returns
+    ///
+    /// A new `RmsNorm` object.
+    pub fn new(hardware: &'hw RefCell<dyn Hardware>, normalized_size: usize, epsilon: f32) -> Self {
+        Self {
+            gamma: Parameter::new(Array::fill_f32(hardware, Shape::new([normalized_size]), 1.)),
+            epsilon,
+        }
+    }
+
+    /// Returns the scaling parameter.
+    pub fn gamma(&self) -> &Parameter<'hw> {
+        &self.gamma
+    }
+}
+
+impl<'hw> Module<'hw> for RmsNorm<'hw> {
+    /// Constructs the forward computation.
+    ///
+    /// `inputs` must be a single `Node` whose last dimension is `normalized_size`, and the output
+    /// has the same shape.
+    fn forward<'op, 'g>(
+        &self,
+        graph: &'g RefCell<Graph<'hw, 'op>>,
+        inputs: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g>
+    where
+        'hw: 'op,
+    {
+        check_num_inputs("RmsNorm", inputs, 1);
+        inputs[0].rms_norm(Node::parameter(graph, &self.gamma), self.epsilon)
+    }
+
+    fn parameters(&self) -> Vec<(String, Parameter<'hw>)> {
+        vec![("gamma".to_string(), self.gamma.clone())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array::IntoArray;
+    use crate::hardware::cpu::CpuHardware;
+    use crate::nn::rms_norm::*;
+    use crate::node::IntoNode;
+
+    #[test]
+    fn test_new() {
+        let hw = RefCell::new(CpuHardware::new());
+        let norm = RmsNorm::new(&hw, 3, 1e-6);
+        assert_eq!(norm.gamma().value().get_values_f32(), vec![1., 1., 1.]);
+        assert_eq!(norm.parameters().len(), 1);
+    }
+
+    #[test]
+    fn test_forward() {
+        let hw = RefCell::new(CpuHardware::new());
+        let g = RefCell::new(Graph::new());
+        let norm = RmsNorm::new(&hw, 2, 0.);
+        norm.gamma()
+            .set_value(vec![1f32, 2.].into_array(&hw))
+            .unwrap();
+
+        // rms = [1, 5]
+        let x = (Shape::new([2, 2]), vec![1f32, -1., 7., 1.]).into_node(&g, &hw);
+        let y = norm.forward(&g, &[x]);
+        assert_eq!(y.shape(), Shape::new([2, 2]));
+        assert_eq!(y.calculate().get_values_f32(), vec![1., -2., 1.4, 0.4]);
+    }
+
+    #[test]
+    fn test_gradient() {
+        let hw = RefCell::new(CpuHardware::new());
+        let g = RefCell::new(Graph::new());
+        let norm = RmsNorm::new(&hw, 2, 0.);
+        let mut store = ParameterStore::new();
+        norm.register_parameters(&mut store, "norm").unwrap();
+
+        let x = (Shape::new([2, 2]), vec![1f32, -1., 7., 1.]).into_node(&g, &hw);
+        store.accumulate_gradients(norm.forward(&g, &[x]));
+
+        // Normalized values are [[1, -1], [1.4, 0.2]].
+        let gamma = store.get("norm.gamma").unwrap().gradient().get_values_f32();
+        assert!((gamma[0] - 2.4).abs() < 1e-5);
+        assert!((gamma[1] + 0.8).abs() < 1e-5);
+    }
+}
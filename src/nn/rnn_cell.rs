@@ -0,0 +1,192 @@
+use crate::hardware::Hardware;
+use crate::nn::mlp::Activation;
+use crate::nn::recurrent::RecurrentCell;
+use crate::nn::*;
+use crate::random::Rng;
+
+/// Elman recurrent cell.
+///
+/// ```text
+/// h' = activation(W_i x + b + W_h h)
+/// ```
+pub struct RnnCell<'hw> {
+    gate: Gate<'hw>,
+    activation: Activation,
+    hidden_size: usize,
+}
+
+impl<'hw> RnnCell<'hw> {
+    /// Creates a new `RnnCell` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - Random number generator for the initialization.
+    /// * `hardware` - `Hardware` object to host the parameters.
+    /// * `input_size` - Number of input features.
+    /// * `hidden_size` - Number of features of the hidden state.
+    /// * `activation` - Activation function applied to the next hidden state.
+    ///
+    /// # Returns
+    ///
+    /// A new `RnnCell` object.
+    pub fn new(
+        rng: &mut Rng,
+        hardware: &'hw RefCell<dyn Hardware>,
+        input_size: usize,
+        hidden_size: usize,
+        activation: Activation,
+    ) -> Self {
+        Self {
+            gate: Gate::new(rng, hardware, input_size, hidden_size),
+            activation,
+            hidden_size,
+        }
+    }
+
+    /// Returns the activation function.
+    pub fn activation(&self) -> Activation {
+        self.activation
+    }
+}
+
+impl<'hw> RecurrentCell<'hw> for RnnCell<'hw> {
+    fn hidden_size(&self) -> usize {
+        self.hidden_size
+    }
+
+    /// Creates a zero-initialized state: `[h]`.
+    fn zero_states<'op, 'g>(
+        &self,
+        graph: &'g RefCell<Graph<'hw, 'op>>,
+        batch_size: usize,
+    ) -> Vec<Node<'hw, 'op, 'g>>
+    where
+        'hw: 'op,
+    {
+        let hardware = self.gate.input.weight().hardware();
+        fill_zero_states(
+            graph,
+            hardware,
+            Shape::new([batch_size, self.hidden_size]),
+            1,
+        )
+    }
+
+    /// Constructs the computation of a single time step. `state` must be `[h]`.
+    fn step<'op, 'g>(
+        &self,
+        graph: &'g RefCell<Graph<'hw, 'op>>,
+        x: Node<'hw, 'op, 'g>,
+        state: &[Node<'hw, 'op, 'g>],
+    ) -> Vec<Node<'hw, 'op, 'g>>
+    where
+        'hw: 'op,
+    {
+        check_num_states("RnnCell", state, 1);
+        vec![self.forward(graph, &[x, state[0]])]
+    }
+}
+
+impl<'hw> Module<'hw> for RnnCell<'hw> {
+    /// Constructs the computation of a single time step.
+    ///
+    /// `inputs` must be `[x, h]`, where `x` is of the shape `[batch_size, input_size]` and `h` is
+    /// of the shape `[batch_size, hidden_size]`. The output is the next hidden state.
+    fn forward<'op, 'g>(
+        &self,
+        graph: &'g RefCell<Graph<'hw, 'op>>,
+        inputs: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g>
+    where
+        'hw: 'op,
+    {
+        check_num_inputs("RnnCell", inputs, 2);
+        self.activation
+            .apply(self.gate.forward(graph, inputs[0], inputs[1]))
+    }
+
+    fn parameters(&self) -> Vec<(String, Parameter<'hw>)> {
+        self.gate.parameters()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array::Array;
+    use crate::hardware::cpu::CpuHardware;
+    use crate::nn::rnn_cell::*;
+    use crate::node::IntoNode;
+
+    #[test]
+    fn test_new() {
+        let hw = RefCell::new(CpuHardware::new());
+        let cell = RnnCell::new(&mut Rng::new(1), &hw, 3, 2, Activation::Tanh);
+        assert_eq!(cell.hidden_size(), 2);
+        assert_eq!(cell.activation(), Activation::Tanh);
+        let parameters = cell.parameters();
+        assert_eq!(parameters.len(), 3);
+        assert_eq!(parameters[0].0, "input.weight");
+        assert_eq!(parameters[0].1.shape(), Shape::new([2, 3]));
+        assert_eq!(parameters[1].0, "input.bias");
+        assert_eq!(parameters[1].1.shape(), Shape::new([2]));
+        assert_eq!(parameters[2].0, "hidden.weight");
+        assert_eq!(parameters[2].1.shape(), Shape::new([2, 2]));
+    }
+
+    #[test]
+    fn test_zero_states() {
+        let hw = RefCell::new(CpuHardware::new());
+        let g = RefCell::new(Graph::new());
+        let cell = RnnCell::new(&mut Rng::new(1), &hw, 3, 2, Activation::Tanh);
+        let state = cell.zero_states(&g, 4);
+        assert_eq!(state.len(), 1);
+        assert_eq!(state[0].shape(), Shape::new([4, 2]));
+        assert_eq!(state[0].calculate().get_values_f32(), vec![0.; 8]);
+    }
+
+    #[test]
+    fn test_step() {
+        let hw = RefCell::new(CpuHardware::new());
+        let g = RefCell::new(Graph::new());
+        let cell = RnnCell::new(&mut Rng::new(1), &hw, 1, 1, Activation::Tanh);
+        for (name, value) in [
+            ("input.weight", 1.),
+            ("input.bias", 0.5),
+            ("hidden.weight", 2.),
+        ] {
+            let parameter = cell
+                .parameters()
+                .into_iter()
+                .find(|(n, _)| n == name)
+                .unwrap()
+                .1;
+            parameter
+                .set_value(Array::fill_f32(&hw, parameter.shape(), value))
+                .unwrap();
+        }
+
+        let x = (Shape::new([2, 1]), vec![1f32, -1.]).into_node(&g, &hw);
+        let h = (Shape::new([2, 1]), vec![0.5f32, 0.25]).into_node(&g, &hw);
+        let state = cell.step(&g, x, &[h]);
+        assert_eq!(state.len(), 1);
+        let expected = [2.5f32.tanh(), 0f32.tanh()];
+        let observed = state[0].calculate().get_values_f32();
+        for (o, e) in observed.iter().zip(expected) {
+            assert!((o - e).abs() < 1e-5);
+        }
+        assert_eq!(
+            cell.forward(&g, &[x, h]).calculate().get_values_f32(),
+            observed
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_step_invalid_state() {
+        let hw = RefCell::new(CpuHardware::new());
+        let g = RefCell::new(Graph::new());
+        let cell = RnnCell::new(&mut Rng::new(1), &hw, 1, 1, Activation::Tanh);
+        let x = Node::fill(&g, &hw, Shape::new([1, 1]), 0.);
+        let _ = cell.step(&g, x, &[x, x]);
+    }
+}
@@ -0,0 +1,216 @@
+use crate::array::IntoArray;
+use crate::error::Error;
+use crate::hardware::cpu::CpuHardware;
+use crate::nn::embedding::Embedding;
+use crate::nn::gru_cell::GruCell;
+use crate::nn::layer_norm::LayerNorm;
+use crate::nn::linear::Linear;
+use crate::nn::lstm_cell::LstmCell;
+use crate::nn::mlp::{Activation, Mlp};
+use crate::nn::recurrent::{unroll, RecurrentCell};
+use crate::nn::rnn_cell::RnnCell;
+use crate::nn::*;
+use crate::node::IntoNode;
+use crate::optim::adam::Adam;
+use crate::optim::Optimizer;
+use crate::random::Rng;
+
+#[test]
+fn test_join_name() {
+    assert_eq!(join_name("", "weight"), "weight");
+    assert_eq!(join_name("fc", "weight"), "fc.weight");
+    assert_eq!(join_name("a.b", "c.d"), "a.b.c.d");
+}
+
+#[test]
+fn test_register_parameters() {
+    let hw = RefCell::new(CpuHardware::new());
+    let mut rng = Rng::new(1);
+    let embedding = Embedding::new(&mut rng, &hw, 10, 4);
+    let norm = LayerNorm::new(&hw, 4, 1e-5);
+    let mut store = ParameterStore::new();
+
+    embedding.register_parameters(&mut store, "embed").unwrap();
+    norm.register_parameters(&mut store, "norm").unwrap();
+    let names = store.iter().map(|(name, _)| name).collect::<Vec<_>>();
+    assert_eq!(names, ["embed.weight", "norm.beta", "norm.gamma"]);
+
+    // Registered parameters share values with the module.
+    assert!(store.get("embed.weight").unwrap() == embedding.weight());
+
+    assert!(matches!(
+        norm.register_parameters(&mut store, "norm"),
+        Err(Error::InvalidName(_))
+    ));
+}
+
+#[test]
+fn test_modules_across_graphs() {
+    let hw = RefCell::new(CpuHardware::new());
+    let linear = Linear::new(&mut Rng::new(1), &hw, 3, 2, true);
+
+    let run = || {
+        let g = RefCell::new(Graph::new());
+        let x = (Shape::new([1, 3]), vec![1f32, 2., 3.]).into_node(&g, &hw);
+        linear.forward(&g, &[x]).calculate().get_values_f32()
+    };
+    assert_eq!(run(), run());
+}
+
+#[test]
+fn test_train_mlp() {
+    let hw = RefCell::new(CpuHardware::new());
+    let mut rng = Rng::new(42);
+    let mlp = Mlp::new(&mut rng, &hw, &[2, 8, 1], Activation::Tanh);
+    let mut store = ParameterStore::new();
+    mlp.register_parameters(&mut store, "mlp").unwrap();
+    let mut optimizer = Adam::new(0.05, 0.9, 0.999, 1e-8, 0.);
+
+    // XOR
+    let inputs = (Shape::new([4, 2]), vec![0f32, 0., 0., 1., 1., 0., 1., 1.]).into_array(&hw);
+    let targets = (Shape::new([4, 1]), vec![0f32, 1., 1., 0.]).into_array(&hw);
+
+    let mut losses = vec![];
+    for _ in 0..300 {
+        let g = RefCell::new(Graph::new());
+        let x = Node::constant(&g, inputs.clone());
+        let t = Node::constant(&g, targets.clone());
+        let d = mlp.forward(&g, &[x]) - t;
+        let loss = (d * d).sum_axis(1).sum_axis(0);
+        losses.push(f32::try_from(loss).unwrap());
+
+        store.reset_gradients();
+        store.accumulate_gradients(loss);
+        optimizer.update(&store).unwrap();
+    }
+
+    assert!(losses[0] > 0.5, "{}", losses[0]);
+    assert!(
+        losses[losses.len() - 1] < 0.01,
+        "{:?}",
+        &losses[losses.len() - 10..]
+    );
+}
+
+#[test]
+fn test_unroll() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let cell = LstmCell::new(&mut Rng::new(1), &hw, 2, 3);
+    let inputs = (0..4)
+        .map(|i| Node::fill(&g, &hw, Shape::new([5, 2]), i as f32 * 0.25))
+        .collect::<Vec<_>>();
+
+    let (outputs, state) = unroll(&cell, &g, &inputs, None);
+    assert_eq!(outputs.len(), 4);
+    assert_eq!(state.len(), 2);
+    assert_eq!(outputs[3], state[0]);
+
+    // Equivalent to stepping manually from the zero state.
+    let mut expected = cell.zero_states(&g, 5);
+    for &x in &inputs {
+        expected = cell.step(&g, x, &expected);
+    }
+    assert_eq!(expected[0].shape(), Shape::new([5, 3]));
+    assert_eq!(
+        state[1].calculate().get_values_f32(),
+        expected[1].calculate().get_values_f32()
+    );
+
+    // Continues from the given state.
+    let (outputs2, state2) = unroll(
+        &cell,
+        &g,
+        &inputs[2..],
+        Some(&unroll(&cell, &g, &inputs[..2], None).1),
+    );
+    assert_eq!(outputs2.len(), 2);
+    assert_eq!(
+        state2[0].calculate().get_values_f32(),
+        state[0].calculate().get_values_f32()
+    );
+
+    // Empty sequence keeps the initial state.
+    let (outputs3, state3) = unroll(&cell, &g, &[], Some(&state));
+    assert!(outputs3.is_empty());
+    assert_eq!(state3, state);
+}
+
+#[test]
+#[should_panic]
+fn test_unroll_empty() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let cell = GruCell::new(&mut Rng::new(1), &hw, 2, 3);
+    let _ = unroll(&cell, &g, &[], None);
+}
+
+/// Trains `cell` and a linear readout to predict the mean of variable-length sequences, building
+/// a separate graph for each sequence length, and returns the losses of each epoch.
+fn train_sequence_mean<'hw, C: RecurrentCell<'hw> + Module<'hw>>(
+    hw: &'hw RefCell<CpuHardware>,
+    rng: &mut Rng,
+    cell: &C,
+) -> Vec<f32> {
+    const BATCH_SIZE: usize = 8;
+    let readout = Linear::new(rng, hw, cell.hidden_size(), 1, true);
+    let mut store = ParameterStore::new();
+    cell.register_parameters(&mut store, "cell").unwrap();
+    readout.register_parameters(&mut store, "readout").unwrap();
+    let mut optimizer = Adam::new(0.02, 0.9, 0.999, 1e-8, 0.);
+
+    // Sequences of lengths 1 to 5, with elements in [-1, 1).
+    let dataset = (1..=5)
+        .map(|length| {
+            let values = rng
+                .uniform_f32(hw, Shape::new([length, BATCH_SIZE, 1]), -1., 1.)
+                .get_values_f32();
+            let targets = (0..BATCH_SIZE)
+                .map(|b| {
+                    (0..length).map(|t| values[t * BATCH_SIZE + b]).sum::<f32>() / length as f32
+                })
+                .collect::<Vec<_>>();
+            (values, targets)
+        })
+        .collect::<Vec<_>>();
+
+    let mut losses = vec![];
+    for _ in 0..200 {
+        let mut epoch_loss = 0.;
+        store.reset_gradients();
+        for (values, targets) in &dataset {
+            let g = RefCell::new(Graph::new());
+            let inputs = values
+                .chunks(BATCH_SIZE)
+                .map(|step| (Shape::new([BATCH_SIZE, 1]), step.to_vec()).into_node(&g, hw))
+                .collect::<Vec<_>>();
+            let (_, state) = unroll(cell, &g, &inputs, None);
+            let t = (Shape::new([BATCH_SIZE, 1]), targets.clone()).into_node(&g, hw);
+            let d = readout.forward(&g, &[state[0]]) - t;
+            let loss = (d * d).sum_axis(1).sum_axis(0);
+            epoch_loss += f32::try_from(loss).unwrap();
+            store.accumulate_gradients(loss);
+        }
+        optimizer.update(&store).unwrap();
+        losses.push(epoch_loss);
+    }
+    losses
+}
+
+#[test]
+fn test_train_recurrent() {
+    let hw = RefCell::new(CpuHardware::new());
+    let mut rng = Rng::new(7);
+    let rnn = RnnCell::new(&mut rng, &hw, 1, 8, Activation::Tanh);
+    let lstm = LstmCell::new(&mut rng, &hw, 1, 8);
+    let gru = GruCell::new(&mut rng, &hw, 1, 8);
+
+    for losses in [
+        train_sequence_mean(&hw, &mut rng, &rnn),
+        train_sequence_mean(&hw, &mut rng, &lstm),
+        train_sequence_mean(&hw, &mut rng, &gru),
+    ] {
+        let last = losses[losses.len() - 1];
+        assert!(last < 0.05 * losses[0], "{} -> {}", losses[0], last);
+    }
+}
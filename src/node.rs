@@ -1,8 +1,11 @@
-use crate::array::Array;
+use crate::array::{Array, IntoArray};
+use crate::conv::{Conv1dOptions, Conv2dOptions, Pool2dOptions};
 use crate::error::Error;
 use crate::graph::Graph;
 use crate::hardware::Hardware;
-use crate::operator;
+use crate::operator::{self, Operator};
+use crate::parameter::Parameter;
+use crate::random::Rng;
 use crate::result::Result;
 use crate::shape::Shape;
 use std::cell::RefCell;
@@ -20,15 +23,49 @@ pub struct Node<'hw: 'op, 'op: 'g, 'g> {
 }
 
 impl<'hw: 'op, 'op: 'g, 'g> Node<'hw, 'op, 'g> {
-    fn new(graph: &'g RefCell<Graph<'hw, 'op>>, step_id: usize) -> Self {
+    pub(crate) fn new(graph: &'g RefCell<Graph<'hw, 'op>>, step_id: usize) -> Self {
         Self { graph, step_id }
     }
 
+    /// Obtains the node of an existing step, e.g., a step in a graph obtained by `Graph::load()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - `Graph` object holding the step.
+    /// * `step_id` - ID of the step.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Node)` - The node of the step.
+    /// * `Err(Error)` - `step_id` is invalid.
+    pub fn from_step(graph: &'g RefCell<Graph<'hw, 'op>>, step_id: usize) -> Result<Self> {
+        graph.borrow().get_step(step_id)?;
+        Ok(Self::new(graph, step_id))
+    }
+
+    /// Returns the `Graph` that this node belongs to.
+    ///
+    /// # Returns
+    ///
+    /// A reference to the associated `Graph`.
+    pub fn graph(&self) -> &'g RefCell<Graph<'hw, 'op>> {
+        self.graph
+    }
+
+    /// Returns the ID of the `Step` holding the value of this node.
+    ///
+    /// # Returns
+    ///
+    /// The step ID, which can be passed to `Graph::get_step()`.
+    pub fn step_id(&self) -> usize {
+        self.step_id
+    }
+
     pub fn check_graph(&self, others: &[&Self]) -> Result<&'g RefCell<Graph<'hw, 'op>>> {
         others
             .iter()
             .all(|&o| ptr::eq(self.graph, o.graph))
-            .then(|| self.graph)
+            .then_some(self.graph)
             .ok_or_else(|| {
                 Error::InvalidGraph(
                     "Attempted calculation between Nodes on different Graph.".to_string(),
@@ -36,53 +73,1050 @@ impl<'hw: 'op, 'op: 'g, 'g> Node<'hw, 'op, 'g> {
             })
     }
 
-    pub fn shape(&self) -> Shape {
-        self.graph
-            .borrow()
-            .get_step(self.step_id)
-            .unwrap()
-            .output
-            .shape()
-            .clone()
+    pub fn shape(&self) -> Shape {
+        self.graph
+            .borrow()
+            .get_step(self.step_id)
+            .unwrap()
+            .output
+            .shape()
+            .clone()
+    }
+
+    pub fn hardware(&self) -> &'hw RefCell<dyn Hardware> {
+        self.graph
+            .borrow()
+            .get_step(self.step_id)
+            .unwrap()
+            .output
+            .hardware()
+    }
+
+    /// Calculates the value of this node, reusing values already calculated in the graph.
+    ///
+    /// # Returns
+    ///
+    /// A new `Array` holding the value of this node.
+    ///
+    /// # Panics
+    ///
+    /// * `Operator::perform()` of this node or some node it depends on returns an error. Built-in
+    ///   operators validate their inputs when the node is constructed, but user-defined operators
+    ///   may fail at this point.
+    pub fn calculate(&self) -> Array<'hw> {
+        self.graph.borrow_mut().calculate(self.step_id).clone()
+    }
+
+    /// Renders the associated graph in the Graphviz DOT language, highlighting the subgraph
+    /// necessary to calculate this node.
+    ///
+    /// # Returns
+    ///
+    /// A DOT representation of the graph. See also `Graph::to_dot()`.
+    pub fn to_dot(&self) -> String {
+        self.graph.borrow().render_dot(Some(self.step_id))
+    }
+
+    /// Registers `Fill` operation to the graph.
+    ///
+    /// In the hash-consing mode, fills with the same hardware, shape and value return the same
+    /// node, and their gradients are not distinguished. Use `Node::variable()` to create
+    /// independent inputs for differentiation.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - `Graph` object to register the operation.
+    /// * `hardware` - `Hardware` object to hold the value.
+    /// * `shape` - `Shape` of the output array.
+    /// * `value` - Value of each element in the output array.
+    pub fn fill(
+        graph: &'g RefCell<Graph<'hw, 'op>>,
+        hardware: &'hw RefCell<dyn Hardware>,
+        shape: Shape,
+        value: f32,
+    ) -> Self {
+        Self::new(
+            graph,
+            graph
+                .borrow_mut()
+                .add_step(
+                    Box::new(operator::fill::Fill::new(hardware, shape, value)),
+                    vec![],
+                )
+                .unwrap(),
+        )
+    }
+
+    /// Creates a new leaf node filled by a single value.
+    ///
+    /// Unlike `Node::fill()`, the resulting node is never shared with other steps even in the
+    /// hash-consing mode, so that it can be used as an independent input of `grad()`, `vjp()`
+    /// and `jvp()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - `Graph` object to register the operation.
+    /// * `hardware` - `Hardware` object to hold the value.
+    /// * `shape` - `Shape` of the output array.
+    /// * `value` - Value of each element in the output array.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the value.
+    pub fn variable(
+        graph: &'g RefCell<Graph<'hw, 'op>>,
+        hardware: &'hw RefCell<dyn Hardware>,
+        shape: Shape,
+        value: f32,
+    ) -> Self {
+        Self::constant(graph, Array::fill_f32(hardware, shape, value))
+    }
+
+    /// Registers `Constant` operation to the graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - `Graph` object to register the operation.
+    /// * `value` - `Array` object holding the values. The resulting node is placed on the same
+    ///   hardware as `value`.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding `value`.
+    pub fn constant(graph: &'g RefCell<Graph<'hw, 'op>>, value: Array<'hw>) -> Self {
+        Self::apply(
+            graph,
+            Box::new(operator::constant::Constant::new(value)),
+            &[],
+        )
+        .unwrap()
+    }
+
+    /// Injects a `Parameter` into the graph.
+    ///
+    /// The resulting node holds a snapshot of the current value of `parameter`: later updates of
+    /// the parameter do not affect the graph. Calling this function multiple times with the same
+    /// parameter returns the same node, so that all usages of the parameter in the graph share a
+    /// single gradient.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - `Graph` object to register the operation.
+    /// * `parameter` - `Parameter` to be injected.
+    ///
+    /// # Returns
+    ///
+    /// A `Node` holding the value of `parameter`.
+    pub fn parameter(graph: &'g RefCell<Graph<'hw, 'op>>, parameter: &Parameter<'hw>) -> Self {
+        if let Some(step_id) = graph.borrow().get_parameter_step(parameter.id()) {
+            return Self::new(graph, step_id);
+        }
+        let node = Self::constant(graph, parameter.value());
+        graph
+            .borrow_mut()
+            .set_parameter_step(parameter.id(), node.step_id);
+        node
+    }
+
+    /// Registers an arbitrary operation to the graph.
+    ///
+    /// This function is the entry point of user-defined operators: any type implementing the
+    /// `Operator` trait can be registered.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - `Graph` object to register the operation.
+    /// * `operator` - `Operator` object to be performed.
+    /// * `inputs` - Input `Node`s of the operation. The number of elements must be the same as the
+    ///   return value of `operator.input_size()`, and all nodes must belong to `graph`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Node)` - A new `Node` representing the output of the operation.
+    /// * `Err(Error)` - Some error occurred during the process, e.g., shape inference failed.
+    pub fn apply(
+        graph: &'g RefCell<Graph<'hw, 'op>>,
+        operator: Box<dyn Operator<'hw> + 'op>,
+        inputs: &[Self],
+    ) -> Result<Self> {
+        if !inputs.iter().all(|node| ptr::eq(node.graph, graph)) {
+            return Err(Error::InvalidGraph(
+                "Attempted calculation between Nodes on different Graph.".to_string(),
+            ));
+        }
+        let step_id = graph
+            .borrow_mut()
+            .add_step(operator, inputs.iter().map(|node| node.step_id).collect())?;
+        Ok(Self::new(graph, step_id))
+    }
+
+    /// Registers `Sqrt` operation to the graph: elementwise square root.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the result.
+    pub fn sqrt(self) -> Self {
+        Self::apply(self.graph, Box::new(operator::sqrt::Sqrt::new()), &[self]).unwrap()
+    }
+
+    /// Registers `Tanh` operation to the graph: elementwise hyperbolic tangent.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the result.
+    pub fn tanh(self) -> Self {
+        Self::apply(self.graph, Box::new(operator::tanh::Tanh::new()), &[self]).unwrap()
+    }
+
+    /// Registers `Sigmoid` operation to the graph: elementwise logistic sigmoid 1 / (1 + exp(-x)).
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the result.
+    pub fn sigmoid(self) -> Self {
+        Self::apply(
+            self.graph,
+            Box::new(operator::sigmoid::Sigmoid::new()),
+            &[self],
+        )
+        .unwrap()
+    }
+
+    /// Registers `Relu` operation to the graph: elementwise max(x, 0).
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the result.
+    pub fn relu(self) -> Self {
+        Self::apply(self.graph, Box::new(operator::relu::Relu::new()), &[self]).unwrap()
+    }
+
+    /// Registers `Clamp` operation to the graph: elementwise min(max(x, min), max).
+    ///
+    /// NaN values are kept as is. Gradients are propagated only to the elements within
+    /// [`min`, `max`].
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - Lower bound of the resulting values.
+    /// * `max` - Upper bound of the resulting values.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the result.
+    pub fn clamp(self, min: f32, max: f32) -> Self {
+        Self::apply(
+            self.graph,
+            Box::new(operator::clamp::Clamp::new(min, max)),
+            &[self],
+        )
+        .unwrap()
+    }
+
+    /// Registers `Minimum` operation to the graph: elementwise min(x, other).
+    ///
+    /// Gradients are propagated to the smaller argument, or to `self` if both are equal. The
+    /// result is NaN if either argument is NaN.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Right-hand side argument.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the result.
+    ///
+    /// # Panics
+    ///
+    /// * `self` and `other` belong to different graphs, or they have different shapes.
+    pub fn minimum(self, other: Self) -> Self {
+        Self::apply(
+            self.graph,
+            Box::new(operator::minimum::Minimum::new()),
+            &[self, other],
+        )
+        .unwrap()
+    }
+
+    /// Registers `Maximum` operation to the graph: elementwise max(x, other).
+    ///
+    /// Gradients are propagated to the larger argument, or to `self` if both are equal. The
+    /// result is NaN if either argument is NaN.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Right-hand side argument.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the result.
+    ///
+    /// # Panics
+    ///
+    /// * `self` and `other` belong to different graphs, or they have different shapes.
+    pub fn maximum(self, other: Self) -> Self {
+        Self::apply(
+            self.graph,
+            Box::new(operator::maximum::Maximum::new()),
+            &[self, other],
+        )
+        .unwrap()
+    }
+
+    /// Registers `Equal` operation to the graph: elementwise x == other.
+    ///
+    /// The result is 1 for true and 0 for false, and no gradient is propagated through this
+    /// operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Right-hand side argument.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the result.
+    ///
+    /// # Panics
+    ///
+    /// * `self` and `other` belong to different graphs, or they have different shapes.
+    pub fn equal(self, other: Self) -> Self {
+        Self::apply(
+            self.graph,
+            Box::new(operator::equal::Equal::new()),
+            &[self, other],
+        )
+        .unwrap()
+    }
+
+    /// Registers `Less` operation to the graph: elementwise x < other.
+    ///
+    /// The result is 1 for true and 0 for false, and no gradient is propagated through this
+    /// operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Right-hand side argument.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the result.
+    ///
+    /// # Panics
+    ///
+    /// * `self` and `other` belong to different graphs, or they have different shapes.
+    pub fn less(self, other: Self) -> Self {
+        Self::apply(
+            self.graph,
+            Box::new(operator::less::Less::new()),
+            &[self, other],
+        )
+        .unwrap()
+    }
+
+    /// Registers `LessEqual` operation to the graph: elementwise x <= other.
+    ///
+    /// The result is 1 for true and 0 for false, and no gradient is propagated through this
+    /// operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Right-hand side argument.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the result.
+    ///
+    /// # Panics
+    ///
+    /// * `self` and `other` belong to different graphs, or they have different shapes.
+    pub fn less_equal(self, other: Self) -> Self {
+        Self::apply(
+            self.graph,
+            Box::new(operator::less_equal::LessEqual::new()),
+            &[self, other],
+        )
+        .unwrap()
+    }
+
+    /// Registers `Less` operation to the graph with swapped arguments: elementwise x > other.
+    ///
+    /// The result is 1 for true and 0 for false, and no gradient is propagated through this
+    /// operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Right-hand side argument.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the result.
+    ///
+    /// # Panics
+    ///
+    /// * `self` and `other` belong to different graphs, or they have different shapes.
+    pub fn greater(self, other: Self) -> Self {
+        Self::apply(
+            self.graph,
+            Box::new(operator::less::Less::new()),
+            &[other, self],
+        )
+        .unwrap()
+    }
+
+    /// Registers `LessEqual` operation to the graph with swapped arguments: elementwise x >= other.
+    ///
+    /// The result is 1 for true and 0 for false, and no gradient is propagated through this
+    /// operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Right-hand side argument.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the result.
+    ///
+    /// # Panics
+    ///
+    /// * `self` and `other` belong to different graphs, or they have different shapes.
+    pub fn greater_equal(self, other: Self) -> Self {
+        Self::apply(
+            self.graph,
+            Box::new(operator::less_equal::LessEqual::new()),
+            &[other, self],
+        )
+        .unwrap()
+    }
+
+    /// Registers `LogicalAnd` operation to the graph: elementwise logical AND.
+    ///
+    /// Nonzero values are regarded as true. The result is 1 for true and 0 for false, and no
+    /// gradient is propagated through this operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Right-hand side argument.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the result.
+    ///
+    /// # Panics
+    ///
+    /// * `self` and `other` belong to different graphs, or they have different shapes.
+    pub fn logical_and(self, other: Self) -> Self {
+        Self::apply(
+            self.graph,
+            Box::new(operator::logical_and::LogicalAnd::new()),
+            &[self, other],
+        )
+        .unwrap()
+    }
+
+    /// Registers `LogicalOr` operation to the graph: elementwise logical OR.
+    ///
+    /// Nonzero values are regarded as true. The result is 1 for true and 0 for false, and no
+    /// gradient is propagated through this operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Right-hand side argument.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the result.
+    ///
+    /// # Panics
+    ///
+    /// * `self` and `other` belong to different graphs, or they have different shapes.
+    pub fn logical_or(self, other: Self) -> Self {
+        Self::apply(
+            self.graph,
+            Box::new(operator::logical_or::LogicalOr::new()),
+            &[self, other],
+        )
+        .unwrap()
+    }
+
+    /// Registers `LogicalNot` operation to the graph: elementwise logical NOT, i.e., 1 for zeros and 0 otherwise.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the result.
+    pub fn logical_not(self) -> Self {
+        Self::apply(
+            self.graph,
+            Box::new(operator::logical_not::LogicalNot::new()),
+            &[self],
+        )
+        .unwrap()
+    }
+
+    /// Registers `IsNan` operation to the graph: elementwise check of NaN, i.e., 1 for NaN and 0 otherwise.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the result.
+    pub fn isnan(self) -> Self {
+        Self::apply(self.graph, Box::new(operator::isnan::IsNan::new()), &[self]).unwrap()
+    }
+
+    /// Registers `IsInf` operation to the graph: elementwise check of infinity, i.e., 1 for positive or negative infinity and
+    /// 0 otherwise.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the result.
+    pub fn isinf(self) -> Self {
+        Self::apply(self.graph, Box::new(operator::isinf::IsInf::new()), &[self]).unwrap()
+    }
+
+    /// Registers `Select` operation to the graph: elementwise selection, also known as "where",
+    /// which picks `on_true` where the condition `self` is nonzero, and `on_false` otherwise.
+    ///
+    /// Gradients are propagated only to the selected argument, and the condition receives no
+    /// gradient.
+    ///
+    /// # Arguments
+    ///
+    /// * `on_true` - Values selected by nonzero conditions.
+    /// * `on_false` - Values selected by zero conditions.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the result.
+    ///
+    /// # Panics
+    ///
+    /// * The nodes belong to different graphs, or they have different shapes.
+    pub fn select(self, on_true: Self, on_false: Self) -> Self {
+        Self::apply(
+            self.graph,
+            Box::new(operator::select::Select::new()),
+            &[self, on_true, on_false],
+        )
+        .unwrap()
+    }
+
+    /// Registers `MatMul` operation to the graph: matrix multiplication `self @ other`.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Right-hand side matrix.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the result.
+    ///
+    /// # Panics
+    ///
+    /// * `self` and `other` belong to different graphs, or they are not matrices with the
+    ///   matching inner dimension.
+    pub fn matmul(self, other: Self) -> Self {
+        Self::apply(
+            self.graph,
+            Box::new(operator::matmul::MatMul::new()),
+            &[self, other],
+        )
+        .unwrap()
+    }
+
+    /// Registers `Transpose` operation to the graph.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the transposed matrix.
+    ///
+    /// # Panics
+    ///
+    /// * `self` is not a matrix.
+    pub fn transpose(self) -> Self {
+        Self::apply(
+            self.graph,
+            Box::new(operator::transpose::Transpose::new()),
+            &[self],
+        )
+        .unwrap()
+    }
+
+    /// Registers `SumAxis` operation to the graph: sums up values along `axis`.
+    ///
+    /// # Arguments
+    ///
+    /// * `axis` - Index of the axis to be reduced. This axis is removed from the result.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the result.
+    ///
+    /// # Panics
+    ///
+    /// * `axis` is out-of-range.
+    pub fn sum_axis(self, axis: usize) -> Self {
+        Self::apply(
+            self.graph,
+            Box::new(operator::sum_axis::SumAxis::new(axis)),
+            &[self],
+        )
+        .unwrap()
+    }
+
+    /// Registers `ExpandAxis` operation to the graph: repeats values along a new axis.
+    ///
+    /// This operation is used to broadcast values explicitly, e.g., a bias of the shape `[n]` can
+    /// be added to a batch of the shape `[m, n]` by `bias.expand_axis(0, m)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `axis` - Index of the new axis in the result.
+    /// * `size` - Number of repetitions.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the result.
+    ///
+    /// # Panics
+    ///
+    /// * `axis` is greater than the number of dimensions of `self`.
+    pub fn expand_axis(self, axis: usize, size: usize) -> Self {
+        Self::apply(
+            self.graph,
+            Box::new(operator::expand_axis::ExpandAxis::new(axis, size)),
+            &[self],
+        )
+        .unwrap()
+    }
+
+    /// Registers `GatherRows` operation to the graph: looks up rows of `self` by `indices`.
+    ///
+    /// The resulting shape is the shape of `indices` followed by the dimensions of `self` except
+    /// the first one. Gradients are accumulated into the referred rows, and no gradient is
+    /// propagated to `indices`.
+    ///
+    /// # Arguments
+    ///
+    /// * `indices` - Row indices. Values must be non-negative integers less than the first
+    ///   dimension of `self`; otherwise the calculation of the resulting node panics.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the result.
+    ///
+    /// # Panics
+    ///
+    /// * `self` and `indices` belong to different graphs, or `self` is a scalar.
+    pub fn gather_rows(self, indices: Self) -> Self {
+        Self::apply(
+            self.graph,
+            Box::new(operator::gather_rows::GatherRows::new()),
+            &[self, indices],
+        )
+        .unwrap()
+    }
+
+    /// Registers `Conv2d` operation to the graph: 2-dimensional convolution (cross-correlation)
+    /// of images `self` by kernels `weight`.
+    ///
+    /// # Arguments
+    ///
+    /// * `weight` - Kernels of the shape `[out_channels, in_channels / groups, kernel_height,
+    ///   kernel_width]`.
+    /// * `options` - Stride, padding, dilation and groups of the convolution.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` of the shape `[batch, out_channels, out_height, out_width]`.
+    ///
+    /// # Panics
+    ///
+    /// * `self` and `weight` belong to different graphs, `self` is not of the shape
+    ///   `[batch, in_channels, height, width]`, or the shapes and options are not consistent.
+    pub fn conv2d(self, weight: Self, options: Conv2dOptions) -> Self {
+        Self::apply(
+            self.graph,
+            Box::new(operator::conv2d::Conv2d::new(options)),
+            &[self, weight],
+        )
+        .unwrap()
+    }
+
+    /// Registers operations of 1-dimensional convolution (cross-correlation) of sequences `self`
+    /// by kernels `weight`.
+    ///
+    /// This operation is calculated as `Conv2d` over images of height 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `weight` - Kernels of the shape `[out_channels, in_channels / groups, kernel_size]`.
+    /// * `options` - Stride, padding, dilation and groups of the convolution.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` of the shape `[batch, out_channels, out_length]`.
+    ///
+    /// # Panics
+    ///
+    /// * `self` and `weight` belong to different graphs, `self` is not of the shape
+    ///   `[batch, in_channels, length]`, or the shapes and options are not consistent.
+    pub fn conv1d(self, weight: Self, options: Conv1dOptions) -> Self {
+        self.expand_axis(2, 1)
+            .conv2d(weight.expand_axis(2, 1), options.to_2d())
+            .sum_axis(2)
+    }
+
+    /// Registers `MaxPool2d` operation to the graph: maximum of each window over images `self`.
+    ///
+    /// Gradients are propagated only to the first maximum of each window.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Kernel size, stride and padding of the pooling.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` of the shape `[batch, channels, out_height, out_width]`.
+    ///
+    /// # Panics
+    ///
+    /// * `self` is not of the shape `[batch, channels, height, width]`, or the shape and options
+    ///   are not consistent.
+    pub fn max_pool2d(self, options: Pool2dOptions) -> Self {
+        Self::apply(
+            self.graph,
+            Box::new(operator::max_pool2d::MaxPool2d::new(options)),
+            &[self],
+        )
+        .unwrap()
+    }
+
+    /// Registers `AvgPool2d` operation to the graph: average of each window over images `self`.
+    ///
+    /// Padded values are treated as zeros, i.e., every window is divided by the kernel size.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Kernel size, stride and padding of the pooling.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` of the shape `[batch, channels, out_height, out_width]`.
+    ///
+    /// # Panics
+    ///
+    /// * `self` is not of the shape `[batch, channels, height, width]`, or the shape and options
+    ///   are not consistent.
+    pub fn avg_pool2d(self, options: Pool2dOptions) -> Self {
+        Self::apply(
+            self.graph,
+            Box::new(operator::avg_pool2d::AvgPool2d::new(options)),
+            &[self],
+        )
+        .unwrap()
+    }
+
+    /// Registers `LayerNorm` operation to the graph: normalization over the last axis.
+    ///
+    /// ```text
+    /// y = (x - mean(x)) / sqrt(var(x) + epsilon) * gamma + beta
+    /// ```
+    ///
+    /// The mean and the biased variance are calculated over the last axis. Gradients of any order
+    /// are supported.
+    ///
+    /// # Arguments
+    ///
+    /// * `gamma` - Scaling factors of the shape `[size]`, where `size` is the last dimension of
+    ///   `self`.
+    /// * `beta` - Shifts of the shape `[size]`.
+    /// * `epsilon` - Small value added to the variance.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` of the same shape as `self`.
+    ///
+    /// # Panics
+    ///
+    /// * The nodes belong to different graphs, `self` is a scalar, or `gamma` or `beta` is not of
+    ///   the shape `[size]`.
+    pub fn layer_norm(self, gamma: Self, beta: Self, epsilon: f32) -> Self {
+        Self::apply(
+            self.graph,
+            Box::new(operator::layer_norm::LayerNorm::new(epsilon)),
+            &[self, gamma, beta],
+        )
+        .unwrap()
+    }
+
+    /// Registers `RmsNorm` operation to the graph: normalization by the root mean square over the
+    /// last axis.
+    ///
+    /// ```text
+    /// y = x / sqrt(mean(x^2) + epsilon) * gamma
+    /// ```
+    ///
+    /// Gradients of any order are supported.
+    ///
+    /// # Arguments
+    ///
+    /// * `gamma` - Scaling factors of the shape `[size]`, where `size` is the last dimension of
+    ///   `self`.
+    /// * `epsilon` - Small value added to the mean square.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` of the same shape as `self`.
+    ///
+    /// # Panics
+    ///
+    /// * The nodes belong to different graphs, `self` is a scalar, or `gamma` is not of the shape
+    ///   `[size]`.
+    pub fn rms_norm(self, gamma: Self, epsilon: f32) -> Self {
+        Self::apply(
+            self.graph,
+            Box::new(operator::rms_norm::RmsNorm::new(epsilon)),
+            &[self, gamma],
+        )
+        .unwrap()
+    }
+
+    /// Registers `BatchNorm` operation to the graph: normalization of each channel with the
+    /// statistics of the batch `self`.
+    ///
+    /// ```text
+    /// y = (x - mean(x)) / sqrt(var(x) + epsilon) * gamma + beta
+    /// ```
+    ///
+    /// The mean and the biased variance are calculated over every axis except the channel axis,
+    /// i.e., the second one. Gradients of any order are supported.
+    ///
+    /// # Arguments
+    ///
+    /// * `gamma` - Scaling factors of the shape `[channels]`.
+    /// * `beta` - Shifts of the shape `[channels]`.
+    /// * `epsilon` - Small value added to the variance.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` of the same shape as `self`.
+    ///
+    /// # Panics
+    ///
+    /// * The nodes belong to different graphs, `self` is not of the shape
+    ///   `[batch, channels, ...]`, or `gamma` or `beta` is not of the shape `[channels]`.
+    pub fn batch_norm(self, gamma: Self, beta: Self, epsilon: f32) -> Self {
+        Self::apply(
+            self.graph,
+            Box::new(operator::batch_norm::BatchNorm::new(epsilon)),
+            &[self, gamma, beta],
+        )
+        .unwrap()
+    }
+
+    /// Registers `BatchNormInference` operation to the graph: normalization of each channel with
+    /// given statistics, e.g., running statistics collected during training.
+    ///
+    /// ```text
+    /// y = (x - mean) / sqrt(variance + epsilon) * gamma + beta
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `mean` - Means of the shape `[channels]`.
+    /// * `variance` - Variances of the shape `[channels]`.
+    /// * `gamma` - Scaling factors of the shape `[channels]`.
+    /// * `beta` - Shifts of the shape `[channels]`.
+    /// * `epsilon` - Small value added to the variance.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` of the same shape as `self`.
+    ///
+    /// # Panics
+    ///
+    /// * The nodes belong to different graphs, `self` is not of the shape
+    ///   `[batch, channels, ...]`, or some of the other nodes is not of the shape `[channels]`.
+    pub fn batch_norm_inference(
+        self,
+        mean: Self,
+        variance: Self,
+        gamma: Self,
+        beta: Self,
+        epsilon: f32,
+    ) -> Self {
+        Self::apply(
+            self.graph,
+            Box::new(operator::batch_norm_inference::BatchNormInference::new(
+                epsilon,
+            )),
+            &[self, mean, variance, gamma, beta],
+        )
+        .unwrap()
+    }
+
+    /// Registers `StopGradient` operation to the graph.
+    ///
+    /// The resulting node has the same value as `self`, but no gradient is propagated through it,
+    /// i.e., it is treated as a constant in `grad`, `vjp` and `jvp`.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the same value as `self`.
+    pub fn stop_gradient(self) -> Self {
+        Self::apply(
+            self.graph,
+            Box::new(operator::stop_gradient::StopGradient::new()),
+            &[self],
+        )
+        .unwrap()
     }
 
-    pub fn hardware(&self) -> &'hw RefCell<dyn Hardware> {
-        self.graph
-            .borrow()
-            .get_step(self.step_id)
-            .unwrap()
-            .output
-            .hardware()
+    /// Registers `StraightThrough` operation to the graph.
+    ///
+    /// The resulting node has the value of `forward`, while its gradient is propagated to
+    /// `backward_like` as if the node were `backward_like` itself. This is known as the
+    /// straight-through estimator, e.g., `Node::straight_through(round(x), x)` behaves like
+    /// rounding in the forward computation and like the identity in the backward computation.
+    ///
+    /// # Arguments
+    ///
+    /// * `forward` - `Node` providing the value.
+    /// * `backward_like` - `Node` receiving the gradient. It must have the same shape as
+    ///   `forward`.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the same value as `forward`.
+    ///
+    /// # Panics
+    ///
+    /// * `forward` and `backward_like` belong to different graphs, or have different shapes.
+    pub fn straight_through(forward: Self, backward_like: Self) -> Self {
+        Self::apply(
+            forward.graph,
+            Box::new(operator::straight_through::StraightThrough::new()),
+            &[forward, backward_like],
+        )
+        .unwrap()
     }
 
-    pub fn calculate(&self) -> Array<'hw> {
-        self.graph.borrow_mut().calculate(self.step_id).clone()
+    /// Registers `ClipGradient` operation to the graph.
+    ///
+    /// The resulting node has the same value as `self`, while every element of the gradient
+    /// passing through this node is clamped into the range [`min`, `max`] during backpropagation.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - Lower bound of the gradient.
+    /// * `max` - Upper bound of the gradient.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the same value as `self`.
+    ///
+    /// # Panics
+    ///
+    /// * `min` is greater than `max`.
+    pub fn clip_gradient(self, min: f32, max: f32) -> Self {
+        assert!(min <= max, "min must not be greater than max.");
+        Self::apply(
+            self.graph,
+            Box::new(operator::clip_gradient::ClipGradient::new(min, max)),
+            &[self],
+        )
+        .unwrap()
     }
 
-    /// Registers `Fill` operation to the graph.
+    /// Registers `Dropout` operation to the graph.
+    ///
+    /// In the training mode, each element of `self` is zeroed out with probability `p`, and the
+    /// remaining elements are scaled by 1 / (1 - p) to keep the expectation. The mask is sampled
+    /// from `rng`, and the same mask is applied to the gradient and the tangent passing through
+    /// the resulting node.
+    ///
+    /// In the evaluation mode, or if `p` is 0, this function returns `self` as is and `rng` is not
+    /// consumed.
     ///
     /// # Arguments
     ///
-    /// * `graph` - `Graph` object to register the operation.
-    /// * `hardware` - `Hardware` object to hold the value.
-    /// * `shape` - `Shape` of the output array.
-    /// * `value` - Value of each element in the output array.
-    pub fn fill(
-        graph: &'g RefCell<Graph<'hw, 'op>>,
-        hardware: &'hw RefCell<dyn Hardware>,
-        shape: Shape,
-        value: f32,
-    ) -> Self {
-        Self::new(
+    /// * `rng` - Random number generator to sample the mask.
+    /// * `p` - Probability to drop each element.
+    /// * `training` - Whether the model is in the training mode or not.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the result, or `self` in the evaluation mode.
+    ///
+    /// # Panics
+    ///
+    /// * `p` is not in [0, 1].
+    pub fn dropout(self, rng: &mut Rng, p: f32, training: bool) -> Self {
+        assert!((0. ..=1.).contains(&p), "p must be in [0, 1].");
+        if !training || p == 0. {
+            return self;
+        }
+        let offset = rng.advance(self.shape().num_elements());
+        Self::apply(
+            self.graph,
+            Box::new(operator::dropout::Dropout::new(p, rng.seed(), offset)),
+            &[self],
+        )
+        .unwrap()
+    }
+
+    /// Calculates a function with the user-defined gradient.
+    ///
+    /// `forward_fn` is called immediately to construct the forward computation, and its result
+    /// is used as the value of the resulting node. Gradients are never propagated through the
+    /// graph constructed by `forward_fn`: `backward_fn` is called instead during backpropagation
+    /// to construct the gradients of `inputs`.
+    ///
+    /// Forward-mode differentiation (`jvp`) is not supported through the resulting node.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - Arguments of the function. This must not be empty.
+    /// * `forward_fn` - Function constructing the result from `inputs`.
+    /// * `backward_fn` - Function taking `inputs`, the result and its gradient `gy`, and
+    ///   returning the gradients of `inputs`. The number and shapes of the returned nodes must be
+    ///   the same as `inputs`. Since it is called in backpropagation, the returned nodes can be
+    ///   differentiated again to obtain higher-order derivatives.
+    ///
+    /// # Returns
+    ///
+    /// A new `Node` holding the result of `forward_fn`.
+    ///
+    /// # Panics
+    ///
+    /// * `inputs` is empty, or some nodes belong to different graphs.
+    /// * `backward_fn` returns an invalid number of nodes or nodes with invalid shapes.
+    /// * `jvp()` is called with tangents propagated to the resulting node.
+    pub fn custom_gradient<F, B>(inputs: &[Self], forward_fn: F, backward_fn: B) -> Self
+    where
+        F: FnOnce(&[Self]) -> Self,
+        B: for<'a, 'b, 'c> Fn(
+                &[Node<'a, 'b, 'c>],
+                Node<'a, 'b, 'c>,
+                Node<'a, 'b, 'c>,
+            ) -> Vec<Node<'a, 'b, 'c>>
+            + 'static,
+    {
+        assert!(
+            !inputs.is_empty(),
+            "custom_gradient requires at least one input."
+        );
+        let graph = inputs[0].graph;
+        let result = forward_fn(inputs).stop_gradient();
+        let mut args = inputs.to_vec();
+        args.push(result);
+        Self::apply(
             graph,
-            graph
-                .borrow_mut()
-                .add_step(
-                    Box::new(operator::fill::Fill::new(hardware, shape, value)),
-                    vec![],
-                )
-                .unwrap(),
+            Box::new(operator::custom_gradient::CustomGradient::new(
+                inputs.len(),
+                std::rc::Rc::new(backward_fn),
+            )),
+            &args,
         )
+        .unwrap()
     }
 }
 
@@ -147,6 +1181,28 @@ impl IntoNode for f32 {
     }
 }
 
+// Values other than scalars are first converted into `Array`s, and then registered as `Constant`.
+macro_rules! define_into_node_via_array {
+    ( $src_ty:ty ) => {
+        impl IntoNode for $src_ty {
+            fn into_node<'hw: 'op, 'op: 'g, 'g>(
+                self,
+                graph: &'g RefCell<Graph<'hw, 'op>>,
+                hardware: &'hw RefCell<dyn Hardware>,
+            ) -> Node<'hw, 'op, 'g> {
+                Node::constant(graph, self.into_array(hardware))
+            }
+        }
+    };
+}
+
+define_into_node_via_array!(Array<'_>);
+define_into_node_via_array!(&Array<'_>);
+define_into_node_via_array!(&[f32]);
+define_into_node_via_array!(Vec<f32>);
+define_into_node_via_array!((Shape, &[f32]));
+define_into_node_via_array!((Shape, Vec<f32>));
+
 /// Directly obtaining a scalar value from a Node.
 impl<'hw: 'op, 'op: 'g, 'g> TryFrom<Node<'hw, 'op, 'g>> for f32 {
     type Error = Error;
@@ -254,6 +1310,13 @@ impl<'hw: 'op, 'op: 'g, 'g> std::ops::Div for Node<'hw, 'op, 'g> {
 
 /// Calculates the value of the derivative dy/dx.
 ///
+/// If `y` is not a scalar, this function calculates the derivative of the sum of all elements in
+/// `y`, i.e., the backpropagation starts from a gradient filled by 1. Use `vjp` to start from
+/// arbitrary gradients.
+///
+/// Resulting nodes are usual nodes in the same graph, and they can be differentiated again to
+/// obtain higher-order derivatives, e.g., `grad(grad(y, &[x])[0], &[x])`.
+///
 /// # Arguments
 ///
 /// * `y` - `Node` representing the output value.
@@ -272,32 +1335,111 @@ pub fn grad<'hw, 'op, 'g>(
     y: Node<'hw, 'op, 'g>,
     x: &[Node<'hw, 'op, 'g>],
 ) -> Vec<Node<'hw, 'op, 'g>> {
-    // Strategy: calculates gradients of every step between the earliest step in `x` and `y`.
-    // This is redundant because some steps may not belong to the path between any of `x` and `y`,
-    // But it may be enough efficient because the usual use-case of this function may be
-    // "calculating graditns from the last step to every input."
+    if x.is_empty() {
+        return vec![]; // No need to calculate any gradients.
+    }
 
-    let g = y.graph;
+    // Assigns the gradient of `y` == 1.
+    let gy = Node::fill(y.graph, y.hardware(), y.shape(), 1.);
+
+    vjp(&[y], x, &[gy])
+}
+
+/// Calculates the value of the vector-Jacobian product gy * (dy/dx) by reverse-mode
+/// differentiation.
+///
+/// Unlike `grad`, this function takes arbitrary gradients of the outputs. This is useful to
+/// backpropagate through a part of the whole computation, e.g., when the downstream computation
+/// is performed elsewhere and only its gradients are available.
+///
+/// # Arguments
+///
+/// * `y` - List of `Node`s representing the output values.
+/// * `x` - List of `Node`s representing the input values.
+/// * `gy` - List of `Node`s representing the gradients of `y`. The number of elements must be the
+///   same as `y`, and each gradient must have the same shape as the corresponding output.
+///
+/// # Returns
+///
+/// New `Node`s representing sum(gy[i] * dy[i]/dx). The order of elements corresponds to that of
+/// `x`.
+///
+/// # Panics
+///
+/// * Attempting to calculate gradients between nodes on different graphs.
+/// * `y` and `gy` have different lengths, or some gradient has a different shape with its output.
+/// * Some nodes hold invalid information.
+pub fn vjp<'hw, 'op, 'g>(
+    y: &[Node<'hw, 'op, 'g>],
+    x: &[Node<'hw, 'op, 'g>],
+    gy: &[Node<'hw, 'op, 'g>],
+) -> Vec<Node<'hw, 'op, 'g>> {
+    // Strategy: calculates gradients of only steps on some path between `x` and `y`.
+    // A step is differentiated only if it depends on some `x` (forward reachability), and
+    // gradients of `y` are propagated to it (backward reachability). Other steps are skipped so
+    // that side computations in the graph do not produce unnecessary gradient steps.
+
+    let g = match x.first() {
+        Some(node) => node.graph,
+        None => return vec![], // `x` is empty. No need to calculate any gradients.
+    };
     assert!(
-        x.iter().all(|node| ptr::eq(node.graph, g)),
+        x.iter()
+            .chain(y.iter())
+            .chain(gy.iter())
+            .all(|node| ptr::eq(node.graph, g)),
         "Gradients can not be calculated beyond different graphs."
     );
+    assert_eq!(
+        y.len(),
+        gy.len(),
+        "Number of output gradients must be the same as that of outputs."
+    );
+    assert!(
+        y.iter()
+            .zip(gy.iter())
+            .all(|(y, gy)| y.shape() == gy.shape()),
+        "Shapes of output gradients must be the same as those of outputs."
+    );
 
-    let first_step_id = match x.iter().map(|node| node.step_id).min() {
-        Some(step_id) => step_id,
-        None => return vec![], // `x` is empty. No need to calculate any gradients.
-    };
-    let last_step_id = y.step_id;
+    let first_step_id = x.iter().map(|node| node.step_id).min().unwrap();
+    let last_step_id = y.iter().map(|node| node.step_id).max();
 
     // Placeholder of gradient nodes.
     let mut gradients = vec![None; g.borrow().num_steps()];
 
-    // Assigns the gradient of `y` == 1.
-    *(unsafe { gradients.get_unchecked_mut(last_step_id) }) =
-        Some(Node::fill(g, y.hardware(), y.shape(), 1.));
+    // Marks steps depending on some `x`.
+    let mut depends_on_x = vec![false; gradients.len()];
+    for node in x {
+        *(unsafe { depends_on_x.get_unchecked_mut(node.step_id) }) = true;
+    }
+    for step_id in (first_step_id + 1)..=last_step_id.unwrap_or(0) {
+        let depends = g
+            .borrow()
+            .get_step(step_id)
+            .unwrap()
+            .inputs
+            .iter()
+            .any(|&input_id| *unsafe { depends_on_x.get_unchecked(input_id) });
+        let cur = unsafe { depends_on_x.get_unchecked_mut(step_id) };
+        *cur = *cur || depends;
+    }
+
+    // Assigns the initial gradients.
+    for (node, &seed) in y.iter().zip(gy.iter()) {
+        let prev_gy = unsafe { gradients.get_unchecked_mut(node.step_id) };
+        *prev_gy = match prev_gy {
+            Some(prev) => Some(*prev + seed),
+            None => Some(seed),
+        };
+    }
 
     // Performs backpropagation.
-    for step_id in ((first_step_id + 1)..=last_step_id).rev() {
+    for step_id in ((first_step_id + 1)..=last_step_id.unwrap_or(0)).rev() {
+        if !*unsafe { depends_on_x.get_unchecked(step_id) } {
+            continue; // This step does not depend on any `x`.
+        }
+
         let cur_gy = match unsafe { gradients.get_unchecked(step_id) } {
             Some(node) => *node,
             None => continue, // No preceding gradients propagated to this step.
@@ -314,16 +1456,32 @@ pub fn grad<'hw, 'op, 'g>(
             None => continue, // No gradient operation is defined for this step.
         };
 
+        // Only gradients of inputs depending on some `x` are used. This step may be some `x`
+        // which does not depend on any other `x`.
+        let needs_grad = cur_xs_ids
+            .iter()
+            .map(|&x_id| *unsafe { depends_on_x.get_unchecked(x_id) })
+            .collect::<Vec<_>>();
+        if !needs_grad.iter().any(|&b| b) {
+            continue; // No gradients are propagated through this step.
+        }
+
         // Calculates gradients for this step.
         let cur_xs = cur_xs_ids
             .iter()
             .map(|&step_id| Node::new(g, step_id))
             .collect::<Vec<_>>();
         let cur_y = Node::new(g, step_id);
-        let cur_gxs = grad_fn.perform(&cur_xs, cur_y, cur_gy);
+        let cur_gxs = grad_fn.perform(&cur_xs, cur_y, cur_gy, &needs_grad);
 
         // Integrates gradients.
-        for (&cur_x_id, &cur_gx) in cur_xs_ids.iter().zip(cur_gxs.iter()) {
+        for ((&cur_x_id, &cur_gx), &needs_grad) in
+            cur_xs_ids.iter().zip(cur_gxs.iter()).zip(needs_grad.iter())
+        {
+            let cur_gx = match cur_gx {
+                Some(node) if needs_grad => node,
+                _ => continue, // Gradients of this input are 0 or never used.
+            };
             let prev_gx = unsafe { gradients.get_unchecked_mut(cur_x_id) };
             *prev_gx = match prev_gx {
                 Some(node) => Some(*node + cur_gx),
@@ -345,11 +1503,295 @@ pub fn grad<'hw, 'op, 'g>(
         .collect::<Vec<_>>()
 }
 
+/// Calculates the value of the Hessian-vector product (d^2y/dx^2) * v.
+///
+/// This function performs the reverse-mode differentiation twice: the first pass obtains dy/dx,
+/// and the second pass backpropagates `v` through dy/dx. As the Hessian is symmetric, the result
+/// is equal to the product between the Hessian and `v`.
+///
+/// If `y` is not a scalar, this function treats the sum of all elements in `y` as the output.
+///
+/// # Arguments
+///
+/// * `y` - `Node` representing the output value.
+/// * `x` - List of `Node`s representing the input values.
+/// * `v` - List of `Node`s representing the vector. The number of elements must be the same as
+///   `x`, and each node must have the same shape as the corresponding input.
+///
+/// # Returns
+///
+/// New `Node`s representing sum_j (d^2y/dx[i]dx[j]) * v[j]. The order of elements corresponds to
+/// that of `x`.
+///
+/// # Panics
+///
+/// * Attempting to calculate gradients between nodes on different graphs.
+/// * `x` and `v` have different lengths, or some node in `v` has a different shape with its
+///   input.
+/// * Some nodes hold invalid information.
+pub fn hvp<'hw, 'op, 'g>(
+    y: Node<'hw, 'op, 'g>,
+    x: &[Node<'hw, 'op, 'g>],
+    v: &[Node<'hw, 'op, 'g>],
+) -> Vec<Node<'hw, 'op, 'g>> {
+    assert_eq!(
+        x.len(),
+        v.len(),
+        "Number of vectors must be the same as that of inputs."
+    );
+    let gx = grad(y, x);
+    vjp(&gx, x, v)
+}
+
+/// Calculates all second-order derivatives d^2y/dx[i]dx[j].
+///
+/// This function calculates the Hessian-vector products with every one-hot vector, which requires
+/// as many backpropagations as the number of elements in `x`. It is intended to be used only for
+/// small inputs.
+///
+/// If `y` is not a scalar, this function treats the sum of all elements in `y` as the output.
+///
+/// # Arguments
+///
+/// * `y` - `Node` representing the output value.
+/// * `x` - List of `Node`s representing the input values.
+///
+/// # Returns
+///
+/// Rows of the Hessian. Each row corresponds to an element of `x`: all elements of `x[0]` in the
+/// row-major order, then all elements of `x[1]`, and so on.
+/// Each row holds new `Node`s representing d^2y/dx[i]dx[j] for the corresponding element of `x[i]`.
+/// The order of nodes in each row corresponds to that of `x`, and each node has the same shape as
+/// the corresponding input.
+///
+/// # Panics
+///
+/// * Attempting to calculate gradients between nodes on different graphs.
+/// * Some nodes hold invalid information.
+pub fn hessian<'hw, 'op, 'g>(
+    y: Node<'hw, 'op, 'g>,
+    x: &[Node<'hw, 'op, 'g>],
+) -> Vec<Vec<Node<'hw, 'op, 'g>>> {
+    let g = y.graph;
+    let gx = grad(y, x);
+
+    // Zero vectors are shared by all rows.
+    let zeros = x
+        .iter()
+        .map(|node| Node::fill(g, node.hardware(), node.shape(), 0.))
+        .collect::<Vec<_>>();
+
+    let mut rows = vec![];
+    for (i, node) in x.iter().enumerate() {
+        let shape = node.shape();
+        let num_elements = shape.num_elements();
+        for k in 0..num_elements {
+            let mut values = vec![0.; num_elements];
+            values[k] = 1.;
+            let one_hot = Array::constant_f32(node.hardware(), shape.clone(), &values).unwrap();
+            let one_hot = Node::new(
+                g,
+                g.borrow_mut()
+                    .add_step(Box::new(operator::constant::Constant::new(one_hot)), vec![])
+                    .unwrap(),
+            );
+            let mut v = zeros.clone();
+            v[i] = one_hot;
+            rows.push(vjp(&gx, x, &v));
+        }
+    }
+    rows
+}
+
+/// Calculates the value of the Jacobian-vector product (dy/dx) * dx by forward-mode
+/// differentiation.
+///
+/// # Arguments
+///
+/// * `y` - List of `Node`s representing the output values.
+/// * `x` - List of `Node`s representing the input values.
+/// * `dx` - List of `Node`s representing the tangents of `x`. The number of elements must be the
+///   same as `x`, and each tangent must have the same shape as the corresponding input.
+///
+/// # Returns
+///
+/// New `Node`s representing the tangents of `y`: sum((dy/dx[i]) * dx[i]). The order of elements
+/// corresponds to that of `y`.
+///
+/// # Panics
+///
+/// * Attempting to calculate tangents between nodes on different graphs.
+/// * `x` and `dx` have different lengths, or some tangent has a different shape with its input.
+/// * Some step between `x` and `y` receives tangents, but its operator does not implement the
+///   forward-mode gradient.
+/// * Some nodes hold invalid information.
+pub fn jvp<'hw, 'op, 'g>(
+    y: &[Node<'hw, 'op, 'g>],
+    x: &[Node<'hw, 'op, 'g>],
+    dx: &[Node<'hw, 'op, 'g>],
+) -> Vec<Node<'hw, 'op, 'g>> {
+    // Strategy: propagates tangents through only steps on some path between `x` and `y`.
+    // A step is differentiated only if tangents are propagated to it (forward reachability), and
+    // it contributes to some `y` (backward reachability). Other steps are skipped so that side
+    // computations in the graph do not produce unnecessary tangent steps.
+
+    let g = match y.first() {
+        Some(node) => node.graph,
+        None => return vec![], // `y` is empty. No need to calculate any tangents.
+    };
+    assert!(
+        y.iter()
+            .chain(x.iter())
+            .chain(dx.iter())
+            .all(|node| ptr::eq(node.graph, g)),
+        "Tangents can not be calculated beyond different graphs."
+    );
+    assert_eq!(
+        x.len(),
+        dx.len(),
+        "Number of tangents must be the same as that of inputs."
+    );
+    assert!(
+        x.iter()
+            .zip(dx.iter())
+            .all(|(x, dx)| x.shape() == dx.shape()),
+        "Shapes of tangents must be the same as those of inputs."
+    );
+
+    let last_step_id = y.iter().map(|node| node.step_id).max().unwrap();
+    let first_step_id = x
+        .iter()
+        .map(|node| node.step_id)
+        .min()
+        .unwrap_or(last_step_id + 1);
+
+    // Placeholder of tangent nodes.
+    let mut tangents = vec![None; last_step_id + 1];
+
+    // Marks steps contributing to some `y`.
+    let mut contributes_to_y = vec![false; last_step_id + 1];
+    for node in y {
+        *(unsafe { contributes_to_y.get_unchecked_mut(node.step_id) }) = true;
+    }
+    for step_id in (first_step_id..=last_step_id).rev() {
+        if !*unsafe { contributes_to_y.get_unchecked(step_id) } {
+            continue;
+        }
+        for &input_id in &g.borrow().get_step(step_id).unwrap().inputs {
+            *(unsafe { contributes_to_y.get_unchecked_mut(input_id) }) = true;
+        }
+    }
+
+    // Tangents given to `x`. They are integrated with propagated tangents below so that `x` which
+    // depends on other `x` obtains the total derivative.
+    let mut seeds = vec![None; last_step_id + 1];
+    for (node, &seed) in x.iter().zip(dx.iter()) {
+        if node.step_id <= last_step_id {
+            let prev = unsafe { seeds.get_unchecked_mut(node.step_id) };
+            *prev = match prev {
+                Some(prev) => Some(*prev + seed),
+                None => Some(seed),
+            };
+        }
+    }
+
+    // Performs forward propagation.
+    for step_id in first_step_id..=last_step_id {
+        if !*unsafe { contributes_to_y.get_unchecked(step_id) } {
+            continue; // This step does not contribute to any `y`.
+        }
+
+        let (cur_xs_ids, op_name, maybe_fwd_fn) = {
+            let g = g.borrow();
+            let step = g.get_step(step_id).unwrap();
+            (
+                step.inputs.clone(),
+                step.operator.name(),
+                step.operator.get_forward_gradient_fn(),
+            )
+        };
+
+        let propagated = if cur_xs_ids
+            .iter()
+            .any(|&x_id| unsafe { tangents.get_unchecked(x_id) }.is_some())
+        {
+            // Operators with locally constant outputs explicitly define zero tangents, so a missing
+            // forward-mode gradient would silently lose the derivative.
+            let fwd_fn = maybe_fwd_fn.unwrap_or_else(|| {
+                panic!("Forward-mode gradient is not implemented for {}.", op_name)
+            });
+            let cur_xs = cur_xs_ids
+                .iter()
+                .map(|&x_id| Node::new(g, x_id))
+                .collect::<Vec<_>>();
+            // Inputs without tangents do not depend on `x`, assuming that their tangents are
+            // 0.
+            let cur_dxs = cur_xs
+                .iter()
+                .map(
+                    |cur_x| match unsafe { tangents.get_unchecked(cur_x.step_id) } {
+                        Some(dx) => *dx,
+                        None => Node::fill(g, cur_x.hardware(), cur_x.shape(), 0.),
+                    },
+                )
+                .collect::<Vec<_>>();
+            Some(fwd_fn.perform(&cur_xs, Node::new(g, step_id), &cur_dxs))
+        } else {
+            None // No preceding tangents propagated to this step.
+        };
+
+        *(unsafe { tangents.get_unchecked_mut(step_id) }) =
+            match (propagated, *unsafe { seeds.get_unchecked(step_id) }) {
+                (Some(propagated), Some(seed)) => Some(propagated + seed),
+                (propagated, seed) => propagated.or(seed),
+            };
+    }
+
+    // Collects the nodes representing tangents of `y`.
+    y.iter()
+        .map(|node| {
+            match unsafe { tangents.get_unchecked(node.step_id) } {
+                Some(tangent) => *tangent,
+                // No tangent propagation occurred for this node,
+                // assuming that the tangent is 0.
+                None => Node::fill(g, node.hardware(), node.shape(), 0.),
+            }
+        })
+        .collect::<Vec<_>>()
+}
+
 #[cfg(test)]
 mod tests;
 
 #[cfg(test)]
 mod grad_tests;
 
+#[cfg(test)]
+mod vjp_tests;
+
+#[cfg(test)]
+mod jvp_tests;
+
+#[cfg(test)]
+mod higher_order_tests;
+
+#[cfg(test)]
+mod apply_tests;
+
+#[cfg(test)]
+mod gradient_surgery_tests;
+
+#[cfg(test)]
+mod dropout_tests;
+
+#[cfg(test)]
+mod math_tests;
+
+#[cfg(test)]
+mod conv_tests;
+
+#[cfg(test)]
+mod norm_tests;
+
 #[cfg(feature = "ndarray-support")]
 mod convert_ndarray;
@@ -0,0 +1,341 @@
+use crate::hardware::cpu::CpuHardware;
+use crate::node::*;
+use crate::operator::{ForwardGradient, Gradient};
+use crate::serialize::Encoder;
+
+/// Example of user-defined unary operator: y = x * x.
+struct Square;
+
+impl<'hw> Operator<'hw> for Square {
+    fn name(&self) -> String {
+        String::from("Square")
+    }
+
+    fn input_size(&self) -> usize {
+        1
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        Ok(inputs[0].clone())
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        inputs[0].elementwise_mul_f32(inputs[0])
+    }
+
+    fn encode_attributes(&self, _encoder: &mut Encoder) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(SquareGrad {}))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(SquareForwardGrad {}))
+    }
+}
+
+/// Gradient for Square.
+struct SquareGrad;
+
+impl Gradient for SquareGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        _needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        vec![Some(gy * (x[0] + x[0]))]
+    }
+}
+
+/// Forward-mode gradient for Square.
+struct SquareForwardGrad;
+
+impl ForwardGradient for SquareForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        dx[0] * (x[0] + x[0])
+    }
+}
+
+/// Example of user-defined nullary operator: y = [0, 1, ..., n - 1].
+/// This operator does not define its gradient.
+struct Arange<'hw> {
+    hardware: &'hw RefCell<dyn Hardware>,
+    size: usize,
+}
+
+impl<'hw> Operator<'hw> for Arange<'hw> {
+    fn name(&self) -> String {
+        String::from("Arange")
+    }
+
+    fn input_size(&self) -> usize {
+        0
+    }
+
+    fn perform_shape(&self, _inputs: &[&Shape]) -> Result<Shape> {
+        Ok(Shape::new([self.size]))
+    }
+
+    fn perform_hardware(
+        &self,
+        _inputs: &[&'hw RefCell<dyn Hardware>],
+    ) -> Result<&'hw RefCell<dyn Hardware>> {
+        Ok(self.hardware)
+    }
+
+    fn perform(&self, _inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        let values = (0..self.size).map(|i| i as f32).collect::<Vec<_>>();
+        Array::constant_f32(self.hardware, Shape::new([self.size]), &values)
+    }
+}
+
+/// Example of user-defined operator which accepts only scalars.
+struct ScalarOnly;
+
+impl<'hw> Operator<'hw> for ScalarOnly {
+    fn name(&self) -> String {
+        String::from("ScalarOnly")
+    }
+
+    fn input_size(&self) -> usize {
+        1
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        inputs[0].check_is_scalar()?;
+        Ok(inputs[0].clone())
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        Ok(inputs[0].clone())
+    }
+}
+
+/// Example of user-defined operator which fails only when it is performed.
+struct AlwaysFail;
+
+impl<'hw> Operator<'hw> for AlwaysFail {
+    fn name(&self) -> String {
+        String::from("AlwaysFail")
+    }
+
+    fn input_size(&self) -> usize {
+        1
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        Ok(inputs[0].clone())
+    }
+
+    fn perform(&self, _inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        Err(Error::InvalidData("Intended failure.".to_string()))
+    }
+}
+
+#[test]
+fn test_apply_unary() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 3f32.into_node(&g, &hw);
+    let y = Node::apply(&g, Box::new(Square {}), &[x]).unwrap();
+
+    assert_eq!(y, Node::new(&g, 1));
+    assert_eq!(y.shape(), Shape::new([]));
+    assert!(ptr::eq(y.hardware(), &hw));
+    assert_eq!(g.borrow().get_step(1).unwrap().operator.name(), "Square");
+    assert_eq!(f32::try_from(y), Ok(9.));
+}
+
+#[test]
+fn test_apply_hash_consing() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    g.borrow_mut().set_hash_consing(true);
+
+    let x = 3f32.into_node(&g, &hw);
+    let y1 = Node::apply(&g, Box::new(Square {}), &[x]).unwrap();
+    let y2 = Node::apply(&g, Box::new(Square {}), &[x]).unwrap();
+    assert_eq!(y1, y2);
+    assert_eq!(g.borrow().num_steps(), 2);
+}
+
+#[test]
+fn test_apply_save() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    // User-defined operators can not be restored by Graph::load().
+    let x = 3f32.into_node(&g, &hw);
+    let _ = Node::apply(&g, Box::new(Square {}), &[x]).unwrap();
+    let mut bytes = vec![];
+    assert!(matches!(
+        g.borrow().save(&mut bytes, false),
+        Err(Error::NotSupported(_))
+    ));
+    assert!(bytes.is_empty());
+}
+
+#[test]
+fn test_apply_nullary() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let y = Node::apply(
+        &g,
+        Box::new(Arange {
+            hardware: &hw,
+            size: 4,
+        }),
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(y.shape(), Shape::new([4]));
+    assert!(ptr::eq(y.hardware(), &hw));
+    assert_eq!(y.calculate().get_values_f32(), vec![0., 1., 2., 3.]);
+}
+
+#[test]
+fn test_apply_chained() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = Node::apply(
+        &g,
+        Box::new(Arange {
+            hardware: &hw,
+            size: 3,
+        }),
+        &[],
+    )
+    .unwrap();
+    let y = Node::apply(&g, Box::new(Square {}), &[x]).unwrap();
+    let z = -y;
+
+    assert_eq!(z.calculate().get_values_f32(), vec![0., -1., -4.]);
+}
+
+#[test]
+fn test_apply_grad() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 3f32.into_node(&g, &hw);
+    let y = Node::apply(&g, Box::new(Square {}), &[x]).unwrap();
+
+    let gx1 = grad(y, &[x])[0];
+    let gx2 = grad(gx1, &[x])[0];
+
+    // y' == 2x
+    assert_eq!(f32::try_from(gx1), Ok(6.));
+    // y'' == 2
+    assert_eq!(f32::try_from(gx2), Ok(2.));
+}
+
+#[test]
+fn test_apply_jvp() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 3f32.into_node(&g, &hw);
+    let y = Node::apply(&g, Box::new(Square {}), &[x]).unwrap();
+    let dx = 0.5f32.into_node(&g, &hw);
+
+    // 2x * dx
+    assert_eq!(f32::try_from(jvp(&[y], &[x], &[dx])[0]), Ok(3.));
+}
+
+#[test]
+fn test_apply_without_gradient() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 3f32.into_node(&g, &hw);
+    let y = Node::apply(&g, Box::new(ScalarOnly {}), &[x]).unwrap();
+
+    // No gradient is propagated through the operator.
+    assert_eq!(f32::try_from(grad(y, &[x])[0]), Ok(0.));
+}
+
+#[test]
+#[should_panic(expected = "Forward-mode gradient is not implemented for ScalarOnly.")]
+fn test_apply_jvp_without_gradient() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 3f32.into_node(&g, &hw);
+    let y = Node::apply(&g, Box::new(ScalarOnly {}), &[x]).unwrap();
+    let dx = 1f32.into_node(&g, &hw);
+    let _dy = jvp(&[y], &[x], &[dx]);
+}
+
+#[test]
+#[should_panic(expected = "Failed to perform AlwaysFail")]
+fn test_apply_perform_error() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 3f32.into_node(&g, &hw);
+    let y = Node::apply(&g, Box::new(AlwaysFail {}), &[x]).unwrap();
+    let _y = y.calculate();
+}
+
+#[test]
+fn test_apply_invalid_shape() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = Node::fill(&g, &hw, Shape::new([3]), 1.);
+
+    assert!(matches!(
+        Node::apply(&g, Box::new(ScalarOnly {}), &[x]),
+        Err(Error::InvalidShape(_))
+    ));
+    // Failed operation is not registered.
+    assert_eq!(g.borrow().num_steps(), 1);
+}
+
+#[test]
+fn test_apply_invalid_num_inputs() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 1f32.into_node(&g, &hw);
+
+    assert!(matches!(
+        Node::apply(&g, Box::new(Square {}), &[x, x]),
+        Err(Error::InvalidLength(_))
+    ));
+    assert!(matches!(
+        Node::apply(&g, Box::new(Square {}), &[]),
+        Err(Error::InvalidLength(_))
+    ));
+}
+
+#[test]
+fn test_apply_different_graph() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g1 = RefCell::new(Graph::new());
+    let g2 = RefCell::new(Graph::new());
+
+    let x = 1f32.into_node(&g1, &hw);
+
+    assert!(matches!(
+        Node::apply(&g2, Box::new(Square {}), &[x]),
+        Err(Error::InvalidGraph(_))
+    ));
+}
@@ -0,0 +1,409 @@
+use crate::conv::{Conv1dOptions, Conv2dOptions, Pool2dOptions};
+use crate::hardware::cpu::CpuHardware;
+use crate::node::*;
+use crate::optim::sgd::Sgd;
+use crate::optim::Optimizer;
+use crate::parameter::ParameterStore;
+
+/// Sums up all elements.
+fn sum_all<'hw: 'op, 'op: 'g, 'g>(node: Node<'hw, 'op, 'g>) -> Node<'hw, 'op, 'g> {
+    (0..node.shape().num_dimensions()).fold(node, |node, _| node.sum_axis(0))
+}
+
+fn random<'hw: 'op, 'op: 'g, 'g>(
+    g: &'g RefCell<Graph<'hw, 'op>>,
+    hw: &'hw RefCell<CpuHardware>,
+    shape: Shape,
+    seed: u64,
+) -> Node<'hw, 'op, 'g> {
+    Node::constant(g, Array::random_uniform_f32(hw, shape, seed, 0, -1., 1.))
+}
+
+fn assert_close(observed: &[f32], expected: &[f32], tolerance: f32) {
+    assert_eq!(observed.len(), expected.len());
+    for (o, e) in observed.iter().zip(expected) {
+        assert!(
+            (o - e).abs() < tolerance,
+            "{:?} != {:?}",
+            observed,
+            expected
+        );
+    }
+}
+
+/// Calculates the gradient of `f` at `x` by central differences.
+fn numerical_grad(f: impl Fn(&[f32]) -> f32, x: &[f32]) -> Vec<f32> {
+    const EPS: f32 = 1e-2;
+    (0..x.len())
+        .map(|i| {
+            let mut x = x.to_vec();
+            x[i] += EPS;
+            let plus = f(&x);
+            x[i] -= 2. * EPS;
+            let minus = f(&x);
+            (plus - minus) / (2. * EPS)
+        })
+        .collect()
+}
+
+/// Checks the gradients and the forward-mode gradients of loss = sum(f(x, w) * r) against the
+/// numerical gradients.
+fn check_binary_gradients<F>(x_shape: Shape, w_shape: Shape, f: F)
+where
+    F: for<'hw, 'op, 'g> Fn(Node<'hw, 'op, 'g>, Node<'hw, 'op, 'g>) -> Node<'hw, 'op, 'g>,
+{
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let x = random(&g, &hw, x_shape.clone(), 1);
+    let w = random(&g, &hw, w_shape.clone(), 2);
+    let y = f(x, w);
+    let r = random(&g, &hw, y.shape(), 3);
+    let loss = sum_all(y * r);
+    let grads = grad(loss, &[x, w]);
+    assert_eq!(grads[0].shape(), x_shape);
+    assert_eq!(grads[1].shape(), w_shape);
+
+    let x_values = x.calculate().get_values_f32();
+    let w_values = w.calculate().get_values_f32();
+    let r_array = r.calculate();
+    let eval = |x_values: &[f32], w_values: &[f32]| {
+        let g = RefCell::new(Graph::new());
+        let x = (x_shape.clone(), x_values.to_vec()).into_node(&g, &hw);
+        let w = (w_shape.clone(), w_values.to_vec()).into_node(&g, &hw);
+        f32::try_from(sum_all(f(x, w) * Node::constant(&g, r_array.clone()))).unwrap()
+    };
+    assert_close(
+        &grads[0].calculate().get_values_f32(),
+        &numerical_grad(|x| eval(x, &w_values), &x_values),
+        1e-2,
+    );
+    assert_close(
+        &grads[1].calculate().get_values_f32(),
+        &numerical_grad(|w| eval(&x_values, w), &w_values),
+        1e-2,
+    );
+
+    // jvp(loss) = <grad x, dx> + <grad w, dw>
+    let dx = random(&g, &hw, x_shape.clone(), 4);
+    let dw = random(&g, &hw, w_shape.clone(), 5);
+    let expected = sum_all(grads[0] * dx) + sum_all(grads[1] * dw);
+    assert_close(
+        &jvp(&[loss], &[x, w], &[dx, dw])[0]
+            .calculate()
+            .get_values_f32(),
+        &expected.calculate().get_values_f32(),
+        1e-3,
+    );
+}
+
+#[test]
+fn test_conv2d() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let values = (1..=9).map(|x| x as f32).collect::<Vec<_>>();
+    let x = (Shape::new([1, 1, 3, 3]), values).into_node(&g, &hw);
+    let w = (Shape::new([1, 1, 2, 2]), vec![1f32, 0., 0., 1.]).into_node(&g, &hw);
+    let y = x.conv2d(w, Conv2dOptions::default());
+    assert_eq!(y.shape(), Shape::new([1, 1, 2, 2]));
+    assert_eq!(y.calculate().get_values_f32(), vec![6., 8., 12., 14.]);
+
+    let grads = grad(sum_all(y), &[x, w]);
+    assert_eq!(
+        grads[0].calculate().get_values_f32(),
+        vec![1., 1., 0., 1., 2., 1., 0., 1., 1.]
+    );
+    assert_eq!(
+        grads[1].calculate().get_values_f32(),
+        vec![12., 16., 24., 28.]
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_conv2d_invalid_shape() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let x = Node::fill(&g, &hw, Shape::new([1, 2, 3, 3]), 0.);
+    let w = Node::fill(&g, &hw, Shape::new([1, 3, 2, 2]), 0.);
+    let _ = x.conv2d(w, Conv2dOptions::default());
+}
+
+#[test]
+fn test_conv2d_gradients() {
+    check_binary_gradients(
+        Shape::new([2, 3, 4, 5]),
+        Shape::new([2, 3, 2, 3]),
+        |x, w| x.conv2d(w, Conv2dOptions::default()),
+    );
+    check_binary_gradients(
+        Shape::new([2, 4, 5, 6]),
+        Shape::new([6, 2, 3, 2]),
+        |x, w| {
+            x.conv2d(
+                w,
+                Conv2dOptions {
+                    stride: [2, 1],
+                    padding: [1, 2],
+                    dilation: [1, 2],
+                    groups: 2,
+                },
+            )
+        },
+    );
+}
+
+#[test]
+fn test_conv2d_second_order() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let options = Conv2dOptions {
+        stride: [2, 2],
+        padding: [1, 0],
+        ..Default::default()
+    };
+    let x = random(&g, &hw, Shape::new([2, 2, 5, 4]), 1);
+    let w = random(&g, &hw, Shape::new([3, 2, 3, 2]), 2);
+    let y = x.conv2d(w, options);
+    let r = random(&g, &hw, y.shape(), 3);
+    let grads = grad(sum_all(y * r), &[x, w]);
+
+    // d/dw <grad x, v> = d/dw <conv(v, w), r>
+    let v = random(&g, &hw, x.shape(), 4);
+    let observed = grad(sum_all(grads[0] * v), &[w])[0];
+    let expected = grad(sum_all(v.conv2d(w, options) * r), &[w])[0];
+    assert_close(
+        &observed.calculate().get_values_f32(),
+        &expected.calculate().get_values_f32(),
+        1e-4,
+    );
+
+    // d/dx <grad w, u> = d/dx <conv(x, u), r>
+    let u = random(&g, &hw, w.shape(), 5);
+    let observed = grad(sum_all(grads[1] * u), &[x])[0];
+    let expected = grad(sum_all(x.conv2d(u, options) * r), &[x])[0];
+    assert_close(
+        &observed.calculate().get_values_f32(),
+        &expected.calculate().get_values_f32(),
+        1e-4,
+    );
+
+    // Gradients with respect to the output gradient.
+    let gx = grad(sum_all(grads[0] * v), &[r])[0];
+    let gw = grad(sum_all(grads[1] * u), &[r])[0];
+    assert_close(
+        &gx.calculate().get_values_f32(),
+        &v.conv2d(w, options).calculate().get_values_f32(),
+        1e-4,
+    );
+    assert_close(
+        &gw.calculate().get_values_f32(),
+        &x.conv2d(u, options).calculate().get_values_f32(),
+        1e-4,
+    );
+}
+
+#[test]
+fn test_conv1d() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let x = (Shape::new([1, 1, 5]), vec![1f32, 2., 3., 4., 5.]).into_node(&g, &hw);
+    let w = (Shape::new([1, 1, 2]), vec![1f32, -1.]).into_node(&g, &hw);
+    let options = Conv1dOptions {
+        stride: 2,
+        padding: 1,
+        dilation: 2,
+        groups: 1,
+    };
+    let y = x.conv1d(w, options);
+    // Padded input: [0, 1, 2, 3, 4, 5, 0], windows at 0, 2 and 4 with taps at +0 and +2.
+    assert_eq!(y.shape(), Shape::new([1, 1, 3]));
+    assert_eq!(y.calculate().get_values_f32(), vec![-2., -2., 4.]);
+
+    check_binary_gradients(Shape::new([2, 4, 7]), Shape::new([4, 2, 3]), |x, w| {
+        x.conv1d(
+            w,
+            Conv1dOptions {
+                padding: 1,
+                groups: 2,
+                ..Default::default()
+            },
+        )
+    });
+}
+
+#[test]
+fn test_max_pool2d() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let x = (
+        Shape::new([1, 1, 2, 4]),
+        vec![1f32, 5., 2., 0., 4., 3., 6., 7.],
+    )
+        .into_node(&g, &hw);
+    let y = x.max_pool2d(Pool2dOptions::new([2, 2]));
+    assert_eq!(y.shape(), Shape::new([1, 1, 1, 2]));
+    assert_eq!(y.calculate().get_values_f32(), vec![5., 7.]);
+
+    let r = (Shape::new([1, 1, 1, 2]), vec![2f32, 3.]).into_node(&g, &hw);
+    let gx = grad(sum_all(y * r), &[x])[0];
+    assert_eq!(
+        gx.calculate().get_values_f32(),
+        vec![0., 2., 0., 0., 0., 0., 0., 3.]
+    );
+
+    let dx = (
+        Shape::new([1, 1, 2, 4]),
+        (0..8).map(|i| i as f32).collect::<Vec<_>>(),
+    )
+        .into_node(&g, &hw);
+    assert_eq!(
+        jvp(&[y], &[x], &[dx])[0].calculate().get_values_f32(),
+        vec![1., 7.]
+    );
+
+    // The gradient is linear in the output gradient, and piecewise constant in the input.
+    let v = (Shape::new([1, 1, 2, 4]), vec![1f32; 8]).into_node(&g, &hw);
+    let second = grad(sum_all(gx * v), &[x, r]);
+    assert_eq!(second[0].calculate().get_values_f32(), vec![0.; 8]);
+    assert_eq!(second[1].calculate().get_values_f32(), vec![1., 1.]);
+}
+
+#[test]
+fn test_max_pool2d_gradients() {
+    // Distinct values keep the positions of maxima stable under perturbation.
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let shape = Shape::new([2, 2, 5, 4]);
+    let values = (0..shape.num_elements())
+        .map(|i| ((i * 37) % 80) as f32 * 0.1)
+        .collect::<Vec<_>>();
+    let options = Pool2dOptions {
+        kernel_size: [3, 2],
+        stride: [2, 1],
+        padding: [1, 1],
+    };
+    let x = (shape.clone(), values.clone()).into_node(&g, &hw);
+    let y = x.max_pool2d(options);
+    assert_eq!(y.shape(), Shape::new([2, 2, 3, 5]));
+    let r = random(&g, &hw, y.shape(), 1);
+    let gx = grad(sum_all(y * r), &[x])[0];
+
+    let r_array = r.calculate();
+    let eval = |values: &[f32]| {
+        let g = RefCell::new(Graph::new());
+        let x = (shape.clone(), values.to_vec()).into_node(&g, &hw);
+        f32::try_from(sum_all(
+            x.max_pool2d(options) * Node::constant(&g, r_array.clone()),
+        ))
+        .unwrap()
+    };
+    assert_close(
+        &gx.calculate().get_values_f32(),
+        &numerical_grad(eval, &values),
+        1e-2,
+    );
+}
+
+#[test]
+fn test_avg_pool2d() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let x = (
+        Shape::new([1, 1, 2, 4]),
+        vec![1f32, 5., 2., 0., 4., 3., 6., 7.],
+    )
+        .into_node(&g, &hw);
+    let y = x.avg_pool2d(Pool2dOptions::new([2, 2]));
+    assert_eq!(y.shape(), Shape::new([1, 1, 1, 2]));
+    assert_eq!(y.calculate().get_values_f32(), vec![3.25, 3.75]);
+
+    let r = (Shape::new([1, 1, 1, 2]), vec![4f32, 8.]).into_node(&g, &hw);
+    let gx = grad(sum_all(y * r), &[x])[0];
+    assert_eq!(
+        gx.calculate().get_values_f32(),
+        vec![1., 1., 2., 2., 1., 1., 2., 2.]
+    );
+    assert_eq!(
+        jvp(&[y], &[x], &[x])[0].calculate().get_values_f32(),
+        vec![3.25, 3.75]
+    );
+
+    // d/dr <grad x, v> = avg_pool2d(v)
+    let v = (
+        Shape::new([1, 1, 2, 4]),
+        (0..8).map(|i| i as f32).collect::<Vec<_>>(),
+    )
+        .into_node(&g, &hw);
+    assert_eq!(
+        grad(sum_all(gx * v), &[r])[0].calculate().get_values_f32(),
+        vec![2.5, 4.5]
+    );
+}
+
+#[test]
+fn test_avg_pool2d_gradients() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let shape = Shape::new([2, 2, 5, 4]);
+    let options = Pool2dOptions {
+        kernel_size: [3, 2],
+        stride: [2, 1],
+        padding: [1, 1],
+    };
+    let x = random(&g, &hw, shape.clone(), 1);
+    let y = x.avg_pool2d(options);
+    let r = random(&g, &hw, y.shape(), 2);
+    let gx = grad(sum_all(y * r), &[x])[0];
+
+    let r_array = r.calculate();
+    let eval = |values: &[f32]| {
+        let g = RefCell::new(Graph::new());
+        let x = (shape.clone(), values.to_vec()).into_node(&g, &hw);
+        f32::try_from(sum_all(
+            x.avg_pool2d(options) * Node::constant(&g, r_array.clone()),
+        ))
+        .unwrap()
+    };
+    assert_close(
+        &gx.calculate().get_values_f32(),
+        &numerical_grad(eval, &x.calculate().get_values_f32()),
+        1e-2,
+    );
+}
+
+#[test]
+fn test_train_conv2d() {
+    // Learns an edge detection kernel from its outputs.
+    let hw = RefCell::new(CpuHardware::new());
+    let mut store = ParameterStore::new();
+    let target = Array::constant_f32(
+        &hw,
+        Shape::new([1, 1, 3, 3]),
+        &[1., 0., -1., 2., 0., -2., 1., 0., -1.],
+    )
+    .unwrap();
+    let weight = Parameter::new(Array::fill_f32(&hw, Shape::new([1, 1, 3, 3]), 0.));
+    store.add("weight", weight.clone()).unwrap();
+    let mut optimizer = Sgd::new(0.01, 0., 0., false);
+
+    let mut losses = vec![];
+    for step in 0..100 {
+        let g = RefCell::new(Graph::new());
+        let x = Node::constant(
+            &g,
+            Array::random_uniform_f32(&hw, Shape::new([4, 1, 6, 6]), 10, step, -1., 1.),
+        );
+        let t = x.conv2d(Node::constant(&g, target.clone()), Conv2dOptions::default());
+        let d = x.conv2d(Node::parameter(&g, &weight), Conv2dOptions::default()) - t;
+        let loss = sum_all(d * d);
+        losses.push(f32::try_from(loss).unwrap());
+        store.reset_gradients();
+        store.accumulate_gradients(loss);
+        optimizer.update(&store).unwrap();
+    }
+    assert!(
+        losses[losses.len() - 1] < 1e-4 * losses[0],
+        "{:?}",
+        &losses[losses.len() - 5..]
+    );
+}
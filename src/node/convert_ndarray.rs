@@ -1,5 +1,16 @@
 use crate::node::*;
 
+// Implements separate definitions of IntoNode for ndarray::ArrayN
+// for the same reason with that of Array.
+
+define_into_node_via_array!(&ndarray::Array0<f32>);
+define_into_node_via_array!(&ndarray::Array1<f32>);
+define_into_node_via_array!(&ndarray::Array2<f32>);
+define_into_node_via_array!(&ndarray::Array3<f32>);
+define_into_node_via_array!(&ndarray::Array4<f32>);
+define_into_node_via_array!(&ndarray::Array5<f32>);
+define_into_node_via_array!(&ndarray::Array6<f32>);
+
 // Implements separate definitions of TryFrom<Array> for ndarray::ArrayN
 // for the same reason with that of Array.
 
@@ -210,3 +210,47 @@ fn test_try_into_array6_fail() {
         assert!(ndarray::Array6::<f32>::try_from(src).is_err());
     }
 }
+
+#[test]
+fn test_into_node_array0() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let src = ndarray::arr0(42f32);
+    let dest = (&src).into_node(&g, &hw);
+    assert_eq!(dest.shape(), Shape::new([]));
+    assert!(ptr::eq(dest.hardware(), &hw));
+    assert_eq!(f32::try_from(dest), Ok(42.));
+}
+
+#[test]
+fn test_into_node_array2() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let src = ndarray::arr2(&[[1f32, 2., 3.], [4., 5., 6.]]);
+    let dest = (&src).into_node(&g, &hw);
+    assert_eq!(dest.shape(), Shape::new([2, 3]));
+    assert_eq!(ndarray::Array2::<f32>::try_from(dest), Ok(src));
+}
+
+#[test]
+fn test_into_node_array2_column_major() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let src = ndarray::arr2(&[[1f32, 2., 3.], [4., 5., 6.]]).reversed_axes();
+    let dest = (&src).into_node(&g, &hw);
+    assert_eq!(dest.shape(), Shape::new([3, 2]));
+    assert_eq!(
+        dest.calculate().get_values_f32(),
+        vec![1., 4., 2., 5., 3., 6.]
+    );
+}
+
+#[test]
+fn test_into_node_array6() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let src = ndarray::Array6::<f32>::from_elem((1, 2, 1, 2, 1, 2), 3.);
+    let dest = (&src).into_node(&g, &hw);
+    assert_eq!(dest.shape(), Shape::new([1, 2, 1, 2, 1, 2]));
+    assert_eq!(dest.calculate().get_values_f32(), vec![3.; 8]);
+}
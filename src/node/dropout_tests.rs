@@ -0,0 +1,117 @@
+use crate::hardware::cpu::CpuHardware;
+use crate::node::*;
+
+#[test]
+fn test_dropout_training() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let mut rng = Rng::new(1);
+
+    let x = Node::fill(&g, &hw, Shape::new([1000]), 3.);
+    let y = x.dropout(&mut rng, 0.25, true);
+
+    assert_ne!(y, x);
+    assert_eq!(y.shape(), Shape::new([1000]));
+    assert!(ptr::eq(y.hardware(), &hw));
+    assert_eq!(rng.offset(), 1000);
+
+    // Elements are either dropped or scaled by 1 / (1 - p).
+    let values = y.calculate().get_values_f32();
+    assert!(values.iter().all(|&v| v == 0. || v == 4.));
+    let num_kept = values.iter().filter(|&&v| v != 0.).count();
+    assert!((700..800).contains(&num_kept));
+}
+
+#[test]
+fn test_dropout_evaluation() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let mut rng = Rng::new(1);
+
+    let x = Node::fill(&g, &hw, Shape::new([3]), 3.);
+    assert_eq!(x.dropout(&mut rng, 0.5, false), x);
+    assert_eq!(x.dropout(&mut rng, 0., true), x);
+    assert_eq!(rng.offset(), 0);
+}
+
+#[test]
+fn test_dropout_drop_all() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let mut rng = Rng::new(1);
+
+    let x = Node::fill(&g, &hw, Shape::new([3]), 3.);
+    let y = x.dropout(&mut rng, 1., true);
+    assert_eq!(y.calculate().get_values_f32(), vec![0., 0., 0.]);
+}
+
+#[test]
+fn test_dropout_reproducible() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = Node::fill(&g, &hw, Shape::new([100]), 1.);
+    let y1 = x.dropout(&mut Rng::new(7), 0.5, true);
+    let y2 = x.dropout(&mut Rng::new(7), 0.5, true);
+    assert_eq!(
+        y1.calculate().get_values_f32(),
+        y2.calculate().get_values_f32()
+    );
+
+    // Successive calls on the same generator sample different masks.
+    let mut rng = Rng::new(7);
+    let y3 = x.dropout(&mut rng, 0.5, true);
+    let y4 = x.dropout(&mut rng, 0.5, true);
+    assert_ne!(
+        y3.calculate().get_values_f32(),
+        y4.calculate().get_values_f32()
+    );
+}
+
+#[test]
+fn test_dropout_grad() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let mut rng = Rng::new(1);
+
+    let x = Node::fill(&g, &hw, Shape::new([100]), 1.);
+    let y = x.dropout(&mut rng, 0.5, true);
+    let gy = Node::fill(&g, &hw, Shape::new([100]), 1.);
+    let gx = vjp(&[y], &[x], &[gy])[0];
+
+    // The gradient is masked by the same mask as the forward value.
+    assert_eq!(
+        gx.calculate().get_values_f32(),
+        y.calculate().get_values_f32()
+    );
+}
+
+#[test]
+fn test_dropout_jvp() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let mut rng = Rng::new(1);
+
+    let x = Node::fill(&g, &hw, Shape::new([100]), 2.);
+    let y = x.dropout(&mut rng, 0.5, true);
+    let dx = Node::fill(&g, &hw, Shape::new([100]), 1.);
+    let dy = jvp(&[y], &[x], &[dx])[0];
+
+    let expected = y
+        .calculate()
+        .get_values_f32()
+        .iter()
+        .map(|v| v / 2.)
+        .collect::<Vec<_>>();
+    assert_eq!(dy.calculate().get_values_f32(), expected);
+}
+
+#[test]
+#[should_panic]
+fn test_dropout_invalid_probability() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = Node::fill(&g, &hw, Shape::new([3]), 1.);
+    let _y = x.dropout(&mut Rng::new(1), 1.5, true);
+}
@@ -292,3 +292,66 @@ fn test_different_graph() {
     let y = 2f32.into_node(&g2, &hw);
     let _gx = grad(y, &[x])[0];
 }
+
+#[test]
+fn test_pruning_unrelated_inputs() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 2f32.into_node(&g, &hw);
+    let c = 3f32.into_node(&g, &hw);
+    // Side computation which does not depend on x.
+    let b = c * c * c;
+    let y = x * b;
+    assert_eq!(g.borrow().num_steps(), 5);
+
+    let gx = grad(y, &[x]);
+
+    // Only the last Mul is differentiated: Fill for dy/dy and a Mul step for the gradient of x.
+    // The gradient of b is never constructed.
+    assert_eq!(g.borrow().num_steps(), 7);
+
+    // dy/dx == b
+    assert_eq!(f32::try_from(gx[0]), Ok(27.));
+}
+
+#[test]
+fn test_pruning_unused_outputs() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 2f32.into_node(&g, &hw);
+    let y = x * x;
+    // Side computations which depend on x, but do not contribute to y.
+    let _z1 = x * x * x;
+    let _z2 = -x;
+    assert_eq!(g.borrow().num_steps(), 5);
+
+    let gx = grad(y, &[x]);
+
+    // Fill for dy/dy, 2 Mul steps for gradients, and 1 Add to integrate them.
+    assert_eq!(g.borrow().num_steps(), 9);
+
+    // dy/dx == 2x
+    assert_eq!(f32::try_from(gx[0]), Ok(4.));
+}
+
+#[test]
+fn test_pruning_intermediate_inputs() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let a = 2f32.into_node(&g, &hw);
+    let b = 3f32.into_node(&g, &hw);
+    let h = a * b;
+    let y = h * h;
+
+    // Gradients are propagated to b through h, but not to a since it does not depend on any
+    // inputs.
+    let gx = grad(y, &[h, b]);
+
+    // dy/dh == 2h
+    assert_eq!(f32::try_from(gx[0]), Ok(12.));
+    // dy/db == 2h * a
+    assert_eq!(f32::try_from(gx[1]), Ok(24.));
+}
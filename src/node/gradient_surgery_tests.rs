@@ -0,0 +1,193 @@
+use crate::hardware::cpu::CpuHardware;
+use crate::node::*;
+
+#[test]
+fn test_stop_gradient() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 3f32.into_node(&g, &hw);
+    let y = x.stop_gradient();
+
+    assert_eq!(y.shape(), Shape::new([]));
+    assert!(ptr::eq(y.hardware(), &hw));
+    assert_eq!(f32::try_from(y), Ok(3.));
+
+    // y is treated as a constant.
+    assert_eq!(f32::try_from(grad(y, &[x])[0]), Ok(0.));
+}
+
+#[test]
+fn test_stop_gradient_partial() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 3f32.into_node(&g, &hw);
+    let y = x * x.stop_gradient();
+    let dx = 1f32.into_node(&g, &hw);
+
+    assert_eq!(f32::try_from(y), Ok(9.));
+    // Only the left-hand side propagates the gradient: d(x * c)/dx == c
+    assert_eq!(f32::try_from(grad(y, &[x])[0]), Ok(3.));
+    assert_eq!(f32::try_from(jvp(&[y], &[x], &[dx])[0]), Ok(3.));
+}
+
+#[test]
+fn test_straight_through() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = Node::fill(&g, &hw, Shape::new([3]), 2.);
+    let forward = x * x;
+    let y = Node::straight_through(forward, x);
+    let coef = Node::fill(&g, &hw, Shape::new([3]), 5.);
+    let z = y * coef;
+
+    assert_eq!(y.shape(), Shape::new([3]));
+    assert_eq!(y.calculate().get_values_f32(), vec![4., 4., 4.]);
+
+    // Gradient is propagated to x as if y == x.
+    let gx = grad(z, &[x, forward]);
+    assert_eq!(gx[0].calculate().get_values_f32(), vec![5., 5., 5.]);
+    assert_eq!(gx[1].calculate().get_values_f32(), vec![0., 0., 0.]);
+
+    let dx = Node::fill(&g, &hw, Shape::new([3]), 1.);
+    let dz = jvp(&[z], &[x], &[dx])[0];
+    assert_eq!(dz.calculate().get_values_f32(), vec![5., 5., 5.]);
+}
+
+#[test]
+#[should_panic]
+fn test_straight_through_invalid_shape() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let a = 1f32.into_node(&g, &hw);
+    let b = Node::fill(&g, &hw, Shape::new([3]), 1.);
+    let _y = Node::straight_through(a, b);
+}
+
+#[test]
+fn test_clip_gradient() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = Node::fill(&g, &hw, Shape::new([3]), 2.);
+    let coef = Array::constant_f32(&hw, Shape::new([3]), &[-5., 0.5, 5.]).unwrap();
+    let coef = Node::apply(&g, Box::new(operator::constant::Constant::new(coef)), &[]).unwrap();
+    let y = x.clip_gradient(-1., 1.);
+    let z = y * coef;
+
+    assert_eq!(y.calculate().get_values_f32(), vec![2., 2., 2.]);
+
+    // Gradients larger than the bounds are clamped.
+    let gx = grad(z, &[x])[0];
+    assert_eq!(gx.calculate().get_values_f32(), vec![-1., 0.5, 1.]);
+
+    // Tangents are not affected.
+    let dx = Node::fill(&g, &hw, Shape::new([3]), 1.);
+    let dz = jvp(&[z], &[x], &[dx])[0];
+    assert_eq!(dz.calculate().get_values_f32(), vec![-5., 0.5, 5.]);
+}
+
+#[test]
+#[should_panic]
+fn test_clip_gradient_invalid_range() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 1f32.into_node(&g, &hw);
+    let _y = x.clip_gradient(1., -1.);
+}
+
+#[test]
+fn test_custom_gradient() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 3f32.into_node(&g, &hw);
+    let y = Node::custom_gradient(&[x], |x| x[0] * x[0], |x, _y, gy| vec![gy * x[0]]);
+
+    assert_eq!(y.shape(), Shape::new([]));
+    assert_eq!(f32::try_from(y), Ok(9.));
+
+    // The gradient is calculated by the backward function, not by the forward graph.
+    let gx1 = grad(y, &[x])[0];
+    assert_eq!(f32::try_from(gx1), Ok(3.));
+
+    // The backward graph is differentiable.
+    let gx2 = grad(gx1, &[x])[0];
+    assert_eq!(f32::try_from(gx2), Ok(1.));
+}
+
+#[test]
+fn test_custom_gradient_multiple_inputs() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let a = 2f32.into_node(&g, &hw);
+    let b = 3f32.into_node(&g, &hw);
+    let y = Node::custom_gradient(&[a, b], |x| x[0] * x[1], |_x, y, gy| vec![gy * y, -gy]);
+    let z = y + y;
+
+    assert_eq!(f32::try_from(z), Ok(12.));
+
+    let gx = grad(z, &[a, b]);
+    // 2 * y
+    assert_eq!(f32::try_from(gx[0]), Ok(12.));
+    // -2
+    assert_eq!(f32::try_from(gx[1]), Ok(-2.));
+}
+
+#[test]
+#[should_panic(expected = "Forward-mode gradient is not implemented for CustomGradient.")]
+fn test_custom_gradient_jvp() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 3f32.into_node(&g, &hw);
+    let y = Node::custom_gradient(&[x], |x| -x[0], |_x, _y, gy| vec![-gy]);
+    let dx = 1f32.into_node(&g, &hw);
+
+    // Forward-mode differentiation is not supported.
+    let _dy = jvp(&[y], &[x], &[dx]);
+}
+
+#[test]
+#[should_panic]
+fn test_custom_gradient_empty_inputs() {
+    let _y = Node::custom_gradient(&[], |x| x[0], |_x, _y, gy| vec![gy]);
+}
+
+#[test]
+#[should_panic]
+fn test_custom_gradient_invalid_num_gradients() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 3f32.into_node(&g, &hw);
+    let y = Node::custom_gradient(&[x], |x| -x[0], |_x, _y, gy| vec![gy, gy]);
+    let _gx = grad(y, &[x]);
+}
+
+#[test]
+#[should_panic]
+fn test_custom_gradient_invalid_gradient_shape() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 3f32.into_node(&g, &hw);
+    let y = Node::custom_gradient(
+        &[x],
+        |x| -x[0],
+        |x, _y, _gy| {
+            vec![Node::fill(
+                x[0].graph(),
+                x[0].hardware(),
+                Shape::new([3]),
+                1.,
+            )]
+        },
+    );
+    let _gx = grad(y, &[x]);
+}
@@ -0,0 +1,255 @@
+use crate::hardware::cpu::CpuHardware;
+use crate::node::*;
+
+#[test]
+fn test_second_order_neg() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 3f32.into_node(&g, &hw);
+    let y = -(x * x);
+
+    let gx1 = grad(y, &[x])[0];
+    let gx2 = grad(gx1, &[x])[0];
+
+    // y' == -2x
+    assert_eq!(f32::try_from(gx1), Ok(-6.));
+    // y'' == -2
+    assert_eq!(f32::try_from(gx2), Ok(-2.));
+}
+
+#[test]
+fn test_second_order_sub() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 3f32.into_node(&g, &hw);
+    let y = x * x - x;
+
+    let gx1 = grad(y, &[x])[0];
+    let gx2 = grad(gx1, &[x])[0];
+
+    // y' == 2x - 1
+    assert_eq!(f32::try_from(gx1), Ok(5.));
+    // y'' == 2
+    assert_eq!(f32::try_from(gx2), Ok(2.));
+}
+
+#[test]
+fn test_higher_order_div() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let one = 1f32.into_node(&g, &hw);
+    let x = 2f32.into_node(&g, &hw);
+    let y = one / x;
+
+    let gx1 = grad(y, &[x])[0];
+    let gx2 = grad(gx1, &[x])[0];
+    let gx3 = grad(gx2, &[x])[0];
+
+    // y' == -1/x^2
+    assert_eq!(f32::try_from(gx1), Ok(-0.25));
+    // y'' == 2/x^3
+    assert_eq!(f32::try_from(gx2), Ok(0.25));
+    // y''' == -6/x^4
+    assert_eq!(f32::try_from(gx3), Ok(-0.375));
+}
+
+#[test]
+fn test_higher_order_n() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = Node::fill(&g, &hw, Shape::new([3]), 2.);
+    let y = x * x * x;
+
+    let gx1 = grad(y, &[x])[0];
+    let gx2 = grad(gx1, &[x])[0];
+
+    assert_eq!(gx1.shape(), Shape::new([3]));
+    assert_eq!(gx2.shape(), Shape::new([3]));
+
+    // y' == 3x^2
+    assert_eq!(gx1.calculate().get_values_f32(), vec![12., 12., 12.]);
+    // y'' == 6x
+    assert_eq!(gx2.calculate().get_values_f32(), vec![12., 12., 12.]);
+}
+
+#[test]
+fn test_forward_over_reverse() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 5f32.into_node(&g, &hw);
+    let y = x * x * x;
+    let dx = 1f32.into_node(&g, &hw);
+
+    let gx1 = grad(y, &[x])[0];
+    let gx2 = jvp(&[gx1], &[x], &[dx])[0];
+
+    // y'' == 6x
+    assert_eq!(f32::try_from(gx2), Ok(30.));
+}
+
+#[test]
+fn test_hvp_empty() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let y = 1f32.into_node(&g, &hw);
+
+    assert!(hvp(y, &[], &[]).is_empty());
+}
+
+#[test]
+fn test_hvp() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let a = 2f32.into_node(&g, &hw);
+    let b = 3f32.into_node(&g, &hw);
+    let y = a * a * b;
+    let va = 1f32.into_node(&g, &hw);
+    let vb = 10f32.into_node(&g, &hw);
+
+    let hv = hvp(y, &[a, b], &[va, vb]);
+    assert_eq!(hv.len(), 2);
+
+    assert_eq!(hv[0].shape(), Shape::new([]));
+    assert_eq!(hv[1].shape(), Shape::new([]));
+    assert!(ptr::eq(hv[0].hardware(), &hw));
+    assert!(ptr::eq(hv[1].hardware(), &hw));
+
+    // H == [[2b, 2a], [2a, 0]]
+    // 2b * va + 2a * vb
+    assert_eq!(f32::try_from(hv[0]), Ok(46.));
+    // 2a * va
+    assert_eq!(f32::try_from(hv[1]), Ok(4.));
+}
+
+#[test]
+fn test_hvp_unrelated() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let a = 2f32.into_node(&g, &hw);
+    let b = 3f32.into_node(&g, &hw);
+    let y = a * a;
+    let va = 1f32.into_node(&g, &hw);
+    let vb = 1f32.into_node(&g, &hw);
+
+    let hv = hvp(y, &[a, b], &[va, vb]);
+
+    // H == [[2, 0], [0, 0]]
+    assert_eq!(f32::try_from(hv[0]), Ok(2.));
+    assert_eq!(f32::try_from(hv[1]), Ok(0.));
+}
+
+#[test]
+fn test_hvp_n() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = Node::fill(&g, &hw, Shape::new([3]), 2.);
+    let y = x * x * x;
+    let v = Node::fill(&g, &hw, Shape::new([3]), 0.5);
+
+    let hv = hvp(y, &[x], &[v]);
+    assert_eq!(hv[0].shape(), Shape::new([3]));
+
+    // H == diag(6x)
+    assert_eq!(hv[0].calculate().get_values_f32(), vec![6., 6., 6.]);
+}
+
+#[test]
+#[should_panic]
+fn test_hvp_invalid_num_vectors() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 1f32.into_node(&g, &hw);
+    let y = x * x;
+    let _hv = hvp(y, &[x], &[]);
+}
+
+#[test]
+#[should_panic]
+fn test_hvp_invalid_shape() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 1f32.into_node(&g, &hw);
+    let y = x * x;
+    let v = Node::fill(&g, &hw, Shape::new([3]), 1.);
+    let _hv = hvp(y, &[x], &[v]);
+}
+
+#[test]
+fn test_hessian_empty() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let y = 1f32.into_node(&g, &hw);
+
+    assert!(hessian(y, &[]).is_empty());
+}
+
+#[test]
+fn test_hessian_scalars() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let a = 2f32.into_node(&g, &hw);
+    let b = 3f32.into_node(&g, &hw);
+    let y = a * a * b;
+
+    let h = hessian(y, &[a, b]);
+    assert_eq!(h.len(), 2);
+    assert_eq!(h[0].len(), 2);
+    assert_eq!(h[1].len(), 2);
+
+    let values = h
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|&node| f32::try_from(node).unwrap())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    // [[2b, 2a], [2a, 0]]
+    assert_eq!(values, vec![vec![6., 4.], vec![4., 0.]]);
+}
+
+#[test]
+fn test_hessian_n() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = Node::fill(&g, &hw, Shape::new([2]), 3.);
+    let c = 2f32.into_node(&g, &hw);
+    let y = x * x * x;
+    let z = c * c;
+
+    let h = hessian(y, &[x, c]);
+    // 2 rows for x, 1 row for c.
+    assert_eq!(h.len(), 3);
+
+    for row in &h {
+        assert_eq!(row.len(), 2);
+        assert_eq!(row[0].shape(), Shape::new([2]));
+        assert_eq!(row[1].shape(), Shape::new([]));
+    }
+
+    // d^2y/dx^2 == diag(6x), y does not depend on c.
+    assert_eq!(h[0][0].calculate().get_values_f32(), vec![18., 0.]);
+    assert_eq!(h[1][0].calculate().get_values_f32(), vec![0., 18.]);
+    assert_eq!(h[2][0].calculate().get_values_f32(), vec![0., 0.]);
+    assert_eq!(f32::try_from(h[0][1]), Ok(0.));
+    assert_eq!(f32::try_from(h[1][1]), Ok(0.));
+    assert_eq!(f32::try_from(h[2][1]), Ok(0.));
+
+    // Independent computation in the same graph is not affected.
+    assert_eq!(f32::try_from(hessian(z, &[c])[0][0]), Ok(2.));
+}
@@ -0,0 +1,357 @@
+use crate::hardware::cpu::CpuHardware;
+use crate::node::*;
+
+#[test]
+fn test_empty_outputs() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 42f32.into_node(&g, &hw);
+    let dx = 1f32.into_node(&g, &hw);
+
+    let dy = jvp(&[], &[x], &[dx]);
+    assert!(dy.is_empty());
+}
+
+#[test]
+fn test_empty_inputs() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let y = 42f32.into_node(&g, &hw);
+
+    let dy = jvp(&[y], &[], &[]);
+    assert_eq!(dy.len(), 1);
+
+    assert_eq!(dy[0].shape(), Shape::new([]));
+    assert!(ptr::eq(dy[0].hardware(), &hw));
+
+    // No inputs are perturbed.
+    assert_eq!(f32::try_from(dy[0]), Ok(0.));
+}
+
+#[test]
+fn test_self() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 42f32.into_node(&g, &hw);
+    let dx = 3f32.into_node(&g, &hw);
+
+    let dy = jvp(&[x], &[x], &[dx]);
+    assert_eq!(dy.len(), 1);
+
+    // dx/dx * dx == dx
+    assert_eq!(f32::try_from(dy[0]), Ok(3.));
+}
+
+#[test]
+fn test_unrelated() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 42f32.into_node(&g, &hw);
+    let y = 42f32.into_node(&g, &hw);
+    let dx = 1f32.into_node(&g, &hw);
+
+    let dy = jvp(&[y], &[x], &[dx]);
+    assert_eq!(dy.len(), 1);
+
+    assert_eq!(dy[0].shape(), Shape::new([]));
+    assert!(ptr::eq(dy[0].hardware(), &hw));
+
+    // dy/dx == 0 since y is not calculated by x.
+    assert_eq!(f32::try_from(dy[0]), Ok(0.));
+}
+
+#[test]
+fn test_neg() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 42f32.into_node(&g, &hw);
+    let y = -x;
+    let dx = 2f32.into_node(&g, &hw);
+
+    let dy = jvp(&[y], &[x], &[dx]);
+    assert_eq!(dy.len(), 1);
+
+    assert_eq!(dy[0].shape(), Shape::new([]));
+    assert!(ptr::eq(dy[0].hardware(), &hw));
+
+    // dy/dx * dx == -dx
+    assert_eq!(f32::try_from(dy[0]), Ok(-2.));
+}
+
+#[test]
+fn test_add() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let a = 123f32.into_node(&g, &hw);
+    let b = 456f32.into_node(&g, &hw);
+    let y = a + b;
+    let da = 2f32.into_node(&g, &hw);
+    let db = 3f32.into_node(&g, &hw);
+
+    // da + db
+    assert_eq!(f32::try_from(jvp(&[y], &[a, b], &[da, db])[0]), Ok(5.));
+    // da
+    assert_eq!(f32::try_from(jvp(&[y], &[a], &[da])[0]), Ok(2.));
+    // db
+    assert_eq!(f32::try_from(jvp(&[y], &[b], &[db])[0]), Ok(3.));
+}
+
+#[test]
+fn test_sub() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let a = 123f32.into_node(&g, &hw);
+    let b = 456f32.into_node(&g, &hw);
+    let y = a - b;
+    let da = 2f32.into_node(&g, &hw);
+    let db = 3f32.into_node(&g, &hw);
+
+    // da - db
+    assert_eq!(f32::try_from(jvp(&[y], &[a, b], &[da, db])[0]), Ok(-1.));
+    // da
+    assert_eq!(f32::try_from(jvp(&[y], &[a], &[da])[0]), Ok(2.));
+    // -db
+    assert_eq!(f32::try_from(jvp(&[y], &[b], &[db])[0]), Ok(-3.));
+}
+
+#[test]
+fn test_mul() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let a = 5f32.into_node(&g, &hw);
+    let b = 7f32.into_node(&g, &hw);
+    let y = a * b;
+    let da = 2f32.into_node(&g, &hw);
+    let db = 3f32.into_node(&g, &hw);
+
+    // b * da + a * db
+    assert_eq!(f32::try_from(jvp(&[y], &[a, b], &[da, db])[0]), Ok(29.));
+    // b * da
+    assert_eq!(f32::try_from(jvp(&[y], &[a], &[da])[0]), Ok(14.));
+    // a * db
+    assert_eq!(f32::try_from(jvp(&[y], &[b], &[db])[0]), Ok(15.));
+}
+
+#[test]
+fn test_mul_quadratic() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 123f32.into_node(&g, &hw);
+    let y = x * x;
+    let dx = 1f32.into_node(&g, &hw);
+
+    // 2x * dx
+    assert_eq!(f32::try_from(jvp(&[y], &[x], &[dx])[0]), Ok(246.));
+}
+
+#[test]
+fn test_div() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let a = 3f32.into_node(&g, &hw);
+    let b = 2f32.into_node(&g, &hw);
+    let y = a / b;
+    let da = 1f32.into_node(&g, &hw);
+    let db = 1f32.into_node(&g, &hw);
+
+    // da / b
+    assert_eq!(f32::try_from(jvp(&[y], &[a], &[da])[0]), Ok(0.5));
+    // -a / b^2 * db
+    assert_eq!(f32::try_from(jvp(&[y], &[b], &[db])[0]), Ok(-0.75));
+    // da / b - a / b^2 * db
+    assert_eq!(f32::try_from(jvp(&[y], &[a, b], &[da, db])[0]), Ok(-0.25));
+}
+
+#[test]
+fn test_multiple_outputs() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let a = 2f32.into_node(&g, &hw);
+    let b = 3f32.into_node(&g, &hw);
+    let y1 = a * b;
+    let y2 = a + b;
+    let y3 = 4f32.into_node(&g, &hw);
+    let da = 1f32.into_node(&g, &hw);
+    let db = 10f32.into_node(&g, &hw);
+
+    let dy = jvp(&[y1, y2, y3], &[a, b], &[da, db]);
+    assert_eq!(dy.len(), 3);
+
+    // b * da + a * db
+    assert_eq!(f32::try_from(dy[0]), Ok(23.));
+    // da + db
+    assert_eq!(f32::try_from(dy[1]), Ok(11.));
+    // y3 is not calculated by a, b.
+    assert_eq!(f32::try_from(dy[2]), Ok(0.));
+}
+
+#[test]
+fn test_dependent_inputs() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let a = 2f32.into_node(&g, &hw);
+    let b = a * a;
+    let y = a * b;
+    let da = 1f32.into_node(&g, &hw);
+    let db = 10f32.into_node(&g, &hw);
+
+    // Tangents of `b` are integrated with those propagated from `a`, which is consistent with
+    // `grad`: dy/da * da + dy/db * db == (b + a * 2a) * da + a * db
+    let dy = jvp(&[y], &[a, b], &[da, db]);
+    assert_eq!(f32::try_from(dy[0]), Ok(32.));
+
+    let gx = grad(y, &[a, b]);
+    assert_eq!(f32::try_from(gx[0] * da + gx[1] * db), f32::try_from(dy[0]));
+}
+
+#[test]
+fn test_multiple_computation() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let a = 1f32.into_node(&g, &hw);
+    let b = 2f32.into_node(&g, &hw);
+    let c = 3f32.into_node(&g, &hw);
+    let y = a + -b * c;
+    let one = 1f32.into_node(&g, &hw);
+
+    // dy/da == 1
+    assert_eq!(f32::try_from(jvp(&[y], &[a], &[one])[0]), Ok(1.));
+    // dy/db == -c
+    assert_eq!(f32::try_from(jvp(&[y], &[b], &[one])[0]), Ok(-3.));
+    // dy/dc = -b
+    assert_eq!(f32::try_from(jvp(&[y], &[c], &[one])[0]), Ok(-2.));
+}
+
+#[test]
+fn test_n() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = Node::fill(&g, &hw, Shape::new([3]), 2.);
+    let y = x * x * x;
+    let dx = Node::fill(&g, &hw, Shape::new([3]), 0.5);
+
+    let dy = jvp(&[y], &[x], &[dx]);
+    assert_eq!(dy[0].shape(), Shape::new([3]));
+
+    // 3x^2 * dx
+    assert_eq!(dy[0].calculate().get_values_f32(), vec![6., 6., 6.]);
+}
+
+#[test]
+fn test_higher_order_gradients() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 5f32.into_node(&g, &hw);
+    let y = x * x * x;
+    let dx = 1f32.into_node(&g, &hw);
+
+    let dy1 = jvp(&[y], &[x], &[dx])[0];
+    let dy2 = jvp(&[dy1], &[x], &[dx])[0];
+    let dy3 = jvp(&[dy2], &[x], &[dx])[0];
+    let dy4 = jvp(&[dy3], &[x], &[dx])[0];
+
+    // y' == 3x^2
+    assert_eq!(f32::try_from(dy1), Ok(75.));
+    // y'' == 6x
+    assert_eq!(f32::try_from(dy2), Ok(30.));
+    // y''' == 6
+    assert_eq!(f32::try_from(dy3), Ok(6.));
+    // y'''' == 0
+    assert_eq!(f32::try_from(dy4), Ok(0.));
+}
+
+#[test]
+fn test_pruning_unused_outputs() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 2f32.into_node(&g, &hw);
+    // Side computations which depend on x, but do not contribute to y.
+    let _z1 = x * x * x;
+    let _z2 = x.sqrt();
+    let y = -x;
+    let dx = 1f32.into_node(&g, &hw);
+    let num_steps = g.borrow().num_steps();
+
+    let dy = jvp(&[y], &[x], &[dx]);
+
+    // Only a Neg step for the tangent of y.
+    assert_eq!(g.borrow().num_steps(), num_steps + 1);
+    assert_eq!(f32::try_from(dy[0]), Ok(-1.));
+}
+
+#[test]
+fn test_constant_operators() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 2f32.into_node(&g, &hw);
+    let dx = 1f32.into_node(&g, &hw);
+    let y = x.stop_gradient() * x + x.isnan();
+
+    // Only the direct use of x contributes to the tangent: d(c * x)/dx == c.
+    assert_eq!(f32::try_from(jvp(&[y], &[x], &[dx])[0]), Ok(2.));
+}
+
+#[test]
+fn test_constant_operators_unrelated() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 2f32.into_node(&g, &hw);
+    let dx = 1f32.into_node(&g, &hw);
+    let y = x.less(3f32.into_node(&g, &hw));
+
+    assert_eq!(f32::try_from(jvp(&[y], &[x], &[dx])[0]), Ok(0.));
+}
+
+#[test]
+#[should_panic]
+fn test_different_graph() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g1 = RefCell::new(Graph::new());
+    let g2 = RefCell::new(Graph::new());
+
+    let x = 1f32.into_node(&g1, &hw);
+    let dx = 1f32.into_node(&g1, &hw);
+    let y = 2f32.into_node(&g2, &hw);
+    let _dy = jvp(&[y], &[x], &[dx]);
+}
+
+#[test]
+#[should_panic]
+fn test_invalid_num_tangents() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 1f32.into_node(&g, &hw);
+    let y = -x;
+    let _dy = jvp(&[y], &[x], &[]);
+}
+
+#[test]
+#[should_panic]
+fn test_invalid_tangent_shape() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 1f32.into_node(&g, &hw);
+    let y = -x;
+    let dx = Node::fill(&g, &hw, Shape::new([3]), 1.);
+    let _dy = jvp(&[y], &[x], &[dx]);
+}
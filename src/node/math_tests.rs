@@ -0,0 +1,336 @@
+use crate::hardware::cpu::CpuHardware;
+use crate::node::*;
+
+fn assert_close(observed: Vec<f32>, expected: &[f32]) {
+    assert_eq!(observed.len(), expected.len());
+    for (o, e) in observed.iter().zip(expected) {
+        assert!((o - e).abs() < 1e-5, "{:?} != {:?}", observed, expected);
+    }
+}
+
+#[test]
+fn test_sqrt() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 4f32.into_node(&g, &hw);
+    let y = x.sqrt();
+    let dx = 1f32.into_node(&g, &hw);
+
+    assert_eq!(f32::try_from(y), Ok(2.));
+    // 1 / (2 sqrt(x))
+    assert_eq!(f32::try_from(grad(y, &[x])[0]), Ok(0.25));
+    assert_eq!(f32::try_from(jvp(&[y], &[x], &[dx])[0]), Ok(0.25));
+}
+
+#[test]
+fn test_tanh() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 0.5f32.into_node(&g, &hw);
+    let y = x.tanh();
+    let dx = 1f32.into_node(&g, &hw);
+    let t = 0.5f32.tanh();
+
+    assert_eq!(f32::try_from(y), Ok(t));
+    // 1 - tanh(x)^2
+    assert_close(grad(y, &[x])[0].calculate().get_values_f32(), &[1. - t * t]);
+    assert_close(
+        jvp(&[y], &[x], &[dx])[0].calculate().get_values_f32(),
+        &[1. - t * t],
+    );
+}
+
+#[test]
+fn test_sigmoid() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 0f32.into_node(&g, &hw);
+    let y = x.sigmoid();
+    let dx = 2f32.into_node(&g, &hw);
+
+    assert_eq!(f32::try_from(y), Ok(0.5));
+    // sigmoid(x) (1 - sigmoid(x))
+    assert_eq!(f32::try_from(grad(y, &[x])[0]), Ok(0.25));
+    assert_eq!(f32::try_from(jvp(&[y], &[x], &[dx])[0]), Ok(0.5));
+}
+
+#[test]
+fn test_relu() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = vec![-2f32, 0., 3.].into_node(&g, &hw);
+    let y = x.relu();
+    let gy = vec![1f32, 1., 1.].into_node(&g, &hw);
+
+    assert_eq!(y.calculate().get_values_f32(), vec![0., 0., 3.]);
+    assert_eq!(
+        vjp(&[y], &[x], &[gy])[0].calculate().get_values_f32(),
+        vec![0., 0., 1.]
+    );
+    assert_eq!(
+        jvp(&[y], &[x], &[gy])[0].calculate().get_values_f32(),
+        vec![0., 0., 1.]
+    );
+}
+
+#[test]
+fn test_matmul() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let a = (Shape::new([2, 2]), vec![1f32, 2., 3., 4.]).into_node(&g, &hw);
+    let b = (Shape::new([2, 1]), vec![5f32, 6.]).into_node(&g, &hw);
+    let y = a.matmul(b);
+    let gy = (Shape::new([2, 1]), vec![1f32, 10.]).into_node(&g, &hw);
+
+    assert_eq!(y.shape(), Shape::new([2, 1]));
+    assert_eq!(y.calculate().get_values_f32(), vec![17., 39.]);
+
+    let gx = vjp(&[y], &[a, b], &[gy]);
+    // gy @ b^T
+    assert_eq!(gx[0].calculate().get_values_f32(), vec![5., 6., 50., 60.]);
+    // a^T @ gy
+    assert_eq!(gx[1].calculate().get_values_f32(), vec![31., 42.]);
+
+    // da @ b + a @ db
+    let da = (Shape::new([2, 2]), vec![1f32, 0., 0., 0.]).into_node(&g, &hw);
+    let db = (Shape::new([2, 1]), vec![0f32, 1.]).into_node(&g, &hw);
+    assert_eq!(
+        jvp(&[y], &[a, b], &[da, db])[0]
+            .calculate()
+            .get_values_f32(),
+        vec![7., 4.]
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_matmul_invalid_shape() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let a = Node::fill(&g, &hw, Shape::new([2, 3]), 1.);
+    let _y = a.matmul(a);
+}
+
+#[test]
+fn test_transpose() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = (Shape::new([1, 3]), vec![1f32, 2., 3.]).into_node(&g, &hw);
+    let y = x.transpose();
+    let gy = (Shape::new([3, 1]), vec![4f32, 5., 6.]).into_node(&g, &hw);
+
+    assert_eq!(y.shape(), Shape::new([3, 1]));
+    assert_eq!(y.calculate().get_values_f32(), vec![1., 2., 3.]);
+    let gx = vjp(&[y], &[x], &[gy])[0];
+    assert_eq!(gx.shape(), Shape::new([1, 3]));
+    assert_eq!(gx.calculate().get_values_f32(), vec![4., 5., 6.]);
+}
+
+#[test]
+fn test_sum_axis_and_expand_axis() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = (Shape::new([2, 3]), vec![1f32, 2., 3., 4., 5., 6.]).into_node(&g, &hw);
+    let s = x.sum_axis(1);
+    let gs = vec![1f32, 10.].into_node(&g, &hw);
+
+    assert_eq!(s.calculate().get_values_f32(), vec![6., 15.]);
+    // Gradient of sum is broadcast back.
+    assert_eq!(
+        vjp(&[s], &[x], &[gs])[0].calculate().get_values_f32(),
+        vec![1., 1., 1., 10., 10., 10.]
+    );
+
+    let v = vec![1f32, 2.].into_node(&g, &hw);
+    let e = v.expand_axis(0, 3);
+    let ge = (Shape::new([3, 2]), vec![1f32, 2., 3., 4., 5., 6.]).into_node(&g, &hw);
+
+    assert_eq!(e.shape(), Shape::new([3, 2]));
+    assert_eq!(e.calculate().get_values_f32(), vec![1., 2., 1., 2., 1., 2.]);
+    // Gradient of broadcast is summed up.
+    assert_eq!(
+        vjp(&[e], &[v], &[ge])[0].calculate().get_values_f32(),
+        vec![9., 12.]
+    );
+}
+
+#[test]
+fn test_gather_rows() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let table = (Shape::new([3, 2]), vec![1f32, 2., 3., 4., 5., 6.]).into_node(&g, &hw);
+    let indices = vec![2f32, 0., 2.].into_node(&g, &hw);
+    let y = table.gather_rows(indices);
+    let gy = (Shape::new([3, 2]), vec![1f32, 1., 2., 2., 3., 3.]).into_node(&g, &hw);
+
+    assert_eq!(y.shape(), Shape::new([3, 2]));
+    assert_eq!(y.calculate().get_values_f32(), vec![5., 6., 1., 2., 5., 6.]);
+
+    let gx = vjp(&[y], &[table, indices], &[gy]);
+    // Gradients of the same row are accumulated.
+    assert_eq!(
+        gx[0].calculate().get_values_f32(),
+        vec![2., 2., 0., 0., 4., 4.]
+    );
+    // Indices receive no gradient.
+    assert_eq!(gx[1].calculate().get_values_f32(), vec![0., 0., 0.]);
+
+    // Second-order: the gradient of the scatter is the gather again.
+    let ggx = vjp(&[gx[0]], &[gy], &[table])[0];
+    assert_eq!(
+        ggx.calculate().get_values_f32(),
+        vec![5., 6., 1., 2., 5., 6.]
+    );
+}
+
+#[test]
+fn test_comparisons() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let a = vec![1f32, 2., 3., f32::NAN].into_node(&g, &hw);
+    let b = vec![2f32, 2., 2., 2.].into_node(&g, &hw);
+    // `PartialEq` still compares the identity of nodes.
+    assert!(a.eq(&a) && a.ne(&b));
+    assert_eq!(
+        a.equal(b).calculate().get_values_f32(),
+        vec![0., 1., 0., 0.]
+    );
+    assert_eq!(a.less(b).calculate().get_values_f32(), vec![1., 0., 0., 0.]);
+    assert_eq!(
+        a.less_equal(b).calculate().get_values_f32(),
+        vec![1., 1., 0., 0.]
+    );
+    assert_eq!(
+        a.greater(b).calculate().get_values_f32(),
+        vec![0., 0., 1., 0.]
+    );
+    assert_eq!(
+        a.greater_equal(b).calculate().get_values_f32(),
+        vec![0., 1., 1., 0.]
+    );
+    assert_eq!(a.isnan().calculate().get_values_f32(), vec![0., 0., 0., 1.]);
+    assert_eq!(a.isinf().calculate().get_values_f32(), vec![0., 0., 0., 0.]);
+
+    // Masks do not propagate gradients.
+    let gx = grad(a.less(b) * b, &[b])[0];
+    assert_eq!(gx.calculate().get_values_f32(), vec![1., 0., 0., 0.]);
+}
+
+#[test]
+fn test_logical() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let a = vec![0f32, 0., 1., 1.].into_node(&g, &hw);
+    let b = vec![0f32, 1., 0., 1.].into_node(&g, &hw);
+    assert_eq!(
+        a.logical_and(b).calculate().get_values_f32(),
+        vec![0., 0., 0., 1.]
+    );
+    assert_eq!(
+        a.logical_or(b).calculate().get_values_f32(),
+        vec![0., 1., 1., 1.]
+    );
+    assert_eq!(
+        a.logical_not().calculate().get_values_f32(),
+        vec![1., 1., 0., 0.]
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_comparison_invalid_shape() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let a = vec![1f32, 2.].into_node(&g, &hw);
+    let b = vec![1f32, 2., 3.].into_node(&g, &hw);
+    let _ = a.less(b);
+}
+
+#[test]
+fn test_select() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    // Piecewise function: y = x^2 if x < 0, 3x otherwise.
+    let x = vec![-2f32, -0.5, 0., 1.].into_node(&g, &hw);
+    let zero = Node::fill(&g, &hw, x.shape(), 0.);
+    let three = Node::fill(&g, &hw, x.shape(), 3.);
+    let cond = x.less(zero);
+    let y = cond.select(x * x, three * x);
+    assert_eq!(y.calculate().get_values_f32(), vec![4., 0.25, 0., 3.]);
+
+    let gx = grad(y, &[x, cond]);
+    assert_eq!(gx[0].calculate().get_values_f32(), vec![-4., -1., 3., 3.]);
+    assert_eq!(gx[1].calculate().get_values_f32(), vec![0.; 4]);
+
+    let dx = vec![1f32, 2., 3., 4.].into_node(&g, &hw);
+    assert_eq!(
+        jvp(&[y], &[x], &[dx])[0].calculate().get_values_f32(),
+        vec![-4., -2., 9., 12.]
+    );
+}
+
+#[test]
+fn test_minimum_maximum() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let a = vec![1f32, 2., 3.].into_node(&g, &hw);
+    let b = vec![2f32, 2., 1.].into_node(&g, &hw);
+    let da = vec![10f32, 20., 30.].into_node(&g, &hw);
+    let db = vec![40f32, 50., 60.].into_node(&g, &hw);
+
+    let y = a.minimum(b);
+    assert_eq!(y.calculate().get_values_f32(), vec![1., 2., 1.]);
+    let gs = grad(y, &[a, b]);
+    assert_eq!(gs[0].calculate().get_values_f32(), vec![1., 1., 0.]);
+    assert_eq!(gs[1].calculate().get_values_f32(), vec![0., 0., 1.]);
+    assert_eq!(
+        jvp(&[y], &[a, b], &[da, db])[0]
+            .calculate()
+            .get_values_f32(),
+        vec![10., 20., 60.]
+    );
+
+    let y = a.maximum(b);
+    assert_eq!(y.calculate().get_values_f32(), vec![2., 2., 3.]);
+    let gs = grad(y, &[a, b]);
+    assert_eq!(gs[0].calculate().get_values_f32(), vec![0., 1., 1.]);
+    assert_eq!(gs[1].calculate().get_values_f32(), vec![1., 0., 0.]);
+    assert_eq!(
+        jvp(&[y], &[a, b], &[da, db])[0]
+            .calculate()
+            .get_values_f32(),
+        vec![40., 20., 30.]
+    );
+}
+
+#[test]
+fn test_clamp() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = vec![-2f32, -1., 0.5, 1., 3.].into_node(&g, &hw);
+    let y = x.clamp(-1., 1.);
+    assert_eq!(y.calculate().get_values_f32(), vec![-1., -1., 0.5, 1., 1.]);
+    assert_eq!(
+        grad(y, &[x])[0].calculate().get_values_f32(),
+        vec![0., 1., 1., 1., 0.]
+    );
+    let dx = vec![1f32, 2., 3., 4., 5.].into_node(&g, &hw);
+    assert_eq!(
+        jvp(&[y], &[x], &[dx])[0].calculate().get_values_f32(),
+        vec![0., 2., 3., 4., 0.]
+    );
+}
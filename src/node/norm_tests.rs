@@ -0,0 +1,391 @@
+use crate::hardware::cpu::CpuHardware;
+use crate::node::*;
+
+/// Sums up all elements.
+fn sum_all<'hw: 'op, 'op: 'g, 'g>(node: Node<'hw, 'op, 'g>) -> Node<'hw, 'op, 'g> {
+    (0..node.shape().num_dimensions()).fold(node, |node, _| node.sum_axis(0))
+}
+
+fn assert_close(observed: &[f32], expected: &[f32], tolerance: f32) {
+    assert_eq!(observed.len(), expected.len());
+    for (o, e) in observed.iter().zip(expected) {
+        assert!(
+            (o - e).abs() < tolerance,
+            "{:?} != {:?}",
+            observed,
+            expected
+        );
+    }
+}
+
+/// Calculates the gradient of `f` at `x` by central differences.
+fn numerical_grad(f: impl Fn(&[f32]) -> f32, x: &[f32]) -> Vec<f32> {
+    const EPS: f32 = 1e-2;
+    (0..x.len())
+        .map(|i| {
+            let mut x = x.to_vec();
+            x[i] += EPS;
+            let plus = f(&x);
+            x[i] -= 2. * EPS;
+            let minus = f(&x);
+            (plus - minus) / (2. * EPS)
+        })
+        .collect()
+}
+
+/// Checks the gradients and the forward-mode gradients of loss = sum(f(inputs) * r) against the
+/// numerical gradients.
+fn check_gradients<F>(shapes: &[Shape], f: F)
+where
+    F: for<'hw, 'op, 'g> Fn(&[Node<'hw, 'op, 'g>]) -> Node<'hw, 'op, 'g>,
+{
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let random = |shape: &Shape, seed| {
+        Node::constant(
+            &g,
+            Array::random_uniform_f32(&hw, shape.clone(), seed, 0, -1., 1.),
+        )
+    };
+    let inputs = shapes
+        .iter()
+        .zip(1..)
+        .map(|(shape, seed)| random(shape, seed))
+        .collect::<Vec<_>>();
+    let y = f(&inputs);
+    let r = random(&y.shape(), 100);
+    let loss = sum_all(y * r);
+    let grads = grad(loss, &inputs);
+
+    let values = inputs
+        .iter()
+        .map(|x| x.calculate().get_values_f32())
+        .collect::<Vec<_>>();
+    let r_array = r.calculate();
+    let eval = |values: &[Vec<f32>]| {
+        let g = RefCell::new(Graph::new());
+        let inputs = shapes
+            .iter()
+            .zip(values)
+            .map(|(shape, values)| (shape.clone(), values.clone()).into_node(&g, &hw))
+            .collect::<Vec<_>>();
+        f32::try_from(sum_all(f(&inputs) * Node::constant(&g, r_array.clone()))).unwrap()
+    };
+    for (i, grad) in grads.iter().enumerate() {
+        assert_eq!(grad.shape(), shapes[i]);
+        let expected = numerical_grad(
+            |x| {
+                let mut values = values.clone();
+                values[i] = x.to_vec();
+                eval(&values)
+            },
+            &values[i],
+        );
+        assert_close(&grad.calculate().get_values_f32(), &expected, 1e-2);
+    }
+
+    // jvp(loss) = sum_i <grad x_i, dx_i>
+    let tangents = shapes
+        .iter()
+        .zip(200..)
+        .map(|(shape, seed)| random(shape, seed))
+        .collect::<Vec<_>>();
+    let expected = grads
+        .iter()
+        .zip(&tangents)
+        .map(|(&grad, &dx)| sum_all(grad * dx))
+        .reduce(|a, b| a + b)
+        .unwrap();
+    assert_close(
+        &jvp(&[loss], &inputs, &tangents)[0]
+            .calculate()
+            .get_values_f32(),
+        &expected.calculate().get_values_f32(),
+        1e-3,
+    );
+}
+
+/// Normalizes each group of values with the biased variance.
+fn standardize(values: &[f64], epsilon: f64) -> Vec<f64> {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / n;
+    values
+        .iter()
+        .map(|v| (v - mean) / (variance + epsilon).sqrt())
+        .collect()
+}
+
+#[test]
+fn test_layer_norm() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let values = [1f32, 2., 4., 1., -3., 0.5, 0., 2.];
+    let x = (Shape::new([2, 4]), values.to_vec()).into_node(&g, &hw);
+    let gamma = (Shape::new([4]), vec![1f32, 2., 0.5, -1.]).into_node(&g, &hw);
+    let beta = (Shape::new([4]), vec![0f32, 1., 0., -1.]).into_node(&g, &hw);
+    let y = x.layer_norm(gamma, beta, 1e-5);
+    assert_eq!(y.shape(), Shape::new([2, 4]));
+
+    let expected = values
+        .chunks(4)
+        .flat_map(|row| {
+            let row = row.iter().map(|&v| v as f64).collect::<Vec<_>>();
+            standardize(&row, 1e-5)
+                .into_iter()
+                .zip([(1., 0.), (2., 1.), (0.5, 0.), (-1., -1.)])
+                .map(|(v, (g, b))| (v * g + b) as f32)
+        })
+        .collect::<Vec<_>>();
+    assert_close(&y.calculate().get_values_f32(), &expected, 1e-5);
+}
+
+#[test]
+fn test_layer_norm_large_offset() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let x = (
+        Shape::new([1, 4]),
+        vec![1e4f32, 1e4 + 1., 1e4 + 2., 1e4 + 3.],
+    )
+        .into_node(&g, &hw);
+    let gamma = Node::fill(&g, &hw, Shape::new([4]), 1.);
+    let beta = Node::fill(&g, &hw, Shape::new([4]), 0.);
+    let expected = standardize(&[0., 1., 2., 3.], 0.)
+        .into_iter()
+        .map(|v| v as f32)
+        .collect::<Vec<_>>();
+    assert_close(
+        &x.layer_norm(gamma, beta, 0.).calculate().get_values_f32(),
+        &expected,
+        1e-5,
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_layer_norm_invalid_shape() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let x = Node::fill(&g, &hw, Shape::new([2, 3]), 0.);
+    let gamma = Node::fill(&g, &hw, Shape::new([2]), 1.);
+    let _ = x.layer_norm(gamma, gamma, 1e-5);
+}
+
+#[test]
+fn test_layer_norm_gradients() {
+    check_gradients(
+        &[Shape::new([3, 5]), Shape::new([5]), Shape::new([5])],
+        |x| x[0].layer_norm(x[1], x[2], 1e-5),
+    );
+    check_gradients(
+        &[Shape::new([2, 3, 4]), Shape::new([4]), Shape::new([4])],
+        |x| x[0].layer_norm(x[1], x[2], 1e-3),
+    );
+    check_gradients(&[Shape::new([6]), Shape::new([6]), Shape::new([6])], |x| {
+        x[0].layer_norm(x[1], x[2], 1e-5)
+    });
+}
+
+#[test]
+fn test_rms_norm() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let x = (Shape::new([2, 2]), vec![3f32, -4., 0., 2.]).into_node(&g, &hw);
+    let gamma = (Shape::new([2]), vec![1f32, 2.]).into_node(&g, &hw);
+    let y = x.rms_norm(gamma, 0.);
+    assert_eq!(y.shape(), Shape::new([2, 2]));
+    // rms = [sqrt(12.5), sqrt(2)]
+    let expected = [
+        3. / 12.5f32.sqrt(),
+        -8. / 12.5f32.sqrt(),
+        0.,
+        4. / 2f32.sqrt(),
+    ];
+    assert_close(&y.calculate().get_values_f32(), &expected, 1e-6);
+}
+
+#[test]
+fn test_rms_norm_gradients() {
+    check_gradients(&[Shape::new([3, 5]), Shape::new([5])], |x| {
+        x[0].rms_norm(x[1], 1e-5)
+    });
+    check_gradients(&[Shape::new([2, 3, 4]), Shape::new([4])], |x| {
+        x[0].rms_norm(x[1], 1e-3)
+    });
+}
+
+#[test]
+fn test_batch_norm() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    // [batch = 2, channels = 2, length = 2]
+    let values = [1f32, 2., 10., 20., 3., 6., 30., 40.];
+    let x = (Shape::new([2, 2, 2]), values.to_vec()).into_node(&g, &hw);
+    let gamma = (Shape::new([2]), vec![1f32, 2.]).into_node(&g, &hw);
+    let beta = (Shape::new([2]), vec![0f32, 1.]).into_node(&g, &hw);
+    let y = x.batch_norm(gamma, beta, 1e-5);
+    assert_eq!(y.shape(), Shape::new([2, 2, 2]));
+
+    let channel0 = standardize(&[1., 2., 3., 6.], 1e-5);
+    let channel1 = standardize(&[10., 20., 30., 40.], 1e-5);
+    let expected = [
+        channel0[0],
+        channel0[1],
+        2. * channel1[0] + 1.,
+        2. * channel1[1] + 1.,
+        channel0[2],
+        channel0[3],
+        2. * channel1[2] + 1.,
+        2. * channel1[3] + 1.,
+    ]
+    .map(|v| v as f32);
+    assert_close(&y.calculate().get_values_f32(), &expected, 1e-5);
+
+    // Statistics of the batch give the same result in the inference mode.
+    let (mean, variance) = x.calculate().batch_norm_statistics_f32().unwrap();
+    let z = x.batch_norm_inference(
+        Node::constant(&g, mean),
+        Node::constant(&g, variance),
+        gamma,
+        beta,
+        1e-5,
+    );
+    assert_close(&z.calculate().get_values_f32(), &expected, 1e-5);
+}
+
+#[test]
+#[should_panic]
+fn test_batch_norm_invalid_shape() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let x = Node::fill(&g, &hw, Shape::new([2, 3, 4]), 0.);
+    let gamma = Node::fill(&g, &hw, Shape::new([4]), 1.);
+    let _ = x.batch_norm(gamma, gamma, 1e-5);
+}
+
+#[test]
+fn test_batch_norm_gradients() {
+    check_gradients(
+        &[Shape::new([6, 3]), Shape::new([3]), Shape::new([3])],
+        |x| x[0].batch_norm(x[1], x[2], 1e-5),
+    );
+    check_gradients(
+        &[Shape::new([2, 3, 2, 2]), Shape::new([3]), Shape::new([3])],
+        |x| x[0].batch_norm(x[1], x[2], 1e-3),
+    );
+}
+
+#[test]
+fn test_batch_norm_inference_gradients() {
+    let c = Shape::new([3]);
+    check_gradients(
+        &[Shape::new([2, 3, 4]), c.clone(), c.clone(), c.clone(), c],
+        |x| {
+            // Keeps the variance positive.
+            let variance =
+                x[2] * x[2] + Node::fill(x[2].graph(), x[2].hardware(), x[2].shape(), 0.5);
+            x[0].batch_norm_inference(x[1], variance, x[3], x[4], 1e-5)
+        },
+    );
+}
+
+#[test]
+fn test_layer_norm_backward_gradients() {
+    use crate::operator::layer_norm::layer_norm_backward;
+    check_gradients(
+        &[Shape::new([3, 5]), Shape::new([5]), Shape::new([3, 5])],
+        |x| layer_norm_backward(x[0], x[1], x[2], 1e-3),
+    );
+    check_gradients(
+        &[
+            Shape::new([2, 2, 4]),
+            Shape::new([4]),
+            Shape::new([2, 2, 4]),
+        ],
+        |x| layer_norm_backward(x[0], x[1], x[2], 1e-3),
+    );
+}
+
+#[test]
+fn test_rms_norm_backward_gradients() {
+    use crate::operator::rms_norm::rms_norm_backward;
+    check_gradients(
+        &[Shape::new([3, 5]), Shape::new([5]), Shape::new([3, 5])],
+        |x| rms_norm_backward(x[0], x[1], x[2], 1e-3),
+    );
+}
+
+#[test]
+fn test_batch_norm_backward_gradients() {
+    use crate::operator::batch_norm::batch_norm_backward;
+    check_gradients(
+        &[Shape::new([6, 3]), Shape::new([3]), Shape::new([6, 3])],
+        |x| batch_norm_backward(x[0], x[1], x[2], 1e-3),
+    );
+    check_gradients(
+        &[
+            Shape::new([2, 3, 2]),
+            Shape::new([3]),
+            Shape::new([2, 3, 2]),
+        ],
+        |x| batch_norm_backward(x[0], x[1], x[2], 1e-3),
+    );
+}
+
+#[test]
+fn test_norm_second_order_gradients() {
+    // The first-order gradients are differentiated again: x[3] weights the outputs.
+    let shapes = |x: Shape, p: Shape| [x.clone(), p.clone(), p, x];
+    check_gradients(&shapes(Shape::new([3, 4]), Shape::new([4])), |x| {
+        let y = sum_all(x[0].layer_norm(x[1], x[2], 1e-3) * x[3]);
+        let g = grad(y, &[x[0], x[1]]);
+        g[0] + g[1].expand_axis(0, 3)
+    });
+    check_gradients(&shapes(Shape::new([3, 4]), Shape::new([4])), |x| {
+        let y = sum_all(x[0].rms_norm(x[1], 1e-3) * x[3] + x[2].expand_axis(0, 3));
+        grad(y, &[x[0]])[0]
+    });
+    check_gradients(&shapes(Shape::new([4, 2]), Shape::new([2])), |x| {
+        let y = sum_all(x[0].batch_norm(x[1], x[2], 1e-3) * x[3]);
+        grad(y, &[x[0]])[0]
+    });
+}
+
+#[test]
+fn test_layer_norm_hvp() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let values = [0.5f32, -1., 2., 0.25, 1., -0.5];
+    let x = (Shape::new([2, 3]), values.to_vec()).into_node(&g, &hw);
+    let gamma = (Shape::new([3]), vec![1f32, -2., 0.5]).into_node(&g, &hw);
+    let beta = Node::fill(&g, &hw, Shape::new([3]), 0.);
+    let w = (Shape::new([2, 3]), vec![1f32, 0., -1., 2., 1., 0.5]).into_node(&g, &hw);
+    let v = (Shape::new([2, 3]), vec![0.5f32, 1., 0., -1., 0.5, 2.]).into_node(&g, &hw);
+    let y = sum_all(x.layer_norm(gamma, beta, 1e-3) * w);
+    let observed = hvp(y, &[x], &[v])[0].calculate().get_values_f32();
+
+    // Central differences of the gradient along v.
+    let v_values = v.calculate().get_values_f32();
+    let eval_grad = |t: f32| {
+        let g = RefCell::new(Graph::new());
+        let shifted = values
+            .iter()
+            .zip(&v_values)
+            .map(|(x, v)| x + t * v)
+            .collect::<Vec<_>>();
+        let x = (Shape::new([2, 3]), shifted).into_node(&g, &hw);
+        let gamma = (Shape::new([3]), vec![1f32, -2., 0.5]).into_node(&g, &hw);
+        let beta = Node::fill(&g, &hw, Shape::new([3]), 0.);
+        let w = (Shape::new([2, 3]), vec![1f32, 0., -1., 2., 1., 0.5]).into_node(&g, &hw);
+        let y = sum_all(x.layer_norm(gamma, beta, 1e-3) * w);
+        grad(y, &[x])[0].calculate().get_values_f32()
+    };
+    let (plus, minus) = (eval_grad(1e-2), eval_grad(-1e-2));
+    let expected = plus
+        .iter()
+        .zip(&minus)
+        .map(|(p, m)| (p - m) / 2e-2)
+        .collect::<Vec<_>>();
+    assert_close(&observed, &expected, 2e-2);
+}
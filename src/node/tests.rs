@@ -154,6 +154,90 @@ fn test_fill_n() {
     assert_eq!(ret.calculate().get_values_f32(), vec![123., 123., 123.]);
 }
 
+#[test]
+fn test_constant() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let value = Array::constant_f32(&hw, Shape::new([2, 2]), &[1., 2., 3., 4.]).unwrap();
+    let ret = Node::constant(&g, value);
+    assert_eq!(ret.shape(), Shape::new([2, 2]));
+    assert!(ptr::eq(ret.hardware(), &hw));
+    assert_eq!(g.borrow().get_step(0).unwrap().operator.name(), "Constant");
+    assert_eq!(ret.calculate().get_values_f32(), vec![1., 2., 3., 4.]);
+}
+
+#[test]
+fn test_variable() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let ret = Node::variable(&g, &hw, Shape::new([2]), 3.);
+    assert_eq!(ret.shape(), Shape::new([2]));
+    assert!(ptr::eq(ret.hardware(), &hw));
+    assert_eq!(ret.calculate().get_values_f32(), vec![3., 3.]);
+}
+
+#[test]
+fn test_into_node_array() {
+    let hw1 = RefCell::new(CpuHardware::new());
+    let hw2 = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let value = Array::constant_f32(&hw1, Shape::new([3]), &[1., 2., 3.]).unwrap();
+
+    let a = (&value).into_node(&g, &hw2);
+    assert_eq!(a.shape(), Shape::new([3]));
+    assert!(ptr::eq(a.hardware(), &hw2));
+    assert_eq!(a.calculate().get_values_f32(), vec![1., 2., 3.]);
+
+    let b = value.into_node(&g, &hw1);
+    assert_eq!(b.shape(), Shape::new([3]));
+    assert!(ptr::eq(b.hardware(), &hw1));
+    assert_eq!(b.calculate().get_values_f32(), vec![1., 2., 3.]);
+}
+
+#[test]
+fn test_into_node_host_data() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let a = [1f32, 2., 3.][..].into_node(&g, &hw);
+    let b = vec![4f32, 5., 6.].into_node(&g, &hw);
+    let c = (Shape::new([3]), &[7f32, 8., 9.][..]).into_node(&g, &hw);
+    let d = (Shape::new([3]), vec![10f32, 11., 12.]).into_node(&g, &hw);
+    let y = a + b * c - d;
+
+    for node in [a, b, c, d, y] {
+        assert_eq!(node.shape(), Shape::new([3]));
+        assert!(ptr::eq(node.hardware(), &hw));
+    }
+    assert_eq!(y.calculate().get_values_f32(), vec![19., 31., 45.]);
+
+    let m = (Shape::new([2, 2]), vec![1f32, 2., 3., 4.]).into_node(&g, &hw);
+    assert_eq!(m.shape(), Shape::new([2, 2]));
+    assert_eq!(m.calculate().get_values_f32(), vec![1., 2., 3., 4.]);
+}
+
+#[test]
+#[should_panic]
+fn test_into_node_host_data_invalid() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let _x = (Shape::new([2, 2]), vec![1f32, 2., 3.]).into_node(&g, &hw);
+}
+
+#[test]
+fn test_constant_grad() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = vec![1f32, 2., 3.].into_node(&g, &hw);
+    let c = vec![4f32, 5., 6.].into_node(&g, &hw);
+    let y = x * c;
+
+    let gx = grad(y, &[x, c]);
+    assert_eq!(gx[0].calculate().get_values_f32(), vec![4., 5., 6.]);
+    assert_eq!(gx[1].calculate().get_values_f32(), vec![1., 2., 3.]);
+}
+
 #[test]
 fn test_multiple_computation() {
     let hw = RefCell::new(CpuHardware::new());
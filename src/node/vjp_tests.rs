@@ -0,0 +1,266 @@
+use crate::hardware::cpu::CpuHardware;
+use crate::node::*;
+
+#[test]
+fn test_empty_inputs() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let y = 42f32.into_node(&g, &hw);
+    let gy = 1f32.into_node(&g, &hw);
+
+    assert!(vjp(&[y], &[], &[gy]).is_empty());
+}
+
+#[test]
+fn test_empty_outputs() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 42f32.into_node(&g, &hw);
+
+    let gx = vjp(&[], &[x], &[]);
+    assert_eq!(gx.len(), 1);
+
+    assert_eq!(gx[0].shape(), Shape::new([]));
+    assert!(ptr::eq(gx[0].hardware(), &hw));
+
+    // Nothing is backpropagated.
+    assert_eq!(f32::try_from(gx[0]), Ok(0.));
+}
+
+#[test]
+fn test_self() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 42f32.into_node(&g, &hw);
+    let gy = 3f32.into_node(&g, &hw);
+
+    // gy * dx/dx == gy
+    assert_eq!(f32::try_from(vjp(&[x], &[x], &[gy])[0]), Ok(3.));
+}
+
+#[test]
+fn test_unrelated() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 42f32.into_node(&g, &hw);
+    let y = 42f32.into_node(&g, &hw);
+    let gy = 3f32.into_node(&g, &hw);
+
+    // y is not calculated by x.
+    assert_eq!(f32::try_from(vjp(&[y], &[x], &[gy])[0]), Ok(0.));
+}
+
+#[test]
+fn test_seed() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let a = 2f32.into_node(&g, &hw);
+    let b = 3f32.into_node(&g, &hw);
+    let y = a * b;
+    let gy = 10f32.into_node(&g, &hw);
+
+    let gx = vjp(&[y], &[a, b], &[gy]);
+    assert_eq!(gx.len(), 2);
+
+    // gy * b
+    assert_eq!(f32::try_from(gx[0]), Ok(30.));
+    // gy * a
+    assert_eq!(f32::try_from(gx[1]), Ok(20.));
+}
+
+#[test]
+fn test_seed_n() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = Node::fill(&g, &hw, Shape::new([3]), 2.);
+    let y = x * x;
+    let gy = Node::fill(&g, &hw, Shape::new([3]), -1.);
+
+    let gx = vjp(&[y], &[x], &[gy]);
+    assert_eq!(gx[0].shape(), Shape::new([3]));
+
+    // gy * 2x
+    assert_eq!(gx[0].calculate().get_values_f32(), vec![-4., -4., -4.]);
+}
+
+#[test]
+fn test_consistent_with_grad() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let a = 1f32.into_node(&g, &hw);
+    let b = 2f32.into_node(&g, &hw);
+    let c = 3f32.into_node(&g, &hw);
+    let y = a + -b * c;
+    let one = 1f32.into_node(&g, &hw);
+
+    let expected = grad(y, &[a, b, c])
+        .into_iter()
+        .map(|node| f32::try_from(node).unwrap())
+        .collect::<Vec<_>>();
+    let observed = vjp(&[y], &[a, b, c], &[one])
+        .into_iter()
+        .map(|node| f32::try_from(node).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(observed, expected);
+}
+
+#[test]
+fn test_multiple_outputs() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let a = 2f32.into_node(&g, &hw);
+    let b = 3f32.into_node(&g, &hw);
+    let y1 = a * b;
+    let y2 = a - b;
+    let gy1 = 1f32.into_node(&g, &hw);
+    let gy2 = 10f32.into_node(&g, &hw);
+
+    let gx = vjp(&[y1, y2], &[a, b], &[gy1, gy2]);
+    assert_eq!(gx.len(), 2);
+
+    // gy1 * b + gy2
+    assert_eq!(f32::try_from(gx[0]), Ok(13.));
+    // gy1 * a - gy2
+    assert_eq!(f32::try_from(gx[1]), Ok(-8.));
+}
+
+#[test]
+fn test_multiple_outputs_chained() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 3f32.into_node(&g, &hw);
+    let h = x * x;
+    let y = h * x;
+    let gh = 1f32.into_node(&g, &hw);
+    let gy = 2f32.into_node(&g, &hw);
+
+    // Both the intermediate and the final outputs receive gradients:
+    // gh * 2x + gy * 3x^2
+    let gx = vjp(&[h, y], &[x], &[gh, gy]);
+    assert_eq!(f32::try_from(gx[0]), Ok(60.));
+}
+
+#[test]
+fn test_duplicated_outputs() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 3f32.into_node(&g, &hw);
+    let y = x * x;
+    let gy1 = 1f32.into_node(&g, &hw);
+    let gy2 = 2f32.into_node(&g, &hw);
+
+    // Seeds of the same output are summed up: (gy1 + gy2) * 2x
+    let gx = vjp(&[y, y], &[x], &[gy1, gy2]);
+    assert_eq!(f32::try_from(gx[0]), Ok(18.));
+}
+
+#[test]
+fn test_partial_backpropagation() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    // Upstream part of the model.
+    let x = 2f32.into_node(&g, &hw);
+    let h = x * x * x;
+
+    // Downstream part is calculated in another graph: z = h * h.
+    let g2 = RefCell::new(Graph::new());
+    let h2 = f32::try_from(h).unwrap().into_node(&g2, &hw);
+    let z = h2 * h2;
+    let gh2 = grad(z, &[h2])[0];
+
+    // Brings the downstream gradient dz/dh back to the upstream graph.
+    let gh = f32::try_from(gh2).unwrap().into_node(&g, &hw);
+    let gx = vjp(&[h], &[x], &[gh])[0];
+
+    // dz/dx == 2h * 3x^2 == 6x^5
+    assert_eq!(f32::try_from(gx), Ok(192.));
+}
+
+#[test]
+fn test_pruning_unrelated_inputs() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = Node::fill(&g, &hw, Shape::new([2, 3]), 1.);
+    let w = Node::fill(&g, &hw, Shape::new([3, 4]), 2.);
+    let gamma = Node::fill(&g, &hw, Shape::new([4]), 1.);
+    let beta = Node::fill(&g, &hw, Shape::new([4]), 0.);
+    let y = x.matmul(w).layer_norm(gamma, beta, 1e-5);
+    let gy = Node::fill(&g, &hw, Shape::new([2, 4]), 1.);
+    let num_steps = g.borrow().num_steps();
+
+    let gw = vjp(&[y], &[w], &[gy]);
+
+    // LayerNormBackward for the gradient of x.matmul(w), and Transpose and MatMul for w.
+    // Gradients of x, gamma and beta are never constructed.
+    assert_eq!(g.borrow().num_steps(), num_steps + 3);
+    assert_eq!(gw[0].shape(), Shape::new([3, 4]));
+}
+
+#[test]
+fn test_pruning_independent_inputs() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let a = 2f32.into_node(&g, &hw);
+    let c = 3f32.into_node(&g, &hw);
+    let b = c * c;
+    let y = a * b;
+    let gy = 1f32.into_node(&g, &hw);
+    let num_steps = g.borrow().num_steps();
+
+    let gx = vjp(&[y], &[a, b], &[gy]);
+
+    // 2 Mul steps for the gradients of a and b. b does not depend on a, so gradients are never
+    // propagated to c.
+    assert_eq!(g.borrow().num_steps(), num_steps + 2);
+    assert_eq!(f32::try_from(gx[0]), Ok(9.));
+    assert_eq!(f32::try_from(gx[1]), Ok(2.));
+}
+
+#[test]
+#[should_panic]
+fn test_different_graph() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g1 = RefCell::new(Graph::new());
+    let g2 = RefCell::new(Graph::new());
+
+    let x = 1f32.into_node(&g1, &hw);
+    let y = -x;
+    let gy = 1f32.into_node(&g2, &hw);
+    let _gx = vjp(&[y], &[x], &[gy]);
+}
+
+#[test]
+#[should_panic]
+fn test_invalid_num_seeds() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 1f32.into_node(&g, &hw);
+    let y = -x;
+    let _gx = vjp(&[y], &[x], &[]);
+}
+
+#[test]
+#[should_panic]
+fn test_invalid_seed_shape() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+
+    let x = 1f32.into_node(&g, &hw);
+    let y = -x;
+    let gy = Node::fill(&g, &hw, Shape::new([3]), 1.);
+    let _gx = vjp(&[y], &[x], &[gy]);
+}
@@ -0,0 +1,502 @@
+use crate::error::Error;
+use crate::graph::Step;
+use crate::node::Node;
+use crate::result::Result;
+use crate::serialize::{self, Decoder, Encoder};
+use crate::shape::Shape;
+use std::collections::HashMap;
+use std::io;
+
+/// ONNX IR version of exported models.
+const IR_VERSION: i64 = 8;
+
+/// Version of the default ONNX operator set used by exported models.
+const OPSET_VERSION: i64 = 17;
+
+/// `TensorProto.DataType` of 32-bit floating point numbers.
+const DATA_TYPE_FLOAT: i64 = 1;
+
+/// `TensorProto.DataType` of 64-bit signed integers.
+const DATA_TYPE_INT64: i64 = 7;
+
+/// `TensorProto.DataType` of booleans.
+const DATA_TYPE_BOOL: i64 = 9;
+
+/// `AttributeProto.AttributeType` of a float value.
+const ATTRIBUTE_FLOAT: i64 = 1;
+
+/// `AttributeProto.AttributeType` of an integer value.
+const ATTRIBUTE_INT: i64 = 2;
+
+/// `AttributeProto.AttributeType` of a list of integers.
+const ATTRIBUTE_INTS: i64 = 7;
+
+/// Exports the computation between the given nodes as an ONNX model.
+///
+/// All steps necessary to calculate `outputs` are exported, except that the traversal stops at
+/// `inputs`. Values of `Node::constant()`, `Node::parameter()` and `Node::fill()` are stored as
+/// initializers of the model. Inputs and outputs of the model are named `input_{i}` and
+/// `output_{i}` respectively.
+///
+/// Each step is mapped to ONNX operators (opset 17) according to its operator name.
+/// Operators without forward computation on their own, e.g., `StopGradient`, are exported as
+/// `Identity`, and `RmsNorm` is decomposed into elementwise operators and `ReduceSum`.
+/// The following operators have no ONNX counterpart and can not be exported: `BatchNorm`,
+/// `CustomGradient`, `Dropout`, `Step` and the operators constructing gradients, e.g.,
+/// `Conv2dBackwardInput` and `ScatterAddRows`.
+///
+/// # Arguments
+///
+/// * `inputs` - `Node`s to be the inputs of the model.
+/// * `outputs` - `Node`s to be the outputs of the model. At least one node is required.
+/// * `writer` - Destination of the serialized `ModelProto`.
+///
+/// # Returns
+///
+/// * `Ok(())` - The model is exported.
+/// * `Err(Error)` - Nodes belong to different graphs, some step has no ONNX counterpart, or
+///   writing failed.
+pub fn export<'hw: 'op, 'op: 'g, 'g, W: io::Write>(
+    inputs: &[Node<'hw, 'op, 'g>],
+    outputs: &[Node<'hw, 'op, 'g>],
+    writer: &mut W,
+) -> Result<()> {
+    let first = outputs
+        .first()
+        .ok_or_else(|| Error::InvalidLength("At least one output is required.".to_string()))?;
+    let graph = first
+        .check_graph(&inputs.iter().chain(outputs).collect::<Vec<_>>())?
+        .borrow();
+
+    // Names of the values available in the model.
+    let mut names = HashMap::new();
+    for (i, input) in inputs.iter().enumerate() {
+        if names
+            .insert(input.step_id(), format!("input_{}", i))
+            .is_some()
+        {
+            return Err(Error::InvalidNode(format!("Duplicated input: {}", input)));
+        }
+    }
+
+    // Collects the steps to be exported.
+    let mut required = vec![false; graph.num_steps()];
+    let mut stack = outputs.iter().map(|n| n.step_id()).collect::<Vec<_>>();
+    while let Some(step_id) = stack.pop() {
+        if required[step_id] || names.contains_key(&step_id) {
+            continue;
+        }
+        required[step_id] = true;
+        stack.extend(graph.get_step(step_id)?.inputs());
+    }
+
+    // Step IDs are already sorted in the topological order.
+    let mut builder = GraphBuilder::new();
+    for (step_id, step) in graph.steps().iter().enumerate() {
+        if !required[step_id] {
+            continue;
+        }
+        let name = format!("s{}", step_id);
+        let step_inputs = step
+            .inputs()
+            .iter()
+            .map(|input| names[input].as_str())
+            .collect::<Vec<_>>();
+        export_step(&mut builder, step, &step_inputs, &name)?;
+        names.insert(step_id, name);
+    }
+
+    let mut model_graph = protobuf::Message::new();
+    for node in &builder.nodes {
+        model_graph.message(1, node);
+    }
+    for (i, output) in outputs.iter().enumerate() {
+        let name = format!("output_{}", i);
+        model_graph.message(
+            1,
+            &node("Identity", &[&names[&output.step_id()]], &name, &[]),
+        );
+    }
+    model_graph.string(2, "dycg");
+    for initializer in &builder.initializers {
+        model_graph.message(5, initializer);
+    }
+    for (i, input) in inputs.iter().enumerate() {
+        model_graph.message(11, &value_info(&format!("input_{}", i), &input.shape()));
+    }
+    for (i, output) in outputs.iter().enumerate() {
+        model_graph.message(12, &value_info(&format!("output_{}", i), &output.shape()));
+    }
+
+    let mut opset = protobuf::Message::new();
+    opset.int(2, OPSET_VERSION);
+
+    let mut model = protobuf::Message::new();
+    model
+        .int(1, IR_VERSION)
+        .string(2, "dycg")
+        .string(3, env!("CARGO_PKG_VERSION"))
+        .message(7, &model_graph)
+        .message(8, &opset);
+
+    writer
+        .write_all(&model.into_bytes())
+        .map_err(|e| Error::Io(e.to_string()))
+}
+
+/// Accumulates `NodeProto`s and initializers of a `GraphProto`.
+struct GraphBuilder {
+    /// Encoded `NodeProto`s.
+    nodes: Vec<protobuf::Message>,
+
+    /// Encoded `TensorProto`s.
+    initializers: Vec<protobuf::Message>,
+}
+
+impl GraphBuilder {
+    fn new() -> Self {
+        Self {
+            nodes: vec![],
+            initializers: vec![],
+        }
+    }
+
+    /// Adds a `NodeProto` with a single output.
+    fn node(
+        &mut self,
+        op_type: &str,
+        inputs: &[&str],
+        output: &str,
+        attributes: &[protobuf::Message],
+    ) {
+        self.nodes.push(node(op_type, inputs, output, attributes));
+    }
+
+    /// Adds a float initializer.
+    fn float_initializer(&mut self, name: &str, shape: &Shape, values: &[f32]) {
+        let raw = values
+            .iter()
+            .flat_map(|x| x.to_le_bytes())
+            .collect::<Vec<_>>();
+        self.initializers
+            .push(tensor(name, DATA_TYPE_FLOAT, shape.dimensions(), &raw));
+    }
+
+    /// Adds a 1-dimensional int64 initializer.
+    fn int64_initializer(&mut self, name: &str, values: &[i64]) {
+        let raw = values
+            .iter()
+            .flat_map(|x| x.to_le_bytes())
+            .collect::<Vec<_>>();
+        self.initializers
+            .push(tensor(name, DATA_TYPE_INT64, &[values.len()], &raw));
+    }
+}
+
+/// Maps a single step to ONNX operators.
+///
+/// # Arguments
+///
+/// * `builder` - `GraphBuilder` to add the resulting nodes and initializers.
+/// * `step` - `Step` to be exported.
+/// * `inputs` - Names of the input values of the step.
+/// * `output` - Name of the output value of the step. Auxiliary values are named with this value
+///   as a prefix.
+///
+/// # Returns
+///
+/// * `Ok(())` - The step is exported.
+/// * `Err(Error)` - The operator has no ONNX counterpart.
+fn export_step(
+    builder: &mut GraphBuilder,
+    step: &Step,
+    inputs: &[&str],
+    output: &str,
+) -> Result<()> {
+    let name = step.operator_name();
+    let not_supported = || Error::NotSupported(format!("{} can not be exported to ONNX.", name));
+    let mut encoder = Encoder::new();
+    step.operator
+        .encode_attributes(&mut encoder)
+        .map_err(|_| not_supported())?;
+    let attributes = encoder.into_bytes();
+    let d = &mut Decoder::new(&attributes);
+    let aux = |suffix: &str| format!("{}_{}", output, suffix);
+
+    match name.as_str() {
+        "Constant" => {
+            let shape = d.read_shape()?;
+            let values = d.read_f32s(shape.num_elements())?;
+            builder.float_initializer(output, &shape, &values);
+        }
+        "Fill" => {
+            let shape = d.read_shape()?;
+            let values = vec![d.read_f32()?; shape.num_elements()];
+            builder.float_initializer(output, &shape, &values);
+        }
+        "Add" | "Div" | "MatMul" | "Mul" | "Neg" | "Relu" | "Sigmoid" | "Sqrt" | "Sub" | "Tanh"
+        | "Transpose" => builder.node(&name, inputs, output, &[]),
+        "Maximum" => builder.node("Max", inputs, output, &[]),
+        "Minimum" => builder.node("Min", inputs, output, &[]),
+        "ClipGradient" | "StopGradient" | "StraightThrough" => {
+            builder.node("Identity", &inputs[..1], output, &[])
+        }
+        "Clamp" => {
+            let (min, max) = (aux("min"), aux("max"));
+            builder.float_initializer(&min, &Shape::new([]), &[d.read_f32()?]);
+            builder.float_initializer(&max, &Shape::new([]), &[d.read_f32()?]);
+            builder.node("Clip", &[inputs[0], &min, &max], output, &[]);
+        }
+        "SumAxis" => {
+            let axes = aux("axes");
+            builder.int64_initializer(&axes, &[d.read_usize()? as i64]);
+            builder.node(
+                "ReduceSum",
+                &[inputs[0], &axes],
+                output,
+                &[attribute_int("keepdims", 0)],
+            );
+        }
+        "ExpandAxis" => {
+            let (axes, unsqueezed, shape) = (aux("axes"), aux("unsqueezed"), aux("shape"));
+            builder.int64_initializer(&axes, &[d.read_usize()? as i64]);
+            builder.int64_initializer(&shape, &to_i64s(step.output().shape().dimensions()));
+            builder.node("Unsqueeze", &[inputs[0], &axes], &unsqueezed, &[]);
+            builder.node("Expand", &[&unsqueezed, &shape], output, &[]);
+        }
+        "Equal" | "IsInf" | "IsNan" | "Less" | "LessEqual" => {
+            // ONNX comparisons return booleans, while dycg represents them by 0 and 1.
+            let op_type = match name.as_str() {
+                "IsNan" => "IsNaN",
+                "LessEqual" => "LessOrEqual",
+                op_type => op_type,
+            };
+            let condition = aux("condition");
+            builder.node(op_type, inputs, &condition, &[]);
+            builder.node(
+                "Cast",
+                &[&condition],
+                output,
+                &[attribute_int("to", DATA_TYPE_FLOAT)],
+            );
+        }
+        "LogicalAnd" | "LogicalNot" | "LogicalOr" => {
+            // ONNX logical operators take booleans, while dycg treats nonzero values as true.
+            let operands = (0..inputs.len())
+                .map(|i| aux(&format!("operand{}", i)))
+                .collect::<Vec<_>>();
+            for (input, operand) in inputs.iter().zip(&operands) {
+                builder.node(
+                    "Cast",
+                    &[input],
+                    operand,
+                    &[attribute_int("to", DATA_TYPE_BOOL)],
+                );
+            }
+            let condition = aux("condition");
+            builder.node(
+                &name["Logical".len()..],
+                &operands.iter().map(|o| o.as_str()).collect::<Vec<_>>(),
+                &condition,
+                &[],
+            );
+            builder.node(
+                "Cast",
+                &[&condition],
+                output,
+                &[attribute_int("to", DATA_TYPE_FLOAT)],
+            );
+        }
+        "Select" => {
+            let condition = aux("condition");
+            builder.node(
+                "Cast",
+                &inputs[..1],
+                &condition,
+                &[attribute_int("to", DATA_TYPE_BOOL)],
+            );
+            builder.node("Where", &[&condition, inputs[1], inputs[2]], output, &[]);
+        }
+        "GatherRows" => {
+            let indices = aux("indices");
+            builder.node(
+                "Cast",
+                &inputs[1..],
+                &indices,
+                &[attribute_int("to", DATA_TYPE_INT64)],
+            );
+            builder.node(
+                "Gather",
+                &[inputs[0], &indices],
+                output,
+                &[attribute_int("axis", 0)],
+            );
+        }
+        "Conv2d" => {
+            let options = serialize::read_conv2d_options(d)?;
+            builder.node(
+                "Conv",
+                inputs,
+                output,
+                &[
+                    attribute_ints("strides", &to_i64s(&options.stride)),
+                    attribute_ints(
+                        "pads",
+                        &to_i64s(&[options.padding, options.padding].concat()),
+                    ),
+                    attribute_ints("dilations", &to_i64s(&options.dilation)),
+                    attribute_int("group", options.groups as i64),
+                ],
+            );
+        }
+        "AvgPool2d" | "MaxPool2d" => {
+            let options = serialize::read_pool2d_options(d)?;
+            let mut attributes = vec![
+                attribute_ints("kernel_shape", &to_i64s(&options.kernel_size)),
+                attribute_ints("strides", &to_i64s(&options.stride)),
+                attribute_ints(
+                    "pads",
+                    &to_i64s(&[options.padding, options.padding].concat()),
+                ),
+            ];
+            let op_type = if name == "AvgPool2d" {
+                // Padded values are treated as zeros by dycg.
+                attributes.push(attribute_int("count_include_pad", 1));
+                "AveragePool"
+            } else {
+                "MaxPool"
+            };
+            builder.node(op_type, inputs, output, &attributes);
+        }
+        "LayerNorm" => builder.node(
+            "LayerNormalization",
+            inputs,
+            output,
+            &[
+                attribute_int("axis", -1),
+                attribute_float("epsilon", d.read_f32()?),
+            ],
+        ),
+        "RmsNorm" => {
+            // x * gamma / sqrt(mean(x * x) + epsilon) over the last axis.
+            let dims = step.output().shape().dimensions().to_vec();
+            let (square, axes, sum, size) = (aux("square"), aux("axes"), aux("sum"), aux("size"));
+            let (mean, epsilon, shifted) = (aux("mean"), aux("epsilon"), aux("shifted"));
+            let (rms, normalized) = (aux("rms"), aux("normalized"));
+            builder.node("Mul", &[inputs[0], inputs[0]], &square, &[]);
+            builder.int64_initializer(&axes, &[dims.len() as i64 - 1]);
+            builder.node(
+                "ReduceSum",
+                &[&square, &axes],
+                &sum,
+                &[attribute_int("keepdims", 1)],
+            );
+            builder.float_initializer(&size, &Shape::new([]), &[dims[dims.len() - 1] as f32]);
+            builder.node("Div", &[&sum, &size], &mean, &[]);
+            builder.float_initializer(&epsilon, &Shape::new([]), &[d.read_f32()?]);
+            builder.node("Add", &[&mean, &epsilon], &shifted, &[]);
+            builder.node("Sqrt", &[&shifted], &rms, &[]);
+            builder.node("Div", &[inputs[0], &rms], &normalized, &[]);
+            builder.node("Mul", &[&normalized, inputs[1]], output, &[]);
+        }
+        "BatchNormInference" => builder.node(
+            "BatchNormalization",
+            &[inputs[0], inputs[3], inputs[4], inputs[1], inputs[2]],
+            output,
+            &[attribute_float("epsilon", d.read_f32()?)],
+        ),
+        _ => return Err(not_supported()),
+    }
+    Ok(())
+}
+
+/// Converts dimensions into ONNX integers.
+fn to_i64s(values: &[usize]) -> Vec<i64> {
+    values.iter().map(|&x| x as i64).collect()
+}
+
+/// Encodes a `NodeProto` with a single output.
+fn node(
+    op_type: &str,
+    inputs: &[&str],
+    output: &str,
+    attributes: &[protobuf::Message],
+) -> protobuf::Message {
+    let mut node = protobuf::Message::new();
+    for input in inputs {
+        node.string(1, input);
+    }
+    node.string(2, output).string(3, output).string(4, op_type);
+    for attribute in attributes {
+        node.message(5, attribute);
+    }
+    node
+}
+
+/// Encodes an `AttributeProto` holding a float value.
+fn attribute_float(name: &str, value: f32) -> protobuf::Message {
+    let mut attribute = protobuf::Message::new();
+    attribute
+        .string(1, name)
+        .float(2, value)
+        .int(20, ATTRIBUTE_FLOAT);
+    attribute
+}
+
+/// Encodes an `AttributeProto` holding an integer value.
+fn attribute_int(name: &str, value: i64) -> protobuf::Message {
+    let mut attribute = protobuf::Message::new();
+    attribute
+        .string(1, name)
+        .int(3, value)
+        .int(20, ATTRIBUTE_INT);
+    attribute
+}
+
+/// Encodes an `AttributeProto` holding a list of integers.
+fn attribute_ints(name: &str, values: &[i64]) -> protobuf::Message {
+    let mut attribute = protobuf::Message::new();
+    attribute
+        .string(1, name)
+        .packed_ints(8, values)
+        .int(20, ATTRIBUTE_INTS);
+    attribute
+}
+
+/// Encodes a `TensorProto` with raw data.
+fn tensor(name: &str, data_type: i64, dims: &[usize], raw: &[u8]) -> protobuf::Message {
+    let mut tensor = protobuf::Message::new();
+    tensor
+        .packed_ints(1, &to_i64s(dims))
+        .int(2, data_type)
+        .string(8, name)
+        .bytes(9, raw);
+    tensor
+}
+
+/// Encodes a `ValueInfoProto` of a float tensor.
+fn value_info(name: &str, shape: &Shape) -> protobuf::Message {
+    let mut tensor_shape = protobuf::Message::new();
+    for &dim in shape.dimensions() {
+        let mut dimension = protobuf::Message::new();
+        dimension.int(1, dim as i64);
+        tensor_shape.message(1, &dimension);
+    }
+    let mut tensor_type = protobuf::Message::new();
+    tensor_type
+        .int(1, DATA_TYPE_FLOAT)
+        .message(2, &tensor_shape);
+    let mut value_type = protobuf::Message::new();
+    value_type.message(1, &tensor_type);
+    let mut value_info = protobuf::Message::new();
+    value_info.string(1, name).message(2, &value_type);
+    value_info
+}
+
+mod import;
+mod protobuf;
+
+pub use import::{import, Model};
+
+#[cfg(test)]
+mod tests;
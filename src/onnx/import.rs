@@ -0,0 +1,993 @@
+use crate::array::Array;
+use crate::conv::{Conv2dOptions, Pool2dOptions};
+use crate::error::Error;
+use crate::graph::Graph;
+use crate::hardware::Hardware;
+use crate::node::Node;
+use crate::onnx::protobuf::Fields;
+use crate::onnx::{DATA_TYPE_BOOL, DATA_TYPE_FLOAT, DATA_TYPE_INT64};
+use crate::operator::{self, Operator};
+use crate::result::Result;
+use crate::shape::{Shape, MAX_NUM_DIMENSIONS};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io;
+
+/// `TensorProto.DataType` of 32-bit signed integers.
+const DATA_TYPE_INT32: i64 = 6;
+
+/// `TensorProto.DataLocation` of tensors stored in external files.
+const DATA_LOCATION_EXTERNAL: i64 = 1;
+
+/// ONNX model loaded into a `Graph`.
+pub struct Model<'hw: 'op, 'op: 'g, 'g> {
+    /// Inputs of the model with their names, which are the nodes passed to `import()`.
+    pub inputs: Vec<(String, Node<'hw, 'op, 'g>)>,
+
+    /// Outputs of the model with their names.
+    pub outputs: Vec<(String, Node<'hw, 'op, 'g>)>,
+
+    /// Float initializers of the model with their names, loaded as `Constant` steps.
+    /// These nodes can be used to calculate gradients for fine-tuning.
+    pub initializers: Vec<(String, Node<'hw, 'op, 'g>)>,
+}
+
+/// Loads an ONNX model into a `Graph`.
+///
+/// Supported operators are mapped to the corresponding dycg operators. Multidirectional
+/// broadcasting of ONNX is emulated by `ExpandAxis` and `SumAxis`. Boolean values are represented
+/// by 0 and 1 in float as well as the comparison operators of dycg. Integer values are supported
+/// only as constant arguments, e.g., axes, and as indices of `Gather` cast from float values.
+///
+/// # Arguments
+///
+/// * `graph` - `Graph` object to register the operations.
+/// * `hardware` - `Hardware` object to host the initializers and constants of the model.
+/// * `reader` - Source of the serialized `ModelProto`.
+/// * `inputs` - `Node`s to be bound to the inputs of the model, in the order of declaration.
+///   Inputs which also appear in the initializers are not counted.
+///
+/// # Returns
+///
+/// * `Ok(Model)` - Named inputs, outputs and initializers of the loaded model.
+/// * `Err(Error)` - The model is broken, it contains unsupported operators or data types, or
+///   `inputs` do not match the inputs of the model.
+pub fn import<'hw: 'op, 'op: 'g, 'g, R: io::Read>(
+    graph: &'g RefCell<Graph<'hw, 'op>>,
+    hardware: &'hw RefCell<dyn Hardware>,
+    reader: &mut R,
+    inputs: &[Node<'hw, 'op, 'g>],
+) -> Result<Model<'hw, 'op, 'g>> {
+    let mut bytes = vec![];
+    reader
+        .read_to_end(&mut bytes)
+        .map_err(|e| Error::Io(e.to_string()))?;
+    let model = Fields::parse(&bytes)?;
+    for opset in model.messages(8)? {
+        let domain = opset.string(1)?.unwrap_or("");
+        if !is_default_domain(domain) {
+            return Err(Error::NotSupported(format!(
+                "Unsupported operator set: {}",
+                domain
+            )));
+        }
+    }
+    let model_graph = model
+        .messages(7)?
+        .pop()
+        .ok_or_else(|| Error::InvalidData("The model has no graph.".to_string()))?;
+
+    let mut importer = Importer {
+        graph,
+        hardware,
+        values: HashMap::new(),
+        int_constants: HashMap::new(),
+        indices: HashMap::new(),
+    };
+
+    let mut initializers = vec![];
+    for tensor in model_graph.messages(5)? {
+        let name = tensor.string(8)?.unwrap_or("").to_string();
+        if let Some(node) = importer.add_tensor(&name, &tensor)? {
+            initializers.push((name, node));
+        }
+    }
+
+    let declared_inputs = model_graph
+        .messages(11)?
+        .into_iter()
+        .map(|value_info| {
+            let name = value_info.string(1)?.unwrap_or("").to_string();
+            Ok((name, value_info))
+        })
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .filter(|(name, _)| !importer.values.contains_key(name))
+        .filter(|(name, _)| !importer.int_constants.contains_key(name))
+        .collect::<Vec<_>>();
+    if declared_inputs.len() != inputs.len() {
+        return Err(Error::InvalidLength(format!(
+            "The model requires {} inputs, but got {}.",
+            declared_inputs.len(),
+            inputs.len()
+        )));
+    }
+    let mut model_inputs = vec![];
+    for ((name, value_info), &node) in declared_inputs.into_iter().zip(inputs) {
+        if !ptr_eq_graph(node, graph) {
+            return Err(Error::InvalidGraph(format!(
+                "Input {} belongs to a different graph.",
+                name
+            )));
+        }
+        if let Some(dims) = static_dimensions(&value_info)? {
+            if node.shape().dimensions() != dims {
+                return Err(Error::InvalidShape(format!(
+                    "Input {} requires a shape {:?}, but got {}.",
+                    name,
+                    dims,
+                    node.shape()
+                )));
+            }
+        }
+        importer.values.insert(name.clone(), node);
+        model_inputs.push((name, node));
+    }
+
+    for node in model_graph.messages(1)? {
+        importer.import_node(&node)?;
+    }
+
+    let outputs = model_graph
+        .messages(12)?
+        .iter()
+        .map(|value_info| {
+            let name = value_info.string(1)?.unwrap_or("").to_string();
+            let node = importer.get(&name)?;
+            Ok((name, node))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Model {
+        inputs: model_inputs,
+        outputs,
+        initializers,
+    })
+}
+
+/// Checks whether the domain represents the default ONNX operator set.
+fn is_default_domain(domain: &str) -> bool {
+    domain.is_empty() || domain == "ai.onnx"
+}
+
+/// Checks whether the node belongs to the graph.
+fn ptr_eq_graph<'hw: 'op, 'op: 'g, 'g>(
+    node: Node<'hw, 'op, 'g>,
+    graph: &'g RefCell<Graph<'hw, 'op>>,
+) -> bool {
+    std::ptr::eq(node.graph(), graph)
+}
+
+/// Obtains the shape declared by a `ValueInfoProto`.
+///
+/// # Returns
+///
+/// * `Ok(Some(Vec<usize>))` - All dimensions are declared by values.
+/// * `Ok(None)` - The shape is not declared, or some dimension is symbolic.
+/// * `Err(Error)` - The `ValueInfoProto` is broken, or it declares a non-float tensor.
+fn static_dimensions(value_info: &Fields) -> Result<Option<Vec<usize>>> {
+    let Some(value_type) = value_info.messages(2)?.pop() else {
+        return Ok(None);
+    };
+    let Some(tensor_type) = value_type.messages(1)?.pop() else {
+        return Err(Error::NotSupported(
+            "Only tensor inputs are supported.".to_string(),
+        ));
+    };
+    if let Some(elem_type) = tensor_type.int(1)? {
+        if elem_type != DATA_TYPE_FLOAT {
+            return Err(Error::NotSupported(format!(
+                "Unsupported input type: {}",
+                elem_type
+            )));
+        }
+    }
+    let Some(shape) = tensor_type.messages(2)?.pop() else {
+        return Ok(None);
+    };
+    let mut dims = vec![];
+    for dim in shape.messages(1)? {
+        match dim.int(1)? {
+            Some(value) if value >= 0 => dims.push(value as usize),
+            _ => return Ok(None),
+        }
+    }
+    Ok(Some(dims))
+}
+
+/// Attributes of a `NodeProto`.
+struct Attributes<'a> {
+    /// Attributes by names.
+    attributes: HashMap<&'a str, Fields<'a>>,
+}
+
+impl<'a> Attributes<'a> {
+    fn new(node: &Fields<'a>) -> Result<Self> {
+        let mut attributes = HashMap::new();
+        for attribute in node.messages(5)? {
+            if let Some(name) = attribute.string(1)? {
+                attributes.insert(name, attribute);
+            }
+        }
+        Ok(Self { attributes })
+    }
+
+    /// Obtains an integer attribute.
+    fn int(&self, name: &str) -> Result<Option<i64>> {
+        match self.attributes.get(name) {
+            Some(attribute) => attribute.int(3),
+            None => Ok(None),
+        }
+    }
+
+    /// Obtains a float attribute.
+    fn float(&self, name: &str) -> Result<Option<f32>> {
+        match self.attributes.get(name) {
+            Some(attribute) => attribute.float(2),
+            None => Ok(None),
+        }
+    }
+
+    /// Obtains a string attribute.
+    fn string(&self, name: &str) -> Result<Option<&'a str>> {
+        match self.attributes.get(name) {
+            Some(attribute) => Ok(attribute
+                .bytes(4)?
+                .pop()
+                .map(|bytes| std::str::from_utf8(bytes).unwrap_or(""))),
+            None => Ok(None),
+        }
+    }
+
+    /// Obtains a list of integers attribute.
+    fn ints(&self, name: &str) -> Result<Option<Vec<i64>>> {
+        match self.attributes.get(name) {
+            Some(attribute) => attribute.ints(8).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Obtains a list of floats attribute.
+    fn floats(&self, name: &str) -> Result<Option<Vec<f32>>> {
+        match self.attributes.get(name) {
+            Some(attribute) => attribute.floats(7).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Obtains a tensor attribute.
+    fn tensor(&self, name: &str) -> Result<Option<Fields<'a>>> {
+        match self.attributes.get(name) {
+            Some(attribute) => Ok(attribute.messages(5)?.pop()),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Decoded contents of a `TensorProto`.
+enum TensorData {
+    Float(Vec<f32>),
+    Int(Vec<i64>),
+}
+
+/// Decodes a `TensorProto`.
+fn read_tensor(tensor: &Fields) -> Result<(Shape, TensorData)> {
+    if tensor.int(14)? == Some(DATA_LOCATION_EXTERNAL) {
+        return Err(Error::NotSupported(
+            "Tensors in external files are not supported.".to_string(),
+        ));
+    }
+    let dims = tensor
+        .ints(1)?
+        .into_iter()
+        .map(|d| usize::try_from(d).map_err(|_| Error::InvalidData(format!("Invalid dim: {}", d))))
+        .collect::<Result<Vec<_>>>()?;
+    if dims.len() > MAX_NUM_DIMENSIONS
+        || dims
+            .iter()
+            .try_fold(1usize, |acc, &d| acc.checked_mul(d))
+            .is_none()
+    {
+        return Err(Error::InvalidShape(format!(
+            "Unsupported shape: {:?}",
+            dims
+        )));
+    }
+    let shape = Shape::from_slice(&dims);
+    let raw = tensor.bytes(9)?.pop();
+
+    let data = match tensor.int(2)?.unwrap_or(0) {
+        DATA_TYPE_FLOAT => TensorData::Float(match raw {
+            Some(raw) if raw.len() % 4 == 0 => raw
+                .chunks_exact(4)
+                .map(|chunk| f32::from_le_bytes(chunk.try_into().unwrap()))
+                .collect(),
+            Some(_) => return Err(Error::InvalidData("Broken float tensor.".to_string())),
+            None => tensor.floats(4)?,
+        }),
+        DATA_TYPE_INT64 => TensorData::Int(match raw {
+            Some(raw) if raw.len() % 8 == 0 => raw
+                .chunks_exact(8)
+                .map(|chunk| i64::from_le_bytes(chunk.try_into().unwrap()))
+                .collect(),
+            Some(_) => return Err(Error::InvalidData("Broken int64 tensor.".to_string())),
+            None => tensor.ints(7)?,
+        }),
+        DATA_TYPE_INT32 | DATA_TYPE_BOOL => TensorData::Int(match raw {
+            Some(raw) if tensor.int(2)? == Some(DATA_TYPE_BOOL) => {
+                raw.iter().map(|&x| x as i64).collect()
+            }
+            Some(raw) if raw.len() % 4 == 0 => raw
+                .chunks_exact(4)
+                .map(|chunk| i32::from_le_bytes(chunk.try_into().unwrap()) as i64)
+                .collect(),
+            Some(_) => return Err(Error::InvalidData("Broken int32 tensor.".to_string())),
+            None => tensor.ints(5)?,
+        }),
+        data_type => {
+            return Err(Error::NotSupported(format!(
+                "Unsupported data type: {}",
+                data_type
+            )))
+        }
+    };
+
+    let len = match &data {
+        TensorData::Float(values) => values.len(),
+        TensorData::Int(values) => values.len(),
+    };
+    if len != shape.num_elements() {
+        return Err(Error::InvalidData(format!(
+            "Tensor {} has {} values.",
+            shape, len
+        )));
+    }
+    Ok((shape, data))
+}
+
+/// Normalizes a possibly negative axis.
+fn normalize_axis(axis: i64, rank: usize) -> Result<usize> {
+    let normalized = if axis < 0 { axis + rank as i64 } else { axis };
+    if normalized < 0 || normalized >= rank as i64 {
+        return Err(Error::OutOfRange(format!(
+            "Axis {} is out of range for rank {}.",
+            axis, rank
+        )));
+    }
+    Ok(normalized as usize)
+}
+
+/// Converts ONNX integers into a fixed number of sizes.
+fn to_sizes<const N: usize>(name: &str, values: &[i64]) -> Result<[usize; N]> {
+    let sizes = values
+        .iter()
+        .map(|&x| usize::try_from(x))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .ok()
+        .and_then(|sizes| <[usize; N]>::try_from(sizes).ok());
+    sizes.ok_or_else(|| Error::NotSupported(format!("Unsupported {}: {:?}", name, values)))
+}
+
+/// Converts symmetric ONNX pads `[begin..., end...]` into dycg paddings.
+fn to_padding(pads: Option<Vec<i64>>) -> Result<[usize; 2]> {
+    let Some(pads) = pads else {
+        return Ok([0, 0]);
+    };
+    let [top, left, bottom, right] = to_sizes("pads", &pads)?;
+    if [top, left] != [bottom, right] {
+        return Err(Error::NotSupported(format!(
+            "Asymmetric pads are not supported: {:?}",
+            pads
+        )));
+    }
+    Ok([top, left])
+}
+
+/// Calculates the shape of multidirectional broadcasting.
+fn broadcast_shape(a: &Shape, b: &Shape) -> Result<Shape> {
+    let (a, b) = (a.dimensions(), b.dimensions());
+    let rank = a.len().max(b.len());
+    let dim = |dims: &[usize], i: usize| {
+        (i + dims.len())
+            .checked_sub(rank)
+            .map_or(1, |index| dims[index])
+    };
+    let dims = (0..rank)
+        .map(|i| match (dim(a, i), dim(b, i)) {
+            (x, y) if x == y || y == 1 => Ok(x),
+            (1, y) => Ok(y),
+            _ => Err(Error::InvalidShape(format!(
+                "Shapes {:?} and {:?} can not be broadcasted.",
+                a, b
+            ))),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Shape::from_slice(&dims))
+}
+
+/// State of the import process.
+struct Importer<'hw: 'op, 'op: 'g, 'g> {
+    /// `Graph` object to register the operations.
+    graph: &'g RefCell<Graph<'hw, 'op>>,
+
+    /// `Hardware` object to host constants.
+    hardware: &'hw RefCell<dyn Hardware>,
+
+    /// Float values available in the model.
+    values: HashMap<String, Node<'hw, 'op, 'g>>,
+
+    /// Integer constants available in the model, which are used as operator arguments.
+    int_constants: HashMap<String, Vec<i64>>,
+
+    /// Float values cast to integers, which are used as indices of `Gather`.
+    indices: HashMap<String, Node<'hw, 'op, 'g>>,
+}
+
+impl<'hw: 'op, 'op: 'g, 'g> Importer<'hw, 'op, 'g> {
+    /// Obtains a float value.
+    fn get(&self, name: &str) -> Result<Node<'hw, 'op, 'g>> {
+        self.values
+            .get(name)
+            .copied()
+            .ok_or_else(|| Error::InvalidData(format!("Unknown value: {}", name)))
+    }
+
+    /// Obtains an integer constant.
+    fn get_ints(&self, name: &str) -> Result<&[i64]> {
+        self.int_constants
+            .get(name)
+            .map(|values| values.as_slice())
+            .ok_or_else(|| {
+                Error::NotSupported(format!("{} must be a constant integer tensor.", name))
+            })
+    }
+
+    /// Registers a new operation.
+    fn apply(
+        &self,
+        operator: impl Operator<'hw> + 'op,
+        inputs: &[Node<'hw, 'op, 'g>],
+    ) -> Result<Node<'hw, 'op, 'g>> {
+        Node::apply(self.graph, Box::new(operator), inputs)
+    }
+
+    /// Registers a float constant.
+    fn constant(&self, shape: Shape, values: &[f32]) -> Result<Node<'hw, 'op, 'g>> {
+        self.apply(
+            operator::constant::Constant::new(Array::constant_f32(self.hardware, shape, values)?),
+            &[],
+        )
+    }
+
+    /// Registers a value filled by a single value.
+    fn fill(&self, shape: Shape, value: f32) -> Result<Node<'hw, 'op, 'g>> {
+        self.apply(operator::fill::Fill::new(self.hardware, shape, value), &[])
+    }
+
+    /// Registers a `TensorProto` as a value.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(Node))` - The tensor is registered as a float constant.
+    /// * `Ok(None)` - The tensor is registered as an integer constant.
+    /// * `Err(Error)` - The tensor is broken or not supported.
+    fn add_tensor(&mut self, name: &str, tensor: &Fields) -> Result<Option<Node<'hw, 'op, 'g>>> {
+        match read_tensor(tensor)? {
+            (shape, TensorData::Float(values)) => {
+                let node = self.constant(shape, &values)?;
+                self.values.insert(name.to_string(), node);
+                Ok(Some(node))
+            }
+            (_, TensorData::Int(values)) => {
+                self.int_constants.insert(name.to_string(), values);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Broadcasts a value to the given shape.
+    fn broadcast_to(&self, node: Node<'hw, 'op, 'g>, shape: &Shape) -> Result<Node<'hw, 'op, 'g>> {
+        let source = node.shape();
+        let (dims, target) = (source.dimensions(), shape.dimensions());
+        if dims.len() > target.len() {
+            return Err(Error::InvalidShape(format!(
+                "{} can not be broadcasted to {}.",
+                source, shape
+            )));
+        }
+        let leading = target.len() - dims.len();
+        let mut node = node;
+        for (axis, (&d, &t)) in dims.iter().zip(&target[leading..]).enumerate() {
+            if d == t {
+                continue;
+            }
+            if d != 1 {
+                return Err(Error::InvalidShape(format!(
+                    "{} can not be broadcasted to {}.",
+                    source, shape
+                )));
+            }
+            // Replaces the axis of size 1 by the axis of size t.
+            node = self.apply(operator::sum_axis::SumAxis::new(axis), &[node])?;
+            node = self.apply(operator::expand_axis::ExpandAxis::new(axis, t), &[node])?;
+        }
+        for &t in target[..leading].iter().rev() {
+            node = self.apply(operator::expand_axis::ExpandAxis::new(0, t), &[node])?;
+        }
+        Ok(node)
+    }
+
+    /// Broadcasts all values to the common shape.
+    fn broadcast(&self, nodes: &[Node<'hw, 'op, 'g>]) -> Result<Vec<Node<'hw, 'op, 'g>>> {
+        let mut shape = nodes[0].shape();
+        for node in &nodes[1..] {
+            shape = broadcast_shape(&shape, &node.shape())?;
+        }
+        nodes
+            .iter()
+            .map(|&node| self.broadcast_to(node, &shape))
+            .collect()
+    }
+
+    /// Obtains the axes given by an attribute (older opsets) or an input (newer opsets).
+    fn axes(
+        &self,
+        attributes: &Attributes,
+        inputs: &[&str],
+        index: usize,
+        rank: usize,
+    ) -> Result<Option<Vec<usize>>> {
+        let axes = match attributes.ints("axes")? {
+            Some(axes) => axes,
+            None => match inputs.get(index) {
+                Some(name) if !name.is_empty() => self.get_ints(name)?.to_vec(),
+                _ => return Ok(None),
+            },
+        };
+        let mut axes = axes
+            .into_iter()
+            .map(|axis| normalize_axis(axis, rank))
+            .collect::<Result<Vec<_>>>()?;
+        axes.sort_unstable();
+        Ok(Some(axes))
+    }
+
+    /// Obtains a scalar float constant.
+    fn scalar(&self, name: &str) -> Result<Option<f32>> {
+        if name.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(self.get(name)?.calculate().get_scalar_f32()?))
+    }
+
+    /// Imports a single `NodeProto`.
+    fn import_node(&mut self, node: &Fields) -> Result<()> {
+        let op_type = node.string(4)?.unwrap_or("");
+        let domain = node.string(7)?.unwrap_or("");
+        if !is_default_domain(domain) {
+            return Err(Error::NotSupported(format!(
+                "Unsupported operator: {}.{}",
+                domain, op_type
+            )));
+        }
+        let inputs = node.strings(1)?;
+        let outputs = node.strings(2)?;
+        let attributes = Attributes::new(node)?;
+
+        // Optional outputs other than the first one are not supported.
+        let output = match outputs.as_slice() {
+            [output, rest @ ..] if rest.iter().all(|name| name.is_empty()) => *output,
+            _ => {
+                return Err(Error::NotSupported(format!(
+                    "{} with {} outputs is not supported.",
+                    op_type,
+                    outputs.len()
+                )))
+            }
+        };
+
+        if op_type == "Constant" {
+            return self.import_constant(output, &attributes);
+        }
+
+        let operands = inputs
+            .iter()
+            .map(|&name| {
+                if name.is_empty()
+                    || self.int_constants.contains_key(name)
+                    || self.indices.contains_key(name)
+                {
+                    Ok(None)
+                } else {
+                    self.get(name).map(Some)
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let arg = |index: usize| {
+            operands.get(index).copied().flatten().ok_or_else(|| {
+                Error::InvalidData(format!("{} requires the input {}.", op_type, index))
+            })
+        };
+
+        use operator::*;
+        let y = match op_type {
+            "Identity" => arg(0)?,
+            "Neg" => self.apply(neg::Neg::new(), &[arg(0)?])?,
+            "Relu" => self.apply(relu::Relu::new(), &[arg(0)?])?,
+            "Sigmoid" => self.apply(sigmoid::Sigmoid::new(), &[arg(0)?])?,
+            "Sqrt" => self.apply(sqrt::Sqrt::new(), &[arg(0)?])?,
+            "Tanh" => self.apply(tanh::Tanh::new(), &[arg(0)?])?,
+            "Not" => self.apply(logical_not::LogicalNot::new(), &[arg(0)?])?,
+            "IsNaN" => self.apply(isnan::IsNan::new(), &[arg(0)?])?,
+            "IsInf" => {
+                if attributes.int("detect_negative")? == Some(0)
+                    || attributes.int("detect_positive")? == Some(0)
+                {
+                    return Err(Error::NotSupported(
+                        "IsInf detecting only one sign is not supported.".to_string(),
+                    ));
+                }
+                self.apply(isinf::IsInf::new(), &[arg(0)?])?
+            }
+            "Add" | "And" | "Div" | "Equal" | "Greater" | "GreaterOrEqual" | "Less"
+            | "LessOrEqual" | "Max" | "Min" | "Mul" | "Or" | "Sub" => {
+                if operands.len() != 2 {
+                    return Err(Error::NotSupported(format!(
+                        "{} with {} inputs is not supported.",
+                        op_type,
+                        operands.len()
+                    )));
+                }
+                let ab = self.broadcast(&[arg(0)?, arg(1)?])?;
+                let (a, b) = (ab[0], ab[1]);
+                match op_type {
+                    "Add" => self.apply(add::Add::new(), &[a, b])?,
+                    "And" => self.apply(logical_and::LogicalAnd::new(), &[a, b])?,
+                    "Div" => self.apply(div::Div::new(), &[a, b])?,
+                    "Equal" => self.apply(equal::Equal::new(), &[a, b])?,
+                    "Greater" => self.apply(less::Less::new(), &[b, a])?,
+                    "GreaterOrEqual" => self.apply(less_equal::LessEqual::new(), &[b, a])?,
+                    "Less" => self.apply(less::Less::new(), &[a, b])?,
+                    "LessOrEqual" => self.apply(less_equal::LessEqual::new(), &[a, b])?,
+                    "Max" => self.apply(maximum::Maximum::new(), &[a, b])?,
+                    "Min" => self.apply(minimum::Minimum::new(), &[a, b])?,
+                    "Mul" => self.apply(mul::Mul::new(), &[a, b])?,
+                    "Or" => self.apply(logical_or::LogicalOr::new(), &[a, b])?,
+                    _ => self.apply(sub::Sub::new(), &[a, b])?,
+                }
+            }
+            "Where" => {
+                let args = self.broadcast(&[arg(0)?, arg(1)?, arg(2)?])?;
+                self.apply(select::Select::new(), &args)?
+            }
+            "Cast" => match attributes.int("to")? {
+                Some(DATA_TYPE_FLOAT) => arg(0)?,
+                Some(DATA_TYPE_INT32 | DATA_TYPE_INT64) => {
+                    // Values stay in float, since they are only used as indices of Gather.
+                    self.indices.insert(output.to_string(), arg(0)?);
+                    return Ok(());
+                }
+                Some(DATA_TYPE_BOOL) => {
+                    // Nonzero values are mapped to 1.
+                    let not = self.apply(logical_not::LogicalNot::new(), &[arg(0)?])?;
+                    self.apply(logical_not::LogicalNot::new(), &[not])?
+                }
+                to => {
+                    return Err(Error::NotSupported(format!(
+                        "Cast to {:?} is not supported.",
+                        to
+                    )))
+                }
+            },
+            "Clip" => {
+                let min = match attributes.float("min")? {
+                    Some(min) => Some(min),
+                    None => self.scalar(inputs.get(1).copied().unwrap_or(""))?,
+                };
+                let max = match attributes.float("max")? {
+                    Some(max) => Some(max),
+                    None => self.scalar(inputs.get(2).copied().unwrap_or(""))?,
+                };
+                self.apply(
+                    clamp::Clamp::new(
+                        min.unwrap_or(f32::NEG_INFINITY),
+                        max.unwrap_or(f32::INFINITY),
+                    ),
+                    &[arg(0)?],
+                )?
+            }
+            "MatMul" => self.apply(matmul::MatMul::new(), &[arg(0)?, arg(1)?])?,
+            "Gather" => {
+                let x = arg(0)?;
+                let rank = x.shape().num_dimensions();
+                if normalize_axis(attributes.int("axis")?.unwrap_or(0), rank)? != 0 {
+                    return Err(Error::NotSupported(
+                        "Gather is supported only for the first axis.".to_string(),
+                    ));
+                }
+                let name = inputs.get(1).copied().unwrap_or("");
+                let indices = self.indices.get(name).copied().ok_or_else(|| {
+                    Error::NotSupported(format!(
+                        "Indices {} of Gather must be cast from a float value.",
+                        name
+                    ))
+                })?;
+                self.apply(gather_rows::GatherRows::new(), &[x, indices])?
+            }
+            "Transpose" => {
+                let x = arg(0)?;
+                match attributes.ints("perm")? {
+                    None => {}
+                    Some(perm) if perm == [1, 0] => {}
+                    Some(perm) => {
+                        return Err(Error::NotSupported(format!(
+                            "Transpose with perm {:?} is not supported.",
+                            perm
+                        )))
+                    }
+                }
+                self.apply(transpose::Transpose::new(), &[x])?
+            }
+            "Gemm" => self.import_gemm(
+                &attributes,
+                arg(0)?,
+                arg(1)?,
+                operands.get(2).copied().flatten(),
+            )?,
+            "ReduceSum" => {
+                let x = arg(0)?;
+                let rank = x.shape().num_dimensions();
+                let axes = match self.axes(&attributes, &inputs, 1, rank)? {
+                    Some(axes) if !axes.is_empty() => axes,
+                    _ if attributes.int("noop_with_empty_axes")? == Some(1) => vec![],
+                    _ => (0..rank).collect(),
+                };
+                let keepdims = attributes.int("keepdims")?.unwrap_or(1) != 0;
+                let mut y = x;
+                for &axis in axes.iter().rev() {
+                    y = self.apply(sum_axis::SumAxis::new(axis), &[y])?;
+                    if keepdims {
+                        y = self.apply(expand_axis::ExpandAxis::new(axis, 1), &[y])?;
+                    }
+                }
+                y
+            }
+            "Unsqueeze" => {
+                let x = arg(0)?;
+                let rank = x.shape().num_dimensions();
+                let num_axes = match attributes.ints("axes")? {
+                    Some(axes) => axes.len(),
+                    None => self.get_ints(inputs.get(1).copied().unwrap_or(""))?.len(),
+                };
+                let axes = self
+                    .axes(&attributes, &inputs, 1, rank + num_axes)?
+                    .unwrap_or_default();
+                let mut y = x;
+                for axis in axes {
+                    y = self.apply(expand_axis::ExpandAxis::new(axis, 1), &[y])?;
+                }
+                y
+            }
+            "Expand" => {
+                let x = arg(0)?;
+                let dims = self.get_ints(inputs.get(1).copied().unwrap_or(""))?;
+                let dims = dims
+                    .iter()
+                    .map(|&d| usize::try_from(d))
+                    .collect::<std::result::Result<Vec<_>, _>>()
+                    .map_err(|_| Error::InvalidShape(format!("Invalid shape: {:?}", dims)))?;
+                if dims.len() > MAX_NUM_DIMENSIONS {
+                    return Err(Error::InvalidShape(format!("Invalid shape: {:?}", dims)));
+                }
+                let shape = broadcast_shape(&x.shape(), &Shape::from_slice(&dims))?;
+                self.broadcast_to(x, &shape)?
+            }
+            "Conv" => self.import_conv(
+                &attributes,
+                arg(0)?,
+                arg(1)?,
+                operands.get(2).copied().flatten(),
+            )?,
+            "AveragePool" | "MaxPool" => {
+                let x = arg(0)?;
+                let kernel_size = to_sizes(
+                    "kernel_shape",
+                    &attributes.ints("kernel_shape")?.unwrap_or_default(),
+                )?;
+                let stride = match attributes.ints("strides")? {
+                    Some(strides) => to_sizes("strides", &strides)?,
+                    None => [1, 1],
+                };
+                let padding = to_padding(attributes.ints("pads")?)?;
+                self.check_auto_pad(&attributes)?;
+                if attributes.int("ceil_mode")?.unwrap_or(0) != 0 {
+                    return Err(Error::NotSupported(
+                        "ceil_mode is not supported.".to_string(),
+                    ));
+                }
+                if let Some(dilations) = attributes.ints("dilations")? {
+                    if dilations.iter().any(|&d| d != 1) {
+                        return Err(Error::NotSupported(
+                            "Dilated pooling is not supported.".to_string(),
+                        ));
+                    }
+                }
+                let options = Pool2dOptions {
+                    kernel_size,
+                    stride,
+                    padding,
+                };
+                if op_type == "MaxPool" {
+                    self.apply(max_pool2d::MaxPool2d::new(options), &[x])?
+                } else {
+                    // dycg treats padded values as zeros.
+                    if padding != [0, 0] && attributes.int("count_include_pad")?.unwrap_or(0) == 0 {
+                        return Err(Error::NotSupported(
+                            "AveragePool excluding pads is not supported.".to_string(),
+                        ));
+                    }
+                    self.apply(avg_pool2d::AvgPool2d::new(options), &[x])?
+                }
+            }
+            "LayerNormalization" => {
+                let x = arg(0)?;
+                let rank = x.shape().num_dimensions();
+                if normalize_axis(attributes.int("axis")?.unwrap_or(-1), rank)? != rank - 1 {
+                    return Err(Error::NotSupported(
+                        "LayerNormalization is supported only for the last axis.".to_string(),
+                    ));
+                }
+                let gamma = arg(1)?;
+                let beta = match operands.get(2).copied().flatten() {
+                    Some(beta) => beta,
+                    None => self.fill(gamma.shape(), 0.)?,
+                };
+                self.apply(
+                    layer_norm::LayerNorm::new(attributes.float("epsilon")?.unwrap_or(1e-5)),
+                    &[x, gamma, beta],
+                )?
+            }
+            "BatchNormalization" => {
+                if attributes.int("training_mode")?.unwrap_or(0) != 0 {
+                    return Err(Error::NotSupported(
+                        "BatchNormalization in training mode is not supported.".to_string(),
+                    ));
+                }
+                self.apply(
+                    batch_norm_inference::BatchNormInference::new(
+                        attributes.float("epsilon")?.unwrap_or(1e-5),
+                    ),
+                    &[arg(0)?, arg(3)?, arg(4)?, arg(1)?, arg(2)?],
+                )?
+            }
+            _ => {
+                return Err(Error::NotSupported(format!(
+                    "Unsupported operator: {}",
+                    op_type
+                )))
+            }
+        };
+
+        self.values.insert(output.to_string(), y);
+        Ok(())
+    }
+
+    /// Imports a `Constant` node.
+    fn import_constant(&mut self, output: &str, attributes: &Attributes) -> Result<()> {
+        if let Some(tensor) = attributes.tensor("value")? {
+            self.add_tensor(output, &tensor)?;
+        } else if let Some(value) = attributes.float("value_float")? {
+            let node = self.constant(Shape::new([]), &[value])?;
+            self.values.insert(output.to_string(), node);
+        } else if let Some(values) = attributes.floats("value_floats")? {
+            let node = self.constant(Shape::new([values.len()]), &values)?;
+            self.values.insert(output.to_string(), node);
+        } else if let Some(value) = attributes.int("value_int")? {
+            self.int_constants.insert(output.to_string(), vec![value]);
+        } else if let Some(values) = attributes.ints("value_ints")? {
+            self.int_constants.insert(output.to_string(), values);
+        } else {
+            return Err(Error::NotSupported(
+                "Unsupported value of Constant.".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Imports a `Gemm` node: alpha * A' * B' + beta * C.
+    fn import_gemm(
+        &self,
+        attributes: &Attributes,
+        a: Node<'hw, 'op, 'g>,
+        b: Node<'hw, 'op, 'g>,
+        c: Option<Node<'hw, 'op, 'g>>,
+    ) -> Result<Node<'hw, 'op, 'g>> {
+        use operator::*;
+        let a = match attributes.int("transA")?.unwrap_or(0) {
+            0 => a,
+            _ => self.apply(transpose::Transpose::new(), &[a])?,
+        };
+        let b = match attributes.int("transB")?.unwrap_or(0) {
+            0 => b,
+            _ => self.apply(transpose::Transpose::new(), &[b])?,
+        };
+        let mut y = self.apply(matmul::MatMul::new(), &[a, b])?;
+        let alpha = attributes.float("alpha")?.unwrap_or(1.);
+        if alpha != 1. {
+            let alpha = self.fill(y.shape(), alpha)?;
+            y = self.apply(mul::Mul::new(), &[y, alpha])?;
+        }
+        if let Some(c) = c {
+            let mut c = self.broadcast_to(c, &y.shape())?;
+            let beta = attributes.float("beta")?.unwrap_or(1.);
+            if beta != 1. {
+                let beta = self.fill(c.shape(), beta)?;
+                c = self.apply(mul::Mul::new(), &[c, beta])?;
+            }
+            y = self.apply(add::Add::new(), &[y, c])?;
+        }
+        Ok(y)
+    }
+
+    /// Imports a `Conv` node.
+    fn import_conv(
+        &self,
+        attributes: &Attributes,
+        x: Node<'hw, 'op, 'g>,
+        w: Node<'hw, 'op, 'g>,
+        bias: Option<Node<'hw, 'op, 'g>>,
+    ) -> Result<Node<'hw, 'op, 'g>> {
+        self.check_auto_pad(attributes)?;
+        let options = Conv2dOptions {
+            stride: match attributes.ints("strides")? {
+                Some(strides) => to_sizes("strides", &strides)?,
+                None => [1, 1],
+            },
+            padding: to_padding(attributes.ints("pads")?)?,
+            dilation: match attributes.ints("dilations")? {
+                Some(dilations) => to_sizes("dilations", &dilations)?,
+                None => [1, 1],
+            },
+            groups: to_sizes::<1>("group", &[attributes.int("group")?.unwrap_or(1)])?[0],
+        };
+        let y = self.apply(operator::conv2d::Conv2d::new(options), &[x, w])?;
+        let Some(bias) = bias else {
+            return Ok(y);
+        };
+
+        // Broadcasts the bias [C] to [N, C, H, W].
+        let shape = y.shape();
+        let &[n, _, h, w] = shape.dimensions() else {
+            return Err(Error::InvalidShape(format!(
+                "Conv requires 4D inputs, but got {}.",
+                shape
+            )));
+        };
+        let mut bias = bias;
+        for (axis, size) in [(1, h), (2, w), (0, n)] {
+            bias = self.apply(operator::expand_axis::ExpandAxis::new(axis, size), &[bias])?;
+        }
+        self.apply(operator::add::Add::new(), &[y, bias])
+    }
+
+    /// Checks that `auto_pad` does not require implicit paddings.
+    fn check_auto_pad(&self, attributes: &Attributes) -> Result<()> {
+        match attributes.string("auto_pad")? {
+            None | Some("NOTSET") | Some("VALID") => Ok(()),
+            Some(auto_pad) => Err(Error::NotSupported(format!(
+                "auto_pad {} is not supported.",
+                auto_pad
+            ))),
+        }
+    }
+}
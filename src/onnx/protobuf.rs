@@ -0,0 +1,370 @@
+use crate::error::Error;
+use crate::result::Result;
+
+/// Wire type of varint fields: int32, int64, uint64, bool and enum.
+const WIRE_VARINT: u64 = 0;
+
+/// Wire type of length-delimited fields: string, bytes, embedded messages and packed fields.
+const WIRE_LEN: u64 = 2;
+
+/// Wire type of 64-bit fixed-size fields: double.
+const WIRE_FIXED64: u64 = 1;
+
+/// Wire type of 32-bit fixed-size fields: float.
+const WIRE_FIXED32: u64 = 5;
+
+/// Serialized Protocol Buffers message.
+///
+/// This is a minimal encoder of the wire format, which is sufficient to write ONNX models.
+#[derive(Default)]
+pub(crate) struct Message {
+    /// Encoded bytes.
+    bytes: Vec<u8>,
+}
+
+impl Message {
+    /// Creates a new empty `Message` object.
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the encoded bytes.
+    pub(crate) fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Appends a variable-length integer.
+    fn write_varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.bytes.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        self.bytes.push(value as u8);
+    }
+
+    /// Appends a field key.
+    fn write_key(&mut self, field: u32, wire_type: u64) {
+        self.write_varint(((field as u64) << 3) | wire_type);
+    }
+
+    /// Appends an integer field. Negative values are encoded as 10-byte varints.
+    pub(crate) fn int(&mut self, field: u32, value: i64) -> &mut Self {
+        self.write_key(field, WIRE_VARINT);
+        self.write_varint(value as u64);
+        self
+    }
+
+    /// Appends a float field.
+    pub(crate) fn float(&mut self, field: u32, value: f32) -> &mut Self {
+        self.write_key(field, WIRE_FIXED32);
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    /// Appends a bytes field.
+    pub(crate) fn bytes(&mut self, field: u32, value: &[u8]) -> &mut Self {
+        self.write_key(field, WIRE_LEN);
+        self.write_varint(value.len() as u64);
+        self.bytes.extend_from_slice(value);
+        self
+    }
+
+    /// Appends a string field.
+    pub(crate) fn string(&mut self, field: u32, value: &str) -> &mut Self {
+        self.bytes(field, value.as_bytes())
+    }
+
+    /// Appends an embedded message field.
+    pub(crate) fn message(&mut self, field: u32, value: &Message) -> &mut Self {
+        self.bytes(field, &value.bytes)
+    }
+
+    /// Appends a packed repeated integer field.
+    pub(crate) fn packed_ints(&mut self, field: u32, values: &[i64]) -> &mut Self {
+        let mut packed = Message::new();
+        for &value in values {
+            packed.write_varint(value as u64);
+        }
+        self.bytes(field, &packed.bytes)
+    }
+}
+
+/// Reads a variable-length integer.
+fn read_varint(bytes: &[u8], position: &mut usize) -> Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes
+            .get(*position)
+            .ok_or_else(|| Error::InvalidData("Unexpected end of a varint.".to_string()))?;
+        *position += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte < 0x80 {
+            return Ok(value);
+        }
+    }
+    Err(Error::InvalidData("Too long varint.".to_string()))
+}
+
+/// Reads a fixed number of bytes.
+fn read_bytes<'a>(bytes: &'a [u8], position: &mut usize, size: usize) -> Result<&'a [u8]> {
+    if size > bytes.len() - *position {
+        return Err(Error::InvalidData(format!(
+            "Unexpected end of data: required {} bytes, but only {} bytes remain.",
+            size,
+            bytes.len() - *position
+        )));
+    }
+    *position += size;
+    Ok(&bytes[*position - size..*position])
+}
+
+/// Field value in the wire format.
+#[derive(Clone, Copy, Debug)]
+enum Field<'a> {
+    Varint(u64),
+    Fixed32(u32),
+    Fixed64,
+    Bytes(&'a [u8]),
+}
+
+/// Parsed Protocol Buffers message.
+///
+/// This is a minimal decoder of the wire format, which does not require the schema. Fields are
+/// interpreted when they are accessed.
+pub(crate) struct Fields<'a> {
+    /// Pairs of field numbers and values, in the order of appearance.
+    fields: Vec<(u32, Field<'a>)>,
+}
+
+impl<'a> Fields<'a> {
+    /// Parses all fields of a message.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - Encoded message.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Fields)` - Parsed fields.
+    /// * `Err(Error)` - `bytes` is not a valid message.
+    pub(crate) fn parse(bytes: &'a [u8]) -> Result<Self> {
+        let mut fields = vec![];
+        let mut position = 0;
+        while position < bytes.len() {
+            let key = read_varint(bytes, &mut position)?;
+            let field = u32::try_from(key >> 3)
+                .map_err(|_| Error::InvalidData(format!("Invalid field number: {}", key >> 3)))?;
+            let value = match key & 7 {
+                WIRE_VARINT => Field::Varint(read_varint(bytes, &mut position)?),
+                WIRE_FIXED64 => {
+                    read_bytes(bytes, &mut position, 8)?;
+                    Field::Fixed64
+                }
+                WIRE_LEN => {
+                    let size = usize::try_from(read_varint(bytes, &mut position)?)
+                        .map_err(|_| Error::InvalidData("Too long field.".to_string()))?;
+                    Field::Bytes(read_bytes(bytes, &mut position, size)?)
+                }
+                WIRE_FIXED32 => Field::Fixed32(u32::from_le_bytes(
+                    read_bytes(bytes, &mut position, 4)?.try_into().unwrap(),
+                )),
+                wire_type => {
+                    return Err(Error::InvalidData(format!(
+                        "Unsupported wire type: {}",
+                        wire_type
+                    )))
+                }
+            };
+            fields.push((field, value));
+        }
+        Ok(Self { fields })
+    }
+
+    /// Returns all values of the specified field.
+    fn values(&self, field: u32) -> impl Iterator<Item = Field<'a>> + '_ {
+        self.fields
+            .iter()
+            .filter(move |(f, _)| *f == field)
+            .map(|(_, v)| *v)
+    }
+
+    /// Creates an error for a field with an unexpected wire type.
+    fn wire_type_error(field: u32) -> Error {
+        Error::InvalidData(format!("Unexpected wire type of field {}.", field))
+    }
+
+    /// Obtains an integer field. If the field appears multiple times, the last one is used.
+    pub(crate) fn int(&self, field: u32) -> Result<Option<i64>> {
+        match self.values(field).last() {
+            Some(Field::Varint(value)) => Ok(Some(value as i64)),
+            Some(_) => Err(Self::wire_type_error(field)),
+            None => Ok(None),
+        }
+    }
+
+    /// Obtains a float field. If the field appears multiple times, the last one is used.
+    pub(crate) fn float(&self, field: u32) -> Result<Option<f32>> {
+        match self.values(field).last() {
+            Some(Field::Fixed32(value)) => Ok(Some(f32::from_bits(value))),
+            Some(_) => Err(Self::wire_type_error(field)),
+            None => Ok(None),
+        }
+    }
+
+    /// Obtains all values of a bytes field.
+    pub(crate) fn bytes(&self, field: u32) -> Result<Vec<&'a [u8]>> {
+        self.values(field)
+            .map(|value| match value {
+                Field::Bytes(bytes) => Ok(bytes),
+                _ => Err(Self::wire_type_error(field)),
+            })
+            .collect()
+    }
+
+    /// Obtains all values of a string field.
+    pub(crate) fn strings(&self, field: u32) -> Result<Vec<&'a str>> {
+        self.bytes(field)?
+            .into_iter()
+            .map(|bytes| {
+                std::str::from_utf8(bytes)
+                    .map_err(|e| Error::InvalidData(format!("Invalid string: {}", e)))
+            })
+            .collect()
+    }
+
+    /// Obtains a string field. If the field appears multiple times, the last one is used.
+    pub(crate) fn string(&self, field: u32) -> Result<Option<&'a str>> {
+        Ok(self.strings(field)?.pop())
+    }
+
+    /// Obtains all values of an embedded message field.
+    pub(crate) fn messages(&self, field: u32) -> Result<Vec<Fields<'a>>> {
+        self.bytes(field)?.into_iter().map(Fields::parse).collect()
+    }
+
+    /// Obtains all values of a repeated integer field, which may or may not be packed.
+    pub(crate) fn ints(&self, field: u32) -> Result<Vec<i64>> {
+        let mut values = vec![];
+        for value in self.values(field) {
+            match value {
+                Field::Varint(value) => values.push(value as i64),
+                Field::Bytes(bytes) => {
+                    let mut position = 0;
+                    while position < bytes.len() {
+                        values.push(read_varint(bytes, &mut position)? as i64);
+                    }
+                }
+                _ => return Err(Self::wire_type_error(field)),
+            }
+        }
+        Ok(values)
+    }
+
+    /// Obtains all values of a repeated float field, which may or may not be packed.
+    pub(crate) fn floats(&self, field: u32) -> Result<Vec<f32>> {
+        let mut values = vec![];
+        for value in self.values(field) {
+            match value {
+                Field::Fixed32(value) => values.push(f32::from_bits(value)),
+                Field::Bytes(bytes) if bytes.len() % 4 == 0 => values.extend(
+                    bytes
+                        .chunks_exact(4)
+                        .map(|chunk| f32::from_le_bytes(chunk.try_into().unwrap())),
+                ),
+                _ => return Err(Self::wire_type_error(field)),
+            }
+        }
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::onnx::protobuf::*;
+
+    #[test]
+    fn test_varint() {
+        let mut m = Message::new();
+        m.int(1, 0).int(1, 1).int(1, 300).int(2, -1);
+        assert_eq!(
+            m.into_bytes(),
+            vec![
+                0x08, 0x00, 0x08, 0x01, 0x08, 0xac, 0x02, 0x10, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+                0xff, 0xff, 0xff, 0x01
+            ]
+        );
+    }
+
+    #[test]
+    fn test_length_delimited() {
+        let mut inner = Message::new();
+        inner.int(1, 150);
+        let mut m = Message::new();
+        m.string(2, "testing")
+            .message(3, &inner)
+            .packed_ints(4, &[3, 270]);
+        assert_eq!(
+            m.into_bytes(),
+            vec![
+                0x12, 0x07, b't', b'e', b's', b't', b'i', b'n', b'g', 0x1a, 0x03, 0x08, 0x96, 0x01,
+                0x22, 0x03, 0x03, 0x8e, 0x02
+            ]
+        );
+    }
+
+    #[test]
+    fn test_float() {
+        let mut m = Message::new();
+        m.float(5, 1.5);
+        assert_eq!(m.into_bytes(), vec![0x2d, 0x00, 0x00, 0xc0, 0x3f]);
+    }
+
+    #[test]
+    fn test_parse() {
+        let mut inner = Message::new();
+        inner.int(1, 150);
+        let mut m = Message::new();
+        m.int(1, -1)
+            .int(1, 300)
+            .float(2, 1.5)
+            .string(3, "foo")
+            .string(3, "bar")
+            .message(4, &inner)
+            .packed_ints(5, &[3, 270])
+            .int(5, 4)
+            .bytes(6, &[0, 0, 0x80, 0x3f, 0, 0, 0, 0x40]);
+        let bytes = m.into_bytes();
+
+        let fields = Fields::parse(&bytes).unwrap();
+        assert_eq!(fields.int(1), Ok(Some(300)));
+        assert_eq!(fields.int(7), Ok(None));
+        assert_eq!(fields.float(2), Ok(Some(1.5)));
+        assert_eq!(fields.strings(3), Ok(vec!["foo", "bar"]));
+        assert_eq!(fields.string(3), Ok(Some("bar")));
+        assert_eq!(fields.messages(4).unwrap()[0].int(1), Ok(Some(150)));
+        assert_eq!(fields.ints(5), Ok(vec![3, 270, 4]));
+        assert_eq!(fields.floats(6), Ok(vec![1., 2.]));
+
+        // Wire types are checked when the fields are accessed.
+        assert!(fields.int(3).is_err());
+        assert!(fields.float(1).is_err());
+        assert!(fields.strings(1).is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        // Truncated varint.
+        assert!(Fields::parse(&[0x08, 0x80]).is_err());
+        // Too long varint.
+        assert!(Fields::parse(&[
+            0x08, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01
+        ])
+        .is_err());
+        // Truncated bytes.
+        assert!(Fields::parse(&[0x12, 0x03, 0x00]).is_err());
+        // Truncated fixed32.
+        assert!(Fields::parse(&[0x15, 0x00]).is_err());
+        // Unsupported wire type (groups).
+        assert!(Fields::parse(&[0x0b]).is_err());
+    }
+}
@@ -0,0 +1,1050 @@
+use crate::array::Array;
+use crate::conv::{Conv2dOptions, Pool2dOptions};
+use crate::graph::Graph;
+use crate::hardware::cpu::CpuHardware;
+use crate::node::IntoNode;
+use crate::onnx::*;
+use std::cell::RefCell;
+
+// Small reference reader of ONNX models, independent of the exporter.
+
+/// Field value in the Protocol Buffers wire format.
+#[derive(Clone, Debug)]
+enum Value {
+    Varint(u64),
+    Fixed32(u32),
+    Bytes(Vec<u8>),
+}
+
+/// Parses a varint.
+fn read_varint(bytes: &[u8], pos: &mut usize) -> u64 {
+    let mut value = 0u64;
+    let mut shift = 0;
+    loop {
+        let b = bytes[*pos];
+        *pos += 1;
+        value |= ((b & 0x7f) as u64) << shift;
+        if b < 0x80 {
+            return value;
+        }
+        shift += 7;
+    }
+}
+
+/// Parses all fields of a message.
+fn parse(bytes: &[u8]) -> Vec<(u32, Value)> {
+    let mut fields = vec![];
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let key = read_varint(bytes, &mut pos);
+        let field = (key >> 3) as u32;
+        let value = match key & 7 {
+            0 => Value::Varint(read_varint(bytes, &mut pos)),
+            2 => {
+                let len = read_varint(bytes, &mut pos) as usize;
+                pos += len;
+                Value::Bytes(bytes[pos - len..pos].to_vec())
+            }
+            5 => {
+                pos += 4;
+                Value::Fixed32(u32::from_le_bytes(bytes[pos - 4..pos].try_into().unwrap()))
+            }
+            t => panic!("Unsupported wire type: {}", t),
+        };
+        fields.push((field, value));
+    }
+    assert_eq!(pos, bytes.len());
+    fields
+}
+
+fn get_all(fields: &[(u32, Value)], field: u32) -> Vec<Value> {
+    fields
+        .iter()
+        .filter(|(f, _)| *f == field)
+        .map(|(_, v)| v.clone())
+        .collect()
+}
+
+fn get_int(fields: &[(u32, Value)], field: u32) -> Option<i64> {
+    get_all(fields, field).last().map(|v| match v {
+        Value::Varint(x) => *x as i64,
+        v => panic!("Not a varint: {:?}", v),
+    })
+}
+
+fn get_bytes(fields: &[(u32, Value)], field: u32) -> Vec<Vec<u8>> {
+    get_all(fields, field)
+        .into_iter()
+        .map(|v| match v {
+            Value::Bytes(x) => x,
+            v => panic!("Not bytes: {:?}", v),
+        })
+        .collect()
+}
+
+fn get_strings(fields: &[(u32, Value)], field: u32) -> Vec<String> {
+    get_bytes(fields, field)
+        .into_iter()
+        .map(|x| String::from_utf8(x).unwrap())
+        .collect()
+}
+
+fn get_messages(fields: &[(u32, Value)], field: u32) -> Vec<Vec<(u32, Value)>> {
+    get_bytes(fields, field).iter().map(|x| parse(x)).collect()
+}
+
+fn get_packed_ints(fields: &[(u32, Value)], field: u32) -> Vec<i64> {
+    let mut values = vec![];
+    for bytes in get_bytes(fields, field) {
+        let mut pos = 0;
+        while pos < bytes.len() {
+            values.push(read_varint(&bytes, &mut pos) as i64);
+        }
+    }
+    values
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Attribute {
+    Float(f32),
+    Int(i64),
+    Ints(Vec<i64>),
+}
+
+#[derive(Debug)]
+struct NodeDef {
+    op_type: String,
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+    attributes: HashMap<String, Attribute>,
+}
+
+#[derive(Clone, Debug)]
+struct Tensor {
+    dims: Vec<usize>,
+    values: Vec<f32>,
+}
+
+#[derive(Debug)]
+struct Model {
+    ir_version: i64,
+    opset_version: i64,
+    nodes: Vec<NodeDef>,
+    initializers: HashMap<String, (i64, Tensor)>,
+    inputs: Vec<(String, Vec<usize>)>,
+    outputs: Vec<(String, Vec<usize>)>,
+}
+
+fn read_value_info(fields: &[(u32, Value)]) -> (String, Vec<usize>) {
+    let value_type = &get_messages(fields, 2)[0];
+    let tensor_type = &get_messages(value_type, 1)[0];
+    assert_eq!(get_int(tensor_type, 1), Some(1));
+    let shape = &get_messages(tensor_type, 2)[0];
+    let dims = get_messages(shape, 1)
+        .iter()
+        .map(|d| get_int(d, 1).unwrap() as usize)
+        .collect();
+    (get_strings(fields, 1)[0].clone(), dims)
+}
+
+fn read_model(bytes: &[u8]) -> Model {
+    let model = parse(bytes);
+    let graph = &get_messages(&model, 7)[0];
+    let opset = &get_messages(&model, 8)[0];
+
+    let nodes = get_messages(graph, 1)
+        .iter()
+        .map(|node| NodeDef {
+            op_type: get_strings(node, 4)[0].clone(),
+            inputs: get_strings(node, 1),
+            outputs: get_strings(node, 2),
+            attributes: get_messages(node, 5)
+                .iter()
+                .map(|a| {
+                    let value = match get_int(a, 20).unwrap() {
+                        1 => match get_all(a, 2)[0] {
+                            Value::Fixed32(x) => Attribute::Float(f32::from_bits(x)),
+                            ref v => panic!("Not a float: {:?}", v),
+                        },
+                        2 => Attribute::Int(get_int(a, 3).unwrap()),
+                        7 => Attribute::Ints(get_packed_ints(a, 8)),
+                        t => panic!("Unsupported attribute type: {}", t),
+                    };
+                    (get_strings(a, 1)[0].clone(), value)
+                })
+                .collect(),
+        })
+        .collect();
+
+    let initializers = get_messages(graph, 5)
+        .iter()
+        .map(|t| {
+            let data_type = get_int(t, 2).unwrap();
+            let raw = &get_bytes(t, 9)[0];
+            let values = match data_type {
+                1 => raw
+                    .chunks_exact(4)
+                    .map(|c| f32::from_le_bytes(c.try_into().unwrap()))
+                    .collect(),
+                7 => raw
+                    .chunks_exact(8)
+                    .map(|c| i64::from_le_bytes(c.try_into().unwrap()) as f32)
+                    .collect(),
+                t => panic!("Unsupported data type: {}", t),
+            };
+            let dims = get_packed_ints(t, 1)
+                .into_iter()
+                .map(|d| d as usize)
+                .collect();
+            (
+                get_strings(t, 8)[0].clone(),
+                (data_type, Tensor { dims, values }),
+            )
+        })
+        .collect();
+
+    Model {
+        ir_version: get_int(&model, 1).unwrap(),
+        opset_version: get_int(opset, 2).unwrap(),
+        nodes,
+        initializers,
+        inputs: get_messages(graph, 11)
+            .iter()
+            .map(|v| read_value_info(v))
+            .collect(),
+        outputs: get_messages(graph, 12)
+            .iter()
+            .map(|v| read_value_info(v))
+            .collect(),
+    }
+}
+
+/// Evaluates a model on the host. Only a subset of operators are supported.
+fn evaluate(model: &Model, inputs: &[Tensor]) -> Vec<Tensor> {
+    let mut values: HashMap<String, Tensor> = model
+        .initializers
+        .iter()
+        .map(|(name, (_, t))| (name.clone(), t.clone()))
+        .collect();
+    for ((name, dims), input) in model.inputs.iter().zip(inputs) {
+        assert_eq!(*dims, input.dims);
+        values.insert(name.clone(), input.clone());
+    }
+
+    let unary = |x: &Tensor, f: &dyn Fn(f32) -> f32| Tensor {
+        dims: x.dims.clone(),
+        values: x.values.iter().map(|&v| f(v)).collect(),
+    };
+    // Multidirectional broadcasting.
+    let binary = |a: &Tensor, b: &Tensor, f: &dyn Fn(f32, f32) -> f32| {
+        let rank = a.dims.len().max(b.dims.len());
+        let pad = |dims: &[usize]| [vec![1; rank - dims.len()], dims.to_vec()].concat();
+        let (da, db) = (pad(&a.dims), pad(&b.dims));
+        let dims = da
+            .iter()
+            .zip(&db)
+            .map(|(&x, &y)| x.max(y))
+            .collect::<Vec<_>>();
+        let offset = |d: &[usize], mut i: usize| {
+            let (mut offset, mut stride) = (0, 1);
+            for k in (0..rank).rev() {
+                if d[k] != 1 {
+                    offset += i % dims[k] * stride;
+                }
+                i /= dims[k];
+                stride *= d[k];
+            }
+            offset
+        };
+        Tensor {
+            values: (0..dims.iter().product())
+                .map(|i| f(a.values[offset(&da, i)], b.values[offset(&db, i)]))
+                .collect(),
+            dims,
+        }
+    };
+
+    for node in &model.nodes {
+        let x = node
+            .inputs
+            .iter()
+            .map(|name| values[name].clone())
+            .collect::<Vec<_>>();
+        let y = match node.op_type.as_str() {
+            "Identity" => x[0].clone(),
+            "Neg" => unary(&x[0], &|v| -v),
+            "Relu" => unary(&x[0], &|v| v.max(0.)),
+            "Tanh" => unary(&x[0], &|v| v.tanh()),
+            "Sqrt" => unary(&x[0], &|v| v.sqrt()),
+            "Sigmoid" => unary(&x[0], &|v| 1. / (1. + (-v).exp())),
+            "IsNaN" => unary(&x[0], &|v| v.is_nan() as u8 as f32),
+            "IsInf" => unary(&x[0], &|v| v.is_infinite() as u8 as f32),
+            "Not" => unary(&x[0], &|v| (v == 0.) as u8 as f32),
+            "And" => binary(&x[0], &x[1], &|a, b| (a != 0. && b != 0.) as u8 as f32),
+            "Or" => binary(&x[0], &x[1], &|a, b| (a != 0. || b != 0.) as u8 as f32),
+            "Add" => binary(&x[0], &x[1], &|a, b| a + b),
+            "Sub" => binary(&x[0], &x[1], &|a, b| a - b),
+            "Mul" => binary(&x[0], &x[1], &|a, b| a * b),
+            "Div" => binary(&x[0], &x[1], &|a, b| a / b),
+            "Max" => binary(&x[0], &x[1], &|a, b| a.max(b)),
+            "Min" => binary(&x[0], &x[1], &|a, b| a.min(b)),
+            "Equal" => binary(&x[0], &x[1], &|a, b| (a == b) as u8 as f32),
+            "Less" => binary(&x[0], &x[1], &|a, b| (a < b) as u8 as f32),
+            "LessOrEqual" => binary(&x[0], &x[1], &|a, b| (a <= b) as u8 as f32),
+            "Cast" => match node.attributes["to"] {
+                Attribute::Int(1) => x[0].clone(),
+                Attribute::Int(7) => unary(&x[0], &|v| v.trunc()),
+                Attribute::Int(9) => unary(&x[0], &|v| (v != 0.) as u8 as f32),
+                ref a => panic!("Unsupported cast: {:?}", a),
+            },
+            "Where" => Tensor {
+                dims: x[0].dims.clone(),
+                values: (0..x[0].values.len())
+                    .map(|i| {
+                        if x[0].values[i] != 0. {
+                            x[1].values[i]
+                        } else {
+                            x[2].values[i]
+                        }
+                    })
+                    .collect(),
+            },
+            "Clip" => {
+                let (min, max) = (x[1].values[0], x[2].values[0]);
+                unary(&x[0], &|v| v.max(min).min(max))
+            }
+            "MatMul" => {
+                let ([m, k], [_, n]) = (
+                    <[usize; 2]>::try_from(x[0].dims.as_slice()).unwrap(),
+                    <[usize; 2]>::try_from(x[1].dims.as_slice()).unwrap(),
+                );
+                let mut values = vec![0.; m * n];
+                for i in 0..m {
+                    for j in 0..n {
+                        for l in 0..k {
+                            values[i * n + j] += x[0].values[i * k + l] * x[1].values[l * n + j];
+                        }
+                    }
+                }
+                Tensor {
+                    dims: vec![m, n],
+                    values,
+                }
+            }
+            "Transpose" => {
+                let [m, n] = <[usize; 2]>::try_from(x[0].dims.as_slice()).unwrap();
+                Tensor {
+                    dims: vec![n, m],
+                    values: (0..m * n)
+                        .map(|k| x[0].values[(k % m) * n + k / m])
+                        .collect(),
+                }
+            }
+            "ReduceSum" => {
+                let axis = x[1].values[0] as usize;
+                let outer = x[0].dims[..axis].iter().product::<usize>();
+                let size = x[0].dims[axis];
+                let inner = x[0].dims[axis + 1..].iter().product::<usize>();
+                let mut values = vec![0.; outer * inner];
+                for o in 0..outer {
+                    for s in 0..size {
+                        for i in 0..inner {
+                            values[o * inner + i] += x[0].values[(o * size + s) * inner + i];
+                        }
+                    }
+                }
+                let mut dims = x[0].dims.clone();
+                match node.attributes["keepdims"] {
+                    Attribute::Int(0) => drop(dims.remove(axis)),
+                    _ => dims[axis] = 1,
+                }
+                Tensor { dims, values }
+            }
+            "Gather" => {
+                assert_eq!(node.attributes["axis"], Attribute::Int(0));
+                let row_size = x[0].dims[1..].iter().product::<usize>();
+                Tensor {
+                    dims: [&x[1].dims, &x[0].dims[1..]].concat(),
+                    values: x[1]
+                        .values
+                        .iter()
+                        .flat_map(|&i| {
+                            let i = i as usize;
+                            x[0].values[i * row_size..(i + 1) * row_size].to_vec()
+                        })
+                        .collect(),
+                }
+            }
+            "Unsqueeze" => {
+                let mut dims = x[0].dims.clone();
+                dims.insert(x[1].values[0] as usize, 1);
+                Tensor {
+                    dims,
+                    values: x[0].values.clone(),
+                }
+            }
+            "Expand" => {
+                let dims = x[1].values.iter().map(|&d| d as usize).collect::<Vec<_>>();
+                let axis = x[0].dims.iter().position(|&d| d == 1).unwrap();
+                let outer = dims[..axis].iter().product::<usize>();
+                let size = dims[axis];
+                let inner = dims[axis + 1..].iter().product::<usize>();
+                let mut values = vec![];
+                for o in 0..outer {
+                    for _ in 0..size {
+                        values.extend_from_slice(&x[0].values[o * inner..(o + 1) * inner]);
+                    }
+                }
+                Tensor { dims, values }
+            }
+            op => panic!("Unsupported operator: {}", op),
+        };
+        values.insert(node.outputs[0].clone(), y);
+    }
+
+    model
+        .outputs
+        .iter()
+        .map(|(name, dims)| {
+            assert_eq!(values[name].dims, *dims);
+            values[name].clone()
+        })
+        .collect()
+}
+
+fn export_model<'hw: 'op, 'op: 'g, 'g>(
+    inputs: &[Node<'hw, 'op, 'g>],
+    outputs: &[Node<'hw, 'op, 'g>],
+) -> Model {
+    let mut bytes = vec![];
+    export(inputs, outputs, &mut bytes).unwrap();
+    read_model(&bytes)
+}
+
+fn tensor_of(array: &Array) -> Tensor {
+    Tensor {
+        dims: array.shape().dimensions().to_vec(),
+        values: array.get_values_f32(),
+    }
+}
+
+fn assert_close(observed: &Tensor, expected: &Tensor) {
+    assert_eq!(observed.dims, expected.dims);
+    for (o, e) in observed.values.iter().zip(&expected.values) {
+        assert!((o - e).abs() < 1e-5, "{:?} != {:?}", observed, expected);
+    }
+}
+
+#[test]
+fn test_export_arithmetic() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let x = (Shape::new([2, 2]), vec![1f32, -2., 3., -4.]).into_node(&g, &hw);
+    let c = (Shape::new([2, 2]), vec![0.5f32, 1., 2., 4.]).into_node(&g, &hw);
+    let f = Node::fill(&g, &hw, Shape::new([2, 2]), 3.);
+    let y = -((x + c) * f - x) / c;
+
+    let model = export_model(&[x], &[y]);
+    assert_eq!(model.ir_version, 8);
+    assert_eq!(model.opset_version, 17);
+    assert_eq!(model.inputs, [("input_0".to_string(), vec![2, 2])]);
+    assert_eq!(model.outputs, [("output_0".to_string(), vec![2, 2])]);
+    assert_eq!(
+        model
+            .nodes
+            .iter()
+            .map(|n| n.op_type.as_str())
+            .collect::<Vec<_>>(),
+        ["Add", "Mul", "Sub", "Neg", "Div", "Identity"]
+    );
+    assert_eq!(model.nodes[0].inputs, ["input_0", "s1"]);
+    assert_eq!(model.initializers.len(), 2);
+    assert_eq!(model.initializers["s1"].1.values, vec![0.5, 1., 2., 4.]);
+    assert_eq!(model.initializers["s2"].1.values, vec![3.; 4]);
+
+    let input = Tensor {
+        dims: vec![2, 2],
+        values: vec![1., -2., 3., -4.],
+    };
+    assert_close(&evaluate(&model, &[input])[0], &tensor_of(&y.calculate()));
+}
+
+#[test]
+fn test_export_functions() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let x = (Shape::new([2, 3]), vec![1f32, -2., 3., -4., 5., 0.5]).into_node(&g, &hw);
+    let w = (Shape::new([2, 3]), vec![0.5f32, 1., -1., 2., 0., 1.]).into_node(&g, &hw);
+    let h = x.matmul(w.transpose()).tanh() + x.sum_axis(1).expand_axis(1, 2).sigmoid();
+    let y = h
+        .relu()
+        .maximum(h.clamp(-0.5, 0.5))
+        .minimum((h * h).sqrt().stop_gradient());
+    let z = x
+        .less(w)
+        .select(x, w)
+        .sum_axis(0)
+        .expand_axis(0, 2)
+        .matmul(w.transpose());
+
+    let model = export_model(&[x], &[y, z]);
+    assert_eq!(model.outputs.len(), 2);
+    assert!(model
+        .initializers
+        .values()
+        .any(|(data_type, _)| *data_type == 7));
+
+    let input = tensor_of(&x.calculate());
+    let outputs = evaluate(&model, &[input]);
+    assert_close(&outputs[0], &tensor_of(&y.calculate()));
+    assert_close(&outputs[1], &tensor_of(&z.calculate()));
+}
+
+#[test]
+fn test_export_attributes() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let x = Node::fill(&g, &hw, Shape::new([1, 2, 5, 5]), 1.);
+    let w = Node::fill(&g, &hw, Shape::new([4, 1, 3, 3]), 1.);
+    let options = Conv2dOptions {
+        stride: [1, 2],
+        padding: [1, 0],
+        dilation: [1, 1],
+        groups: 2,
+    };
+    let pool = Pool2dOptions {
+        kernel_size: [2, 2],
+        stride: [1, 1],
+        padding: [1, 1],
+    };
+    let y = x.conv2d(w, options).max_pool2d(pool).avg_pool2d(pool);
+
+    let model = export_model(&[x], &[y]);
+    let conv = &model.nodes[0];
+    assert_eq!(conv.op_type, "Conv");
+    assert_eq!(conv.inputs, ["input_0", "s1"]);
+    assert_eq!(conv.attributes["strides"], Attribute::Ints(vec![1, 2]));
+    assert_eq!(conv.attributes["pads"], Attribute::Ints(vec![1, 0, 1, 0]));
+    assert_eq!(conv.attributes["dilations"], Attribute::Ints(vec![1, 1]));
+    assert_eq!(conv.attributes["group"], Attribute::Int(2));
+    let max_pool = &model.nodes[1];
+    assert_eq!(max_pool.op_type, "MaxPool");
+    assert_eq!(
+        max_pool.attributes["kernel_shape"],
+        Attribute::Ints(vec![2, 2])
+    );
+    assert_eq!(
+        max_pool.attributes["pads"],
+        Attribute::Ints(vec![1, 1, 1, 1])
+    );
+    assert!(!max_pool.attributes.contains_key("count_include_pad"));
+    let avg_pool = &model.nodes[2];
+    assert_eq!(avg_pool.op_type, "AveragePool");
+    assert_eq!(avg_pool.attributes["count_include_pad"], Attribute::Int(1));
+    assert_eq!(model.outputs[0].1, y.shape().dimensions());
+}
+
+#[test]
+fn test_export_norm() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let x = Node::fill(&g, &hw, Shape::new([2, 3]), 1.);
+    let gamma = Node::fill(&g, &hw, Shape::new([3]), 1.);
+    let beta = Node::fill(&g, &hw, Shape::new([3]), 0.);
+    let mean = Node::fill(&g, &hw, Shape::new([3]), 0.);
+    let var = Node::fill(&g, &hw, Shape::new([3]), 1.);
+    let y = x
+        .layer_norm(gamma, beta, 1e-5)
+        .batch_norm_inference(mean, var, gamma, beta, 1e-3);
+
+    let model = export_model(&[x], &[y]);
+    assert_eq!(model.nodes[0].op_type, "LayerNormalization");
+    assert_eq!(model.nodes[0].inputs, ["input_0", "s1", "s2"]);
+    assert_eq!(model.nodes[0].attributes["axis"], Attribute::Int(-1));
+    assert_eq!(model.nodes[0].attributes["epsilon"], Attribute::Float(1e-5));
+    assert_eq!(model.nodes[1].op_type, "BatchNormalization");
+    assert_eq!(model.nodes[1].inputs, ["s5", "s1", "s2", "s3", "s4"]);
+    assert_eq!(model.nodes[1].attributes["epsilon"], Attribute::Float(1e-3));
+}
+
+#[test]
+fn test_export_logical() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let x = (
+        Shape::new([2, 3]),
+        vec![1f32, 0., f32::NAN, f32::INFINITY, -2., 0.],
+    )
+        .into_node(&g, &hw);
+    let w = (Shape::new([2, 3]), vec![2f32, 0., 1., 0., -2., -1.]).into_node(&g, &hw);
+    let y = x.isnan().logical_or(x.isinf()).logical_not();
+    let z = x.logical_and(w).logical_or(x.less_equal(w));
+
+    let model = export_model(&[x], &[y, z]);
+    let op_types = model
+        .nodes
+        .iter()
+        .map(|n| n.op_type.as_str())
+        .collect::<Vec<_>>();
+    for op_type in ["IsNaN", "IsInf", "And", "Or", "Not", "LessOrEqual"] {
+        assert!(op_types.contains(&op_type), "{}", op_type);
+    }
+    assert!(!op_types.contains(&"LessEqual"));
+
+    let input = tensor_of(&x.calculate());
+    let outputs = evaluate(&model, &[input]);
+    assert_eq!(outputs[0].values, y.calculate().get_values_f32());
+    assert_eq!(outputs[1].values, z.calculate().get_values_f32());
+}
+
+#[test]
+fn test_export_gather_rows() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let table = (Shape::new([3, 2]), vec![1f32, 2., 3., 4., 5., 6.]).into_node(&g, &hw);
+    let indices = (Shape::new([2, 2]), vec![2f32, 0., 1., 2.]).into_node(&g, &hw);
+    let y = table.gather_rows(indices);
+
+    let model = export_model(&[table, indices], &[y]);
+    assert_eq!(model.nodes[0].op_type, "Cast");
+    assert_eq!(model.nodes[0].attributes["to"], Attribute::Int(7));
+    assert_eq!(model.nodes[1].op_type, "Gather");
+    assert_eq!(model.nodes[1].inputs, ["input_0", "s2_indices"]);
+
+    let inputs = [
+        tensor_of(&table.calculate()),
+        tensor_of(&indices.calculate()),
+    ];
+    assert_close(&evaluate(&model, &inputs)[0], &tensor_of(&y.calculate()));
+}
+
+#[test]
+fn test_export_rms_norm() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let x = (Shape::new([2, 3]), vec![1f32, 2., 4., -1., 0., 1.]).into_node(&g, &hw);
+    let gamma = (Shape::new([3]), vec![1f32, 2., 3.]).into_node(&g, &hw);
+    let y = x.rms_norm(gamma, 1e-3);
+
+    let model = export_model(&[x], &[y]);
+    assert_eq!(model.nodes[1].op_type, "ReduceSum");
+    assert_eq!(model.nodes[1].attributes["keepdims"], Attribute::Int(1));
+    assert_eq!(model.initializers["s2_axes"].1.values, vec![1.]);
+    assert_eq!(model.initializers["s2_epsilon"].1.values, vec![1e-3]);
+
+    let input = tensor_of(&x.calculate());
+    assert_close(&evaluate(&model, &[input])[0], &tensor_of(&y.calculate()));
+}
+
+#[test]
+fn test_export_subgraph() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let a = 1f32.into_node(&g, &hw);
+    let b = a + a;
+    let c = -b;
+    let _ = c * c;
+
+    // Steps preceding the inputs and following the outputs are not exported.
+    let model = export_model(&[b], &[c]);
+    assert_eq!(model.initializers.len(), 0);
+    assert_eq!(
+        model
+            .nodes
+            .iter()
+            .map(|n| n.op_type.as_str())
+            .collect::<Vec<_>>(),
+        ["Neg", "Identity"]
+    );
+    assert_eq!(model.nodes[0].inputs, ["input_0"]);
+    assert_eq!(model.nodes[1].inputs, ["s2"]);
+
+    // Inputs can be directly exported as outputs.
+    let model = export_model(&[a], &[a, b]);
+    assert_eq!(model.nodes[0].op_type, "Add");
+    assert_eq!(model.nodes[1].inputs, ["input_0"]);
+    assert_eq!(model.nodes[2].inputs, ["s1"]);
+}
+
+#[test]
+fn test_export_errors() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let x = 1f32.into_node(&g, &hw);
+    let mut bytes = vec![];
+
+    assert!(matches!(
+        export(&[x], &[], &mut bytes),
+        Err(Error::InvalidLength(_))
+    ));
+    assert!(matches!(
+        export(&[x, x], &[x], &mut bytes),
+        Err(Error::InvalidNode(_))
+    ));
+
+    let g2 = RefCell::new(Graph::new());
+    let y = 1f32.into_node(&g2, &hw);
+    assert!(matches!(
+        export(&[x], &[y], &mut bytes),
+        Err(Error::InvalidGraph(_))
+    ));
+
+    let y = x.dropout(&mut crate::random::Rng::new(1), 0.5, true);
+    assert!(matches!(
+        export(&[x], &[y], &mut bytes),
+        Err(Error::NotSupported(_))
+    ));
+    let y = Node::custom_gradient(&[x], |args| -args[0], |_, _, gy| vec![gy]);
+    assert!(matches!(
+        export(&[x], &[y], &mut bytes),
+        Err(Error::NotSupported(_))
+    ));
+    assert!(bytes.is_empty());
+}
+
+fn import_values<'hw: 'op, 'op: 'g, 'g>(
+    inputs: &[Node<'hw, 'op, 'g>],
+    outputs: &[Node<'hw, 'op, 'g>],
+    new_inputs: &[Node<'hw, 'op, 'g>],
+) -> Vec<Tensor> {
+    let mut bytes = vec![];
+    export(inputs, outputs, &mut bytes).unwrap();
+    let g = new_inputs[0].graph();
+    let hw = new_inputs[0].calculate().hardware();
+    let model = import(g, hw, &mut bytes.as_slice(), new_inputs).unwrap();
+    assert_eq!(model.inputs.len(), new_inputs.len());
+    model
+        .outputs
+        .iter()
+        .map(|(_, node)| tensor_of(&node.calculate()))
+        .collect()
+}
+
+fn build_model(
+    nodes: &[protobuf::Message],
+    initializers: &[protobuf::Message],
+    inputs: &[(&str, Shape)],
+    outputs: &[&str],
+) -> Vec<u8> {
+    let mut model_graph = protobuf::Message::new();
+    for node in nodes {
+        model_graph.message(1, node);
+    }
+    for initializer in initializers {
+        model_graph.message(5, initializer);
+    }
+    for (name, shape) in inputs {
+        model_graph.message(11, &value_info(name, shape));
+    }
+    for name in outputs {
+        let mut output = protobuf::Message::new();
+        output.string(1, name);
+        model_graph.message(12, &output);
+    }
+    let mut model = protobuf::Message::new();
+    model.int(1, IR_VERSION).message(7, &model_graph);
+    model.into_bytes()
+}
+
+fn float_tensor(name: &str, dims: &[usize], values: &[f32]) -> protobuf::Message {
+    let raw = values
+        .iter()
+        .flat_map(|x| x.to_le_bytes())
+        .collect::<Vec<_>>();
+    tensor(name, DATA_TYPE_FLOAT, dims, &raw)
+}
+
+#[test]
+fn test_import_round_trip() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let g2 = RefCell::new(Graph::new());
+
+    // Arithmetic
+    let values = vec![1f32, -2., 3., -4.];
+    let x = (Shape::new([2, 2]), values.clone()).into_node(&g, &hw);
+    let c = (Shape::new([2, 2]), vec![0.5f32, 1., 2., 4.]).into_node(&g, &hw);
+    let f = Node::fill(&g, &hw, Shape::new([2, 2]), 3.);
+    let y = -((x + c) * f - x) / c;
+    let x2 = (Shape::new([2, 2]), values).into_node(&g2, &hw);
+    let outputs = import_values(&[x], &[y], &[x2]);
+    assert_close(&outputs[0], &tensor_of(&y.calculate()));
+
+    // Functions
+    let values = vec![1f32, -2., 3., -4., 5., 0.5];
+    let x = (Shape::new([2, 3]), values.clone()).into_node(&g, &hw);
+    let w = (Shape::new([2, 3]), vec![0.5f32, 1., -1., 2., 0., 1.]).into_node(&g, &hw);
+    let h = x.matmul(w.transpose()).tanh() + x.sum_axis(1).expand_axis(1, 2).sigmoid();
+    let y = h
+        .relu()
+        .maximum(h.clamp(-0.5, 0.5))
+        .minimum((h * h).sqrt().stop_gradient());
+    let z = x
+        .less(w)
+        .select(x, w)
+        .sum_axis(0)
+        .expand_axis(0, 2)
+        .matmul(w.transpose());
+    let x2 = (Shape::new([2, 3]), values).into_node(&g2, &hw);
+    let outputs = import_values(&[x], &[y, z], &[x2]);
+    assert_close(&outputs[0], &tensor_of(&y.calculate()));
+    assert_close(&outputs[1], &tensor_of(&z.calculate()));
+
+    // Attributes
+    let x = Node::fill(&g, &hw, Shape::new([1, 2, 5, 5]), 1.);
+    let w = Node::fill(&g, &hw, Shape::new([4, 1, 3, 3]), 1.);
+    let options = Conv2dOptions {
+        stride: [1, 2],
+        padding: [1, 0],
+        dilation: [1, 1],
+        groups: 2,
+    };
+    let pool = Pool2dOptions {
+        kernel_size: [2, 2],
+        stride: [1, 1],
+        padding: [1, 1],
+    };
+    let y = x.conv2d(w, options).max_pool2d(pool).avg_pool2d(pool);
+    let x2 = Node::fill(&g2, &hw, Shape::new([1, 2, 5, 5]), 1.);
+    let outputs = import_values(&[x], &[y], &[x2]);
+    assert_close(&outputs[0], &tensor_of(&y.calculate()));
+
+    // Normalization
+    let values = vec![1f32, 2., 4., -1., 0., 1.];
+    let x = (Shape::new([2, 3]), values.clone()).into_node(&g, &hw);
+    let gamma = (Shape::new([3]), vec![1f32, 2., 3.]).into_node(&g, &hw);
+    let beta = (Shape::new([3]), vec![0f32, 1., 2.]).into_node(&g, &hw);
+    let mean = (Shape::new([3]), vec![0.5f32, 0., -0.5]).into_node(&g, &hw);
+    let var = (Shape::new([3]), vec![1f32, 2., 4.]).into_node(&g, &hw);
+    let y = x
+        .layer_norm(gamma, beta, 1e-5)
+        .batch_norm_inference(mean, var, gamma, beta, 1e-3);
+    let x2 = (Shape::new([2, 3]), values.clone()).into_node(&g2, &hw);
+    let outputs = import_values(&[x], &[y], &[x2]);
+    assert_close(&outputs[0], &tensor_of(&y.calculate()));
+    let y = x.rms_norm(gamma, 1e-3);
+    let outputs = import_values(&[x], &[y], &[x2]);
+    assert_close(&outputs[0], &tensor_of(&y.calculate()));
+
+    // Logical operators
+    let values = vec![1f32, 0., f32::NAN, f32::INFINITY, -2., 0.];
+    let x = (Shape::new([2, 3]), values.clone()).into_node(&g, &hw);
+    let w = (Shape::new([2, 3]), vec![2f32, 0., 1., 0., -2., -1.]).into_node(&g, &hw);
+    let y = x.isnan().logical_or(x.isinf()).logical_not();
+    let z = x.logical_and(w).logical_or(x.less_equal(w));
+    let x2 = (Shape::new([2, 3]), values).into_node(&g2, &hw);
+    let outputs = import_values(&[x], &[y, z], &[x2]);
+    assert_close(&outputs[0], &tensor_of(&y.calculate()));
+    assert_close(&outputs[1], &tensor_of(&z.calculate()));
+
+    // Gathering
+    let values = vec![1f32, 2., 3., 4., 5., 6.];
+    let table = (Shape::new([3, 2]), values.clone()).into_node(&g, &hw);
+    let indices = (Shape::new([2, 2]), vec![2f32, 0., 1., 2.]).into_node(&g, &hw);
+    let y = table.gather_rows(indices);
+    let table2 = (Shape::new([3, 2]), values).into_node(&g2, &hw);
+    let indices2 = (Shape::new([2, 2]), vec![1f32, 1., 0., 2.]).into_node(&g2, &hw);
+    let outputs = import_values(&[table, indices], &[y], &[table2, indices2]);
+    assert_eq!(outputs[0].dims, vec![2, 2, 2]);
+    assert_eq!(outputs[0].values, vec![3., 4., 3., 4., 1., 2., 5., 6.]);
+}
+
+#[test]
+fn test_import_initializers() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let bytes = build_model(
+        &[node("Mul", &["x", "w"], "y", &[])],
+        &[float_tensor("w", &[2], &[2., 3.])],
+        &[("x", Shape::new([2])), ("w", Shape::new([2]))],
+        &["y"],
+    );
+
+    // Initializers are not counted as inputs.
+    let x = (Shape::new([2]), vec![1f32, -1.]).into_node(&g, &hw);
+    let model = import(&g, &hw, &mut bytes.as_slice(), &[x]).unwrap();
+    assert_eq!(model.inputs[0].0, "x");
+    assert_eq!(model.inputs[0].1, x);
+    assert_eq!(model.initializers.len(), 1);
+    let (name, w) = &model.initializers[0];
+    assert_eq!(name, "w");
+    assert_eq!(
+        g.borrow().get_step(w.step_id()).unwrap().operator_name(),
+        "Constant"
+    );
+    assert_eq!(model.outputs[0].0, "y");
+    assert_eq!(
+        model.outputs[0].1.calculate().get_values_f32(),
+        vec![2., -3.]
+    );
+}
+
+#[test]
+fn test_import_operators() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let mut value = protobuf::Message::new();
+    value
+        .string(1, "value_float")
+        .float(2, 0.)
+        .int(20, ATTRIBUTE_FLOAT);
+    let bytes = build_model(
+        &[
+            node("Add", &["x", "b"], "add", &[]),
+            node("Mul", &["add", "c"], "mul", &[]),
+            node("Constant", &[], "zero", &[value]),
+            node("Greater", &["mul", "zero"], "gt", &[]),
+            node("Where", &["gt", "x", "zero"], "where", &[]),
+            node(
+                "ReduceSum",
+                &["where"],
+                "sum",
+                &[attribute_ints("axes", &[-1]), attribute_int("keepdims", 1)],
+            ),
+            node("Clip", &["mul", "", "max"], "clip", &[]),
+            node(
+                "Gemm",
+                &["x", "w", "bias"],
+                "gemm",
+                &[
+                    attribute_int("transB", 1),
+                    attribute_float("alpha", 2.),
+                    attribute_float("beta", 0.5),
+                ],
+            ),
+        ],
+        &[
+            float_tensor("b", &[3], &[10., 20., 30.]),
+            float_tensor("c", &[2, 1], &[1., -1.]),
+            float_tensor("max", &[], &[20.]),
+            float_tensor("w", &[2, 3], &[1., 0., 0., 0., 1., 1.]),
+            float_tensor("bias", &[2], &[1., 2.]),
+        ],
+        &[("x", Shape::new([2, 3]))],
+        &["where", "sum", "clip", "gemm"],
+    );
+
+    let x = (Shape::new([2, 3]), vec![1f32, 2., 3., 4., 5., 6.]).into_node(&g, &hw);
+    let model = import(&g, &hw, &mut bytes.as_slice(), &[x]).unwrap();
+    let outputs = model
+        .outputs
+        .iter()
+        .map(|(_, node)| tensor_of(&node.calculate()))
+        .collect::<Vec<_>>();
+    assert_eq!(outputs[0].dims, vec![2, 3]);
+    assert_eq!(outputs[0].values, vec![1., 2., 3., 0., 0., 0.]);
+    assert_eq!(outputs[1].dims, vec![2, 1]);
+    assert_eq!(outputs[1].values, vec![6., 0.]);
+    assert_eq!(outputs[2].values, vec![11., 20., 20., -14., -25., -36.]);
+    assert_eq!(outputs[3].dims, vec![2, 2]);
+    assert_eq!(outputs[3].values, vec![2.5, 11., 8.5, 23.]);
+}
+
+fn import_nodes<'hw: 'op, 'op: 'g, 'g>(
+    graph: &'g RefCell<Graph<'hw, 'op>>,
+    nodes: &[protobuf::Message],
+    inputs: &[Node<'hw, 'op, 'g>],
+) -> Result<()> {
+    let bytes = build_model(nodes, &[], &[("x", Shape::new([2]))], &["y"]);
+    import(graph, inputs[0].hardware(), &mut bytes.as_slice(), inputs).map(|_| ())
+}
+
+#[test]
+fn test_import_errors() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let x = (Shape::new([2]), vec![1f32, 2.]).into_node(&g, &hw);
+
+    assert_eq!(
+        import_nodes(&g, &[node("Neg", &["x"], "y", &[])], &[x]),
+        Ok(())
+    );
+    assert!(matches!(
+        import_nodes(&g, &[node("Foo", &["x"], "y", &[])], &[x]),
+        Err(Error::NotSupported(_))
+    ));
+    let mut custom = node("Neg", &["x"], "y", &[]);
+    custom.string(7, "com.example");
+    assert!(matches!(
+        import_nodes(&g, &[custom], &[x]),
+        Err(Error::NotSupported(_))
+    ));
+    assert!(matches!(
+        import_nodes(&g, &[node("Neg", &["z"], "y", &[])], &[x]),
+        Err(Error::InvalidData(_))
+    ));
+    assert!(matches!(
+        import_nodes(&g, &[node("Neg", &["x"], "y", &[])], &[x, x]),
+        Err(Error::InvalidLength(_))
+    ));
+    let scalar = 1f32.into_node(&g, &hw);
+    assert!(matches!(
+        import_nodes(&g, &[node("Neg", &["x"], "y", &[])], &[scalar]),
+        Err(Error::InvalidShape(_))
+    ));
+
+    // Incompatible shapes.
+    let bytes = build_model(
+        &[node("Add", &["x", "w"], "y", &[])],
+        &[float_tensor("w", &[3], &[1., 2., 3.])],
+        &[("x", Shape::new([2]))],
+        &["y"],
+    );
+    assert!(matches!(
+        import(&g, &hw, &mut bytes.as_slice(), &[x]),
+        Err(Error::InvalidShape(_))
+    ));
+
+    // Unsupported attributes and integer values.
+    assert!(matches!(
+        import_nodes(
+            &g,
+            &[node(
+                "IsInf",
+                &["x"],
+                "y",
+                &[attribute_int("detect_negative", 0)]
+            )],
+            &[x]
+        ),
+        Err(Error::NotSupported(_))
+    ));
+    let cast = || node("Cast", &["x"], "i", &[attribute_int("to", DATA_TYPE_INT64)]);
+    assert_eq!(
+        import_nodes(&g, &[cast(), node("Gather", &["x", "i"], "y", &[])], &[x]),
+        Ok(())
+    );
+    assert!(matches!(
+        import_nodes(&g, &[node("Gather", &["x", "x"], "y", &[])], &[x]),
+        Err(Error::NotSupported(_))
+    ));
+    assert!(matches!(
+        import_nodes(&g, &[cast(), node("Neg", &["i"], "y", &[])], &[x]),
+        Err(Error::InvalidData(_))
+    ));
+    let matrix = (Shape::new([2, 2]), vec![1f32, 0., 0., 1.]).into_node(&g, &hw);
+    let bytes = build_model(
+        &[
+            cast(),
+            node("Gather", &["m", "i"], "y", &[attribute_int("axis", 1)]),
+        ],
+        &[],
+        &[("x", Shape::new([2])), ("m", Shape::new([2, 2]))],
+        &["y"],
+    );
+    assert!(matches!(
+        import(&g, &hw, &mut bytes.as_slice(), &[x, matrix]),
+        Err(Error::NotSupported(_))
+    ));
+
+    // Inputs of other graphs.
+    let g2 = RefCell::new(Graph::new());
+    let x2 = (Shape::new([2]), vec![1f32, 2.]).into_node(&g2, &hw);
+    assert!(matches!(
+        import_nodes(&g, &[node("Neg", &["x"], "y", &[])], &[x2]),
+        Err(Error::InvalidGraph(_))
+    ));
+
+    // Broken data.
+    assert!(import(&g, &hw, &mut [0xffu8, 0xff].as_slice(), &[x]).is_err());
+}
@@ -3,12 +3,16 @@ use crate::error::Error;
 use crate::hardware::Hardware;
 use crate::node::Node;
 use crate::result::Result;
+use crate::serialize::{self, Encoder};
 use crate::shape::Shape;
 use std::cell::RefCell;
 use std::ptr;
 
 /// Operator represents an individual computation process in the computation graph.
-pub(crate) trait Operator<'hw> {
+///
+/// Users can implement this trait to define their own operators, and register them to a `Graph`
+/// through `Node::apply()`.
+pub trait Operator<'hw> {
     /// Returns the name of the operator.
     ///
     /// # Returns
@@ -56,7 +60,7 @@ pub(crate) trait Operator<'hw> {
         // Most operations assume that all inputs are on the same hardware.
         if self.input_size() > 0 {
             let hw = inputs[0];
-            if inputs.iter().skip(1).all(|&x| ptr::eq(hw, x)) {
+            if inputs.iter().skip(1).all(|&x| ptr::addr_eq(hw, x)) {
                 Ok(hw)
             } else {
                 Err(Error::InvalidNode(format!(
@@ -82,9 +86,48 @@ pub(crate) trait Operator<'hw> {
     /// # Returns:
     ///
     /// * `Ok(Array)` - The ouptut array.
-    /// * `Err(Error)` - Some error occurred during the process.
+    /// * `Err(Error)` - Some error occurred during the process. Since the calculation is
+    ///   performed lazily, `Node::calculate()` panics with this error. Invalid inputs should be
+    ///   rejected by `perform_shape()` or `perform_hardware()` instead.
     fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>>;
 
+    /// Encodes the attributes of the operator, which are required to reconstruct the operator
+    /// by `Graph::load()`.
+    ///
+    /// Only built-in operators can be reconstructed, so `Graph::save()` fails on graphs with
+    /// user-defined operators even if they implement this function. User-defined operators may
+    /// still implement it to share their steps in the hash-consing mode (see `is_pure()`).
+    ///
+    /// # Arguments
+    ///
+    /// * `encoder` - `Encoder` to write the attributes.
+    ///
+    /// # Returns:
+    ///
+    /// * `Ok(())` - Attributes are encoded.
+    /// * `Err(Error)` - The operator does not support serialization.
+    fn encode_attributes(&self, _encoder: &mut Encoder) -> Result<()> {
+        Err(Error::NotSupported(format!(
+            "{} does not support serialization.",
+            self.name()
+        )))
+    }
+
+    /// Returns whether the operator is pure: its output depends only on the inputs and the
+    /// attributes written by `encode_attributes()`.
+    ///
+    /// Graphs in the hash-consing mode share a single step between pure operators with the same
+    /// name, attributes, inputs and output hardware. Operators must not return `true` if their
+    /// encoded attributes do not fully determine the output.
+    ///
+    /// # Returns:
+    ///
+    /// * `true` - The operator is pure, and equivalent steps can be shared.
+    /// * `false` - The operator is not pure, or its steps must not be shared.
+    fn is_pure(&self) -> bool {
+        false
+    }
+
     /// Obtains the gradient function.
     ///
     /// # Returns:
@@ -94,10 +137,23 @@ pub(crate) trait Operator<'hw> {
     fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
         None
     }
+
+    /// Obtains the forward-mode gradient function.
+    ///
+    /// Operators whose outputs do not change with their inputs must still return a function that
+    /// yields 0 tangents: `jvp()` panics on operators without forward-mode gradients.
+    ///
+    /// # Returns:
+    ///
+    /// * `Some(Box<dyn ForwardGradient>)` - Forward-mode gradient function of this operator.
+    /// * `None` - Forward-mode gradient is not implemented for this operator.
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        None
+    }
 }
 
 /// Interface of the gradient function.
-pub(crate) trait Gradient {
+pub trait Gradient {
     /// Constructs the gradient graph.
     ///
     /// # Arguments:
@@ -107,20 +163,67 @@ pub(crate) trait Gradient {
     /// * `y` - `Node` of the output value.
     /// * `gy` - `Node` of the gradient for `y`: df/dy. The target value "f" depends on the
     ///   context when this function is called.
+    /// * `needs_grad` - Whether the gradient for each `x[i]` is required. The number of elements
+    ///   is the same as `x`, and at least one element is `true`. Implementations should not
+    ///   construct gradients which are not required.
     ///
     /// # Returns:
     ///
-    /// List of `Node`s of the gradient for `x[i]`: df/dx[i]. The number of elements must be the
-    /// same as the return value of `input_size()`.
-    /// Resulting nodes usually represent gy * dy/dx[i] according to the chain rule of derivatives,
-    /// but operators can implement other calculation instead for representing unusual gradient
-    /// manipulations.
+    /// List of gradients for `x[i]`: df/dx[i]. The number of elements must be the same as the
+    /// return value of `input_size()`. Each element is:
+    ///
+    /// * `Some(Node)` - `Node` of the gradient. Resulting nodes usually represent gy * dy/dx[i]
+    ///   according to the chain rule of derivatives, but operators can implement other calculation
+    ///   instead for representing unusual gradient manipulations.
+    /// * `None` - The gradient is not required, or is always 0.
     fn perform<'hw: 'op, 'op: 'g, 'g>(
         &self,
         x: &[Node<'hw, 'op, 'g>],
         y: Node<'hw, 'op, 'g>,
         gy: Node<'hw, 'op, 'g>,
-    ) -> Vec<Node<'hw, 'op, 'g>>;
+        needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>>;
+}
+
+/// Interface of the forward-mode gradient function.
+pub trait ForwardGradient {
+    /// Constructs the graph of the directional derivative.
+    ///
+    /// # Arguments:
+    ///
+    /// * `x` - `Node`s of input values. The number of elements must be the same as the return
+    ///   value of `input_size()`.
+    /// * `y` - `Node` of the output value.
+    /// * `dx` - `Node`s of the tangents for `x`: dx[i]/dt. The number of elements must be the same
+    ///   as the return value of `input_size()`. The parameter "t" depends on the context when this
+    ///   function is called.
+    ///
+    /// # Returns:
+    ///
+    /// `Node` of the tangent for `y`: dy/dt.
+    /// Resulting node usually represents sum(dy/dx[i] * dx[i]/dt) according to the chain rule of
+    /// derivatives.
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g>;
+}
+
+/// Forward-mode gradient of operators whose outputs are locally constant with respect to their
+/// inputs. The tangent of the output is always 0.
+pub(crate) struct ZeroForwardGrad;
+
+impl ForwardGradient for ZeroForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        _x: &[Node<'hw, 'op, 'g>],
+        y: Node<'hw, 'op, 'g>,
+        _dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        Node::fill(y.graph(), y.hardware(), y.shape(), 0.)
+    }
 }
 
 // Nullary operators
@@ -128,10 +231,59 @@ pub(crate) mod constant;
 pub(crate) mod fill;
 
 // Unary operators
+pub(crate) mod avg_pool2d;
+pub(crate) mod avg_pool2d_backward;
+pub(crate) mod clamp;
+pub(crate) mod clip_gradient;
+pub(crate) mod dropout;
+pub(crate) mod expand_axis;
+pub(crate) mod isinf;
+pub(crate) mod isnan;
+pub(crate) mod logical_not;
+pub(crate) mod max_pool2d;
 pub(crate) mod neg;
+pub(crate) mod relu;
+pub(crate) mod sigmoid;
+pub(crate) mod sqrt;
+pub(crate) mod step;
+pub(crate) mod stop_gradient;
+pub(crate) mod sum_axis;
+pub(crate) mod tanh;
+pub(crate) mod transpose;
 
 // Binary operators
 pub(crate) mod add;
+pub(crate) mod conv2d;
+pub(crate) mod conv2d_backward_input;
+pub(crate) mod conv2d_backward_weight;
 pub(crate) mod div;
+pub(crate) mod equal;
+pub(crate) mod gather_rows;
+pub(crate) mod less;
+pub(crate) mod less_equal;
+pub(crate) mod logical_and;
+pub(crate) mod logical_or;
+pub(crate) mod matmul;
+pub(crate) mod max_pool2d_gather;
+pub(crate) mod max_pool2d_scatter;
+pub(crate) mod maximum;
+pub(crate) mod minimum;
 pub(crate) mod mul;
+pub(crate) mod rms_norm;
+pub(crate) mod scatter_add_rows;
+pub(crate) mod straight_through;
 pub(crate) mod sub;
+
+// Ternary operators
+pub(crate) mod batch_norm;
+pub(crate) mod batch_norm_backward;
+pub(crate) mod layer_norm;
+pub(crate) mod layer_norm_backward;
+pub(crate) mod rms_norm_backward;
+pub(crate) mod select;
+
+// Quinary operators
+pub(crate) mod batch_norm_inference;
+
+// Variadic operators
+pub(crate) mod custom_gradient;
@@ -17,6 +17,14 @@ impl<'hw> Operator<'hw> for Add {
         2
     }
 
+    fn encode_attributes(&self, _encoder: &mut Encoder) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
     fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
         inputs[0].elementwise(inputs[1])
     }
@@ -28,6 +36,10 @@ impl<'hw> Operator<'hw> for Add {
     fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
         Some(Box::new(AddGrad {}))
     }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(AddForwardGrad {}))
+    }
 }
 
 /// Gradient for Add.
@@ -39,8 +51,23 @@ impl Gradient for AddGrad {
         _x: &[Node<'hw, 'op, 'g>],
         _y: Node<'hw, 'op, 'g>,
         gy: Node<'hw, 'op, 'g>,
-    ) -> Vec<Node<'hw, 'op, 'g>> {
-        vec![gy, gy]
+        needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        vec![needs_grad[0].then_some(gy), needs_grad[1].then_some(gy)]
+    }
+}
+
+/// Forward-mode gradient for Add.
+struct AddForwardGrad;
+
+impl ForwardGradient for AddForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        _x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        dx[0] + dx[1]
     }
 }
 
@@ -0,0 +1,152 @@
+use crate::conv::{Conv2dGeometry, Pool2dOptions};
+use crate::operator::*;
+
+/// AvgPool2d operator: average of each 2-dimensional window.
+pub(crate) struct AvgPool2d {
+    /// Pooling options.
+    options: Pool2dOptions,
+}
+
+impl AvgPool2d {
+    pub(crate) fn new(options: Pool2dOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl<'hw> Operator<'hw> for AvgPool2d {
+    fn name(&self) -> String {
+        String::from("AvgPool2d")
+    }
+
+    fn input_size(&self) -> usize {
+        1
+    }
+
+    fn encode_attributes(&self, encoder: &mut Encoder) -> Result<()> {
+        serialize::write_pool2d_options(encoder, &self.options);
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        Ok(Conv2dGeometry::pool2d(inputs[0], &self.options)?.output_shape())
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        inputs[0].avg_pool2d_f32(&self.options)
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(AvgPool2dGrad {
+            options: self.options,
+        }))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(AvgPool2dForwardGrad {
+            options: self.options,
+        }))
+    }
+}
+
+/// Gradient for AvgPool2d.
+struct AvgPool2dGrad {
+    options: Pool2dOptions,
+}
+
+impl Gradient for AvgPool2dGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        _needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        vec![Some(avg_pool2d_backward(
+            gy,
+            conv2d::spatial_size(x[0]),
+            self.options,
+        ))]
+    }
+}
+
+/// Forward-mode gradient for AvgPool2d.
+struct AvgPool2dForwardGrad {
+    options: Pool2dOptions,
+}
+
+impl ForwardGradient for AvgPool2dForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        _x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        dx[0].avg_pool2d(self.options)
+    }
+}
+
+/// Registers `AvgPool2dBackward` operation: the gradient of AvgPool2d with respect to the input.
+pub(crate) fn avg_pool2d_backward<'hw: 'op, 'op: 'g, 'g>(
+    gy: Node<'hw, 'op, 'g>,
+    in_size: [usize; 2],
+    options: Pool2dOptions,
+) -> Node<'hw, 'op, 'g> {
+    Node::apply(
+        gy.graph(),
+        Box::new(avg_pool2d_backward::AvgPool2dBackward::new(
+            in_size, options,
+        )),
+        &[gy],
+    )
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::avg_pool2d::*;
+
+    #[test]
+    fn test_properties() {
+        let op = AvgPool2d::new(Pool2dOptions::new([2, 2]));
+        assert_eq!(op.name(), "AvgPool2d");
+        assert_eq!(op.input_size(), 1);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = AvgPool2d::new(Pool2dOptions::new([2, 2]));
+        assert_eq!(op.perform_shape(&[&Shape::new([2, 3, 5, 4])]), Ok(Shape::new([2, 3, 2, 2])));
+        assert_eq!(op.perform_shape(&[&Shape::new([0, 0, 2, 2])]), Ok(Shape::new([0, 0, 1, 1])));
+        assert!(op.perform_shape(&[&Shape::new([2, 3, 1, 4])]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([3, 5, 4])]).is_err());
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = AvgPool2d::new(Pool2dOptions::new([2, 2]));
+
+        assert!(ptr::eq(op.perform_hardware(&[&hw]).unwrap(), &hw));
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = AvgPool2d::new(Pool2dOptions::new([1, 2]));
+        let input = Array::constant_f32(
+            &hw,
+            Shape::new([1, 2, 1, 4]),
+            &[1., -2., 3., 4., -5., -6., 0., 7.],
+        )
+        .unwrap();
+        let observed = op.perform(&[&input]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([1, 2, 1, 2]));
+        assert_eq!(observed.get_values_f32(), vec![-0.5, 3.5, -5.5, 3.5]);
+    }
+}
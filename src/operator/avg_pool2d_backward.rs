@@ -0,0 +1,136 @@
+use crate::conv::{Conv2dGeometry, Pool2dOptions};
+use crate::operator::avg_pool2d::avg_pool2d_backward;
+use crate::operator::*;
+
+/// AvgPool2dBackward operator: gradient of AvgPool2d with respect to the input, calculated from
+/// the output gradient gy.
+pub(crate) struct AvgPool2dBackward {
+    /// Height and width of the input of the pooling.
+    in_size: [usize; 2],
+
+    /// Pooling options.
+    options: Pool2dOptions,
+}
+
+impl AvgPool2dBackward {
+    pub(crate) fn new(in_size: [usize; 2], options: Pool2dOptions) -> Self {
+        Self { in_size, options }
+    }
+}
+
+impl<'hw> Operator<'hw> for AvgPool2dBackward {
+    fn name(&self) -> String {
+        String::from("AvgPool2dBackward")
+    }
+
+    fn input_size(&self) -> usize {
+        1
+    }
+
+    fn encode_attributes(&self, encoder: &mut Encoder) -> Result<()> {
+        for &size in &self.in_size {
+            encoder.write_usize(size);
+        }
+        serialize::write_pool2d_options(encoder, &self.options);
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        Ok(Conv2dGeometry::pool2d_backward(inputs[0], self.in_size, &self.options)?.input_shape())
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        inputs[0].avg_pool2d_backward_f32(self.in_size, &self.options)
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(AvgPool2dBackwardGrad {
+            options: self.options,
+        }))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(AvgPool2dBackwardForwardGrad {
+            in_size: self.in_size,
+            options: self.options,
+        }))
+    }
+}
+
+/// Gradient for AvgPool2dBackward.
+struct AvgPool2dBackwardGrad {
+    options: Pool2dOptions,
+}
+
+impl Gradient for AvgPool2dBackwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        _x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        _needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        vec![Some(gy.avg_pool2d(self.options))]
+    }
+}
+
+/// Forward-mode gradient for AvgPool2dBackward.
+struct AvgPool2dBackwardForwardGrad {
+    in_size: [usize; 2],
+    options: Pool2dOptions,
+}
+
+impl ForwardGradient for AvgPool2dBackwardForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        _x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        avg_pool2d_backward(dx[0], self.in_size, self.options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::avg_pool2d_backward::*;
+
+    #[test]
+    fn test_properties() {
+        let op = AvgPool2dBackward::new([4, 4], Pool2dOptions::new([2, 2]));
+        assert_eq!(op.name(), "AvgPool2dBackward");
+        assert_eq!(op.input_size(), 1);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = AvgPool2dBackward::new([5, 4], Pool2dOptions::new([2, 2]));
+        assert_eq!(op.perform_shape(&[&Shape::new([2, 3, 2, 2])]), Ok(Shape::new([2, 3, 5, 4])));
+        assert!(op.perform_shape(&[&Shape::new([2, 3, 3, 2])]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([3, 2, 2])]).is_err());
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = AvgPool2dBackward::new([4, 4], Pool2dOptions::new([2, 2]));
+
+        assert!(ptr::eq(op.perform_hardware(&[&hw]).unwrap(), &hw));
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = AvgPool2dBackward::new([1, 5], Pool2dOptions::new([1, 2]));
+        let gy = Array::constant_f32(&hw, Shape::new([1, 1, 1, 2]), &[2., 4.]).unwrap();
+        let observed = op.perform(&[&gy]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([1, 1, 1, 5]));
+        assert_eq!(observed.get_values_f32(), vec![1., 1., 2., 2., 0.]);
+    }
+}
@@ -0,0 +1,187 @@
+use crate::operator::layer_norm::{check_same_shape, fill_like};
+use crate::operator::*;
+
+/// BatchNorm operator: normalization of x over every axis except the channel axis (the second
+/// one) with the statistics of x itself, scaled by gamma and shifted by beta.
+pub(crate) struct BatchNorm {
+    /// Small value added to the variance.
+    epsilon: f32,
+}
+
+impl BatchNorm {
+    pub(crate) fn new(epsilon: f32) -> Self {
+        Self { epsilon }
+    }
+}
+
+impl<'hw> Operator<'hw> for BatchNorm {
+    fn name(&self) -> String {
+        String::from("BatchNorm")
+    }
+
+    fn input_size(&self) -> usize {
+        3
+    }
+
+    fn encode_attributes(&self, encoder: &mut Encoder) -> Result<()> {
+        encoder.write_f32(self.epsilon);
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        check_same_shape(inputs[1], inputs[2])?;
+        inputs[0].channel_layout(inputs[1])?;
+        Ok(inputs[0].clone())
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        inputs[0].batch_norm_f32(inputs[1], inputs[2], self.epsilon)
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(BatchNormGrad {
+            epsilon: self.epsilon,
+        }))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(BatchNormForwardGrad {
+            epsilon: self.epsilon,
+        }))
+    }
+}
+
+/// Gradient for BatchNorm.
+struct BatchNormGrad {
+    epsilon: f32,
+}
+
+impl Gradient for BatchNormGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        vec![
+            needs_grad[0].then(|| batch_norm_backward(x[0], x[1], gy, self.epsilon)),
+            needs_grad[1].then(|| {
+                let xhat = x[0].batch_norm(fill_like(x[1], 1.), fill_like(x[2], 0.), self.epsilon);
+                reduce_channels(gy * xhat)
+            }),
+            needs_grad[2].then(|| reduce_channels(gy)),
+        ]
+    }
+}
+
+/// Forward-mode gradient for BatchNorm.
+struct BatchNormForwardGrad {
+    epsilon: f32,
+}
+
+impl ForwardGradient for BatchNormForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        let xhat = x[0].batch_norm(fill_like(x[1], 1.), fill_like(x[2], 0.), self.epsilon);
+        let shape = x[0].shape();
+        batch_norm_backward(x[0], x[1], dx[0], self.epsilon)
+            + broadcast_channels(dx[1], &shape) * xhat
+            + broadcast_channels(dx[2], &shape)
+    }
+}
+
+/// Broadcasts per-channel values of the shape `[channels]` to `shape`, whose second dimension is
+/// `channels`.
+pub(crate) fn broadcast_channels<'hw: 'op, 'op: 'g, 'g>(
+    node: Node<'hw, 'op, 'g>,
+    shape: &Shape,
+) -> Node<'hw, 'op, 'g> {
+    let dims = shape.dimensions();
+    let node = node.expand_axis(0, dims[0]);
+    (2..dims.len()).fold(node, |node, axis| node.expand_axis(axis, dims[axis]))
+}
+
+/// Sums values over every axis except the second one. This is the adjoint of
+/// `broadcast_channels()`.
+pub(crate) fn reduce_channels<'hw: 'op, 'op: 'g, 'g>(
+    node: Node<'hw, 'op, 'g>,
+) -> Node<'hw, 'op, 'g> {
+    let num_dimensions = node.shape().num_dimensions();
+    (2..num_dimensions)
+        .rev()
+        .fold(node, |node, axis| node.sum_axis(axis))
+        .sum_axis(0)
+}
+
+/// Registers `BatchNormBackward` operation: the gradient of BatchNorm with respect to the input.
+pub(crate) fn batch_norm_backward<'hw: 'op, 'op: 'g, 'g>(
+    x: Node<'hw, 'op, 'g>,
+    gamma: Node<'hw, 'op, 'g>,
+    gy: Node<'hw, 'op, 'g>,
+    epsilon: f32,
+) -> Node<'hw, 'op, 'g> {
+    Node::apply(
+        x.graph(),
+        Box::new(batch_norm_backward::BatchNormBackward::new(epsilon)),
+        &[x, gamma, gy],
+    )
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::batch_norm::*;
+
+    #[test]
+    fn test_properties() {
+        let op = BatchNorm::new(1e-5);
+        assert_eq!(op.name(), "BatchNorm");
+        assert_eq!(op.input_size(), 3);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = BatchNorm::new(1e-5);
+        assert_eq!(op.perform_shape(&[&Shape::new([4, 3]), &Shape::new([3]), &Shape::new([3])]), Ok(Shape::new([4, 3])));
+        assert_eq!(op.perform_shape(&[&Shape::new([2, 3, 5, 4]), &Shape::new([3]), &Shape::new([3])]), Ok(Shape::new([2, 3, 5, 4])));
+        assert!(op.perform_shape(&[&Shape::new([3]), &Shape::new([3]), &Shape::new([3])]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([2, 3, 5]), &Shape::new([5]), &Shape::new([5])]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([2, 3, 5]), &Shape::new([3]), &Shape::new([2])]).is_err());
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw1 = RefCell::new(CpuHardware::new());
+        let hw2 = RefCell::new(CpuHardware::new());
+        let op = BatchNorm::new(1e-5);
+
+        assert!(ptr::eq(
+            op.perform_hardware(&[&hw1, &hw1, &hw1]).unwrap(),
+            &hw1
+        ));
+        assert!(op.perform_hardware(&[&hw1, &hw2, &hw1]).is_err());
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = BatchNorm::new(0.);
+        let x = Array::constant_f32(&hw, Shape::new([2, 2]), &[-1., 3., 1., 7.]).unwrap();
+        let gamma = Array::constant_f32(&hw, Shape::new([2]), &[2., 3.]).unwrap();
+        let beta = Array::constant_f32(&hw, Shape::new([2]), &[0., 1.]).unwrap();
+        let observed = op.perform(&[&x, &gamma, &beta]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([2, 2]));
+        assert_eq!(observed.get_values_f32(), vec![-2., -2., 2., 4.]);
+    }
+}
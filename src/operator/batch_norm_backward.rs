@@ -0,0 +1,111 @@
+use crate::operator::layer_norm_backward::{
+    Normalization, NormalizationBackwardForwardGrad, NormalizationBackwardGrad,
+};
+use crate::operator::*;
+
+/// BatchNormBackward operator: gradient of BatchNorm with respect to the input, calculated from
+/// the input x, scaling factors gamma and the output gradient gy.
+///
+/// Gradients of this operator provide higher-order derivatives of BatchNorm.
+pub(crate) struct BatchNormBackward {
+    /// Small value added to the variance.
+    epsilon: f32,
+}
+
+impl BatchNormBackward {
+    pub(crate) fn new(epsilon: f32) -> Self {
+        Self { epsilon }
+    }
+}
+
+impl<'hw> Operator<'hw> for BatchNormBackward {
+    fn name(&self) -> String {
+        String::from("BatchNormBackward")
+    }
+
+    fn input_size(&self) -> usize {
+        3
+    }
+
+    fn encode_attributes(&self, encoder: &mut Encoder) -> Result<()> {
+        encoder.write_f32(self.epsilon);
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        layer_norm::check_same_shape(inputs[0], inputs[2])?;
+        inputs[0].channel_layout(inputs[1])?;
+        Ok(inputs[0].clone())
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        inputs[0].batch_norm_backward_f32(inputs[1], inputs[2], self.epsilon)
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(NormalizationBackwardGrad {
+            normalization: Normalization::Batch,
+            epsilon: self.epsilon,
+        }))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(NormalizationBackwardForwardGrad {
+            normalization: Normalization::Batch,
+            epsilon: self.epsilon,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::batch_norm_backward::*;
+
+    #[test]
+    fn test_properties() {
+        let op = BatchNormBackward::new(1e-5);
+        assert_eq!(op.name(), "BatchNormBackward");
+        assert_eq!(op.input_size(), 3);
+        assert!(op.get_gradient_fn().is_some());
+        assert!(op.get_forward_gradient_fn().is_some());
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = BatchNormBackward::new(1e-5);
+        assert_eq!(op.perform_shape(&[&Shape::new([2, 3, 4]), &Shape::new([3]), &Shape::new([2, 3, 4])]), Ok(Shape::new([2, 3, 4])));
+        assert!(op.perform_shape(&[&Shape::new([2, 3, 4]), &Shape::new([3]), &Shape::new([2, 3])]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([2, 3, 4]), &Shape::new([4]), &Shape::new([2, 3, 4])]).is_err());
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw1 = RefCell::new(CpuHardware::new());
+        let hw2 = RefCell::new(CpuHardware::new());
+        let op = BatchNormBackward::new(1e-5);
+
+        assert!(ptr::eq(
+            op.perform_hardware(&[&hw1, &hw1, &hw1]).unwrap(),
+            &hw1
+        ));
+        assert!(op.perform_hardware(&[&hw1, &hw1, &hw2]).is_err());
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = BatchNormBackward::new(0.);
+        let x = Array::constant_f32(&hw, Shape::new([2, 1, 2]), &[-1., -1., 1., 1.]).unwrap();
+        let gamma = Array::constant_f32(&hw, Shape::new([1]), &[2.]).unwrap();
+        let gy = Array::constant_f32(&hw, Shape::new([2, 1, 2]), &[1., 0., 0., 0.]).unwrap();
+        let observed = op.perform(&[&x, &gamma, &gy]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([2, 1, 2]));
+        assert_eq!(observed.get_values_f32(), vec![1., -1., 0., 0.]);
+    }
+}
@@ -0,0 +1,180 @@
+use crate::operator::batch_norm::{broadcast_channels, reduce_channels};
+use crate::operator::layer_norm::{check_same_shape, fill_like};
+use crate::operator::*;
+
+/// BatchNormInference operator: normalization of x by given per-channel mean and variance, scaled
+/// by gamma and shifted by beta.
+pub(crate) struct BatchNormInference {
+    /// Small value added to the variance.
+    epsilon: f32,
+}
+
+impl BatchNormInference {
+    pub(crate) fn new(epsilon: f32) -> Self {
+        Self { epsilon }
+    }
+}
+
+impl<'hw> Operator<'hw> for BatchNormInference {
+    fn name(&self) -> String {
+        String::from("BatchNormInference")
+    }
+
+    fn input_size(&self) -> usize {
+        5
+    }
+
+    fn encode_attributes(&self, encoder: &mut Encoder) -> Result<()> {
+        encoder.write_f32(self.epsilon);
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        for other in &inputs[2..] {
+            check_same_shape(inputs[1], other)?;
+        }
+        inputs[0].channel_layout(inputs[1])?;
+        Ok(inputs[0].clone())
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        inputs[0].batch_norm_inference_f32(inputs[1], inputs[2], inputs[3], inputs[4], self.epsilon)
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(BatchNormInferenceGrad {
+            epsilon: self.epsilon,
+        }))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(BatchNormInferenceForwardGrad {
+            epsilon: self.epsilon,
+        }))
+    }
+}
+
+/// Gradient for BatchNormInference.
+struct BatchNormInferenceGrad {
+    epsilon: f32,
+}
+
+impl Gradient for BatchNormInferenceGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        let (mean, variance, gamma) = (x[1], x[2], x[3]);
+
+        // y = (x - mean) * gamma / sqrt(variance + epsilon) + beta
+        let gx = (needs_grad[0] || needs_grad[1]).then(|| {
+            let zeros = fill_like(mean, 0.);
+            gy.batch_norm_inference(zeros, variance, gamma, zeros, self.epsilon)
+        });
+        let ggamma = (needs_grad[2] || needs_grad[3]).then(|| {
+            let (ones, zeros) = (fill_like(gamma, 1.), fill_like(mean, 0.));
+            let xhat = x[0].batch_norm_inference(mean, variance, ones, zeros, self.epsilon);
+            reduce_channels(gy * xhat)
+        });
+        vec![
+            gx.filter(|_| needs_grad[0]),
+            gx.filter(|_| needs_grad[1]).map(|gx| -reduce_channels(gx)),
+            ggamma.filter(|_| needs_grad[2]).map(|ggamma| {
+                let scale = gamma / (variance + fill_like(variance, self.epsilon));
+                fill_like(variance, -0.5) * ggamma * scale
+            }),
+            ggamma.filter(|_| needs_grad[3]),
+            needs_grad[4].then(|| reduce_channels(gy)),
+        ]
+    }
+}
+
+/// Forward-mode gradient for BatchNormInference.
+struct BatchNormInferenceForwardGrad {
+    epsilon: f32,
+}
+
+impl ForwardGradient for BatchNormInferenceForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        let (mean, variance, gamma) = (x[1], x[2], x[3]);
+        let zeros = fill_like(mean, 0.);
+        let xhat =
+            x[0].batch_norm_inference(mean, variance, fill_like(gamma, 1.), zeros, self.epsilon);
+        let shape = x[0].shape();
+
+        let centered = dx[0] - broadcast_channels(dx[1], &shape);
+        let scale = gamma / (variance + fill_like(variance, self.epsilon));
+        let dscale = fill_like(variance, -0.5) * dx[2] * scale + dx[3];
+        centered.batch_norm_inference(zeros, variance, gamma, zeros, self.epsilon)
+            + broadcast_channels(dscale, &shape) * xhat
+            + broadcast_channels(dx[4], &shape)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::batch_norm_inference::*;
+
+    #[test]
+    fn test_properties() {
+        let op = BatchNormInference::new(1e-5);
+        assert_eq!(op.name(), "BatchNormInference");
+        assert_eq!(op.input_size(), 5);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = BatchNormInference::new(1e-5);
+        let c = Shape::new([3]);
+        assert_eq!(op.perform_shape(&[&Shape::new([2, 3, 4]), &c, &c, &c, &c]), Ok(Shape::new([2, 3, 4])));
+        assert_eq!(op.perform_shape(&[&Shape::new([2, 3]), &c, &c, &c, &c]), Ok(Shape::new([2, 3])));
+        assert!(op.perform_shape(&[&Shape::new([3]), &c, &c, &c, &c]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([2, 4]), &c, &c, &c, &c]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([2, 3]), &c, &c, &c, &Shape::new([2])]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([2, 3]), &c, &Shape::new([2]), &c, &c]).is_err());
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw1 = RefCell::new(CpuHardware::new());
+        let hw2 = RefCell::new(CpuHardware::new());
+        let op = BatchNormInference::new(1e-5);
+
+        assert!(ptr::eq(
+            op.perform_hardware(&[&hw1, &hw1, &hw1, &hw1, &hw1])
+                .unwrap(),
+            &hw1
+        ));
+        assert!(op
+            .perform_hardware(&[&hw1, &hw1, &hw1, &hw1, &hw2])
+            .is_err());
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = BatchNormInference::new(0.);
+        let x = Array::constant_f32(&hw, Shape::new([2, 2]), &[-1., 3., 1., 7.]).unwrap();
+        let mean = Array::constant_f32(&hw, Shape::new([2]), &[0., 5.]).unwrap();
+        let variance = Array::constant_f32(&hw, Shape::new([2]), &[1., 4.]).unwrap();
+        let gamma = Array::constant_f32(&hw, Shape::new([2]), &[2., 3.]).unwrap();
+        let beta = Array::constant_f32(&hw, Shape::new([2]), &[0., 1.]).unwrap();
+        let observed = op.perform(&[&x, &mean, &variance, &gamma, &beta]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([2, 2]));
+        assert_eq!(observed.get_values_f32(), vec![-2., -2., 2., 4.]);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::operator::*;
+
+/// Clamp operator: limits every element into the range [min, max]. NaN values are kept as is.
+///
+/// Gradients are propagated only to the elements within the range.
+pub(crate) struct Clamp {
+    /// Lower bound of the resulting values.
+    min: f32,
+
+    /// Upper bound of the resulting values.
+    max: f32,
+}
+
+impl Clamp {
+    pub(crate) fn new(min: f32, max: f32) -> Self {
+        Self { min, max }
+    }
+}
+
+impl<'hw> Operator<'hw> for Clamp {
+    fn name(&self) -> String {
+        String::from("Clamp")
+    }
+
+    fn input_size(&self) -> usize {
+        1
+    }
+
+    fn encode_attributes(&self, encoder: &mut Encoder) -> Result<()> {
+        encoder.write_f32(self.min);
+        encoder.write_f32(self.max);
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        Ok(inputs[0].clone())
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        Ok(inputs[0].elementwise_clamp_f32(self.min, self.max))
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(ClampGrad {
+            min: self.min,
+            max: self.max,
+        }))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(ClampForwardGrad {
+            min: self.min,
+            max: self.max,
+        }))
+    }
+}
+
+/// Gradient for Clamp.
+struct ClampGrad {
+    min: f32,
+    max: f32,
+}
+
+impl Gradient for ClampGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        _needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        let zero = Node::fill(gy.graph(), gy.hardware(), gy.shape(), 0.);
+        vec![Some(in_range(x[0], self.min, self.max).select(gy, zero))]
+    }
+}
+
+/// Forward-mode gradient for Clamp.
+struct ClampForwardGrad {
+    min: f32,
+    max: f32,
+}
+
+impl ForwardGradient for ClampForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        let zero = Node::fill(dx[0].graph(), dx[0].hardware(), dx[0].shape(), 0.);
+        in_range(x[0], self.min, self.max).select(dx[0], zero)
+    }
+}
+
+/// Obtains the mask of elements within [min, max].
+fn in_range<'hw: 'op, 'op: 'g, 'g>(
+    x: Node<'hw, 'op, 'g>,
+    min: f32,
+    max: f32,
+) -> Node<'hw, 'op, 'g> {
+    let fill = |value| Node::fill(x.graph(), x.hardware(), x.shape(), value);
+    x.greater_equal(fill(min))
+        .logical_and(x.less_equal(fill(max)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::clamp::*;
+
+    #[test]
+    fn test_properties() {
+        let op = Clamp::new(-1., 1.);
+        assert_eq!(op.name(), "Clamp");
+        assert_eq!(op.input_size(), 1);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = Clamp::new(-1., 1.);
+        assert_eq!(op.perform_shape(&[&Shape::new([])]), Ok(Shape::new([])));
+        assert_eq!(op.perform_shape(&[&Shape::new([0])]), Ok(Shape::new([0])));
+        assert_eq!(op.perform_shape(&[&Shape::new([3])]), Ok(Shape::new([3])));
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = Clamp::new(-1., 1.);
+
+        assert!(ptr::eq(op.perform_hardware(&[&hw]).unwrap(), &hw));
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = Clamp::new(-1., 1.);
+        let input = Array::constant_f32(&hw, Shape::new([3]), &[-2., 0.5, 2.]).unwrap();
+        let observed = op.perform(&[&input]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([3]));
+        assert_eq!(observed.get_values_f32(), vec![-1., 0.5, 1.]);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::operator::*;
+
+/// ClipGradient operator: returns the input as is, while the gradient passed through this
+/// operator is clamped into the range [min, max].
+pub(crate) struct ClipGradient {
+    /// Lower bound of the gradient.
+    min: f32,
+
+    /// Upper bound of the gradient.
+    max: f32,
+}
+
+impl ClipGradient {
+    pub(crate) fn new(min: f32, max: f32) -> Self {
+        Self { min, max }
+    }
+}
+
+impl<'hw> Operator<'hw> for ClipGradient {
+    fn name(&self) -> String {
+        String::from("ClipGradient")
+    }
+
+    fn input_size(&self) -> usize {
+        1
+    }
+
+    fn encode_attributes(&self, encoder: &mut Encoder) -> Result<()> {
+        encoder.write_f32(self.min);
+        encoder.write_f32(self.max);
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        Ok(inputs[0].clone())
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        Ok(inputs[0].clone())
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(ClipGradientGrad {
+            min: self.min,
+            max: self.max,
+        }))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(ClipGradientForwardGrad {}))
+    }
+}
+
+/// Gradient for ClipGradient.
+struct ClipGradientGrad {
+    min: f32,
+    max: f32,
+}
+
+impl Gradient for ClipGradientGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        _x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        _needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        vec![Some(
+            Node::apply(
+                gy.graph(),
+                Box::new(clamp::Clamp::new(self.min, self.max)),
+                &[gy],
+            )
+            .unwrap(),
+        )]
+    }
+}
+
+/// Forward-mode gradient for ClipGradient.
+/// Clipping affects only the backward computation, and tangents are passed as is.
+struct ClipGradientForwardGrad;
+
+impl ForwardGradient for ClipGradientForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        _x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        dx[0]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array::IntoArray;
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::clip_gradient::*;
+
+    #[test]
+    fn test_properties() {
+        let op = ClipGradient::new(-1., 1.);
+        assert_eq!(op.name(), "ClipGradient");
+        assert_eq!(op.input_size(), 1);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = ClipGradient::new(-1., 1.);
+        assert_eq!(op.perform_shape(&[&Shape::new([])]), Ok(Shape::new([])));
+        assert_eq!(op.perform_shape(&[&Shape::new([0])]), Ok(Shape::new([0])));
+        assert_eq!(op.perform_shape(&[&Shape::new([3])]), Ok(Shape::new([3])));
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = ClipGradient::new(-1., 1.);
+
+        assert!(ptr::eq(op.perform_hardware(&[&hw]).unwrap(), &hw));
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = ClipGradient::new(-1., 1.);
+        let input = 42f32.into_array(&hw);
+        let observed = op.perform(&[&input]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([]));
+        assert_eq!(observed.get_scalar_f32(), Ok(42.));
+    }
+}
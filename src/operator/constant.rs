@@ -14,7 +14,6 @@ impl<'hw> Constant<'hw> {
     /// # Returns
     ///
     /// A new `Constant` object.
-    #[allow(dead_code)] // This function is used later to bring ndarrays into Graph.
     pub(crate) fn new(value: Array<'hw>) -> Self {
         Self { value }
     }
@@ -29,6 +28,16 @@ impl<'hw> Operator<'hw> for Constant<'hw> {
         0
     }
 
+    fn encode_attributes(&self, encoder: &mut Encoder) -> Result<()> {
+        encoder.write_array(&self.value);
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        // Constants are not shared, so that each `Node::parameter()` keeps its own step.
+        false
+    }
+
     fn perform_shape(&self, _inputs: &[&Shape]) -> Result<Shape> {
         Ok(self.value.shape().clone())
     }
@@ -43,6 +52,25 @@ impl<'hw> Operator<'hw> for Constant<'hw> {
     fn perform(&self, _inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
         Ok(self.value.clone())
     }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(ConstantForwardGrad {}))
+    }
+}
+
+/// Forward-mode gradient for Constant.
+struct ConstantForwardGrad;
+
+impl ForwardGradient for ConstantForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        _x: &[Node<'hw, 'op, 'g>],
+        y: Node<'hw, 'op, 'g>,
+        _dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        // The output does not depend on any variables.
+        Node::fill(y.graph(), y.hardware(), y.shape(), 0.)
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,185 @@
+use crate::conv::{Conv2dGeometry, Conv2dOptions};
+use crate::operator::*;
+
+/// Conv2d operator: 2-dimensional convolution of images x by kernels w.
+pub(crate) struct Conv2d {
+    /// Convolution options.
+    options: Conv2dOptions,
+}
+
+impl Conv2d {
+    pub(crate) fn new(options: Conv2dOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl<'hw> Operator<'hw> for Conv2d {
+    fn name(&self) -> String {
+        String::from("Conv2d")
+    }
+
+    fn input_size(&self) -> usize {
+        2
+    }
+
+    fn encode_attributes(&self, encoder: &mut Encoder) -> Result<()> {
+        serialize::write_conv2d_options(encoder, &self.options);
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        Ok(Conv2dGeometry::conv2d(inputs[0], inputs[1], &self.options)?.output_shape())
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        inputs[0].conv2d_f32(inputs[1], &self.options)
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(Conv2dGrad {
+            options: self.options,
+        }))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(Conv2dForwardGrad {
+            options: self.options,
+        }))
+    }
+}
+
+/// Gradient for Conv2d.
+struct Conv2dGrad {
+    options: Conv2dOptions,
+}
+
+impl Gradient for Conv2dGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        vec![
+            needs_grad[0]
+                .then(|| conv2d_backward_input(gy, x[1], spatial_size(x[0]), self.options)),
+            needs_grad[1]
+                .then(|| conv2d_backward_weight(x[0], gy, spatial_size(x[1]), self.options)),
+        ]
+    }
+}
+
+/// Forward-mode gradient for Conv2d.
+struct Conv2dForwardGrad {
+    options: Conv2dOptions,
+}
+
+impl ForwardGradient for Conv2dForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        dx[0].conv2d(x[1], self.options) + x[0].conv2d(dx[1], self.options)
+    }
+}
+
+/// Obtains the last 2 dimensions of a 4-dimensional node.
+pub(crate) fn spatial_size(node: Node) -> [usize; 2] {
+    let [_, _, height, width] = node.shape().as_array4().unwrap();
+    [height, width]
+}
+
+/// Registers `Conv2dBackwardInput` operation: the gradient of Conv2d with respect to the input.
+pub(crate) fn conv2d_backward_input<'hw: 'op, 'op: 'g, 'g>(
+    gy: Node<'hw, 'op, 'g>,
+    w: Node<'hw, 'op, 'g>,
+    in_size: [usize; 2],
+    options: Conv2dOptions,
+) -> Node<'hw, 'op, 'g> {
+    Node::apply(
+        gy.graph(),
+        Box::new(conv2d_backward_input::Conv2dBackwardInput::new(
+            in_size, options,
+        )),
+        &[gy, w],
+    )
+    .unwrap()
+}
+
+/// Registers `Conv2dBackwardWeight` operation: the gradient of Conv2d with respect to the kernel.
+pub(crate) fn conv2d_backward_weight<'hw: 'op, 'op: 'g, 'g>(
+    x: Node<'hw, 'op, 'g>,
+    gy: Node<'hw, 'op, 'g>,
+    kernel_size: [usize; 2],
+    options: Conv2dOptions,
+) -> Node<'hw, 'op, 'g> {
+    Node::apply(
+        x.graph(),
+        Box::new(conv2d_backward_weight::Conv2dBackwardWeight::new(
+            kernel_size,
+            options,
+        )),
+        &[x, gy],
+    )
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::conv2d::*;
+
+    #[test]
+    fn test_properties() {
+        let op = Conv2d::new(Conv2dOptions::default());
+        assert_eq!(op.name(), "Conv2d");
+        assert_eq!(op.input_size(), 2);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = Conv2d::new(Conv2dOptions::default());
+        assert_eq!(op.perform_shape(&[&Shape::new([2, 3, 5, 4]), &Shape::new([6, 3, 2, 2])]), Ok(Shape::new([2, 6, 4, 3])));
+        assert_eq!(op.perform_shape(&[&Shape::new([0, 3, 5, 4]), &Shape::new([0, 3, 5, 4])]), Ok(Shape::new([0, 0, 1, 1])));
+        assert!(op.perform_shape(&[&Shape::new([2, 3, 5, 4]), &Shape::new([6, 2, 2, 2])]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([2, 3, 5, 4]), &Shape::new([6, 3, 6, 2])]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([3, 5, 4]), &Shape::new([6, 3, 2])]).is_err());
+
+        let op = Conv2d::new(Conv2dOptions { stride: [2, 3], padding: [1, 1], dilation: [2, 1], groups: 3 });
+        assert_eq!(op.perform_shape(&[&Shape::new([2, 3, 5, 4]), &Shape::new([6, 1, 2, 2])]), Ok(Shape::new([2, 6, 3, 2])));
+        assert!(op.perform_shape(&[&Shape::new([2, 3, 5, 4]), &Shape::new([6, 3, 2, 2])]).is_err());
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw1 = RefCell::new(CpuHardware::new());
+        let hw2 = RefCell::new(CpuHardware::new());
+        let op = Conv2d::new(Conv2dOptions::default());
+
+        assert!(ptr::eq(op.perform_hardware(&[&hw1, &hw1]).unwrap(), &hw1));
+        assert!(op.perform_hardware(&[&hw1, &hw2]).is_err());
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = Conv2d::new(Conv2dOptions::default());
+        let x =
+            Array::constant_f32(&hw, Shape::new([1, 1, 2, 3]), &[1., 2., 3., 4., 5., 6.]).unwrap();
+        let w = Array::constant_f32(&hw, Shape::new([2, 1, 1, 2]), &[1., 1., 1., -1.]).unwrap();
+        let observed = op.perform(&[&x, &w]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([1, 2, 2, 2]));
+        assert_eq!(
+            observed.get_values_f32(),
+            vec![3., 5., 9., 11., -1., -1., -1., -1.]
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use crate::conv::{Conv2dGeometry, Conv2dOptions};
+use crate::operator::conv2d::{conv2d_backward_input, conv2d_backward_weight};
+use crate::operator::*;
+
+/// Conv2dBackwardInput operator: gradient of Conv2d with respect to the input, calculated from the
+/// output gradient gy and kernels w.
+pub(crate) struct Conv2dBackwardInput {
+    /// Height and width of the input of the convolution.
+    in_size: [usize; 2],
+
+    /// Convolution options.
+    options: Conv2dOptions,
+}
+
+impl Conv2dBackwardInput {
+    pub(crate) fn new(in_size: [usize; 2], options: Conv2dOptions) -> Self {
+        Self { in_size, options }
+    }
+}
+
+impl<'hw> Operator<'hw> for Conv2dBackwardInput {
+    fn name(&self) -> String {
+        String::from("Conv2dBackwardInput")
+    }
+
+    fn input_size(&self) -> usize {
+        2
+    }
+
+    fn encode_attributes(&self, encoder: &mut Encoder) -> Result<()> {
+        for &size in &self.in_size {
+            encoder.write_usize(size);
+        }
+        serialize::write_conv2d_options(encoder, &self.options);
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        Ok(Conv2dGeometry::conv2d_backward_input(
+            inputs[0],
+            inputs[1],
+            self.in_size,
+            &self.options,
+        )?
+        .input_shape())
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        inputs[0].conv2d_backward_input_f32(inputs[1], self.in_size, &self.options)
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(Conv2dBackwardInputGrad {
+            options: self.options,
+        }))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(Conv2dBackwardInputForwardGrad {
+            in_size: self.in_size,
+            options: self.options,
+        }))
+    }
+}
+
+/// Gradient for Conv2dBackwardInput.
+struct Conv2dBackwardInputGrad {
+    options: Conv2dOptions,
+}
+
+impl Gradient for Conv2dBackwardInputGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        let kernel_size = conv2d::spatial_size(x[1]);
+        vec![
+            needs_grad[0].then(|| gy.conv2d(x[1], self.options)),
+            needs_grad[1].then(|| conv2d_backward_weight(gy, x[0], kernel_size, self.options)),
+        ]
+    }
+}
+
+/// Forward-mode gradient for Conv2dBackwardInput.
+struct Conv2dBackwardInputForwardGrad {
+    in_size: [usize; 2],
+    options: Conv2dOptions,
+}
+
+impl ForwardGradient for Conv2dBackwardInputForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        conv2d_backward_input(dx[0], x[1], self.in_size, self.options)
+            + conv2d_backward_input(x[0], dx[1], self.in_size, self.options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::conv2d_backward_input::*;
+
+    #[test]
+    fn test_properties() {
+        let op = Conv2dBackwardInput::new([3, 3], Conv2dOptions::default());
+        assert_eq!(op.name(), "Conv2dBackwardInput");
+        assert_eq!(op.input_size(), 2);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = Conv2dBackwardInput::new([5, 4], Conv2dOptions::default());
+        assert_eq!(op.perform_shape(&[&Shape::new([2, 6, 4, 3]), &Shape::new([6, 3, 2, 2])]), Ok(Shape::new([2, 3, 5, 4])));
+        assert!(op.perform_shape(&[&Shape::new([2, 6, 4, 4]), &Shape::new([6, 3, 2, 2])]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([2, 5, 4, 3]), &Shape::new([6, 3, 2, 2])]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([2, 6, 4]), &Shape::new([6, 3, 2, 2])]).is_err());
+
+        let op = Conv2dBackwardInput::new([6, 4], Conv2dOptions { stride: [2, 2], ..Default::default() });
+        assert_eq!(op.perform_shape(&[&Shape::new([2, 6, 3, 2]), &Shape::new([6, 3, 2, 2])]), Ok(Shape::new([2, 3, 6, 4])));
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw1 = RefCell::new(CpuHardware::new());
+        let hw2 = RefCell::new(CpuHardware::new());
+        let op = Conv2dBackwardInput::new([3, 3], Conv2dOptions::default());
+
+        assert!(ptr::eq(op.perform_hardware(&[&hw1, &hw1]).unwrap(), &hw1));
+        assert!(op.perform_hardware(&[&hw1, &hw2]).is_err());
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = Conv2dBackwardInput::new([2, 3], Conv2dOptions::default());
+        let gy = Array::constant_f32(
+            &hw,
+            Shape::new([1, 2, 2, 2]),
+            &[1., 0., 0., 1., 1., 1., 1., 1.],
+        )
+        .unwrap();
+        let w = Array::constant_f32(&hw, Shape::new([2, 1, 1, 2]), &[1., 1., 1., -1.]).unwrap();
+        let observed = op.perform(&[&gy, &w]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([1, 1, 2, 3]));
+        assert_eq!(observed.get_values_f32(), vec![2., 1., -1., 1., 1., 0.]);
+    }
+}
@@ -0,0 +1,164 @@
+use crate::conv::{Conv2dGeometry, Conv2dOptions};
+use crate::operator::conv2d::{conv2d_backward_input, conv2d_backward_weight};
+use crate::operator::*;
+
+/// Conv2dBackwardWeight operator: gradient of Conv2d with respect to the kernels, calculated from
+/// the input x and the output gradient gy.
+pub(crate) struct Conv2dBackwardWeight {
+    /// Height and width of the kernels.
+    kernel_size: [usize; 2],
+
+    /// Convolution options.
+    options: Conv2dOptions,
+}
+
+impl Conv2dBackwardWeight {
+    pub(crate) fn new(kernel_size: [usize; 2], options: Conv2dOptions) -> Self {
+        Self {
+            kernel_size,
+            options,
+        }
+    }
+}
+
+impl<'hw> Operator<'hw> for Conv2dBackwardWeight {
+    fn name(&self) -> String {
+        String::from("Conv2dBackwardWeight")
+    }
+
+    fn input_size(&self) -> usize {
+        2
+    }
+
+    fn encode_attributes(&self, encoder: &mut Encoder) -> Result<()> {
+        for &size in &self.kernel_size {
+            encoder.write_usize(size);
+        }
+        serialize::write_conv2d_options(encoder, &self.options);
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        Ok(Conv2dGeometry::conv2d_backward_weight(
+            inputs[0],
+            inputs[1],
+            self.kernel_size,
+            &self.options,
+        )?
+        .weight_shape())
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        inputs[0].conv2d_backward_weight_f32(inputs[1], self.kernel_size, &self.options)
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(Conv2dBackwardWeightGrad {
+            options: self.options,
+        }))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(Conv2dBackwardWeightForwardGrad {
+            kernel_size: self.kernel_size,
+            options: self.options,
+        }))
+    }
+}
+
+/// Gradient for Conv2dBackwardWeight.
+struct Conv2dBackwardWeightGrad {
+    options: Conv2dOptions,
+}
+
+impl Gradient for Conv2dBackwardWeightGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        let in_size = conv2d::spatial_size(x[0]);
+        vec![
+            needs_grad[0].then(|| conv2d_backward_input(x[1], gy, in_size, self.options)),
+            needs_grad[1].then(|| x[0].conv2d(gy, self.options)),
+        ]
+    }
+}
+
+/// Forward-mode gradient for Conv2dBackwardWeight.
+struct Conv2dBackwardWeightForwardGrad {
+    kernel_size: [usize; 2],
+    options: Conv2dOptions,
+}
+
+impl ForwardGradient for Conv2dBackwardWeightForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        conv2d_backward_weight(dx[0], x[1], self.kernel_size, self.options)
+            + conv2d_backward_weight(x[0], dx[1], self.kernel_size, self.options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::conv2d_backward_weight::*;
+
+    #[test]
+    fn test_properties() {
+        let op = Conv2dBackwardWeight::new([2, 2], Conv2dOptions::default());
+        assert_eq!(op.name(), "Conv2dBackwardWeight");
+        assert_eq!(op.input_size(), 2);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = Conv2dBackwardWeight::new([2, 2], Conv2dOptions::default());
+        assert_eq!(op.perform_shape(&[&Shape::new([2, 3, 5, 4]), &Shape::new([2, 6, 4, 3])]), Ok(Shape::new([6, 3, 2, 2])));
+        assert!(op.perform_shape(&[&Shape::new([2, 3, 5, 4]), &Shape::new([2, 6, 4, 4])]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([1, 3, 5, 4]), &Shape::new([2, 6, 4, 3])]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([2, 3, 5]), &Shape::new([2, 6, 4, 3])]).is_err());
+
+        let op = Conv2dBackwardWeight::new([2, 2], Conv2dOptions { groups: 3, ..Default::default() });
+        assert_eq!(op.perform_shape(&[&Shape::new([2, 3, 5, 4]), &Shape::new([2, 6, 4, 3])]), Ok(Shape::new([6, 1, 2, 2])));
+        assert!(op.perform_shape(&[&Shape::new([2, 3, 5, 4]), &Shape::new([2, 4, 4, 3])]).is_err());
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw1 = RefCell::new(CpuHardware::new());
+        let hw2 = RefCell::new(CpuHardware::new());
+        let op = Conv2dBackwardWeight::new([2, 2], Conv2dOptions::default());
+
+        assert!(ptr::eq(op.perform_hardware(&[&hw1, &hw1]).unwrap(), &hw1));
+        assert!(op.perform_hardware(&[&hw1, &hw2]).is_err());
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = Conv2dBackwardWeight::new([1, 2], Conv2dOptions::default());
+        let x =
+            Array::constant_f32(&hw, Shape::new([1, 1, 2, 3]), &[1., 2., 3., 4., 5., 6.]).unwrap();
+        let gy = Array::constant_f32(
+            &hw,
+            Shape::new([1, 2, 2, 2]),
+            &[1., 0., 0., 1., 1., 1., 1., 1.],
+        )
+        .unwrap();
+        let observed = op.perform(&[&x, &gy]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([2, 1, 1, 2]));
+        assert_eq!(observed.get_values_f32(), vec![6., 8., 12., 16.]);
+    }
+}
@@ -0,0 +1,141 @@
+use crate::operator::*;
+use std::rc::Rc;
+
+/// Type of the user-defined backward function used by `CustomGradient`.
+///
+/// The function takes input nodes `x`, the output node `y` and its gradient `gy`, and returns the
+/// gradients of `x`.
+pub(crate) type BackwardFn = dyn for<'hw, 'op, 'g> Fn(
+    &[Node<'hw, 'op, 'g>],
+    Node<'hw, 'op, 'g>,
+    Node<'hw, 'op, 'g>,
+) -> Vec<Node<'hw, 'op, 'g>>;
+
+/// CustomGradient operator: returns the last input as is, while the gradient is calculated by the
+/// user-defined backward function.
+///
+/// This operator takes N + 1 inputs: the first N inputs are the arguments of the user-defined
+/// function, and the last input is the result of the function. The result must not propagate
+/// gradients by itself, i.e., it is typically wrapped by `StopGradient`.
+pub(crate) struct CustomGradient {
+    /// Number of the arguments, not including the result.
+    num_args: usize,
+
+    /// User-defined backward function.
+    backward_fn: Rc<BackwardFn>,
+}
+
+impl CustomGradient {
+    pub(crate) fn new(num_args: usize, backward_fn: Rc<BackwardFn>) -> Self {
+        Self {
+            num_args,
+            backward_fn,
+        }
+    }
+}
+
+impl<'hw> Operator<'hw> for CustomGradient {
+    fn name(&self) -> String {
+        String::from("CustomGradient")
+    }
+
+    fn input_size(&self) -> usize {
+        self.num_args + 1
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        Ok(inputs[self.num_args].clone())
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        Ok(inputs[self.num_args].clone())
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(CustomGradientGrad {
+            num_args: self.num_args,
+            backward_fn: self.backward_fn.clone(),
+        }))
+    }
+}
+
+/// Gradient for CustomGradient.
+struct CustomGradientGrad {
+    num_args: usize,
+    backward_fn: Rc<BackwardFn>,
+}
+
+impl Gradient for CustomGradientGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        _needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        let args = &x[..self.num_args];
+        let gx = (self.backward_fn)(args, y, gy);
+        assert_eq!(
+            gx.len(),
+            self.num_args,
+            "Custom backward function must return the same number of gradients as its arguments."
+        );
+        assert!(
+            args.iter()
+                .zip(gx.iter())
+                .all(|(x, gx)| x.shape() == gx.shape()),
+            "Custom backward function must return gradients with the same shapes as its arguments."
+        );
+
+        // The result of the user-defined function does not obtain any gradient.
+        gx.into_iter().map(Some).chain([None]).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array::IntoArray;
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::custom_gradient::*;
+
+    fn new_op(num_args: usize) -> CustomGradient {
+        CustomGradient::new(num_args, Rc::new(|_x, _y, gy| vec![gy]))
+    }
+
+    #[test]
+    fn test_properties() {
+        let op = new_op(1);
+        assert_eq!(op.name(), "CustomGradient");
+        assert_eq!(op.input_size(), 2);
+        assert_eq!(new_op(0).input_size(), 1);
+        assert_eq!(new_op(3).input_size(), 4);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = new_op(1);
+        assert_eq!(op.perform_shape(&[&Shape::new([]), &Shape::new([])]), Ok(Shape::new([])));
+        assert_eq!(op.perform_shape(&[&Shape::new([3]), &Shape::new([])]), Ok(Shape::new([])));
+        assert_eq!(op.perform_shape(&[&Shape::new([]), &Shape::new([3])]), Ok(Shape::new([3])));
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = new_op(1);
+
+        assert!(ptr::eq(op.perform_hardware(&[&hw, &hw]).unwrap(), &hw));
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = new_op(1);
+        let arg = 1f32.into_array(&hw);
+        let result = 2f32.into_array(&hw);
+        let observed = op.perform(&[&arg, &result]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([]));
+        assert_eq!(observed.get_scalar_f32(), Ok(2.));
+    }
+}
@@ -17,6 +17,14 @@ impl<'hw> Operator<'hw> for Div {
         2
     }
 
+    fn encode_attributes(&self, _encoder: &mut Encoder) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
     fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
         inputs[0].elementwise(inputs[1])
     }
@@ -28,6 +36,10 @@ impl<'hw> Operator<'hw> for Div {
     fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
         Some(Box::new(DivGrad {}))
     }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(DivForwardGrad {}))
+    }
 }
 
 /// Gradient for Div.
@@ -39,9 +51,27 @@ impl Gradient for DivGrad {
         x: &[Node<'hw, 'op, 'g>],
         y: Node<'hw, 'op, 'g>,
         gy: Node<'hw, 'op, 'g>,
-    ) -> Vec<Node<'hw, 'op, 'g>> {
+        needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
         let gx0 = gy / x[1];
-        vec![gx0, -y * gx0]
+        vec![
+            needs_grad[0].then_some(gx0),
+            needs_grad[1].then(|| -y * gx0),
+        ]
+    }
+}
+
+/// Forward-mode gradient for Div.
+struct DivForwardGrad;
+
+impl ForwardGradient for DivForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        (dx[0] - y * dx[1]) / x[1]
     }
 }
 
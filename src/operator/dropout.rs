@@ -0,0 +1,188 @@
+use crate::operator::*;
+
+/// Dropout operator: each element of the input is zeroed out with probability p, and the
+/// remaining elements are scaled by 1 / (1 - p).
+///
+/// The mask is determined only by the seed and the offset of the random stream, so that the
+/// gradient reproduces the same mask without keeping it on the memory.
+pub(crate) struct Dropout {
+    /// Probability to drop each element.
+    p: f32,
+
+    /// Seed of the random stream.
+    seed: u64,
+
+    /// Index of the first element in the random stream.
+    offset: u64,
+}
+
+impl Dropout {
+    pub(crate) fn new(p: f32, seed: u64, offset: u64) -> Self {
+        Self { p, seed, offset }
+    }
+}
+
+impl<'hw> Operator<'hw> for Dropout {
+    fn name(&self) -> String {
+        String::from("Dropout")
+    }
+
+    fn input_size(&self) -> usize {
+        1
+    }
+
+    fn encode_attributes(&self, encoder: &mut Encoder) -> Result<()> {
+        encoder.write_f32(self.p);
+        encoder.write_u64(self.seed);
+        encoder.write_u64(self.offset);
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        Ok(inputs[0].clone())
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        let keep = 1. - self.p;
+        let scale = if keep > 0. { 1. / keep } else { 0. };
+        let shape = inputs[0].shape().clone();
+        let mut mask = Array::random_bernoulli_f32(
+            inputs[0].hardware(),
+            shape.clone(),
+            self.seed,
+            self.offset,
+            keep,
+        );
+        mask.mul_assign_f32(&Array::fill_colocated_f32(&mask, shape, scale))?;
+        inputs[0].elementwise_mul_f32(&mask)
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(DropoutGrad {
+            p: self.p,
+            seed: self.seed,
+            offset: self.offset,
+        }))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(DropoutForwardGrad {
+            p: self.p,
+            seed: self.seed,
+            offset: self.offset,
+        }))
+    }
+}
+
+/// Gradient for Dropout.
+/// The same mask is applied to the gradient.
+struct DropoutGrad {
+    p: f32,
+    seed: u64,
+    offset: u64,
+}
+
+impl Gradient for DropoutGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        _x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        _needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        vec![Some(
+            Node::apply(
+                gy.graph(),
+                Box::new(Dropout::new(self.p, self.seed, self.offset)),
+                &[gy],
+            )
+            .unwrap(),
+        )]
+    }
+}
+
+/// Forward-mode gradient for Dropout.
+/// The same mask is applied to the tangent.
+struct DropoutForwardGrad {
+    p: f32,
+    seed: u64,
+    offset: u64,
+}
+
+impl ForwardGradient for DropoutForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        _x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        Node::apply(
+            dx[0].graph(),
+            Box::new(Dropout::new(self.p, self.seed, self.offset)),
+            &[dx[0]],
+        )
+        .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array::IntoArray;
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::dropout::*;
+
+    #[test]
+    fn test_properties() {
+        let op = Dropout::new(0.5, 1, 0);
+        assert_eq!(op.name(), "Dropout");
+        assert_eq!(op.input_size(), 1);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = Dropout::new(0.5, 1, 0);
+        assert_eq!(op.perform_shape(&[&Shape::new([])]), Ok(Shape::new([])));
+        assert_eq!(op.perform_shape(&[&Shape::new([0])]), Ok(Shape::new([0])));
+        assert_eq!(op.perform_shape(&[&Shape::new([3])]), Ok(Shape::new([3])));
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = Dropout::new(0.5, 1, 0);
+
+        assert!(ptr::eq(op.perform_hardware(&[&hw]).unwrap(), &hw));
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = Dropout::new(0.75, 1, 0);
+        let input = vec![2f32; 100].into_array(&hw);
+        let observed = op.perform(&[&input]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([100]));
+
+        // Kept elements are scaled by 1 / (1 - p).
+        let mask = Array::random_bernoulli_f32(&hw, Shape::new([100]), 1, 0, 0.25);
+        let expected = mask
+            .get_values_f32()
+            .iter()
+            .map(|m| m * 8.)
+            .collect::<Vec<_>>();
+        assert_eq!(observed.get_values_f32(), expected);
+    }
+
+    #[test]
+    fn test_perform_drop_all() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = Dropout::new(1., 1, 0);
+        let input = vec![2f32; 10].into_array(&hw);
+        let observed = op.perform(&[&input]).unwrap();
+        assert_eq!(observed.get_values_f32(), vec![0.; 10]);
+    }
+}
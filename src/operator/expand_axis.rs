@@ -0,0 +1,129 @@
+use crate::operator::*;
+
+/// ExpandAxis operator: repeats values along a new axis.
+pub(crate) struct ExpandAxis {
+    /// Index of the new axis in the output.
+    axis: usize,
+
+    /// Size of the new axis.
+    size: usize,
+}
+
+impl ExpandAxis {
+    pub(crate) fn new(axis: usize, size: usize) -> Self {
+        Self { axis, size }
+    }
+}
+
+impl<'hw> Operator<'hw> for ExpandAxis {
+    fn name(&self) -> String {
+        String::from("ExpandAxis")
+    }
+
+    fn input_size(&self) -> usize {
+        1
+    }
+
+    fn encode_attributes(&self, encoder: &mut Encoder) -> Result<()> {
+        encoder.write_usize(self.axis);
+        encoder.write_usize(self.size);
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        inputs[0].insert_axis(self.axis, self.size)
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        inputs[0].expand_axis_f32(self.axis, self.size)
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(ExpandAxisGrad { axis: self.axis }))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(ExpandAxisForwardGrad {
+            axis: self.axis,
+            size: self.size,
+        }))
+    }
+}
+
+/// Gradient for ExpandAxis.
+struct ExpandAxisGrad {
+    axis: usize,
+}
+
+impl Gradient for ExpandAxisGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        _x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        _needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        vec![Some(gy.sum_axis(self.axis))]
+    }
+}
+
+/// Forward-mode gradient for ExpandAxis.
+struct ExpandAxisForwardGrad {
+    axis: usize,
+    size: usize,
+}
+
+impl ForwardGradient for ExpandAxisForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        _x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        dx[0].expand_axis(self.axis, self.size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::expand_axis::*;
+
+    #[test]
+    fn test_properties() {
+        let op = ExpandAxis::new(0, 3);
+        assert_eq!(op.name(), "ExpandAxis");
+        assert_eq!(op.input_size(), 1);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        assert_eq!(ExpandAxis::new(0, 3).perform_shape(&[&Shape::new([])]), Ok(Shape::new([3])));
+        assert_eq!(ExpandAxis::new(0, 3).perform_shape(&[&Shape::new([2])]), Ok(Shape::new([3, 2])));
+        assert_eq!(ExpandAxis::new(1, 3).perform_shape(&[&Shape::new([2])]), Ok(Shape::new([2, 3])));
+        assert!(ExpandAxis::new(2, 3).perform_shape(&[&Shape::new([2])]).is_err());
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = ExpandAxis::new(0, 3);
+
+        assert!(ptr::eq(op.perform_hardware(&[&hw]).unwrap(), &hw));
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = ExpandAxis::new(1, 2);
+        let input = Array::constant_f32(&hw, Shape::new([2]), &[1., 2.]).unwrap();
+        let observed = op.perform(&[&input]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([2, 2]));
+        assert_eq!(observed.get_values_f32(), vec![1., 1., 2., 2.]);
+    }
+}
@@ -31,6 +31,16 @@ impl<'hw> Operator<'hw> for Fill<'hw> {
         0
     }
 
+    fn encode_attributes(&self, encoder: &mut Encoder) -> Result<()> {
+        encoder.write_shape(&self.shape);
+        encoder.write_f32(self.value);
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
     fn perform_shape(&self, _inputs: &[&Shape]) -> Result<Shape> {
         Ok(self.shape.clone())
     }
@@ -49,6 +59,25 @@ impl<'hw> Operator<'hw> for Fill<'hw> {
             self.value,
         ))
     }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(FillForwardGrad {}))
+    }
+}
+
+/// Forward-mode gradient for Fill.
+struct FillForwardGrad;
+
+impl ForwardGradient for FillForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        _x: &[Node<'hw, 'op, 'g>],
+        y: Node<'hw, 'op, 'g>,
+        _dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        // The output does not depend on any variables.
+        Node::fill(y.graph(), y.hardware(), y.shape(), 0.)
+    }
 }
 
 #[cfg(test)]
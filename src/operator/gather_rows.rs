@@ -0,0 +1,143 @@
+use crate::operator::*;
+
+/// GatherRows operator: y[i, ...] = table[indices[i], ...].
+///
+/// The second input holds row indices as floating-point values. No gradient is propagated to the
+/// indices.
+pub(crate) struct GatherRows;
+
+impl GatherRows {
+    pub(crate) fn new() -> Self {
+        Self {}
+    }
+}
+
+impl<'hw> Operator<'hw> for GatherRows {
+    fn name(&self) -> String {
+        String::from("GatherRows")
+    }
+
+    fn input_size(&self) -> usize {
+        2
+    }
+
+    fn encode_attributes(&self, _encoder: &mut Encoder) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        inputs[0].gather_rows(inputs[1])
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        inputs[0].gather_rows_f32(inputs[1])
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(GatherRowsGrad {}))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(GatherRowsForwardGrad {}))
+    }
+}
+
+/// Gradient for GatherRows.
+struct GatherRowsGrad;
+
+impl Gradient for GatherRowsGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        let num_rows = x[0].shape().dimension(0).unwrap();
+        // Indices are locally constant.
+        vec![
+            needs_grad[0].then(|| {
+                Node::apply(
+                    gy.graph(),
+                    Box::new(scatter_add_rows::ScatterAddRows::new(num_rows)),
+                    &[gy, x[1]],
+                )
+                .unwrap()
+            }),
+            None,
+        ]
+    }
+}
+
+/// Forward-mode gradient for GatherRows.
+struct GatherRowsForwardGrad;
+
+impl ForwardGradient for GatherRowsForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        dx[0].gather_rows(x[1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::gather_rows::*;
+
+    #[test]
+    fn test_properties() {
+        let op = GatherRows::new();
+        assert_eq!(op.name(), "GatherRows");
+        assert_eq!(op.input_size(), 2);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = GatherRows::new();
+        assert_eq!(op.perform_shape(&[&Shape::new([5, 2]), &Shape::new([3])]), Ok(Shape::new([3, 2])));
+        assert_eq!(op.perform_shape(&[&Shape::new([5, 2]), &Shape::new([])]), Ok(Shape::new([2])));
+        assert!(op.perform_shape(&[&Shape::new([]), &Shape::new([3])]).is_err());
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw1 = RefCell::new(CpuHardware::new());
+        let hw2 = RefCell::new(CpuHardware::new());
+        let op = GatherRows::new();
+
+        assert!(ptr::eq(op.perform_hardware(&[&hw1, &hw1]).unwrap(), &hw1));
+        assert!(op.perform_hardware(&[&hw1, &hw2]).is_err());
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = GatherRows::new();
+        let table = Array::constant_f32(&hw, Shape::new([2, 2]), &[1., 2., 3., 4.]).unwrap();
+        let indices = Array::constant_f32(&hw, Shape::new([3]), &[1., 0., 1.]).unwrap();
+        let observed = op.perform(&[&table, &indices]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([3, 2]));
+        assert_eq!(observed.get_values_f32(), vec![3., 4., 1., 2., 3., 4.]);
+    }
+
+    #[test]
+    fn test_perform_out_of_range() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = GatherRows::new();
+        let table = Array::fill_f32(&hw, Shape::new([2, 2]), 0.);
+        let indices = Array::fill_f32(&hw, Shape::new([1]), 2.);
+        assert!(matches!(
+            op.perform(&[&table, &indices]),
+            Err(Error::OutOfRange(_))
+        ));
+    }
+}
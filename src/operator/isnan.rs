@@ -0,0 +1,86 @@
+use crate::operator::*;
+
+/// IsNan operator: y = 1 if x is NaN, 0 otherwise.
+///
+/// The derivative of this function is 0 almost everywhere. This operator does not define the
+/// reverse-mode gradient, and its forward-mode gradient is always 0.
+pub(crate) struct IsNan;
+
+impl IsNan {
+    pub(crate) fn new() -> Self {
+        Self {}
+    }
+}
+
+impl<'hw> Operator<'hw> for IsNan {
+    fn name(&self) -> String {
+        String::from("IsNan")
+    }
+
+    fn input_size(&self) -> usize {
+        1
+    }
+
+    fn encode_attributes(&self, _encoder: &mut Encoder) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        Ok(inputs[0].clone())
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        Ok(inputs[0].elementwise_isnan_f32())
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(ZeroForwardGrad))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::isnan::*;
+
+    #[test]
+    fn test_properties() {
+        let op = IsNan::new();
+        assert_eq!(op.name(), "IsNan");
+        assert_eq!(op.input_size(), 1);
+        assert!(op.get_gradient_fn().is_none());
+        assert!(op.get_forward_gradient_fn().is_some());
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = IsNan::new();
+        assert_eq!(op.perform_shape(&[&Shape::new([])]), Ok(Shape::new([])));
+        assert_eq!(op.perform_shape(&[&Shape::new([0])]), Ok(Shape::new([0])));
+        assert_eq!(op.perform_shape(&[&Shape::new([3])]), Ok(Shape::new([3])));
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = IsNan::new();
+
+        assert!(ptr::eq(op.perform_hardware(&[&hw]).unwrap(), &hw));
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = IsNan::new();
+        let input =
+            Array::constant_f32(&hw, Shape::new([4]), &[1., f32::NAN, f32::INFINITY, 0.]).unwrap();
+        let observed = op.perform(&[&input]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([4]));
+        assert_eq!(observed.get_values_f32(), vec![0., 1., 0., 0.]);
+    }
+}
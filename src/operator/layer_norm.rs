@@ -0,0 +1,206 @@
+use crate::operator::*;
+
+/// LayerNorm operator: normalization of x over the last axis, scaled by gamma and shifted by beta.
+pub(crate) struct LayerNorm {
+    /// Small value added to the variance.
+    epsilon: f32,
+}
+
+impl LayerNorm {
+    pub(crate) fn new(epsilon: f32) -> Self {
+        Self { epsilon }
+    }
+}
+
+impl<'hw> Operator<'hw> for LayerNorm {
+    fn name(&self) -> String {
+        String::from("LayerNorm")
+    }
+
+    fn input_size(&self) -> usize {
+        3
+    }
+
+    fn encode_attributes(&self, encoder: &mut Encoder) -> Result<()> {
+        encoder.write_f32(self.epsilon);
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        check_same_shape(inputs[1], inputs[2])?;
+        inputs[0].feature_layout(inputs[1])?;
+        Ok(inputs[0].clone())
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        inputs[0].layer_norm_f32(inputs[1], inputs[2], self.epsilon)
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(LayerNormGrad {
+            epsilon: self.epsilon,
+        }))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(LayerNormForwardGrad {
+            epsilon: self.epsilon,
+        }))
+    }
+}
+
+/// Gradient for LayerNorm.
+struct LayerNormGrad {
+    epsilon: f32,
+}
+
+impl Gradient for LayerNormGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        vec![
+            needs_grad[0].then(|| layer_norm_backward(x[0], x[1], gy, self.epsilon)),
+            needs_grad[1].then(|| {
+                let xhat = x[0].layer_norm(fill_like(x[1], 1.), fill_like(x[2], 0.), self.epsilon);
+                reduce_features(gy * xhat)
+            }),
+            needs_grad[2].then(|| reduce_features(gy)),
+        ]
+    }
+}
+
+/// Forward-mode gradient for LayerNorm.
+struct LayerNormForwardGrad {
+    epsilon: f32,
+}
+
+impl ForwardGradient for LayerNormForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        let ones = fill_like(x[1], 1.);
+        let xhat = x[0].layer_norm(ones, fill_like(x[2], 0.), self.epsilon);
+        let dxhat = layer_norm_backward(x[0], ones, dx[0], self.epsilon);
+        let shape = x[0].shape();
+        dxhat * broadcast_features(x[1], &shape)
+            + broadcast_features(dx[1], &shape) * xhat
+            + broadcast_features(dx[2], &shape)
+    }
+}
+
+/// Checks that two parameters have the same shape.
+pub(crate) fn check_same_shape(a: &Shape, b: &Shape) -> Result<()> {
+    if a != b {
+        return Err(Error::InvalidShape(format!(
+            "Shapes must be the same, but got {} and {}.",
+            a, b
+        )));
+    }
+    Ok(())
+}
+
+/// Creates a `Node` filled by a single value with the same shape and hardware as `like`.
+pub(crate) fn fill_like<'hw: 'op, 'op: 'g, 'g>(
+    like: Node<'hw, 'op, 'g>,
+    value: f32,
+) -> Node<'hw, 'op, 'g> {
+    Node::fill(like.graph(), like.hardware(), like.shape(), value)
+}
+
+/// Broadcasts per-feature values of the shape `[size]` to `shape`, whose last dimension is `size`.
+pub(crate) fn broadcast_features<'hw: 'op, 'op: 'g, 'g>(
+    node: Node<'hw, 'op, 'g>,
+    shape: &Shape,
+) -> Node<'hw, 'op, 'g> {
+    let dims = shape.dimensions();
+    dims[..dims.len() - 1]
+        .iter()
+        .enumerate()
+        .fold(node, |node, (axis, &size)| node.expand_axis(axis, size))
+}
+
+/// Sums values over every axis except the last one. This is the adjoint of
+/// `broadcast_features()`.
+pub(crate) fn reduce_features<'hw: 'op, 'op: 'g, 'g>(
+    node: Node<'hw, 'op, 'g>,
+) -> Node<'hw, 'op, 'g> {
+    let num_dimensions = node.shape().num_dimensions();
+    (1..num_dimensions).fold(node, |node, _| node.sum_axis(0))
+}
+
+/// Registers `LayerNormBackward` operation: the gradient of LayerNorm with respect to the input.
+pub(crate) fn layer_norm_backward<'hw: 'op, 'op: 'g, 'g>(
+    x: Node<'hw, 'op, 'g>,
+    gamma: Node<'hw, 'op, 'g>,
+    gy: Node<'hw, 'op, 'g>,
+    epsilon: f32,
+) -> Node<'hw, 'op, 'g> {
+    Node::apply(
+        x.graph(),
+        Box::new(layer_norm_backward::LayerNormBackward::new(epsilon)),
+        &[x, gamma, gy],
+    )
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::layer_norm::*;
+
+    #[test]
+    fn test_properties() {
+        let op = LayerNorm::new(1e-5);
+        assert_eq!(op.name(), "LayerNorm");
+        assert_eq!(op.input_size(), 3);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = LayerNorm::new(1e-5);
+        assert_eq!(op.perform_shape(&[&Shape::new([3]), &Shape::new([3]), &Shape::new([3])]), Ok(Shape::new([3])));
+        assert_eq!(op.perform_shape(&[&Shape::new([2, 4, 3]), &Shape::new([3]), &Shape::new([3])]), Ok(Shape::new([2, 4, 3])));
+        assert_eq!(op.perform_shape(&[&Shape::new([0, 3]), &Shape::new([3]), &Shape::new([3])]), Ok(Shape::new([0, 3])));
+        assert!(op.perform_shape(&[&Shape::new([]), &Shape::new([]), &Shape::new([])]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([2, 3]), &Shape::new([2]), &Shape::new([2])]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([2, 3]), &Shape::new([3]), &Shape::new([2])]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([2, 3]), &Shape::new([2, 3]), &Shape::new([2, 3])]).is_err());
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw1 = RefCell::new(CpuHardware::new());
+        let hw2 = RefCell::new(CpuHardware::new());
+        let op = LayerNorm::new(1e-5);
+
+        assert!(ptr::eq(
+            op.perform_hardware(&[&hw1, &hw1, &hw1]).unwrap(),
+            &hw1
+        ));
+        assert!(op.perform_hardware(&[&hw1, &hw1, &hw2]).is_err());
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = LayerNorm::new(0.);
+        let x = Array::constant_f32(&hw, Shape::new([2, 2]), &[1., 3., 0., 4.]).unwrap();
+        let gamma = Array::constant_f32(&hw, Shape::new([2]), &[2., 1.]).unwrap();
+        let beta = Array::constant_f32(&hw, Shape::new([2]), &[0., 1.]).unwrap();
+        let observed = op.perform(&[&x, &gamma, &beta]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([2, 2]));
+        assert_eq!(observed.get_values_f32(), vec![-2., 2., -2., 2.]);
+    }
+}
@@ -0,0 +1,330 @@
+use crate::operator::batch_norm::{batch_norm_backward, broadcast_channels, reduce_channels};
+use crate::operator::layer_norm::{
+    broadcast_features, fill_like, layer_norm_backward, reduce_features,
+};
+use crate::operator::rms_norm::rms_norm_backward;
+use crate::operator::*;
+
+/// LayerNormBackward operator: gradient of LayerNorm with respect to the input, calculated from
+/// the input x, scaling factors gamma and the output gradient gy.
+///
+/// Gradients of this operator provide higher-order derivatives of LayerNorm.
+pub(crate) struct LayerNormBackward {
+    /// Small value added to the variance.
+    epsilon: f32,
+}
+
+impl LayerNormBackward {
+    pub(crate) fn new(epsilon: f32) -> Self {
+        Self { epsilon }
+    }
+}
+
+impl<'hw> Operator<'hw> for LayerNormBackward {
+    fn name(&self) -> String {
+        String::from("LayerNormBackward")
+    }
+
+    fn input_size(&self) -> usize {
+        3
+    }
+
+    fn encode_attributes(&self, encoder: &mut Encoder) -> Result<()> {
+        encoder.write_f32(self.epsilon);
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        layer_norm::check_same_shape(inputs[0], inputs[2])?;
+        inputs[0].feature_layout(inputs[1])?;
+        Ok(inputs[0].clone())
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        inputs[0].layer_norm_backward_f32(inputs[1], inputs[2], self.epsilon)
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(NormalizationBackwardGrad {
+            normalization: Normalization::Layer,
+            epsilon: self.epsilon,
+        }))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(NormalizationBackwardForwardGrad {
+            normalization: Normalization::Layer,
+            epsilon: self.epsilon,
+        }))
+    }
+}
+
+/// Kinds of normalization whose backward operators share the same form of derivatives.
+///
+/// Each backward operator calculates `gx = N(u)` with `u = gamma * gy`, where
+/// `N(v) = s * (v - mean(v) - xhat * mean(v * xhat))` is the (symmetric) Jacobian of the
+/// normalized input `xhat` with respect to `x`, `s` is the inverse standard deviation, and `mean`
+/// is taken over each group of normalized elements. RmsNorm omits the `mean(v)` term.
+#[derive(Clone, Copy)]
+pub(crate) enum Normalization {
+    /// LayerNorm: groups are the last axis.
+    Layer,
+
+    /// RmsNorm: groups are the last axis, and values are not centered.
+    Rms,
+
+    /// BatchNorm: groups are every axis except the channel axis.
+    Batch,
+}
+
+impl Normalization {
+    /// Creates a parameter-like `Node` (gamma or beta) for `x`, filled by `value`.
+    fn fill_parameter<'hw: 'op, 'op: 'g, 'g>(
+        self,
+        x: Node<'hw, 'op, 'g>,
+        value: f32,
+    ) -> Node<'hw, 'op, 'g> {
+        let shape = x.shape();
+        let dims = shape.dimensions();
+        let size = match self {
+            Self::Layer | Self::Rms => dims[dims.len() - 1],
+            Self::Batch => dims[1],
+        };
+        Node::fill(x.graph(), x.hardware(), Shape::new([size]), value)
+    }
+
+    /// Broadcasts a parameter to `shape`.
+    fn broadcast<'hw: 'op, 'op: 'g, 'g>(
+        self,
+        node: Node<'hw, 'op, 'g>,
+        shape: &Shape,
+    ) -> Node<'hw, 'op, 'g> {
+        match self {
+            Self::Layer | Self::Rms => broadcast_features(node, shape),
+            Self::Batch => broadcast_channels(node, shape),
+        }
+    }
+
+    /// Sums values into the shape of parameters. This is the adjoint of `broadcast()`.
+    fn reduce<'hw: 'op, 'op: 'g, 'g>(self, node: Node<'hw, 'op, 'g>) -> Node<'hw, 'op, 'g> {
+        match self {
+            Self::Layer | Self::Rms => reduce_features(node),
+            Self::Batch => reduce_channels(node),
+        }
+    }
+
+    /// Calculates the mean of each group, broadcasted to the shape of `node`.
+    fn mean<'hw: 'op, 'op: 'g, 'g>(self, node: Node<'hw, 'op, 'g>) -> Node<'hw, 'op, 'g> {
+        let shape = node.shape();
+        let dims = shape.dimensions();
+        let (sum, count) = match self {
+            Self::Layer | Self::Rms => {
+                let axis = dims.len() - 1;
+                let sum = node.sum_axis(axis).expand_axis(axis, dims[axis]);
+                (sum, dims[axis])
+            }
+            Self::Batch => {
+                let sum = broadcast_channels(reduce_channels(node), &shape);
+                (sum, shape.num_elements() / dims[1].max(1))
+            }
+        };
+        sum * fill_like(sum, 1. / count.max(1) as f32)
+    }
+
+    /// Calculates the normalized input `xhat`.
+    fn normalize<'hw: 'op, 'op: 'g, 'g>(
+        self,
+        x: Node<'hw, 'op, 'g>,
+        epsilon: f32,
+    ) -> Node<'hw, 'op, 'g> {
+        let ones = self.fill_parameter(x, 1.);
+        let zeros = self.fill_parameter(x, 0.);
+        match self {
+            Self::Layer => x.layer_norm(ones, zeros, epsilon),
+            Self::Rms => x.rms_norm(ones, epsilon),
+            Self::Batch => x.batch_norm(ones, zeros, epsilon),
+        }
+    }
+
+    /// Calculates the inverse standard deviation `s` of each group, broadcasted to the shape of
+    /// `x`.
+    fn inverse_std<'hw: 'op, 'op: 'g, 'g>(
+        self,
+        x: Node<'hw, 'op, 'g>,
+        epsilon: f32,
+    ) -> Node<'hw, 'op, 'g> {
+        let centered = match self {
+            Self::Layer | Self::Batch => x - self.mean(x),
+            Self::Rms => x,
+        };
+        let variance = self.mean(centered * centered);
+        fill_like(x, 1.) / (variance + fill_like(variance, epsilon)).sqrt()
+    }
+
+    /// Applies the Jacobian `N` of `xhat` with respect to `x` to `v`.
+    fn jacobian<'hw: 'op, 'op: 'g, 'g>(
+        self,
+        x: Node<'hw, 'op, 'g>,
+        v: Node<'hw, 'op, 'g>,
+        epsilon: f32,
+    ) -> Node<'hw, 'op, 'g> {
+        let ones = self.fill_parameter(x, 1.);
+        match self {
+            Self::Layer => layer_norm_backward(x, ones, v, epsilon),
+            Self::Rms => rms_norm_backward(x, ones, v, epsilon),
+            Self::Batch => batch_norm_backward(x, ones, v, epsilon),
+        }
+    }
+
+    /// Calculates the gradients of the backward operator with inputs `x` (x, gamma, gy) and the
+    /// output gradient `ggx`. Only gradients marked by `needs_grad` are constructed.
+    fn backward_gradient<'hw: 'op, 'op: 'g, 'g>(
+        self,
+        x: &[Node<'hw, 'op, 'g>],
+        ggx: Node<'hw, 'op, 'g>,
+        epsilon: f32,
+        needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        let (x, gamma, gy) = (x[0], x[1], x[2]);
+        let broadcast_gamma = || self.broadcast(gamma, &x.shape());
+
+        let gx = needs_grad[0].then(|| {
+            let u = gy * broadcast_gamma();
+            let xhat = self.normalize(x, epsilon);
+            let s = self.inverse_std(x, epsilon);
+
+            // <ggx, N(u)> = s * p, where p depends on x through s and xhat.
+            let b = self.mean(u * xhat);
+            let c = self.mean(ggx * xhat);
+            let mut p = self.mean(ggx * u) - b * c;
+            if !matches!(self, Self::Rms) {
+                p = p - self.mean(u) * self.mean(ggx);
+            }
+            s * self.jacobian(x, -(c * u + b * ggx), epsilon) - s * s * p * xhat
+        });
+
+        // N is symmetric.
+        let gu = (needs_grad[1] || needs_grad[2]).then(|| self.jacobian(x, ggx, epsilon));
+        vec![
+            gx,
+            gu.filter(|_| needs_grad[1]).map(|gu| self.reduce(gy * gu)),
+            gu.filter(|_| needs_grad[2])
+                .map(|gu| gu * broadcast_gamma()),
+        ]
+    }
+
+    /// Calculates the forward-mode gradient of the backward operator with inputs `x` (x, gamma,
+    /// gy), the output `gx` and the input gradients `dx`.
+    fn backward_forward_gradient<'hw: 'op, 'op: 'g, 'g>(
+        self,
+        x: &[Node<'hw, 'op, 'g>],
+        gx: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+        epsilon: f32,
+    ) -> Node<'hw, 'op, 'g> {
+        let shape = x[0].shape();
+        let gamma = self.broadcast(x[1], &shape);
+        let u = x[2] * gamma;
+        let du = dx[2] * gamma + x[2] * self.broadcast(dx[1], &shape);
+        let xhat = self.normalize(x[0], epsilon);
+        let s = self.inverse_std(x[0], epsilon);
+
+        // d(N(u)) = N(du) + ds / s * N(u) - s * (b * dxhat + xhat * mean(u * dxhat)),
+        // where ds / s = -s * mean(xhat * dx) and dxhat = N(dx).
+        let dxhat = self.jacobian(x[0], dx[0], epsilon);
+        let b = self.mean(u * xhat);
+        self.jacobian(x[0], du, epsilon)
+            - s * self.mean(xhat * dx[0]) * gx
+            - s * (b * dxhat + xhat * self.mean(u * dxhat))
+    }
+}
+
+/// Gradient for LayerNormBackward, RmsNormBackward and BatchNormBackward.
+pub(crate) struct NormalizationBackwardGrad {
+    pub(crate) normalization: Normalization,
+    pub(crate) epsilon: f32,
+}
+
+impl Gradient for NormalizationBackwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        self.normalization
+            .backward_gradient(x, gy, self.epsilon, needs_grad)
+    }
+}
+
+/// Forward-mode gradient for LayerNormBackward, RmsNormBackward and BatchNormBackward.
+pub(crate) struct NormalizationBackwardForwardGrad {
+    pub(crate) normalization: Normalization,
+    pub(crate) epsilon: f32,
+}
+
+impl ForwardGradient for NormalizationBackwardForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        self.normalization
+            .backward_forward_gradient(x, y, dx, self.epsilon)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::layer_norm_backward::*;
+
+    #[test]
+    fn test_properties() {
+        let op = LayerNormBackward::new(1e-5);
+        assert_eq!(op.name(), "LayerNormBackward");
+        assert_eq!(op.input_size(), 3);
+        assert!(op.get_gradient_fn().is_some());
+        assert!(op.get_forward_gradient_fn().is_some());
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = LayerNormBackward::new(1e-5);
+        assert_eq!(op.perform_shape(&[&Shape::new([2, 3]), &Shape::new([3]), &Shape::new([2, 3])]), Ok(Shape::new([2, 3])));
+        assert!(op.perform_shape(&[&Shape::new([2, 3]), &Shape::new([3]), &Shape::new([3])]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([2, 3]), &Shape::new([2]), &Shape::new([2, 3])]).is_err());
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw1 = RefCell::new(CpuHardware::new());
+        let hw2 = RefCell::new(CpuHardware::new());
+        let op = LayerNormBackward::new(1e-5);
+
+        assert!(ptr::eq(
+            op.perform_hardware(&[&hw1, &hw1, &hw1]).unwrap(),
+            &hw1
+        ));
+        assert!(op.perform_hardware(&[&hw2, &hw1, &hw1]).is_err());
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = LayerNormBackward::new(0.);
+        let x = Array::constant_f32(&hw, Shape::new([1, 4]), &[-1., -1., 1., 1.]).unwrap();
+        let gamma = Array::constant_f32(&hw, Shape::new([4]), &[2., 1., 1., 1.]).unwrap();
+        let gy = Array::constant_f32(&hw, Shape::new([1, 4]), &[1., 0., 0., 0.]).unwrap();
+        let observed = op.perform(&[&x, &gamma, &gy]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([1, 4]));
+        assert_eq!(observed.get_values_f32(), vec![1., -1., 0., 0.]);
+    }
+}
@@ -0,0 +1,90 @@
+use crate::operator::*;
+
+/// LessEqual operator: y = 1 if a <= b, 0 otherwise.
+///
+/// The derivative of this function is 0 almost everywhere. This operator does not define the
+/// reverse-mode gradient, and its forward-mode gradient is always 0.
+pub(crate) struct LessEqual;
+
+impl LessEqual {
+    pub(crate) fn new() -> Self {
+        Self {}
+    }
+}
+
+impl<'hw> Operator<'hw> for LessEqual {
+    fn name(&self) -> String {
+        String::from("LessEqual")
+    }
+
+    fn input_size(&self) -> usize {
+        2
+    }
+
+    fn encode_attributes(&self, _encoder: &mut Encoder) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        inputs[0].elementwise(inputs[1])
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        inputs[0].elementwise_le_f32(inputs[1])
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(ZeroForwardGrad))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::less_equal::*;
+
+    #[test]
+    fn test_properties() {
+        let op = LessEqual::new();
+        assert_eq!(op.name(), "LessEqual");
+        assert_eq!(op.input_size(), 2);
+        assert!(op.get_gradient_fn().is_none());
+        assert!(op.get_forward_gradient_fn().is_some());
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = LessEqual::new();
+        assert_eq!(op.perform_shape(&[&Shape::new([]), &Shape::new([])]), Ok(Shape::new([])));
+        assert_eq!(op.perform_shape(&[&Shape::new([0]), &Shape::new([0])]), Ok(Shape::new([0])));
+        assert_eq!(op.perform_shape(&[&Shape::new([3]), &Shape::new([3])]), Ok(Shape::new([3])));
+        assert!(op.perform_shape(&[&Shape::new([]), &Shape::new([3])]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([3]), &Shape::new([0])]).is_err());
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw1 = RefCell::new(CpuHardware::new());
+        let hw2 = RefCell::new(CpuHardware::new());
+        let op = LessEqual::new();
+
+        assert!(ptr::eq(op.perform_hardware(&[&hw1, &hw1]).unwrap(), &hw1));
+        assert!(op.perform_hardware(&[&hw1, &hw2]).is_err());
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = LessEqual::new();
+        let lhs = Array::constant_f32(&hw, Shape::new([4]), &[2., 2., 1., 5.]).unwrap();
+        let rhs = Array::constant_f32(&hw, Shape::new([4]), &[1., 2., 2., 5.]).unwrap();
+        let observed = op.perform(&[&lhs, &rhs]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([4]));
+        assert_eq!(observed.get_values_f32(), vec![0., 1., 1., 1.]);
+    }
+}
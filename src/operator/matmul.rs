@@ -0,0 +1,120 @@
+use crate::operator::*;
+
+/// MatMul operator: y = a @ b for matrices a and b.
+pub(crate) struct MatMul;
+
+impl MatMul {
+    pub(crate) fn new() -> Self {
+        Self {}
+    }
+}
+
+impl<'hw> Operator<'hw> for MatMul {
+    fn name(&self) -> String {
+        String::from("MatMul")
+    }
+
+    fn input_size(&self) -> usize {
+        2
+    }
+
+    fn encode_attributes(&self, _encoder: &mut Encoder) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        inputs[0].matmul(inputs[1])
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        inputs[0].matmul_f32(inputs[1])
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(MatMulGrad {}))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(MatMulForwardGrad {}))
+    }
+}
+
+/// Gradient for MatMul.
+struct MatMulGrad;
+
+impl Gradient for MatMulGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        vec![
+            needs_grad[0].then(|| gy.matmul(x[1].transpose())),
+            needs_grad[1].then(|| x[0].transpose().matmul(gy)),
+        ]
+    }
+}
+
+/// Forward-mode gradient for MatMul.
+struct MatMulForwardGrad;
+
+impl ForwardGradient for MatMulForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        dx[0].matmul(x[1]) + x[0].matmul(dx[1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::matmul::*;
+
+    #[test]
+    fn test_properties() {
+        let op = MatMul::new();
+        assert_eq!(op.name(), "MatMul");
+        assert_eq!(op.input_size(), 2);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = MatMul::new();
+        assert_eq!(op.perform_shape(&[&Shape::new([2, 3]), &Shape::new([3, 4])]), Ok(Shape::new([2, 4])));
+        assert_eq!(op.perform_shape(&[&Shape::new([0, 3]), &Shape::new([3, 0])]), Ok(Shape::new([0, 0])));
+        assert!(op.perform_shape(&[&Shape::new([2, 3]), &Shape::new([2, 3])]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([3]), &Shape::new([3])]).is_err());
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw1 = RefCell::new(CpuHardware::new());
+        let hw2 = RefCell::new(CpuHardware::new());
+        let op = MatMul::new();
+
+        assert!(ptr::eq(op.perform_hardware(&[&hw1, &hw1]).unwrap(), &hw1));
+        assert!(op.perform_hardware(&[&hw1, &hw2]).is_err());
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = MatMul::new();
+        let lhs = Array::constant_f32(&hw, Shape::new([1, 2]), &[1., 2.]).unwrap();
+        let rhs = Array::constant_f32(&hw, Shape::new([2, 2]), &[3., 4., 5., 6.]).unwrap();
+        let observed = op.perform(&[&lhs, &rhs]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([1, 2]));
+        assert_eq!(observed.get_values_f32(), vec![13., 16.]);
+    }
+}
@@ -0,0 +1,164 @@
+use crate::conv::{Conv2dGeometry, Pool2dOptions};
+use crate::operator::*;
+
+/// MaxPool2d operator: maximum of each 2-dimensional window.
+pub(crate) struct MaxPool2d {
+    /// Pooling options.
+    options: Pool2dOptions,
+}
+
+impl MaxPool2d {
+    pub(crate) fn new(options: Pool2dOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl<'hw> Operator<'hw> for MaxPool2d {
+    fn name(&self) -> String {
+        String::from("MaxPool2d")
+    }
+
+    fn input_size(&self) -> usize {
+        1
+    }
+
+    fn encode_attributes(&self, encoder: &mut Encoder) -> Result<()> {
+        serialize::write_pool2d_options(encoder, &self.options);
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        Ok(Conv2dGeometry::pool2d(inputs[0], &self.options)?.output_shape())
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        inputs[0].max_pool2d_f32(&self.options)
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(MaxPool2dGrad {
+            options: self.options,
+        }))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(MaxPool2dForwardGrad {
+            options: self.options,
+        }))
+    }
+}
+
+/// Gradient for MaxPool2d.
+struct MaxPool2dGrad {
+    options: Pool2dOptions,
+}
+
+impl Gradient for MaxPool2dGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        _needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        vec![Some(max_pool2d_scatter(x[0], gy, self.options))]
+    }
+}
+
+/// Forward-mode gradient for MaxPool2d.
+struct MaxPool2dForwardGrad {
+    options: Pool2dOptions,
+}
+
+impl ForwardGradient for MaxPool2dForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        max_pool2d_gather(x[0], dx[0], self.options)
+    }
+}
+
+/// Registers `MaxPool2dGather` operation: values of `src` at the maxima of each window of `x`.
+pub(crate) fn max_pool2d_gather<'hw: 'op, 'op: 'g, 'g>(
+    x: Node<'hw, 'op, 'g>,
+    src: Node<'hw, 'op, 'g>,
+    options: Pool2dOptions,
+) -> Node<'hw, 'op, 'g> {
+    Node::apply(
+        x.graph(),
+        Box::new(max_pool2d_gather::MaxPool2dGather::new(options)),
+        &[x, src],
+    )
+    .unwrap()
+}
+
+/// Registers `MaxPool2dScatter` operation: values of `src` accumulated to the maxima of each
+/// window of `x`.
+pub(crate) fn max_pool2d_scatter<'hw: 'op, 'op: 'g, 'g>(
+    x: Node<'hw, 'op, 'g>,
+    src: Node<'hw, 'op, 'g>,
+    options: Pool2dOptions,
+) -> Node<'hw, 'op, 'g> {
+    Node::apply(
+        x.graph(),
+        Box::new(max_pool2d_scatter::MaxPool2dScatter::new(options)),
+        &[x, src],
+    )
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::max_pool2d::*;
+
+    #[test]
+    fn test_properties() {
+        let op = MaxPool2d::new(Pool2dOptions::new([2, 2]));
+        assert_eq!(op.name(), "MaxPool2d");
+        assert_eq!(op.input_size(), 1);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = MaxPool2d::new(Pool2dOptions::new([2, 2]));
+        assert_eq!(op.perform_shape(&[&Shape::new([2, 3, 5, 4])]), Ok(Shape::new([2, 3, 2, 2])));
+        assert_eq!(op.perform_shape(&[&Shape::new([0, 0, 2, 2])]), Ok(Shape::new([0, 0, 1, 1])));
+        assert!(op.perform_shape(&[&Shape::new([2, 3, 1, 4])]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([3, 5, 4])]).is_err());
+
+        let op = MaxPool2d::new(Pool2dOptions { kernel_size: [3, 3], stride: [1, 2], padding: [1, 1] });
+        assert_eq!(op.perform_shape(&[&Shape::new([2, 3, 5, 4])]), Ok(Shape::new([2, 3, 5, 2])));
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = MaxPool2d::new(Pool2dOptions::new([2, 2]));
+
+        assert!(ptr::eq(op.perform_hardware(&[&hw]).unwrap(), &hw));
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = MaxPool2d::new(Pool2dOptions::new([1, 2]));
+        let input = Array::constant_f32(
+            &hw,
+            Shape::new([1, 2, 1, 4]),
+            &[1., -2., 3., 4., -5., -6., 0., 7.],
+        )
+        .unwrap();
+        let observed = op.perform(&[&input]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([1, 2, 1, 2]));
+        assert_eq!(observed.get_values_f32(), vec![1., 4., -5., 7.]);
+    }
+}
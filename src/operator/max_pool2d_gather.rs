@@ -0,0 +1,141 @@
+use crate::conv::{Conv2dGeometry, Pool2dOptions};
+use crate::operator::max_pool2d::{max_pool2d_gather, max_pool2d_scatter};
+use crate::operator::*;
+
+/// MaxPool2dGather operator: values of src at the positions of maxima of each 2-dimensional
+/// window of x.
+pub(crate) struct MaxPool2dGather {
+    /// Pooling options.
+    options: Pool2dOptions,
+}
+
+impl MaxPool2dGather {
+    pub(crate) fn new(options: Pool2dOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl<'hw> Operator<'hw> for MaxPool2dGather {
+    fn name(&self) -> String {
+        String::from("MaxPool2dGather")
+    }
+
+    fn input_size(&self) -> usize {
+        2
+    }
+
+    fn encode_attributes(&self, encoder: &mut Encoder) -> Result<()> {
+        serialize::write_pool2d_options(encoder, &self.options);
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        if inputs[0] != inputs[1] {
+            return Err(Error::InvalidShape(format!(
+                "Shapes must be the same, but got {} and {}.",
+                inputs[0], inputs[1]
+            )));
+        }
+        Ok(Conv2dGeometry::pool2d(inputs[0], &self.options)?.output_shape())
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        inputs[0].max_pool2d_gather_f32(inputs[1], &self.options)
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(MaxPool2dGatherGrad {
+            options: self.options,
+        }))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(MaxPool2dGatherForwardGrad {
+            options: self.options,
+        }))
+    }
+}
+
+/// Gradient for MaxPool2dGather.
+struct MaxPool2dGatherGrad {
+    options: Pool2dOptions,
+}
+
+impl Gradient for MaxPool2dGatherGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        // Positions of maxima are locally constant.
+        vec![
+            None,
+            needs_grad[1].then(|| max_pool2d_scatter(x[0], gy, self.options)),
+        ]
+    }
+}
+
+/// Forward-mode gradient for MaxPool2dGather.
+struct MaxPool2dGatherForwardGrad {
+    options: Pool2dOptions,
+}
+
+impl ForwardGradient for MaxPool2dGatherForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        max_pool2d_gather(x[0], dx[1], self.options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::max_pool2d_gather::*;
+
+    #[test]
+    fn test_properties() {
+        let op = MaxPool2dGather::new(Pool2dOptions::new([2, 2]));
+        assert_eq!(op.name(), "MaxPool2dGather");
+        assert_eq!(op.input_size(), 2);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = MaxPool2dGather::new(Pool2dOptions::new([2, 2]));
+        assert_eq!(op.perform_shape(&[&Shape::new([2, 3, 5, 4]), &Shape::new([2, 3, 5, 4])]), Ok(Shape::new([2, 3, 2, 2])));
+        assert!(op.perform_shape(&[&Shape::new([2, 3, 5, 4]), &Shape::new([2, 3, 2, 2])]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([2, 3, 1, 4]), &Shape::new([2, 3, 1, 4])]).is_err());
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw1 = RefCell::new(CpuHardware::new());
+        let hw2 = RefCell::new(CpuHardware::new());
+        let op = MaxPool2dGather::new(Pool2dOptions::new([2, 2]));
+
+        assert!(ptr::eq(op.perform_hardware(&[&hw1, &hw1]).unwrap(), &hw1));
+        assert!(op.perform_hardware(&[&hw1, &hw2]).is_err());
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = MaxPool2dGather::new(Pool2dOptions::new([1, 2]));
+        let x = Array::constant_f32(&hw, Shape::new([1, 1, 1, 4]), &[1., -2., 3., 4.]).unwrap();
+        let src = Array::constant_f32(&hw, Shape::new([1, 1, 1, 4]), &[5., 6., 7., 8.]).unwrap();
+        let observed = op.perform(&[&x, &src]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([1, 1, 1, 2]));
+        assert_eq!(observed.get_values_f32(), vec![5., 8.]);
+    }
+}
@@ -0,0 +1,136 @@
+use crate::conv::{Conv2dGeometry, Pool2dOptions};
+use crate::operator::max_pool2d::{max_pool2d_gather, max_pool2d_scatter};
+use crate::operator::*;
+
+/// MaxPool2dScatter operator: values of src accumulated to the positions of maxima of each
+/// 2-dimensional window of x. This is the gradient of MaxPool2d with respect to x.
+pub(crate) struct MaxPool2dScatter {
+    /// Pooling options.
+    options: Pool2dOptions,
+}
+
+impl MaxPool2dScatter {
+    pub(crate) fn new(options: Pool2dOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl<'hw> Operator<'hw> for MaxPool2dScatter {
+    fn name(&self) -> String {
+        String::from("MaxPool2dScatter")
+    }
+
+    fn input_size(&self) -> usize {
+        2
+    }
+
+    fn encode_attributes(&self, encoder: &mut Encoder) -> Result<()> {
+        serialize::write_pool2d_options(encoder, &self.options);
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        Conv2dGeometry::pool2d(inputs[0], &self.options)?.check_output(inputs[1])?;
+        Ok(inputs[0].clone())
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        inputs[0].max_pool2d_scatter_f32(inputs[1], &self.options)
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(MaxPool2dScatterGrad {
+            options: self.options,
+        }))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(MaxPool2dScatterForwardGrad {
+            options: self.options,
+        }))
+    }
+}
+
+/// Gradient for MaxPool2dScatter.
+struct MaxPool2dScatterGrad {
+    options: Pool2dOptions,
+}
+
+impl Gradient for MaxPool2dScatterGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        // Positions of maxima are locally constant.
+        vec![
+            None,
+            needs_grad[1].then(|| max_pool2d_gather(x[0], gy, self.options)),
+        ]
+    }
+}
+
+/// Forward-mode gradient for MaxPool2dScatter.
+struct MaxPool2dScatterForwardGrad {
+    options: Pool2dOptions,
+}
+
+impl ForwardGradient for MaxPool2dScatterForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        max_pool2d_scatter(x[0], dx[1], self.options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::max_pool2d_scatter::*;
+
+    #[test]
+    fn test_properties() {
+        let op = MaxPool2dScatter::new(Pool2dOptions::new([2, 2]));
+        assert_eq!(op.name(), "MaxPool2dScatter");
+        assert_eq!(op.input_size(), 2);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = MaxPool2dScatter::new(Pool2dOptions::new([2, 2]));
+        assert_eq!(op.perform_shape(&[&Shape::new([2, 3, 5, 4]), &Shape::new([2, 3, 2, 2])]), Ok(Shape::new([2, 3, 5, 4])));
+        assert!(op.perform_shape(&[&Shape::new([2, 3, 5, 4]), &Shape::new([2, 3, 5, 4])]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([2, 3, 1, 4]), &Shape::new([2, 3, 0, 2])]).is_err());
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw1 = RefCell::new(CpuHardware::new());
+        let hw2 = RefCell::new(CpuHardware::new());
+        let op = MaxPool2dScatter::new(Pool2dOptions::new([2, 2]));
+
+        assert!(ptr::eq(op.perform_hardware(&[&hw1, &hw1]).unwrap(), &hw1));
+        assert!(op.perform_hardware(&[&hw1, &hw2]).is_err());
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = MaxPool2dScatter::new(Pool2dOptions::new([1, 2]));
+        let x = Array::constant_f32(&hw, Shape::new([1, 1, 1, 4]), &[1., -2., 3., 4.]).unwrap();
+        let src = Array::constant_f32(&hw, Shape::new([1, 1, 1, 2]), &[5., 6.]).unwrap();
+        let observed = op.perform(&[&x, &src]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([1, 1, 1, 4]));
+        assert_eq!(observed.get_values_f32(), vec![5., 0., 0., 6.]);
+    }
+}
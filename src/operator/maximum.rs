@@ -0,0 +1,125 @@
+use crate::operator::*;
+
+/// Maximum operator: y = max(a, b).
+///
+/// Gradients are propagated to the larger argument, or to a if both are equal.
+pub(crate) struct Maximum;
+
+impl Maximum {
+    pub(crate) fn new() -> Self {
+        Self {}
+    }
+}
+
+impl<'hw> Operator<'hw> for Maximum {
+    fn name(&self) -> String {
+        String::from("Maximum")
+    }
+
+    fn input_size(&self) -> usize {
+        2
+    }
+
+    fn encode_attributes(&self, _encoder: &mut Encoder) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        inputs[0].elementwise(inputs[1])
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        inputs[0].elementwise_maximum_f32(inputs[1])
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(MaximumGrad {}))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(MaximumForwardGrad {}))
+    }
+}
+
+/// Gradient for Maximum.
+struct MaximumGrad;
+
+impl Gradient for MaximumGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        let mask = x[1].less_equal(x[0]);
+        let zero = Node::fill(gy.graph(), gy.hardware(), gy.shape(), 0.);
+        vec![
+            needs_grad[0].then(|| mask.select(gy, zero)),
+            needs_grad[1].then(|| mask.select(zero, gy)),
+        ]
+    }
+}
+
+/// Forward-mode gradient for Maximum.
+struct MaximumForwardGrad;
+
+impl ForwardGradient for MaximumForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        x[1].less_equal(x[0]).select(dx[0], dx[1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::maximum::*;
+
+    #[test]
+    fn test_properties() {
+        let op = Maximum::new();
+        assert_eq!(op.name(), "Maximum");
+        assert_eq!(op.input_size(), 2);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = Maximum::new();
+        assert_eq!(op.perform_shape(&[&Shape::new([]), &Shape::new([])]), Ok(Shape::new([])));
+        assert_eq!(op.perform_shape(&[&Shape::new([0]), &Shape::new([0])]), Ok(Shape::new([0])));
+        assert_eq!(op.perform_shape(&[&Shape::new([3]), &Shape::new([3])]), Ok(Shape::new([3])));
+        assert!(op.perform_shape(&[&Shape::new([]), &Shape::new([3])]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([3]), &Shape::new([0])]).is_err());
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw1 = RefCell::new(CpuHardware::new());
+        let hw2 = RefCell::new(CpuHardware::new());
+        let op = Maximum::new();
+
+        assert!(ptr::eq(op.perform_hardware(&[&hw1, &hw1]).unwrap(), &hw1));
+        assert!(op.perform_hardware(&[&hw1, &hw2]).is_err());
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = Maximum::new();
+        let lhs = Array::constant_f32(&hw, Shape::new([4]), &[2., 2., 1., -5.]).unwrap();
+        let rhs = Array::constant_f32(&hw, Shape::new([4]), &[1., 2., 2., 5.]).unwrap();
+        let observed = op.perform(&[&lhs, &rhs]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([4]));
+        assert_eq!(observed.get_values_f32(), vec![2., 2., 2., 5.]);
+    }
+}
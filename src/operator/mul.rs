@@ -17,6 +17,14 @@ impl<'hw> Operator<'hw> for Mul {
         2
     }
 
+    fn encode_attributes(&self, _encoder: &mut Encoder) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
     fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
         inputs[0].elementwise(inputs[1])
     }
@@ -28,6 +36,10 @@ impl<'hw> Operator<'hw> for Mul {
     fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
         Some(Box::new(MulGrad {}))
     }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(MulForwardGrad {}))
+    }
 }
 
 /// Gradient for Mul.
@@ -39,8 +51,26 @@ impl Gradient for MulGrad {
         x: &[Node<'hw, 'op, 'g>],
         _y: Node<'hw, 'op, 'g>,
         gy: Node<'hw, 'op, 'g>,
-    ) -> Vec<Node<'hw, 'op, 'g>> {
-        vec![gy * x[1], gy * x[0]]
+        needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        vec![
+            needs_grad[0].then(|| gy * x[1]),
+            needs_grad[1].then(|| gy * x[0]),
+        ]
+    }
+}
+
+/// Forward-mode gradient for Mul.
+struct MulForwardGrad;
+
+impl ForwardGradient for MulForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        dx[0] * x[1] + x[0] * dx[1]
     }
 }
 
@@ -17,6 +17,14 @@ impl<'hw> Operator<'hw> for Neg {
         1
     }
 
+    fn encode_attributes(&self, _encoder: &mut Encoder) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
     fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
         Ok(inputs[0].clone())
     }
@@ -28,6 +36,10 @@ impl<'hw> Operator<'hw> for Neg {
     fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
         Some(Box::new(NegGrad {}))
     }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(NegForwardGrad {}))
+    }
 }
 
 /// Gradient for Neg.
@@ -39,8 +51,23 @@ impl Gradient for NegGrad {
         _x: &[Node<'hw, 'op, 'g>],
         _y: Node<'hw, 'op, 'g>,
         gy: Node<'hw, 'op, 'g>,
-    ) -> Vec<Node<'hw, 'op, 'g>> {
-        vec![-gy]
+        _needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        vec![Some(-gy)]
+    }
+}
+
+/// Forward-mode gradient for Neg.
+struct NegForwardGrad;
+
+impl ForwardGradient for NegForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        _x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        -dx[0]
     }
 }
 
@@ -0,0 +1,120 @@
+use crate::operator::*;
+
+/// ReLU operator: y = max(x, 0).
+pub(crate) struct Relu;
+
+impl Relu {
+    pub(crate) fn new() -> Self {
+        Self {}
+    }
+}
+
+impl<'hw> Operator<'hw> for Relu {
+    fn name(&self) -> String {
+        String::from("Relu")
+    }
+
+    fn input_size(&self) -> usize {
+        1
+    }
+
+    fn encode_attributes(&self, _encoder: &mut Encoder) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        Ok(inputs[0].clone())
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        Ok(inputs[0].elementwise_relu_f32())
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(ReluGrad {}))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(ReluForwardGrad {}))
+    }
+}
+
+/// Registers Step operation for the derivative of ReLU.
+fn step<'hw: 'op, 'op: 'g, 'g>(x: Node<'hw, 'op, 'g>) -> Node<'hw, 'op, 'g> {
+    Node::apply(x.graph(), Box::new(step::Step::new()), &[x]).unwrap()
+}
+
+/// Gradient for Relu.
+struct ReluGrad;
+
+impl Gradient for ReluGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        _needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        vec![Some(gy * step(x[0]))]
+    }
+}
+
+/// Forward-mode gradient for Relu.
+struct ReluForwardGrad;
+
+impl ForwardGradient for ReluForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        dx[0] * step(x[0])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array::IntoArray;
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::relu::*;
+
+    #[test]
+    fn test_properties() {
+        let op = Relu::new();
+        assert_eq!(op.name(), "Relu");
+        assert_eq!(op.input_size(), 1);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = Relu::new();
+        assert_eq!(op.perform_shape(&[&Shape::new([])]), Ok(Shape::new([])));
+        assert_eq!(op.perform_shape(&[&Shape::new([0])]), Ok(Shape::new([0])));
+        assert_eq!(op.perform_shape(&[&Shape::new([3])]), Ok(Shape::new([3])));
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = Relu::new();
+
+        assert!(ptr::eq(op.perform_hardware(&[&hw]).unwrap(), &hw));
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = Relu::new();
+        let input = (-3f32).into_array(&hw);
+        let expected = 0f32.into_array(&hw);
+        let observed = op.perform(&[&input]).unwrap();
+        assert_eq!(observed.shape(), expected.shape());
+        assert_eq!(observed.get_scalar_f32(), expected.get_scalar_f32());
+    }
+}
@@ -0,0 +1,160 @@
+use crate::operator::layer_norm::{broadcast_features, fill_like, reduce_features};
+use crate::operator::*;
+
+/// RmsNorm operator: division of x by the root mean square over the last axis, scaled by gamma.
+pub(crate) struct RmsNorm {
+    /// Small value added to the mean square.
+    epsilon: f32,
+}
+
+impl RmsNorm {
+    pub(crate) fn new(epsilon: f32) -> Self {
+        Self { epsilon }
+    }
+}
+
+impl<'hw> Operator<'hw> for RmsNorm {
+    fn name(&self) -> String {
+        String::from("RmsNorm")
+    }
+
+    fn input_size(&self) -> usize {
+        2
+    }
+
+    fn encode_attributes(&self, encoder: &mut Encoder) -> Result<()> {
+        encoder.write_f32(self.epsilon);
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        inputs[0].feature_layout(inputs[1])?;
+        Ok(inputs[0].clone())
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        inputs[0].rms_norm_f32(inputs[1], self.epsilon)
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(RmsNormGrad {
+            epsilon: self.epsilon,
+        }))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(RmsNormForwardGrad {
+            epsilon: self.epsilon,
+        }))
+    }
+}
+
+/// Gradient for RmsNorm.
+struct RmsNormGrad {
+    epsilon: f32,
+}
+
+impl Gradient for RmsNormGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        vec![
+            needs_grad[0].then(|| rms_norm_backward(x[0], x[1], gy, self.epsilon)),
+            needs_grad[1].then(|| {
+                let xhat = x[0].rms_norm(fill_like(x[1], 1.), self.epsilon);
+                reduce_features(gy * xhat)
+            }),
+        ]
+    }
+}
+
+/// Forward-mode gradient for RmsNorm.
+struct RmsNormForwardGrad {
+    epsilon: f32,
+}
+
+impl ForwardGradient for RmsNormForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        let ones = fill_like(x[1], 1.);
+        let xhat = x[0].rms_norm(ones, self.epsilon);
+        let dxhat = rms_norm_backward(x[0], ones, dx[0], self.epsilon);
+        let shape = x[0].shape();
+        dxhat * broadcast_features(x[1], &shape) + broadcast_features(dx[1], &shape) * xhat
+    }
+}
+
+/// Registers `RmsNormBackward` operation: the gradient of RmsNorm with respect to the input.
+pub(crate) fn rms_norm_backward<'hw: 'op, 'op: 'g, 'g>(
+    x: Node<'hw, 'op, 'g>,
+    gamma: Node<'hw, 'op, 'g>,
+    gy: Node<'hw, 'op, 'g>,
+    epsilon: f32,
+) -> Node<'hw, 'op, 'g> {
+    Node::apply(
+        x.graph(),
+        Box::new(rms_norm_backward::RmsNormBackward::new(epsilon)),
+        &[x, gamma, gy],
+    )
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::rms_norm::*;
+
+    #[test]
+    fn test_properties() {
+        let op = RmsNorm::new(1e-5);
+        assert_eq!(op.name(), "RmsNorm");
+        assert_eq!(op.input_size(), 2);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = RmsNorm::new(1e-5);
+        assert_eq!(op.perform_shape(&[&Shape::new([3]), &Shape::new([3])]), Ok(Shape::new([3])));
+        assert_eq!(op.perform_shape(&[&Shape::new([2, 4, 3]), &Shape::new([3])]), Ok(Shape::new([2, 4, 3])));
+        assert!(op.perform_shape(&[&Shape::new([]), &Shape::new([])]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([2, 3]), &Shape::new([2])]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([2, 3]), &Shape::new([2, 3])]).is_err());
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw1 = RefCell::new(CpuHardware::new());
+        let hw2 = RefCell::new(CpuHardware::new());
+        let op = RmsNorm::new(1e-5);
+
+        assert!(ptr::eq(op.perform_hardware(&[&hw1, &hw1]).unwrap(), &hw1));
+        assert!(op.perform_hardware(&[&hw1, &hw2]).is_err());
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = RmsNorm::new(0.);
+        let x = Array::constant_f32(&hw, Shape::new([2, 2]), &[3., -3., 0., 4.]).unwrap();
+        let gamma = Array::constant_f32(&hw, Shape::new([2]), &[2., 1.]).unwrap();
+        let observed = op.perform(&[&x, &gamma]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([2, 2]));
+        let expected = [2., -1., 0., 2f32.sqrt()];
+        for (o, e) in observed.get_values_f32().iter().zip(expected) {
+            assert!((o - e).abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use crate::operator::*;
+
+/// ScatterAddRows operator: y = 0, then y[indices[i], ...] += x[i, ...].
+///
+/// This is the adjoint of GatherRows. The second input holds row indices as floating-point values.
+/// No gradient is propagated to the indices.
+pub(crate) struct ScatterAddRows {
+    /// Number of rows of the output.
+    num_rows: usize,
+}
+
+impl ScatterAddRows {
+    pub(crate) fn new(num_rows: usize) -> Self {
+        Self { num_rows }
+    }
+}
+
+impl<'hw> Operator<'hw> for ScatterAddRows {
+    fn name(&self) -> String {
+        String::from("ScatterAddRows")
+    }
+
+    fn input_size(&self) -> usize {
+        2
+    }
+
+    fn encode_attributes(&self, encoder: &mut Encoder) -> Result<()> {
+        encoder.write_usize(self.num_rows);
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        inputs[0].scatter_rows(inputs[1], self.num_rows)
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        inputs[0].scatter_add_rows_f32(inputs[1], self.num_rows)
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(ScatterAddRowsGrad {}))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(ScatterAddRowsForwardGrad {
+            num_rows: self.num_rows,
+        }))
+    }
+}
+
+/// Gradient for ScatterAddRows.
+struct ScatterAddRowsGrad;
+
+impl Gradient for ScatterAddRowsGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        // Indices are locally constant.
+        vec![needs_grad[0].then(|| gy.gather_rows(x[1])), None]
+    }
+}
+
+/// Forward-mode gradient for ScatterAddRows.
+struct ScatterAddRowsForwardGrad {
+    num_rows: usize,
+}
+
+impl ForwardGradient for ScatterAddRowsForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        Node::apply(
+            dx[0].graph(),
+            Box::new(ScatterAddRows::new(self.num_rows)),
+            &[dx[0], x[1]],
+        )
+        .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::scatter_add_rows::*;
+
+    #[test]
+    fn test_properties() {
+        let op = ScatterAddRows::new(5);
+        assert_eq!(op.name(), "ScatterAddRows");
+        assert_eq!(op.input_size(), 2);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = ScatterAddRows::new(5);
+        assert_eq!(op.perform_shape(&[&Shape::new([3, 2]), &Shape::new([3])]), Ok(Shape::new([5, 2])));
+        assert_eq!(op.perform_shape(&[&Shape::new([2]), &Shape::new([])]), Ok(Shape::new([5, 2])));
+        assert!(op.perform_shape(&[&Shape::new([3, 2]), &Shape::new([2])]).is_err());
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw1 = RefCell::new(CpuHardware::new());
+        let hw2 = RefCell::new(CpuHardware::new());
+        let op = ScatterAddRows::new(5);
+
+        assert!(ptr::eq(op.perform_hardware(&[&hw1, &hw1]).unwrap(), &hw1));
+        assert!(op.perform_hardware(&[&hw1, &hw2]).is_err());
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = ScatterAddRows::new(2);
+        let src = Array::constant_f32(&hw, Shape::new([3, 1]), &[1., 2., 3.]).unwrap();
+        let indices = Array::constant_f32(&hw, Shape::new([3]), &[1., 0., 1.]).unwrap();
+        let observed = op.perform(&[&src, &indices]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([2, 1]));
+        assert_eq!(observed.get_values_f32(), vec![2., 4.]);
+    }
+}
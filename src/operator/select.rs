@@ -0,0 +1,130 @@
+use crate::operator::*;
+
+/// Select operator: y = a if cond is nonzero, b otherwise.
+///
+/// Gradients are propagated to the selected argument, and cond receives no gradient.
+pub(crate) struct Select;
+
+impl Select {
+    pub(crate) fn new() -> Self {
+        Self {}
+    }
+}
+
+impl<'hw> Operator<'hw> for Select {
+    fn name(&self) -> String {
+        String::from("Select")
+    }
+
+    fn input_size(&self) -> usize {
+        3
+    }
+
+    fn encode_attributes(&self, _encoder: &mut Encoder) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        inputs[0].elementwise(inputs[1])?.elementwise(inputs[2])
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        inputs[0].elementwise_where_f32(inputs[1], inputs[2])
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(SelectGrad {}))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(SelectForwardGrad {}))
+    }
+}
+
+/// Gradient for Select.
+struct SelectGrad;
+
+impl Gradient for SelectGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        let zero = Node::fill(gy.graph(), gy.hardware(), gy.shape(), 0.);
+        vec![
+            None,
+            needs_grad[1].then(|| x[0].select(gy, zero)),
+            needs_grad[2].then(|| x[0].select(zero, gy)),
+        ]
+    }
+}
+
+/// Forward-mode gradient for Select.
+struct SelectForwardGrad;
+
+impl ForwardGradient for SelectForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        x[0].select(dx[1], dx[2])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::select::*;
+
+    #[test]
+    fn test_properties() {
+        let op = Select::new();
+        assert_eq!(op.name(), "Select");
+        assert_eq!(op.input_size(), 3);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = Select::new();
+        assert_eq!(op.perform_shape(&[&Shape::new([]), &Shape::new([]), &Shape::new([])]), Ok(Shape::new([])));
+        assert_eq!(op.perform_shape(&[&Shape::new([0]), &Shape::new([0]), &Shape::new([0])]), Ok(Shape::new([0])));
+        assert_eq!(op.perform_shape(&[&Shape::new([2, 3]), &Shape::new([2, 3]), &Shape::new([2, 3])]), Ok(Shape::new([2, 3])));
+        assert!(op.perform_shape(&[&Shape::new([3]), &Shape::new([3]), &Shape::new([])]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([3]), &Shape::new([2]), &Shape::new([3])]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([2]), &Shape::new([3]), &Shape::new([3])]).is_err());
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw1 = RefCell::new(CpuHardware::new());
+        let hw2 = RefCell::new(CpuHardware::new());
+        let op = Select::new();
+
+        assert!(ptr::eq(
+            op.perform_hardware(&[&hw1, &hw1, &hw1]).unwrap(),
+            &hw1
+        ));
+        assert!(op.perform_hardware(&[&hw1, &hw1, &hw2]).is_err());
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = Select::new();
+        let cond = Array::constant_f32(&hw, Shape::new([4]), &[1., 0., -2., 0.]).unwrap();
+        let a = Array::constant_f32(&hw, Shape::new([4]), &[1., 2., 3., 4.]).unwrap();
+        let b = Array::constant_f32(&hw, Shape::new([4]), &[5., 6., 7., 8.]).unwrap();
+        let observed = op.perform(&[&cond, &a, &b]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([4]));
+        assert_eq!(observed.get_values_f32(), vec![1., 6., 3., 8.]);
+    }
+}
@@ -0,0 +1,115 @@
+use crate::operator::*;
+
+/// Sigmoid operator: y = 1 / (1 + exp(-x)).
+pub(crate) struct Sigmoid;
+
+impl Sigmoid {
+    pub(crate) fn new() -> Self {
+        Self {}
+    }
+}
+
+impl<'hw> Operator<'hw> for Sigmoid {
+    fn name(&self) -> String {
+        String::from("Sigmoid")
+    }
+
+    fn input_size(&self) -> usize {
+        1
+    }
+
+    fn encode_attributes(&self, _encoder: &mut Encoder) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        Ok(inputs[0].clone())
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        Ok(inputs[0].elementwise_sigmoid_f32())
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(SigmoidGrad {}))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(SigmoidForwardGrad {}))
+    }
+}
+
+/// Gradient for Sigmoid.
+struct SigmoidGrad;
+
+impl Gradient for SigmoidGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        _x: &[Node<'hw, 'op, 'g>],
+        y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        _needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        vec![Some(gy * (y - y * y))]
+    }
+}
+
+/// Forward-mode gradient for Sigmoid.
+struct SigmoidForwardGrad;
+
+impl ForwardGradient for SigmoidForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        _x: &[Node<'hw, 'op, 'g>],
+        y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        dx[0] * (y - y * y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array::IntoArray;
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::sigmoid::*;
+
+    #[test]
+    fn test_properties() {
+        let op = Sigmoid::new();
+        assert_eq!(op.name(), "Sigmoid");
+        assert_eq!(op.input_size(), 1);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = Sigmoid::new();
+        assert_eq!(op.perform_shape(&[&Shape::new([])]), Ok(Shape::new([])));
+        assert_eq!(op.perform_shape(&[&Shape::new([0])]), Ok(Shape::new([0])));
+        assert_eq!(op.perform_shape(&[&Shape::new([3])]), Ok(Shape::new([3])));
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = Sigmoid::new();
+
+        assert!(ptr::eq(op.perform_hardware(&[&hw]).unwrap(), &hw));
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = Sigmoid::new();
+        let input = 0f32.into_array(&hw);
+        let expected = 0.5f32.into_array(&hw);
+        let observed = op.perform(&[&input]).unwrap();
+        assert_eq!(observed.shape(), expected.shape());
+        assert_eq!(observed.get_scalar_f32(), expected.get_scalar_f32());
+    }
+}
@@ -0,0 +1,123 @@
+use crate::operator::*;
+
+/// SumAxis operator: sums up values along an axis, which is removed from the output.
+pub(crate) struct SumAxis {
+    /// Index of the reduced axis.
+    axis: usize,
+}
+
+impl SumAxis {
+    pub(crate) fn new(axis: usize) -> Self {
+        Self { axis }
+    }
+}
+
+impl<'hw> Operator<'hw> for SumAxis {
+    fn name(&self) -> String {
+        String::from("SumAxis")
+    }
+
+    fn input_size(&self) -> usize {
+        1
+    }
+
+    fn encode_attributes(&self, encoder: &mut Encoder) -> Result<()> {
+        encoder.write_usize(self.axis);
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        inputs[0].remove_axis(self.axis)
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        inputs[0].sum_axis_f32(self.axis)
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(SumAxisGrad { axis: self.axis }))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(SumAxisForwardGrad { axis: self.axis }))
+    }
+}
+
+/// Gradient for SumAxis.
+struct SumAxisGrad {
+    axis: usize,
+}
+
+impl Gradient for SumAxisGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        _needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        let size = x[0].shape().dimension(self.axis).unwrap();
+        vec![Some(gy.expand_axis(self.axis, size))]
+    }
+}
+
+/// Forward-mode gradient for SumAxis.
+struct SumAxisForwardGrad {
+    axis: usize,
+}
+
+impl ForwardGradient for SumAxisForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        _x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        dx[0].sum_axis(self.axis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::sum_axis::*;
+
+    #[test]
+    fn test_properties() {
+        let op = SumAxis::new(0);
+        assert_eq!(op.name(), "SumAxis");
+        assert_eq!(op.input_size(), 1);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        assert_eq!(SumAxis::new(0).perform_shape(&[&Shape::new([3])]), Ok(Shape::new([])));
+        assert_eq!(SumAxis::new(0).perform_shape(&[&Shape::new([2, 3])]), Ok(Shape::new([3])));
+        assert_eq!(SumAxis::new(1).perform_shape(&[&Shape::new([2, 3])]), Ok(Shape::new([2])));
+        assert!(SumAxis::new(0).perform_shape(&[&Shape::new([])]).is_err());
+        assert!(SumAxis::new(2).perform_shape(&[&Shape::new([2, 3])]).is_err());
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = SumAxis::new(0);
+
+        assert!(ptr::eq(op.perform_hardware(&[&hw]).unwrap(), &hw));
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = SumAxis::new(1);
+        let input = Array::constant_f32(&hw, Shape::new([2, 2]), &[1., 2., 3., 4.]).unwrap();
+        let observed = op.perform(&[&input]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([2]));
+        assert_eq!(observed.get_values_f32(), vec![3., 7.]);
+    }
+}
@@ -0,0 +1,114 @@
+use crate::operator::*;
+
+/// Transpose operator: swaps rows and columns of a matrix.
+pub(crate) struct Transpose;
+
+impl Transpose {
+    pub(crate) fn new() -> Self {
+        Self {}
+    }
+}
+
+impl<'hw> Operator<'hw> for Transpose {
+    fn name(&self) -> String {
+        String::from("Transpose")
+    }
+
+    fn input_size(&self) -> usize {
+        1
+    }
+
+    fn encode_attributes(&self, _encoder: &mut Encoder) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_pure(&self) -> bool {
+        true
+    }
+
+    fn perform_shape(&self, inputs: &[&Shape]) -> Result<Shape> {
+        inputs[0].transpose()
+    }
+
+    fn perform(&self, inputs: &[&Array<'hw>]) -> Result<Array<'hw>> {
+        inputs[0].transpose_f32()
+    }
+
+    fn get_gradient_fn(&self) -> Option<Box<dyn Gradient>> {
+        Some(Box::new(TransposeGrad {}))
+    }
+
+    fn get_forward_gradient_fn(&self) -> Option<Box<dyn ForwardGradient>> {
+        Some(Box::new(TransposeForwardGrad {}))
+    }
+}
+
+/// Gradient for Transpose.
+struct TransposeGrad;
+
+impl Gradient for TransposeGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        _x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        gy: Node<'hw, 'op, 'g>,
+        _needs_grad: &[bool],
+    ) -> Vec<Option<Node<'hw, 'op, 'g>>> {
+        vec![Some(gy.transpose())]
+    }
+}
+
+/// Forward-mode gradient for Transpose.
+struct TransposeForwardGrad;
+
+impl ForwardGradient for TransposeForwardGrad {
+    fn perform<'hw: 'op, 'op: 'g, 'g>(
+        &self,
+        _x: &[Node<'hw, 'op, 'g>],
+        _y: Node<'hw, 'op, 'g>,
+        dx: &[Node<'hw, 'op, 'g>],
+    ) -> Node<'hw, 'op, 'g> {
+        dx[0].transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hardware::cpu::CpuHardware;
+    use crate::operator::transpose::*;
+
+    #[test]
+    fn test_properties() {
+        let op = Transpose::new();
+        assert_eq!(op.name(), "Transpose");
+        assert_eq!(op.input_size(), 1);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_perform_shape() {
+        let op = Transpose::new();
+        assert_eq!(op.perform_shape(&[&Shape::new([2, 3])]), Ok(Shape::new([3, 2])));
+        assert_eq!(op.perform_shape(&[&Shape::new([0, 3])]), Ok(Shape::new([3, 0])));
+        assert!(op.perform_shape(&[&Shape::new([])]).is_err());
+        assert!(op.perform_shape(&[&Shape::new([3])]).is_err());
+    }
+
+    #[test]
+    fn test_perform_hardware() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = Transpose::new();
+
+        assert!(ptr::eq(op.perform_hardware(&[&hw]).unwrap(), &hw));
+    }
+
+    #[test]
+    fn test_perform() {
+        let hw = RefCell::new(CpuHardware::new());
+        let op = Transpose::new();
+        let input = Array::constant_f32(&hw, Shape::new([1, 2]), &[1., 2.]).unwrap();
+        let observed = op.perform(&[&input]).unwrap();
+        assert_eq!(*observed.shape(), Shape::new([2, 1]));
+        assert_eq!(observed.get_values_f32(), vec![1., 2.]);
+    }
+}
@@ -0,0 +1,301 @@
+use crate::array::Array;
+use crate::error::Error;
+use crate::parameter::ParameterStore;
+use crate::result::Result;
+use std::collections::BTreeMap;
+
+/// Interface of optimizers, which update `Parameter`s using their accumulated gradients.
+///
+/// Optimizers identify parameters by their names in the `ParameterStore`, and hold internal
+/// states (e.g., momentum buffers) for each name.
+pub trait Optimizer<'hw> {
+    /// Returns the current learning rate.
+    ///
+    /// # Returns
+    ///
+    /// The learning rate used by the next update.
+    fn learning_rate(&self) -> f32;
+
+    /// Sets a new learning rate.
+    ///
+    /// # Arguments
+    ///
+    /// * `learning_rate` - New learning rate.
+    fn set_learning_rate(&mut self, learning_rate: f32);
+
+    /// Returns the number of performed updates.
+    ///
+    /// # Returns
+    ///
+    /// The number of `update()` calls so far.
+    fn num_steps(&self) -> u64;
+
+    /// Updates all parameters in place using their accumulated gradients.
+    ///
+    /// Gradients are not reset by this function.
+    ///
+    /// # Arguments
+    ///
+    /// * `parameters` - `ParameterStore` holding the parameters to be updated.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - All parameters are updated.
+    /// * `Err(Error)` - Some error occurred during the process.
+    fn update(&mut self, parameters: &ParameterStore<'hw>) -> Result<()>;
+
+    /// Obtains the internal state of the optimizer.
+    ///
+    /// # Returns
+    ///
+    /// A new `OptimizerState` object holding a copy of the internal state.
+    fn state(&self) -> OptimizerState;
+
+    /// Restores the internal state of the optimizer.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - `OptimizerState` obtained by `state()` of the same kind of optimizer.
+    /// * `parameters` - `ParameterStore` holding the parameters to be updated later. States are
+    ///   placed on the same hardware as the corresponding parameters.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The state is restored.
+    /// * `Err(Error)` - `state` is not compatible with this optimizer or `parameters`.
+    fn load_state(
+        &mut self,
+        state: &OptimizerState,
+        parameters: &ParameterStore<'hw>,
+    ) -> Result<()>;
+}
+
+/// Hardware-independent snapshot of the internal state of an `Optimizer`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OptimizerState {
+    /// Number of performed updates.
+    pub num_steps: u64,
+
+    /// Values of the internal states for each parameter name.
+    /// Each parameter has a fixed number of arrays, whose meaning depends on the optimizer.
+    pub slots: BTreeMap<String, Vec<Vec<f32>>>,
+}
+
+/// Magic bytes of the serialized `OptimizerState`.
+const STATE_MAGIC: &[u8; 8] = b"DYCGOPTS";
+
+/// Format version of the serialized `OptimizerState`.
+const STATE_VERSION: u32 = 1;
+
+impl OptimizerState {
+    /// Serializes the state into bytes.
+    ///
+    /// All integers and values are stored with the little endian.
+    ///
+    /// # Returns
+    ///
+    /// Serialized bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut data = vec![];
+        data.extend_from_slice(STATE_MAGIC);
+        data.extend_from_slice(&STATE_VERSION.to_le_bytes());
+        data.extend_from_slice(&self.num_steps.to_le_bytes());
+        data.extend_from_slice(&(self.slots.len() as u64).to_le_bytes());
+        for (name, slots) in &self.slots {
+            data.extend_from_slice(&(name.len() as u64).to_le_bytes());
+            data.extend_from_slice(name.as_bytes());
+            data.extend_from_slice(&(slots.len() as u64).to_le_bytes());
+            for slot in slots {
+                data.extend_from_slice(&(slot.len() as u64).to_le_bytes());
+                for value in slot {
+                    data.extend_from_slice(&value.to_le_bytes());
+                }
+            }
+        }
+        data
+    }
+
+    /// Deserializes the state from bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - Bytes generated by `to_bytes()`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(OptimizerState)` - A new `OptimizerState` object.
+    /// * `Err(Error)` - `data` is broken.
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let mut reader = ByteReader { data };
+        if reader.read(STATE_MAGIC.len())? != STATE_MAGIC {
+            return Err(Error::InvalidData(
+                "Data is not an optimizer state.".to_string(),
+            ));
+        }
+        let version = u32::from_le_bytes(reader.read(4)?.try_into().unwrap());
+        if version != STATE_VERSION {
+            return Err(Error::NotSupported(format!(
+                "Unsupported optimizer state version: {}",
+                version
+            )));
+        }
+        let num_steps = reader.read_u64()?;
+        let num_entries = reader.read_u64()?;
+        let mut slots = BTreeMap::new();
+        for _ in 0..num_entries {
+            let name_len = reader.read_len()?;
+            let name = String::from_utf8(reader.read(name_len)?.to_vec()).map_err(|_| {
+                Error::InvalidData("Parameter name is not a valid UTF-8 string.".to_string())
+            })?;
+            let num_slots = reader.read_u64()?;
+            let mut entry = vec![];
+            for _ in 0..num_slots {
+                let len = reader.read_len()?;
+                let bytes = reader
+                    .read(len.checked_mul(4).ok_or_else(|| {
+                        Error::InvalidData("Slot size is too large.".to_string())
+                    })?)?;
+                entry.push(
+                    bytes
+                        .chunks_exact(4)
+                        .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+                        .collect(),
+                );
+            }
+            slots.insert(name, entry);
+        }
+        if !reader.data.is_empty() {
+            return Err(Error::InvalidData(
+                "Optimizer state has trailing bytes.".to_string(),
+            ));
+        }
+        Ok(Self { num_steps, slots })
+    }
+}
+
+/// Helper to consume bytes from the head of a slice.
+struct ByteReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> ByteReader<'a> {
+    fn read(&mut self, size: usize) -> Result<&'a [u8]> {
+        if self.data.len() < size {
+            return Err(Error::InvalidData(
+                "Optimizer state is truncated.".to_string(),
+            ));
+        }
+        let (head, tail) = self.data.split_at(size);
+        self.data = tail;
+        Ok(head)
+    }
+
+    fn read_u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.read(8)?.try_into().unwrap()))
+    }
+
+    fn read_len(&mut self) -> Result<usize> {
+        usize::try_from(self.read_u64()?)
+            .map_err(|_| Error::InvalidData("Length is too large.".to_string()))
+    }
+}
+
+/// Internal states of optimizers: a fixed number of arrays for each parameter.
+struct Slots<'hw> {
+    /// Number of arrays for each parameter.
+    num_slots: usize,
+
+    /// Number of performed updates.
+    num_steps: u64,
+
+    /// Arrays for each parameter name.
+    values: BTreeMap<String, Vec<Array<'hw>>>,
+}
+
+impl<'hw> Slots<'hw> {
+    fn new(num_slots: usize) -> Self {
+        Self {
+            num_slots,
+            num_steps: 0,
+            values: BTreeMap::new(),
+        }
+    }
+
+    /// Increments the step and applies `f` to every parameter.
+    ///
+    /// # Arguments
+    ///
+    /// * `parameters` - Parameters to be updated.
+    /// * `f` - Function taking the value, the gradient, slots of the parameter and the step
+    ///   number (starting from 1). Slots are initialized by 0 for new parameters.
+    fn update<F>(&mut self, parameters: &ParameterStore<'hw>, mut f: F) -> Result<()>
+    where
+        F: FnMut(&mut Array<'hw>, &Array<'hw>, &mut [Array<'hw>], u64) -> Result<()>,
+    {
+        self.num_steps += 1;
+        let num_steps = self.num_steps;
+        let num_slots = self.num_slots;
+        for (name, parameter) in parameters.iter() {
+            let slots = self.values.entry(name.to_string()).or_insert_with(|| {
+                (0..num_slots)
+                    .map(|_| Array::fill_f32(parameter.hardware(), parameter.shape(), 0.))
+                    .collect()
+            });
+            parameter.update_value(|value, gradient| f(value, gradient, slots, num_steps))?;
+        }
+        Ok(())
+    }
+
+    fn state(&self) -> OptimizerState {
+        OptimizerState {
+            num_steps: self.num_steps,
+            slots: self
+                .values
+                .iter()
+                .map(|(name, slots)| {
+                    (
+                        name.clone(),
+                        slots.iter().map(|slot| slot.get_values_f32()).collect(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    fn load_state(
+        &mut self,
+        state: &OptimizerState,
+        parameters: &ParameterStore<'hw>,
+    ) -> Result<()> {
+        let mut values = BTreeMap::new();
+        for (name, slots) in &state.slots {
+            let parameter = parameters.get(name).ok_or_else(|| {
+                Error::InvalidName(format!("Parameter \"{}\" does not exist.", name))
+            })?;
+            if slots.len() != self.num_slots {
+                return Err(Error::InvalidLength(format!(
+                    "Optimizer requires {} slots, but got {}.",
+                    self.num_slots,
+                    slots.len()
+                )));
+            }
+            let arrays = slots
+                .iter()
+                .map(|slot| Array::constant_f32(parameter.hardware(), parameter.shape(), slot))
+                .collect::<Result<Vec<_>>>()?;
+            values.insert(name.clone(), arrays);
+        }
+        self.num_steps = state.num_steps;
+        self.values = values;
+        Ok(())
+    }
+}
+
+pub mod adagrad;
+pub mod adam;
+pub mod rmsprop;
+pub mod schedule;
+pub mod sgd;
+
+#[cfg(test)]
+mod tests;
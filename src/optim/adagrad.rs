@@ -0,0 +1,101 @@
+use crate::optim::*;
+
+/// Adagrad optimizer with optional L2 penalty.
+///
+/// This optimizer holds one state for each parameter: the sum of squared gradients.
+pub struct Adagrad<'hw> {
+    learning_rate: f32,
+    epsilon: f32,
+    weight_decay: f32,
+    slots: Slots<'hw>,
+}
+
+impl<'hw> Adagrad<'hw> {
+    /// Creates a new `Adagrad` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `learning_rate` - Learning rate.
+    /// * `epsilon` - Small value to avoid zero division, typically 1e-10.
+    /// * `weight_decay` - Coefficient of the L2 penalty.
+    ///
+    /// # Returns
+    ///
+    /// A new `Adagrad` object.
+    pub fn new(learning_rate: f32, epsilon: f32, weight_decay: f32) -> Self {
+        Self {
+            learning_rate,
+            epsilon,
+            weight_decay,
+            slots: Slots::new(1),
+        }
+    }
+}
+
+impl<'hw> Optimizer<'hw> for Adagrad<'hw> {
+    fn learning_rate(&self) -> f32 {
+        self.learning_rate
+    }
+
+    fn set_learning_rate(&mut self, learning_rate: f32) {
+        self.learning_rate = learning_rate;
+    }
+
+    fn num_steps(&self) -> u64 {
+        self.slots.num_steps
+    }
+
+    fn update(&mut self, parameters: &ParameterStore<'hw>) -> Result<()> {
+        let (learning_rate, epsilon, weight_decay) =
+            (self.learning_rate, self.epsilon, self.weight_decay);
+        self.slots
+            .update(parameters, |value, gradient, slots, _step| {
+                value.adagrad_update_f32(
+                    gradient,
+                    &mut slots[0],
+                    learning_rate,
+                    epsilon,
+                    weight_decay,
+                )
+            })
+    }
+
+    fn state(&self) -> OptimizerState {
+        self.slots.state()
+    }
+
+    fn load_state(
+        &mut self,
+        state: &OptimizerState,
+        parameters: &ParameterStore<'hw>,
+    ) -> Result<()> {
+        self.slots.load_state(state, parameters)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array::IntoArray;
+    use crate::hardware::cpu::CpuHardware;
+    use crate::optim::adagrad::*;
+    use crate::parameter::Parameter;
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_update() {
+        let hw = RefCell::new(CpuHardware::new());
+        let mut store = ParameterStore::new();
+        let w = Parameter::new(vec![1f32, 2.].into_array(&hw));
+        store.add("w", w.clone()).unwrap();
+        w.accumulate_gradient(&vec![3f32, -4.].into_array(&hw))
+            .unwrap();
+
+        let mut opt = Adagrad::new(0.5, 0., 0.);
+        opt.update(&store).unwrap();
+        assert_eq!(opt.state().slots["w"], vec![vec![9., 16.]]);
+        assert_eq!(w.value().get_values_f32(), vec![0.5, 2.5]);
+
+        opt.update(&store).unwrap();
+        assert_eq!(opt.state().slots["w"], vec![vec![18., 32.]]);
+    }
+}
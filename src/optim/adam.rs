@@ -0,0 +1,220 @@
+use crate::optim::*;
+
+/// Adam optimizer with optional L2 penalty.
+///
+/// This optimizer holds two states for each parameter: the first and second moments.
+pub struct Adam<'hw> {
+    learning_rate: f32,
+    beta1: f32,
+    beta2: f32,
+    epsilon: f32,
+    weight_decay: f32,
+    decoupled: bool,
+    slots: Slots<'hw>,
+}
+
+impl<'hw> Adam<'hw> {
+    /// Creates a new `Adam` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `learning_rate` - Learning rate.
+    /// * `beta1` - Decay rate of the first moment, typically 0.9.
+    /// * `beta2` - Decay rate of the second moment, typically 0.999.
+    /// * `epsilon` - Small value to avoid zero division, typically 1e-8.
+    /// * `weight_decay` - Coefficient of the L2 penalty added to the gradient.
+    ///
+    /// # Returns
+    ///
+    /// A new `Adam` object.
+    pub fn new(
+        learning_rate: f32,
+        beta1: f32,
+        beta2: f32,
+        epsilon: f32,
+        weight_decay: f32,
+    ) -> Self {
+        Self {
+            learning_rate,
+            beta1,
+            beta2,
+            epsilon,
+            weight_decay,
+            decoupled: false,
+            slots: Slots::new(2),
+        }
+    }
+}
+
+impl<'hw> Optimizer<'hw> for Adam<'hw> {
+    fn learning_rate(&self) -> f32 {
+        self.learning_rate
+    }
+
+    fn set_learning_rate(&mut self, learning_rate: f32) {
+        self.learning_rate = learning_rate;
+    }
+
+    fn num_steps(&self) -> u64 {
+        self.slots.num_steps
+    }
+
+    fn update(&mut self, parameters: &ParameterStore<'hw>) -> Result<()> {
+        let (learning_rate, beta1, beta2, epsilon, weight_decay, decoupled) = (
+            self.learning_rate,
+            self.beta1,
+            self.beta2,
+            self.epsilon,
+            self.weight_decay,
+            self.decoupled,
+        );
+        self.slots
+            .update(parameters, |value, gradient, slots, step| {
+                let (m, v) = slots.split_at_mut(1);
+                value.adam_update_f32(
+                    gradient,
+                    &mut m[0],
+                    &mut v[0],
+                    learning_rate,
+                    beta1,
+                    beta2,
+                    epsilon,
+                    weight_decay,
+                    decoupled,
+                    step,
+                )
+            })
+    }
+
+    fn state(&self) -> OptimizerState {
+        self.slots.state()
+    }
+
+    fn load_state(
+        &mut self,
+        state: &OptimizerState,
+        parameters: &ParameterStore<'hw>,
+    ) -> Result<()> {
+        self.slots.load_state(state, parameters)
+    }
+}
+
+/// Adam optimizer with decoupled weight decay.
+///
+/// Unlike `Adam`, the weight decay is applied directly to the parameter rather than added to the
+/// gradient, so that it is not scaled by the adaptive learning rate.
+pub struct AdamW<'hw> {
+    inner: Adam<'hw>,
+}
+
+impl<'hw> AdamW<'hw> {
+    /// Creates a new `AdamW` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `learning_rate` - Learning rate.
+    /// * `beta1` - Decay rate of the first moment, typically 0.9.
+    /// * `beta2` - Decay rate of the second moment, typically 0.999.
+    /// * `epsilon` - Small value to avoid zero division, typically 1e-8.
+    /// * `weight_decay` - Coefficient of the decoupled weight decay, typically 0.01.
+    ///
+    /// # Returns
+    ///
+    /// A new `AdamW` object.
+    pub fn new(
+        learning_rate: f32,
+        beta1: f32,
+        beta2: f32,
+        epsilon: f32,
+        weight_decay: f32,
+    ) -> Self {
+        let mut inner = Adam::new(learning_rate, beta1, beta2, epsilon, weight_decay);
+        inner.decoupled = true;
+        Self { inner }
+    }
+}
+
+impl<'hw> Optimizer<'hw> for AdamW<'hw> {
+    fn learning_rate(&self) -> f32 {
+        self.inner.learning_rate()
+    }
+
+    fn set_learning_rate(&mut self, learning_rate: f32) {
+        self.inner.set_learning_rate(learning_rate);
+    }
+
+    fn num_steps(&self) -> u64 {
+        self.inner.num_steps()
+    }
+
+    fn update(&mut self, parameters: &ParameterStore<'hw>) -> Result<()> {
+        self.inner.update(parameters)
+    }
+
+    fn state(&self) -> OptimizerState {
+        self.inner.state()
+    }
+
+    fn load_state(
+        &mut self,
+        state: &OptimizerState,
+        parameters: &ParameterStore<'hw>,
+    ) -> Result<()> {
+        self.inner.load_state(state, parameters)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array::IntoArray;
+    use crate::hardware::cpu::CpuHardware;
+    use crate::optim::adam::*;
+    use crate::parameter::Parameter;
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_update() {
+        let hw = RefCell::new(CpuHardware::new());
+        let mut store = ParameterStore::new();
+        let w = Parameter::new(vec![1f32, 2.].into_array(&hw));
+        store.add("w", w.clone()).unwrap();
+        w.accumulate_gradient(&vec![2f32, -4.].into_array(&hw))
+            .unwrap();
+
+        let mut opt = Adam::new(0.5, 0.5, 0.75, 0., 0.);
+        // With a constant gradient, every bias-corrected step moves the value by the learning
+        // rate.
+        opt.update(&store).unwrap();
+        assert_eq!(w.value().get_values_f32(), vec![0.5, 2.5]);
+        opt.update(&store).unwrap();
+        assert_eq!(w.value().get_values_f32(), vec![0., 3.]);
+        assert_eq!(opt.num_steps(), 2);
+
+        let state = opt.state();
+        assert_eq!(state.num_steps, 2);
+        // m == (1 - 0.5^2) * g, v == (1 - 0.75^2) * g^2
+        assert_eq!(state.slots["w"], vec![vec![1.5, -3.], vec![1.75, 7.]]);
+    }
+
+    #[test]
+    fn test_update_weight_decay() {
+        let hw = RefCell::new(CpuHardware::new());
+        let mut store = ParameterStore::new();
+        let w = Parameter::new(4f32.into_array(&hw));
+        store.add("w", w.clone()).unwrap();
+
+        // Adam: the decay is normalized with the gradient, and the step is equal to the learning
+        // rate.
+        let mut adam = Adam::new(0.5, 0.5, 0.5, 0., 0.5);
+        adam.update(&store).unwrap();
+        assert_eq!(w.value().get_scalar_f32(), Ok(3.5));
+
+        // AdamW: the decay is applied directly.
+        w.set_value(4f32.into_array(&hw)).unwrap();
+        w.accumulate_gradient(&1f32.into_array(&hw)).unwrap();
+        let mut adamw = AdamW::new(0.5, 0.5, 0.5, 0., 0.5);
+        adamw.update(&store).unwrap();
+        // 4 - 0.5 * (1 + 0.5 * 4)
+        assert_eq!(w.value().get_scalar_f32(), Ok(2.5));
+    }
+}
@@ -0,0 +1,261 @@
+use crate::optim::Optimizer;
+use std::f32::consts::PI;
+
+/// Interface of learning rate schedules.
+///
+/// A schedule calculates the learning rate from the number of performed updates, so that it can
+/// be resumed from `OptimizerState::num_steps` without any additional state.
+pub trait LearningRateSchedule {
+    /// Calculates the learning rate.
+    ///
+    /// # Arguments
+    ///
+    /// * `step` - Number of performed updates, starting from 0.
+    ///
+    /// # Returns
+    ///
+    /// The learning rate used by the next update.
+    fn learning_rate(&self, step: u64) -> f32;
+
+    /// Sets the learning rate of the optimizer according to its number of performed updates.
+    ///
+    /// This function is typically called just before `Optimizer::update()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `optimizer` - `Optimizer` to be modified.
+    fn apply<'hw>(&self, optimizer: &mut dyn Optimizer<'hw>) {
+        optimizer.set_learning_rate(self.learning_rate(optimizer.num_steps()));
+    }
+}
+
+/// Constant learning rate.
+pub struct ConstantSchedule {
+    learning_rate: f32,
+}
+
+impl ConstantSchedule {
+    /// Creates a new `ConstantSchedule` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `learning_rate` - Learning rate.
+    pub fn new(learning_rate: f32) -> Self {
+        Self { learning_rate }
+    }
+}
+
+impl LearningRateSchedule for ConstantSchedule {
+    fn learning_rate(&self, _step: u64) -> f32 {
+        self.learning_rate
+    }
+}
+
+/// Learning rate multiplied by `gamma` every `step_size` updates.
+pub struct StepDecay {
+    initial: f32,
+    step_size: u64,
+    gamma: f32,
+}
+
+impl StepDecay {
+    /// Creates a new `StepDecay` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial` - Initial learning rate.
+    /// * `step_size` - Number of updates between two decays.
+    /// * `gamma` - Decay factor.
+    ///
+    /// # Panics
+    ///
+    /// `step_size` is 0.
+    pub fn new(initial: f32, step_size: u64, gamma: f32) -> Self {
+        assert!(step_size > 0, "step_size must be greater than 0.");
+        Self {
+            initial,
+            step_size,
+            gamma,
+        }
+    }
+}
+
+impl LearningRateSchedule for StepDecay {
+    fn learning_rate(&self, step: u64) -> f32 {
+        let num_decays = (step / self.step_size).min(i32::MAX as u64) as i32;
+        self.initial * self.gamma.powi(num_decays)
+    }
+}
+
+/// Learning rate multiplied by `gamma` every update.
+pub struct ExponentialDecay {
+    initial: f32,
+    gamma: f32,
+}
+
+impl ExponentialDecay {
+    /// Creates a new `ExponentialDecay` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial` - Initial learning rate.
+    /// * `gamma` - Decay factor.
+    pub fn new(initial: f32, gamma: f32) -> Self {
+        Self { initial, gamma }
+    }
+}
+
+impl LearningRateSchedule for ExponentialDecay {
+    fn learning_rate(&self, step: u64) -> f32 {
+        self.initial * self.gamma.powi(step.min(i32::MAX as u64) as i32)
+    }
+}
+
+/// Learning rate annealed from `initial` to `minimum` along a half cosine curve over `period`
+/// updates, and kept `minimum` afterwards.
+pub struct CosineAnnealing {
+    initial: f32,
+    minimum: f32,
+    period: u64,
+}
+
+impl CosineAnnealing {
+    /// Creates a new `CosineAnnealing` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial` - Initial learning rate.
+    /// * `minimum` - Final learning rate.
+    /// * `period` - Number of updates to reach `minimum`.
+    ///
+    /// # Panics
+    ///
+    /// `period` is 0.
+    pub fn new(initial: f32, minimum: f32, period: u64) -> Self {
+        assert!(period > 0, "period must be greater than 0.");
+        Self {
+            initial,
+            minimum,
+            period,
+        }
+    }
+}
+
+impl LearningRateSchedule for CosineAnnealing {
+    fn learning_rate(&self, step: u64) -> f32 {
+        let ratio = step.min(self.period) as f32 / self.period as f32;
+        self.minimum + 0.5 * (self.initial - self.minimum) * (1. + (PI * ratio).cos())
+    }
+}
+
+/// Learning rate linearly increased during the first `warmup_steps` updates, and determined by
+/// the inner schedule afterwards.
+pub struct LinearWarmup<S: LearningRateSchedule> {
+    warmup_steps: u64,
+    schedule: S,
+}
+
+impl<S: LearningRateSchedule> LinearWarmup<S> {
+    /// Creates a new `LinearWarmup` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `warmup_steps` - Number of updates for the warmup.
+    /// * `schedule` - Inner schedule. During the warmup, its learning rate is multiplied by
+    ///   (step + 1) / `warmup_steps`.
+    pub fn new(warmup_steps: u64, schedule: S) -> Self {
+        Self {
+            warmup_steps,
+            schedule,
+        }
+    }
+}
+
+impl<S: LearningRateSchedule> LearningRateSchedule for LinearWarmup<S> {
+    fn learning_rate(&self, step: u64) -> f32 {
+        let learning_rate = self.schedule.learning_rate(step);
+        if step < self.warmup_steps {
+            learning_rate * (step + 1) as f32 / self.warmup_steps as f32
+        } else {
+            learning_rate
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array::IntoArray;
+    use crate::hardware::cpu::CpuHardware;
+    use crate::optim::schedule::*;
+    use crate::optim::sgd::Sgd;
+    use crate::parameter::{Parameter, ParameterStore};
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_constant() {
+        let schedule = ConstantSchedule::new(0.5);
+        assert_eq!(schedule.learning_rate(0), 0.5);
+        assert_eq!(schedule.learning_rate(1000), 0.5);
+    }
+
+    #[test]
+    fn test_step_decay() {
+        let schedule = StepDecay::new(1., 2, 0.5);
+        let observed = (0..6)
+            .map(|i| schedule.learning_rate(i))
+            .collect::<Vec<_>>();
+        assert_eq!(observed, vec![1., 1., 0.5, 0.5, 0.25, 0.25]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_step_decay_invalid() {
+        let _schedule = StepDecay::new(1., 0, 0.5);
+    }
+
+    #[test]
+    fn test_exponential_decay() {
+        let schedule = ExponentialDecay::new(1., 0.5);
+        let observed = (0..4)
+            .map(|i| schedule.learning_rate(i))
+            .collect::<Vec<_>>();
+        assert_eq!(observed, vec![1., 0.5, 0.25, 0.125]);
+    }
+
+    #[test]
+    fn test_cosine_annealing() {
+        let schedule = CosineAnnealing::new(1., 0.5, 4);
+        assert_eq!(schedule.learning_rate(0), 1.);
+        assert!((schedule.learning_rate(2) - 0.75).abs() < 1e-6);
+        assert_eq!(schedule.learning_rate(4), 0.5);
+        assert_eq!(schedule.learning_rate(100), 0.5);
+    }
+
+    #[test]
+    fn test_linear_warmup() {
+        let schedule = LinearWarmup::new(4, ConstantSchedule::new(1.));
+        let observed = (0..6)
+            .map(|i| schedule.learning_rate(i))
+            .collect::<Vec<_>>();
+        assert_eq!(observed, vec![0.25, 0.5, 0.75, 1., 1., 1.]);
+    }
+
+    #[test]
+    fn test_apply() {
+        let hw = RefCell::new(CpuHardware::new());
+        let mut store = ParameterStore::new();
+        let w = Parameter::new(0f32.into_array(&hw));
+        store.add("w", w.clone()).unwrap();
+        w.accumulate_gradient(&1f32.into_array(&hw)).unwrap();
+
+        let schedule = ExponentialDecay::new(1., 0.5);
+        let mut opt = Sgd::new(0., 0., 0., false);
+        for _ in 0..3 {
+            schedule.apply(&mut opt);
+            opt.update(&store).unwrap();
+        }
+        assert_eq!(opt.learning_rate(), 0.25);
+        // -(1 + 0.5 + 0.25)
+        assert_eq!(w.value().get_scalar_f32(), Ok(-1.75));
+    }
+}
@@ -0,0 +1,149 @@
+use crate::optim::*;
+
+/// Stochastic gradient descent with optional momentum, Nesterov momentum and L2 penalty.
+///
+/// This optimizer holds one state for each parameter: the momentum buffer.
+pub struct Sgd<'hw> {
+    learning_rate: f32,
+    momentum: f32,
+    weight_decay: f32,
+    nesterov: bool,
+    slots: Slots<'hw>,
+}
+
+impl<'hw> Sgd<'hw> {
+    /// Creates a new `Sgd` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `learning_rate` - Learning rate.
+    /// * `momentum` - Momentum factor. 0 represents the plain SGD.
+    /// * `weight_decay` - Coefficient of the L2 penalty.
+    /// * `nesterov` - Whether to use the Nesterov momentum or not.
+    ///
+    /// # Returns
+    ///
+    /// A new `Sgd` object.
+    pub fn new(learning_rate: f32, momentum: f32, weight_decay: f32, nesterov: bool) -> Self {
+        Self {
+            learning_rate,
+            momentum,
+            weight_decay,
+            nesterov,
+            slots: Slots::new(1),
+        }
+    }
+}
+
+impl<'hw> Optimizer<'hw> for Sgd<'hw> {
+    fn learning_rate(&self) -> f32 {
+        self.learning_rate
+    }
+
+    fn set_learning_rate(&mut self, learning_rate: f32) {
+        self.learning_rate = learning_rate;
+    }
+
+    fn num_steps(&self) -> u64 {
+        self.slots.num_steps
+    }
+
+    fn update(&mut self, parameters: &ParameterStore<'hw>) -> Result<()> {
+        let (learning_rate, momentum, weight_decay, nesterov) = (
+            self.learning_rate,
+            self.momentum,
+            self.weight_decay,
+            self.nesterov,
+        );
+        self.slots
+            .update(parameters, |value, gradient, slots, _step| {
+                value.sgd_update_f32(
+                    gradient,
+                    &mut slots[0],
+                    learning_rate,
+                    momentum,
+                    weight_decay,
+                    nesterov,
+                )
+            })
+    }
+
+    fn state(&self) -> OptimizerState {
+        self.slots.state()
+    }
+
+    fn load_state(
+        &mut self,
+        state: &OptimizerState,
+        parameters: &ParameterStore<'hw>,
+    ) -> Result<()> {
+        self.slots.load_state(state, parameters)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array::IntoArray;
+    use crate::hardware::cpu::CpuHardware;
+    use crate::optim::sgd::*;
+    use crate::parameter::Parameter;
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_update() {
+        let hw = RefCell::new(CpuHardware::new());
+        let mut store = ParameterStore::new();
+        let w = Parameter::new(vec![1f32, 2.].into_array(&hw));
+        store.add("w", w.clone()).unwrap();
+        w.accumulate_gradient(&vec![1f32, -2.].into_array(&hw))
+            .unwrap();
+
+        let mut opt = Sgd::new(0.5, 0., 0., false);
+        assert_eq!(opt.learning_rate(), 0.5);
+        assert_eq!(opt.num_steps(), 0);
+
+        opt.update(&store).unwrap();
+        assert_eq!(opt.num_steps(), 1);
+        assert_eq!(w.value().get_values_f32(), vec![0.5, 3.]);
+
+        opt.set_learning_rate(0.25);
+        opt.update(&store).unwrap();
+        assert_eq!(w.value().get_values_f32(), vec![0.25, 3.5]);
+
+        // Gradients are not modified.
+        assert_eq!(w.gradient().get_values_f32(), vec![1., -2.]);
+    }
+
+    #[test]
+    fn test_update_momentum() {
+        let hw = RefCell::new(CpuHardware::new());
+        let mut store = ParameterStore::new();
+        let w = Parameter::new(0f32.into_array(&hw));
+        store.add("w", w.clone()).unwrap();
+        w.accumulate_gradient(&1f32.into_array(&hw)).unwrap();
+
+        let mut opt = Sgd::new(1., 0.5, 0., false);
+        // velocity: 1, 1.5, 1.75
+        for expected in [-1., -2.5, -4.25] {
+            opt.update(&store).unwrap();
+            assert_eq!(w.value().get_scalar_f32(), Ok(expected));
+        }
+        assert_eq!(opt.state().slots["w"], vec![vec![1.75]]);
+    }
+
+    #[test]
+    fn test_update_nesterov() {
+        let hw = RefCell::new(CpuHardware::new());
+        let mut store = ParameterStore::new();
+        let w = Parameter::new(0f32.into_array(&hw));
+        store.add("w", w.clone()).unwrap();
+        w.accumulate_gradient(&1f32.into_array(&hw)).unwrap();
+
+        let mut opt = Sgd::new(1., 0.5, 0., true);
+        // step: 1 + 0.5 * 1, 1 + 0.5 * 1.5
+        for expected in [-1.5, -3.25] {
+            opt.update(&store).unwrap();
+            assert_eq!(w.value().get_scalar_f32(), Ok(expected));
+        }
+    }
+}
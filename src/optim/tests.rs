@@ -0,0 +1,187 @@
+use crate::array::IntoArray;
+use crate::graph::Graph;
+use crate::hardware::cpu::CpuHardware;
+use crate::node::Node;
+use crate::optim::adagrad::Adagrad;
+use crate::optim::adam::{Adam, AdamW};
+use crate::optim::rmsprop::RmsProp;
+use crate::optim::sgd::Sgd;
+use crate::optim::*;
+use crate::parameter::Parameter;
+use crate::shape::Shape;
+use std::cell::RefCell;
+
+#[test]
+fn test_state_bytes() {
+    let mut slots = BTreeMap::new();
+    slots.insert("a".to_string(), vec![vec![1., 2.], vec![]]);
+    slots.insert("bb".to_string(), vec![vec![-3.5]]);
+    let state = OptimizerState {
+        num_steps: 42,
+        slots,
+    };
+
+    let data = state.to_bytes();
+    assert_eq!(OptimizerState::from_bytes(&data), Ok(state));
+}
+
+#[test]
+fn test_state_bytes_empty() {
+    let state = OptimizerState {
+        num_steps: 0,
+        slots: BTreeMap::new(),
+    };
+    assert_eq!(OptimizerState::from_bytes(&state.to_bytes()), Ok(state));
+}
+
+#[test]
+fn test_state_bytes_invalid() {
+    let mut slots = BTreeMap::new();
+    slots.insert("a".to_string(), vec![vec![1., 2.]]);
+    let data = OptimizerState {
+        num_steps: 1,
+        slots,
+    }
+    .to_bytes();
+
+    // Truncated.
+    for size in 0..data.len() {
+        assert!(matches!(
+            OptimizerState::from_bytes(&data[..size]),
+            Err(Error::InvalidData(_))
+        ));
+    }
+
+    // Trailing bytes.
+    let mut trailing = data.clone();
+    trailing.push(0);
+    assert!(matches!(
+        OptimizerState::from_bytes(&trailing),
+        Err(Error::InvalidData(_))
+    ));
+
+    // Broken magic.
+    let mut magic = data.clone();
+    magic[0] = b'X';
+    assert!(matches!(
+        OptimizerState::from_bytes(&magic),
+        Err(Error::InvalidData(_))
+    ));
+
+    // Unknown version.
+    let mut version = data;
+    version[8] = 2;
+    assert!(matches!(
+        OptimizerState::from_bytes(&version),
+        Err(Error::NotSupported(_))
+    ));
+}
+
+#[test]
+fn test_load_state() {
+    let hw = RefCell::new(CpuHardware::new());
+    let mut store = ParameterStore::new();
+    let w = Parameter::new(vec![0f32, 0.].into_array(&hw));
+    store.add("w", w.clone()).unwrap();
+    w.accumulate_gradient(&vec![1f32, 2.].into_array(&hw))
+        .unwrap();
+
+    let mut opt1 = Sgd::new(1., 0.5, 0., false);
+    opt1.update(&store).unwrap();
+    let data = opt1.state().to_bytes();
+
+    // Resumes the momentum.
+    let mut opt2 = Sgd::new(1., 0.5, 0., false);
+    opt2.load_state(&OptimizerState::from_bytes(&data).unwrap(), &store)
+        .unwrap();
+    assert_eq!(opt2.num_steps(), 1);
+    opt1.update(&store).unwrap();
+    let expected = w.value().get_values_f32();
+    w.set_value(vec![-1f32, -2.].into_array(&hw)).unwrap();
+    opt2.update(&store).unwrap();
+    assert_eq!(w.value().get_values_f32(), expected);
+    assert_eq!(opt1.state(), opt2.state());
+}
+
+#[test]
+fn test_load_state_invalid() {
+    let hw = RefCell::new(CpuHardware::new());
+    let mut store = ParameterStore::new();
+    store
+        .add("w", Parameter::new(vec![0f32, 0.].into_array(&hw)))
+        .unwrap();
+    let mut opt = Adam::new(1., 0.9, 0.999, 1e-8, 0.);
+
+    let state = |name: &str, slots: Vec<Vec<f32>>| OptimizerState {
+        num_steps: 1,
+        slots: BTreeMap::from([(name.to_string(), slots)]),
+    };
+
+    assert!(matches!(
+        opt.load_state(&state("x", vec![vec![0., 0.], vec![0., 0.]]), &store),
+        Err(Error::InvalidName(_))
+    ));
+    assert!(matches!(
+        opt.load_state(&state("w", vec![vec![0., 0.]]), &store),
+        Err(Error::InvalidLength(_))
+    ));
+    assert!(matches!(
+        opt.load_state(&state("w", vec![vec![0., 0.], vec![0.]]), &store),
+        Err(Error::InvalidLength(_))
+    ));
+    assert_eq!(opt.num_steps(), 0);
+    assert!(opt
+        .load_state(&state("w", vec![vec![0., 0.], vec![0., 0.]]), &store)
+        .is_ok());
+    assert_eq!(opt.num_steps(), 1);
+}
+
+/// Minimizes (w - 3)^2 + (b + 1)^2 and returns the final values.
+fn minimize<'hw>(
+    hw: &'hw RefCell<CpuHardware>,
+    opt: &mut dyn Optimizer<'hw>,
+    num_steps: usize,
+) -> (f32, f32) {
+    let mut store = ParameterStore::new();
+    let w = Parameter::new(0f32.into_array(hw));
+    let b = Parameter::new(0f32.into_array(hw));
+    store.add("w", w.clone()).unwrap();
+    store.add("b", b.clone()).unwrap();
+
+    for _ in 0..num_steps {
+        let g = RefCell::new(Graph::new());
+        let three = Node::fill(&g, hw, Shape::new([]), 3.);
+        let one = Node::fill(&g, hw, Shape::new([]), 1.);
+        let dw = Node::parameter(&g, &w) - three;
+        let db = Node::parameter(&g, &b) + one;
+        let loss = dw * dw + db * db;
+
+        store.reset_gradients();
+        store.accumulate_gradients(loss);
+        opt.update(&store).unwrap();
+    }
+
+    (
+        w.value().get_scalar_f32().unwrap(),
+        b.value().get_scalar_f32().unwrap(),
+    )
+}
+
+#[test]
+fn test_convergence() {
+    let hw = RefCell::new(CpuHardware::new());
+    let mut optimizers: Vec<Box<dyn Optimizer>> = vec![
+        Box::new(Sgd::new(0.1, 0., 0., false)),
+        Box::new(Sgd::new(0.05, 0.5, 0., false)),
+        Box::new(Sgd::new(0.05, 0.5, 0., true)),
+        Box::new(Adam::new(0.1, 0.9, 0.999, 1e-8, 0.)),
+        Box::new(AdamW::new(0.1, 0.9, 0.999, 1e-8, 0.)),
+        Box::new(RmsProp::new(0.02, 0.9, 1e-8, 0.)),
+        Box::new(Adagrad::new(0.5, 1e-10, 0.)),
+    ];
+    for opt in optimizers.iter_mut() {
+        let (w, b) = minimize(&hw, opt.as_mut(), 300);
+        assert!((w - 3.).abs() < 1e-2, "w = {}", w);
+        assert!((b + 1.).abs() < 1e-2, "b = {}", b);
+    }
+}
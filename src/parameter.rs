@@ -0,0 +1,355 @@
+use crate::array::Array;
+use crate::error::Error;
+use crate::hardware::Hardware;
+use crate::node::{self, Node};
+use crate::result::Result;
+use crate::shape::Shape;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::ptr;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Counter to assign unique IDs to `Parameter`s.
+static NEXT_PARAMETER_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Inner values of `Parameter`.
+struct ParameterInner<'hw> {
+    /// Current value of the parameter.
+    value: Array<'hw>,
+
+    /// Accumulated gradient of the parameter.
+    gradient: Array<'hw>,
+}
+
+/// Trainable parameter which persists across multiple `Graph`s.
+///
+/// `Parameter` is a handle of the shared value: cloned objects refer to the same value and
+/// gradient.
+#[derive(Clone)]
+pub struct Parameter<'hw> {
+    /// Unique ID of the parameter.
+    id: usize,
+
+    /// Shared values.
+    inner: Rc<RefCell<ParameterInner<'hw>>>,
+}
+
+impl<'hw> Parameter<'hw> {
+    /// Creates a new `Parameter` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Initial value of the parameter.
+    ///
+    /// # Returns
+    ///
+    /// A new `Parameter` object, with the gradient initialized by 0.
+    pub fn new(value: Array<'hw>) -> Self {
+        let gradient = Array::fill_colocated_f32(&value, value.shape().clone(), 0.);
+        Self {
+            id: NEXT_PARAMETER_ID.fetch_add(1, Ordering::Relaxed),
+            inner: Rc::new(RefCell::new(ParameterInner { value, gradient })),
+        }
+    }
+
+    /// Returns the unique ID of the parameter.
+    pub(crate) fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Returns the shape of the parameter.
+    ///
+    /// # Returns
+    ///
+    /// `Shape` of the value.
+    pub fn shape(&self) -> Shape {
+        self.inner.borrow().value.shape().clone()
+    }
+
+    /// Returns the hardware of the parameter.
+    ///
+    /// # Returns
+    ///
+    /// A reference to the `Hardware` holding the value.
+    pub fn hardware(&self) -> &'hw RefCell<dyn Hardware> {
+        self.inner.borrow().value.hardware()
+    }
+
+    /// Returns a copy of the current value.
+    ///
+    /// # Returns
+    ///
+    /// A new `Array` holding the same values with the parameter.
+    pub fn value(&self) -> Array<'hw> {
+        self.inner.borrow().value.clone()
+    }
+
+    /// Returns a copy of the accumulated gradient.
+    ///
+    /// # Returns
+    ///
+    /// A new `Array` holding the same values with the gradient.
+    pub fn gradient(&self) -> Array<'hw> {
+        self.inner.borrow().gradient.clone()
+    }
+
+    /// Replaces the value of the parameter.
+    ///
+    /// Nodes already created from this parameter are not affected.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - New value. It must have the same shape and hardware with the current value.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The value is replaced.
+    /// * `Err(Error)` - `value` has an incompatible shape or hardware.
+    pub fn set_value(&self, value: Array<'hw>) -> Result<()> {
+        let mut inner = self.inner.borrow_mut();
+        check_compatible(&inner.value, &value)?;
+        inner.value = value;
+        Ok(())
+    }
+
+    /// Adds a value to the accumulated gradient.
+    ///
+    /// # Arguments
+    ///
+    /// * `gradient` - Value to be added. It must have the same shape and hardware with the
+    ///   parameter.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The gradient is updated.
+    /// * `Err(Error)` - `gradient` has an incompatible shape or hardware.
+    pub fn accumulate_gradient(&self, gradient: &Array<'hw>) -> Result<()> {
+        self.inner.borrow_mut().gradient.add_assign_f32(gradient)
+    }
+
+    /// Performs an in-place operation on the value using the accumulated gradient.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - Function taking the mutable value and the gradient.
+    ///
+    /// # Returns
+    ///
+    /// The return value of `f`.
+    pub(crate) fn update_value<R>(&self, f: impl FnOnce(&mut Array<'hw>, &Array<'hw>) -> R) -> R {
+        let inner = &mut *self.inner.borrow_mut();
+        f(&mut inner.value, &inner.gradient)
+    }
+
+    /// Resets the accumulated gradient to 0.
+    pub fn reset_gradient(&self) {
+        self.inner.borrow_mut().gradient.fill_assign_f32(0.);
+    }
+}
+
+impl<'hw> PartialEq for Parameter<'hw> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<'hw> Eq for Parameter<'hw> {}
+
+/// Checks if two arrays have the same shape and hardware.
+fn check_compatible(expected: &Array, actual: &Array) -> Result<()> {
+    if actual.shape() != expected.shape() {
+        return Err(Error::InvalidShape(format!(
+            "Expected shape {}, but got {}.",
+            expected.shape(),
+            actual.shape()
+        )));
+    }
+    if !ptr::addr_eq(actual.hardware(), expected.hardware()) {
+        return Err(Error::InvalidHardware(
+            "Parameter values must be on the same hardware.".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Collection of named `Parameter`s.
+///
+/// Parameters are ordered by their names, which provides a stable order for optimizers and
+/// checkpointing.
+pub struct ParameterStore<'hw> {
+    /// Registered parameters.
+    parameters: BTreeMap<String, Parameter<'hw>>,
+}
+
+impl<'hw> ParameterStore<'hw> {
+    /// Creates a new empty `ParameterStore` object.
+    ///
+    /// # Returns
+    ///
+    /// A new `ParameterStore` object.
+    pub fn new() -> Self {
+        Self {
+            parameters: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the number of registered parameters.
+    ///
+    /// # Returns
+    ///
+    /// The number of registered parameters.
+    pub fn len(&self) -> usize {
+        self.parameters.len()
+    }
+
+    /// Checks if the store has no parameters.
+    ///
+    /// # Returns
+    ///
+    /// `true` if no parameters are registered, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.parameters.is_empty()
+    }
+
+    /// Registers a parameter.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the parameter. It must be unique in this store.
+    /// * `parameter` - `Parameter` to be registered.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The parameter is registered.
+    /// * `Err(Error)` - `name` is already used.
+    pub fn add(&mut self, name: &str, parameter: Parameter<'hw>) -> Result<()> {
+        if self.parameters.contains_key(name) {
+            return Err(Error::InvalidName(format!(
+                "Parameter \"{}\" already exists.",
+                name
+            )));
+        }
+        self.parameters.insert(name.to_string(), parameter);
+        Ok(())
+    }
+
+    /// Obtains a parameter.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the parameter.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(&Parameter)` - The parameter associated to `name`.
+    /// * `None` - No parameter is associated to `name`.
+    pub fn get(&self, name: &str) -> Option<&Parameter<'hw>> {
+        self.parameters.get(name)
+    }
+
+    /// Returns an iterator over all parameters, ordered by their names.
+    ///
+    /// # Returns
+    ///
+    /// An iterator of `(name, parameter)` pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Parameter<'hw>)> {
+        self.parameters
+            .iter()
+            .map(|(name, parameter)| (name.as_str(), parameter))
+    }
+
+    /// Replaces values of all parameters at once, e.g., by values loaded from a checkpoint.
+    ///
+    /// No parameter is updated if some value is not compatible.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - New values with the names of parameters. Each parameter in this store must
+    ///   have exactly one value with the same shape and hardware.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - All values are replaced.
+    /// * `Err(Error)` - Some name is missing, unknown or duplicated, or some value is not
+    ///   compatible.
+    pub fn set_values(&self, values: Vec<(String, Array<'hw>)>) -> Result<()> {
+        let mut new_values = BTreeMap::new();
+        for (name, value) in values {
+            let Some(parameter) = self.parameters.get(&name) else {
+                return Err(Error::InvalidName(format!(
+                    "Parameter \"{}\" does not exist.",
+                    name
+                )));
+            };
+            check_compatible(&parameter.inner.borrow().value, &value)?;
+            if new_values.insert(name.clone(), value).is_some() {
+                return Err(Error::InvalidName(format!(
+                    "Parameter \"{}\" has multiple values.",
+                    name
+                )));
+            }
+        }
+        if let Some(name) = self
+            .parameters
+            .keys()
+            .find(|name| !new_values.contains_key(*name))
+        {
+            return Err(Error::InvalidName(format!(
+                "Parameter \"{}\" has no value.",
+                name
+            )));
+        }
+        for (name, value) in new_values {
+            self.parameters[&name].inner.borrow_mut().value = value;
+        }
+        Ok(())
+    }
+
+    /// Calculates gradients of `loss` and accumulates them into the parameters.
+    ///
+    /// Only parameters injected into the graph of `loss` by `Node::parameter()` are updated.
+    /// If `loss` is not a scalar, the gradient of the sum of all elements is used.
+    ///
+    /// # Arguments
+    ///
+    /// * `loss` - `Node` representing the value to be differentiated.
+    pub fn accumulate_gradients<'op, 'g>(&self, loss: Node<'hw, 'op, 'g>)
+    where
+        'hw: 'op,
+    {
+        let graph = loss.graph();
+        let (parameters, nodes): (Vec<_>, Vec<_>) = self
+            .parameters
+            .values()
+            .filter_map(|parameter| {
+                graph
+                    .borrow()
+                    .get_parameter_step(parameter.id())
+                    .map(|step_id| (parameter, Node::new(graph, step_id)))
+            })
+            .unzip();
+
+        for (parameter, gradient) in parameters.iter().zip(node::grad(loss, &nodes)) {
+            parameter
+                .accumulate_gradient(&gradient.calculate())
+                .unwrap();
+        }
+    }
+
+    /// Resets gradients of all parameters to 0.
+    pub fn reset_gradients(&self) {
+        for parameter in self.parameters.values() {
+            parameter.reset_gradient();
+        }
+    }
+}
+
+impl<'hw> Default for ParameterStore<'hw> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests;
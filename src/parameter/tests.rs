@@ -0,0 +1,226 @@
+use crate::array::IntoArray;
+use crate::graph::Graph;
+use crate::hardware::cpu::CpuHardware;
+use crate::node::IntoNode;
+use crate::parameter::*;
+
+#[test]
+fn test_new() {
+    let hw = RefCell::new(CpuHardware::new());
+    let param = Parameter::new(vec![1f32, 2., 3.].into_array(&hw));
+    assert_eq!(param.shape(), Shape::new([3]));
+    assert!(ptr::eq(param.hardware(), &hw));
+    assert_eq!(param.value().get_values_f32(), vec![1., 2., 3.]);
+    assert_eq!(param.gradient().get_values_f32(), vec![0., 0., 0.]);
+}
+
+#[test]
+fn test_clone_shares_values() {
+    let hw = RefCell::new(CpuHardware::new());
+    let param1 = Parameter::new(1f32.into_array(&hw));
+    let param2 = param1.clone();
+    let param3 = Parameter::new(1f32.into_array(&hw));
+    assert!(param1 == param2);
+    assert!(param1 != param3);
+
+    param2.set_value(2f32.into_array(&hw)).unwrap();
+    assert_eq!(param1.value().get_scalar_f32(), Ok(2.));
+    assert_eq!(param3.value().get_scalar_f32(), Ok(1.));
+}
+
+#[test]
+fn test_set_value() {
+    let hw = RefCell::new(CpuHardware::new());
+    let param = Parameter::new(vec![1f32, 2.].into_array(&hw));
+    param.set_value(vec![3f32, 4.].into_array(&hw)).unwrap();
+    assert_eq!(param.value().get_values_f32(), vec![3., 4.]);
+}
+
+#[test]
+fn test_set_value_invalid() {
+    let hw1 = RefCell::new(CpuHardware::new());
+    let hw2 = RefCell::new(CpuHardware::new());
+    let param = Parameter::new(vec![1f32, 2.].into_array(&hw1));
+    assert!(matches!(
+        param.set_value(vec![3f32, 4., 5.].into_array(&hw1)),
+        Err(Error::InvalidShape(_))
+    ));
+    assert!(matches!(
+        param.set_value(vec![3f32, 4.].into_array(&hw2)),
+        Err(Error::InvalidHardware(_))
+    ));
+    assert_eq!(param.value().get_values_f32(), vec![1., 2.]);
+}
+
+#[test]
+fn test_accumulate_gradient() {
+    let hw = RefCell::new(CpuHardware::new());
+    let param = Parameter::new(vec![1f32, 2.].into_array(&hw));
+    param
+        .accumulate_gradient(&vec![1f32, 2.].into_array(&hw))
+        .unwrap();
+    param
+        .accumulate_gradient(&vec![10f32, 20.].into_array(&hw))
+        .unwrap();
+    assert_eq!(param.gradient().get_values_f32(), vec![11., 22.]);
+    assert!(matches!(
+        param.accumulate_gradient(&1f32.into_array(&hw)),
+        Err(Error::InvalidShape(_))
+    ));
+
+    param.reset_gradient();
+    assert_eq!(param.gradient().get_values_f32(), vec![0., 0.]);
+}
+
+#[test]
+fn test_node() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let param = Parameter::new(vec![1f32, 2.].into_array(&hw));
+
+    let x1 = Node::parameter(&g, &param);
+    let x2 = Node::parameter(&g, &param);
+
+    // The same node is shared in the same graph.
+    assert_eq!(x1, x2);
+    assert_eq!(g.borrow().num_steps(), 1);
+    assert_eq!(x1.shape(), Shape::new([2]));
+    assert!(ptr::eq(x1.hardware(), &hw));
+
+    // The node holds a snapshot of the value.
+    param.set_value(vec![3f32, 4.].into_array(&hw)).unwrap();
+    assert_eq!(x1.calculate().get_values_f32(), vec![1., 2.]);
+
+    // Another graph obtains its own node.
+    let g2 = RefCell::new(Graph::new());
+    let y = Node::parameter(&g2, &param);
+    assert_eq!(y.calculate().get_values_f32(), vec![3., 4.]);
+}
+
+#[test]
+fn test_store() {
+    let hw = RefCell::new(CpuHardware::new());
+    let mut store = ParameterStore::new();
+    assert!(store.is_empty());
+
+    let w = Parameter::new(1f32.into_array(&hw));
+    let b = Parameter::new(2f32.into_array(&hw));
+    store.add("w", w.clone()).unwrap();
+    store.add("b", b.clone()).unwrap();
+    assert_eq!(store.len(), 2);
+    assert!(matches!(
+        store.add("w", b.clone()),
+        Err(Error::InvalidName(_))
+    ));
+
+    assert!(*store.get("w").unwrap() == w);
+    assert!(*store.get("b").unwrap() == b);
+    assert!(store.get("c").is_none());
+
+    // Ordered by names.
+    let names = store.iter().map(|(name, _)| name).collect::<Vec<_>>();
+    assert_eq!(names, vec!["b", "w"]);
+}
+
+#[test]
+fn test_store_set_values() {
+    let hw = RefCell::new(CpuHardware::new());
+    let mut store = ParameterStore::new();
+    let w = Parameter::new(vec![1f32, 2.].into_array(&hw));
+    let b = Parameter::new(3f32.into_array(&hw));
+    store.add("w", w.clone()).unwrap();
+    store.add("b", b.clone()).unwrap();
+
+    store
+        .set_values(vec![
+            ("w".to_string(), vec![4f32, 5.].into_array(&hw)),
+            ("b".to_string(), 6f32.into_array(&hw)),
+        ])
+        .unwrap();
+    assert_eq!(w.value().get_values_f32(), vec![4., 5.]);
+    assert_eq!(b.value().get_scalar_f32(), Ok(6.));
+}
+
+#[test]
+fn test_store_set_values_invalid() {
+    let hw = RefCell::new(CpuHardware::new());
+    let mut store = ParameterStore::new();
+    let w = Parameter::new(vec![1f32, 2.].into_array(&hw));
+    let b = Parameter::new(3f32.into_array(&hw));
+    store.add("w", w.clone()).unwrap();
+    store.add("b", b.clone()).unwrap();
+
+    let new_w = || ("w".to_string(), vec![4f32, 5.].into_array(&hw));
+    let new_b = || ("b".to_string(), 6f32.into_array(&hw));
+    // Missing
+    assert!(matches!(
+        store.set_values(vec![new_w()]),
+        Err(Error::InvalidName(_))
+    ));
+    // Unknown
+    assert!(matches!(
+        store.set_values(vec![
+            new_w(),
+            new_b(),
+            ("c".to_string(), 7f32.into_array(&hw))
+        ]),
+        Err(Error::InvalidName(_))
+    ));
+    // Duplicated
+    assert!(matches!(
+        store.set_values(vec![new_w(), new_b(), new_b()]),
+        Err(Error::InvalidName(_))
+    ));
+    // Incompatible
+    assert!(matches!(
+        store.set_values(vec![new_w(), ("b".to_string(), vec![6f32].into_array(&hw))]),
+        Err(Error::InvalidShape(_))
+    ));
+
+    assert_eq!(w.value().get_values_f32(), vec![1., 2.]);
+    assert_eq!(b.value().get_scalar_f32(), Ok(3.));
+}
+
+#[test]
+fn test_store_accumulate_gradients() {
+    let hw = RefCell::new(CpuHardware::new());
+    let mut store = ParameterStore::new();
+    let w = Parameter::new(vec![2f32, 3.].into_array(&hw));
+    let b = Parameter::new(vec![1f32, 1.].into_array(&hw));
+    let unused = Parameter::new(5f32.into_array(&hw));
+    store.add("w", w.clone()).unwrap();
+    store.add("b", b.clone()).unwrap();
+    store.add("unused", unused.clone()).unwrap();
+
+    for x in [1f32, 2.] {
+        let g = RefCell::new(Graph::new());
+        let x = Node::fill(&g, &hw, Shape::new([2]), x);
+        let wn = Node::parameter(&g, &w);
+        let bn = Node::parameter(&g, &b);
+        // w appears twice: y = w * x * w + b
+        let y = wn * x * Node::parameter(&g, &w) + bn;
+        store.accumulate_gradients(y);
+    }
+
+    // dy/dw == 2wx, summed over x == 1, 2
+    assert_eq!(w.gradient().get_values_f32(), vec![12., 18.]);
+    assert_eq!(b.gradient().get_values_f32(), vec![2., 2.]);
+    assert_eq!(unused.gradient().get_scalar_f32(), Ok(0.));
+
+    store.reset_gradients();
+    assert_eq!(w.gradient().get_values_f32(), vec![0., 0.]);
+    assert_eq!(b.gradient().get_values_f32(), vec![0., 0.]);
+}
+
+#[test]
+fn test_store_accumulate_gradients_without_parameters() {
+    let hw = RefCell::new(CpuHardware::new());
+    let g = RefCell::new(Graph::new());
+    let mut store = ParameterStore::new();
+    let w = Parameter::new(1f32.into_array(&hw));
+    store.add("w", w.clone()).unwrap();
+
+    let y = 1f32.into_node(&g, &hw);
+    store.accumulate_gradients(y);
+    assert_eq!(w.gradient().get_scalar_f32(), Ok(0.));
+}
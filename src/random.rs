@@ -0,0 +1,279 @@
+use crate::array::Array;
+use crate::hardware::Hardware;
+use crate::shape::Shape;
+use std::cell::RefCell;
+use std::f32::consts::PI;
+
+/// Calculates the Philox4x32-10 block function.
+///
+/// This function is the basis of all random number generators in this crate: every random value
+/// is derived from a pair of (seed, index), so that the resulting streams are reproducible and
+/// independent of how the work is partitioned by hardwares.
+///
+/// # Arguments
+///
+/// * `counter` - Counter block.
+/// * `key` - Key.
+///
+/// # Returns
+///
+/// 4 random words.
+pub fn philox4x32(counter: [u32; 4], key: [u32; 2]) -> [u32; 4] {
+    const M0: u64 = 0xD251_1F53;
+    const M1: u64 = 0xCD9E_8D57;
+    const W0: u32 = 0x9E37_79B9;
+    const W1: u32 = 0xBB67_AE85;
+
+    let mut c = counter;
+    let mut k = key;
+    for round in 0..10 {
+        if round > 0 {
+            k[0] = k[0].wrapping_add(W0);
+            k[1] = k[1].wrapping_add(W1);
+        }
+        let p0 = M0 * c[0] as u64;
+        let p1 = M1 * c[2] as u64;
+        c = [
+            (p1 >> 32) as u32 ^ c[1] ^ k[0],
+            p1 as u32,
+            (p0 >> 32) as u32 ^ c[3] ^ k[1],
+            p0 as u32,
+        ];
+    }
+    c
+}
+
+/// Generates 4 random words for the specific element.
+fn words(seed: u64, index: u64, attempt: u32) -> [u32; 4] {
+    philox4x32(
+        [index as u32, (index >> 32) as u32, attempt, 0],
+        [seed as u32, (seed >> 32) as u32],
+    )
+}
+
+/// Converts a random word into a float in [0, 1).
+fn to_unit(word: u32) -> f32 {
+    (word >> 8) as f32 * (1. / (1u32 << 24) as f32)
+}
+
+/// Generates a uniform random number in [0, 1) for the specific element.
+///
+/// # Arguments
+///
+/// * `seed` - Seed of the stream.
+/// * `index` - Index of the element in the stream.
+///
+/// # Returns
+///
+/// A random number.
+pub fn sample_uniform(seed: u64, index: u64) -> f32 {
+    to_unit(words(seed, index, 0)[0])
+}
+
+/// Generates a standard normal random number for the specific element.
+///
+/// # Arguments
+///
+/// * `seed` - Seed of the stream.
+/// * `index` - Index of the element in the stream.
+/// * `attempt` - Attempt number, used to draw another value for the same element, e.g., by
+///   rejection sampling.
+///
+/// # Returns
+///
+/// A random number.
+pub fn sample_normal(seed: u64, index: u64, attempt: u32) -> f32 {
+    // Box-Muller transform. u1 is in (0, 1] to avoid ln(0).
+    let w = words(seed, index, attempt);
+    let u1 = 1. - to_unit(w[0]);
+    let u2 = to_unit(w[1]);
+    (-2. * u1.ln()).sqrt() * (2. * PI * u2).cos()
+}
+
+/// Generates a standard normal random number truncated into [-bound, bound] for the specific
+/// element.
+///
+/// Values are resampled until they fall into the range. If no value is accepted after many
+/// attempts, the last value is clamped.
+///
+/// # Arguments
+///
+/// * `seed` - Seed of the stream.
+/// * `index` - Index of the element in the stream.
+/// * `bound` - Bound of the absolute value.
+///
+/// # Returns
+///
+/// A random number.
+pub fn sample_truncated_normal(seed: u64, index: u64, bound: f32) -> f32 {
+    const MAX_ATTEMPTS: u32 = 256;
+    let mut value = 0.;
+    for attempt in 0..MAX_ATTEMPTS {
+        value = sample_normal(seed, index, attempt);
+        if value.abs() <= bound {
+            return value;
+        }
+    }
+    value.max(-bound).min(bound)
+}
+
+/// Seedable counter-based random number generator.
+///
+/// `Rng` holds a seed and an offset of the stream. Each generation consumes as many indices as
+/// the number of generated elements, and the i-th element is determined only by the seed and
+/// offset + i. Thus the results are identical regardless of the hardware implementation and its
+/// parallelism.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rng {
+    /// Seed of the stream.
+    seed: u64,
+
+    /// Index of the next element in the stream.
+    offset: u64,
+}
+
+impl Rng {
+    /// Creates a new `Rng` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - Seed of the stream.
+    ///
+    /// # Returns
+    ///
+    /// A new `Rng` object starting at the head of the stream.
+    pub fn new(seed: u64) -> Self {
+        Self { seed, offset: 0 }
+    }
+
+    /// Returns the seed of the stream.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the index of the next element in the stream.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Reserves a range of the stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `num_elements` - Number of elements to be reserved.
+    ///
+    /// # Returns
+    ///
+    /// The first index of the reserved range.
+    pub fn advance(&mut self, num_elements: usize) -> u64 {
+        let offset = self.offset;
+        self.offset = self.offset.wrapping_add(num_elements as u64);
+        offset
+    }
+
+    /// Generates an `Array` of uniform random numbers in [`low`, `high`).
+    ///
+    /// # Arguments
+    ///
+    /// * `hardware` - `Hardware` object to host the value.
+    /// * `shape` - `Shape` of the resulting `Array`.
+    /// * `low` - Lower bound of the values.
+    /// * `high` - Upper bound of the values.
+    ///
+    /// # Returns
+    ///
+    /// A new `Array` object.
+    pub fn uniform_f32<'hw>(
+        &mut self,
+        hardware: &'hw RefCell<dyn Hardware>,
+        shape: Shape,
+        low: f32,
+        high: f32,
+    ) -> Array<'hw> {
+        let offset = self.advance(shape.num_elements());
+        Array::random_uniform_f32(hardware, shape, self.seed, offset, low, high)
+    }
+
+    /// Generates an `Array` of normal random numbers.
+    ///
+    /// # Arguments
+    ///
+    /// * `hardware` - `Hardware` object to host the value.
+    /// * `shape` - `Shape` of the resulting `Array`.
+    /// * `mean` - Mean of the distribution.
+    /// * `stddev` - Standard deviation of the distribution.
+    ///
+    /// # Returns
+    ///
+    /// A new `Array` object.
+    pub fn normal_f32<'hw>(
+        &mut self,
+        hardware: &'hw RefCell<dyn Hardware>,
+        shape: Shape,
+        mean: f32,
+        stddev: f32,
+    ) -> Array<'hw> {
+        let offset = self.advance(shape.num_elements());
+        Array::random_normal_f32(hardware, shape, self.seed, offset, mean, stddev)
+    }
+
+    /// Generates an `Array` of normal random numbers truncated into
+    /// [`mean` - `bound` * `stddev`, `mean` + `bound` * `stddev`].
+    ///
+    /// # Arguments
+    ///
+    /// * `hardware` - `Hardware` object to host the value.
+    /// * `shape` - `Shape` of the resulting `Array`.
+    /// * `mean` - Mean of the distribution before truncation.
+    /// * `stddev` - Standard deviation of the distribution before truncation.
+    /// * `bound` - Bound of the values in the unit of `stddev`, typically 2.
+    ///
+    /// # Returns
+    ///
+    /// A new `Array` object.
+    ///
+    /// # Panics
+    ///
+    /// `bound` is not positive.
+    pub fn truncated_normal_f32<'hw>(
+        &mut self,
+        hardware: &'hw RefCell<dyn Hardware>,
+        shape: Shape,
+        mean: f32,
+        stddev: f32,
+        bound: f32,
+    ) -> Array<'hw> {
+        assert!(bound > 0., "bound must be positive.");
+        let offset = self.advance(shape.num_elements());
+        Array::random_truncated_normal_f32(hardware, shape, self.seed, offset, mean, stddev, bound)
+    }
+
+    /// Generates an `Array` of Bernoulli random numbers: 1 with probability `p`, 0 otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `hardware` - `Hardware` object to host the value.
+    /// * `shape` - `Shape` of the resulting `Array`.
+    /// * `p` - Probability to obtain 1.
+    ///
+    /// # Returns
+    ///
+    /// A new `Array` object.
+    ///
+    /// # Panics
+    ///
+    /// `p` is not in [0, 1].
+    pub fn bernoulli_f32<'hw>(
+        &mut self,
+        hardware: &'hw RefCell<dyn Hardware>,
+        shape: Shape,
+        p: f32,
+    ) -> Array<'hw> {
+        assert!((0. ..=1.).contains(&p), "p must be in [0, 1].");
+        let offset = self.advance(shape.num_elements());
+        Array::random_bernoulli_f32(hardware, shape, self.seed, offset, p)
+    }
+}
+
+#[cfg(test)]
+mod tests;
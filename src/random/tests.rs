@@ -0,0 +1,164 @@
+use crate::hardware::cpu::CpuHardware;
+use crate::random::*;
+
+fn mean_and_variance(values: &[f32]) -> (f32, f32) {
+    let n = values.len() as f32;
+    let mean = values.iter().sum::<f32>() / n;
+    let variance = values.iter().map(|x| (x - mean) * (x - mean)).sum::<f32>() / n;
+    (mean, variance)
+}
+
+#[test]
+fn test_philox4x32() {
+    // Known answers from the Random123 reference implementation.
+    assert_eq!(
+        philox4x32([0, 0, 0, 0], [0, 0]),
+        [0x6627e8d5, 0xe169c58d, 0xbc57ac4c, 0x9b00dbd8]
+    );
+    assert_eq!(
+        philox4x32([u32::MAX; 4], [u32::MAX; 2]),
+        [0x408f276d, 0x41c83b0e, 0xa20bc7c6, 0x6d5451fd]
+    );
+    assert_eq!(
+        philox4x32(
+            [0x243f6a88, 0x85a308d3, 0x13198a2e, 0x03707344],
+            [0xa4093822, 0x299f31d0]
+        ),
+        [0xd16cfe09, 0x94fdcceb, 0x5001e420, 0x24126ea1]
+    );
+}
+
+#[test]
+fn test_sample_uniform() {
+    for i in 0..1000 {
+        let u = sample_uniform(123, i);
+        assert!((0. ..1.).contains(&u));
+    }
+    assert_eq!(sample_uniform(123, 7), sample_uniform(123, 7));
+    assert_ne!(sample_uniform(123, 7), sample_uniform(123, 8));
+    assert_ne!(sample_uniform(123, 7), sample_uniform(124, 7));
+}
+
+#[test]
+fn test_sample_normal() {
+    assert!((0..1000).all(|i| sample_normal(123, i, 0).is_finite()));
+    assert_ne!(sample_normal(123, 7, 0), sample_normal(123, 7, 1));
+}
+
+#[test]
+fn test_sample_truncated_normal() {
+    for i in 0..1000 {
+        assert!(sample_truncated_normal(123, i, 0.5).abs() <= 0.5);
+    }
+}
+
+#[test]
+fn test_rng_new() {
+    let rng = Rng::new(42);
+    assert_eq!(rng.seed(), 42);
+    assert_eq!(rng.offset(), 0);
+}
+
+#[test]
+fn test_rng_advance() {
+    let mut rng = Rng::new(42);
+    assert_eq!(rng.advance(3), 0);
+    assert_eq!(rng.advance(5), 3);
+    assert_eq!(rng.offset(), 8);
+}
+
+#[test]
+fn test_rng_reproducible() {
+    let hw = RefCell::new(CpuHardware::new());
+    let mut rng1 = Rng::new(42);
+    let mut rng2 = Rng::new(42);
+
+    let a = rng1.normal_f32(&hw, Shape::new([2, 3]), 0., 1.);
+    let b = rng2.normal_f32(&hw, Shape::new([2, 3]), 0., 1.);
+    assert_eq!(a.get_values_f32(), b.get_values_f32());
+    assert_eq!(rng1.offset(), 6);
+
+    // Subsequent generations do not repeat the stream.
+    let c = rng1.normal_f32(&hw, Shape::new([2, 3]), 0., 1.);
+    assert_ne!(a.get_values_f32(), c.get_values_f32());
+}
+
+#[test]
+fn test_rng_independent_of_partition() {
+    let hw = RefCell::new(CpuHardware::new());
+    let whole = Rng::new(42)
+        .uniform_f32(&hw, Shape::new([10]), 0., 1.)
+        .get_values_f32();
+
+    // Generating the same range in several chunks yields the same stream.
+    let mut rng = Rng::new(42);
+    let mut chunks = rng
+        .uniform_f32(&hw, Shape::new([3]), 0., 1.)
+        .get_values_f32();
+    chunks.extend(
+        rng.uniform_f32(&hw, Shape::new([7]), 0., 1.)
+            .get_values_f32(),
+    );
+    assert_eq!(chunks, whole);
+}
+
+#[test]
+fn test_rng_uniform_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let values = Rng::new(1)
+        .uniform_f32(&hw, Shape::new([10000]), -2., 4.)
+        .get_values_f32();
+    assert!(values.iter().all(|x| (-2. ..4.).contains(x)));
+    let (mean, variance) = mean_and_variance(&values);
+    assert!((mean - 1.).abs() < 0.1);
+    assert!((variance - 3.).abs() < 0.2);
+}
+
+#[test]
+fn test_rng_normal_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let values = Rng::new(1)
+        .normal_f32(&hw, Shape::new([10000]), 1., 2.)
+        .get_values_f32();
+    let (mean, variance) = mean_and_variance(&values);
+    assert!((mean - 1.).abs() < 0.1);
+    assert!((variance - 4.).abs() < 0.2);
+}
+
+#[test]
+fn test_rng_truncated_normal_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let values = Rng::new(1)
+        .truncated_normal_f32(&hw, Shape::new([10000]), 1., 2., 2.)
+        .get_values_f32();
+    assert!(values.iter().all(|x| (-3. ..=5.).contains(x)));
+    let (mean, variance) = mean_and_variance(&values);
+    assert!((mean - 1.).abs() < 0.1);
+    // Variance of the standard normal truncated into [-2, 2] is about 0.774.
+    assert!((variance - 4. * 0.774).abs() < 0.2);
+}
+
+#[test]
+fn test_rng_bernoulli_f32() {
+    let hw = RefCell::new(CpuHardware::new());
+    let values = Rng::new(1)
+        .bernoulli_f32(&hw, Shape::new([10000]), 0.3)
+        .get_values_f32();
+    assert!(values.iter().all(|&x| x == 0. || x == 1.));
+    let (mean, _) = mean_and_variance(&values);
+    assert!((mean - 0.3).abs() < 0.02);
+}
+
+#[test]
+#[should_panic]
+fn test_rng_truncated_normal_f32_invalid_bound() {
+    let hw = RefCell::new(CpuHardware::new());
+    let _array = Rng::new(1).truncated_normal_f32(&hw, Shape::new([]), 0., 1., 0.);
+}
+
+#[test]
+#[should_panic]
+fn test_rng_bernoulli_f32_invalid_probability() {
+    let hw = RefCell::new(CpuHardware::new());
+    let _array = Rng::new(1).bernoulli_f32(&hw, Shape::new([]), 1.5);
+}
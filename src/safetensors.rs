@@ -0,0 +1,465 @@
+use crate::array::Array;
+use crate::buffer::Buffer;
+use crate::error::Error;
+use crate::hardware::cpu::mapped::MappedFile;
+use crate::hardware::cpu::CpuHardware;
+use crate::hardware::Hardware;
+use crate::parameter::ParameterStore;
+use crate::result::Result;
+use crate::safetensors::json::Value;
+use crate::shape::{Shape, MAX_NUM_DIMENSIONS};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io;
+use std::mem;
+use std::path::Path;
+use std::rc::Rc;
+
+/// Size in bytes of the header length at the beginning of files.
+const HEADER_LENGTH_SIZE: usize = 8;
+
+/// Total length of the header length and the header is aligned to this value.
+const HEADER_ALIGNMENT: usize = 8;
+
+/// Maximum size in bytes of the header, following the reference implementation.
+const MAX_HEADER_SIZE: usize = 100_000_000;
+
+/// Reserved key of the header holding free-form string metadata.
+const METADATA_KEY: &str = "__metadata__";
+
+/// Element types supported in safetensors files.
+#[derive(Clone, Copy, PartialEq)]
+enum DataType {
+    Bool,
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    U64,
+    I64,
+    F16,
+    BF16,
+    F32,
+    F64,
+}
+
+impl DataType {
+    /// Obtains the data type from the `dtype` field of the header.
+    fn from_name(name: &str) -> Result<Self> {
+        Ok(match name {
+            "BOOL" => Self::Bool,
+            "U8" => Self::U8,
+            "I8" => Self::I8,
+            "U16" => Self::U16,
+            "I16" => Self::I16,
+            "U32" => Self::U32,
+            "I32" => Self::I32,
+            "U64" => Self::U64,
+            "I64" => Self::I64,
+            "F16" => Self::F16,
+            "BF16" => Self::BF16,
+            "F32" => Self::F32,
+            "F64" => Self::F64,
+            _ => {
+                return Err(Error::NotSupported(format!(
+                    "Unsupported data type: {}",
+                    name
+                )))
+            }
+        })
+    }
+
+    /// Number of bytes of each element.
+    fn size(self) -> usize {
+        match self {
+            Self::Bool | Self::U8 | Self::I8 => 1,
+            Self::U16 | Self::I16 | Self::F16 | Self::BF16 => 2,
+            Self::U32 | Self::I32 | Self::F32 => 4,
+            Self::U64 | Self::I64 | Self::F64 => 8,
+        }
+    }
+
+    /// Converts a little-endian element into `f32`.
+    fn to_f32(self, bytes: &[u8]) -> f32 {
+        match self {
+            Self::Bool | Self::U8 => bytes[0] as f32,
+            Self::I8 => bytes[0] as i8 as f32,
+            Self::U16 => u16::from_le_bytes(bytes.try_into().unwrap()) as f32,
+            Self::I16 => i16::from_le_bytes(bytes.try_into().unwrap()) as f32,
+            Self::U32 => u32::from_le_bytes(bytes.try_into().unwrap()) as f32,
+            Self::I32 => i32::from_le_bytes(bytes.try_into().unwrap()) as f32,
+            Self::U64 => u64::from_le_bytes(bytes.try_into().unwrap()) as f32,
+            Self::I64 => i64::from_le_bytes(bytes.try_into().unwrap()) as f32,
+            Self::F16 => f16_to_f32(u16::from_le_bytes(bytes.try_into().unwrap())),
+            Self::BF16 => {
+                f32::from_bits((u16::from_le_bytes(bytes.try_into().unwrap()) as u32) << 16)
+            }
+            Self::F32 => f32::from_le_bytes(bytes.try_into().unwrap()),
+            Self::F64 => f64::from_le_bytes(bytes.try_into().unwrap()) as f32,
+        }
+    }
+}
+
+/// Converts an IEEE 754 half precision number into `f32`.
+fn f16_to_f32(bits: u16) -> f32 {
+    let sign = (bits as u32 & 0x8000) << 16;
+    let exponent = (bits >> 10) & 0x1f;
+    let mantissa = (bits & 0x3ff) as u32;
+    let magnitude = match exponent {
+        // Zeros and subnormal numbers
+        0 => mantissa as f32 / (1 << 24) as f32,
+        // Infinities and NaNs
+        0x1f => f32::from_bits(0x7f800000 | mantissa << 13),
+        _ => f32::from_bits((exponent as u32 + 127 - 15) << 23 | mantissa << 13),
+    };
+    f32::from_bits(sign | magnitude.to_bits())
+}
+
+/// Location of a tensor in the data section.
+struct TensorInfo {
+    name: String,
+    data_type: DataType,
+    shape: Shape,
+
+    /// Offset in bytes of the beginning of the tensor, relative to the data section.
+    begin: usize,
+
+    /// Offset in bytes of the end of the tensor, relative to the data section.
+    end: usize,
+}
+
+impl TensorInfo {
+    /// Parses a member of the header.
+    fn parse(name: String, value: &Value) -> Result<Self> {
+        let error = || Error::InvalidData(format!("Invalid header of tensor {}.", name));
+        let Value::Object(fields) = value else {
+            return Err(error());
+        };
+        let field = |key: &str| {
+            fields
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v)
+                .ok_or_else(error)
+        };
+        let Value::String(data_type) = field("dtype")? else {
+            return Err(error());
+        };
+        let data_type = DataType::from_name(data_type)?;
+        let dims = match field("shape")? {
+            Value::Array(dims) => dims
+                .iter()
+                .map(|dim| dim.as_usize().ok_or_else(error))
+                .collect::<Result<Vec<_>>>()?,
+            _ => return Err(error()),
+        };
+        let (begin, end) = match field("data_offsets")? {
+            Value::Array(offsets) => match offsets.as_slice() {
+                [begin, end] => (
+                    begin.as_usize().ok_or_else(error)?,
+                    end.as_usize().ok_or_else(error)?,
+                ),
+                _ => return Err(error()),
+            },
+            _ => return Err(error()),
+        };
+
+        let size = dims
+            .iter()
+            .try_fold(data_type.size(), |acc, &d| acc.checked_mul(d));
+        if dims.len() > MAX_NUM_DIMENSIONS || size.is_none() {
+            return Err(Error::InvalidShape(format!(
+                "Unsupported shape of tensor {}: {:?}",
+                name, dims
+            )));
+        }
+        if begin > end || Some(end - begin) != size {
+            return Err(Error::InvalidData(format!(
+                "Data offsets of tensor {} do not match its shape.",
+                name
+            )));
+        }
+        Ok(Self {
+            name,
+            data_type,
+            shape: Shape::from_slice(&dims),
+            begin,
+            end,
+        })
+    }
+
+    /// Decodes the tensor by copying its data.
+    fn decode<'hw>(&self, data: &[u8], hardware: &'hw RefCell<dyn Hardware>) -> Result<Array<'hw>> {
+        let values = data[self.begin..self.end]
+            .chunks_exact(self.data_type.size())
+            .map(|bytes| self.data_type.to_f32(bytes))
+            .collect::<Vec<_>>();
+        Array::constant_f32(hardware, self.shape.clone(), &values)
+    }
+}
+
+/// Parses the layout of a safetensors file.
+///
+/// # Arguments
+///
+/// * `bytes` - Whole contents of the file.
+///
+/// # Returns
+///
+/// * `Ok((Vec<TensorInfo>, usize))` - Tensors sorted by their offsets, and the offset of the
+///   data section in the file.
+/// * `Err(Error)` - The file is broken or not supported.
+fn parse_layout(bytes: &[u8]) -> Result<(Vec<TensorInfo>, usize)> {
+    let header_len = bytes
+        .get(..HEADER_LENGTH_SIZE)
+        .map(|len| u64::from_le_bytes(len.try_into().unwrap()))
+        .ok_or_else(|| Error::InvalidData("Not a safetensors file.".to_string()))?;
+    let data_offset = usize::try_from(header_len)
+        .ok()
+        .filter(|&len| len <= MAX_HEADER_SIZE)
+        .map(|len| HEADER_LENGTH_SIZE + len)
+        .filter(|&offset| offset <= bytes.len())
+        .ok_or_else(|| Error::InvalidData(format!("Invalid header length: {}", header_len)))?;
+    let header = std::str::from_utf8(&bytes[HEADER_LENGTH_SIZE..data_offset])
+        .map_err(|_| Error::InvalidData("Header is not a valid UTF-8 string.".to_string()))?;
+    if !header.starts_with('{') {
+        return Err(Error::InvalidData(
+            "Header is not a JSON object.".to_string(),
+        ));
+    }
+    let Value::Object(members) = json::parse(header)? else {
+        return Err(Error::InvalidData(
+            "Header is not a JSON object.".to_string(),
+        ));
+    };
+
+    let mut names = HashSet::new();
+    let mut tensors = vec![];
+    for (name, value) in members {
+        if !names.insert(name.clone()) {
+            return Err(Error::InvalidData(format!("Duplicated name: {}", name)));
+        }
+        if name == METADATA_KEY {
+            match &value {
+                Value::Object(entries)
+                    if entries.iter().all(|(_, v)| matches!(v, Value::String(_))) => {}
+                _ => {
+                    return Err(Error::InvalidData(
+                        "Metadata must be a map of strings.".to_string(),
+                    ))
+                }
+            }
+            continue;
+        }
+        tensors.push(TensorInfo::parse(name, &value)?);
+    }
+
+    // Tensors must cover the data section without gaps or overlaps.
+    tensors.sort_by_key(|tensor| (tensor.begin, tensor.end));
+    let mut expected = 0;
+    for tensor in &tensors {
+        if tensor.begin != expected {
+            return Err(Error::InvalidData(format!(
+                "Tensor {} is not adjacent to the previous tensor.",
+                tensor.name
+            )));
+        }
+        expected = tensor.end;
+    }
+    if data_offset + expected != bytes.len() {
+        return Err(Error::InvalidData(format!(
+            "Data section has {} bytes, but tensors require {} bytes.",
+            bytes.len() - data_offset,
+            expected
+        )));
+    }
+    Ok((tensors, data_offset))
+}
+
+/// Saves named arrays in the safetensors format.
+///
+/// The file consists of the header length (u64), the JSON header, and the raw values of arrays.
+/// Arrays are stored as little-endian `F32` in the given order, and the header is padded with
+/// spaces so that the data section is aligned to 8 bytes.
+///
+/// # Arguments
+///
+/// * `arrays` - Names and arrays to be saved.
+/// * `writer` - Destination of the data.
+///
+/// # Returns
+///
+/// * `Ok(())` - The arrays are saved.
+/// * `Err(Error)` - Names are duplicated or reserved, or writing failed.
+pub fn save<W: io::Write>(arrays: &[(&str, &Array)], writer: &mut W) -> Result<()> {
+    let mut names = HashSet::new();
+    let mut header = String::from("{");
+    let mut offset = 0;
+    for (i, (name, array)) in arrays.iter().enumerate() {
+        if *name == METADATA_KEY || !names.insert(*name) {
+            return Err(Error::InvalidName(format!(
+                "Name is duplicated or reserved: {}",
+                name
+            )));
+        }
+        if i > 0 {
+            header.push(',');
+        }
+        json::write_string(&mut header, name);
+        let size = array.shape().num_elements() * mem::size_of::<f32>();
+        header.push_str(&format!(
+            ":{{\"dtype\":\"F32\",\"shape\":[{}],\"data_offsets\":[{},{}]}}",
+            array
+                .shape()
+                .dimensions()
+                .iter()
+                .map(|d| d.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+            offset,
+            offset + size
+        ));
+        offset += size;
+    }
+    header.push('}');
+    let padded_len = (HEADER_LENGTH_SIZE + header.len()).next_multiple_of(HEADER_ALIGNMENT);
+    header.extend(std::iter::repeat_n(
+        ' ',
+        padded_len - HEADER_LENGTH_SIZE - header.len(),
+    ));
+
+    let mut bytes = Vec::with_capacity(padded_len + offset);
+    bytes.extend((header.len() as u64).to_le_bytes());
+    bytes.extend(header.as_bytes());
+    for (_, array) in arrays {
+        for value in array.get_values_f32() {
+            bytes.extend(value.to_le_bytes());
+        }
+    }
+    writer
+        .write_all(&bytes)
+        .map_err(|e| Error::Io(e.to_string()))
+}
+
+/// Saves values of all parameters in the safetensors format.
+///
+/// # Arguments
+///
+/// * `parameters` - `ParameterStore` to be saved. Parameters are stored with their names.
+/// * `writer` - Destination of the data.
+///
+/// # Returns
+///
+/// * `Ok(())` - The parameters are saved.
+/// * `Err(Error)` - Some name is reserved, or writing failed.
+pub fn save_parameters<W: io::Write>(parameters: &ParameterStore, writer: &mut W) -> Result<()> {
+    let values = parameters
+        .iter()
+        .map(|(name, parameter)| (name, parameter.value()))
+        .collect::<Vec<_>>();
+    let arrays = values
+        .iter()
+        .map(|(name, value)| (*name, value))
+        .collect::<Vec<_>>();
+    save(&arrays, writer)
+}
+
+/// Loads named arrays from the safetensors format.
+///
+/// Values of all supported data types (`BOOL`, `U8`, `I8`, `U16`, `I16`, `U32`, `I32`, `U64`,
+/// `I64`, `F16`, `BF16`, `F32` and `F64`) are converted into `f32`. Metadata is ignored.
+///
+/// # Arguments
+///
+/// * `hardware` - `Hardware` object to host the values.
+/// * `reader` - Source of the data. All bytes until the end are consumed.
+///
+/// # Returns
+///
+/// * `Ok(Vec<(String, Array)>)` - Names and arrays in the order of the data section.
+/// * `Err(Error)` - The data is broken, or it uses an unsupported data type.
+pub fn load<'hw, R: io::Read>(
+    hardware: &'hw RefCell<dyn Hardware>,
+    reader: &mut R,
+) -> Result<Vec<(String, Array<'hw>)>> {
+    let mut bytes = vec![];
+    reader
+        .read_to_end(&mut bytes)
+        .map_err(|e| Error::Io(e.to_string()))?;
+    let (tensors, data_offset) = parse_layout(&bytes)?;
+    let data = &bytes[data_offset..];
+    tensors
+        .into_iter()
+        .map(|tensor| {
+            let array = tensor.decode(data, hardware)?;
+            Ok((tensor.name, array))
+        })
+        .collect()
+}
+
+/// Loads named arrays from a safetensors file using memory mapping.
+///
+/// `F32` tensors aligned to 4 bytes in the file, which is the usual case, are not copied: the
+/// resulting arrays directly use memories on the file mapped with copy-on-write pages, so that
+/// updating the arrays never modifies the file. The mapping is released when all such arrays are
+/// dropped. Other tensors are converted in the same manner as `load()`.
+///
+/// Memory mapping is used on 64-bit Unix platforms. Other platforms read the whole file instead.
+/// Use `load()` to always copy the values.
+///
+/// # Arguments
+///
+/// * `hardware` - `CpuHardware` object to host the values.
+/// * `path` - Path to the file.
+///
+/// # Returns
+///
+/// * `Ok(Vec<(String, Array)>)` - Names and arrays in the order of the data section.
+/// * `Err(Error)` - The file could not be opened, is broken, or uses an unsupported data type.
+///
+/// # Safety
+///
+/// The file must not be truncated or modified, by this or any other process, until all the
+/// resulting arrays are dropped. Otherwise, accessing the arrays results in undefined behavior,
+/// e.g., `SIGBUS` or values changing without any writes.
+pub unsafe fn load_mmap<'hw, P: AsRef<Path>>(
+    hardware: &'hw RefCell<CpuHardware>,
+    path: P,
+) -> Result<Vec<(String, Array<'hw>)>> {
+    let file = File::open(path).map_err(|e| Error::Io(e.to_string()))?;
+    let mapped = Rc::new(MappedFile::new(&file)?);
+    let bytes = mapped.as_slice();
+    let (tensors, data_offset) = parse_layout(bytes)?;
+
+    let mut arrays = vec![];
+    for tensor in tensors {
+        let offset = data_offset + tensor.begin;
+        let size = tensor.end - tensor.begin;
+        let zero_copy = tensor.data_type == DataType::F32
+            && cfg!(target_endian = "little")
+            && size > 0
+            && (bytes.as_ptr() as usize + offset).is_multiple_of(mem::align_of::<f32>());
+        // `parse_layout()` guarantees that non-empty tensors never overlap, so that each range of
+        // the mapping is supplied at most once.
+        let array = if zero_copy {
+            unsafe {
+                let handle = hardware
+                    .borrow_mut()
+                    .supply_mapped_memory(&mapped, offset, size);
+                Array::from_buffer(tensor.shape, Buffer::from_handle(hardware, handle, size))
+            }
+        } else {
+            tensor.decode(&bytes[data_offset..], hardware)?
+        };
+        arrays.push((tensor.name, array));
+    }
+    Ok(arrays)
+}
+
+mod json;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,259 @@
+use crate::error::Error;
+use crate::result::Result;
+
+/// Maximum depth of nested arrays and objects.
+const MAX_DEPTH: usize = 64;
+
+/// JSON value.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    /// Numbers are kept as they are written, to be parsed in the desired type.
+    Number(String),
+    String(String),
+    Array(Vec<Value>),
+    /// Members are kept in the written order, including duplicated keys.
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Obtains the value as an unsigned integer.
+    pub(crate) fn as_usize(&self) -> Option<usize> {
+        match self {
+            Self::Number(text) => text.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Parses a JSON text.
+///
+/// # Arguments
+///
+/// * `text` - JSON text.
+///
+/// # Returns
+///
+/// * `Ok(Value)` - Parsed value.
+/// * `Err(Error)` - `text` is not a valid JSON.
+pub(crate) fn parse(text: &str) -> Result<Value> {
+    let mut parser = Parser {
+        text: text.as_bytes(),
+        pos: 0,
+    };
+    let value = parser.value(0)?;
+    if parser.peek().is_some() {
+        return Err(parser.error());
+    }
+    Ok(value)
+}
+
+/// Appends a JSON string literal.
+///
+/// # Arguments
+///
+/// * `output` - Destination of the literal.
+/// * `value` - String to be written.
+pub(crate) fn write_string(output: &mut String, value: &str) {
+    output.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if (c as u32) < 0x20 => output.push_str(&format!("\\u{:04x}", c as u32)),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+}
+
+/// Recursive descent parser of JSON.
+struct Parser<'a> {
+    text: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self) -> Error {
+        Error::InvalidData(format!("Invalid JSON at {}.", self.pos))
+    }
+
+    /// Skips whitespaces and returns the next character without consuming it.
+    fn peek(&mut self) -> Option<u8> {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.text.get(self.pos) {
+            self.pos += 1;
+        }
+        self.text.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, c: u8) -> Result<()> {
+        if self.peek() != Some(c) {
+            return Err(self.error());
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    /// Consumes a keyword such as `true`.
+    fn keyword(&mut self, keyword: &[u8], value: Value) -> Result<Value> {
+        if !self.text[self.pos..].starts_with(keyword) {
+            return Err(self.error());
+        }
+        self.pos += keyword.len();
+        Ok(value)
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Value> {
+        if depth > MAX_DEPTH {
+            return Err(self.error());
+        }
+        match self.peek().ok_or_else(|| self.error())? {
+            b'n' => self.keyword(b"null", Value::Null),
+            b't' => self.keyword(b"true", Value::Bool(true)),
+            b'f' => self.keyword(b"false", Value::Bool(false)),
+            b'"' => Ok(Value::String(self.string()?)),
+            b'[' => {
+                self.pos += 1;
+                let mut values = vec![];
+                if self.peek() == Some(b']') {
+                    self.pos += 1;
+                    return Ok(Value::Array(values));
+                }
+                loop {
+                    values.push(self.value(depth + 1)?);
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b']') => {
+                            self.pos += 1;
+                            return Ok(Value::Array(values));
+                        }
+                        _ => return Err(self.error()),
+                    }
+                }
+            }
+            b'{' => {
+                self.pos += 1;
+                let mut members = vec![];
+                if self.peek() == Some(b'}') {
+                    self.pos += 1;
+                    return Ok(Value::Object(members));
+                }
+                loop {
+                    if self.peek() != Some(b'"') {
+                        return Err(self.error());
+                    }
+                    let key = self.string()?;
+                    self.expect(b':')?;
+                    members.push((key, self.value(depth + 1)?));
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b'}') => {
+                            self.pos += 1;
+                            return Ok(Value::Object(members));
+                        }
+                        _ => return Err(self.error()),
+                    }
+                }
+            }
+            b'-' | b'0'..=b'9' => self.number(),
+            _ => Err(self.error()),
+        }
+    }
+
+    fn number(&mut self) -> Result<Value> {
+        let begin = self.pos;
+        let digits = |parser: &mut Self| {
+            let begin = parser.pos;
+            while parser.text.get(parser.pos).is_some_and(u8::is_ascii_digit) {
+                parser.pos += 1;
+            }
+            parser.pos > begin
+        };
+        if self.text[self.pos] == b'-' {
+            self.pos += 1;
+        }
+        if !digits(self) {
+            return Err(self.error());
+        }
+        if self.text.get(self.pos) == Some(&b'.') {
+            self.pos += 1;
+            if !digits(self) {
+                return Err(self.error());
+            }
+        }
+        if let Some(b'e' | b'E') = self.text.get(self.pos) {
+            self.pos += 1;
+            if let Some(b'+' | b'-') = self.text.get(self.pos) {
+                self.pos += 1;
+            }
+            if !digits(self) {
+                return Err(self.error());
+            }
+        }
+        // The text consists of ASCII characters.
+        let text = std::str::from_utf8(&self.text[begin..self.pos]).unwrap();
+        Ok(Value::Number(text.to_string()))
+    }
+
+    /// Reads 4 hexadecimal digits of `\u` escapes.
+    fn hex4(&mut self) -> Result<u32> {
+        let digits = self
+            .text
+            .get(self.pos..self.pos + 4)
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+            .ok_or_else(|| self.error())?;
+        self.pos += 4;
+        Ok(digits)
+    }
+
+    fn string(&mut self) -> Result<String> {
+        self.expect(b'"')?;
+        let mut bytes = vec![];
+        loop {
+            let c = *self.text.get(self.pos).ok_or_else(|| self.error())?;
+            self.pos += 1;
+            match c {
+                b'"' => break,
+                b'\\' => {
+                    let escaped = *self.text.get(self.pos).ok_or_else(|| self.error())?;
+                    self.pos += 1;
+                    let c = match escaped {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => {
+                            let mut code = self.hex4()?;
+                            // Surrogate pair
+                            if (0xd800..0xdc00).contains(&code)
+                                && self.text[self.pos..].starts_with(b"\\u")
+                            {
+                                self.pos += 2;
+                                let low = self.hex4()?;
+                                if !(0xdc00..0xe000).contains(&low) {
+                                    return Err(self.error());
+                                }
+                                code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                            }
+                            char::from_u32(code).ok_or_else(|| self.error())?
+                        }
+                        _ => return Err(self.error()),
+                    };
+                    bytes.extend(c.encode_utf8(&mut [0; 4]).as_bytes());
+                }
+                c if c < 0x20 => return Err(self.error()),
+                c => bytes.push(c),
+            }
+        }
+        String::from_utf8(bytes).map_err(|_| self.error())
+    }
+}
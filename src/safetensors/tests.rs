@@ -0,0 +1,511 @@
+use crate::array::{Array, IntoArray};
+use crate::error::Error;
+use crate::hardware::cpu::CpuHardware;
+use crate::parameter::{Parameter, ParameterStore};
+use crate::safetensors::json::{self, Value};
+use crate::safetensors::*;
+use crate::shape::Shape;
+use std::cell::RefCell;
+use std::path::PathBuf;
+
+/// Builds a safetensors file from a header and a data section.
+fn make_file(header: &str, data: &[u8]) -> Vec<u8> {
+    let mut bytes = (header.len() as u64).to_le_bytes().to_vec();
+    bytes.extend(header.as_bytes());
+    bytes.extend(data);
+    bytes
+}
+
+/// Temporary file removed on drop.
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new(name: &str, contents: &[u8]) -> Self {
+        let path =
+            std::env::temp_dir().join(format!("dycg-safetensors-{}-{}", name, std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        Self(path)
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+#[test]
+fn test_save() {
+    let hw = RefCell::new(CpuHardware::new());
+    let a = vec![1f32, 2.].into_array(&hw);
+    let b = Array::constant_f32(&hw, Shape::new([2, 1]), &[3., 4.]).unwrap();
+    let c = 5f32.into_array(&hw);
+    let mut bytes = vec![];
+    save(&[("a", &a), ("b\"", &b), ("c", &c)], &mut bytes).unwrap();
+
+    let header = concat!(
+        "{\"a\":{\"dtype\":\"F32\",\"shape\":[2],\"data_offsets\":[0,8]},",
+        "\"b\\\"\":{\"dtype\":\"F32\",\"shape\":[2,1],\"data_offsets\":[8,16]},",
+        "\"c\":{\"dtype\":\"F32\",\"shape\":[],\"data_offsets\":[16,20]}}",
+    );
+    let header_len = (header.len() + 8).next_multiple_of(8) - 8;
+    assert_eq!(bytes.len(), 8 + header_len + 20);
+    assert_eq!(bytes[..8], (header_len as u64).to_le_bytes());
+    assert_eq!(&bytes[8..8 + header.len()], header.as_bytes());
+    assert!(bytes[8 + header.len()..8 + header_len]
+        .iter()
+        .all(|&b| b == b' '));
+    let values = bytes[8 + header_len..]
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(values, vec![1., 2., 3., 4., 5.]);
+}
+
+#[test]
+fn test_save_empty() {
+    let mut bytes = vec![];
+    save(&[], &mut bytes).unwrap();
+    assert_eq!(bytes, make_file("{}      ", &[]));
+
+    let hw = RefCell::new(CpuHardware::new());
+    assert!(load(&hw, &mut bytes.as_slice()).unwrap().is_empty());
+}
+
+#[test]
+fn test_save_invalid_name() {
+    let hw = RefCell::new(CpuHardware::new());
+    let a = 1f32.into_array(&hw);
+    let mut bytes = vec![];
+    assert!(matches!(
+        save(&[("a", &a), ("a", &a)], &mut bytes),
+        Err(Error::InvalidName(_))
+    ));
+    assert!(matches!(
+        save(&[("__metadata__", &a)], &mut bytes),
+        Err(Error::InvalidName(_))
+    ));
+    assert!(bytes.is_empty());
+}
+
+#[test]
+fn test_load_round_trip() {
+    let hw = RefCell::new(CpuHardware::new());
+    let a = Array::constant_f32(&hw, Shape::new([2, 3]), &[1., -2., 3.5, 0., 1e-30, 7.]).unwrap();
+    let b = Array::fill_f32(&hw, Shape::new([0, 4]), 0.);
+    let c = 42f32.into_array(&hw);
+    let mut bytes = vec![];
+    save(&[("z", &a), ("empty", &b), ("c", &c)], &mut bytes).unwrap();
+
+    let loaded = load(&hw, &mut bytes.as_slice()).unwrap();
+    assert_eq!(loaded.len(), 3);
+    // Arrays of the same offset are ordered by their ends.
+    let names = loaded.iter().map(|(n, _)| n.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, vec!["z", "empty", "c"]);
+    for ((_, loaded), expected) in loaded.iter().zip([&a, &b, &c]) {
+        assert_eq!(loaded.shape(), expected.shape());
+        assert_eq!(loaded.get_values_f32(), expected.get_values_f32());
+    }
+}
+
+#[test]
+fn test_load_data_types() {
+    let header = concat!(
+        "{\"__metadata__\":{\"format\":\"pt\"},",
+        "\"f64\":{\"dtype\":\"F64\",\"shape\":[2],\"data_offsets\":[0,16]},",
+        "\"i64\":{\"dtype\":\"I64\",\"shape\":[1],\"data_offsets\":[16,24]},",
+        "\"f16\":{\"dtype\":\"F16\",\"shape\":[2,2],\"data_offsets\":[24,32]},",
+        "\"bf16\":{\"dtype\":\"BF16\",\"shape\":[1],\"data_offsets\":[32,34]},",
+        "\"i16\":{\"dtype\":\"I16\",\"shape\":[1],\"data_offsets\":[34,36]},",
+        "\"i32\":{\"dtype\":\"I32\",\"shape\":[1],\"data_offsets\":[36,40]},",
+        "\"bool\":{\"dtype\":\"BOOL\",\"shape\":[2],\"data_offsets\":[40,42]},",
+        "\"i8\":{\"dtype\":\"I8\",\"shape\":[1],\"data_offsets\":[42,43]},",
+        "\"\\u00e9\":{\"dtype\":\"U8\",\"shape\":[1],\"data_offsets\":[43,44]}}",
+    );
+    let mut data = vec![];
+    data.extend(1.5f64.to_le_bytes());
+    data.extend((-3f64).to_le_bytes());
+    data.extend((-7i64).to_le_bytes());
+    // 1, -2, 2^-24 (subnormal), infinity
+    for bits in [0x3c00u16, 0xc000, 0x0001, 0x7c00] {
+        data.extend(bits.to_le_bytes());
+    }
+    data.extend(0x3fc0u16.to_le_bytes()); // 1.5
+    data.extend((-300i16).to_le_bytes());
+    data.extend(70000i32.to_le_bytes());
+    data.extend([1, 0]);
+    data.push(-5i8 as u8);
+    data.push(200);
+    let bytes = make_file(header, &data);
+
+    let hw = RefCell::new(CpuHardware::new());
+    let loaded = load(&hw, &mut bytes.as_slice()).unwrap();
+    let expected: [(&str, Shape, Vec<f32>); 9] = [
+        ("f64", Shape::new([2]), vec![1.5, -3.]),
+        ("i64", Shape::new([1]), vec![-7.]),
+        (
+            "f16",
+            Shape::new([2, 2]),
+            vec![1., -2., 2f32.powi(-24), f32::INFINITY],
+        ),
+        ("bf16", Shape::new([1]), vec![1.5]),
+        ("i16", Shape::new([1]), vec![-300.]),
+        ("i32", Shape::new([1]), vec![70000.]),
+        ("bool", Shape::new([2]), vec![1., 0.]),
+        ("i8", Shape::new([1]), vec![-5.]),
+        ("\u{e9}", Shape::new([1]), vec![200.]),
+    ];
+    assert_eq!(loaded.len(), expected.len());
+    for ((name, array), (expected_name, expected_shape, expected_values)) in
+        loaded.iter().zip(expected)
+    {
+        assert_eq!(name, expected_name);
+        assert_eq!(*array.shape(), expected_shape);
+        assert_eq!(array.get_values_f32(), expected_values);
+    }
+}
+
+#[test]
+fn test_f16_to_f32() {
+    assert_eq!(f16_to_f32(0x0000).to_bits(), 0f32.to_bits());
+    assert_eq!(f16_to_f32(0x8000).to_bits(), (-0f32).to_bits());
+    assert_eq!(f16_to_f32(0x3555), 0.33325195);
+    assert_eq!(f16_to_f32(0x7bff), 65504.);
+    assert_eq!(f16_to_f32(0x0400), 2f32.powi(-14));
+    assert_eq!(f16_to_f32(0x83ff), -1023. * 2f32.powi(-24));
+    assert_eq!(f16_to_f32(0xfc00), f32::NEG_INFINITY);
+    assert!(f16_to_f32(0x7e00).is_nan());
+}
+
+#[test]
+fn test_load_invalid() {
+    let hw = RefCell::new(CpuHardware::new());
+    let tensor = |offsets: &str| {
+        format!(
+            "{{\"x\":{{\"dtype\":\"F32\",\"shape\":[2],\"data_offsets\":{}}}}}",
+            offsets
+        )
+    };
+    let cases = [
+        // Too short
+        vec![1, 0, 0],
+        // Header length exceeding the file
+        make_file("{}", &[])[..9].to_vec(),
+        u64::MAX.to_le_bytes().to_vec(),
+        // Not an object
+        make_file("[]", &[]),
+        make_file(" {}", &[]),
+        // Broken JSON
+        make_file("{\"x\":}", &[]),
+        make_file("{} {}", &[]),
+        make_file("{\"x\":{\"dtype\":\"F32\",\"shape\":[1],}}", &[0; 4]),
+        // Invalid UTF-8
+        {
+            let mut bytes = make_file("{\"?\":1}", &[]);
+            bytes[10] = 0xff;
+            bytes
+        },
+        // Duplicated names
+        make_file(
+            concat!(
+                "{\"x\":{\"dtype\":\"F32\",\"shape\":[2],\"data_offsets\":[0,8]},",
+                "\"x\":{\"dtype\":\"F32\",\"shape\":[2],\"data_offsets\":[8,16]}}",
+            ),
+            &[0; 16],
+        ),
+        // Invalid metadata
+        make_file("{\"__metadata__\":{\"a\":1}}", &[]),
+        make_file("{\"__metadata__\":\"a\"}", &[]),
+        // Offsets not matching the shape
+        make_file(&tensor("[0,4]"), &[0; 4]),
+        make_file(&tensor("[8,0]"), &[0; 8]),
+        make_file(&tensor("[0]"), &[0; 8]),
+        make_file(&tensor("[0,-8]"), &[0; 8]),
+        // Gap before the tensor
+        make_file(&tensor("[4,12]"), &[0; 12]),
+        // Data section too short or too long
+        make_file(&tensor("[0,8]"), &[0; 4]),
+        make_file(&tensor("[0,8]"), &[0; 12]),
+        // Invalid shape
+        make_file(
+            "{\"x\":{\"dtype\":\"F32\",\"shape\":[-1],\"data_offsets\":[0,0]}}",
+            &[],
+        ),
+        make_file(
+            "{\"x\":{\"dtype\":\"F32\",\"shape\":[1.5],\"data_offsets\":[0,6]}}",
+            &[0; 6],
+        ),
+    ];
+    for bytes in cases {
+        assert!(
+            matches!(load(&hw, &mut bytes.as_slice()), Err(Error::InvalidData(_))),
+            "{:?}",
+            String::from_utf8_lossy(&bytes)
+        );
+    }
+
+    let bytes = make_file(
+        "{\"x\":{\"dtype\":\"F32\",\"shape\":[1,1,1,1,1,1,1,1,1],\"data_offsets\":[0,4]}}",
+        &[0; 4],
+    );
+    assert!(matches!(
+        load(&hw, &mut bytes.as_slice()),
+        Err(Error::InvalidShape(_))
+    ));
+    let bytes = make_file(
+        "{\"x\":{\"dtype\":\"F8_E4M3\",\"shape\":[1],\"data_offsets\":[0,1]}}",
+        &[0],
+    );
+    assert!(matches!(
+        load(&hw, &mut bytes.as_slice()),
+        Err(Error::NotSupported(_))
+    ));
+}
+
+#[test]
+fn test_load_mmap() {
+    let hw = RefCell::new(CpuHardware::new());
+    let a = Array::constant_f32(&hw, Shape::new([2, 2]), &[1., 2., 3., 4.]).unwrap();
+    let b = Array::fill_f32(&hw, Shape::new([0]), 0.);
+    let c = 5f32.into_array(&hw);
+    let mut bytes = vec![];
+    save(&[("a", &a), ("b", &b), ("c", &c)], &mut bytes).unwrap();
+    let file = TempFile::new("mmap", &bytes);
+
+    // The file is not modified during the test.
+    let mut loaded = unsafe { load_mmap(&hw, &file.0) }.unwrap();
+    // The empty array is not mapped.
+    assert_eq!(hw.borrow().num_mapped_memories(), 2);
+    let names = loaded.iter().map(|(n, _)| n.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, vec!["a", "b", "c"]);
+    assert_eq!(*loaded[0].1.shape(), Shape::new([2, 2]));
+    assert_eq!(loaded[0].1.get_values_f32(), vec![1., 2., 3., 4.]);
+    assert_eq!(loaded[1].1.get_values_f32(), vec![]);
+    assert_eq!(loaded[2].1.get_scalar_f32(), Ok(5.));
+
+    // Updates never reach the file.
+    loaded[0].1.add_assign_f32(&a).unwrap();
+    assert_eq!(loaded[0].1.get_values_f32(), vec![2., 4., 6., 8.]);
+    assert_eq!(std::fs::read(&file.0).unwrap(), bytes);
+
+    // The mapping is alive until all arrays are dropped.
+    let c = loaded.pop().unwrap().1;
+    drop(loaded);
+    assert_eq!(hw.borrow().num_mapped_memories(), 1);
+    assert_eq!(c.get_scalar_f32(), Ok(5.));
+    drop(c);
+    assert_eq!(hw.borrow().num_mapped_memories(), 0);
+}
+
+#[test]
+fn test_load_mmap_unaligned() {
+    let header = concat!(
+        "{\"u8\":{\"dtype\":\"U8\",\"shape\":[1],\"data_offsets\":[0,1]},",
+        "\"unaligned\":{\"dtype\":\"F32\",\"shape\":[1],\"data_offsets\":[1,5]},",
+        "\"f16\":{\"dtype\":\"F16\",\"shape\":[1],\"data_offsets\":[5,7]},",
+        "\"pad\":{\"dtype\":\"U8\",\"shape\":[1],\"data_offsets\":[7,8]},",
+        "\"aligned\":{\"dtype\":\"F32\",\"shape\":[2],\"data_offsets\":[8,16]}}",
+    );
+    // Pads the header so that the data section is aligned to 8 bytes.
+    let header = format!(
+        "{}{}",
+        header,
+        " ".repeat((header.len() + 8).next_multiple_of(8) - 8 - header.len())
+    );
+    let mut data = vec![3];
+    data.extend(1.25f32.to_le_bytes());
+    data.extend(0xc000u16.to_le_bytes());
+    data.push(0);
+    data.extend(6f32.to_le_bytes());
+    data.extend((-7f32).to_le_bytes());
+    let file = TempFile::new("unaligned", &make_file(&header, &data));
+
+    let hw = RefCell::new(CpuHardware::new());
+    let loaded = unsafe { load_mmap(&hw, &file.0) }.unwrap();
+    assert_eq!(hw.borrow().num_mapped_memories(), 1);
+    let values = loaded
+        .iter()
+        .map(|(n, a)| (n.as_str(), a.get_values_f32()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        values,
+        vec![
+            ("u8", vec![3.]),
+            ("unaligned", vec![1.25]),
+            ("f16", vec![-2.]),
+            ("pad", vec![0.]),
+            ("aligned", vec![6., -7.]),
+        ]
+    );
+}
+
+#[test]
+fn test_load_mmap_invalid() {
+    let hw = RefCell::new(CpuHardware::new());
+    assert!(matches!(
+        unsafe { load_mmap(&hw, std::env::temp_dir().join("dycg-safetensors-missing")) },
+        Err(Error::Io(_))
+    ));
+    let file = TempFile::new("invalid", &make_file("{\"x\":1}", &[]));
+    assert!(matches!(
+        unsafe { load_mmap(&hw, &file.0) },
+        Err(Error::InvalidData(_))
+    ));
+    let file = TempFile::new("empty", &[]);
+    assert!(matches!(
+        unsafe { load_mmap(&hw, &file.0) },
+        Err(Error::InvalidData(_))
+    ));
+    assert_eq!(hw.borrow().num_mapped_memories(), 0);
+}
+
+#[test]
+fn test_save_parameters() {
+    let hw = RefCell::new(CpuHardware::new());
+    let mut params = ParameterStore::new();
+    params
+        .add("w", Parameter::new(vec![1f32, 2.].into_array(&hw)))
+        .unwrap();
+    params
+        .add("b", Parameter::new(3f32.into_array(&hw)))
+        .unwrap();
+    let mut bytes = vec![];
+    save_parameters(&params, &mut bytes).unwrap();
+
+    let hw2 = RefCell::new(CpuHardware::new());
+    let mut params2 = ParameterStore::new();
+    params2
+        .add("w", Parameter::new(vec![0f32, 0.].into_array(&hw2)))
+        .unwrap();
+    params2
+        .add("b", Parameter::new(0f32.into_array(&hw2)))
+        .unwrap();
+    params2
+        .set_values(load(&hw2, &mut bytes.as_slice()).unwrap())
+        .unwrap();
+    assert_eq!(
+        params2.get("w").unwrap().value().get_values_f32(),
+        vec![1., 2.]
+    );
+    assert_eq!(params2.get("b").unwrap().value().get_scalar_f32(), Ok(3.));
+}
+
+#[test]
+fn test_json_parse() {
+    let value = json::parse(
+        " {\"a\" : [1, -2.5e+3, true, false, null], \"b\":{}, \"c\":[], \"a\":\"\\\"\\\\\\/\\b\\f\\n\\r\\t\\u00e9\\ud83d\\ude00\"} ",
+    )
+    .unwrap();
+    assert_eq!(
+        value,
+        Value::Object(vec![
+            (
+                "a".to_string(),
+                Value::Array(vec![
+                    Value::Number("1".to_string()),
+                    Value::Number("-2.5e+3".to_string()),
+                    Value::Bool(true),
+                    Value::Bool(false),
+                    Value::Null,
+                ])
+            ),
+            ("b".to_string(), Value::Object(vec![])),
+            ("c".to_string(), Value::Array(vec![])),
+            (
+                "a".to_string(),
+                Value::String("\"\\/\u{8}\u{c}\n\r\t\u{e9}\u{1f600}".to_string())
+            ),
+        ])
+    );
+    assert_eq!(Value::Number("12".to_string()).as_usize(), Some(12));
+    assert_eq!(Value::Number("-1".to_string()).as_usize(), None);
+    assert_eq!(Value::Number("1e3".to_string()).as_usize(), None);
+    assert_eq!(Value::String("1".to_string()).as_usize(), None);
+}
+
+#[test]
+fn test_json_parse_invalid() {
+    let nested = "[".repeat(100) + &"]".repeat(100);
+    for text in [
+        "",
+        "{",
+        "[1,]",
+        "{\"a\"}",
+        "{1:2}",
+        "nul",
+        "01x",
+        "-",
+        "1.",
+        "1e",
+        "\"\\x\"",
+        "\"\\u12\"",
+        "\"\\ud83d\\u0041\"",
+        "\"\\udc00\"",
+        "\"\n\"",
+        "\"abc",
+        "1 2",
+        &nested,
+    ] {
+        assert!(
+            matches!(json::parse(text), Err(Error::InvalidData(_))),
+            "{:?}",
+            text
+        );
+    }
+}
+
+#[test]
+fn test_json_write_string() {
+    let text = "a\"\\\n\r\t\u{1}\u{e9}";
+    let mut output = String::new();
+    json::write_string(&mut output, text);
+    assert_eq!(output, "\"a\\\"\\\\\\n\\r\\t\\u0001\u{e9}\"");
+    assert_eq!(json::parse(&output), Ok(Value::String(text.to_string())));
+}
+
+#[test]
+fn test_load_mmap_overlapping() {
+    // Overlapping tensors are rejected before supplying any memory, so that no range of the
+    // mapping is supplied twice.
+    let hw = RefCell::new(CpuHardware::new());
+    let cases = [
+        (
+            concat!(
+                "{\"a\":{\"dtype\":\"F32\",\"shape\":[2],\"data_offsets\":[0,8]},",
+                "\"b\":{\"dtype\":\"F32\",\"shape\":[2],\"data_offsets\":[0,8]}}",
+            ),
+            8,
+        ),
+        (
+            concat!(
+                "{\"a\":{\"dtype\":\"F32\",\"shape\":[2],\"data_offsets\":[0,8]},",
+                "\"b\":{\"dtype\":\"F32\",\"shape\":[2],\"data_offsets\":[4,12]}}",
+            ),
+            12,
+        ),
+    ];
+    for (header, size) in cases {
+        let file = TempFile::new("overlapping", &make_file(header, &vec![0; size]));
+        assert!(matches!(
+            unsafe { load_mmap(&hw, &file.0) },
+            Err(Error::InvalidData(_))
+        ));
+        assert_eq!(hw.borrow().num_mapped_memories(), 0);
+    }
+
+    // Empty tensors may share offsets with others, but they are never mapped.
+    let header = concat!(
+        "{\"a\":{\"dtype\":\"F32\",\"shape\":[0],\"data_offsets\":[0,0]},",
+        "\"b\":{\"dtype\":\"F32\",\"shape\":[2],\"data_offsets\":[0,8]},",
+        "\"c\":{\"dtype\":\"F32\",\"shape\":[0,3],\"data_offsets\":[8,8]}}",
+    );
+    let header = format!(
+        "{}{}",
+        header,
+        " ".repeat((header.len() + 8).next_multiple_of(8) - 8 - header.len())
+    );
+    let file = TempFile::new("empty-tensors", &make_file(&header, &[0; 8]));
+    let loaded = unsafe { load_mmap(&hw, &file.0) }.unwrap();
+    assert_eq!(loaded.len(), 3);
+    assert_eq!(hw.borrow().num_mapped_memories(), 1);
+}
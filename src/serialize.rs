@@ -0,0 +1,445 @@
+use crate::array::Array;
+use crate::conv::{Conv2dOptions, Pool2dOptions};
+use crate::error::Error;
+use crate::hardware::Hardware;
+use crate::operator::{self, Operator};
+use crate::result::Result;
+use crate::shape::{Shape, MAX_NUM_DIMENSIONS};
+use std::cell::RefCell;
+use std::mem;
+
+/// Magic bytes at the beginning of serialized graphs.
+pub(crate) const MAGIC: &[u8; 4] = b"DYCG";
+
+/// Version of the format of serialized graphs.
+/// This value must be incremented whenever the format changes.
+pub(crate) const VERSION: u32 = 1;
+
+/// Encoder of the binary format used by `Graph::save()`.
+///
+/// All integers are encoded in little endian, and `usize` values are always encoded as 64-bit
+/// integers regardless of the platform.
+pub struct Encoder {
+    /// Encoded bytes.
+    bytes: Vec<u8>,
+}
+
+impl Encoder {
+    /// Creates a new empty `Encoder` object.
+    pub(crate) fn new() -> Self {
+        Self { bytes: vec![] }
+    }
+
+    /// Returns the encoded bytes.
+    pub(crate) fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Appends raw bytes.
+    pub fn write_bytes(&mut self, value: &[u8]) {
+        self.bytes.extend_from_slice(value);
+    }
+
+    /// Appends an 8-bit unsigned integer.
+    pub fn write_u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    /// Appends a 32-bit unsigned integer.
+    pub fn write_u32(&mut self, value: u32) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    /// Appends a 64-bit unsigned integer.
+    pub fn write_u64(&mut self, value: u64) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    /// Appends a `usize` value as a 64-bit unsigned integer.
+    pub fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+
+    /// Appends a 32-bit floating point number.
+    pub fn write_f32(&mut self, value: f32) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    /// Appends a sequence of 32-bit floating point numbers without their length.
+    pub fn write_f32s(&mut self, values: &[f32]) {
+        self.bytes.reserve(mem::size_of_val(values));
+        for &value in values {
+            self.write_f32(value);
+        }
+    }
+
+    /// Appends a UTF-8 string with its length.
+    pub fn write_string(&mut self, value: &str) {
+        self.write_usize(value.len());
+        self.write_bytes(value.as_bytes());
+    }
+
+    /// Appends a `Shape` with its number of dimensions.
+    pub fn write_shape(&mut self, value: &Shape) {
+        self.write_usize(value.num_dimensions());
+        for &dim in value.dimensions() {
+            self.write_usize(dim);
+        }
+    }
+
+    /// Appends the shape and all values of an `Array`.
+    pub fn write_array(&mut self, value: &Array) {
+        self.write_shape(value.shape());
+        self.write_f32s(&value.get_values_f32());
+    }
+}
+
+/// Decoder of the binary format used by `Graph::load()`.
+pub(crate) struct Decoder<'a> {
+    /// Bytes to be decoded.
+    bytes: &'a [u8],
+
+    /// Position of the next byte to be decoded.
+    position: usize,
+}
+
+impl<'a> Decoder<'a> {
+    /// Creates a new `Decoder` object.
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    /// Checks that all bytes were consumed.
+    pub(crate) fn finish(&self) -> Result<()> {
+        if self.position != self.bytes.len() {
+            return Err(Error::InvalidData(format!(
+                "{} trailing bytes remain.",
+                self.bytes.len() - self.position
+            )));
+        }
+        Ok(())
+    }
+
+    /// Reads raw bytes.
+    pub(crate) fn read_bytes(&mut self, size: usize) -> Result<&'a [u8]> {
+        if size > self.bytes.len() - self.position {
+            return Err(Error::InvalidData(format!(
+                "Unexpected end of data: required {} bytes at {}, but only {} bytes remain.",
+                size,
+                self.position,
+                self.bytes.len() - self.position
+            )));
+        }
+        let bytes = &self.bytes[self.position..self.position + size];
+        self.position += size;
+        Ok(bytes)
+    }
+
+    /// Reads a fixed-size byte array.
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.read_bytes(N)?.try_into().unwrap())
+    }
+
+    /// Reads an 8-bit unsigned integer.
+    pub(crate) fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.read_array::<1>()?[0])
+    }
+
+    /// Reads a 32-bit unsigned integer.
+    pub(crate) fn read_u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.read_array()?))
+    }
+
+    /// Reads a 64-bit unsigned integer.
+    pub(crate) fn read_u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.read_array()?))
+    }
+
+    /// Reads a 64-bit unsigned integer as `usize`.
+    pub(crate) fn read_usize(&mut self) -> Result<usize> {
+        let value = self.read_u64()?;
+        usize::try_from(value)
+            .map_err(|_| Error::InvalidData(format!("Value {} does not fit in usize.", value)))
+    }
+
+    /// Reads a fixed number of `usize` values.
+    pub(crate) fn read_usizes<const N: usize>(&mut self) -> Result<[usize; N]> {
+        let mut values = [0; N];
+        for value in values.iter_mut() {
+            *value = self.read_usize()?;
+        }
+        Ok(values)
+    }
+
+    /// Reads a 32-bit floating point number.
+    pub(crate) fn read_f32(&mut self) -> Result<f32> {
+        Ok(f32::from_le_bytes(self.read_array()?))
+    }
+
+    /// Reads a sequence of 32-bit floating point numbers.
+    pub(crate) fn read_f32s(&mut self, size: usize) -> Result<Vec<f32>> {
+        let num_bytes = size
+            .checked_mul(mem::size_of::<f32>())
+            .ok_or_else(|| Error::InvalidData(format!("Too many values: {}", size)))?;
+        Ok(self
+            .read_bytes(num_bytes)?
+            .chunks_exact(mem::size_of::<f32>())
+            .map(|chunk| f32::from_le_bytes(chunk.try_into().unwrap()))
+            .collect())
+    }
+
+    /// Reads a UTF-8 string.
+    pub(crate) fn read_string(&mut self) -> Result<String> {
+        let size = self.read_usize()?;
+        String::from_utf8(self.read_bytes(size)?.to_vec())
+            .map_err(|e| Error::InvalidData(format!("Invalid string: {}", e)))
+    }
+
+    /// Reads a `Shape`.
+    pub(crate) fn read_shape(&mut self) -> Result<Shape> {
+        let num_dimensions = self.read_usize()?;
+        if num_dimensions > MAX_NUM_DIMENSIONS {
+            return Err(Error::InvalidData(format!(
+                "Too many dimensions: {}",
+                num_dimensions
+            )));
+        }
+        let dims = (0..num_dimensions)
+            .map(|_| self.read_usize())
+            .collect::<Result<Vec<_>>>()?;
+        if dims
+            .iter()
+            .try_fold(1usize, |acc, &d| acc.checked_mul(d))
+            .is_none()
+        {
+            return Err(Error::InvalidData(format!("Too large shape: {:?}", dims)));
+        }
+        Ok(Shape::from_slice(&dims))
+    }
+
+    /// Reads an `Array` and places it on the given hardware.
+    pub(crate) fn read_array_f32<'hw>(
+        &mut self,
+        hardware: &'hw RefCell<dyn Hardware>,
+    ) -> Result<Array<'hw>> {
+        let shape = self.read_shape()?;
+        let values = self.read_f32s(shape.num_elements())?;
+        Array::constant_f32(hardware, shape, &values)
+    }
+}
+
+/// Encodes `Conv2dOptions`.
+pub(crate) fn write_conv2d_options(encoder: &mut Encoder, options: &Conv2dOptions) {
+    for value in options
+        .stride
+        .iter()
+        .chain(&options.padding)
+        .chain(&options.dilation)
+    {
+        encoder.write_usize(*value);
+    }
+    encoder.write_usize(options.groups);
+}
+
+/// Decodes `Conv2dOptions`.
+pub(crate) fn read_conv2d_options(decoder: &mut Decoder) -> Result<Conv2dOptions> {
+    Ok(Conv2dOptions {
+        stride: decoder.read_usizes()?,
+        padding: decoder.read_usizes()?,
+        dilation: decoder.read_usizes()?,
+        groups: decoder.read_usize()?,
+    })
+}
+
+/// Encodes `Pool2dOptions`.
+pub(crate) fn write_pool2d_options(encoder: &mut Encoder, options: &Pool2dOptions) {
+    for value in options
+        .kernel_size
+        .iter()
+        .chain(&options.stride)
+        .chain(&options.padding)
+    {
+        encoder.write_usize(*value);
+    }
+}
+
+/// Decodes `Pool2dOptions`.
+pub(crate) fn read_pool2d_options(decoder: &mut Decoder) -> Result<Pool2dOptions> {
+    Ok(Pool2dOptions {
+        kernel_size: decoder.read_usizes()?,
+        stride: decoder.read_usizes()?,
+        padding: decoder.read_usizes()?,
+    })
+}
+
+/// Names of the built-in operators, which `read_operator()` can reconstruct.
+pub(crate) const BUILTIN_OPERATORS: &[&str] = &[
+    // Nullary operators
+    "Constant",
+    "Fill",
+    // Unary operators
+    "AvgPool2d",
+    "AvgPool2dBackward",
+    "Clamp",
+    "ClipGradient",
+    "Dropout",
+    "ExpandAxis",
+    "IsInf",
+    "IsNan",
+    "LogicalNot",
+    "MaxPool2d",
+    "Neg",
+    "Relu",
+    "Sigmoid",
+    "Sqrt",
+    "Step",
+    "StopGradient",
+    "SumAxis",
+    "Tanh",
+    "Transpose",
+    // Binary operators
+    "Add",
+    "Conv2d",
+    "Conv2dBackwardInput",
+    "Conv2dBackwardWeight",
+    "Div",
+    "Equal",
+    "GatherRows",
+    "Less",
+    "LessEqual",
+    "LogicalAnd",
+    "LogicalOr",
+    "MatMul",
+    "MaxPool2dGather",
+    "MaxPool2dScatter",
+    "Maximum",
+    "Minimum",
+    "Mul",
+    "RmsNorm",
+    "ScatterAddRows",
+    "StraightThrough",
+    "Sub",
+    // Ternary operators
+    "BatchNorm",
+    "BatchNormBackward",
+    "LayerNorm",
+    "LayerNormBackward",
+    "RmsNormBackward",
+    "Select",
+    // Quinary operators
+    "BatchNormInference",
+];
+
+/// Reconstructs a built-in operator from its name and encoded attributes.
+///
+/// # Arguments
+///
+/// * `name` - Name of the operator, which is the return value of `Operator::name()`.
+/// * `decoder` - `Decoder` holding the attributes encoded by `Operator::encode_attributes()`.
+/// * `hardware` - `Hardware` object to host the values owned by the operator.
+///
+/// # Returns
+///
+/// * `Ok(Box<dyn Operator>)` - The reconstructed operator.
+/// * `Err(Error)` - `name` is not a built-in operator, or the attributes are broken.
+pub(crate) fn read_operator<'hw>(
+    name: &str,
+    decoder: &mut Decoder,
+    hardware: &'hw RefCell<dyn Hardware>,
+) -> Result<Box<dyn Operator<'hw> + 'hw>> {
+    use operator::*;
+
+    let d = decoder;
+    let op: Box<dyn Operator<'hw>> = match name {
+        // Nullary operators
+        "Constant" => Box::new(constant::Constant::new(d.read_array_f32(hardware)?)),
+        "Fill" => Box::new(fill::Fill::new(hardware, d.read_shape()?, d.read_f32()?)),
+
+        // Unary operators
+        "AvgPool2d" => Box::new(avg_pool2d::AvgPool2d::new(read_pool2d_options(d)?)),
+        "AvgPool2dBackward" => Box::new(avg_pool2d_backward::AvgPool2dBackward::new(
+            d.read_usizes()?,
+            read_pool2d_options(d)?,
+        )),
+        "Clamp" => Box::new(clamp::Clamp::new(d.read_f32()?, d.read_f32()?)),
+        "ClipGradient" => Box::new(clip_gradient::ClipGradient::new(
+            d.read_f32()?,
+            d.read_f32()?,
+        )),
+        "Dropout" => Box::new(dropout::Dropout::new(
+            d.read_f32()?,
+            d.read_u64()?,
+            d.read_u64()?,
+        )),
+        "ExpandAxis" => Box::new(expand_axis::ExpandAxis::new(
+            d.read_usize()?,
+            d.read_usize()?,
+        )),
+        "IsInf" => Box::new(isinf::IsInf::new()),
+        "IsNan" => Box::new(isnan::IsNan::new()),
+        "LogicalNot" => Box::new(logical_not::LogicalNot::new()),
+        "MaxPool2d" => Box::new(max_pool2d::MaxPool2d::new(read_pool2d_options(d)?)),
+        "Neg" => Box::new(neg::Neg::new()),
+        "Relu" => Box::new(relu::Relu::new()),
+        "Sigmoid" => Box::new(sigmoid::Sigmoid::new()),
+        "Sqrt" => Box::new(sqrt::Sqrt::new()),
+        "Step" => Box::new(step::Step::new()),
+        "StopGradient" => Box::new(stop_gradient::StopGradient::new()),
+        "SumAxis" => Box::new(sum_axis::SumAxis::new(d.read_usize()?)),
+        "Tanh" => Box::new(tanh::Tanh::new()),
+        "Transpose" => Box::new(transpose::Transpose::new()),
+
+        // Binary operators
+        "Add" => Box::new(add::Add::new()),
+        "Conv2d" => Box::new(conv2d::Conv2d::new(read_conv2d_options(d)?)),
+        "Conv2dBackwardInput" => Box::new(conv2d_backward_input::Conv2dBackwardInput::new(
+            d.read_usizes()?,
+            read_conv2d_options(d)?,
+        )),
+        "Conv2dBackwardWeight" => Box::new(conv2d_backward_weight::Conv2dBackwardWeight::new(
+            d.read_usizes()?,
+            read_conv2d_options(d)?,
+        )),
+        "Div" => Box::new(div::Div::new()),
+        "Equal" => Box::new(equal::Equal::new()),
+        "GatherRows" => Box::new(gather_rows::GatherRows::new()),
+        "Less" => Box::new(less::Less::new()),
+        "LessEqual" => Box::new(less_equal::LessEqual::new()),
+        "LogicalAnd" => Box::new(logical_and::LogicalAnd::new()),
+        "LogicalOr" => Box::new(logical_or::LogicalOr::new()),
+        "MatMul" => Box::new(matmul::MatMul::new()),
+        "MaxPool2dGather" => Box::new(max_pool2d_gather::MaxPool2dGather::new(
+            read_pool2d_options(d)?,
+        )),
+        "MaxPool2dScatter" => Box::new(max_pool2d_scatter::MaxPool2dScatter::new(
+            read_pool2d_options(d)?,
+        )),
+        "Maximum" => Box::new(maximum::Maximum::new()),
+        "Minimum" => Box::new(minimum::Minimum::new()),
+        "Mul" => Box::new(mul::Mul::new()),
+        "RmsNorm" => Box::new(rms_norm::RmsNorm::new(d.read_f32()?)),
+        "ScatterAddRows" => Box::new(scatter_add_rows::ScatterAddRows::new(d.read_usize()?)),
+        "StraightThrough" => Box::new(straight_through::StraightThrough::new()),
+        "Sub" => Box::new(sub::Sub::new()),
+
+        // Ternary operators
+        "BatchNorm" => Box::new(batch_norm::BatchNorm::new(d.read_f32()?)),
+        "BatchNormBackward" => Box::new(batch_norm_backward::BatchNormBackward::new(d.read_f32()?)),
+        "LayerNorm" => Box::new(layer_norm::LayerNorm::new(d.read_f32()?)),
+        "LayerNormBackward" => Box::new(layer_norm_backward::LayerNormBackward::new(d.read_f32()?)),
+        "RmsNormBackward" => Box::new(rms_norm_backward::RmsNormBackward::new(d.read_f32()?)),
+        "Select" => Box::new(select::Select::new()),
+
+        // Quinary operators
+        "BatchNormInference" => {
+            Box::new(batch_norm_inference::BatchNormInference::new(d.read_f32()?))
+        }
+
+        _ => return Err(Error::NotSupported(format!("Unknown operator: {}", name))),
+    };
+    d.finish()?;
+    Ok(op)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,184 @@
+use crate::hardware::cpu::CpuHardware;
+use crate::operator::*;
+use crate::serialize::*;
+
+#[test]
+fn test_encode_decode() {
+    let hw = RefCell::new(CpuHardware::new());
+    let array = Array::constant_f32(&hw, Shape::new([2]), &[1., -2.]).unwrap();
+
+    let mut encoder = Encoder::new();
+    encoder.write_u8(7);
+    encoder.write_u32(0x01020304);
+    encoder.write_u64(u64::MAX);
+    encoder.write_usize(42);
+    encoder.write_f32(1.5);
+    encoder.write_string("hello");
+    encoder.write_shape(&Shape::new([3, 0, 5]));
+    encoder.write_array(&array);
+    let bytes = encoder.into_bytes();
+    assert_eq!(&bytes[1..5], &[4, 3, 2, 1]);
+
+    let mut decoder = Decoder::new(&bytes);
+    assert_eq!(decoder.read_u8(), Ok(7));
+    assert_eq!(decoder.read_u32(), Ok(0x01020304));
+    assert_eq!(decoder.read_u64(), Ok(u64::MAX));
+    assert_eq!(decoder.read_usize(), Ok(42));
+    assert_eq!(decoder.read_f32(), Ok(1.5));
+    assert_eq!(decoder.read_string(), Ok("hello".to_string()));
+    assert_eq!(decoder.read_shape(), Ok(Shape::new([3, 0, 5])));
+    let decoded = decoder.read_array_f32(&hw).unwrap();
+    assert_eq!(*decoded.shape(), Shape::new([2]));
+    assert_eq!(decoded.get_values_f32(), vec![1., -2.]);
+    assert_eq!(decoder.finish(), Ok(()));
+}
+
+#[test]
+fn test_decode_errors() {
+    let mut decoder = Decoder::new(&[1, 2, 3]);
+    assert!(decoder.read_u32().is_err());
+    assert_eq!(decoder.read_u8(), Ok(1));
+    assert!(decoder.finish().is_err());
+    assert!(decoder.read_f32s(usize::MAX).is_err());
+
+    // Too many dimensions.
+    let mut encoder = Encoder::new();
+    encoder.write_usize(9);
+    let bytes = encoder.into_bytes();
+    assert!(Decoder::new(&bytes).read_shape().is_err());
+
+    // Overflowing number of elements.
+    let mut encoder = Encoder::new();
+    encoder.write_usize(2);
+    encoder.write_usize(1 << 40);
+    encoder.write_usize(1 << 40);
+    let bytes = encoder.into_bytes();
+    assert!(Decoder::new(&bytes).read_shape().is_err());
+
+    // Broken UTF-8.
+    let mut encoder = Encoder::new();
+    encoder.write_usize(1);
+    encoder.write_u8(0xff);
+    let bytes = encoder.into_bytes();
+    assert!(Decoder::new(&bytes).read_string().is_err());
+}
+
+#[test]
+fn test_read_operator() {
+    let hw = RefCell::new(CpuHardware::new());
+    let conv = Conv2dOptions {
+        stride: [1, 2],
+        padding: [3, 4],
+        dilation: [5, 6],
+        groups: 7,
+    };
+    let pool = Pool2dOptions {
+        kernel_size: [1, 2],
+        stride: [3, 4],
+        padding: [5, 6],
+    };
+    let array = Array::constant_f32(&hw, Shape::new([2]), &[1., 2.]).unwrap();
+    let ops: Vec<Box<dyn Operator>> = vec![
+        Box::new(add::Add::new()),
+        Box::new(avg_pool2d::AvgPool2d::new(pool)),
+        Box::new(avg_pool2d_backward::AvgPool2dBackward::new([8, 9], pool)),
+        Box::new(batch_norm::BatchNorm::new(1e-5)),
+        Box::new(batch_norm_backward::BatchNormBackward::new(1e-5)),
+        Box::new(batch_norm_inference::BatchNormInference::new(1e-5)),
+        Box::new(clamp::Clamp::new(-1., 2.)),
+        Box::new(clip_gradient::ClipGradient::new(-3., 4.)),
+        Box::new(constant::Constant::new(array)),
+        Box::new(conv2d::Conv2d::new(conv)),
+        Box::new(conv2d_backward_input::Conv2dBackwardInput::new(
+            [8, 9],
+            conv,
+        )),
+        Box::new(conv2d_backward_weight::Conv2dBackwardWeight::new(
+            [8, 9],
+            conv,
+        )),
+        Box::new(div::Div::new()),
+        Box::new(dropout::Dropout::new(0.25, 123, 456)),
+        Box::new(equal::Equal::new()),
+        Box::new(expand_axis::ExpandAxis::new(1, 3)),
+        Box::new(fill::Fill::new(&hw, Shape::new([2, 3]), 4.)),
+        Box::new(gather_rows::GatherRows::new()),
+        Box::new(isinf::IsInf::new()),
+        Box::new(isnan::IsNan::new()),
+        Box::new(layer_norm::LayerNorm::new(1e-5)),
+        Box::new(layer_norm_backward::LayerNormBackward::new(1e-5)),
+        Box::new(less::Less::new()),
+        Box::new(less_equal::LessEqual::new()),
+        Box::new(logical_and::LogicalAnd::new()),
+        Box::new(logical_not::LogicalNot::new()),
+        Box::new(logical_or::LogicalOr::new()),
+        Box::new(matmul::MatMul::new()),
+        Box::new(max_pool2d::MaxPool2d::new(pool)),
+        Box::new(max_pool2d_gather::MaxPool2dGather::new(pool)),
+        Box::new(max_pool2d_scatter::MaxPool2dScatter::new(pool)),
+        Box::new(maximum::Maximum::new()),
+        Box::new(minimum::Minimum::new()),
+        Box::new(mul::Mul::new()),
+        Box::new(neg::Neg::new()),
+        Box::new(relu::Relu::new()),
+        Box::new(rms_norm::RmsNorm::new(1e-5)),
+        Box::new(rms_norm_backward::RmsNormBackward::new(1e-5)),
+        Box::new(scatter_add_rows::ScatterAddRows::new(5)),
+        Box::new(select::Select::new()),
+        Box::new(sigmoid::Sigmoid::new()),
+        Box::new(sqrt::Sqrt::new()),
+        Box::new(step::Step::new()),
+        Box::new(stop_gradient::StopGradient::new()),
+        Box::new(straight_through::StraightThrough::new()),
+        Box::new(sub::Sub::new()),
+        Box::new(sum_axis::SumAxis::new(2)),
+        Box::new(tanh::Tanh::new()),
+        Box::new(transpose::Transpose::new()),
+    ];
+
+    // All built-in operators are covered.
+    assert_eq!(ops.len(), BUILTIN_OPERATORS.len());
+
+    for op in ops {
+        assert!(BUILTIN_OPERATORS.contains(&op.name().as_str()));
+        let mut encoder = Encoder::new();
+        op.encode_attributes(&mut encoder).unwrap();
+        let bytes = encoder.into_bytes();
+
+        let decoded = read_operator(&op.name(), &mut Decoder::new(&bytes), &hw).unwrap();
+        assert_eq!(decoded.name(), op.name());
+        assert_eq!(decoded.input_size(), op.input_size());
+
+        // The attributes survive the round trip.
+        let mut encoder = Encoder::new();
+        decoded.encode_attributes(&mut encoder).unwrap();
+        assert_eq!(encoder.into_bytes(), bytes, "{}", op.name());
+
+        // Missing or extra attributes are rejected.
+        if !bytes.is_empty() {
+            let truncated = &bytes[..bytes.len() - 1];
+            assert!(read_operator(&op.name(), &mut Decoder::new(truncated), &hw).is_err());
+        }
+        let extended = [bytes.as_slice(), &[0]].concat();
+        assert!(read_operator(&op.name(), &mut Decoder::new(&extended), &hw).is_err());
+    }
+}
+
+#[test]
+fn test_read_operator_unknown() {
+    let hw = RefCell::new(CpuHardware::new());
+    assert!(matches!(
+        read_operator("Foo", &mut Decoder::new(&[]), &hw),
+        Err(Error::NotSupported(_))
+    ));
+}
+
+#[test]
+fn test_custom_gradient_not_supported() {
+    let op = custom_gradient::CustomGradient::new(1, std::rc::Rc::new(|_, _, gy| vec![gy]));
+    let mut encoder = Encoder::new();
+    assert!(matches!(
+        op.encode_attributes(&mut encoder),
+        Err(Error::NotSupported(_))
+    ));
+}
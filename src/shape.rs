@@ -4,7 +4,7 @@ use std::fmt;
 use std::mem::{size_of, transmute, MaybeUninit};
 
 /// Maximum number of dimensions.
-const MAX_NUM_DIMENSIONS: usize = 8;
+pub(crate) const MAX_NUM_DIMENSIONS: usize = 8;
 
 /// Macro to define as_arrayN().
 macro_rules! define_as_array {
@@ -16,7 +16,7 @@ macro_rules! define_as_array {
                 for (dest, src) in data.iter_mut().zip(self.dimensions.iter()) {
                     dest.write(*src);
                 }
-                Ok(unsafe { transmute::<_, [usize; $n]>(data) })
+                Ok(unsafe { transmute::<[MaybeUninit<usize>; $n], [usize; $n]>(data) })
             } else {
                 Err(Error::InvalidLength(format!(
                     "Requested dimensions of length {}, but the shape is {}-dimensional",
@@ -145,7 +145,7 @@ impl Shape {
     /// * `Ok(())` - `index` is valid in this shape.
     /// * `Err(Error)` = `index` is invalid.
     pub fn check_index(&self, index: usize) -> Result<()> {
-        (index < self.num_dimensions).then(|| ()).ok_or_else(|| {
+        (index < self.num_dimensions).then_some(()).ok_or_else(|| {
             Error::OutOfRange(format!(
                 "Shape index out of range: index:{} >= num_dimensions:{}",
                 index, self.num_dimensions
@@ -160,7 +160,7 @@ impl Shape {
     /// * `Ok(())` - Shape represents a scalar.
     /// * `Err(Error)` - Shape does not represent a scalar.
     pub fn check_is_scalar(&self) -> Result<()> {
-        (self.num_dimensions == 0).then(|| ()).ok_or_else(|| {
+        (self.num_dimensions == 0).then_some(()).ok_or_else(|| {
             Error::InvalidShape(format!(
                 "Shape is not representing a scalar. num_dimensions: {}",
                 self.num_dimensions
@@ -200,6 +200,15 @@ impl Shape {
         Ok(unsafe { self.dimension_unchecked(index) })
     }
 
+    /// Obtains all dimensions of this shape.
+    ///
+    /// # Returns
+    ///
+    /// A slice of the sizes of each dimension.
+    pub fn dimensions(&self) -> &[usize] {
+        &self.dimensions[..self.num_dimensions]
+    }
+
     /// Calculates the number of elements represented by this shape.
     ///
     /// The number of elements is defined as usually the product of all valid dimension sizes.
@@ -250,6 +259,193 @@ impl Shape {
             )))
         }
     }
+
+    /// Obtains the resulting shape of matrix multiplication.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Right-hand side argument.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Self)` - The shape of `self @ other`: `[m, n]` for `self` of `[m, k]` and `other` of
+    ///   `[k, n]`.
+    /// * `Err(Error)` - Either shape is not a matrix, or inner dimensions mismatch.
+    pub fn matmul(&self, other: &Self) -> Result<Self> {
+        let [m, k1] = self.as_array2()?;
+        let [k2, n] = other.as_array2()?;
+        if k1 == k2 {
+            Ok(Self::new([m, n]))
+        } else {
+            Err(Error::InvalidShape(format!(
+                "Matrix multiplication can not be evaluated for shapes {} and {}.",
+                self, other
+            )))
+        }
+    }
+
+    /// Obtains the resulting shape of matrix transposition.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Self)` - `[n, m]` for `self` of `[m, n]`.
+    /// * `Err(Error)` - `self` is not a matrix.
+    pub fn transpose(&self) -> Result<Self> {
+        let [m, n] = self.as_array2()?;
+        Ok(Self::new([n, m]))
+    }
+
+    /// Obtains a shape without the specified axis.
+    ///
+    /// # Arguments
+    ///
+    /// * `axis` - Index of the axis to be removed.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Self)` - A new shape with `num_dimensions() - 1` dimensions.
+    /// * `Err(Error)` - `axis` is out-of-range.
+    pub fn remove_axis(&self, axis: usize) -> Result<Self> {
+        self.check_index(axis)?;
+        let dims = self.dimensions();
+        Ok(Self::from_slice(
+            &[&dims[..axis], &dims[axis + 1..]].concat(),
+        ))
+    }
+
+    /// Obtains a shape with a new axis.
+    ///
+    /// # Arguments
+    ///
+    /// * `axis` - Index of the new axis in the resulting shape.
+    /// * `size` - Size of the new axis.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Self)` - A new shape with `num_dimensions() + 1` dimensions.
+    /// * `Err(Error)` - `axis` is greater than `num_dimensions()`, or the resulting shape has too
+    ///   many dimensions.
+    pub fn insert_axis(&self, axis: usize, size: usize) -> Result<Self> {
+        if axis > self.num_dimensions {
+            return Err(Error::OutOfRange(format!(
+                "Axis out of range: axis:{} > num_dimensions:{}",
+                axis, self.num_dimensions
+            )));
+        }
+        if self.num_dimensions == MAX_NUM_DIMENSIONS {
+            return Err(Error::InvalidShape(format!(
+                "Can not add an axis to the shape {} with the maximum number of dimensions.",
+                self
+            )));
+        }
+        let dims = self.dimensions();
+        Ok(Self::from_slice(
+            &[&dims[..axis], &[size], &dims[axis..]].concat(),
+        ))
+    }
+
+    /// Obtains the resulting shape of gathering rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `indices` - Shape of the row indices.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Self)` - `indices` followed by the dimensions of `self` except the first one.
+    /// * `Err(Error)` - `self` is a scalar, or the resulting shape has too many dimensions.
+    pub fn gather_rows(&self, indices: &Self) -> Result<Self> {
+        if self.num_dimensions == 0 {
+            return Err(Error::InvalidShape(
+                "Rows can not be gathered from a scalar.".to_string(),
+            ));
+        }
+        indices.append(&self.dimensions()[1..])
+    }
+
+    /// Obtains the resulting shape of scattering rows, i.e., the inverse of `gather_rows()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `indices` - Shape of the row indices.
+    /// * `num_rows` - Number of rows of the resulting shape.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Self)` - `num_rows` followed by the dimensions of `self` after `indices`.
+    /// * `Err(Error)` - `self` does not start with `indices`.
+    pub fn scatter_rows(&self, indices: &Self, num_rows: usize) -> Result<Self> {
+        let dims = self.dimensions();
+        let prefix = indices.dimensions();
+        if dims.len() < prefix.len() || &dims[..prefix.len()] != prefix {
+            return Err(Error::InvalidShape(format!(
+                "Rows of {} can not be scattered by indices {}.",
+                self, indices
+            )));
+        }
+        Self::new([num_rows]).append(&dims[prefix.len()..])
+    }
+
+    /// Obtains the layout of a normalization over the last axis, e.g., layer normalization.
+    ///
+    /// # Arguments
+    ///
+    /// * `parameter` - Shape of the per-feature parameters.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok([usize; 2])` - The number of normalized rows and the size of each row.
+    /// * `Err(Error)` - `self` is a scalar, or `parameter` is not `[size]`.
+    pub fn feature_layout(&self, parameter: &Self) -> Result<[usize; 2]> {
+        let size = match self.dimensions().last() {
+            Some(&size) if *parameter == Self::new([size]) => size,
+            _ => {
+                return Err(Error::InvalidShape(format!(
+                    "Parameter {} does not match the last dimension of {}.",
+                    parameter, self
+                )))
+            }
+        };
+        let rows = self.dimensions()[..self.num_dimensions - 1]
+            .iter()
+            .product();
+        Ok([rows, size])
+    }
+
+    /// Obtains the layout of a normalization over every axis except the second one, e.g., batch
+    /// normalization.
+    ///
+    /// # Arguments
+    ///
+    /// * `parameter` - Shape of the per-channel parameters.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok([usize; 3])` - The first dimension, the number of channels and the product of the
+    ///   remaining dimensions.
+    /// * `Err(Error)` - `self` has less than 2 dimensions, or `parameter` is not `[channels]`.
+    pub fn channel_layout(&self, parameter: &Self) -> Result<[usize; 3]> {
+        let dims = self.dimensions();
+        if dims.len() < 2 || *parameter != Self::new([dims[1]]) {
+            return Err(Error::InvalidShape(format!(
+                "Parameter {} does not match the channels of {}.",
+                parameter, self
+            )));
+        }
+        Ok([dims[0], dims[1], dims[2..].iter().product()])
+    }
+
+    /// Obtains a shape with additional trailing dimensions.
+    fn append(&self, tail: &[usize]) -> Result<Self> {
+        let dims = [self.dimensions(), tail].concat();
+        if dims.len() > MAX_NUM_DIMENSIONS {
+            return Err(Error::InvalidShape(format!(
+                "Appending {:?} to {} yields too many dimensions.",
+                tail, self
+            )));
+        }
+        Ok(Self::from_slice(&dims))
+    }
 }
 
 impl fmt::Display for Shape {
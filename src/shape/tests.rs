@@ -208,6 +208,14 @@ fn test_dimension() {
     assert!(Shape::new(DIMS8).dimension(8).is_err());
 }
 
+#[test]
+fn test_dimensions() {
+    assert_eq!(Shape::new([]).dimensions(), &[] as &[usize]);
+    assert_eq!(Shape::new(DIMS1).dimensions(), &DIMS1);
+    assert_eq!(Shape::new(DIMS2).dimensions(), &DIMS2);
+    assert_eq!(Shape::new(DIMS8).dimensions(), &DIMS8);
+}
+
 #[test]
 fn test_num_elements() {
     assert_eq!(Shape::new([]).num_elements(), 1);
@@ -349,3 +357,130 @@ fn test_fmt() {
     assert_eq!(format!("{}", Shape::new(DIMS7)), "(3, 1, 4, 1, 5, 9, 2)");
     assert_eq!(format!("{}", Shape::new(DIMS8)), "(3, 1, 4, 1, 5, 9, 2, 6)");
 }
+
+#[test]
+fn test_matmul() {
+    assert_eq!(
+        Shape::new([2, 3]).matmul(&Shape::new([3, 4])),
+        Ok(Shape::new([2, 4]))
+    );
+    assert_eq!(
+        Shape::new([0, 3]).matmul(&Shape::new([3, 0])),
+        Ok(Shape::new([0, 0]))
+    );
+    assert!(Shape::new([2, 3]).matmul(&Shape::new([2, 3])).is_err());
+    assert!(Shape::new([3]).matmul(&Shape::new([3, 4])).is_err());
+    assert!(Shape::new([2, 3]).matmul(&Shape::new([3])).is_err());
+}
+
+#[test]
+fn test_transpose() {
+    assert_eq!(Shape::new([2, 3]).transpose(), Ok(Shape::new([3, 2])));
+    assert!(Shape::new([]).transpose().is_err());
+    assert!(Shape::new([3]).transpose().is_err());
+    assert!(Shape::new([1, 2, 3]).transpose().is_err());
+}
+
+#[test]
+fn test_remove_axis() {
+    assert_eq!(Shape::new([3]).remove_axis(0), Ok(Shape::new([])));
+    assert_eq!(Shape::new([2, 3, 4]).remove_axis(0), Ok(Shape::new([3, 4])));
+    assert_eq!(Shape::new([2, 3, 4]).remove_axis(1), Ok(Shape::new([2, 4])));
+    assert_eq!(Shape::new([2, 3, 4]).remove_axis(2), Ok(Shape::new([2, 3])));
+    assert!(Shape::new([]).remove_axis(0).is_err());
+    assert!(Shape::new([2, 3, 4]).remove_axis(3).is_err());
+}
+
+#[test]
+fn test_insert_axis() {
+    assert_eq!(Shape::new([]).insert_axis(0, 5), Ok(Shape::new([5])));
+    assert_eq!(
+        Shape::new([2, 3]).insert_axis(0, 5),
+        Ok(Shape::new([5, 2, 3]))
+    );
+    assert_eq!(
+        Shape::new([2, 3]).insert_axis(1, 5),
+        Ok(Shape::new([2, 5, 3]))
+    );
+    assert_eq!(
+        Shape::new([2, 3]).insert_axis(2, 5),
+        Ok(Shape::new([2, 3, 5]))
+    );
+    assert!(Shape::new([2, 3]).insert_axis(3, 5).is_err());
+    assert!(Shape::new(DIMS8).insert_axis(0, 5).is_err());
+}
+
+#[test]
+fn test_gather_rows() {
+    assert_eq!(
+        Shape::new([10, 4]).gather_rows(&Shape::new([3])),
+        Ok(Shape::new([3, 4]))
+    );
+    assert_eq!(
+        Shape::new([10, 4]).gather_rows(&Shape::new([])),
+        Ok(Shape::new([4]))
+    );
+    assert_eq!(
+        Shape::new([10]).gather_rows(&Shape::new([2, 3])),
+        Ok(Shape::new([2, 3]))
+    );
+    assert!(Shape::new([]).gather_rows(&Shape::new([3])).is_err());
+    assert!(Shape::new([10, 1, 1, 1, 1, 1, 1, 1])
+        .gather_rows(&Shape::new([2, 3]))
+        .is_err());
+}
+
+#[test]
+fn test_scatter_rows() {
+    assert_eq!(
+        Shape::new([3, 4]).scatter_rows(&Shape::new([3]), 10),
+        Ok(Shape::new([10, 4]))
+    );
+    assert_eq!(
+        Shape::new([4]).scatter_rows(&Shape::new([]), 10),
+        Ok(Shape::new([10, 4]))
+    );
+    assert_eq!(
+        Shape::new([2, 3]).scatter_rows(&Shape::new([2, 3]), 10),
+        Ok(Shape::new([10]))
+    );
+    assert!(Shape::new([3, 4])
+        .scatter_rows(&Shape::new([4]), 10)
+        .is_err());
+    assert!(Shape::new([3])
+        .scatter_rows(&Shape::new([3, 4]), 10)
+        .is_err());
+}
+
+#[test]
+fn test_feature_layout() {
+    assert_eq!(Shape::new([3]).feature_layout(&Shape::new([3])), Ok([1, 3]));
+    assert_eq!(
+        Shape::new([2, 5, 3]).feature_layout(&Shape::new([3])),
+        Ok([10, 3])
+    );
+    assert_eq!(
+        Shape::new([0, 3]).feature_layout(&Shape::new([3])),
+        Ok([0, 3])
+    );
+    assert!(Shape::new([]).feature_layout(&Shape::new([1])).is_err());
+    assert!(Shape::new([2, 3]).feature_layout(&Shape::new([2])).is_err());
+    assert!(Shape::new([2, 3])
+        .feature_layout(&Shape::new([1, 3]))
+        .is_err());
+}
+
+#[test]
+fn test_channel_layout() {
+    assert_eq!(
+        Shape::new([4, 3]).channel_layout(&Shape::new([3])),
+        Ok([4, 3, 1])
+    );
+    assert_eq!(
+        Shape::new([4, 3, 5, 2]).channel_layout(&Shape::new([3])),
+        Ok([4, 3, 10])
+    );
+    assert!(Shape::new([3]).channel_layout(&Shape::new([3])).is_err());
+    assert!(Shape::new([4, 3]).channel_layout(&Shape::new([4])).is_err());
+    assert!(Shape::new([4, 3]).channel_layout(&Shape::new([])).is_err());
+}